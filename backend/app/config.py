@@ -22,10 +22,14 @@ class Settings(BaseSettings):
     # Database -- PINUP_DB is canonical (set by Tauri sidecar)
     pinup_db: str = Field(default="pinup.db", alias="PINUP_DB")
     database_check_same_thread: bool = Field(default=False, alias="DB_CHECK_SAME_THREAD")
+    # SQLCipher key -- 32 bytes as hex, derived from the user's passphrase by the Tauri shell
+    db_key: str = Field(default="", alias="PINUP_DB_KEY", pattern=r"^([0-9a-fA-F]{64})?$")
 
     # Server
     pinup_port: int = Field(default=8000, alias="PINUP_PORT")
     host: str = Field(default="127.0.0.1", alias="PINUP_HOST")
+    # Threads for sync endpoints; 0 keeps the AnyIO default (40)
+    worker_threads: int = Field(default=0, alias="PINUP_WORKER_THREADS")
 
     # Logging
     pinup_log_level: str = Field(default="INFO", alias="PINUP_LOG_LEVEL")
@@ -53,6 +57,18 @@ class Settings(BaseSettings):
     # Backup
     backup_dir: str = Field(default="", alias="PINUP_BACKUP_DIR")
 
+    # Local LLM -- Ollama endpoint, passed by the Tauri shell when enabled
+    ollama_url: str = Field(default="", alias="PINUP_OLLAMA_URL")
+
+    # TLS -- PEM bundle of user-supplied CAs, written by the Tauri shell
+    extra_ca_certs: str = Field(default="", alias="PINUP_CA_CERTS")
+
+    # Region -- the user's locale (BCP 47), app language and IANA time zone,
+    # passed by the Tauri shell; empty uses the server's own
+    locale: str = Field(default="", alias="PINUP_LOCALE")
+    language: str = Field(default="", alias="PINUP_LANGUAGE")
+    timezone: str = Field(default="", alias="PINUP_TIMEZONE")
+
     # Trial
     trial_days: int = Field(default=14, alias="PINUP_TRIAL_DAYS")
 
@@ -1,6 +1,8 @@
 """Database initialization and connection management."""
 
+import json
 import logging
+import os
 import sqlite3
 import time
 from contextlib import contextmanager
@@ -16,9 +18,131 @@ Base = declarative_base()
 
 DATABASE_URL = settings.get_database_url()
 
+_PLAINTEXT_HEADER = b"SQLite format 3\x00"
+
+
+def _dbapi():
+    """sqlite3, or SQLCipher's drop-in replacement when a key is set."""
+    if not settings.db_key:
+        return sqlite3
+    try:
+        from sqlcipher3 import dbapi2
+    except ImportError as e:
+        raise RuntimeError("PINUP_DB_KEY is set but sqlcipher3 is not installed") from e
+    return dbapi2
+
+
+dbapi = _dbapi()
+
+
+def _key_pragma() -> str:
+    return f"PRAGMA key = \"x'{settings.db_key}'\""
+
+
+def is_plaintext(path: str) -> bool:
+    """True for an unencrypted SQLite file."""
+    try:
+        with open(path, "rb") as f:
+            return f.read(16) == _PLAINTEXT_HEADER
+    except OSError:
+        return False
+
+
+def connect(path: str, immutable: bool = False, **kwargs):
+    """Open a database file directly (backups, maintenance).
+
+    With a key set, new and encrypted files are keyed; existing plaintext
+    files (older backups) are opened as they are.
+    """
+    target = f"file:{path}?immutable=1" if immutable else path
+    con = dbapi.connect(target, uri=immutable, **kwargs)
+    if settings.db_key and not is_plaintext(path):
+        con.execute(_key_pragma())
+    return con
+
+
+def _encrypt_file(path: str) -> None:
+    """Convert one plaintext SQLite file to SQLCipher in place.
+
+    The export is written to a temp file, checked, then renamed over the
+    original, so a failure leaves the plaintext file as it was.
+    """
+    tmp = path + ".encrypting"
+    for p in (tmp, tmp + "-wal", tmp + "-shm"):
+        if os.path.isfile(p):
+            os.remove(p)
+    quoted = tmp.replace("'", "''")
+    con = dbapi.connect(path)
+    try:
+        con.execute(f"ATTACH DATABASE '{quoted}' AS encrypted KEY \"x'{settings.db_key}'\"")
+        con.execute("SELECT sqlcipher_export('encrypted')")
+        con.execute("DETACH DATABASE encrypted")
+    finally:
+        con.close()
+    check = connect(tmp)
+    try:
+        result = check.execute("PRAGMA quick_check").fetchone()[0]
+    finally:
+        check.close()
+    if result != "ok":
+        os.remove(tmp)
+        raise RuntimeError(f"Encrypted copy failed its check: {result}")
+    os.replace(tmp, path)
+    for ext in ("-wal", "-shm"):
+        if os.path.isfile(path + ext):
+            os.remove(path + ext)
+
+
+def encrypt_plaintext_database() -> bool:
+    """With a key set, convert a plaintext pinup.db to SQLCipher in place.
+
+    Runs before the engine opens the file.
+    """
+    path = settings.get_database_path()
+    if not settings.db_key or not is_plaintext(path):
+        return False
+    _encrypt_file(path)
+    logger.info("Database converted to SQLCipher")
+    return True
+
+
+def encrypt_plaintext_copies() -> list[str]:
+    """With a key set, encrypt copies left from before encryption was on.
+
+    Covers every backup's pinup.db and the pre-restore database. A backup's
+    recorded size is updated to match. Returns the copies that could not be
+    converted, which stay plaintext.
+    """
+    if not settings.db_key:
+        return []
+    backup_dir = settings.get_backup_dir()
+    copies = [settings.get_database_path() + ".pre_restore"]
+    if os.path.isdir(backup_dir):
+        copies += [os.path.join(backup_dir, name, "pinup.db") for name in sorted(os.listdir(backup_dir))]
+    failed = []
+    for path in copies:
+        if not is_plaintext(path):
+            continue
+        try:
+            _encrypt_file(path)
+        except Exception as e:
+            logger.warning("Could not encrypt %s: %s", path, e)
+            failed.append(path)
+            continue
+        meta_path = os.path.join(os.path.dirname(path), "backup.json")
+        if os.path.isfile(meta_path):
+            with open(meta_path) as f:
+                meta = json.load(f)
+            meta["db_size_bytes"] = os.path.getsize(path)
+            with open(meta_path, "w") as f:
+                json.dump(meta, f, indent=2)
+        logger.info("Encrypted plaintext copy %s", path)
+    return failed
+
 if DATABASE_URL.startswith("sqlite"):
     engine = create_engine(
         DATABASE_URL,
+        module=dbapi,
         connect_args={"check_same_thread": settings.database_check_same_thread},
         poolclass=StaticPool,
         echo=settings.debug,
@@ -35,8 +159,10 @@ def get_uptime_ms() -> int:
 
 @event.listens_for(engine, "connect")
 def set_sqlite_pragmas(dbapi_conn, connection_record):
-    if isinstance(dbapi_conn, sqlite3.Connection):
+    if isinstance(dbapi_conn, dbapi.Connection):
         c = dbapi_conn.cursor()
+        if settings.db_key:
+            c.execute(_key_pragma())  # must come before anything touches the file
         c.execute("PRAGMA foreign_keys = ON")
         c.execute("PRAGMA journal_mode = WAL")
         c.execute("PRAGMA synchronous = NORMAL")
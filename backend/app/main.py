@@ -1,16 +1,27 @@
 """FastAPI application factory."""
 
 import logging
+import anyio
 from contextlib import asynccontextmanager
 from fastapi import FastAPI, Request
 from fastapi.responses import JSONResponse
 from fastapi.middleware.cors import CORSMiddleware
 
 from app.config import settings
-from app.database import init_db, get_db, SessionLocal
+from app.database import (
+    init_db,
+    get_db,
+    SessionLocal,
+    encrypt_plaintext_copies,
+    encrypt_plaintext_database,
+)
 from app.auth import ensure_token
-from app.security import setup_logging, RequestIDMiddleware, RateLimitMiddleware
+from app.security import (
+    setup_logging, setup_ca_bundle, RequestIDMiddleware, RateLimitMiddleware,
+    MaintenanceMiddleware,
+)
 from app.routers import api_router
+from app.services import backup_service
 
 setup_logging()
 logger = logging.getLogger(__name__)
@@ -23,7 +34,17 @@ _install_token: str = ""
 async def lifespan(app: FastAPI):
     global _install_token
     logger.info(f"Starting {settings.app_name} v{settings.app_version}")
+    encrypt_plaintext_database()
+    encrypt_plaintext_copies()
+    try:
+        backup_service.backup_before_migration()
+    except Exception as e:
+        logger.warning("Pre-migration backup failed: %s", e)
     init_db()
+    backup_service.record_schema_version()
+    setup_ca_bundle()
+    if settings.worker_threads > 0:
+        anyio.to_thread.current_default_thread_limiter().total_tokens = settings.worker_threads
     # Ensure install token
     db = SessionLocal()
     try:
@@ -57,6 +78,7 @@ def create_app() -> FastAPI:
         allow_headers=["*"],
     )
     app.add_middleware(RequestIDMiddleware)
+    app.add_middleware(MaintenanceMiddleware)
     if settings.rate_limit_enabled:
         app.add_middleware(RateLimitMiddleware)
 
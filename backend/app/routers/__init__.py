@@ -14,6 +14,11 @@ from app.routers import (
     settings as settings_router,
     stats,
     mcp,
+    power,
+    network,
+    locale,
+    log_level,
+    maintenance,
 )
 
 api_router = APIRouter()
@@ -29,5 +34,10 @@ api_router.include_router(license.router)
 api_router.include_router(settings_router.router)
 api_router.include_router(stats.router)
 api_router.include_router(mcp.router)
+api_router.include_router(power.router)
+api_router.include_router(network.router)
+api_router.include_router(locale.router)
+api_router.include_router(log_level.router)
+api_router.include_router(maintenance.router)
 
 __all__ = ["api_router"]
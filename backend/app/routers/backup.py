@@ -1,20 +1,23 @@
 """Backup / Restore router per backup-restore-spec.md."""
 
+from typing import Optional
+
 from fastapi import APIRouter, Depends, HTTPException
-from sqlalchemy.orm import Session
 
-from app.database import get_db
 from app.auth import verify_token
-from app.services import backup_service as svc
+from app.schemas import BackupRun
+from app.services import backup_service as svc, power_service
 
 router = APIRouter(prefix="/backup", tags=["backup"], dependencies=[Depends(verify_token)])
 
 
 @router.post("/run")
-def run_backup(db: Session = Depends(get_db)):
-    """Run a manual backup now."""
+def run_backup(body: Optional[BackupRun] = None):
+    """Run a backup now; safe while the app is writing. `tag` labels it."""
+    if body and body.scheduled and power_service.is_saving():
+        raise HTTPException(status_code=409, detail={"code": "POWER_SAVER", "message": "Scheduled backups wait until power saver mode ends"})
     try:
-        info = svc.run_backup(db)
+        info = svc.run_backup(tag=body.tag if body else None)
         return info
     except Exception as e:
         raise HTTPException(status_code=500, detail={"code": "DB_ERROR", "message": str(e)})
@@ -26,6 +29,15 @@ def list_backups():
     return {"items": svc.list_backups()}
 
 
+@router.get("/verify/{name}")
+def verify_backup(name: str):
+    """Integrity-check a backup before restoring it."""
+    result = svc.verify_backup(name)
+    if result is None:
+        raise HTTPException(status_code=404, detail={"code": "NOT_FOUND", "message": f"Backup '{name}' not found"})
+    return result
+
+
 @router.post("/restore/{name}")
 def restore_backup(name: str):
     """Restore from a named backup. Backend restart required after."""
@@ -0,0 +1,22 @@
+"""Locale router — the desktop shell reports the user's locale and time zone here."""
+
+from fastapi import APIRouter, Depends, HTTPException
+from app.auth import verify_token
+from app.schemas import LocaleState
+from app.services import locale_service as svc
+
+router = APIRouter(prefix="/locale", tags=["locale"], dependencies=[Depends(verify_token)])
+
+
+@router.get("")
+def get_locale():
+    return svc.status()
+
+
+@router.post("")
+def set_locale(body: LocaleState):
+    """Search date filters and backup names follow this from now on."""
+    try:
+        return svc.set_region(body.locale, body.language, body.timezone)
+    except ValueError as e:
+        raise HTTPException(status_code=422, detail={"code": "VALIDATION_ERROR", "message": str(e)})
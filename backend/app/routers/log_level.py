@@ -0,0 +1,21 @@
+"""Log level router — the desktop shell switches debug logging here."""
+
+import logging
+
+from fastapi import APIRouter, Depends
+from app.auth import verify_token
+from app.schemas import LogLevelUpdate
+from app.security.logging import set_level
+
+router = APIRouter(prefix="/logging", tags=["logging"], dependencies=[Depends(verify_token)])
+
+
+@router.get("")
+async def get_log_level():
+    return {"level": logging.getLevelName(logging.getLogger().level)}
+
+
+@router.post("")
+async def set_log_level(body: LogLevelUpdate):
+    """Applies immediately; the shell also passes it via PINUP_LOG_LEVEL on restart."""
+    return {"level": set_level(body.level)}
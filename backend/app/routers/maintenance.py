@@ -0,0 +1,29 @@
+"""Database maintenance router — integrity checks and optimization for the desktop shell."""
+
+from fastapi import APIRouter, Depends, HTTPException
+
+from app.auth import verify_token
+from app.database import dbapi
+from app.security import quiesced
+from app.services import backup_service as svc
+
+router = APIRouter(prefix="/maintenance", tags=["maintenance"], dependencies=[Depends(verify_token)])
+
+
+@router.get("/integrity")
+def check_integrity(full: bool = False):
+    """quick_check by default; `full=true` runs the slower integrity_check."""
+    try:
+        return svc.check_integrity(full)
+    except dbapi.OperationalError as e:
+        raise HTTPException(status_code=409, detail={"code": "DB_BUSY", "message": str(e)})
+
+
+@router.post("/optimize")
+def optimize():
+    """Quiesce the API, then VACUUM / optimize / truncate the WAL."""
+    try:
+        with quiesced():
+            return svc.optimize_database()
+    except (TimeoutError, RuntimeError, dbapi.OperationalError) as e:
+        raise HTTPException(status_code=409, detail={"code": "DB_BUSY", "message": str(e)})
@@ -0,0 +1,19 @@
+"""Network router — the desktop shell reports connectivity changes here."""
+
+from fastapi import APIRouter, Depends
+from app.auth import verify_token
+from app.schemas import NetworkState
+from app.services import network_service as svc
+
+router = APIRouter(prefix="/network", tags=["network"], dependencies=[Depends(verify_token)])
+
+
+@router.get("")
+def get_network():
+    return svc.status()
+
+
+@router.post("")
+def set_network(body: NetworkState):
+    """Record connectivity; license revalidation waits while offline."""
+    return svc.set_online(body.online)
@@ -0,0 +1,19 @@
+"""Power router — the desktop shell reports battery saver mode here."""
+
+from fastapi import APIRouter, Depends
+from app.auth import verify_token
+from app.schemas import PowerState
+from app.services import power_service as svc
+
+router = APIRouter(prefix="/power", tags=["power"], dependencies=[Depends(verify_token)])
+
+
+@router.get("")
+async def get_power():
+    return svc.status()
+
+
+@router.post("")
+async def set_power(body: PowerState):
+    """Refuse scheduled jobs and shrink the worker pool while saving."""
+    return svc.set_saver(body.saver)
@@ -1,7 +1,7 @@
 """Pydantic v2 schemas matching api-contract.md exactly."""
 
 from __future__ import annotations
-from typing import Optional
+from typing import Literal, Optional
 from pydantic import BaseModel, Field
 
 
@@ -188,3 +188,32 @@ class BackupInfo(BaseModel):
     created_at: int
     db_size_bytes: int
     app_version: str
+    tag: Optional[str] = None
+
+
+class BackupRun(BaseModel):
+    tag: Optional[str] = Field(default=None, max_length=64, pattern=r"^[A-Za-z0-9._-]+$")
+    # Background runs from the shell's schedule; refused in power saver mode
+    scheduled: bool = False
+
+
+# ── Power ───────────────────────────────────────────────────────────────
+class PowerState(BaseModel):
+    saver: bool
+
+
+# ── Network ─────────────────────────────────────────────────────────────
+class NetworkState(BaseModel):
+    online: bool
+
+
+# ── Locale ──────────────────────────────────────────────────────────────
+class LocaleState(BaseModel):
+    locale: str = Field(default="", max_length=35, pattern=r"^([A-Za-z]{2,8}(-[A-Za-z0-9]{1,8})*)?$")
+    language: str = Field(default="", max_length=8, pattern=r"^[a-z]{0,8}$")
+    timezone: str = Field(default="", max_length=64)
+
+
+# ── Logging ─────────────────────────────────────────────────────────────
+class LogLevelUpdate(BaseModel):
+    level: Literal["DEBUG", "INFO", "WARNING", "ERROR"]
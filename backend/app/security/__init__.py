@@ -4,10 +4,15 @@ from app.security.logging import setup_logging
 from app.security.cors import setup_cors
 from app.security.request_id import RequestIDMiddleware
 from app.security.rate_limit import RateLimitMiddleware
+from app.security.maintenance import MaintenanceMiddleware, quiesced
+from app.security.tls import setup_ca_bundle
 
 __all__ = [
     "setup_logging",
     "setup_cors",
     "RequestIDMiddleware",
     "RateLimitMiddleware",
+    "MaintenanceMiddleware",
+    "quiesced",
+    "setup_ca_bundle",
 ]
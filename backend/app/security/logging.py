@@ -73,3 +73,13 @@ def setup_logging() -> None:
     logging.getLogger("uvicorn.access").setLevel(logging.WARNING)
 
     logger.info(f"Logging configured: format={settings.log_format}, level={settings.pinup_log_level}")
+
+
+def set_level(level: str) -> str:
+    """Change the root log level at runtime (desktop shell's log switch)."""
+    logger = logging.getLogger()
+    logger.setLevel(level)
+    for handler in logger.handlers:
+        handler.setLevel(level)
+    logger.info("Log level set to %s", level)
+    return logging.getLevelName(logger.level)
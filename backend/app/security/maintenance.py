@@ -0,0 +1,69 @@
+"""Maintenance gate — holds off API requests while the database is rebuilt.
+
+`quiesced()` stops new requests (503 MAINTENANCE) and waits for in-flight
+ones to finish, so VACUUM and WAL truncation run with the database idle.
+Health and maintenance endpoints themselves are never held.
+"""
+
+import logging
+import threading
+from contextlib import contextmanager
+
+from fastapi import Request
+from fastapi.responses import JSONResponse
+from starlette.middleware.base import BaseHTTPMiddleware
+
+logger = logging.getLogger(__name__)
+
+_EXEMPT = ("/api/health", "/api/maintenance")
+
+_idle = threading.Condition()
+_active = 0
+_paused = False
+
+
+class MaintenanceMiddleware(BaseHTTPMiddleware):
+    """Rejects requests while the gate is closed and counts the rest."""
+
+    async def dispatch(self, request: Request, call_next):
+        global _active
+        if request.url.path.startswith(_EXEMPT):
+            return await call_next(request)
+        with _idle:
+            if _paused:
+                return JSONResponse(
+                    status_code=503,
+                    headers={"Retry-After": "5"},
+                    content={
+                        "code": "MAINTENANCE",
+                        "message": "Database maintenance in progress",
+                        "details": None,
+                    },
+                )
+            _active += 1
+        try:
+            return await call_next(request)
+        finally:
+            with _idle:
+                _active -= 1
+                _idle.notify_all()
+
+
+@contextmanager
+def quiesced(timeout: float = 30.0):
+    """Close the gate and wait for in-flight requests; raises TimeoutError."""
+    global _paused
+    with _idle:
+        if _paused:
+            raise RuntimeError("Maintenance already in progress")
+        _paused = True
+        if not _idle.wait_for(lambda: _active == 0, timeout):
+            _paused = False
+            raise TimeoutError(f"{_active} request(s) still running")
+    logger.info("API quiesced for maintenance")
+    try:
+        yield
+    finally:
+        with _idle:
+            _paused = False
+        logger.info("API resumed after maintenance")
@@ -0,0 +1,40 @@
+"""Extra CA certificates passed by the Tauri shell (private model gateways)."""
+
+import logging
+import os
+import tempfile
+from typing import Optional
+
+import certifi
+
+from app.config import settings
+
+logger = logging.getLogger(__name__)
+
+
+def setup_ca_bundle() -> Optional[str]:
+    """Merge PINUP_CA_CERTS into certifi's bundle and point SSL_CERT_FILE at it.
+
+    httpx (trust_env) and requests-based tools read SSL_CERT_FILE /
+    REQUESTS_CA_BUNDLE, which replace the default store, so the public roots
+    are kept alongside the custom ones. Returns the bundle path, if any.
+    """
+    extra_path = settings.extra_ca_certs
+    if not extra_path:
+        return None
+    try:
+        with open(certifi.where(), encoding="ascii") as f:
+            roots = f.read()
+        with open(extra_path, encoding="ascii") as f:
+            extra = f.read()
+    except OSError as exc:
+        logger.warning("Could not load extra CA certificates: %s", exc)
+        return None
+
+    fd, bundle = tempfile.mkstemp(prefix="pinup-ca-", suffix=".pem")
+    with os.fdopen(fd, "w", encoding="ascii") as f:
+        f.write(roots.rstrip("\n") + "\n" + extra)
+    os.environ["SSL_CERT_FILE"] = bundle
+    os.environ["REQUESTS_CA_BUNDLE"] = bundle
+    logger.info("Trusting extra CA certificates from %s", extra_path)
+    return bundle
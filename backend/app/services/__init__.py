@@ -8,7 +8,10 @@ from app.services import (
     license_service,
     export_service,
     import_service,
+    locale_service,
     backup_service,
+    power_service,
+    network_service,
 )
 
 __all__ = [
@@ -19,5 +22,8 @@ __all__ = [
     "license_service",
     "export_service",
     "import_service",
+    "locale_service",
     "backup_service",
+    "power_service",
+    "network_service",
 ]
@@ -1,38 +1,60 @@
-"""Backup service — WAL checkpoint + copy per backup-restore-spec.md."""
+"""Backup service — online SQLite backups per backup-restore-spec.md."""
 
 import json
 import logging
 import os
 import shutil
 import time
-from datetime import datetime
-
-from sqlalchemy import text
-from sqlalchemy.orm import Session
+from typing import Optional
 
 from app.config import settings
+from app.database import connect, dbapi
+from app.services import locale_service
 
 logger = logging.getLogger(__name__)
 
+# settings row holding the app version that last opened the database
+_SCHEMA_VERSION_KEY = "schema_app_version"
 
-def run_backup(db: Session) -> dict:
-    """Run a manual backup: checkpoint WAL, copy DB file."""
-    backup_dir = settings.get_backup_dir()
-    timestamp = datetime.now().strftime("%Y-%m-%d_%H%M")
-    dest_dir = os.path.join(backup_dir, timestamp)
-    os.makedirs(dest_dir, exist_ok=True)
 
-    db_path = settings.get_database_path()
+def _snapshot(src_path: str, dest_path: str) -> None:
+    """Copy a live database with the SQLite online backup API.
 
-    # Checkpoint WAL
+    Unlike a file copy, the backup reads a consistent snapshot through SQLite
+    (WAL included) while other connections keep writing. The copy is checked
+    and only then moved into place.
+    """
+    tmp_path = dest_path + ".tmp"
+    src = connect(src_path)
     try:
-        db.execute(text("PRAGMA wal_checkpoint(TRUNCATE)"))
-    except Exception as e:
-        logger.warning("WAL checkpoint warning: %s", e)
+        dest = connect(tmp_path)
+        try:
+            src.backup(dest, pages=1024)
+            result = dest.execute("PRAGMA quick_check").fetchone()[0]
+        finally:
+            dest.close()
+    finally:
+        src.close()
+    if result != "ok":
+        os.remove(tmp_path)
+        raise RuntimeError(f"Backup failed integrity check: {result}")
+    os.replace(tmp_path, dest_path)
+
+
+def run_backup(tag: Optional[str] = None) -> dict:
+    """Run a backup of the live database into a timestamped folder.
+
+    A tag (e.g. "pre-update-0.3.0") is kept in the metadata and appended to
+    the folder name, so tagged backups never collide with scheduled ones.
+    """
+    backup_dir = settings.get_backup_dir()
+    timestamp = locale_service.now().strftime("%Y-%m-%d_%H%M")
+    name = f"{timestamp}_{tag}" if tag else timestamp
+    dest_dir = os.path.join(backup_dir, name)
+    os.makedirs(dest_dir, exist_ok=True)
 
-    # Copy DB
     dest_db = os.path.join(dest_dir, "pinup.db")
-    shutil.copy2(db_path, dest_db)
+    _snapshot(settings.get_database_path(), dest_db)
 
     db_size = os.path.getsize(dest_db)
 
@@ -41,6 +63,7 @@ def run_backup(db: Session) -> dict:
         "created_at": int(time.time() * 1000),
         "db_size_bytes": db_size,
         "app_version": settings.app_version,
+        "tag": tag,
     }
     meta_path = os.path.join(dest_dir, "backup.json")
     with open(meta_path, "w") as f:
@@ -48,10 +71,11 @@ def run_backup(db: Session) -> dict:
 
     logger.info("Backup created: %s", dest_dir)
     return {
-        "name": timestamp,
+        "name": name,
         "created_at": meta["created_at"],
         "db_size_bytes": db_size,
         "app_version": settings.app_version,
+        "tag": tag,
     }
 
 
@@ -73,20 +97,154 @@ def list_backups() -> list[dict]:
                     "created_at": meta.get("created_at", 0),
                     "db_size_bytes": meta.get("db_size_bytes", 0),
                     "app_version": meta.get("app_version", "unknown"),
+                    "tag": meta.get("tag"),
                 })
             except Exception:
                 pass
     return results
 
 
+def backup_before_migration() -> Optional[dict]:
+    """Take a "pre-migration" backup when this version opens a database last
+    used by a different one, before init_db touches the schema."""
+    db_path = settings.get_database_path()
+    if not os.path.isfile(db_path) or os.path.getsize(db_path) == 0:
+        return None
+    con = connect(db_path)
+    try:
+        row = con.execute(
+            "SELECT value FROM settings WHERE key=?", (_SCHEMA_VERSION_KEY,)
+        ).fetchone()
+    except dbapi.OperationalError:
+        row = None  # no settings table yet
+    finally:
+        con.close()
+    if row and row[0] == settings.app_version:
+        return None
+    info = run_backup(tag="pre-migration")
+    logger.info("Pre-migration backup %s taken", info["name"])
+    return info
+
+
+def record_schema_version() -> None:
+    """Remember which version last migrated the database."""
+    con = connect(settings.get_database_path())
+    try:
+        with con:
+            con.execute(
+                "INSERT OR REPLACE INTO settings(key, value) VALUES(?, ?)",
+                (_SCHEMA_VERSION_KEY, settings.app_version),
+            )
+    finally:
+        con.close()
+
+
+def check_integrity(full: bool = False) -> dict:
+    """Check the live database with quick_check, or integrity_check if full.
+
+    The check runs inside BEGIN IMMEDIATE so writes are held off until it
+    finishes (other connections wait on their busy_timeout). Lock errors are
+    raised as dbapi.OperationalError; corruption is reported, not raised.
+    """
+    db_path = settings.get_database_path()
+    pragma = "integrity_check" if full else "quick_check"
+    started = time.monotonic()
+    con = connect(db_path, timeout=10, isolation_level=None)
+    try:
+        con.execute("BEGIN IMMEDIATE")
+        try:
+            rows = con.execute(f"PRAGMA {pragma}").fetchall()
+        finally:
+            con.execute("ROLLBACK")
+    except dbapi.OperationalError:
+        raise
+    except dbapi.DatabaseError as e:
+        rows = [(str(e),)]
+    finally:
+        con.close()
+    errors = [str(r[0]) for r in rows if r[0] != "ok"]
+    if errors:
+        logger.error("Database %s found %d problem(s)", pragma, len(errors))
+    return {
+        "ok": not errors,
+        "mode": "full" if full else "quick",
+        "errors": errors,
+        "duration_ms": int((time.monotonic() - started) * 1000),
+        "db_size_bytes": os.path.getsize(db_path),
+    }
+
+
+def _files_size(db_path: str) -> int:
+    """Size of the database plus its WAL/SHM files."""
+    return sum(
+        os.path.getsize(db_path + ext)
+        for ext in ("", "-wal", "-shm")
+        if os.path.isfile(db_path + ext)
+    )
+
+
+def optimize_database() -> dict:
+    """VACUUM, FTS and planner optimize, then a truncating WAL checkpoint.
+
+    Run inside security.quiesced(): VACUUM rewrites the whole file and the
+    checkpoint can only truncate the WAL when no one else is reading.
+    """
+    db_path = settings.get_database_path()
+    before = _files_size(db_path)
+    started = time.monotonic()
+    con = connect(db_path, timeout=30, isolation_level=None)
+    try:
+        con.execute("PRAGMA wal_checkpoint(TRUNCATE)")
+        con.execute("INSERT INTO snippets_fts(snippets_fts) VALUES('optimize')")
+        con.execute("VACUUM")
+        con.execute("PRAGMA optimize")
+        busy = con.execute("PRAGMA wal_checkpoint(TRUNCATE)").fetchone()[0]
+    finally:
+        con.close()
+    after = _files_size(db_path)
+    logger.info("Database optimized: %d -> %d bytes", before, after)
+    return {
+        "size_before_bytes": before,
+        "size_after_bytes": after,
+        "reclaimed_bytes": max(0, before - after),
+        "duration_ms": int((time.monotonic() - started) * 1000),
+        "wal_truncated": busy == 0,
+    }
+
+
+def _backup_db(name: str) -> Optional[str]:
+    """Database file of backup `name`; None unless it is a plain folder name
+    inside the backup directory (".." would reach the live database)."""
+    if os.path.basename(name) != name or name in ("", ".", ".."):
+        return None
+    src_db = os.path.join(settings.get_backup_dir(), name, "pinup.db")
+    return src_db if os.path.isfile(src_db) else None
+
+
+def verify_backup(name: str) -> Optional[dict]:
+    """Run PRAGMA integrity_check on a backup without modifying it."""
+    src_db = _backup_db(name)
+    if src_db is None:
+        return None
+    # immutable: no locks, no -wal/-shm files created next to the backup
+    con = connect(src_db, immutable=True)
+    try:
+        rows = con.execute("PRAGMA integrity_check").fetchall()
+    except dbapi.DatabaseError as e:
+        return {"ok": False, "result": str(e)}
+    finally:
+        con.close()
+    result = "; ".join(str(r[0]) for r in rows)
+    return {"ok": result == "ok", "result": result}
+
+
 def restore_backup(name: str) -> bool:
     """Restore from a named backup.
 
     WARNING: This replaces the current DB. Caller must restart the backend.
     """
-    backup_dir = settings.get_backup_dir()
-    src_db = os.path.join(backup_dir, name, "pinup.db")
-    if not os.path.isfile(src_db):
+    src_db = _backup_db(name)
+    if src_db is None:
         return False
 
     db_path = settings.get_database_path()
@@ -14,6 +14,7 @@ from sqlalchemy import text
 from sqlalchemy.orm import Session
 
 from app.config import settings as app_settings
+from app.services import network_service
 
 logger = logging.getLogger(__name__)
 
@@ -169,7 +170,7 @@ def get_license_status(db: Session) -> dict:
             # Grace expired — fall through to trial
 
         # Periodic revalidation (non-blocking)
-        elif stored_key and _should_revalidate(db):
+        elif stored_key and _should_revalidate(db) and network_service.is_online():
             result = _validate_gumroad(stored_key)
             if result["valid"]:
                 _set_setting(db, _LICENSE_VALIDATED_AT_KEY, str(_now_ms()))
@@ -190,7 +191,7 @@ def get_license_status(db: Session) -> dict:
                     "plan": "pro",
                 }
         else:
-            # No revalidation needed or no stored key (offline mode)
+            # No revalidation needed, no stored key or no connectivity (offline mode)
             return {
                 "status": "licensed_active",
                 "days_left": 999,
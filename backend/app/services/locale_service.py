@@ -0,0 +1,97 @@
+"""Locale and time zone pushed by the desktop shell, so dates typed in
+search and backup names match the user's environment rather than the
+server's."""
+
+import logging
+import re
+from datetime import date, datetime, time, timedelta, tzinfo
+from typing import Optional
+from zoneinfo import ZoneInfo, ZoneInfoNotFoundError
+
+from app.config import settings
+
+logger = logging.getLogger(__name__)
+
+DEFAULT_LOCALE = "en-US"
+
+# 15.10.2026, 15/10/2026 or 10/15/2026, read in the locale's order
+_NUMERIC_DATE_RE = re.compile(r"^(\d{1,2})[./](\d{1,2})[./](\d{4})$")
+
+_locale = settings.locale
+_language = settings.language
+_timezone = settings.timezone
+
+
+def _zone(name: str) -> Optional[ZoneInfo]:
+    if not name:
+        return None
+    try:
+        return ZoneInfo(name)
+    except (ZoneInfoNotFoundError, ValueError):
+        return None
+
+
+def locale() -> str:
+    """BCP 47 tag for formatting dates and numbers, e.g. "de-DE"."""
+    return _locale or DEFAULT_LOCALE
+
+
+def language() -> str:
+    """Language code, e.g. "de"; reported to clients that localize text."""
+    return _language or locale().split("-")[0].lower()
+
+
+def tz() -> Optional[tzinfo]:
+    """The user's time zone; None means the server's local time."""
+    return _zone(_timezone)
+
+
+def now() -> datetime:
+    """Wall-clock time where the user is."""
+    return datetime.now(tz()) if tz() else datetime.now()
+
+
+def parse_date(value: str) -> Optional[date]:
+    """A day as the user types it: today, yesterday, ISO 2026-10-15, or
+    numeric with the month first for US locales and the day first elsewhere."""
+    value = value.strip().lower()
+    if value in ("today", "yesterday"):
+        return now().date() - timedelta(days=1 if value == "yesterday" else 0)
+    try:
+        return date.fromisoformat(value)
+    except ValueError:
+        pass
+    m = _NUMERIC_DATE_RE.match(value)
+    if not m:
+        return None
+    first, second, year = (int(g) for g in m.groups())
+    month, day = (first, second) if locale().upper().endswith("-US") else (second, first)
+    try:
+        return date(year, month, day)
+    except ValueError:
+        return None
+
+
+def day_start_ms(day: date) -> int:
+    """Epoch ms of midnight starting `day` in the user's time zone."""
+    return int(datetime.combine(day, time.min, tzinfo=tz()).timestamp() * 1000)
+
+
+def set_region(locale: str, language: str, timezone: str) -> dict:
+    """Replace the region; an unknown time zone is rejected."""
+    global _locale, _language, _timezone
+    if timezone and _zone(timezone) is None:
+        raise ValueError(f"Unknown time zone {timezone!r}")
+    if (locale, language, timezone) != (_locale, _language, _timezone):
+        logger.info("Region %s (%s), %s", locale or "-", language or "-", timezone or "local")
+    _locale, _language, _timezone = locale, language, timezone
+    return status()
+
+
+def status() -> dict:
+    return {
+        "locale": locale(),
+        "language": language(),
+        "timezone": _timezone or None,
+        "utc_offset_minutes": int(now().utcoffset().total_seconds() // 60) if tz() else None,
+    }
@@ -0,0 +1,24 @@
+"""Connectivity pushed by the desktop shell."""
+
+import logging
+
+logger = logging.getLogger(__name__)
+
+_online = True
+
+
+def is_online() -> bool:
+    """False while the shell reports no connectivity; skip remote calls."""
+    return _online
+
+
+def set_online(online: bool) -> dict:
+    global _online
+    if online != _online:
+        logger.info("Network %s", "online" if online else "offline")
+    _online = online
+    return status()
+
+
+def status() -> dict:
+    return {"online": _online}
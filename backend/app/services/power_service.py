@@ -0,0 +1,40 @@
+"""Power state pushed by the desktop shell (battery-aware throttling)."""
+
+import logging
+from typing import Optional
+
+import anyio
+
+from app.config import settings
+
+logger = logging.getLogger(__name__)
+
+# Worker threads while the shell reports power saver mode
+SAVER_THREADS = 2
+
+_saver = False
+_normal_threads: Optional[int] = None
+
+
+def is_saving() -> bool:
+    """True while scheduled background jobs should wait."""
+    return _saver
+
+
+def set_saver(saver: bool) -> dict:
+    """Switch power saver mode; must run on the event loop."""
+    global _saver, _normal_threads
+    limiter = anyio.to_thread.current_default_thread_limiter()
+    if _normal_threads is None:
+        _normal_threads = int(limiter.total_tokens)
+    if saver != _saver:
+        logger.info("Power saver %s", "on" if saver else "off")
+    _saver = saver
+    limiter.total_tokens = min(SAVER_THREADS, _normal_threads) if saver else _normal_threads
+    return {"saver": _saver, "worker_threads": int(limiter.total_tokens)}
+
+
+def status() -> dict:
+    limiter = anyio.to_thread.current_default_thread_limiter()
+    return {"saver": _saver, "worker_threads": int(limiter.total_tokens),
+            "configured_threads": settings.worker_threads}
@@ -4,11 +4,14 @@ import logging
 import re
 import shlex
 from dataclasses import dataclass, field
+from datetime import timedelta
 from typing import Optional
 
 from sqlalchemy import text
 from sqlalchemy.orm import Session
 
+from app.services import locale_service
+
 logger = logging.getLogger(__name__)
 
 
@@ -22,19 +25,40 @@ class ParsedQuery:
     language: Optional[str] = None
     pinned: Optional[bool] = None
     archived: Optional[bool] = None
+    # [from, to) in epoch ms; either end may be open
+    created: Optional[tuple[Optional[int], Optional[int]]] = None
+    updated: Optional[tuple[Optional[int], Optional[int]]] = None
 
 
 _FILTER_RE = re.compile(
-    r"(tag|collection|source|language|pinned|archived):(\S+)", re.IGNORECASE
+    r"(tag|collection|source|language|pinned|archived|created|updated):(\S+)", re.IGNORECASE
 )
 
 
+def _day_range(val: str) -> Optional[tuple[Optional[int], Optional[int]]]:
+    """`2026-10-15` is that day in the user's time zone; `>` means after it,
+    `<` before it. None if the date can't be read."""
+    op = val[0] if val[0] in "<>" else ""
+    day = locale_service.parse_date(val[len(op):])
+    if day is None:
+        return None
+    start = locale_service.day_start_ms(day)
+    end = locale_service.day_start_ms(day + timedelta(days=1))
+    if op == ">":
+        return (end, None)
+    if op == "<":
+        return (None, start)
+    return (start, end)
+
+
 def parse_query(q: str) -> ParsedQuery:
     """Parse a search-spec.md DSL query string.
 
     Filters:
       tag:<name>  collection:<name>  source:<name>  language:<name>
       pinned:true|false  archived:true|false
+      created:<date>  updated:<date>  (prefix > or < for after / before;
+      dates are read in the user's locale and time zone, see locale_service)
     Everything else → FTS MATCH terms.  Quoted phrases preserved.
     """
     result = ParsedQuery()
@@ -56,6 +80,10 @@ def parse_query(q: str) -> ParsedQuery:
             result.pinned = val.lower() in ("true", "1", "yes")
         elif key == "archived":
             result.archived = val.lower() in ("true", "1", "yes")
+        elif key == "created":
+            result.created = _day_range(val)
+        elif key == "updated":
+            result.updated = _day_range(val)
         remainder = remainder.replace(m.group(0), "", 1)
 
     # Remaining text → FTS terms (preserve quoted phrases)
@@ -139,6 +167,18 @@ def search(
     else:
         wheres.append("s.archived = 0")
 
+    # Created / Updated date ranges
+    for col, rng in (("created_at", parsed.created), ("updated_at", parsed.updated)):
+        if rng is None:
+            continue
+        start, end = rng
+        if start is not None:
+            wheres.append(f"s.{col} >= :{col}_from")
+            params[f"{col}_from"] = start
+        if end is not None:
+            wheres.append(f"s.{col} < :{col}_to")
+            params[f"{col}_to"] = end
+
     join_sql = "\n".join(joins)
     where_sql = " AND ".join(wheres)
 
@@ -1,4 +1,4 @@
-"""Comprehensive backend tests: CRUD, search, export/import, auth, license."""
+"""Comprehensive backend tests: CRUD, search, export/import, auth, license, backup, maintenance, locale."""
 
 import hashlib
 import json
@@ -444,6 +444,125 @@ class TestBackup:
         assert r.status_code == 200
         assert len(r.json()["items"]) > 0
 
+    def test_run_backup_tagged(self, client):
+        r = client.post("/api/backup/run", json={"tag": "pre-update-9.9.9"}, headers=auth())
+        assert r.status_code == 200
+        data = r.json()
+        assert data["name"].endswith("_pre-update-9.9.9")
+        assert data["tag"] == "pre-update-9.9.9"
+        listed = {b["name"]: b for b in client.get("/api/backup/list", headers=auth()).json()["items"]}
+        assert listed[data["name"]]["tag"] == "pre-update-9.9.9"
+
+    def test_run_backup_bad_tag(self, client):
+        r = client.post("/api/backup/run", json={"tag": "../escape"}, headers=auth())
+        assert r.status_code == 422
+
+    def test_run_backup_service_tag(self):
+        from app.services import backup_service
+
+        info = backup_service.run_backup(tag="svc-tag")
+        assert info["name"].endswith("_svc-tag")
+        assert info["tag"] == "svc-tag"
+        assert backup_service.run_backup()["tag"] is None
+
+    def test_scheduled_backup_waits_in_power_saver(self, client):
+        client.post("/api/power", json={"saver": True}, headers=auth())
+        try:
+            r = client.post("/api/backup/run", json={"scheduled": True}, headers=auth())
+            assert r.status_code == 409
+            assert r.json()["detail"]["code"] == "POWER_SAVER"
+            r = client.post("/api/backup/run", json={"tag": "manual-saver"}, headers=auth())
+            assert r.status_code == 200
+        finally:
+            client.post("/api/power", json={"saver": False}, headers=auth())
+
+    def test_verify_backup(self, client):
+        name = client.post("/api/backup/run", json={"tag": "verify-me"}, headers=auth()).json()["name"]
+        r = client.get(f"/api/backup/verify/{name}", headers=auth())
+        assert r.status_code == 200
+        assert r.json() == {"ok": True, "result": "ok"}
+
+    def test_verify_damaged_backup(self, client):
+        from app.config import settings as app_settings
+
+        folder = os.path.join(app_settings.get_backup_dir(), "damaged")
+        os.makedirs(folder, exist_ok=True)
+        with open(os.path.join(folder, "pinup.db"), "wb") as f:
+            f.write(b"SQLite format 3\0" + b"\xff" * 4096)
+        r = client.get("/api/backup/verify/damaged", headers=auth())
+        assert r.status_code == 200
+        assert r.json()["ok"] is False
+
+    def test_verify_missing_backup(self, client):
+        from app.services import backup_service
+
+        assert client.get("/api/backup/verify/nope", headers=auth()).status_code == 404
+        # ".." would resolve to the live database next to backups/
+        assert backup_service.verify_backup("..") is None
+
+
+# ──────────────────────────────────────────────────────────────────────
+# Maintenance
+# ──────────────────────────────────────────────────────────────────────
+class TestMaintenance:
+    def test_quick_integrity(self, client):
+        r = client.get("/api/maintenance/integrity", headers=auth())
+        assert r.status_code == 200
+        data = r.json()
+        assert data["ok"] is True
+        assert data["mode"] == "quick"
+        assert data["errors"] == []
+        assert data["db_size_bytes"] > 0
+
+    def test_full_integrity(self, client):
+        r = client.get("/api/maintenance/integrity?full=true", headers=auth())
+        assert r.status_code == 200
+        assert r.json()["mode"] == "full"
+        assert r.json()["ok"] is True
+
+    def test_requires_auth(self, client):
+        assert client.get("/api/maintenance/integrity").status_code == 401
+        assert client.post("/api/maintenance/optimize").status_code == 401
+
+    def test_optimize(self, client):
+        r = client.post("/api/maintenance/optimize", headers=auth())
+        assert r.status_code == 200
+        data = r.json()
+        assert data["reclaimed_bytes"] == max(0, data["size_before_bytes"] - data["size_after_bytes"])
+        assert data["wal_truncated"] is True
+        # The gate reopens afterwards
+        assert client.get("/api/snippets", headers=auth()).status_code == 200
+
+    def test_quiesced_holds_requests(self, client):
+        from app.security import quiesced
+
+        with quiesced():
+            r = client.get("/api/snippets", headers=auth())
+            assert r.status_code == 503
+            assert r.json()["code"] == "MAINTENANCE"
+            assert r.headers["Retry-After"] == "5"
+            # Health and maintenance endpoints stay reachable
+            assert client.get("/api/health").status_code == 200
+            assert client.get("/api/maintenance/integrity", headers=auth()).status_code == 200
+            # A second maintenance run is refused
+            r = client.post("/api/maintenance/optimize", headers=auth())
+            assert r.status_code == 409
+            assert r.json()["detail"]["code"] == "DB_BUSY"
+        assert client.get("/api/snippets", headers=auth()).status_code == 200
+
+    def test_quiesced_times_out(self, client):
+        from app.security import maintenance
+
+        maintenance._active += 1
+        try:
+            with pytest.raises(TimeoutError):
+                with maintenance.quiesced(timeout=0.1):
+                    pass
+        finally:
+            maintenance._active -= 1
+        assert maintenance._paused is False
+        assert client.get("/api/snippets", headers=auth()).status_code == 200
+
 
 # ──────────────────────────────────────────────────────────────────────
 # Settings (last because rotate_token invalidates current token)
@@ -516,3 +635,60 @@ class TestMCPHTTP:
         data = r.json()["data"]
         assert "snippets" in data
         assert "total" in data
+
+
+# ──────────────────────────────────────────────────────────────────────
+# Locale
+# ──────────────────────────────────────────────────────────────────────
+class TestLocale:
+    @pytest.fixture(autouse=True)
+    def restore_region(self, client):
+        yield
+        client.post("/api/locale", json={}, headers=auth())
+
+    def test_get_locale(self, client):
+        r = client.get("/api/locale", headers=auth())
+        assert r.status_code == 200
+        data = r.json()
+        assert {"locale", "language", "timezone", "utc_offset_minutes"} <= data.keys()
+
+    def test_set_locale(self, client):
+        r = client.post("/api/locale", json={
+            "locale": "de-DE", "language": "", "timezone": "Asia/Kolkata",
+        }, headers=auth())
+        assert r.status_code == 200
+        data = client.get("/api/locale", headers=auth()).json()
+        assert data["locale"] == "de-DE"
+        assert data["language"] == "de"
+        assert data["timezone"] == "Asia/Kolkata"
+        assert data["utc_offset_minutes"] == 330
+
+    def test_unknown_timezone(self, client):
+        r = client.post("/api/locale", json={"timezone": "Mars/Olympus"}, headers=auth())
+        assert r.status_code == 422
+        assert r.json()["detail"]["code"] == "VALIDATION_ERROR"
+
+    def test_requires_auth(self, client):
+        assert client.get("/api/locale").status_code == 401
+
+    def test_numeric_dates_follow_locale(self, client):
+        from datetime import date
+        from app.services import locale_service
+
+        client.post("/api/locale", json={"locale": "de-DE"}, headers=auth())
+        assert locale_service.parse_date("15.10.2026") == date(2026, 10, 15)
+        client.post("/api/locale", json={"locale": "en-US"}, headers=auth())
+        assert locale_service.parse_date("10/15/2026") == date(2026, 10, 15)
+        assert locale_service.parse_date("2026-10-15") == date(2026, 10, 15)
+        assert locale_service.parse_date("31/02/2026") is None
+
+    def test_search_created_today(self, client):
+        client.post("/api/locale", json={"timezone": "Pacific/Kiritimati"}, headers=auth())
+        client.post("/api/snippets", json={"body": "locale_date_term_q7"}, headers=auth())
+
+        r = client.get("/api/search?q=created:today locale_date_term_q7", headers=auth())
+        assert r.json()["total"] == 1
+        r = client.get("/api/search?q=created:<today locale_date_term_q7", headers=auth())
+        assert r.json()["total"] == 0
+        r = client.get("/api/search?q=updated:>yesterday locale_date_term_q7", headers=auth())
+        assert r.json()["total"] == 1
@@ -0,0 +1,609 @@
+// Accessibility capture — read the text of the UI element under the cursor.
+//
+// Uses the platform accessibility tree (AX on macOS, UI Automation on
+// Windows, AT-SPI on Linux) rather than OCR, so labels, tooltips, and other
+// unselectable text come through exactly. Bound to a global hotkey that files
+// the result as a snippet; `capture_element_text` returns it to the caller.
+// Whole-window snapshots (`window_text`) back meeting mode.
+
+use serde::Serialize;
+use tauri::{AppHandle, GlobalShortcutManager};
+
+use crate::capture::{self, Capture};
+
+pub const CAPTURE_SHORTCUT: &str = "CmdOrCtrl+Shift+E";
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ElementText {
+    pub text: String,
+    pub role: Option<String>,
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct WindowInfo {
+    /// Opaque, platform-specific handle accepted by `window_text`.
+    pub id: String,
+    pub app: String,
+    pub title: String,
+}
+
+/// Upper bound on accessibility nodes visited per window snapshot.
+const WINDOW_TEXT_BUDGET: usize = 5000;
+
+fn non_empty(s: String) -> Option<String> {
+    if s.trim().is_empty() {
+        None
+    } else {
+        Some(s)
+    }
+}
+
+// ── macOS: AXUIElement ─────────────────────────────────────────────────────
+#[cfg(target_os = "macos")]
+mod imp {
+    use super::{non_empty, ElementText, WindowInfo, WINDOW_TEXT_BUDGET};
+    use crate::app_context::ns_string;
+    use accessibility_sys::{
+        kAXChildrenAttribute, kAXDescriptionAttribute, kAXErrorAPIDisabled, kAXErrorSuccess,
+        kAXRoleAttribute, kAXTitleAttribute, kAXValueAttribute, kAXWindowsAttribute,
+        AXUIElementCopyAttributeValue, AXUIElementCopyElementAtPosition,
+        AXUIElementCreateApplication, AXUIElementCreateSystemWide, AXUIElementRef,
+    };
+    use core_foundation::array::{CFArray, CFArrayRef};
+    use core_foundation::base::{CFRelease, CFType, CFTypeRef, TCFType};
+    use core_foundation::string::CFString;
+    use core_graphics::event::CGEvent;
+    use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
+    use objc::runtime::Object;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    const TEXT_ROLES: &[&str] = &["AXStaticText", "AXTextArea", "AXTextField"];
+
+    fn cursor() -> Result<(f64, f64), String> {
+        let source = CGEventSource::new(CGEventSourceStateID::CombinedSessionState)
+            .map_err(|_| "Could not create event source".to_string())?;
+        let event = CGEvent::new(source).map_err(|_| "Could not read cursor".to_string())?;
+        let p = event.location();
+        Ok((p.x, p.y))
+    }
+
+    unsafe fn copy_attr(element: AXUIElementRef, name: &str) -> Option<CFType> {
+        let attr = CFString::new(name);
+        let mut value: CFTypeRef = std::ptr::null();
+        let err = AXUIElementCopyAttributeValue(element, attr.as_concrete_TypeRef(), &mut value);
+        if err != kAXErrorSuccess || value.is_null() {
+            return None;
+        }
+        Some(CFType::wrap_under_create_rule(value))
+    }
+
+    unsafe fn string_attr(element: AXUIElementRef, name: &str) -> Option<String> {
+        copy_attr(element, name)?
+            .downcast::<CFString>()
+            .and_then(|s| non_empty(s.to_string()))
+    }
+
+    unsafe fn element_list(element: AXUIElementRef, name: &str) -> Vec<CFType> {
+        let Some(value) = copy_attr(element, name) else {
+            return Vec::new();
+        };
+        if value.type_of() != CFArray::<CFType>::type_id() {
+            return Vec::new();
+        }
+        let array = CFArray::<CFType>::wrap_under_get_rule(value.as_CFTypeRef() as CFArrayRef);
+        array.iter().map(|item| item.clone()).collect()
+    }
+
+    fn as_element(value: &CFType) -> AXUIElementRef {
+        value.as_CFTypeRef() as AXUIElementRef
+    }
+
+    unsafe fn app_windows(pid: i32) -> Vec<CFType> {
+        let app = CFType::wrap_under_create_rule(AXUIElementCreateApplication(pid) as CFTypeRef);
+        element_list(as_element(&app), kAXWindowsAttribute)
+    }
+
+    pub fn list_windows() -> Result<Vec<WindowInfo>, String> {
+        let mut windows = Vec::new();
+        unsafe {
+            let workspace: *mut Object = msg_send![class!(NSWorkspace), sharedWorkspace];
+            let apps: *mut Object = msg_send![workspace, runningApplications];
+            let count: usize = msg_send![apps, count];
+            for i in 0..count {
+                let app: *mut Object = msg_send![apps, objectAtIndex: i];
+                // NSApplicationActivationPolicyRegular — skip agents and daemons.
+                let policy: isize = msg_send![app, activationPolicy];
+                if policy != 0 {
+                    continue;
+                }
+                let pid: i32 = msg_send![app, processIdentifier];
+                let name: *mut Object = msg_send![app, localizedName];
+                let name = ns_string(name);
+                for (index, window) in app_windows(pid).iter().enumerate() {
+                    windows.push(WindowInfo {
+                        id: format!("{}:{}", pid, index),
+                        app: name.clone(),
+                        title: string_attr(as_element(window), kAXTitleAttribute)
+                            .unwrap_or_default(),
+                    });
+                }
+            }
+        }
+        Ok(windows)
+    }
+
+    unsafe fn collect_text(element: AXUIElementRef, out: &mut Vec<String>, budget: &mut usize) {
+        if *budget == 0 {
+            return;
+        }
+        *budget -= 1;
+        let role = string_attr(element, kAXRoleAttribute).unwrap_or_default();
+        if TEXT_ROLES.contains(&role.as_str()) {
+            if let Some(text) = string_attr(element, kAXValueAttribute) {
+                out.push(text);
+            }
+        }
+        for child in element_list(element, kAXChildrenAttribute) {
+            collect_text(as_element(&child), out, budget);
+        }
+    }
+
+    pub fn window_text(id: &str) -> Result<String, String> {
+        let (pid, index) = id
+            .split_once(':')
+            .and_then(|(p, i)| Some((p.parse::<i32>().ok()?, i.parse::<usize>().ok()?)))
+            .ok_or("Invalid window id")?;
+        unsafe {
+            let windows = app_windows(pid);
+            let window = windows.get(index).ok_or("Window is no longer open")?;
+            let mut out = Vec::new();
+            let mut budget = WINDOW_TEXT_BUDGET;
+            collect_text(as_element(window), &mut out, &mut budget);
+            Ok(out.join("\n"))
+        }
+    }
+
+    pub fn element_text_at_cursor() -> Result<ElementText, String> {
+        let (x, y) = cursor()?;
+        unsafe {
+            let system = AXUIElementCreateSystemWide();
+            let mut element: AXUIElementRef = std::ptr::null_mut();
+            let err = AXUIElementCopyElementAtPosition(system, x as f32, y as f32, &mut element);
+            CFRelease(system as CFTypeRef);
+            if err == kAXErrorAPIDisabled {
+                return Err("Accessibility permission has not been granted".into());
+            }
+            if err != kAXErrorSuccess || element.is_null() {
+                return Err(format!(
+                    "No accessible element under cursor (AXError {})",
+                    err
+                ));
+            }
+            let text = [
+                kAXValueAttribute,
+                kAXTitleAttribute,
+                kAXDescriptionAttribute,
+            ]
+            .iter()
+            .find_map(|attr| string_attr(element, attr));
+            let role = string_attr(element, kAXRoleAttribute);
+            CFRelease(element as CFTypeRef);
+            let text = text.ok_or("Element under cursor has no text")?;
+            Ok(ElementText { text, role, x, y })
+        }
+    }
+}
+
+// ── Windows: UI Automation ─────────────────────────────────────────────────
+#[cfg(target_os = "windows")]
+mod imp {
+    use super::{non_empty, ElementText, WindowInfo, WINDOW_TEXT_BUDGET};
+    use crate::app_context;
+    use windows::Win32::Foundation::{HWND, POINT};
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED,
+    };
+    use windows::Win32::UI::Accessibility::{
+        CUIAutomation, IUIAutomation, IUIAutomationElement, IUIAutomationValuePattern,
+        TreeScope_Children, TreeScope_Descendants, UIA_DocumentControlTypeId,
+        UIA_EditControlTypeId, UIA_TextControlTypeId, UIA_ValuePatternId,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
+
+    unsafe fn automation() -> Result<IUIAutomation, String> {
+        // Already-initialized is fine; we only need COM on this thread.
+        CoInitializeEx(None, COINIT_MULTITHREADED).ok();
+        CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER)
+            .map_err(|e| format!("UI Automation unavailable: {e}"))
+    }
+
+    unsafe fn value_of(element: &IUIAutomationElement) -> Option<String> {
+        element
+            .GetCurrentPatternAs::<IUIAutomationValuePattern>(UIA_ValuePatternId)
+            .and_then(|p| p.CurrentValue())
+            .ok()
+            .and_then(|b| non_empty(b.to_string()))
+    }
+
+    pub fn element_text_at_cursor() -> Result<ElementText, String> {
+        unsafe {
+            let mut pt = POINT::default();
+            GetCursorPos(&mut pt).map_err(|e| format!("Could not read cursor: {e}"))?;
+            let automation = automation()?;
+            let element = automation
+                .ElementFromPoint(pt)
+                .map_err(|e| format!("No accessible element under cursor: {e}"))?;
+
+            let text = value_of(&element)
+                .or_else(|| {
+                    element
+                        .CurrentName()
+                        .ok()
+                        .and_then(|b| non_empty(b.to_string()))
+                })
+                .or_else(|| {
+                    element
+                        .CurrentHelpText()
+                        .ok()
+                        .and_then(|b| non_empty(b.to_string()))
+                })
+                .ok_or("Element under cursor has no text")?;
+            let role = element
+                .CurrentLocalizedControlType()
+                .ok()
+                .and_then(|b| non_empty(b.to_string()));
+            Ok(ElementText {
+                text,
+                role,
+                x: pt.x as f64,
+                y: pt.y as f64,
+            })
+        }
+    }
+
+    pub fn list_windows() -> Result<Vec<WindowInfo>, String> {
+        unsafe {
+            let automation = automation()?;
+            let all = automation
+                .CreateTrueCondition()
+                .map_err(|e| e.to_string())?;
+            let top_level = automation
+                .GetRootElement()
+                .and_then(|root| root.FindAll(TreeScope_Children, &all))
+                .map_err(|e| format!("Could not list windows: {e}"))?;
+            let mut windows = Vec::new();
+            for i in 0..top_level.Length().unwrap_or(0) {
+                let Ok(element) = top_level.GetElement(i) else {
+                    continue;
+                };
+                let title = element
+                    .CurrentName()
+                    .map(|b| b.to_string())
+                    .unwrap_or_default();
+                let Ok(hwnd) = element.CurrentNativeWindowHandle() else {
+                    continue;
+                };
+                if title.is_empty() {
+                    continue;
+                }
+                let app = element
+                    .CurrentProcessId()
+                    .ok()
+                    .and_then(|pid| app_context::process_name(pid as u32))
+                    .unwrap_or_default();
+                windows.push(WindowInfo {
+                    id: hwnd.0.to_string(),
+                    app,
+                    title,
+                });
+            }
+            Ok(windows)
+        }
+    }
+
+    pub fn window_text(id: &str) -> Result<String, String> {
+        let hwnd = HWND(id.parse::<isize>().map_err(|_| "Invalid window id")?);
+        unsafe {
+            let automation = automation()?;
+            let all = automation
+                .CreateTrueCondition()
+                .map_err(|e| e.to_string())?;
+            let found = automation
+                .ElementFromHandle(hwnd)
+                .and_then(|window| window.FindAll(TreeScope_Descendants, &all))
+                .map_err(|e| format!("Window is no longer open: {e}"))?;
+            let count = found.Length().unwrap_or(0).min(WINDOW_TEXT_BUDGET as i32);
+            let mut out = Vec::new();
+            for i in 0..count {
+                let Ok(element) = found.GetElement(i) else {
+                    continue;
+                };
+                let kind = element.CurrentControlType().unwrap_or_default();
+                let text = if kind == UIA_TextControlTypeId {
+                    element
+                        .CurrentName()
+                        .ok()
+                        .and_then(|b| non_empty(b.to_string()))
+                } else if kind == UIA_EditControlTypeId || kind == UIA_DocumentControlTypeId {
+                    value_of(&element)
+                } else {
+                    None
+                };
+                out.extend(text);
+            }
+            Ok(out.join("\n"))
+        }
+    }
+}
+
+// ── Linux: AT-SPI over D-Bus ───────────────────────────────────────────────
+#[cfg(target_os = "linux")]
+mod imp {
+    use super::{non_empty, ElementText, WindowInfo, WINDOW_TEXT_BUDGET};
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::ConnectionExt;
+    use zbus::blocking::{Connection as Bus, ConnectionBuilder, Proxy};
+    use zbus::zvariant::OwnedObjectPath;
+
+    const NULL_PATH: &str = "/org/a11y/atspi/null";
+    const ACCESSIBLE: &str = "org.a11y.atspi.Accessible";
+    const COMPONENT: &str = "org.a11y.atspi.Component";
+    const TEXT: &str = "org.a11y.atspi.Text";
+    const STATE_ACTIVE: u32 = 1;
+    const COORD_SCREEN: u32 = 0;
+    const TEXT_ROLES: &[&str] = &[
+        "label",
+        "text",
+        "paragraph",
+        "heading",
+        "static",
+        "caption",
+        "entry",
+        "terminal",
+    ];
+
+    type Node = (String, OwnedObjectPath);
+
+    fn root() -> Node {
+        (
+            "org.a11y.atspi.Registry".into(),
+            OwnedObjectPath::try_from("/org/a11y/atspi/accessible/root").unwrap(),
+        )
+    }
+
+    fn cursor() -> Result<(i32, i32), String> {
+        let (conn, screen) = x11rb::connect(None).map_err(|e| format!("No X display: {e}"))?;
+        let root = conn.setup().roots[screen].root;
+        let reply = conn
+            .query_pointer(root)
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| e.to_string())?;
+        Ok((reply.root_x as i32, reply.root_y as i32))
+    }
+
+    fn a11y_bus() -> Result<Bus, String> {
+        let session = Bus::session().map_err(|e| format!("No session bus: {e}"))?;
+        let reply = session
+            .call_method(
+                Some("org.a11y.Bus"),
+                "/org/a11y/bus",
+                Some("org.a11y.Bus"),
+                "GetAddress",
+                &(),
+            )
+            .map_err(|e| format!("AT-SPI bus unavailable: {e}"))?;
+        let address: String = reply.body().map_err(|e| e.to_string())?;
+        ConnectionBuilder::address(address.as_str())
+            .and_then(|b| b.build())
+            .map_err(|e| format!("Could not connect to AT-SPI bus: {e}"))
+    }
+
+    fn proxy<'a>(bus: &'a Bus, node: &'a Node, iface: &'a str) -> zbus::Result<Proxy<'a>> {
+        Proxy::new(bus, node.0.as_str(), node.1.as_str(), iface)
+    }
+
+    fn children(bus: &Bus, node: &Node) -> Vec<Node> {
+        proxy(bus, node, ACCESSIBLE)
+            .and_then(|p| p.call("GetChildren", &()))
+            .unwrap_or_default()
+    }
+
+    fn name_of(bus: &Bus, node: &Node) -> String {
+        proxy(bus, node, ACCESSIBLE)
+            .and_then(|p| p.get_property::<String>("Name"))
+            .unwrap_or_default()
+    }
+
+    fn role_of(bus: &Bus, node: &Node) -> Option<String> {
+        proxy(bus, node, ACCESSIBLE)
+            .and_then(|p| p.call::<_, _, String>("GetRoleName", &()))
+            .ok()
+            .and_then(non_empty)
+    }
+
+    fn is_active(bus: &Bus, node: &Node) -> bool {
+        proxy(bus, node, ACCESSIBLE)
+            .and_then(|p| p.call::<_, _, Vec<u32>>("GetState", &()))
+            .map(|states| states.first().is_some_and(|s| s & (1 << STATE_ACTIVE) != 0))
+            .unwrap_or(false)
+    }
+
+    fn child_at_point(bus: &Bus, node: &Node, x: i32, y: i32) -> Option<Node> {
+        let child: Node = proxy(bus, node, COMPONENT)
+            .and_then(|p| p.call("GetAccessibleAtPoint", &(x, y, COORD_SCREEN)))
+            .ok()?;
+        if child.1.as_str() == NULL_PATH || child == *node {
+            None
+        } else {
+            Some(child)
+        }
+    }
+
+    fn text_of(bus: &Bus, node: &Node) -> Option<String> {
+        if let Ok(text) = proxy(bus, node, TEXT) {
+            if let Ok(count) = text.call::<_, _, i32>("GetCharacterCount", &()) {
+                if let Ok(s) = text.call::<_, _, String>("GetText", &(0i32, count)) {
+                    if let Some(s) = non_empty(s) {
+                        return Some(s);
+                    }
+                }
+            }
+        }
+        let accessible = proxy(bus, node, ACCESSIBLE).ok()?;
+        accessible
+            .get_property::<String>("Name")
+            .ok()
+            .and_then(non_empty)
+            .or_else(|| {
+                accessible
+                    .get_property::<String>("Description")
+                    .ok()
+                    .and_then(non_empty)
+            })
+    }
+
+    pub fn element_text_at_cursor() -> Result<ElementText, String> {
+        let (x, y) = cursor()?;
+        let bus = a11y_bus()?;
+
+        // Find the active top-level window, then descend to the deepest hit.
+        let window = children(&bus, &root())
+            .iter()
+            .flat_map(|app| children(&bus, app))
+            .find(|w| is_active(&bus, w))
+            .ok_or("No active accessible window")?;
+        let mut element = window;
+        while let Some(child) = child_at_point(&bus, &element, x, y) {
+            element = child;
+        }
+
+        let text = text_of(&bus, &element).ok_or("Element under cursor has no text")?;
+        let role = role_of(&bus, &element);
+        Ok(ElementText {
+            text,
+            role,
+            x: x as f64,
+            y: y as f64,
+        })
+    }
+
+    pub fn list_windows() -> Result<Vec<WindowInfo>, String> {
+        let bus = a11y_bus()?;
+        let mut windows = Vec::new();
+        for app in children(&bus, &root()) {
+            let app_name = name_of(&bus, &app);
+            for window in children(&bus, &app) {
+                windows.push(WindowInfo {
+                    id: format!("{}|{}", window.0, window.1.as_str()),
+                    app: app_name.clone(),
+                    title: name_of(&bus, &window),
+                });
+            }
+        }
+        Ok(windows)
+    }
+
+    fn collect_text(bus: &Bus, node: &Node, out: &mut Vec<String>, budget: &mut usize) {
+        if *budget == 0 {
+            return;
+        }
+        *budget -= 1;
+        let role = role_of(bus, node).unwrap_or_default();
+        if TEXT_ROLES.contains(&role.as_str()) {
+            out.extend(text_of(bus, node));
+        }
+        for child in children(bus, node) {
+            collect_text(bus, &child, out, budget);
+        }
+    }
+
+    pub fn window_text(id: &str) -> Result<String, String> {
+        let (name, path) = id.split_once('|').ok_or("Invalid window id")?;
+        let path = OwnedObjectPath::try_from(path).map_err(|_| "Invalid window id")?;
+        let window: Node = (name.to_string(), path);
+        let bus = a11y_bus()?;
+        let mut out = Vec::new();
+        let mut budget = WINDOW_TEXT_BUDGET;
+        collect_text(&bus, &window, &mut out, &mut budget);
+        Ok(out.join("\n"))
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod imp {
+    use super::{ElementText, WindowInfo};
+
+    pub fn element_text_at_cursor() -> Result<ElementText, String> {
+        Err("Accessibility capture is not supported on this platform".into())
+    }
+
+    pub fn list_windows() -> Result<Vec<WindowInfo>, String> {
+        Ok(Vec::new())
+    }
+
+    pub fn window_text(_id: &str) -> Result<String, String> {
+        Err("Accessibility capture is not supported on this platform".into())
+    }
+}
+
+async fn read_element_text() -> Result<ElementText, String> {
+    tauri::async_runtime::spawn_blocking(imp::element_text_at_cursor)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+pub async fn list_windows() -> Result<Vec<WindowInfo>, String> {
+    tauri::async_runtime::spawn_blocking(imp::list_windows)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// All readable text in the window, one accessibility node per line.
+pub async fn window_text(id: String) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || imp::window_text(&id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// ── Hotkey ─────────────────────────────────────────────────────────────────
+pub fn register_shortcut(app: &AppHandle) {
+    let handle = app.clone();
+    let result = app
+        .global_shortcut_manager()
+        .register(CAPTURE_SHORTCUT, move || {
+            let handle = handle.clone();
+            tauri::async_runtime::spawn(async move {
+                let result = match read_element_text().await {
+                    Ok(element) => {
+                        let capture = Capture {
+                            body: element.text,
+                            source: "accessibility".into(),
+                            ..Default::default()
+                        };
+                        capture::submit(&handle, capture).await.map(|_| ())
+                    }
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    log::warn!("Element capture failed: {}", e);
+                }
+            });
+        });
+    if let Err(e) = result {
+        log::warn!("Could not register {}: {}", CAPTURE_SHORTCUT, e);
+    }
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub async fn capture_element_text() -> Result<ElementText, String> {
+    read_element_text().await
+}
+
+#[tauri::command]
+pub async fn list_capture_windows() -> Result<Vec<WindowInfo>, String> {
+    list_windows().await
+}
@@ -0,0 +1,204 @@
+// App context — which application (and window) currently has focus.
+//
+// Used to attribute captures to their source app and to apply per-app rules
+// such as ignoring password managers.
+
+use serde::Serialize;
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct AppContext {
+    /// Human-readable app name ("Firefox", "1Password 7").
+    pub name: String,
+    /// Bundle id on macOS, executable name on Windows, WM_CLASS on Linux.
+    pub id: String,
+    pub window_title: Option<String>,
+}
+
+impl AppContext {
+    /// Case-insensitive match of `pattern` against the app name or id.
+    pub fn matches(&self, pattern: &str) -> bool {
+        let pattern = pattern.to_lowercase();
+        self.name.to_lowercase().contains(&pattern) || self.id.to_lowercase().contains(&pattern)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use super::AppContext;
+    use objc::runtime::Object;
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::ffi::CStr;
+    use std::os::raw::c_char;
+
+    pub unsafe fn ns_string(s: *mut Object) -> String {
+        if s.is_null() {
+            return String::new();
+        }
+        let utf8: *const c_char = msg_send![s, UTF8String];
+        if utf8.is_null() {
+            return String::new();
+        }
+        CStr::from_ptr(utf8).to_string_lossy().into_owned()
+    }
+
+    pub fn frontmost() -> Option<AppContext> {
+        unsafe {
+            let workspace: *mut Object = msg_send![class!(NSWorkspace), sharedWorkspace];
+            let app: *mut Object = msg_send![workspace, frontmostApplication];
+            if app.is_null() {
+                return None;
+            }
+            let name: *mut Object = msg_send![app, localizedName];
+            let bundle: *mut Object = msg_send![app, bundleIdentifier];
+            Some(AppContext {
+                name: ns_string(name),
+                id: ns_string(bundle),
+                window_title: None,
+            })
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use super::AppContext;
+    use std::path::Path;
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, GetWindowTextW, GetWindowThreadProcessId,
+    };
+
+    fn process_image(pid: u32) -> Option<String> {
+        unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+            let mut buf = [0u16; 1024];
+            let mut size = buf.len() as u32;
+            let ok = QueryFullProcessImageNameW(
+                process,
+                PROCESS_NAME_WIN32,
+                PWSTR(buf.as_mut_ptr()),
+                &mut size,
+            );
+            CloseHandle(process).ok();
+            ok.ok()?;
+            Some(String::from_utf16_lossy(&buf[..size as usize]))
+        }
+    }
+
+    /// Executable name without extension, e.g. "firefox".
+    pub fn process_name(pid: u32) -> Option<String> {
+        let exe = process_image(pid)?;
+        Path::new(&exe)
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+    }
+
+    pub fn frontmost() -> Option<AppContext> {
+        unsafe {
+            let hwnd = GetForegroundWindow();
+            if hwnd.0 == 0 {
+                return None;
+            }
+            let mut title = [0u16; 512];
+            let len = GetWindowTextW(hwnd, &mut title) as usize;
+            let title = String::from_utf16_lossy(&title[..len]);
+
+            let mut pid = 0u32;
+            GetWindowThreadProcessId(hwnd, Some(&mut pid));
+            let exe = process_image(pid)?;
+            let path = Path::new(&exe);
+            Some(AppContext {
+                name: path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                id: path
+                    .file_name()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                window_title: if title.is_empty() { None } else { Some(title) },
+            })
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use super::AppContext;
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{AtomEnum, ConnectionExt};
+
+    fn atom(conn: &impl Connection, name: &str) -> Option<u32> {
+        conn.intern_atom(false, name.as_bytes())
+            .ok()?
+            .reply()
+            .ok()
+            .map(|r| r.atom)
+    }
+
+    fn property(conn: &impl Connection, window: u32, prop: u32, ty: u32) -> Option<Vec<u8>> {
+        conn.get_property(false, window, prop, ty, 0, 1024)
+            .ok()?
+            .reply()
+            .ok()
+            .map(|r| r.value)
+    }
+
+    pub fn frontmost() -> Option<AppContext> {
+        let (conn, screen) = x11rb::connect(None).ok()?;
+        let root = conn.setup().roots[screen].root;
+        let active = atom(&conn, "_NET_ACTIVE_WINDOW")?;
+        let value = property(&conn, root, active, AtomEnum::WINDOW.into())?;
+        let window = u32::from_ne_bytes(value.get(..4)?.try_into().ok()?);
+        if window == 0 {
+            return None;
+        }
+
+        // WM_CLASS is "instance\0class\0"; the class is the friendlier name.
+        let class = property(
+            &conn,
+            window,
+            AtomEnum::WM_CLASS.into(),
+            AtomEnum::STRING.into(),
+        )
+        .unwrap_or_default();
+        let mut parts = class
+            .split(|b| *b == 0)
+            .filter(|p| !p.is_empty())
+            .map(|p| String::from_utf8_lossy(p).into_owned());
+        let instance = parts.next().unwrap_or_default();
+        let class = parts.next().unwrap_or_else(|| instance.clone());
+
+        let title = atom(&conn, "_NET_WM_NAME")
+            .zip(atom(&conn, "UTF8_STRING"))
+            .and_then(|(name, utf8)| property(&conn, window, name, utf8))
+            .map(|v| String::from_utf8_lossy(&v).into_owned())
+            .filter(|t| !t.is_empty());
+
+        Some(AppContext {
+            name: class,
+            id: instance,
+            window_title: title,
+        })
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod imp {
+    use super::AppContext;
+
+    pub fn frontmost() -> Option<AppContext> {
+        None
+    }
+}
+
+pub use imp::frontmost;
+#[cfg(target_os = "macos")]
+pub use imp::ns_string;
+#[cfg(target_os = "windows")]
+pub use imp::process_name;
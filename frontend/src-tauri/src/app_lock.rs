@@ -0,0 +1,663 @@
+// App lock — a passcode lock on the app, separate from database encryption.
+//
+// The app locks on `lock_app`, the tray's Lock item, LOCK_SHORTCUT, or once
+// system idle time (no keyboard or mouse input anywhere, read every
+// POLL_INTERVAL) passes `idle_minutes`:
+//   macOS    CGEventSourceSecondsSinceLastEventType
+//   Windows  GetLastInputInfo
+//   Linux    Mutter's IdleMonitor, else the ScreenSaver service (KDE)
+// Locking hides every window behind a passcode prompt (lock.html). While
+// locked, `guard` rejects every IPC command but those in ALLOWED, the
+// clipboard watcher records nothing (see clipboard.rs), the tray only offers
+// the prompt, and links and helper requests (see instance.rs) are refused.
+// `unlock_app` brings the hidden windows back, and so does
+// `unlock_with_biometrics` (Touch ID through LocalAuthentication on macOS,
+// Windows Hello through UserConsentVerifier), which the prompt tries first
+// when it is turned on and available; elsewhere the passcode is the only
+// way. Keyring secrets (see keyring.rs) can't be read until then. The
+// passcode is kept in the keyring as an Argon2id salt and check, like the
+// database key (see encryption.rs), and can't be recovered. With idle
+// locking on, the app also starts locked, the main window waiting behind
+// the prompt. After FREE_ATTEMPTS wrong passcodes in a row each further try
+// waits twice as long as the last, from RETRY_DELAY up to MAX_RETRY_DELAY;
+// the count is kept in the config, so relaunching doesn't reset it.
+// Changes go out as "app-locked" and "app-unlocked".
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{
+    AppHandle, GlobalShortcutManager, Invoke, Manager, Window, WindowBuilder, WindowEvent,
+    WindowUrl,
+};
+
+use crate::encryption::{argon2_key, ARGON2_MEMORY_KIB, ARGON2_PASSES};
+use crate::{config, i18n, keyring, tray};
+
+pub const LOCK_SHORTCUT: &str = "CmdOrCtrl+Alt+L";
+const WINDOW_LABEL: &str = "lock";
+const KEYRING_ACCOUNT: &str = "app-lock";
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+const MIN_PASSCODE: usize = 4;
+const FREE_ATTEMPTS: u32 = 3;
+const RETRY_DELAY: Duration = Duration::from_secs(30);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(15 * 60);
+/// IPC commands that still work while locked: the prompt's own.
+const ALLOWED: &[&str] = &[
+    "get_app_lock_status",
+    "unlock_app",
+    "unlock_with_biometrics",
+];
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct AppLockConfig {
+    pub enabled: bool,
+    pub idle_minutes: u32,
+    /// The passcode itself is in the keyring.
+    pub has_passcode: bool,
+    /// Where earlier versions kept the passcode; moved to the keyring.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub salt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passcode_check: Option<String>,
+    /// Offer Touch ID / Windows Hello where available.
+    pub biometrics: bool,
+    /// Wrong passcodes in a row.
+    pub failed_unlocks: u32,
+    /// When the next try is allowed, in Unix seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<u64>,
+}
+
+impl Default for AppLockConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_minutes: 10,
+            has_passcode: false,
+            salt: None,
+            passcode_check: None,
+            biometrics: true,
+            failed_unlocks: 0,
+            retry_after: None,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct AppLockStatus {
+    enabled: bool,
+    idle_minutes: u32,
+    locked: bool,
+    has_passcode: bool,
+    /// Whether idle time can be read here; locking by hand always works.
+    idle_supported: bool,
+    biometrics: bool,
+    /// "Touch ID" or "Windows Hello" when set up on this machine.
+    biometrics_name: Option<&'static str>,
+}
+
+static LOCKED: AtomicBool = AtomicBool::new(false);
+/// Windows hidden by the lock, to show again on unlock.
+static HIDDEN: Mutex<Vec<String>> = Mutex::new(Vec::new());
+/// Whether the biometric prompt was usable when last checked.
+static BIOMETRICS: AtomicBool = AtomicBool::new(false);
+/// Set while a passcode is being checked, so tries can't run in parallel.
+static CHECKING: AtomicBool = AtomicBool::new(false);
+
+#[cfg(target_os = "macos")]
+fn idle() -> Option<Duration> {
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceSecondsSinceLastEventType(state: i32, event_type: u32) -> f64;
+    }
+    /// kCGEventSourceStateCombinedSessionState, kCGAnyInputEventType.
+    const COMBINED_SESSION: i32 = 0;
+    const ANY_INPUT: u32 = u32::MAX;
+
+    let seconds = unsafe { CGEventSourceSecondsSinceLastEventType(COMBINED_SESSION, ANY_INPUT) };
+    (seconds.is_finite() && seconds >= 0.0).then(|| Duration::from_secs_f64(seconds))
+}
+
+#[cfg(windows)]
+fn idle() -> Option<Duration> {
+    use windows::Win32::System::SystemInformation::GetTickCount;
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+    if !unsafe { GetLastInputInfo(&mut info) }.as_bool() {
+        return None;
+    }
+    // Both wrap after 49.7 days.
+    let ms = unsafe { GetTickCount() }.wrapping_sub(info.dwTime);
+    Some(Duration::from_millis(ms as u64))
+}
+
+#[cfg(target_os = "linux")]
+fn idle() -> Option<Duration> {
+    use once_cell::sync::OnceCell;
+    use zbus::blocking::{Connection, Proxy};
+
+    static BUS: OnceCell<Option<Connection>> = OnceCell::new();
+    let bus = BUS.get_or_init(|| Connection::session().ok()).as_ref()?;
+    let call = |destination: &'static str, path: &'static str, method: &str| {
+        Proxy::new(bus, destination, path, destination)
+            .and_then(|proxy| proxy.call::<_, _, u64>(method, &()))
+            .ok()
+    };
+    let ms = call(
+        "org.gnome.Mutter.IdleMonitor",
+        "/org/gnome/Mutter/IdleMonitor/Core",
+        "GetIdletime",
+    )
+    .or_else(|| {
+        Proxy::new(
+            bus,
+            "org.freedesktop.ScreenSaver",
+            "/org/freedesktop/ScreenSaver",
+            "org.freedesktop.ScreenSaver",
+        )
+        .and_then(|proxy| proxy.call::<_, _, u32>("GetSessionIdleTime", &()))
+        .ok()
+        .map(u64::from)
+    })?;
+    Some(Duration::from_millis(ms))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn idle() -> Option<Duration> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+mod biometrics {
+    use std::ffi::CString;
+    use std::sync::mpsc;
+
+    use block::ConcreteBlock;
+    use objc::runtime::{Object, BOOL, YES};
+    use objc::{class, msg_send, sel, sel_impl};
+
+    pub const NAME: &str = "Touch ID";
+    /// Finishes "Pin-Up AI is trying to …".
+    const REASON: &str = "unlock your notes";
+    /// LAPolicyDeviceOwnerAuthenticationWithBiometrics.
+    const POLICY: isize = 1;
+
+    #[link(name = "LocalAuthentication", kind = "framework")]
+    extern "C" {}
+
+    pub fn available() -> bool {
+        unsafe {
+            let context: *mut Object = msg_send![class!(LAContext), new];
+            if context.is_null() {
+                return false;
+            }
+            let error: *mut *mut Object = std::ptr::null_mut();
+            let ok: BOOL = msg_send![context, canEvaluatePolicy: POLICY error: error];
+            let _: () = msg_send![context, release];
+            ok == YES
+        }
+    }
+
+    /// Shows the Touch ID sheet and waits for the answer.
+    pub fn verify(_window: Option<isize>) -> Result<bool, String> {
+        let reason = CString::new(REASON).unwrap_or_default();
+        let (tx, rx) = mpsc::channel();
+        unsafe {
+            let context: *mut Object = msg_send![class!(LAContext), new];
+            if context.is_null() {
+                return Err("LocalAuthentication is unavailable".into());
+            }
+            let reason: *mut Object =
+                msg_send![class!(NSString), stringWithUTF8String: reason.as_ptr()];
+            let reply = ConcreteBlock::new(move |success: BOOL, _error: *mut Object| {
+                tx.send(success == YES).ok();
+            })
+            .copy();
+            let _: () =
+                msg_send![context, evaluatePolicy: POLICY localizedReason: reason reply: &*reply];
+            let verified = rx.recv().unwrap_or(false);
+            let _: () = msg_send![context, release];
+            Ok(verified)
+        }
+    }
+}
+
+#[cfg(windows)]
+mod biometrics {
+    use windows::core::{factory, HSTRING};
+    use windows::Foundation::IAsyncOperation;
+    use windows::Security::Credentials::UI::{
+        UserConsentVerificationResult, UserConsentVerifier, UserConsentVerifierAvailability,
+    };
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::System::WinRT::IUserConsentVerifierInterop;
+
+    pub const NAME: &str = "Windows Hello";
+    const REASON: &str = "Unlock Pin-Up AI";
+
+    pub fn available() -> bool {
+        UserConsentVerifier::CheckAvailabilityAsync()
+            .and_then(|operation| operation.get())
+            .is_ok_and(|availability| availability == UserConsentVerifierAvailability::Available)
+    }
+
+    /// Shows the Windows Hello prompt, over `window` so it isn't hidden
+    /// behind the app, and waits for the answer.
+    pub fn verify(window: Option<isize>) -> Result<bool, String> {
+        let message = HSTRING::from(REASON);
+        let operation: IAsyncOperation<UserConsentVerificationResult> = match window {
+            Some(hwnd) => factory::<UserConsentVerifier, IUserConsentVerifierInterop>().and_then(
+                |interop| unsafe {
+                    interop.RequestVerificationForWindowAsync(HWND(hwnd), &message)
+                },
+            ),
+            None => UserConsentVerifier::RequestVerificationAsync(&message),
+        }
+        .map_err(|e| format!("Windows Hello: {e}"))?;
+        let result = operation.get().map_err(|e| format!("Windows Hello: {e}"))?;
+        Ok(result == UserConsentVerificationResult::Verified)
+    }
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+mod biometrics {
+    pub const NAME: &str = "Biometric unlock";
+
+    pub fn available() -> bool {
+        false
+    }
+
+    pub fn verify(_window: Option<isize>) -> Result<bool, String> {
+        Err("No biometric prompt on this platform".into())
+    }
+}
+
+fn passcode_check(key: &[u8; 32]) -> String {
+    hex::encode(Sha256::digest(format!(
+        "pinup-app-lock:{}",
+        hex::encode(key)
+    )))
+}
+
+/// The saved salt and check, "<salt>:<check>" in the keyring.
+fn saved_passcode() -> Result<Option<(Vec<u8>, String)>, String> {
+    let Some(text) = keyring::get_while_locked(KEYRING_ACCOUNT)? else {
+        return Ok(None);
+    };
+    let (salt, check) = text.split_once(':').ok_or("Corrupt app lock passcode")?;
+    let salt = hex::decode(salt).map_err(|e| format!("Corrupt app lock passcode: {e}"))?;
+    Ok(Some((salt, check.to_string())))
+}
+
+fn save_passcode(salt: &[u8], check: &str) -> Result<(), String> {
+    keyring::set(KEYRING_ACCOUNT, &format!("{}:{check}", hex::encode(salt)))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Counts a wrong passcode and, past FREE_ATTEMPTS, holds off the next try.
+fn record_failure(app: &AppHandle) {
+    let result = config::update(app, |c| {
+        let lock = &mut c.app_lock;
+        lock.failed_unlocks = lock.failed_unlocks.saturating_add(1);
+        if let Some(extra) = lock.failed_unlocks.checked_sub(FREE_ATTEMPTS + 1) {
+            let delay = RETRY_DELAY
+                .checked_mul(1 << extra.min(16))
+                .map_or(MAX_RETRY_DELAY, |d| d.min(MAX_RETRY_DELAY));
+            lock.retry_after = Some(unix_now() + delay.as_secs());
+        }
+    });
+    if let Err(e) = result {
+        log::warn!("App lock: could not record a failed unlock: {}", e);
+    }
+}
+
+/// True while the app is locked.
+pub fn locked() -> bool {
+    LOCKED.load(Ordering::SeqCst)
+}
+
+/// Whether a passcode is set, so the app can be locked.
+pub fn lockable(app: &AppHandle) -> bool {
+    config::current(app).app_lock.has_passcode
+}
+
+/// Wraps the IPC handler to reject commands while locked.
+pub fn guard(
+    handler: impl Fn(Invoke) + Send + Sync + 'static,
+) -> impl Fn(Invoke) + Send + Sync + 'static {
+    move |invoke| {
+        let command = invoke.message.command();
+        if locked() && !ALLOWED.contains(&command) {
+            log::warn!("App lock: refused {}", command);
+            invoke.resolver.reject("Pin-Up AI is locked");
+            return;
+        }
+        handler(invoke)
+    }
+}
+
+/// Shows the passcode prompt.
+pub fn prompt(app: &AppHandle) {
+    if let Some(window) = app.get_window(WINDOW_LABEL) {
+        window.show().ok();
+        window.set_focus().ok();
+        return;
+    }
+    let result = WindowBuilder::new(app, WINDOW_LABEL, WindowUrl::App("lock.html".into()))
+        .title(i18n::t("window.locked"))
+        .inner_size(360.0, 210.0)
+        .resizable(false)
+        .always_on_top(true)
+        .center()
+        .build();
+    if let Err(e) = result {
+        log::error!("Could not open lock window: {}", e);
+    }
+}
+
+/// Shows `window`, or keeps it for unlock while locked.
+pub fn show(window: &Window) {
+    if locked() {
+        HIDDEN.lock().unwrap().push(window.label().to_string());
+    } else {
+        window.show().ok();
+    }
+}
+
+/// Hides every window behind the passcode prompt.
+pub fn lock(app: &AppHandle) {
+    if LOCKED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    log::info!("App locked");
+    let mut hidden = HIDDEN.lock().unwrap();
+    for (label, window) in app.windows() {
+        if label != WINDOW_LABEL && window.is_visible().unwrap_or(false) {
+            window.hide().ok();
+            hidden.push(label);
+        }
+    }
+    drop(hidden);
+    app.emit_all("app-locked", ()).ok();
+    prompt(app);
+}
+
+fn unlock(app: &AppHandle) {
+    if !LOCKED.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    log::info!("App unlocked");
+    let cfg = config::current(app).app_lock;
+    if cfg.failed_unlocks > 0 || cfg.retry_after.is_some() {
+        let reset = config::update(app, |c| {
+            c.app_lock.failed_unlocks = 0;
+            c.app_lock.retry_after = None;
+        });
+        if let Err(e) = reset {
+            log::warn!("App lock: could not reset failed unlocks: {}", e);
+        }
+    }
+    if let Some(window) = app.get_window(WINDOW_LABEL) {
+        window.close().ok();
+    }
+    for label in std::mem::take(&mut *HIDDEN.lock().unwrap()) {
+        if let Some(window) = app.get_window(&label) {
+            window.show().ok();
+            window.set_focus().ok();
+        }
+    }
+    app.emit_all("app-unlocked", ()).ok();
+}
+
+fn status(app: &AppHandle) -> AppLockStatus {
+    let cfg = config::current(app).app_lock;
+    AppLockStatus {
+        enabled: cfg.enabled,
+        idle_minutes: cfg.idle_minutes,
+        locked: locked(),
+        has_passcode: cfg.has_passcode,
+        idle_supported: idle().is_some(),
+        biometrics: cfg.biometrics,
+        biometrics_name: BIOMETRICS
+            .load(Ordering::SeqCst)
+            .then_some(biometrics::NAME),
+    }
+}
+
+fn register_shortcut(app: &AppHandle) {
+    let mut shortcuts = app.global_shortcut_manager();
+    if shortcuts.is_registered(LOCK_SHORTCUT).unwrap_or(false) {
+        return;
+    }
+    let handle = app.clone();
+    if let Err(e) = shortcuts.register(LOCK_SHORTCUT, move || lock(&handle)) {
+        log::warn!("Could not register {}: {}", LOCK_SHORTCUT, e);
+    }
+}
+
+/// Moves a passcode kept in the config by earlier versions to the keyring.
+fn migrate(app: &AppHandle) {
+    let cfg = config::current(app).app_lock;
+    let (Some(salt), Some(check)) = (cfg.salt, cfg.passcode_check) else {
+        return;
+    };
+    let result = hex::decode(&salt)
+        .map_err(|e| e.to_string())
+        .and_then(|salt| save_passcode(&salt, &check))
+        .and_then(|()| {
+            config::update(app, |c| {
+                c.app_lock.has_passcode = true;
+                c.app_lock.salt = None;
+                c.app_lock.passcode_check = None;
+            })
+        });
+    match result {
+        Ok(_) => tray::refresh(app),
+        Err(e) => log::warn!(
+            "App lock: could not move the passcode to the keyring: {}",
+            e
+        ),
+    }
+}
+
+/// Locks at launch and once the system has been idle for `idle_minutes`,
+/// and keeps the main window hidden while locked, whatever tries to show it.
+pub fn start(app: &AppHandle) {
+    let cfg = config::current(app).app_lock;
+    if cfg.enabled && cfg.has_passcode {
+        log::info!("App locked at launch");
+        LOCKED.store(true, Ordering::SeqCst);
+        prompt(app);
+    }
+    let handle = app.clone();
+    std::thread::spawn(move || {
+        migrate(&handle);
+        if lockable(&handle) {
+            register_shortcut(&handle);
+        }
+    });
+    std::thread::spawn(|| BIOMETRICS.store(biometrics::available(), Ordering::SeqCst));
+    if let Some(window) = app.get_window("main") {
+        let handle = app.clone();
+        let main = window.clone();
+        window.on_window_event(move |event| {
+            if let WindowEvent::Focused(true) = event {
+                if locked() {
+                    main.hide().ok();
+                    HIDDEN.lock().unwrap().push("main".into());
+                    prompt(&handle);
+                }
+            }
+        });
+    }
+    let handle = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let cfg = config::current(&handle).app_lock;
+        if !cfg.enabled || !cfg.has_passcode || locked() {
+            continue;
+        }
+        let limit = Duration::from_secs(cfg.idle_minutes.max(1) as u64 * 60);
+        if idle().is_some_and(|idle| idle >= limit) {
+            lock(&handle);
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn get_app_lock_status(app: AppHandle) -> AppLockStatus {
+    status(&app)
+}
+
+/// Turns idle locking on or off; `passcode` sets a new one and is required
+/// the first time.
+#[tauri::command]
+pub async fn set_app_lock(
+    app: AppHandle,
+    enabled: bool,
+    idle_minutes: u32,
+    passcode: Option<String>,
+) -> Result<AppLockStatus, String> {
+    if !(1..=240).contains(&idle_minutes) {
+        return Err("Lock after 1 to 240 minutes".into());
+    }
+    let passcode = passcode.filter(|p| !p.is_empty());
+    if passcode
+        .as_ref()
+        .is_some_and(|p| p.chars().count() < MIN_PASSCODE)
+    {
+        return Err(format!(
+            "Use a passcode of at least {MIN_PASSCODE} characters"
+        ));
+    }
+    if enabled && passcode.is_none() && !lockable(&app) {
+        return Err("Set a passcode to unlock with".into());
+    }
+    let secret = match passcode {
+        Some(passcode) => {
+            let mut salt = [0u8; 16];
+            getrandom::getrandom(&mut salt).map_err(|e| format!("No random source: {e}"))?;
+            tauri::async_runtime::spawn_blocking(move || {
+                let key = argon2_key(&passcode, &salt, ARGON2_MEMORY_KIB, ARGON2_PASSES)?;
+                save_passcode(&salt, &passcode_check(&key))
+            })
+            .await
+            .map_err(|e| e.to_string())??;
+            true
+        }
+        None => false,
+    };
+    config::update(&app, |c| {
+        c.app_lock.enabled = enabled;
+        c.app_lock.idle_minutes = idle_minutes;
+        c.app_lock.has_passcode |= secret;
+    })?;
+    if secret {
+        log::info!("App lock: passcode set");
+        register_shortcut(&app);
+        tray::refresh(&app);
+    }
+    Ok(status(&app))
+}
+
+/// Locks right away.
+#[tauri::command]
+pub fn lock_app(app: AppHandle) -> Result<(), String> {
+    if !lockable(&app) {
+        return Err("Set a passcode first".into());
+    }
+    lock(&app);
+    Ok(())
+}
+
+/// Checks the passcode and shows the windows again.
+#[tauri::command]
+pub async fn unlock_app(app: AppHandle, passcode: String) -> Result<(), String> {
+    let cfg = config::current(&app).app_lock;
+    if !cfg.has_passcode {
+        unlock(&app);
+        return Ok(());
+    }
+    if let Some(wait) = cfg.retry_after.and_then(|at| at.checked_sub(unix_now())) {
+        if wait > 0 {
+            return Err(format!(
+                "Too many wrong passcodes; try again in {wait} seconds"
+            ));
+        }
+    }
+    if CHECKING.swap(true, Ordering::SeqCst) {
+        return Err("Still checking the last passcode".into());
+    }
+    let matches = tauri::async_runtime::spawn_blocking(move || {
+        let (salt, check) =
+            saved_passcode()?.ok_or("The app lock passcode is missing from the keyring")?;
+        let key = argon2_key(&passcode, &salt, ARGON2_MEMORY_KIB, ARGON2_PASSES)?;
+        Ok::<_, String>(passcode_check(&key) == check)
+    })
+    .await
+    .map_err(|e| e.to_string());
+    CHECKING.store(false, Ordering::SeqCst);
+    if !matches?? {
+        log::warn!("App unlock failed: wrong passcode");
+        record_failure(&app);
+        return Err("Wrong passcode".into());
+    }
+    unlock(&app);
+    Ok(())
+}
+
+/// Unlocks with Touch ID or Windows Hello; errors mean "use the passcode".
+#[tauri::command]
+pub async fn unlock_with_biometrics(app: AppHandle) -> Result<(), String> {
+    if !locked() {
+        return Ok(());
+    }
+    if !config::current(&app).app_lock.biometrics {
+        return Err(format!("{} is turned off", biometrics::NAME));
+    }
+    #[cfg(windows)]
+    let window = app
+        .get_window(WINDOW_LABEL)
+        .and_then(|w| w.hwnd().ok())
+        .map(|hwnd| hwnd.0);
+    #[cfg(not(windows))]
+    let window = None;
+    let verified = tauri::async_runtime::spawn_blocking(move || {
+        let available = biometrics::available();
+        BIOMETRICS.store(available, Ordering::SeqCst);
+        if !available {
+            return Err(format!("{} isn't set up on this machine", biometrics::NAME));
+        }
+        biometrics::verify(window)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    if !verified {
+        log::warn!("App unlock failed: {} not verified", biometrics::NAME);
+        return Err(format!("{} didn't unlock", biometrics::NAME));
+    }
+    unlock(&app);
+    Ok(())
+}
+
+/// Turns Touch ID / Windows Hello unlocking on or off.
+#[tauri::command]
+pub fn set_app_lock_biometrics(app: AppHandle, enabled: bool) -> Result<AppLockStatus, String> {
+    config::update(&app, |c| c.app_lock.biometrics = enabled)?;
+    Ok(status(&app))
+}
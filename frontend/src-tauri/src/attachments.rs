@@ -0,0 +1,309 @@
+// Attachments — content-addressed file store under data_dir()/attachments/.
+//
+// Files are stored as `<hash[..2]>/<hash>.<ext>` (SHA-256 of the bytes) and
+// described in `manifest.json`. Editing an attachment never mutates it: each
+// saved edit becomes a new entry whose `parent` is the version it came from,
+// which gives every attachment a linear history.
+//
+// External edits: `edit_attachment_externally(hash)` copies the file to a
+// temp location, opens it in the default app, and watches for saves. The
+// original is locked (no second edit session) until `end_attachment_edit`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use notify::{RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+
+use crate::disk;
+
+/// Quiet period after the last write before a save is picked up.
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(750);
+/// Edit sessions end on their own after this long.
+const EDIT_TIMEOUT: Duration = Duration::from_secs(4 * 60 * 60);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Attachment {
+    pub hash: String,
+    pub ext: String,
+    pub size: u64,
+    pub created_at: u64,
+    /// Version this one was edited from, if any.
+    pub parent: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Manifest {
+    attachments: BTreeMap<String, Attachment>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct EditSession {
+    hash: String,
+    path: String,
+}
+
+/// Serializes manifest read-modify-write cycles.
+static MANIFEST_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+/// Active external edits, keyed by the hash being edited.
+static EDITS: Lazy<Mutex<HashMap<String, Arc<AtomicBool>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub fn store_dir() -> PathBuf {
+    crate::data_dir().join("attachments")
+}
+
+fn manifest_path() -> PathBuf {
+    store_dir().join("manifest.json")
+}
+
+fn load_manifest() -> Manifest {
+    std::fs::read_to_string(manifest_path())
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save_manifest(manifest: &Manifest) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(manifest).map_err(|e| e.to_string())?;
+    let tmp = manifest_path().with_extension("json.tmp");
+    std::fs::write(&tmp, text).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, manifest_path()).map_err(|e| e.to_string())
+}
+
+/// Copies the manifest to `dest`; false when there is none yet.
+pub fn copy_manifest(dest: &Path) -> Result<bool, String> {
+    let _guard = MANIFEST_LOCK.lock().unwrap();
+    if !manifest_path().is_file() {
+        return Ok(false);
+    }
+    std::fs::copy(manifest_path(), dest)
+        .map(|_| true)
+        .map_err(|e| format!("Could not copy attachments manifest: {e}"))
+}
+
+/// Replaces the manifest with the copy at `src` (a restored backup's).
+pub fn replace_manifest(src: &Path) -> Result<(), String> {
+    let _guard = MANIFEST_LOCK.lock().unwrap();
+    std::fs::create_dir_all(store_dir()).map_err(|e| e.to_string())?;
+    let tmp = manifest_path().with_extension("json.tmp");
+    std::fs::copy(src, &tmp).map_err(|e| format!("Could not restore attachments manifest: {e}"))?;
+    std::fs::rename(&tmp, manifest_path()).map_err(|e| e.to_string())
+}
+
+pub fn hash_bytes(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+fn file_path(hash: &str, ext: &str) -> PathBuf {
+    let name = if ext.is_empty() {
+        hash.to_string()
+    } else {
+        format!("{}.{}", hash, ext)
+    };
+    store_dir().join(&hash[..2]).join(name)
+}
+
+pub fn get(hash: &str) -> Option<Attachment> {
+    load_manifest().attachments.get(hash).cloned()
+}
+
+pub fn path_of(attachment: &Attachment) -> PathBuf {
+    file_path(&attachment.hash, &attachment.ext)
+}
+
+/// Stores `bytes` (deduplicated by content) and returns its manifest entry.
+pub fn store_bytes(bytes: &[u8], ext: &str, parent: Option<&str>) -> Result<Attachment, String> {
+    let hash = hash_bytes(bytes);
+    let _guard = MANIFEST_LOCK.lock().unwrap();
+    let mut manifest = load_manifest();
+    if let Some(existing) = manifest.attachments.get(&hash) {
+        return Ok(existing.clone());
+    }
+
+    let ext = ext.trim_start_matches('.').to_lowercase();
+    let path = file_path(&hash, &ext);
+    std::fs::create_dir_all(path.parent().unwrap()).map_err(|e| e.to_string())?;
+    disk::ensure_space(&path, bytes.len() as u64)?;
+    std::fs::write(&path, bytes).map_err(|e| format!("Could not store attachment: {e}"))?;
+
+    let attachment = Attachment {
+        hash: hash.clone(),
+        ext,
+        size: bytes.len() as u64,
+        created_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        parent: parent.map(String::from),
+    };
+    manifest.attachments.insert(hash, attachment.clone());
+    save_manifest(&manifest)?;
+    Ok(attachment)
+}
+
+fn root_of<'a>(manifest: &'a Manifest, mut hash: &'a str) -> &'a str {
+    while let Some(parent) = manifest
+        .attachments
+        .get(hash)
+        .and_then(|a| a.parent.as_deref())
+    {
+        hash = parent;
+    }
+    hash
+}
+
+/// Every version in `hash`'s lineage, oldest first.
+fn history(hash: &str) -> Vec<Attachment> {
+    let manifest = load_manifest();
+    let root = root_of(&manifest, hash).to_string();
+    let mut versions: Vec<Attachment> = manifest
+        .attachments
+        .values()
+        .filter(|a| root_of(&manifest, &a.hash) == root)
+        .cloned()
+        .collect();
+    versions.sort_by_key(|a| a.created_at);
+    versions
+}
+
+// ── External edit ──────────────────────────────────────────────────────────
+fn edit_dir(hash: &str) -> PathBuf {
+    std::env::temp_dir().join("pin-up-ai-edits").join(hash)
+}
+
+/// Watches `path` until `stop` is set, storing each settled save as a new
+/// version chained onto the previous one.
+fn watch_edits(app: AppHandle, original: String, path: PathBuf, stop: Arc<AtomicBool>) {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = match notify::recommended_watcher(tx) {
+        Ok(w) => w,
+        Err(e) => {
+            log::error!("Cannot watch attachment edits: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = watcher.watch(path.parent().unwrap(), RecursiveMode::NonRecursive) {
+        log::error!("Cannot watch {:?}: {}", path, e);
+        return;
+    }
+
+    let started = Instant::now();
+    let mut current = original.clone();
+    let mut pending: Option<Instant> = None;
+    while !stop.load(Ordering::SeqCst) && started.elapsed() < EDIT_TIMEOUT {
+        match rx.recv_timeout(Duration::from_millis(250)) {
+            Ok(Ok(event)) if event.paths.iter().any(|p| p == &path) => {
+                pending = Some(Instant::now());
+            }
+            Ok(_) | Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+        let settled = pending.is_some_and(|at| at.elapsed() >= SAVE_DEBOUNCE);
+        if !settled {
+            continue;
+        }
+        pending = None;
+
+        let Ok(bytes) = std::fs::read(&path) else {
+            continue;
+        };
+        if bytes.is_empty() || hash_bytes(&bytes) == current {
+            continue;
+        }
+        let ext = path
+            .extension()
+            .map(|e| e.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        match store_bytes(&bytes, &ext, Some(&current)) {
+            Ok(version) => {
+                log::info!("Attachment {} saved as {}", original, version.hash);
+                current = version.hash.clone();
+                app.emit_all(
+                    "attachment-version-created",
+                    serde_json::json!({ "original": original, "version": version }),
+                )
+                .ok();
+            }
+            Err(e) => log::error!("Could not store edited attachment: {}", e),
+        }
+    }
+
+    EDITS.lock().unwrap().remove(&original);
+    std::fs::remove_dir_all(path.parent().unwrap()).ok();
+    app.emit_all("attachment-edit-ended", &original).ok();
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn edit_attachment_externally(app: AppHandle, hash: String) -> Result<EditSession, String> {
+    let attachment = get(&hash).ok_or("Unknown attachment")?;
+    let stop = Arc::new(AtomicBool::new(false));
+    {
+        let mut edits = EDITS.lock().unwrap();
+        if edits.contains_key(&hash) {
+            return Err("This attachment is already being edited".into());
+        }
+        edits.insert(hash.clone(), stop.clone());
+    }
+
+    let start = || -> Result<PathBuf, String> {
+        let dir = edit_dir(&hash);
+        std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        let name = if attachment.ext.is_empty() {
+            "attachment".to_string()
+        } else {
+            format!("attachment.{}", attachment.ext)
+        };
+        let copy = dir.join(name);
+        std::fs::copy(path_of(&attachment), &copy)
+            .map_err(|e| format!("Could not prepare edit copy: {e}"))?;
+        open::that(&copy).map_err(|e| format!("Could not open editor: {e}"))?;
+        Ok(copy)
+    };
+    let copy = match start() {
+        Ok(copy) => copy,
+        Err(e) => {
+            EDITS.lock().unwrap().remove(&hash);
+            return Err(e);
+        }
+    };
+
+    let session = EditSession {
+        hash: hash.clone(),
+        path: copy.to_string_lossy().to_string(),
+    };
+    std::thread::spawn(move || watch_edits(app, hash, copy, stop));
+    Ok(session)
+}
+
+#[tauri::command]
+pub fn end_attachment_edit(hash: String) -> bool {
+    match EDITS.lock().unwrap().get(&hash) {
+        Some(stop) => {
+            stop.store(true, Ordering::SeqCst);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Hashes of attachments currently locked by an external edit.
+#[tauri::command]
+pub fn list_attachment_edits() -> Vec<String> {
+    EDITS.lock().unwrap().keys().cloned().collect()
+}
+
+#[tauri::command]
+pub fn get_attachment_history(hash: String) -> Result<Vec<Attachment>, String> {
+    if get(&hash).is_none() {
+        return Err("Unknown attachment".into());
+    }
+    Ok(history(&hash))
+}
@@ -0,0 +1,225 @@
+// Autostart — launch at login, optionally straight to the tray.
+//
+// The app is registered the way each platform expects:
+//   macOS    ~/Library/LaunchAgents/com.pinupai.app.plist (RunAtLoad)
+//   Windows  HKCU\Software\Microsoft\Windows\CurrentVersion\Run
+//   Linux    ~/.config/autostart/pin-up-ai.desktop (XDG autostart)
+// The registration itself is the setting: `get_autostart` reads it back, so
+// turning it off in Task Manager (StartupApproved) or the desktop's startup
+// settings (Hidden=true) shows up here, and turning it on here undoes that.
+// With `start_hidden` it starts the app with HIDDEN_ARG, which keeps the
+// main window hidden (see `launched_hidden`); the tray opens it. At startup
+// an existing registration is rewritten if the app has moved (an update, a
+// new AppImage).
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::instance;
+
+const HIDDEN_ARG: &str = "--hidden";
+
+#[derive(Serialize, Clone, Debug)]
+pub struct AutostartStatus {
+    pub enabled: bool,
+    pub start_hidden: bool,
+}
+
+/// Whether this process was started at login to sit in the tray.
+pub fn launched_hidden() -> bool {
+    std::env::args_os().skip(1).any(|arg| arg == HIDDEN_ARG)
+}
+
+#[cfg(target_os = "macos")]
+const LABEL: &str = "com.pinupai.app";
+
+#[cfg(target_os = "macos")]
+fn plist_path() -> Option<std::path::PathBuf> {
+    Some(
+        dirs::home_dir()?
+            .join("Library/LaunchAgents")
+            .join(format!("{LABEL}.plist")),
+    )
+}
+
+#[cfg(target_os = "macos")]
+fn register(hidden: bool) -> Result<(), String> {
+    let escape = |text: &str| {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+    let mut args = format!(
+        "<string>{}</string>",
+        escape(&instance::exe_path()?.to_string_lossy())
+    );
+    if hidden {
+        args.push_str(&format!("<string>{HIDDEN_ARG}</string>"));
+    }
+    let plist = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \
+         \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+         <plist version=\"1.0\"><dict>\
+         <key>Label</key><string>{LABEL}</string>\
+         <key>ProgramArguments</key><array>{args}</array>\
+         <key>RunAtLoad</key><true/>\
+         <key>ProcessType</key><string>Interactive</string>\
+         </dict></plist>\n"
+    );
+    let path = plist_path().ok_or("No home directory")?;
+    instance::write_if_changed(&path, &plist).map(drop)
+}
+
+#[cfg(target_os = "macos")]
+fn unregister() -> Result<(), String> {
+    match plist_path() {
+        Some(path) if path.is_file() => std::fs::remove_file(&path)
+            .map_err(|e| format!("Could not remove {}: {e}", path.display())),
+        _ => Ok(()),
+    }
+}
+
+/// Some(start hidden) when registered.
+#[cfg(target_os = "macos")]
+fn registered() -> Option<bool> {
+    let plist = std::fs::read_to_string(plist_path()?).ok()?;
+    Some(plist.contains(&format!("<string>{HIDDEN_ARG}</string>")))
+}
+
+#[cfg(windows)]
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+/// Task Manager's on/off switch for Run entries.
+#[cfg(windows)]
+const APPROVED_KEY: &str =
+    r"HKCU\Software\Microsoft\Windows\CurrentVersion\Explorer\StartupApproved\Run";
+#[cfg(windows)]
+const VALUE: &str = "Pin-Up AI";
+
+#[cfg(windows)]
+fn register(hidden: bool) -> Result<(), String> {
+    let mut command = format!("\"{}\"", instance::exe_path()?.display());
+    if hidden {
+        command.push_str(&format!(" {HIDDEN_ARG}"));
+    }
+    // Drops a "disabled" left by Task Manager.
+    instance::reg(&["delete", APPROVED_KEY, "/v", VALUE, "/f"]).ok();
+    let current = instance::reg(&["query", RUN_KEY, "/v", VALUE]).unwrap_or_default();
+    if current
+        .lines()
+        .any(|line| line.trim_end().ends_with(&command))
+    {
+        return Ok(());
+    }
+    instance::reg(&["add", RUN_KEY, "/v", VALUE, "/d", &command, "/f"]).map(drop)
+}
+
+#[cfg(windows)]
+fn unregister() -> Result<(), String> {
+    if instance::reg(&["query", RUN_KEY, "/v", VALUE]).is_ok() {
+        instance::reg(&["delete", RUN_KEY, "/v", VALUE, "/f"])?;
+    }
+    instance::reg(&["delete", APPROVED_KEY, "/v", VALUE, "/f"]).ok();
+    Ok(())
+}
+
+#[cfg(windows)]
+fn registered() -> Option<bool> {
+    let out = instance::reg(&["query", RUN_KEY, "/v", VALUE]).ok()?;
+    // REG_BINARY starting 03 (or 01) means switched off.
+    let approved = instance::reg(&["query", APPROVED_KEY, "/v", VALUE]).unwrap_or_default();
+    let disabled = approved
+        .split_whitespace()
+        .any(|word| word.len() == 24 && (word.starts_with("03") || word.starts_with("01")));
+    if disabled {
+        return None;
+    }
+    Some(out.contains(HIDDEN_ARG))
+}
+
+#[cfg(target_os = "linux")]
+fn desktop_path() -> Option<std::path::PathBuf> {
+    Some(
+        dirs::config_dir()?
+            .join("autostart")
+            .join("pin-up-ai.desktop"),
+    )
+}
+
+#[cfg(target_os = "linux")]
+fn register(hidden: bool) -> Result<(), String> {
+    let path = desktop_path().ok_or("No config directory")?;
+    let exe = instance::exe_path()?.to_string_lossy().replace('"', "\\\"");
+    let args = if hidden {
+        format!(" {HIDDEN_ARG}")
+    } else {
+        String::new()
+    };
+    let entry = format!(
+        "[Desktop Entry]\nType=Application\nName=Pin-Up AI\nExec=\"{exe}\"{args}\n\
+         Terminal=false\nX-GNOME-Autostart-enabled=true\n"
+    );
+    instance::write_if_changed(&path, &entry).map(drop)
+}
+
+#[cfg(target_os = "linux")]
+fn unregister() -> Result<(), String> {
+    match desktop_path() {
+        Some(path) if path.is_file() => std::fs::remove_file(&path)
+            .map_err(|e| format!("Could not remove {}: {e}", path.display())),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn registered() -> Option<bool> {
+    let entry = std::fs::read_to_string(desktop_path()?).ok()?;
+    // Desktops turn an entry off with Hidden=true rather than deleting it.
+    if entry.lines().any(|line| line.trim() == "Hidden=true") {
+        return None;
+    }
+    Some(entry.contains(HIDDEN_ARG))
+}
+
+fn status() -> AutostartStatus {
+    let registered = registered();
+    AutostartStatus {
+        enabled: registered.is_some(),
+        start_hidden: registered.unwrap_or(false),
+    }
+}
+
+/// Points an existing registration at where the app is now.
+pub fn start(_app: &AppHandle) {
+    tauri::async_runtime::spawn_blocking(|| {
+        if let Some(hidden) = registered() {
+            if let Err(e) = register(hidden) {
+                log::warn!("Autostart: {}", e);
+            }
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub async fn get_autostart() -> Result<AutostartStatus, String> {
+    tauri::async_runtime::spawn_blocking(status)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Launches the app at login, in the tray with `start_hidden`, or stops it.
+#[tauri::command]
+pub async fn set_autostart(enabled: bool, start_hidden: bool) -> Result<AutostartStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        if enabled {
+            register(start_hidden)?;
+        } else {
+            unregister()?;
+        }
+        log::info!("Autostart: {enabled} (hidden: {start_hidden})");
+        Ok(status())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,128 @@
+// Backend client — authenticated HTTP calls from the shell to the sidecar.
+//
+// Shell subsystems that need to read or write data go through these helpers
+// so the base URL, bearer token, and error formatting stay in one place.
+
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use reqwest::multipart::{Form, Part};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio_util::codec::{BytesCodec, FramedRead};
+
+use crate::priority::BackendPriority;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct BackendConfig {
+    /// Port of a backend started outside the app; the sidecar is not spawned.
+    pub external_port: Option<u16>,
+    /// Scheduling priority of the sidecar (see priority.rs).
+    pub priority: BackendPriority,
+}
+
+static CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .unwrap()
+});
+
+pub fn client() -> &'static reqwest::Client {
+    &CLIENT
+}
+
+/// True for errors where the backend never saw the request.
+pub fn is_unreachable(error: &str) -> bool {
+    error.starts_with("Backend not started") || error.starts_with("Backend request failed")
+}
+
+pub fn base_url() -> Result<String, String> {
+    let port = crate::BACKEND_PORT.load(Ordering::SeqCst);
+    if port == 0 {
+        return Err("Backend not started".into());
+    }
+    Ok(format!("http://127.0.0.1:{}/api", port))
+}
+
+/// `/snippets/{id}` for an id the frontend passed in. Snippet ids are UUIDs;
+/// anything else (`../stats`, `x?archived=1`) is refused before it becomes
+/// part of a URL, as `deep_link` does for ids in links.
+pub fn snippet_path(id: &str) -> Result<String, String> {
+    if id.is_empty()
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("Invalid snippet id: {}", id));
+    }
+    Ok(format!("/snippets/{}", id))
+}
+
+async fn send(req: reqwest::RequestBuilder) -> Result<Value, String> {
+    let port = crate::BACKEND_PORT.load(Ordering::SeqCst);
+    let token = crate::fetch_install_token(port).await;
+    let resp = req
+        .bearer_auth(token)
+        .send()
+        .await
+        .map_err(|e| format!("Backend request failed: {e}"))?;
+    let status = resp.status();
+    let body = resp.text().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(format!("Backend returned {}: {}", status, body));
+    }
+    if body.is_empty() {
+        return Ok(Value::Null);
+    }
+    serde_json::from_str(&body).map_err(|e| format!("Invalid backend response: {e}"))
+}
+
+pub async fn get(path: &str) -> Result<Value, String> {
+    let url = format!("{}{}", base_url()?, path);
+    send(client().get(url)).await
+}
+
+pub async fn post(path: &str, body: &Value) -> Result<Value, String> {
+    let url = format!("{}{}", base_url()?, path);
+    send(client().post(url).json(body)).await
+}
+
+/// `post` for endpoints that can outlast the client timeout (maintenance).
+pub async fn post_slow(path: &str, body: &Value, timeout: Duration) -> Result<Value, String> {
+    let url = format!("{}{}", base_url()?, path);
+    send(client().post(url).json(body).timeout(timeout)).await
+}
+
+pub async fn patch(path: &str, body: &Value) -> Result<Value, String> {
+    let url = format!("{}{}", base_url()?, path);
+    send(client().patch(url).json(body)).await
+}
+
+pub async fn delete(path: &str) -> Result<Value, String> {
+    let url = format!("{}{}", base_url()?, path);
+    send(client().delete(url)).await
+}
+
+/// Streams `file` to a multipart endpoint as the `file` field.
+pub async fn upload(path: &str, file: &Path) -> Result<Value, String> {
+    let url = format!("{}{}", base_url()?, path);
+    let handle = tokio::fs::File::open(file)
+        .await
+        .map_err(|e| format!("Could not open {}: {e}", file.display()))?;
+    let len = handle.metadata().await.map(|m| m.len()).ok();
+    let body = reqwest::Body::wrap_stream(FramedRead::new(handle, BytesCodec::new()));
+    let part = match len {
+        Some(len) => Part::stream_with_length(body, len),
+        None => Part::stream(body),
+    }
+    .file_name(
+        file.file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "upload".into()),
+    );
+    send(client().post(url).multipart(Form::new().part("file", part))).await
+}
@@ -0,0 +1,635 @@
+// Backups — scheduled database snapshots, restore and maintenance.
+//
+// Snapshots are taken by the backend (`POST /backup/run`) with SQLite's
+// online backup API, so they are consistent while the app keeps writing —
+// copying pinup.db from here could catch it mid-transaction. They land in
+// data_dir()/backups/<timestamp>/, the same folders manual backups from the
+// settings page use, and the shell adds a copy of the attachments manifest
+// next to the database. The scheduler checks every CHECK_INTERVAL and takes
+// a snapshot when the newest one is older than `backups.interval_hours`;
+// after each snapshot, retention keeps the newest backup of each of the last
+// `keep_daily` days and `keep_weekly` ISO weeks and deletes the rest.
+//
+// Before an app or backend update the shell takes a tagged backup
+// ("pre-update-<version>", "pre-backend-update-<version>"); the backend tags
+// its own "pre-migration" backup when a new version first opens the
+// database. Tagged backups are outside the daily/weekly policy — the newest
+// KEEP_TAGGED are kept.
+//
+// `restore_backup` verifies the backup, stops the sidecar, moves the live
+// database aside as a "pre-restore" backup (so a restore can be undone the
+// same way), renames the backup's files into place and restarts the
+// backend. If the restored database fails its health check the swap is
+// reversed. Each step emits "backup-restore-progress".
+//
+// `check_db_integrity` has the backend run quick_check (or integrity_check)
+// on the live database with writes held off; when it finds corruption the
+// report names the newest backup that passes its own check.
+//
+// `optimize_database` has the backend quiesce its API and run VACUUM, FTS
+// and planner optimize and a truncating WAL checkpoint. The shell checks for
+// the free space VACUUM needs and keeps its own backups out of the way
+// meanwhile.
+//
+// None of the SQL runs here. With encryption on (see encryption.rs) the
+// database is SQLCipher, and snapshots, checks and VACUUM all need a keyed
+// connection from the same SQLCipher build the backend uses; linking a
+// second one into the shell would mean holding the key in two processes.
+// The backend's snapshot is SQLite's online backup API, the same one
+// rusqlite wraps.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use chrono::{Datelike, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::{attachments, backend, config, disk, encryption, power, sidecar, suspend};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+const METADATA_FILE: &str = "backup.json";
+const MANIFEST_FILE: &str = "attachments-manifest.json";
+const DB_FILE: &str = "pinup.db";
+const KEEP_TAGGED: usize = 5;
+/// Backups tried, newest first, when looking for one to suggest.
+const SUGGEST_CANDIDATES: usize = 5;
+const PROGRESS_EVENT: &str = "backup-restore-progress";
+/// VACUUM rewrites the whole file; multi-GB databases take minutes.
+const OPTIMIZE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct BackupConfig {
+    pub enabled: bool,
+    pub interval_hours: u64,
+    pub keep_daily: usize,
+    pub keep_weekly: usize,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_hours: 24,
+            keep_daily: 7,
+            keep_weekly: 4,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Backup {
+    name: String,
+    /// Unix milliseconds.
+    created_at: i64,
+    db_size_bytes: u64,
+    app_version: String,
+    /// Why it was taken, e.g. "pre-update-0.3.0"; None for scheduled and
+    /// manual backups.
+    tag: Option<String>,
+    /// Everything in the backup folder.
+    size_bytes: u64,
+    has_attachments_manifest: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RestoreProgress {
+    /// "verifying", "stopping", "swapping", "starting", "rolling-back",
+    /// "done" or "failed".
+    stage: &'static str,
+    message: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RestoreResult {
+    restored: String,
+    /// The replaced database, kept as a backup tagged "pre-restore".
+    safety_copy: String,
+    port: u16,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct IntegrityReport {
+    ok: bool,
+    /// "quick" or "full".
+    mode: String,
+    /// Problems reported by SQLite; empty when ok.
+    errors: Vec<String>,
+    duration_ms: u64,
+    db_size_bytes: u64,
+    /// Newest backup that passes its own check, when the database is corrupt.
+    #[serde(default)]
+    suggested_backup: Option<String>,
+    #[serde(default)]
+    suggestion: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OptimizeReport {
+    size_before_bytes: u64,
+    size_after_bytes: u64,
+    reclaimed_bytes: u64,
+    duration_ms: u64,
+    /// False when a reader kept the WAL from being truncated.
+    wal_truncated: bool,
+}
+
+/// Set while a restore or optimize has the database.
+static MAINTENANCE: AtomicBool = AtomicBool::new(false);
+
+pub fn backups_dir() -> PathBuf {
+    crate::data_dir().join("backups")
+}
+
+fn read_backup(dir: &Path) -> Option<Backup> {
+    let text = std::fs::read_to_string(dir.join(METADATA_FILE)).ok()?;
+    let meta: Value = serde_json::from_str(&text).ok()?;
+    let size_bytes = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| e.metadata().ok())
+                .map(|m| m.len())
+                .sum()
+        })
+        .unwrap_or(0);
+    Some(Backup {
+        name: dir.file_name()?.to_string_lossy().to_string(),
+        created_at: meta["created_at"].as_i64().unwrap_or(0),
+        db_size_bytes: meta["db_size_bytes"].as_u64().unwrap_or(0),
+        app_version: meta["app_version"]
+            .as_str()
+            .unwrap_or("unknown")
+            .to_string(),
+        tag: meta["tag"].as_str().map(str::to_string),
+        size_bytes,
+        has_attachments_manifest: dir.join(MANIFEST_FILE).is_file(),
+    })
+}
+
+/// All backups, newest first.
+fn list() -> Vec<Backup> {
+    let Ok(entries) = std::fs::read_dir(backups_dir()) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<Backup> = entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| read_backup(&e.path()))
+        .collect();
+    backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+    backups
+}
+
+/// Names of backups outside the retention policy; `backups` newest first.
+fn expired(backups: &[Backup], cfg: &BackupConfig) -> Vec<String> {
+    let mut days: Vec<NaiveDate> = Vec::new();
+    let mut weeks: Vec<(i32, u32)> = Vec::new();
+    let mut tagged = 0;
+    let mut expired = Vec::new();
+    for backup in backups {
+        if backup.tag.is_some() {
+            tagged += 1;
+            if tagged > KEEP_TAGGED {
+                expired.push(backup.name.clone());
+            }
+            continue;
+        }
+        let Some(at) = chrono::Local
+            .timestamp_millis_opt(backup.created_at)
+            .single()
+        else {
+            continue;
+        };
+        let day = at.date_naive();
+        let week = (at.iso_week().year(), at.iso_week().week());
+        let mut keep = false;
+        // At least one daily slot, so the newest backup always survives.
+        if !days.contains(&day) && days.len() < cfg.keep_daily.max(1) {
+            days.push(day);
+            keep = true;
+        }
+        if !weeks.contains(&week) && weeks.len() < cfg.keep_weekly {
+            weeks.push(week);
+            keep = true;
+        }
+        if !keep {
+            expired.push(backup.name.clone());
+        }
+    }
+    expired
+}
+
+/// Only names of existing backup folders, never arbitrary paths.
+fn backup_dir(name: &str) -> Result<PathBuf, String> {
+    let valid = !name.is_empty() && name != ".." && !name.contains(['/', '\\']);
+    let dir = backups_dir().join(name);
+    if !valid || !dir.join(METADATA_FILE).is_file() {
+        return Err(format!("No backup named '{}'", name));
+    }
+    Ok(dir)
+}
+
+fn prune(cfg: &BackupConfig) {
+    for name in expired(&list(), cfg) {
+        match backup_dir(&name).and_then(|dir| {
+            std::fs::remove_dir_all(dir).map_err(|e| format!("Could not delete backup: {e}"))
+        }) {
+            Ok(()) => log::info!("Deleted expired backup {}", name),
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+}
+
+/// `scheduled` lets the backend turn the run down in power saver mode.
+async fn create(app: &AppHandle, tag: Option<&str>, scheduled: bool) -> Result<Backup, String> {
+    if MAINTENANCE.load(Ordering::SeqCst) {
+        return Err("Database maintenance is in progress".into());
+    }
+    let db_size = sidecar::db_files(&crate::db_path())
+        .iter()
+        .filter_map(|path| std::fs::metadata(path).ok())
+        .map(|meta| meta.len())
+        .sum();
+    disk::ensure_space(&backups_dir(), db_size)?;
+
+    let info = backend::post(
+        "/backup/run",
+        &json!({ "tag": tag, "scheduled": scheduled }),
+    )
+    .await?;
+    let name = info["name"]
+        .as_str()
+        .ok_or("Invalid backup response from backend")?;
+    let dir = backup_dir(name)?;
+    attachments::copy_manifest(&dir.join(MANIFEST_FILE))?;
+    prune(&config::current(app).backups);
+    let backup = read_backup(&dir).ok_or("Backup metadata missing")?;
+    log::info!("Backup {} created", backup.name);
+    app.emit_all("backup-created", &backup).ok();
+    Ok(backup)
+}
+
+/// Tagged backup before an update is applied. Failures are logged rather
+/// than returned: the update may be what fixes a broken backend.
+pub async fn before_update(app: &AppHandle, tag: &str) {
+    let tag: String = tag
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || "._-".contains(c) {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    if let Err(e) = create(app, Some(&tag), false).await {
+        log::warn!("Backup before update failed: {}", e);
+    }
+}
+
+// ── Restore ────────────────────────────────────────────────────────────────
+fn progress(app: &AppHandle, stage: &'static str, message: String) {
+    log::info!("Restore: {}", message);
+    app.emit_all(PROGRESS_EVENT, RestoreProgress { stage, message })
+        .ok();
+}
+
+/// SQLite header and page-size sanity check; catches truncated or foreign
+/// files without opening the database.
+fn check_header(path: &Path) -> Result<(), String> {
+    let mut file = std::fs::File::open(path).map_err(|e| format!("Could not open backup: {e}"))?;
+    let mut header = [0u8; 100];
+    file.read_exact(&mut header)
+        .map_err(|_| "Backup database is truncated".to_string())?;
+    if &header[..16] != b"SQLite format 3\0" {
+        if !encryption::active() {
+            return Err("Backup is not a SQLite database".into());
+        }
+        let len = file.metadata().map_err(|e| e.to_string())?.len();
+        if len % encryption::CIPHER_PAGE_SIZE != 0 {
+            return Err("Backup database is truncated".into());
+        }
+        return Ok(());
+    }
+    let page_size = match u16::from_be_bytes([header[16], header[17]]) {
+        1 => 65536,
+        n => n as u64,
+    };
+    let len = file.metadata().map_err(|e| e.to_string())?.len();
+    if page_size < 512 || len % page_size != 0 {
+        return Err("Backup database is truncated".into());
+    }
+    Ok(())
+}
+
+/// Header check here, then `PRAGMA integrity_check` in the backend when it
+/// is running (a broken backend may be why the user is restoring).
+async fn verify(name: &str, dir: &Path) -> Result<(), String> {
+    check_header(&dir.join(DB_FILE))?;
+    match backend::get(&format!("/backup/verify/{}", name)).await {
+        Ok(result) if result["ok"].as_bool() == Some(true) => Ok(()),
+        Ok(result) => Err(format!(
+            "Backup failed its integrity check: {}",
+            result["result"].as_str().unwrap_or("unknown error")
+        )),
+        Err(e) if backend::is_unreachable(&e) => {
+            log::warn!("Backend unavailable; only the backup header was checked");
+            Ok(())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Newest backup that passes the backend's integrity check.
+async fn newest_sound_backup() -> Option<String> {
+    for backup in list().into_iter().take(SUGGEST_CANDIDATES) {
+        let dir = backups_dir().join(&backup.name);
+        if check_header(&dir.join(DB_FILE)).is_err() {
+            continue;
+        }
+        match backend::get(&format!("/backup/verify/{}", backup.name)).await {
+            Ok(result) if result["ok"].as_bool() == Some(true) => return Some(backup.name),
+            Ok(_) => log::warn!("Backup {} is damaged too", backup.name),
+            Err(e) => log::debug!("Could not verify backup {}: {}", backup.name, e),
+        }
+    }
+    None
+}
+
+/// Moves the live database into a new "pre-restore" backup and puts the
+/// files from `source` in its place, by rename. Returns the new backup's
+/// name. The sidecar must be stopped.
+fn swap(app: &AppHandle, source: &Path) -> Result<String, String> {
+    let db = crate::db_path();
+    let staging = crate::data_dir().join("restore-staging");
+    let name = format!(
+        "{}_pre-restore",
+        chrono::Local::now().format("%Y-%m-%d_%H%M%S")
+    );
+    let safety = backups_dir().join(&name);
+
+    // Copy into the data dir first so the swap itself is only renames. The
+    // -shm index is rebuilt by SQLite and never carried over.
+    let incoming = sidecar::db_files(&source.join(DB_FILE));
+    let staged = sidecar::db_files(&staging.join(DB_FILE));
+    let live = sidecar::db_files(&db);
+    let kept = sidecar::db_files(&safety.join(DB_FILE));
+    let size = incoming
+        .iter()
+        .filter_map(|p| std::fs::metadata(p).ok())
+        .map(|m| m.len())
+        .sum();
+    disk::ensure_space(&db, size)?;
+    std::fs::remove_dir_all(&staging).ok();
+    std::fs::create_dir_all(&staging).map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&safety).map_err(|e| e.to_string())?;
+    for (from, to) in incoming.iter().zip(&staged).take(2) {
+        if from.is_file() {
+            std::fs::copy(from, to).map_err(|e| format!("Could not stage backup: {e}"))?;
+        }
+    }
+    attachments::copy_manifest(&safety.join(MANIFEST_FILE))?;
+    // Written before anything moves: without it the folder holding the old
+    // database isn't listed as a backup and can't be restored from.
+    let meta = json!({
+        "created_at": chrono::Utc::now().timestamp_millis(),
+        "db_size_bytes": std::fs::metadata(&live[0]).map(|m| m.len()).unwrap_or(0),
+        "app_version": app.package_info().version.to_string(),
+        "tag": "pre-restore",
+    });
+    if let Err(e) = std::fs::write(
+        safety.join(METADATA_FILE),
+        serde_json::to_string_pretty(&meta).unwrap_or_default(),
+    ) {
+        std::fs::remove_dir_all(&safety).ok();
+        std::fs::remove_dir_all(&staging).ok();
+        return Err(format!("Could not write backup metadata: {e}"));
+    }
+
+    let mut moved = Vec::new();
+    let mut placed = Vec::new();
+    let renamed = (|| {
+        for (from, to) in live.iter().zip(&kept).take(2) {
+            if from.is_file() {
+                std::fs::rename(from, to)?;
+                moved.push((from, to));
+            }
+        }
+        std::fs::remove_file(&live[2]).ok();
+        for (from, to) in staged.iter().zip(&live).take(2) {
+            if from.is_file() {
+                std::fs::rename(from, to)?;
+                placed.push(to);
+            }
+        }
+        Ok::<_, std::io::Error>(())
+    })();
+    if let Err(e) = renamed {
+        for path in placed {
+            std::fs::remove_file(path).ok();
+        }
+        for (from, to) in moved {
+            std::fs::rename(to, from).ok();
+        }
+        // Metadata first: a folder that won't go away isn't then listed as
+        // an empty backup.
+        std::fs::remove_file(safety.join(METADATA_FILE)).ok();
+        std::fs::remove_dir_all(&safety).ok();
+        std::fs::remove_dir_all(&staging).ok();
+        return Err(format!("Could not swap database: {e}"));
+    }
+    std::fs::remove_dir_all(&staging).ok();
+
+    let manifest = source.join(MANIFEST_FILE);
+    if manifest.is_file() {
+        attachments::replace_manifest(&manifest)?;
+    }
+    Ok(name)
+}
+
+async fn restore(app: &AppHandle, name: &str) -> Result<RestoreResult, String> {
+    if config::current(app).backend.external_port.is_some() {
+        return Err("Backups can only be restored into the bundled backend".into());
+    }
+    let source = backup_dir(name)?;
+    progress(app, "verifying", format!("Verifying backup {name}"));
+    verify(name, &source).await?;
+
+    progress(app, "stopping", "Stopping the backend".into());
+    crate::stop_backend(app);
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    progress(
+        app,
+        "swapping",
+        format!("Replacing the database with {name}"),
+    );
+    let safety_copy = match swap(app, &source) {
+        Ok(safety_copy) => safety_copy,
+        Err(e) => {
+            crate::respawn_backend(app).await.ok();
+            return Err(e);
+        }
+    };
+
+    progress(app, "starting", "Starting the backend".into());
+    match crate::respawn_backend(app).await {
+        Ok(port) => {
+            app.emit_all("backend-ready", port).ok();
+            progress(app, "done", format!("Restored {name}"));
+            Ok(RestoreResult {
+                restored: name.to_string(),
+                safety_copy,
+                port,
+            })
+        }
+        Err(e) => {
+            progress(
+                app,
+                "rolling-back",
+                "Restoring the previous database".into(),
+            );
+            crate::stop_backend(app);
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            // The swap back parks the failed database in another backup;
+            // it's a copy of `name`, so drop it.
+            match swap(app, &backups_dir().join(&safety_copy)) {
+                Ok(failed) => {
+                    std::fs::remove_dir_all(backups_dir().join(failed)).ok();
+                }
+                Err(e) => log::error!("Could not put the previous database back: {}", e),
+            }
+            if let Ok(port) = crate::respawn_backend(app).await {
+                app.emit_all("backend-ready", port).ok();
+            }
+            Err(format!(
+                "The restored database failed its health check and was rolled back: {e}"
+            ))
+        }
+    }
+}
+
+fn due(cfg: &BackupConfig) -> bool {
+    let interval_ms = cfg.interval_hours.max(1) as i64 * 3600 * 1000;
+    list().first().map_or(true, |b| {
+        chrono::Utc::now().timestamp_millis() - b.created_at >= interval_ms
+    })
+}
+
+/// Takes a snapshot whenever the newest backup is older than the interval.
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let cfg = config::current(&handle).backups;
+            if !cfg.enabled || suspend::paused() || power::saving() || !due(&cfg) {
+                continue;
+            }
+            if let Err(e) = create(&handle, None, true).await {
+                log::warn!("Scheduled backup failed: {}", e);
+            }
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn list_backups() -> Vec<Backup> {
+    list()
+}
+
+/// Takes a hot backup now, regardless of the schedule.
+#[tauri::command]
+pub async fn create_backup_now(app: AppHandle) -> Result<Backup, String> {
+    create(&app, None, false).await
+}
+
+/// Checks the live database; `full` runs the slower integrity_check. The
+/// backend holds off writes while it runs.
+#[tauri::command]
+pub async fn check_db_integrity(full: Option<bool>) -> Result<IntegrityReport, String> {
+    if MAINTENANCE.load(Ordering::SeqCst) {
+        return Err("Database maintenance is in progress".into());
+    }
+    let full = full.unwrap_or(false);
+    let result = backend::get(&format!("/maintenance/integrity?full={}", full)).await?;
+    let mut report: IntegrityReport =
+        serde_json::from_value(result).map_err(|e| format!("Invalid integrity report: {e}"))?;
+    if report.ok {
+        log::info!(
+            "Database {} check passed in {} ms",
+            report.mode,
+            report.duration_ms
+        );
+        return Ok(report);
+    }
+    log::error!("Database integrity check failed: {:?}", report.errors);
+    report.suggested_backup = newest_sound_backup().await;
+    report.suggestion = Some(match &report.suggested_backup {
+        Some(name) => format!(
+            "The database is damaged. Restore backup {name} to recover; \
+             the damaged database is kept as a pre-restore backup."
+        ),
+        None => "The database is damaged and no intact backup was found. \
+                 Export what you can, then send a diagnostics bundle to support."
+            .into(),
+    });
+    Ok(report)
+}
+
+/// Replaces the database with backup `name` and restarts the backend,
+/// emitting "backup-restore-progress" along the way.
+#[tauri::command]
+pub async fn restore_backup(app: AppHandle, name: String) -> Result<RestoreResult, String> {
+    if MAINTENANCE.swap(true, Ordering::SeqCst) {
+        return Err("Database maintenance is in progress".into());
+    }
+    let result = restore(&app, &name).await;
+    MAINTENANCE.store(false, Ordering::SeqCst);
+    if let Err(e) = &result {
+        progress(&app, "failed", e.clone());
+    }
+    result
+}
+
+/// VACUUM / optimize / WAL truncation in the backend; returns the bytes
+/// reclaimed.
+#[tauri::command]
+pub async fn optimize_database() -> Result<OptimizeReport, String> {
+    // VACUUM builds the new file alongside the old one.
+    let db = crate::db_path();
+    let size = sidecar::db_files(&db)
+        .iter()
+        .filter_map(|p| std::fs::metadata(p).ok())
+        .map(|m| m.len())
+        .sum();
+    disk::ensure_space(&db, size)?;
+    if MAINTENANCE.swap(true, Ordering::SeqCst) {
+        return Err("Database maintenance is in progress".into());
+    }
+    log::info!("Optimizing database ({} bytes)", size);
+    let result = backend::post_slow("/maintenance/optimize", &json!({}), OPTIMIZE_TIMEOUT).await;
+    MAINTENANCE.store(false, Ordering::SeqCst);
+    let report: OptimizeReport =
+        serde_json::from_value(result?).map_err(|e| format!("Invalid optimize report: {e}"))?;
+    log::info!(
+        "Database optimized in {} ms, {} bytes reclaimed",
+        report.duration_ms,
+        report.reclaimed_bytes
+    );
+    Ok(report)
+}
+
+#[tauri::command]
+pub fn delete_backup(name: String) -> Result<(), String> {
+    let dir = backup_dir(&name)?;
+    std::fs::remove_dir_all(dir).map_err(|e| format!("Could not delete backup: {e}"))?;
+    log::info!("Deleted backup {}", name);
+    Ok(())
+}
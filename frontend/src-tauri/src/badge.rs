@@ -0,0 +1,221 @@
+// Badge — a count of items awaiting review on the Dock, taskbar and tray.
+//
+// Captures that arrive while the main window isn't focused (the clipboard
+// hotkeys, Services, Send to, the browser extension, feeds, email, the
+// phone; see capture.rs) and voice-note transcripts finished in the
+// background (see whisper.rs) each add one. Focusing the main window clears
+// it, and the frontend can set its own count with `set_badge_count`. The
+// count shows as the Dock tile's badge on macOS, a taskbar overlay icon on
+// Windows and a red badge on the tray icon (see tray_status.rs), which is all
+// Linux gets. Changes go out as "badge-count".
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use image::{Rgba, RgbaImage};
+use tauri::{AppHandle, Manager, WindowEvent};
+
+use crate::tray_status;
+
+const COLOR: Rgba<u8> = Rgba([220, 38, 38, 255]);
+const TEXT: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+/// 3×5 glyphs, one row per byte, high bit on the left.
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+const PLUS: [u8; 5] = [0b000, 0b010, 0b111, 0b010, 0b000];
+
+static COUNT: AtomicU32 = AtomicU32::new(0);
+
+pub fn count() -> u32 {
+    COUNT.load(Ordering::SeqCst)
+}
+
+/// Draws `count` (capped at "9+") as a red disc `size` pixels across in the
+/// top-right corner, cut out from the image by a transparent ring.
+pub fn stamp(image: &mut RgbaImage, count: u32, size: u32) {
+    let glyphs: Vec<[u8; 5]> = if count > 9 {
+        vec![DIGITS[9], PLUS]
+    } else {
+        vec![DIGITS[count as usize]]
+    };
+    let radius = size as f32 / 2.0;
+    let ring = if size < image.width() { 1.0 } else { 0.0 };
+    let cx = image.width() as f32 - radius;
+    let cy = radius;
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        let distance = (x as f32 + 0.5 - cx).hypot(y as f32 + 0.5 - cy);
+        if distance <= radius - ring {
+            *pixel = COLOR;
+        } else if distance <= radius {
+            *pixel = Rgba([0, 0, 0, 0]);
+        }
+    }
+
+    let columns = glyphs.len() as u32 * 4 - 1;
+    let inner = size * 3 / 5;
+    let scale = (inner / columns).min(inner / 5).max(1);
+    let left = (cx - (columns * scale) as f32 / 2.0).round() as i64;
+    let top = (cy - (5 * scale) as f32 / 2.0).round() as i64;
+    for (i, glyph) in glyphs.iter().enumerate() {
+        for (row, bits) in glyph.iter().enumerate() {
+            for column in 0..3 {
+                if bits & (0b100 >> column) == 0 {
+                    continue;
+                }
+                let x = left + ((i as u32 * 4 + column) * scale) as i64;
+                let y = top + (row as u32 * scale) as i64;
+                for dx in 0..scale as i64 {
+                    for dy in 0..scale as i64 {
+                        let (px, py) = (x + dx, y + dy);
+                        if px >= 0
+                            && py >= 0
+                            && (px as u32) < image.width()
+                            && (py as u32) < image.height()
+                        {
+                            image.put_pixel(px as u32, py as u32, TEXT);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn show(app: &AppHandle, count: u32) {
+    use objc::runtime::Object;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    // The Dock's label, capped at "99+".
+    let label = match count {
+        0 => None,
+        1..=99 => Some(count.to_string()),
+        _ => Some("99+".to_string()),
+    };
+    app.run_on_main_thread(move || unsafe {
+        let ns_app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+        let tile: *mut Object = msg_send![ns_app, dockTile];
+        let text: *mut Object = match label {
+            Some(label) => {
+                let c = std::ffi::CString::new(label).unwrap_or_default();
+                msg_send![class!(NSString), stringWithUTF8String: c.as_ptr()]
+            }
+            None => std::ptr::null_mut(),
+        };
+        let _: () = msg_send![tile, setBadgeLabel: text];
+    })
+    .ok();
+}
+
+#[cfg(windows)]
+fn show(app: &AppHandle, count: u32) {
+    use windows::core::{HSTRING, PCWSTR};
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_INPROC_SERVER};
+    use windows::Win32::UI::Shell::{ITaskbarList3, TaskbarList};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CreateIconFromResourceEx, DestroyIcon, HICON, LR_DEFAULTCOLOR,
+    };
+
+    /// The overlay is 16×16; the icon is built from a PNG.
+    const SIZE: u32 = 16;
+
+    let Some(Ok(hwnd)) = app.get_window("main").map(|w| w.hwnd()) else {
+        return;
+    };
+    let hwnd = HWND(hwnd.0);
+    let png = (count > 0).then(|| {
+        let mut image = RgbaImage::new(SIZE, SIZE);
+        stamp(&mut image, count, SIZE);
+        let mut png = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut png, image::ImageFormat::Png).ok();
+        png.into_inner()
+    });
+    // The taskbar object lives on the UI thread, where COM is set up.
+    app.run_on_main_thread(move || unsafe {
+        let result = (|| {
+            let taskbar: ITaskbarList3 =
+                CoCreateInstance(&TaskbarList, None, CLSCTX_INPROC_SERVER)?;
+            taskbar.HrInit()?;
+            let Some(png) = png else {
+                return taskbar.SetOverlayIcon(hwnd, HICON::default(), PCWSTR::null());
+            };
+            let icon = CreateIconFromResourceEx(
+                &png,
+                true,
+                0x0003_0000,
+                SIZE as i32,
+                SIZE as i32,
+                LR_DEFAULTCOLOR,
+            )?;
+            let description = HSTRING::from(format!("{count} awaiting review"));
+            let result = taskbar.SetOverlayIcon(hwnd, icon, &description);
+            DestroyIcon(icon).ok();
+            result
+        })();
+        if let Err(e) = result {
+            log::warn!("Badge: {}", e);
+        }
+    })
+    .ok();
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn show(_app: &AppHandle, _count: u32) {}
+
+pub fn set(app: &AppHandle, count: u32) {
+    if COUNT.swap(count, Ordering::SeqCst) == count {
+        return;
+    }
+    show(app, count);
+    tray_status::refresh();
+    app.emit_all("badge-count", count).ok();
+}
+
+/// Whether the user is looking at the main window.
+pub fn window_focused(app: &AppHandle) -> bool {
+    app.get_window("main")
+        .is_some_and(|w| w.is_focused().unwrap_or(false) && w.is_visible().unwrap_or(false))
+}
+
+/// Something new arrived; counts unless the user is looking at the window.
+pub fn arrived(app: &AppHandle) {
+    if !window_focused(app) {
+        set(app, count().saturating_add(1));
+    }
+}
+
+/// Clears the count whenever the main window gains focus.
+pub fn start(app: &AppHandle) {
+    let Some(window) = app.get_window("main") else {
+        return;
+    };
+    let handle = app.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Focused(true) = event {
+            set(&handle, 0);
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn get_badge_count() -> u32 {
+    count()
+}
+
+/// Sets the count shown on the Dock, taskbar and tray; 0 clears it.
+#[tauri::command]
+pub fn set_badge_count(app: AppHandle, count: u32) {
+    set(&app, count);
+}
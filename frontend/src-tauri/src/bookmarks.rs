@@ -0,0 +1,733 @@
+// Bookmarks — imports browser bookmarks as link snippets.
+//
+// Accepted files, told apart by content rather than name:
+//   Chrome / Edge   the profile's `Bookmarks` JSON file
+//   any browser     the Netscape HTML export ("Export bookmarks…")
+//   Firefox         the profile's places.sqlite (read directly, see below)
+//
+// Every bookmark becomes one capture (source "bookmarks") whose body is a
+// Markdown link and whose source_url is the bookmark's URL. The folders it
+// sits in become tags, minus the browser's own roots (bookmarks bar, other
+// bookmarks, ...); Firefox and HTML-export tags are added too. A URL that
+// appears in several folders is imported once with all their tags. The
+// import runs as a task (see tasks.rs), one progress step per bookmark, and
+// bookmarks already saved are handled by the duplicate policy (dedupe.rs).
+//
+// There is no SQLite library in the shell, so places.sqlite is read with a
+// small read-only B-tree walker that understands just enough of the file
+// format for two tables. Firefox keeps recent changes in places.sqlite-wal
+// while it runs; that file is not replayed, so import asks for Firefox to
+// be closed instead of silently missing bookmarks.
+
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::capture::Capture;
+use crate::dedupe::{Dedupe, DuplicateCounts};
+use crate::notion;
+use crate::tasks::{self, Task};
+
+/// JSON and HTML files are read whole; real ones are a few MB at most.
+const MAX_FILE_BYTES: u64 = 64 * 1024 * 1024;
+const MAX_PLACES_BYTES: u64 = 1024 * 1024 * 1024;
+/// Firefox's built-in folders; their names are not turned into tags.
+const FIREFOX_ROOTS: [&str; 5] = [
+    "root________",
+    "menu________",
+    "toolbar_____",
+    "unfiled_____",
+    "mobile______",
+];
+const FIREFOX_TAGS_ROOT: &str = "tags________";
+
+static HTML_ITEM: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?is)<h3\b([^>]*)>(.*?)</h3>|<a\b([^>]*)>(.*?)</a>|<(/?)dl\b").unwrap()
+});
+static HTML_ATTR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?i)\b([a-z_]+)="([^"]*)""#).unwrap());
+static HTML_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<[^>]*>").unwrap());
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct BookmarksImport {
+    /// "chromium", "html" or "firefox".
+    format: &'static str,
+    found: usize,
+    imported: usize,
+    /// Rejected by the backend's own duplicate check (flagged duplicates
+    /// when the backend has dedupe enabled).
+    rejected: usize,
+    failed: usize,
+    /// Non-web URLs (javascript:, place:, ...) that were left out.
+    skipped: usize,
+    duplicates: DuplicateCounts,
+}
+
+#[derive(Debug, Default)]
+struct Bookmark {
+    title: String,
+    url: String,
+    folders: Vec<String>,
+    tags: Vec<String>,
+}
+
+// ── Chrome / Edge ──────────────────────────────────────────────────────────
+fn children(node: &Value) -> impl Iterator<Item = &Value> {
+    node.get("children")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+}
+
+fn walk_chromium(node: &Value, path: &mut Vec<String>, out: &mut Vec<Bookmark>) {
+    let name = node.get("name").and_then(Value::as_str).unwrap_or_default();
+    match node.get("type").and_then(Value::as_str) {
+        Some("url") => out.push(Bookmark {
+            title: name.to_string(),
+            url: node
+                .get("url")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            folders: path.clone(),
+            tags: Vec::new(),
+        }),
+        Some("folder") => {
+            path.push(name.to_string());
+            for child in children(node) {
+                walk_chromium(child, path, out);
+            }
+            path.pop();
+        }
+        _ => {}
+    }
+}
+
+fn read_chromium(text: &str) -> Result<Vec<Bookmark>, String> {
+    let value: Value =
+        serde_json::from_str(text).map_err(|e| format!("Invalid bookmarks JSON: {e}"))?;
+    let roots = value
+        .get("roots")
+        .and_then(Value::as_object)
+        .ok_or("Not a Chrome or Edge bookmarks file")?;
+    let mut out = Vec::new();
+    // The roots themselves (bookmark bar, other, synced) are not folders
+    // anyone chose, so their children start with an empty path.
+    for root in roots.values() {
+        for child in children(root) {
+            walk_chromium(child, &mut Vec::new(), &mut out);
+        }
+    }
+    Ok(out)
+}
+
+// ── Netscape HTML ──────────────────────────────────────────────────────────
+fn html_attr(attrs: &str, name: &str) -> Option<String> {
+    HTML_ATTR
+        .captures_iter(attrs)
+        .find(|caps| caps[1].eq_ignore_ascii_case(name))
+        .map(|caps| notion::decode_entities(&caps[2]))
+}
+
+fn html_text(html: &str) -> String {
+    notion::decode_entities(&HTML_TAG.replace_all(html, ""))
+        .trim()
+        .to_string()
+}
+
+fn read_html(text: &str) -> Vec<Bookmark> {
+    // One entry per open <DL>: the folder it belongs to, or None for the
+    // top level and the browser's toolbar/unfiled roots.
+    let mut stack: Vec<Option<String>> = Vec::new();
+    let mut pending = None;
+    let mut out = Vec::new();
+    for caps in HTML_ITEM.captures_iter(text) {
+        if let Some(attrs) = caps.get(1) {
+            let root = html_attr(attrs.as_str(), "personal_toolbar_folder").is_some()
+                || html_attr(attrs.as_str(), "unfiled_bookmarks_folder").is_some();
+            pending = Some(html_text(&caps[2])).filter(|_| !root);
+        } else if let Some(attrs) = caps.get(3) {
+            out.push(Bookmark {
+                title: html_text(&caps[4]),
+                url: html_attr(attrs.as_str(), "href").unwrap_or_default(),
+                folders: stack.iter().flatten().cloned().collect(),
+                tags: html_attr(attrs.as_str(), "tags")
+                    .map(|tags| tags.split(',').map(str::to_string).collect())
+                    .unwrap_or_default(),
+            });
+        } else if &caps[5] == "/" {
+            stack.pop();
+        } else {
+            stack.push(pending.take());
+        }
+    }
+    out
+}
+
+// ── places.sqlite ──────────────────────────────────────────────────────────
+#[derive(Clone, Debug)]
+enum Field {
+    Null,
+    Int(i64),
+    Text(String),
+    /// Floats and blobs; places.sqlite has none that matter here.
+    Other,
+}
+
+impl Field {
+    fn int(&self) -> Option<i64> {
+        match self {
+            Field::Int(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    fn text(&self) -> &str {
+        match self {
+            Field::Text(s) => s,
+            _ => "",
+        }
+    }
+}
+
+struct Table {
+    columns: Vec<String>,
+    rows: Vec<Vec<Field>>,
+}
+
+impl Table {
+    fn column(&self, name: &str) -> Result<usize, String> {
+        self.columns
+            .iter()
+            .position(|c| c.eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("Column {name} is missing"))
+    }
+}
+
+/// Read-only view of an SQLite file held in memory.
+struct Sqlite {
+    data: Vec<u8>,
+    page_size: usize,
+    /// Page size minus the bytes reserved at the end of each page.
+    usable: usize,
+}
+
+fn varint(bytes: &[u8], pos: &mut usize) -> Result<i64, String> {
+    let mut value = 0u64;
+    for i in 0..9 {
+        let byte = *bytes.get(*pos).ok_or("Truncated varint")?;
+        *pos += 1;
+        if i == 8 {
+            value = (value << 8) | byte as u64;
+            break;
+        }
+        value = (value << 7) | (byte & 0x7f) as u64;
+        if byte & 0x80 == 0 {
+            break;
+        }
+    }
+    Ok(value as i64)
+}
+
+fn be_u32(bytes: Option<&[u8]>) -> Result<u32, String> {
+    bytes
+        .and_then(|b| b.try_into().ok())
+        .map(u32::from_be_bytes)
+        .ok_or_else(|| "Truncated page".to_string())
+}
+
+/// Big-endian two's complement integer of 1-8 bytes.
+fn be_int(bytes: &[u8]) -> i64 {
+    let start = if bytes.first().is_some_and(|b| b & 0x80 != 0) {
+        -1
+    } else {
+        0
+    };
+    bytes.iter().fold(start, |v, b| (v << 8) | *b as i64)
+}
+
+/// Decodes a record (header of serial types, then the values).
+fn record(payload: &[u8]) -> Result<Vec<Field>, String> {
+    let mut pos = 0;
+    let header_len = varint(payload, &mut pos)? as usize;
+    let mut types = Vec::new();
+    while pos < header_len {
+        types.push(varint(payload, &mut pos)?);
+    }
+    let mut at = header_len;
+    let mut fields = Vec::with_capacity(types.len());
+    for serial in types {
+        let len = match serial {
+            1..=4 => serial as usize,
+            5 => 6,
+            6 | 7 => 8,
+            n if n >= 12 => ((n - 12) / 2) as usize,
+            _ => 0,
+        };
+        let bytes = payload.get(at..at + len).ok_or("Truncated record")?;
+        at += len;
+        fields.push(match serial {
+            0 => Field::Null,
+            1..=6 => Field::Int(be_int(bytes)),
+            8 => Field::Int(0),
+            9 => Field::Int(1),
+            n if n >= 13 && n % 2 == 1 => Field::Text(String::from_utf8_lossy(bytes).into_owned()),
+            _ => Field::Other,
+        });
+    }
+    Ok(fields)
+}
+
+/// Column names from a CREATE TABLE statement, and the index of the
+/// INTEGER PRIMARY KEY column (stored as the rowid, not in the record).
+fn table_columns(sql: &str) -> (Vec<String>, Option<usize>) {
+    let inner = match (sql.find('('), sql.rfind(')')) {
+        (Some(open), Some(close)) if open < close => &sql[open + 1..close],
+        _ => "",
+    };
+    let mut defs = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in inner.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                defs.push(&inner[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    defs.push(&inner[start..]);
+
+    let mut names = Vec::new();
+    let mut rowid = None;
+    for def in defs {
+        let upper = def.trim().to_uppercase();
+        let constraint = ["PRIMARY", "UNIQUE", "CHECK", "FOREIGN", "CONSTRAINT"]
+            .iter()
+            .any(|k| upper.starts_with(k));
+        if constraint || upper.is_empty() {
+            continue;
+        }
+        if upper.contains("INTEGER PRIMARY KEY") {
+            rowid = Some(names.len());
+        }
+        let name = def.split_whitespace().next().unwrap_or_default();
+        names.push(name.trim_matches(['"', '`', '[', ']']).to_string());
+    }
+    (names, rowid)
+}
+
+impl Sqlite {
+    fn open(data: Vec<u8>) -> Result<Self, String> {
+        if data.len() < 100 || &data[..16] != b"SQLite format 3\0" {
+            return Err("Not an SQLite database".into());
+        }
+        let page_size = match u16::from_be_bytes([data[16], data[17]]) {
+            1 => 65536,
+            n => n as usize,
+        };
+        if page_size < 512 || !page_size.is_power_of_two() {
+            return Err(format!("Invalid page size {}", page_size));
+        }
+        if u32::from_be_bytes([data[56], data[57], data[58], data[59]]) > 1 {
+            return Err("Only UTF-8 databases are supported".into());
+        }
+        Ok(Self {
+            usable: page_size - data[20] as usize,
+            page_size,
+            data,
+        })
+    }
+
+    fn page(&self, number: u32) -> Result<&[u8], String> {
+        let start = (number as usize)
+            .checked_sub(1)
+            .ok_or("Invalid page number 0")?
+            * self.page_size;
+        self.data
+            .get(start..start + self.page_size)
+            .ok_or_else(|| format!("Page {} is past the end of the file", number))
+    }
+
+    /// Payload of a table leaf cell, following overflow pages if it spills.
+    fn payload(&self, cell: &[u8], size: usize) -> Result<Vec<u8>, String> {
+        let usable = self.usable;
+        let max_local = usable - 35;
+        if size <= max_local {
+            return Ok(cell.get(..size).ok_or("Truncated cell")?.to_vec());
+        }
+        let min_local = (usable - 12) * 32 / 255 - 23;
+        let spill = min_local + (size - min_local) % (usable - 4);
+        let local = if spill <= max_local { spill } else { min_local };
+        let mut out = cell.get(..local).ok_or("Truncated cell")?.to_vec();
+        let mut next = be_u32(cell.get(local..local + 4))?;
+        while out.len() < size {
+            if next == 0 {
+                return Err("Overflow chain ends early".into());
+            }
+            let page = self.page(next)?;
+            next = be_u32(page.get(..4))?;
+            let take = (size - out.len()).min(usable - 4);
+            out.extend_from_slice(&page[4..4 + take]);
+        }
+        Ok(out)
+    }
+
+    /// Collects (rowid, record) for every row of the table B-tree at `root`.
+    fn rows(
+        &self,
+        root: u32,
+        depth: usize,
+        out: &mut Vec<(i64, Vec<Field>)>,
+    ) -> Result<(), String> {
+        if depth > 32 {
+            return Err("Table B-tree is too deep".into());
+        }
+        let page = self.page(root)?;
+        let header = if root == 1 { 100 } else { 0 };
+        let interior = match page[header] {
+            0x05 => true,
+            0x0d => false,
+            kind => return Err(format!("Page {} is not a table page ({:#x})", root, kind)),
+        };
+        let cells = u16::from_be_bytes([page[header + 3], page[header + 4]]) as usize;
+        let pointers = header + if interior { 12 } else { 8 };
+        for i in 0..cells {
+            let at = pointers + 2 * i;
+            let offset = page
+                .get(at..at + 2)
+                .map(|b| u16::from_be_bytes([b[0], b[1]]) as usize)
+                .ok_or("Truncated page")?;
+            let cell = page.get(offset..).ok_or("Invalid cell offset")?;
+            if interior {
+                self.rows(be_u32(cell.get(..4))?, depth + 1, out)?;
+            } else {
+                let mut pos = 0;
+                let size = varint(cell, &mut pos)? as usize;
+                let rowid = varint(cell, &mut pos)?;
+                out.push((rowid, record(&self.payload(&cell[pos..], size)?)?));
+            }
+        }
+        if interior {
+            self.rows(be_u32(page.get(header + 8..header + 12))?, depth + 1, out)?;
+        }
+        Ok(())
+    }
+
+    fn table(&self, name: &str) -> Result<Table, String> {
+        let mut schema = Vec::new();
+        self.rows(1, 0, &mut schema)?;
+        let (root, sql) = schema
+            .iter()
+            .find_map(|(_, f)| match (f.first()?, f.get(1)?, f.get(3)?.int()?) {
+                (Field::Text(kind), Field::Text(table), root)
+                    if kind == "table" && table == name =>
+                {
+                    Some((root as u32, f.get(4)?.text().to_string()))
+                }
+                _ => None,
+            })
+            .ok_or_else(|| format!("No {} table", name))?;
+        let (columns, rowid) = table_columns(&sql);
+        let mut raw = Vec::new();
+        self.rows(root, 0, &mut raw)?;
+        let rows = raw
+            .into_iter()
+            .map(|(id, mut fields)| {
+                // Rows written before an ALTER TABLE ADD COLUMN are shorter.
+                fields.resize(columns.len(), Field::Null);
+                if let Some(i) = rowid {
+                    fields[i] = Field::Int(id);
+                }
+                fields
+            })
+            .collect();
+        Ok(Table { columns, rows })
+    }
+}
+
+struct Place {
+    kind: i64,
+    fk: Option<i64>,
+    parent: i64,
+    title: String,
+    guid: String,
+}
+
+fn read_places(path: &Path) -> Result<Vec<Bookmark>, String> {
+    let mut wal = path.as_os_str().to_owned();
+    wal.push("-wal");
+    if std::fs::metadata(&wal).is_ok_and(|m| m.len() > 0) {
+        return Err(
+            "Firefox has unsaved changes in places.sqlite-wal; close Firefox and try again".into(),
+        );
+    }
+    let size = std::fs::metadata(path).map_err(|e| e.to_string())?.len();
+    if size > MAX_PLACES_BYTES {
+        return Err(format!(
+            "places.sqlite is larger than {} MB",
+            MAX_PLACES_BYTES >> 20
+        ));
+    }
+    let data = std::fs::read(path).map_err(|e| {
+        format!(
+            "Could not read {:?} (close Firefox and try again): {e}",
+            path
+        )
+    })?;
+    let db = Sqlite::open(data)?;
+
+    let places = db.table("moz_places")?;
+    let (id, url, title) = (
+        places.column("id")?,
+        places.column("url")?,
+        places.column("title")?,
+    );
+    let urls: HashMap<i64, (String, String)> = places
+        .rows
+        .iter()
+        .filter_map(|row| {
+            let key = row[id].int()?;
+            Some((
+                key,
+                (row[url].text().to_string(), row[title].text().to_string()),
+            ))
+        })
+        .collect();
+
+    let marks = db.table("moz_bookmarks")?;
+    let columns = ["id", "type", "fk", "parent", "title", "guid"]
+        .iter()
+        .map(|c| marks.column(c))
+        .collect::<Result<Vec<_>, _>>()?;
+    let nodes: HashMap<i64, Place> = marks
+        .rows
+        .iter()
+        .filter_map(|row| {
+            let place = Place {
+                kind: row[columns[1]].int()?,
+                fk: row[columns[2]].int(),
+                parent: row[columns[3]].int().unwrap_or_default(),
+                title: row[columns[4]].text().to_string(),
+                guid: row[columns[5]].text().to_string(),
+            };
+            Some((row[columns[0]].int()?, place))
+        })
+        .collect();
+
+    // Firefox stores a tag as a folder under the tags root holding one
+    // bookmark per tagged URL.
+    let mut place_tags: HashMap<i64, Vec<String>> = HashMap::new();
+    for node in nodes.values().filter(|n| n.kind == 1) {
+        let Some(folder) = nodes.get(&node.parent) else {
+            continue;
+        };
+        if nodes.get(&folder.parent).map(|p| p.guid.as_str()) == Some(FIREFOX_TAGS_ROOT) {
+            if let Some(fk) = node.fk {
+                place_tags.entry(fk).or_default().push(folder.title.clone());
+            }
+        }
+    }
+
+    let mut ids: Vec<&i64> = nodes.keys().collect();
+    ids.sort();
+    let mut out = Vec::new();
+    for key in ids {
+        let node = &nodes[key];
+        let Some((url, place_title)) = node
+            .fk
+            .filter(|_| node.kind == 1)
+            .and_then(|fk| urls.get(&fk))
+        else {
+            continue;
+        };
+        let mut folders = Vec::new();
+        let mut parent = node.parent;
+        let mut is_tag = false;
+        // Bounded in case of a parent cycle in a damaged file.
+        for _ in 0..64 {
+            let Some(folder) = nodes.get(&parent) else {
+                break;
+            };
+            if folder.guid == FIREFOX_TAGS_ROOT {
+                is_tag = true;
+                break;
+            }
+            if FIREFOX_ROOTS.contains(&folder.guid.as_str()) {
+                break;
+            }
+            folders.push(folder.title.clone());
+            parent = folder.parent;
+        }
+        if is_tag {
+            continue;
+        }
+        folders.reverse();
+        out.push(Bookmark {
+            title: if node.title.is_empty() {
+                place_title.clone()
+            } else {
+                node.title.clone()
+            },
+            url: url.clone(),
+            folders,
+            tags: node
+                .fk
+                .and_then(|fk| place_tags.get(&fk))
+                .cloned()
+                .unwrap_or_default(),
+        });
+    }
+    Ok(out)
+}
+
+// ── Import ─────────────────────────────────────────────────────────────────
+/// Works out the format and reads every bookmark in it.
+fn read(path: &Path) -> Result<(&'static str, Vec<Bookmark>), String> {
+    let mut head = [0u8; 16];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read(&mut head))
+        .map_err(|e| format!("Could not open {:?}: {e}", path))?;
+    if &head == b"SQLite format 3\0" {
+        return Ok(("firefox", read_places(path)?));
+    }
+    let size = std::fs::metadata(path).map_err(|e| e.to_string())?.len();
+    if size > MAX_FILE_BYTES {
+        return Err(format!("File is larger than {} MB", MAX_FILE_BYTES >> 20));
+    }
+    let bytes = std::fs::read(path).map_err(|e| format!("Could not read {:?}: {e}", path))?;
+    let text = String::from_utf8_lossy(&bytes);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    if text.starts_with('{') {
+        return Ok(("chromium", read_chromium(text)?));
+    }
+    if HTML_ITEM.is_match(text) {
+        return Ok(("html", read_html(text)));
+    }
+    Err("Not a bookmarks file (expected Chrome/Edge Bookmarks, an HTML export or Firefox places.sqlite)".into())
+}
+
+/// Drops non-web URLs and merges repeats of the same URL. Returns the
+/// bookmarks and how many were dropped.
+fn merge(found: Vec<Bookmark>) -> (Vec<Bookmark>, usize) {
+    let mut out: Vec<Bookmark> = Vec::new();
+    let mut by_url: HashMap<String, usize> = HashMap::new();
+    let mut skipped = 0;
+    for mark in found {
+        let url = mark.url.trim();
+        let web = ["http://", "https://", "ftp://", "file://"]
+            .iter()
+            .any(|scheme| {
+                url.len() > scheme.len() && url[..scheme.len()].eq_ignore_ascii_case(scheme)
+            });
+        if !web {
+            skipped += 1;
+            continue;
+        }
+        let index = *by_url.entry(url.to_string()).or_insert_with(|| {
+            out.push(Bookmark {
+                title: mark.title.trim().to_string(),
+                url: url.to_string(),
+                ..Default::default()
+            });
+            out.len() - 1
+        });
+        let target = &mut out[index];
+        let mut seen: HashSet<String> = target.tags.iter().map(|t| t.to_lowercase()).collect();
+        for tag in mark.folders.iter().chain(&mark.tags) {
+            let tag = tag.trim();
+            if !tag.is_empty() && seen.insert(tag.to_lowercase()) {
+                target.tags.push(tag.to_string());
+            }
+        }
+    }
+    (out, skipped)
+}
+
+/// A Markdown link to `url`, safe for any title and URL.
+pub fn link(title: &str, url: &str) -> String {
+    let target = url
+        .replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29");
+    format!(
+        "[{}]({})",
+        title.replace('[', "\\[").replace(']', "\\]"),
+        target
+    )
+}
+
+fn to_capture(mark: &Bookmark) -> Capture {
+    let title = if mark.title.is_empty() {
+        mark.url.clone()
+    } else {
+        mark.title.clone()
+    };
+    Capture {
+        body: link(&title, &mark.url),
+        title: Some(title),
+        source: "bookmarks".into(),
+        source_url: Some(mark.url.clone()),
+        tags: mark.tags.clone(),
+        ..Default::default()
+    }
+}
+
+async fn import(app: AppHandle, task: Task, path: String) -> Result<BookmarksImport, String> {
+    let mut dedupe = Dedupe::load(&app).await?;
+    let source = PathBuf::from(&path);
+    let (format, found) = tauri::async_runtime::spawn_blocking(move || read(&source))
+        .await
+        .map_err(|e| e.to_string())??;
+    let (bookmarks, skipped) = merge(found);
+    let total = bookmarks.len();
+    let mut summary = BookmarksImport {
+        format,
+        found: total,
+        skipped,
+        ..Default::default()
+    };
+    for (index, mark) in bookmarks.iter().enumerate() {
+        if task.cancelled() {
+            break;
+        }
+        task.progress(index as u64, Some(total as u64), Some(&mark.title));
+        match dedupe.submit(&app, to_capture(mark)).await {
+            Ok(Some(_)) => summary.imported += 1,
+            Ok(None) => {}
+            Err(e) if e.contains("DUPLICATE_CONTENT") => summary.rejected += 1,
+            Err(e) => {
+                log::warn!("Bookmark import of entry {} failed: {}", index + 1, e);
+                summary.failed += 1;
+            }
+        }
+    }
+    summary.duplicates = dedupe.counts();
+    log::info!(
+        "Bookmarks import ({}): {} found, {} imported, {} left out as duplicates, {} failed",
+        format,
+        summary.found,
+        summary.imported,
+        dedupe.removed() + summary.rejected,
+        summary.failed
+    );
+    Ok(summary)
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Imports a Chrome/Edge `Bookmarks` file, an HTML bookmarks export or a
+/// Firefox places.sqlite. Returns the task id.
+#[tauri::command]
+pub fn import_bookmarks(app: AppHandle, path: String) -> u64 {
+    let handle = app.clone();
+    tasks::spawn(&app, "import_bookmarks", move |task| {
+        import(handle, task, path)
+    })
+}
@@ -0,0 +1,139 @@
+// Capture — single entry point for turning shell-side captures into snippets.
+//
+// Capture sources (accessibility, hotkeys, ...) build a `Capture` and hand it
+// to `submit`, which runs the post-processing pipeline (see pipeline.rs),
+// creates the snippet on the backend and tells the frontend about it.
+//
+// Captures made while the backend is unreachable are kept in an outbox
+// (data_dir()/outbox.json) and submitted once it is back.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::{backend, badge, config, encryption, history, pipeline, recent, routing};
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Capture {
+    pub title: Option<String>,
+    pub body: String,
+    /// Which capture source produced this, e.g. "accessibility".
+    pub source: String,
+    pub source_url: Option<String>,
+    pub tags: Vec<String>,
+    /// Collection names; the backend creates missing ones.
+    #[serde(default)]
+    pub collections: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Pending {
+    pub id: u64,
+    pub capture: Capture,
+    pub queued_at: u64,
+}
+
+/// Serializes outbox read-modify-write cycles.
+static OUTBOX_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+fn outbox_path() -> PathBuf {
+    crate::data_dir().join("outbox.json")
+}
+
+fn load_outbox() -> Vec<Pending> {
+    encryption::read_store(&outbox_path())
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save_outbox(pending: &[Pending]) {
+    let text = serde_json::to_string_pretty(pending).unwrap_or_default();
+    std::fs::create_dir_all(crate::data_dir()).ok();
+    if let Err(e) = encryption::write_store(&outbox_path(), &text) {
+        log::error!("Could not save capture outbox: {}", e);
+    }
+}
+
+fn enqueue(capture: Capture) {
+    let _guard = OUTBOX_LOCK.lock().unwrap();
+    let mut pending = load_outbox();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let id = pending.last().map_or(now, |p| now.max(p.id + 1));
+    pending.push(Pending {
+        id,
+        capture,
+        queued_at: now / 1000,
+    });
+    save_outbox(&pending);
+}
+
+/// Captures waiting for the backend, oldest first.
+pub fn pending() -> Vec<Pending> {
+    let _guard = OUTBOX_LOCK.lock().unwrap();
+    load_outbox()
+}
+
+/// Submits queued captures; stops at the first one the backend can't take.
+pub async fn flush_outbox(app: &AppHandle) {
+    for item in pending() {
+        match create(app, &item.capture).await {
+            Ok(_) => {
+                let _guard = OUTBOX_LOCK.lock().unwrap();
+                let mut pending = load_outbox();
+                pending.retain(|p| p.id != item.id);
+                save_outbox(&pending);
+            }
+            Err(e) => {
+                log::warn!("Outbox flush stopped: {}", e);
+                return;
+            }
+        }
+    }
+}
+
+async fn create(app: &AppHandle, capture: &Capture) -> Result<Value, String> {
+    let snippet = backend::post(
+        "/snippets",
+        &json!({
+            "title": capture.title,
+            "body": capture.body,
+            "source": capture.source,
+            "source_url": capture.source_url,
+            "tags": capture.tags,
+            "collections": capture.collections,
+        }),
+    )
+    .await?;
+    history::record_snippet(app, &snippet);
+    recent::changed(app);
+    badge::arrived(app);
+    app.emit_all("capture-created", &snippet).ok();
+    Ok(snippet)
+}
+
+pub async fn submit(app: &AppHandle, capture: Capture) -> Result<Value, String> {
+    if capture.body.trim().is_empty() {
+        return Err("Nothing to capture".into());
+    }
+    let cfg = config::current(app);
+    let capture = pipeline::apply(&cfg.pipeline, capture)?;
+    let capture = routing::route(&cfg.routing, capture);
+    match create(app, &capture).await {
+        Err(e) if backend::is_unreachable(&e) => {
+            log::warn!("Backend unreachable, queued capture: {}", e);
+            enqueue(capture);
+            app.emit_all("capture-queued", ()).ok();
+            Err(format!("{e} (capture queued)"))
+        }
+        result => result,
+    }
+}
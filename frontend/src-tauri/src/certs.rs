@@ -0,0 +1,138 @@
+// Custom CA certificates — trust private CAs (self-hosted model gateways,
+// TLS-inspecting proxies) without turning verification off.
+//
+// Every *.pem / *.crt file in app_dir()/certs/ is read as a PEM bundle.
+// The certificates are added as extra roots to outbound reqwest clients
+// (`proxy::client_builder`) and written together to
+// runtime_dir()/ca-certs.pem, which the sidecar receives as PINUP_CA_CERTS
+// and merges with its default store. Files are re-read for each new client
+// and on sidecar restart, so dropping in a file needs no app restart for
+// downloads. Unparseable files are skipped and reported by
+// `list_ca_certificates`.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CaFile {
+    file: String,
+    certificates: usize,
+    error: Option<String>,
+}
+
+pub fn certs_dir() -> PathBuf {
+    crate::app_dir().join("certs")
+}
+
+fn is_cert_file(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("pem") || e.eq_ignore_ascii_case("crt"))
+}
+
+/// Each certificate file with its raw PEM and parse result, sorted by name.
+fn read_all() -> Vec<(CaFile, Vec<u8>, Vec<reqwest::Certificate>)> {
+    let Ok(entries) = std::fs::read_dir(certs_dir()) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| is_cert_file(p))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let file = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let parsed = std::fs::read(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|pem| {
+                    let certs =
+                        reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| e.to_string())?;
+                    if certs.is_empty() {
+                        return Err("no PEM certificates found".to_string());
+                    }
+                    Ok((pem, certs))
+                });
+            match parsed {
+                Ok((pem, certs)) => (
+                    CaFile {
+                        file,
+                        certificates: certs.len(),
+                        error: None,
+                    },
+                    pem,
+                    certs,
+                ),
+                Err(e) => {
+                    log::warn!("Skipping CA file {}: {}", path.display(), e);
+                    (
+                        CaFile {
+                            file,
+                            certificates: 0,
+                            error: Some(e),
+                        },
+                        Vec::new(),
+                        Vec::new(),
+                    )
+                }
+            }
+        })
+        .collect()
+}
+
+/// Adds every custom CA as an extra root; the system store stays in use.
+pub fn add_roots(mut builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+    for (_, _, certs) in read_all() {
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+    builder
+}
+
+/// Sidecar environment pointing at a combined bundle of the custom CAs.
+pub fn backend_env() -> Vec<(String, String)> {
+    let pems: Vec<Vec<u8>> = read_all()
+        .into_iter()
+        .filter(|(_, _, certs)| !certs.is_empty())
+        .map(|(_, pem, _)| pem)
+        .collect();
+    let bundle = crate::runtime_dir().join("ca-certs.pem");
+    if pems.is_empty() {
+        std::fs::remove_file(&bundle).ok();
+        return Vec::new();
+    }
+    let mut joined = Vec::new();
+    for pem in pems {
+        joined.extend_from_slice(&pem);
+        if !joined.ends_with(b"\n") {
+            joined.push(b'\n');
+        }
+    }
+    if let Err(e) = std::fs::write(&bundle, joined) {
+        log::warn!("Could not write {}: {}", bundle.display(), e);
+        return Vec::new();
+    }
+    vec![(
+        "PINUP_CA_CERTS".into(),
+        bundle.to_string_lossy().to_string(),
+    )]
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Certificate files found in the certs folder (created if missing) and
+/// whether they parsed. The sidecar picks up changes on restart.
+#[tauri::command]
+pub fn list_ca_certificates() -> Result<Vec<CaFile>, String> {
+    std::fs::create_dir_all(certs_dir())
+        .map_err(|e| format!("Failed to create certs dir: {}", e))?;
+    Ok(read_all().into_iter().map(|(file, _, _)| file).collect())
+}
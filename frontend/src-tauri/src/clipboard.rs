@@ -0,0 +1,496 @@
+// Clipboard watcher — polls the system clipboard and keeps a recent history.
+//
+// Privacy filters run here in Rust, before anything reaches the webview or
+// the backend:
+//   - copies made while an ignored app (password managers) is frontmost
+//   - text that looks like a credential (see secrets.rs)
+//   - content the source app marked as concealed/transient on the pasteboard
+// Skipped copies only produce a `clipboard-skipped` event with the reason.
+// "Pause Capture" in the tray and Dock menus stops the watcher until it is
+// resumed or the app restarts, without touching the saved setting.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::richtext::{self, RichText};
+use crate::{app_context, app_lock, attachments, backend, config, performance, secrets};
+
+const HISTORY_LIMIT: usize = 200;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ClipboardConfig {
+    pub enabled: bool,
+    pub poll_ms: u64,
+    /// Copies made while one of these apps is frontmost are ignored.
+    pub ignored_apps: Vec<String>,
+    pub skip_secrets: bool,
+    /// Extra regexes treated as secrets, on top of the built-in set.
+    pub secret_patterns: Vec<String>,
+    /// Respect concealed/transient markers set by password managers.
+    pub honor_concealed: bool,
+}
+
+impl Default for ClipboardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            poll_ms: 750,
+            ignored_apps: [
+                "1Password",
+                "Bitwarden",
+                "KeePassXC",
+                "KeePass",
+                "LastPass",
+                "Dashlane",
+                "Enpass",
+                "Keychain Access",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            skip_secrets: true,
+            secret_patterns: Vec::new(),
+            honor_concealed: true,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ClipboardEntry {
+    pub text: String,
+    pub app: Option<String>,
+    pub ts: u64,
+}
+
+static HISTORY: Lazy<Mutex<VecDeque<ClipboardEntry>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(HISTORY_LIMIT)));
+
+/// Text we put on the clipboard ourselves; the watcher must not record it.
+static SELF_WRITE: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+pub fn paused() -> bool {
+    PAUSED.load(Ordering::SeqCst)
+}
+
+/// Pauses or resumes capture and relabels the tray item to match.
+pub fn toggle_paused(app: &AppHandle) {
+    let paused = !PAUSED.fetch_xor(true, Ordering::SeqCst);
+    log::info!(
+        "Clipboard capture {}",
+        if paused { "paused" } else { "resumed" }
+    );
+    crate::tray::refresh(app);
+    app.emit_all("clipboard-paused", paused).ok();
+}
+
+/// Puts `text` on the system clipboard without the watcher picking it up.
+pub fn write_text(text: &str) -> Result<(), String> {
+    *SELF_WRITE.lock().unwrap() = Some(text.to_string());
+    arboard::Clipboard::new()
+        .and_then(|mut c| c.set_text(text.to_string()))
+        .map_err(|e| format!("Could not write clipboard: {e}"))
+}
+
+// ── Rich copy ──────────────────────────────────────────────────────────────
+// Plain text, HTML and RTF go on the clipboard in one write so each target
+// app picks the richest flavor it understands.
+#[cfg(target_os = "macos")]
+mod rich {
+    use crate::richtext::RichText;
+    use objc::runtime::Object;
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::ffi::CString;
+
+    unsafe fn ns_str(s: &str) -> *mut Object {
+        let c = CString::new(s.replace('\0', "")).unwrap_or_default();
+        msg_send![class!(NSString), stringWithUTF8String: c.as_ptr()]
+    }
+
+    pub fn write(rich: &RichText) -> Result<(), String> {
+        unsafe {
+            let pasteboard: *mut Object = msg_send![class!(NSPasteboard), generalPasteboard];
+            let _: isize = msg_send![pasteboard, clearContents];
+            for (ty, value) in [
+                ("public.utf8-plain-text", &rich.plain),
+                ("public.html", &rich.html),
+                ("public.rtf", &rich.rtf),
+            ] {
+                let ok: bool = msg_send![pasteboard, setString: ns_str(value) forType: ns_str(ty)];
+                if !ok {
+                    return Err(format!("Could not write {} to the pasteboard", ty));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod rich {
+    use crate::richtext::RichText;
+    use windows::core::w;
+    use windows::Win32::Foundation::{HANDLE, HWND};
+    use windows::Win32::System::DataExchange::{
+        CloseClipboard, EmptyClipboard, OpenClipboard, RegisterClipboardFormatW, SetClipboardData,
+    };
+    use windows::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+    use windows::Win32::System::Ole::CF_UNICODETEXT;
+
+    /// Wraps `html` in the CF_HTML header, whose byte offsets must be exact.
+    fn cf_html(html: &str) -> String {
+        const HEADER: &str = "Version:0.9\r\nStartHTML:{sh}\r\nEndHTML:{eh}\r\nStartFragment:{sf}\r\nEndFragment:{ef}\r\n";
+        let prefix = "<html><body><!--StartFragment-->";
+        let suffix = "<!--EndFragment--></body></html>";
+        // Every placeholder becomes a 10-digit number.
+        let header_len = HEADER.len() + 4 * (10 - 4);
+        let start_fragment = header_len + prefix.len();
+        let end_fragment = start_fragment + html.len();
+        let end_html = end_fragment + suffix.len();
+        let header = HEADER
+            .replace("{sh}", &format!("{:010}", header_len))
+            .replace("{eh}", &format!("{:010}", end_html))
+            .replace("{sf}", &format!("{:010}", start_fragment))
+            .replace("{ef}", &format!("{:010}", end_fragment));
+        format!("{}{}{}{}", header, prefix, html, suffix)
+    }
+
+    unsafe fn set(format: u32, bytes: &[u8]) -> Result<(), String> {
+        let mem = GlobalAlloc(GMEM_MOVEABLE, bytes.len()).map_err(|e| e.to_string())?;
+        let ptr = GlobalLock(mem) as *mut u8;
+        if ptr.is_null() {
+            return Err("GlobalLock failed".into());
+        }
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len());
+        GlobalUnlock(mem).ok();
+        // The clipboard owns the memory once SetClipboardData succeeds.
+        SetClipboardData(format, HANDLE(mem.0 as isize))
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn write(rich: &RichText) -> Result<(), String> {
+        let mut plain: Vec<u8> = Vec::new();
+        for unit in rich.plain.encode_utf16().chain([0]) {
+            plain.extend_from_slice(&unit.to_le_bytes());
+        }
+        let mut html = cf_html(&rich.html).into_bytes();
+        html.push(0);
+        let mut rtf = rich.rtf.clone().into_bytes();
+        rtf.push(0);
+
+        unsafe {
+            OpenClipboard(HWND(0)).map_err(|e| format!("Could not open clipboard: {e}"))?;
+            let result = EmptyClipboard()
+                .map_err(|e| e.to_string())
+                .and_then(|_| set(CF_UNICODETEXT.0 as u32, &plain))
+                .and_then(|_| set(RegisterClipboardFormatW(w!("HTML Format")), &html))
+                .and_then(|_| set(RegisterClipboardFormatW(w!("Rich Text Format")), &rtf));
+            CloseClipboard().ok();
+            result
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod rich {
+    use crate::richtext::RichText;
+
+    /// X11/Wayland clipboards are served live by the owning process, and
+    /// arboard only offers HTML + plain text; apps here take HTML anyway.
+    pub fn write(rich: &RichText) -> Result<(), String> {
+        arboard::Clipboard::new()
+            .and_then(|mut c| c.set_html(rich.html.clone(), Some(rich.plain.clone())))
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Puts all flavors of `rich` on the clipboard without the watcher picking it up.
+pub fn write_rich(rich: &RichText) -> Result<(), String> {
+    *SELF_WRITE.lock().unwrap() = Some(rich.plain.clone());
+    rich::write(rich).map_err(|e| format!("Could not write clipboard: {e}"))
+}
+
+// ── Concealed-content hints ────────────────────────────────────────────────
+#[cfg(target_os = "macos")]
+mod hints {
+    use objc::runtime::Object;
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::ffi::CStr;
+    use std::os::raw::c_char;
+
+    // http://nspasteboard.org
+    const MARKERS: &[&str] = &[
+        "org.nspasteboard.ConcealedType",
+        "org.nspasteboard.TransientType",
+    ];
+
+    pub fn is_concealed() -> bool {
+        unsafe {
+            let pasteboard: *mut Object = msg_send![class!(NSPasteboard), generalPasteboard];
+            let types: *mut Object = msg_send![pasteboard, types];
+            if types.is_null() {
+                return false;
+            }
+            let count: usize = msg_send![types, count];
+            (0..count).any(|i| {
+                let t: *mut Object = msg_send![types, objectAtIndex: i];
+                let utf8: *const c_char = msg_send![t, UTF8String];
+                !utf8.is_null() && MARKERS.contains(&CStr::from_ptr(utf8).to_str().unwrap_or(""))
+            })
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod hints {
+    use windows::core::w;
+    use windows::Win32::System::DataExchange::{
+        IsClipboardFormatAvailable, RegisterClipboardFormatW,
+    };
+
+    pub fn is_concealed() -> bool {
+        unsafe {
+            // Set by password managers that opt out of clipboard history/monitors.
+            let exclude =
+                RegisterClipboardFormatW(w!("ExcludeClipboardContentFromMonitorProcessing"));
+            let ignore = RegisterClipboardFormatW(w!("Clipboard Viewer Ignore"));
+            [exclude, ignore]
+                .iter()
+                .any(|f| *f != 0 && IsClipboardFormatAvailable(*f).is_ok())
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod hints {
+    use std::time::{Duration, Instant};
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{
+        AtomEnum, ConnectionExt, CreateWindowAux, EventMask, WindowClass,
+    };
+    use x11rb::protocol::Event;
+
+    fn atom(conn: &impl Connection, name: &str) -> Option<u32> {
+        conn.intern_atom(false, name.as_bytes())
+            .ok()?
+            .reply()
+            .ok()
+            .map(|r| r.atom)
+    }
+
+    /// Reads the CLIPBOARD TARGETS and looks for KDE's password-manager hint.
+    pub fn is_concealed() -> bool {
+        targets_include("x-kde-passwordManagerHint").unwrap_or(false)
+    }
+
+    fn targets_include(target: &str) -> Option<bool> {
+        let (conn, screen) = x11rb::connect(None).ok()?;
+        let root = conn.setup().roots[screen].root;
+        let window = conn.generate_id().ok()?;
+        conn.create_window(
+            0,
+            window,
+            root,
+            0,
+            0,
+            1,
+            1,
+            0,
+            WindowClass::INPUT_ONLY,
+            0,
+            &CreateWindowAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+        )
+        .ok()?;
+        let clipboard = atom(&conn, "CLIPBOARD")?;
+        let targets = atom(&conn, "TARGETS")?;
+        let wanted = atom(&conn, target)?;
+        let property = atom(&conn, "PINUP_TARGETS")?;
+        conn.convert_selection(window, clipboard, targets, property, 0u32)
+            .ok()?;
+        conn.flush().ok()?;
+
+        let deadline = Instant::now() + Duration::from_millis(200);
+        let mut found = None;
+        while Instant::now() < deadline {
+            match conn.poll_for_event() {
+                Ok(Some(Event::SelectionNotify(ev))) => {
+                    if ev.property == 0 {
+                        found = Some(false);
+                        break;
+                    }
+                    let reply = conn
+                        .get_property(true, window, property, AtomEnum::ATOM, 0, 1024)
+                        .ok()?
+                        .reply()
+                        .ok()?;
+                    found = Some(
+                        reply
+                            .value32()
+                            .is_some_and(|mut atoms| atoms.any(|a| a == wanted)),
+                    );
+                    break;
+                }
+                Ok(Some(_)) => {}
+                Ok(None) => std::thread::sleep(Duration::from_millis(10)),
+                Err(_) => break,
+            }
+        }
+        conn.destroy_window(window).ok();
+        conn.flush().ok();
+        found
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod hints {
+    pub fn is_concealed() -> bool {
+        false
+    }
+}
+
+// ── Privacy filter ─────────────────────────────────────────────────────────
+/// Returns why a copy must be skipped, or `None` if it may be recorded.
+fn skip_reason(
+    cfg: &ClipboardConfig,
+    text: &str,
+    app: Option<&app_context::AppContext>,
+) -> Option<String> {
+    if cfg.honor_concealed && hints::is_concealed() {
+        return Some("concealed".into());
+    }
+    if let Some(app) = app {
+        if cfg.ignored_apps.iter().any(|p| app.matches(p)) {
+            return Some(format!("ignored_app:{}", app.name));
+        }
+    }
+    if cfg.skip_secrets {
+        if let Some(kind) = secrets::find_secret(text, &cfg.secret_patterns) {
+            return Some(format!("secret:{}", kind));
+        }
+    }
+    None
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// ── Watcher ────────────────────────────────────────────────────────────────
+pub fn start_watcher(app: &AppHandle) {
+    let handle = app.clone();
+    std::thread::spawn(move || {
+        let mut clipboard: Option<arboard::Clipboard> = None;
+        let mut last: Option<String> = None;
+        loop {
+            let cfg = config::current(&handle).clipboard;
+            let factor = performance::capabilities(&handle).poll_factor;
+            std::thread::sleep(Duration::from_millis(cfg.poll_ms.max(100) * factor));
+            if !cfg.enabled || paused() || app_lock::locked() {
+                last = None;
+                continue;
+            }
+            if clipboard.is_none() {
+                clipboard = arboard::Clipboard::new()
+                    .map_err(|e| log::warn!("Clipboard unavailable: {}", e))
+                    .ok();
+            }
+            let text = match clipboard.as_mut().map(|c| c.get_text()) {
+                Some(Ok(text)) => text,
+                _ => continue,
+            };
+            // First read after enabling only establishes the baseline.
+            let changed = last.as_deref().is_some_and(|prev| prev != text);
+            let first = last.is_none();
+            last = Some(text.clone());
+            if first || !changed || text.trim().is_empty() {
+                continue;
+            }
+            if SELF_WRITE.lock().unwrap().take().as_deref() == Some(text.as_str()) {
+                continue;
+            }
+
+            let app = app_context::frontmost();
+            if let Some(reason) = skip_reason(&cfg, &text, app.as_ref()) {
+                log::info!("Clipboard copy skipped ({})", reason);
+                handle.emit_all("clipboard-skipped", reason).ok();
+                continue;
+            }
+
+            let entry = ClipboardEntry {
+                text,
+                app: app.map(|a| a.name),
+                ts: now_millis(),
+            };
+            let mut history = HISTORY.lock().unwrap();
+            if history.len() == HISTORY_LIMIT {
+                history.pop_back();
+            }
+            history.push_front(entry.clone());
+            drop(history);
+            handle.emit_all("clipboard-changed", entry).ok();
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn get_clipboard_history() -> Vec<ClipboardEntry> {
+    HISTORY.lock().unwrap().iter().cloned().collect()
+}
+
+#[tauri::command]
+pub fn clear_clipboard_history() {
+    HISTORY.lock().unwrap().clear();
+}
+
+/// Reads an image (PNG/TIFF/DIB, decoded by arboard) off the clipboard,
+/// re-encodes it as PNG and stores it as an attachment. Returns its id (hash).
+#[tauri::command]
+pub async fn capture_clipboard_image() -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let image = arboard::Clipboard::new()
+            .and_then(|mut c| c.get_image())
+            .map_err(|e| match e {
+                arboard::Error::ContentNotAvailable => "No image on the clipboard".to_string(),
+                e => format!("Could not read clipboard image: {e}"),
+            })?;
+        let rgba = image::RgbaImage::from_raw(
+            image.width as u32,
+            image.height as u32,
+            image.bytes.into_owned(),
+        )
+        .ok_or("Clipboard image has an unexpected size")?;
+        let mut png = std::io::Cursor::new(Vec::new());
+        rgba.write_to(&mut png, image::ImageFormat::Png)
+            .map_err(|e| format!("Could not encode image: {e}"))?;
+        let attachment = attachments::store_bytes(png.get_ref(), "png", None)?;
+        log::info!("Stored clipboard image as {}", attachment.hash);
+        Ok(attachment.hash)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Copies a snippet as plain text, HTML and RTF at once, so Word/Mail keep
+/// formatting while terminals get plain text.
+#[tauri::command]
+pub async fn copy_rich(snippet_id: String) -> Result<(), String> {
+    let snippet = backend::get(&backend::snippet_path(&snippet_id)?).await?;
+    let body = snippet["body"].as_str().ok_or("Snippet has no body")?;
+    let rich = richtext::render(body, snippet["language"].as_str());
+    tauri::async_runtime::spawn_blocking(move || write_rich(&rich))
+        .await
+        .map_err(|e| e.to_string())?
+}
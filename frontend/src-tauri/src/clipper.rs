@@ -0,0 +1,747 @@
+// Web clipper — a page fetched by URL, reduced to its article, as a snippet.
+//
+// The webview can't read cross-origin pages, so the shell fetches them
+// (through the proxy, see proxy.rs). The page is parsed into a DOM and put
+// through a readability pass in the spirit of Arc90's: navigation, ads,
+// comments and other boilerplate are dropped, paragraphs score their parent
+// and grandparent blocks by length and commas, scores are weighed by class
+// names and link density, and the best block is kept along with siblings
+// that look like part of the same article. That block becomes Markdown;
+// its images are downloaded into the attachment store and linked as
+// attachments, so the clip survives the page. The snippet goes through
+// `capture::submit` with the page's URL as its source.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use base64::Engine;
+use kuchikiki::traits::*;
+use kuchikiki::NodeRef;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use reqwest::Url;
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::capture::{self, Capture};
+use crate::{attachments, proxy};
+
+const SOURCE: &str = "web";
+const TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_PAGE_BYTES: usize = 10 * 1024 * 1024;
+const MAX_IMAGE_BYTES: usize = 20 * 1024 * 1024;
+const MAX_IMAGES: usize = 50;
+/// Shorter paragraphs don't count towards a block's score.
+const MIN_PARAGRAPH: usize = 25;
+/// Some sites serve a bare page to unknown clients.
+const USER_AGENT: &str = "Mozilla/5.0 (compatible; Pin-Up AI web clipper)";
+
+/// Never part of an article.
+const DROP_TAGS: &[&str] = &[
+    "script", "style", "noscript", "template", "iframe", "object", "embed", "form", "button",
+    "input", "select", "textarea", "nav", "footer", "aside", "svg", "canvas", "dialog",
+];
+
+static UNLIKELY: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)banner|breadcrumb|combx|comment|community|cookie|disqus|extra|foot|header|legends|menu|modal|related|remark|replies|rss|share|shoutbox|sidebar|skyscraper|social|sponsor|subscribe|newsletter|popup|promo|ad-break|agegate|pagination|pager",
+    )
+    .unwrap()
+});
+static MAYBE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)and|article|body|column|content|main|shadow|story|entry|post").unwrap()
+});
+static POSITIVE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)article|body|content|entry|hentry|h-entry|main|page|post|text|blog|story")
+        .unwrap()
+});
+static NEGATIVE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)hidden|banner|combx|comment|contact|foot|footer|footnote|masthead|media|meta|outbrain|promo|related|scroll|share|shoutbox|sidebar|skyscraper|sponsor|shopping|tags|tool|widget",
+    )
+    .unwrap()
+});
+static WHITESPACE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s+").unwrap());
+static BLANK_LINES: Lazy<Regex> = Lazy::new(|| Regex::new(r"\n[ \t]*(\n[ \t]*){2,}").unwrap());
+static MD_IMAGE: Lazy<Regex> = Lazy::new(|| Regex::new(r"!\[([^\]]*)\]\(([^)\s]+)\)").unwrap());
+
+/// What the readability pass keeps of a page.
+pub struct Article {
+    pub title: Option<String>,
+    /// Images still point at the web until `store_images`.
+    pub markdown: String,
+}
+
+// ── Readability ────────────────────────────────────────────────────────────
+fn tag(node: &NodeRef) -> Option<String> {
+    node.as_element().map(|e| e.name.local.to_string())
+}
+
+pub fn attr(node: &NodeRef, name: &str) -> Option<String> {
+    let element = node.as_element()?;
+    let attributes = element.attributes.borrow();
+    attributes.get(name).map(str::to_string)
+}
+
+/// Class and id, where sites say what a block is for.
+fn class_and_id(node: &NodeRef) -> String {
+    format!(
+        "{} {}",
+        attr(node, "class").unwrap_or_default(),
+        attr(node, "id").unwrap_or_default()
+    )
+}
+
+fn text_len(node: &NodeRef) -> usize {
+    WHITESPACE
+        .replace_all(node.text_contents().trim(), " ")
+        .chars()
+        .count()
+}
+
+/// Share of a block's text that sits in links.
+fn link_density(node: &NodeRef) -> f64 {
+    let total = text_len(node);
+    if total == 0 {
+        return 0.0;
+    }
+    let links: usize = node
+        .select("a")
+        .map(|links| links.map(|a| text_len(a.as_node())).sum())
+        .unwrap_or(0);
+    links as f64 / total as f64
+}
+
+fn class_weight(node: &NodeRef) -> f64 {
+    let names = class_and_id(node);
+    let mut weight = 0.0;
+    if NEGATIVE.is_match(&names) {
+        weight -= 25.0;
+    }
+    if POSITIVE.is_match(&names) {
+        weight += 25.0;
+    }
+    weight
+}
+
+fn base_score(node: &NodeRef) -> f64 {
+    let by_tag = match tag(node).as_deref() {
+        Some("div" | "article" | "main" | "section") => 5.0,
+        Some("pre" | "td" | "blockquote") => 3.0,
+        Some("address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li") => -3.0,
+        Some("h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th") => -5.0,
+        _ => 0.0,
+    };
+    by_tag + class_weight(node)
+}
+
+/// Drops what can't be article text: scripts, forms, navigation, and blocks
+/// whose class or id says they're boilerplate.
+fn strip(root: &NodeRef) {
+    let doomed: Vec<NodeRef> = root
+        .descendants()
+        .filter(|node| {
+            if node.as_comment().is_some() {
+                return true;
+            }
+            let Some(tag) = tag(node) else {
+                return false;
+            };
+            if DROP_TAGS.contains(&tag.as_str()) {
+                return true;
+            }
+            if matches!(tag.as_str(), "html" | "body" | "article" | "main" | "a") {
+                return false;
+            }
+            let names = class_and_id(node);
+            let hidden = attr(node, "hidden").is_some()
+                || attr(node, "aria-hidden").as_deref() == Some("true");
+            hidden || (UNLIKELY.is_match(&names) && !MAYBE.is_match(&names))
+        })
+        .collect();
+    for node in doomed {
+        node.detach();
+    }
+}
+
+/// The block most likely to hold the article.
+fn best_block(body: &NodeRef) -> NodeRef {
+    let mut scores: HashMap<*const kuchikiki::Node, (NodeRef, f64)> = HashMap::new();
+    let paragraphs: Vec<NodeRef> = body
+        .descendants()
+        .filter(|n| matches!(tag(n).as_deref(), Some("p" | "pre" | "td" | "blockquote")))
+        .collect();
+    for paragraph in paragraphs {
+        let text = paragraph.text_contents();
+        let len = text.trim().chars().count();
+        if len < MIN_PARAGRAPH {
+            continue;
+        }
+        let score = 1.0 + text.matches(',').count() as f64 + (len as f64 / 100.0).min(3.0);
+        for (depth, ancestor) in paragraph.ancestors().take(2).enumerate() {
+            if ancestor.as_element().is_none() {
+                break;
+            }
+            let entry = scores
+                .entry(&*ancestor.0 as *const _)
+                .or_insert_with(|| (ancestor.clone(), base_score(&ancestor)));
+            entry.1 += if depth == 0 { score } else { score / 2.0 };
+        }
+    }
+    scores
+        .into_values()
+        .map(|(node, score)| {
+            let score = score * (1.0 - link_density(&node));
+            (node, score)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(node, _)| node)
+        .unwrap_or_else(|| body.clone())
+}
+
+/// The best block, and its siblings that read like more of the article.
+fn article_blocks(body: &NodeRef) -> Vec<NodeRef> {
+    let best = best_block(body);
+    let Some(parent) = best.parent().filter(|_| best != *body) else {
+        return vec![best];
+    };
+    let best_len = text_len(&best).max(1) as f64;
+    parent
+        .children()
+        .filter(|sibling| {
+            if *sibling == best {
+                return true;
+            }
+            match tag(sibling).as_deref() {
+                Some("p") => {
+                    let len = text_len(sibling);
+                    let density = link_density(sibling);
+                    (len > 80 && density < 0.25)
+                        || (len > 0 && density == 0.0 && sibling.text_contents().contains(". "))
+                }
+                Some(_) => {
+                    let names = class_and_id(sibling);
+                    !names.trim().is_empty()
+                        && names == class_and_id(&best)
+                        && text_len(sibling) as f64 > best_len * 0.2
+                }
+                None => false,
+            }
+        })
+        .collect()
+}
+
+pub fn meta(document: &NodeRef, selector: &str) -> Option<String> {
+    let node = document.select_first(selector).ok()?;
+    let content = attr(node.as_node(), "content")?;
+    let content = content.trim();
+    (!content.is_empty()).then(|| content.to_string())
+}
+
+pub fn title(document: &NodeRef) -> Option<String> {
+    meta(document, r#"meta[property="og:title"]"#)
+        .or_else(|| meta(document, r#"meta[name="twitter:title"]"#))
+        .or_else(|| {
+            let title = document.select_first("title").ok()?.text_contents();
+            let title = WHITESPACE.replace_all(title.trim(), " ").to_string();
+            (!title.is_empty()).then_some(title)
+        })
+}
+
+fn extract(html: &str, base: &Url) -> Article {
+    let document = kuchikiki::parse_html().one(html);
+    let title = title(&document);
+    let body = document
+        .select_first("body")
+        .map(|b| b.as_node().clone())
+        .unwrap_or_else(|_| document.clone());
+    strip(&body);
+
+    let mut markdown = Markdown::new(base);
+    for block in article_blocks(&body) {
+        markdown.node(&block);
+        markdown.block();
+    }
+    let mut text = BLANK_LINES
+        .replace_all(&markdown.out, "\n\n")
+        .trim()
+        .to_string();
+    // The page's heading usually repeats its title.
+    if let Some(title) = &title {
+        if let Some(rest) = text.strip_prefix(&format!("# {title}")) {
+            text = rest.trim_start().to_string();
+        }
+    }
+    Article {
+        title,
+        markdown: text,
+    }
+}
+
+// ── Markdown ───────────────────────────────────────────────────────────────
+struct Markdown<'a> {
+    base: &'a Url,
+    out: String,
+    /// Nesting of ordered (Some(next number)) and unordered lists.
+    lists: Vec<Option<usize>>,
+}
+
+impl<'a> Markdown<'a> {
+    fn new(base: &'a Url) -> Self {
+        Self {
+            base,
+            out: String::new(),
+            lists: Vec::new(),
+        }
+    }
+
+    fn url(&self, href: &str) -> Option<String> {
+        let url = self.base.join(href.trim()).ok()?;
+        // cid: points at another part of an email (see email_in.rs).
+        matches!(url.scheme(), "http" | "https" | "mailto" | "data" | "cid")
+            .then(|| url.to_string())
+    }
+
+    fn block(&mut self) {
+        let trimmed = self.out.trim_end_matches([' ', '\t']).len();
+        self.out.truncate(trimmed);
+        if !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push_str(if self.out.ends_with('\n') {
+                "\n"
+            } else {
+                "\n\n"
+            });
+        }
+    }
+
+    fn line(&mut self) {
+        let trimmed = self.out.trim_end_matches([' ', '\t']).len();
+        self.out.truncate(trimmed);
+        self.out.push('\n');
+        // Deep enough for the content of "- " and "1. " items alike.
+        self.out
+            .push_str(&"    ".repeat(self.lists.len().saturating_sub(1)));
+    }
+
+    fn text(&mut self, text: &str) {
+        let text = WHITESPACE.replace_all(text, " ");
+        if text == " " && (self.out.is_empty() || self.out.ends_with([' ', '\n'])) {
+            return;
+        }
+        let text = if self.out.ends_with([' ', '\n']) || self.out.is_empty() {
+            text.trim_start()
+        } else {
+            &text
+        };
+        self.out.push_str(&escape(text));
+    }
+
+    fn children(&mut self, node: &NodeRef) {
+        for child in node.children() {
+            self.node(&child);
+        }
+    }
+
+    /// The children as inline Markdown on their own, for link text and the like.
+    fn inline(&self, node: &NodeRef) -> String {
+        let mut inner = Markdown::new(self.base);
+        inner.children(node);
+        WHITESPACE.replace_all(inner.out.trim(), " ").to_string()
+    }
+
+    fn wrap(&mut self, node: &NodeRef, marker: &str) {
+        let inner = self.inline(node);
+        if !inner.is_empty() {
+            if !self.out.is_empty() && !self.out.ends_with([' ', '\n', '(', '[']) {
+                self.out.push(' ');
+            }
+            self.out.push_str(&format!("{marker}{inner}{marker}"));
+        }
+    }
+
+    fn node(&mut self, node: &NodeRef) {
+        if let Some(text) = node.as_text() {
+            self.text(&text.borrow());
+            return;
+        }
+        let Some(name) = tag(node) else {
+            self.children(node);
+            return;
+        };
+        match name.as_str() {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let text = self.inline(node);
+                if !text.is_empty() {
+                    self.block();
+                    let level = name[1..].parse().unwrap_or(1);
+                    self.out.push_str(&format!("{} {text}", "#".repeat(level)));
+                    self.block();
+                }
+            }
+            "p" | "div" | "section" | "article" | "main" | "header" | "figure" | "table" | "dl"
+            | "details" => {
+                self.block();
+                self.children(node);
+                self.block();
+            }
+            "br" => self.line(),
+            "hr" => {
+                self.block();
+                self.out.push_str("---");
+                self.block();
+            }
+            "strong" | "b" => self.wrap(node, "**"),
+            "em" | "i" => self.wrap(node, "*"),
+            "del" | "s" => self.wrap(node, "~~"),
+            "code" | "kbd" | "samp" => {
+                let code = node.text_contents();
+                if !code.trim().is_empty() {
+                    let fence = if code.contains('`') { "``" } else { "`" };
+                    self.out.push_str(&format!("{fence}{}{fence}", code.trim()));
+                }
+            }
+            "pre" => {
+                let code = node.text_contents();
+                let language = node
+                    .select_first("code")
+                    .ok()
+                    .and_then(|c| attr(c.as_node(), "class"))
+                    .and_then(|class| {
+                        class
+                            .split_whitespace()
+                            .find_map(|c| c.strip_prefix("language-").map(str::to_string))
+                    })
+                    .unwrap_or_default();
+                self.block();
+                self.out
+                    .push_str(&format!("```{language}\n{}\n```", code.trim_matches('\n')));
+                self.block();
+            }
+            "a" => {
+                let text = self.inline(node);
+                let href = attr(node, "href").and_then(|h| self.url(&h));
+                match href {
+                    Some(href) if !text.is_empty() && !href.starts_with("data:") => {
+                        if !self.out.is_empty() && !self.out.ends_with([' ', '\n', '(']) {
+                            self.out.push(' ');
+                        }
+                        self.out.push_str(&format!("[{text}]({href})"));
+                    }
+                    _ => self.out.push_str(&text),
+                }
+            }
+            "img" => {
+                let src = ["data-src", "data-original", "data-lazy-src", "src"]
+                    .iter()
+                    .find_map(|name| attr(node, name).filter(|s| !s.trim().is_empty()));
+                // Tracking pixels.
+                let tiny = ["width", "height"]
+                    .iter()
+                    .any(|name| attr(node, name).is_some_and(|v| v.trim() == "1"));
+                if let Some(url) = src.and_then(|s| self.url(&s)).filter(|_| !tiny) {
+                    let alt = attr(node, "alt").unwrap_or_default();
+                    let alt = WHITESPACE
+                        .replace_all(alt.trim(), " ")
+                        .replace(['[', ']'], "");
+                    self.block();
+                    self.out.push_str(&format!("![{alt}]({url})"));
+                    self.block();
+                }
+            }
+            "figcaption" => {
+                let text = self.inline(node);
+                if !text.is_empty() {
+                    self.block();
+                    self.out.push_str(&format!("*{text}*"));
+                    self.block();
+                }
+            }
+            "ul" | "ol" => {
+                let start = attr(node, "start")
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(1);
+                self.lists.push((name == "ol").then_some(start));
+                if self.lists.len() == 1 {
+                    self.block();
+                }
+                self.children(node);
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.block();
+                }
+            }
+            "li" => {
+                self.line();
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => "- ".to_string(),
+                };
+                self.out.push_str(&marker);
+                self.children(node);
+            }
+            "blockquote" => {
+                let mut inner = Markdown::new(self.base);
+                inner.children(node);
+                let text = BLANK_LINES.replace_all(inner.out.trim(), "\n\n");
+                if !text.is_empty() {
+                    self.block();
+                    let quoted: Vec<String> = text
+                        .lines()
+                        .map(|line| format!("> {line}").trim_end().to_string())
+                        .collect();
+                    self.out.push_str(&quoted.join("\n"));
+                    self.block();
+                }
+            }
+            "tr" => {
+                let cells: Vec<String> = node
+                    .children()
+                    .filter(|c| matches!(tag(c).as_deref(), Some("td" | "th")))
+                    .map(|c| self.inline(&c).replace('|', "\\|"))
+                    .collect();
+                if cells.is_empty() {
+                    return;
+                }
+                let first = !self.out.trim_end().ends_with('|');
+                self.line();
+                self.out.push_str(&format!("| {} |", cells.join(" | ")));
+                if first {
+                    self.line();
+                    self.out
+                        .push_str(&format!("|{}", " --- |".repeat(cells.len())));
+                }
+            }
+            _ => self.children(node),
+        }
+    }
+}
+
+/// Keeps page text from reading as Markdown syntax.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+// ── Fetching ───────────────────────────────────────────────────────────────
+fn client(app: &AppHandle) -> Result<reqwest::Client, String> {
+    proxy::client_builder(app)
+        .timeout(TIMEOUT)
+        .user_agent(USER_AGENT)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// The body of `url`, refusing anything over `limit` bytes.
+async fn fetch(
+    client: &reqwest::Client,
+    url: &str,
+    limit: usize,
+) -> Result<(Vec<u8>, String), String> {
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Could not fetch {url}: {e}"))?;
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_lowercase();
+    if response.content_length().unwrap_or(0) > limit as u64 {
+        return Err(format!("{url} is larger than {} MB", limit >> 20));
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Could not fetch {url}: {e}"))?
+    {
+        bytes.extend_from_slice(&chunk);
+        if bytes.len() > limit {
+            return Err(format!("{url} is larger than {} MB", limit >> 20));
+        }
+    }
+    Ok((bytes, content_type))
+}
+
+/// Decodes the page in the charset its headers or <meta> name.
+pub fn decode(bytes: &[u8], content_type: &str) -> String {
+    static META_CHARSET: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r#"(?i)<meta[^>]+charset=["']?([\w-]+)"#).unwrap());
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(4096)]);
+    let label = content_type
+        .split(';')
+        .find_map(|part| part.trim().strip_prefix("charset="))
+        .map(|c| c.trim_matches('"').to_string())
+        .or_else(|| META_CHARSET.captures(&head).map(|c| c[1].to_string()));
+    let encoding = label
+        .and_then(|l| encoding_rs::Encoding::for_label(l.as_bytes()))
+        .unwrap_or(encoding_rs::UTF_8);
+    encoding.decode(bytes).0.into_owned()
+}
+
+fn image_extension(url: &str, content_type: &str) -> String {
+    let from_type = content_type
+        .split(';')
+        .next()
+        .and_then(|t| t.trim().strip_prefix("image/"))
+        .map(|sub| match sub {
+            "jpeg" => "jpg",
+            "svg+xml" => "svg",
+            sub => sub,
+        });
+    let from_url = url
+        .split(['?', '#'])
+        .next()
+        .and_then(|path| path.rsplit_once('.'))
+        .map(|(_, ext)| ext)
+        .filter(|ext| ext.len() <= 5 && ext.chars().all(|c| c.is_ascii_alphanumeric()));
+    from_type.or(from_url).unwrap_or("img").to_lowercase()
+}
+
+/// Bytes and content type of a data: URL.
+fn data_url(url: &str) -> Option<(Vec<u8>, String)> {
+    let (header, data) = url.strip_prefix("data:")?.split_once(',')?;
+    let content_type = header.strip_suffix(";base64")?;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .ok()?;
+    Some((bytes, content_type.to_string()))
+}
+
+async fn store_image(client: &reqwest::Client, url: &str) -> Result<String, String> {
+    let (bytes, content_type) = match data_url(url) {
+        Some(found) => found,
+        None => fetch(client, url, MAX_IMAGE_BYTES).await?,
+    };
+    if !content_type.is_empty() && !content_type.starts_with("image/") {
+        return Err(format!("{url} is not an image"));
+    }
+    let ext = image_extension(url, &content_type);
+    let attachment =
+        tauri::async_runtime::spawn_blocking(move || attachments::store_bytes(&bytes, &ext, None))
+            .await
+            .map_err(|e| e.to_string())??;
+    Ok(attachment.hash)
+}
+
+/// Points the article's images at attachments; images that can't be
+/// downloaded keep their web address.
+async fn store_images(client: &reqwest::Client, markdown: &str) -> String {
+    let urls: Vec<String> = MD_IMAGE
+        .captures_iter(markdown)
+        .map(|c| c[2].to_string())
+        .filter(|u| u.starts_with("http") || u.starts_with("data:"))
+        .take(MAX_IMAGES)
+        .collect();
+    let mut stored = HashMap::new();
+    for url in urls {
+        if stored.contains_key(&url) {
+            continue;
+        }
+        match store_image(client, &url).await {
+            Ok(hash) => {
+                stored.insert(url, hash);
+            }
+            Err(e) => log::warn!("Web clipper: {}", e),
+        }
+    }
+    MD_IMAGE
+        .replace_all(markdown, |caps: &Captures| match stored.get(&caps[2]) {
+            Some(hash) => format!("![{}](attachment:{hash})", &caps[1]),
+            // Inline data that didn't store is too bulky to keep.
+            None if caps[2].starts_with("data:") => String::new(),
+            None => caps[0].to_string(),
+        })
+        .into_owned()
+}
+
+/// Fetches `url` and returns its article, images stored as attachments.
+pub async fn clip(app: &AppHandle, url: &Url) -> Result<Article, String> {
+    let client = client(app)?;
+    let (bytes, content_type) = fetch(&client, url.as_str(), MAX_PAGE_BYTES).await?;
+    if !content_type.is_empty() && !content_type.contains("html") {
+        return Err(format!("Not a web page ({content_type})"));
+    }
+    clip_html(app, &decode(&bytes, &content_type), url).await
+}
+
+/// The article in `html`, a page already loaded (by a browser extension,
+/// say) from `url`; images are stored as attachments.
+pub async fn clip_html(app: &AppHandle, html: &str, url: &Url) -> Result<Article, String> {
+    let mut article = extract(html, url);
+    if article.markdown.trim().is_empty() {
+        return Err("Found no article text on that page".into());
+    }
+    article.markdown = store_images(&client(app)?, &article.markdown).await;
+    Ok(article)
+}
+
+/// All of `html` as Markdown, without the readability pass.
+pub fn convert(html: &str, base: &Url) -> String {
+    let document = kuchikiki::parse_html().one(html);
+    let body = document
+        .select_first("body")
+        .map(|b| b.as_node().clone())
+        .unwrap_or_else(|_| document.clone());
+    strip(&body);
+    let mut markdown = Markdown::new(base);
+    markdown.node(&body);
+    BLANK_LINES
+        .replace_all(&markdown.out, "\n\n")
+        .trim()
+        .to_string()
+}
+
+/// An HTML fragment (a feed entry's content, say) as Markdown, images
+/// stored as attachments.
+pub async fn fragment(app: &AppHandle, html: &str, base: &Url) -> Result<String, String> {
+    let text = convert(html, base);
+    Ok(store_images(&client(app)?, &text).await)
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Clips the article at `url` into a new snippet and returns it.
+#[tauri::command]
+pub async fn clip_url(app: AppHandle, url: String) -> Result<Value, String> {
+    let url = url.trim();
+    let url = if url.contains("://") {
+        url.to_string()
+    } else {
+        format!("https://{url}")
+    };
+    let parsed = Url::parse(&url).map_err(|e| format!("Invalid URL: {e}"))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("Only web pages can be clipped".into());
+    }
+
+    let article = clip(&app, &parsed).await?;
+    log::info!(
+        "Web clipper: clipped {} ({} chars)",
+        parsed,
+        article.markdown.len()
+    );
+
+    capture::submit(
+        &app,
+        Capture {
+            title: article.title,
+            body: article.markdown,
+            source: SOURCE.into(),
+            source_url: Some(parsed.to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+}
@@ -0,0 +1,549 @@
+// Mobile companion — captures sent from a paired phone over the LAN.
+//
+// While on, the app listens on a fixed TCP port (so a phone finds it again
+// after a restart) with the framing and sealing LAN sync uses (see
+// lan_sync.rs). Pairing: the app shows a QR code (see qr.rs) holding its
+// address, a pairing id and a fresh 256-bit secret, valid for five minutes.
+// The phone scans it and asks to pair; the app answers with an invitation
+// sealed with the secret, holding an id and a key for the phone, and the
+// phone proves it opened it by replying under that key. The secret never
+// crosses the network, and only one phone can pair per code.
+//
+// A paired phone can do one thing: send a capture. Every request is a new
+// connection: a plain hello naming the phone, a random challenge, then the
+// capture and the answer sealed with its key and bound to the challenge. The
+// capture goes through `capture::submit` like any other, so the pipeline and
+// routing apply and it is queued while the backend is down. Removing a phone
+// forgets its key.
+
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::codec::LengthDelimitedCodec;
+
+use crate::capture::{self, Capture};
+use crate::lan_sync::{self, open_frame, random, recv_bytes, seal_frame, send_bytes, Connection};
+use crate::qr::QrCode;
+use crate::sync_log::blocking;
+use crate::{config, keyring, mdns};
+
+const ENABLE_CHECK: Duration = Duration::from_secs(5);
+const RETRY_AFTER: Duration = Duration::from_secs(60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const PAIRING_WINDOW: Duration = Duration::from_secs(5 * 60);
+const PAIRING_ATTEMPTS: u32 = 5;
+/// Largest frame: a long note with some room to spare.
+const MAX_FRAME: usize = 4 * 1024 * 1024;
+const SOURCE: &str = "companion";
+
+const B64: base64::engine::GeneralPurpose = base64::engine::general_purpose::STANDARD;
+const B64_URL: base64::engine::GeneralPurpose = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct CompanionConfig {
+    pub enabled: bool,
+    pub port: u16,
+    pub devices: Vec<CompanionDevice>,
+}
+
+impl Default for CompanionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 47615,
+            devices: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CompanionDevice {
+    pub device: String,
+    pub name: String,
+    /// Unix ms.
+    pub paired_at: i64,
+}
+
+struct Pairing {
+    id: String,
+    secret: [u8; 32],
+    until: Instant,
+    /// Unix ms, for the UI.
+    expires_at: i64,
+    attempts: u32,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PairingCode {
+    /// What the QR code holds, for phones that can't scan.
+    link: String,
+    qr_svg: String,
+    /// Unix ms.
+    expires_at: i64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DeviceStatus {
+    #[serde(flatten)]
+    device: CompanionDevice,
+    /// RFC 3339.
+    last_capture: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CompanionStatus {
+    enabled: bool,
+    port: u16,
+    /// Where phones reach this device, while listening.
+    address: Option<String>,
+    listening: bool,
+    pairing: Option<PairingCode>,
+    devices: Vec<DeviceStatus>,
+    /// Why the listener isn't running.
+    network_error: Option<String>,
+}
+
+static LISTENING: AtomicBool = AtomicBool::new(false);
+static PAIRING: Mutex<Option<Pairing>> = Mutex::new(None);
+static LAST_CAPTURE: Lazy<Mutex<HashMap<String, String>>> = Lazy::new(Default::default);
+static NETWORK_ERROR: Mutex<Option<String>> = Mutex::new(None);
+/// Keyring reads are slow on some platforms; every capture needs the key.
+static KEYS: Lazy<Mutex<HashMap<String, [u8; 32]>>> = Lazy::new(Default::default);
+
+// ── Keys ───────────────────────────────────────────────────────────────────
+fn account(device: &str) -> String {
+    format!("companion:{device}")
+}
+
+async fn device_key(device: &str) -> Result<Option<[u8; 32]>, String> {
+    if let Some(key) = KEYS.lock().unwrap().get(device) {
+        return Ok(Some(*key));
+    }
+    let account = account(device);
+    let Some(text) = blocking(move || keyring::get(&account)).await? else {
+        return Ok(None);
+    };
+    let key: [u8; 32] = hex::decode(text.trim())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or("Damaged companion key; pair the phone again")?;
+    KEYS.lock().unwrap().insert(device.to_string(), key);
+    Ok(Some(key))
+}
+
+async fn save_device_key(device: &str, key: [u8; 32]) -> Result<(), String> {
+    let account = account(device);
+    blocking(move || keyring::set(&account, &hex::encode(key))).await?;
+    KEYS.lock().unwrap().insert(device.to_string(), key);
+    Ok(())
+}
+
+async fn forget_device_key(device: &str) -> Result<(), String> {
+    KEYS.lock().unwrap().remove(device);
+    let account = account(device);
+    blocking(move || keyring::delete(&account)).await
+}
+
+// ── Wire protocol ──────────────────────────────────────────────────────────
+/// Unsealed messages.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Plain {
+    Pair {
+        pairing: String,
+        name: String,
+    },
+    Hello {
+        device: String,
+    },
+    /// `Invitation` sealed with the pairing secret, base64.
+    Invitation {
+        sealed: String,
+    },
+    Challenge {
+        nonce: String,
+    },
+    Paired,
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct Invitation {
+    /// The phone's id from now on.
+    device: String,
+    /// Hex.
+    key: String,
+    /// This computer, for the phone to show.
+    name: String,
+}
+
+/// Everything a phone can ask for.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum Op {
+    Capture {
+        #[serde(default)]
+        title: Option<String>,
+        body: String,
+        #[serde(default)]
+        source_url: Option<String>,
+        #[serde(default)]
+        tags: Vec<String>,
+    },
+    /// Ends a pairing: the invitation was opened.
+    Confirm,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Request {
+    nonce: String,
+    op: Op,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Response {
+    nonce: String,
+    /// The new snippet's id.
+    result: Result<Option<String>, String>,
+}
+
+async fn send_plain(conn: &mut Connection, message: &Plain) -> Result<(), String> {
+    let bytes = serde_json::to_vec(message).map_err(|e| e.to_string())?;
+    send_bytes(conn, bytes).await
+}
+
+async fn recv_plain(conn: &mut Connection) -> Result<Plain, String> {
+    let bytes = recv_bytes(conn).await?;
+    serde_json::from_slice(&bytes).map_err(|e| format!("Unexpected message: {e}"))
+}
+
+async fn refuse(conn: &mut Connection, message: &str) -> Result<(), String> {
+    let error = Plain::Error {
+        message: message.into(),
+    };
+    send_plain(conn, &error).await.ok();
+    Err(message.into())
+}
+
+// ── Server ─────────────────────────────────────────────────────────────────
+async fn serve(app: AppHandle, listener: TcpListener) {
+    loop {
+        let Ok((stream, _)) = listener.accept().await else {
+            continue;
+        };
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let result = tokio::time::timeout(REQUEST_TIMEOUT, handle(&app, stream))
+                .await
+                .unwrap_or_else(|_| Err("timed out".into()));
+            if let Err(e) = result {
+                log::debug!("Companion connection: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle(app: &AppHandle, stream: TcpStream) -> Result<(), String> {
+    let mut conn = LengthDelimitedCodec::builder()
+        .max_frame_length(MAX_FRAME)
+        .new_framed(stream);
+    match recv_plain(&mut conn).await? {
+        Plain::Pair { pairing, name } => accept_pairing(app, &mut conn, &pairing, name).await,
+        Plain::Hello { device } => answer(app, &mut conn, &device).await,
+        _ => Err("Unexpected message".into()),
+    }
+}
+
+/// One capture from a paired phone.
+async fn answer(app: &AppHandle, conn: &mut Connection, device: &str) -> Result<(), String> {
+    let paired = config::current(app)
+        .companion
+        .devices
+        .iter()
+        .any(|d| d.device == device);
+    let key = if paired {
+        device_key(device).await?
+    } else {
+        None
+    };
+    let Some(key) = key else {
+        return refuse(conn, "This phone isn't paired; scan the code again").await;
+    };
+    let nonce = hex::encode(random::<16>()?);
+    send_plain(
+        conn,
+        &Plain::Challenge {
+            nonce: nonce.clone(),
+        },
+    )
+    .await?;
+    let request: Request = open_frame(&key, &recv_bytes(conn).await?)?;
+    if request.nonce != nonce {
+        return Err("Stale request".into());
+    }
+    let result = match request.op {
+        Op::Capture {
+            title,
+            body,
+            source_url,
+            tags,
+        } => {
+            let capture = Capture {
+                title: title.filter(|t| !t.trim().is_empty()),
+                body,
+                source: SOURCE.into(),
+                source_url,
+                tags,
+                collections: Vec::new(),
+            };
+            capture::submit(app, capture)
+                .await
+                .map(|snippet| snippet["id"].as_str().map(str::to_string))
+        }
+        Op::Confirm => Err("Unexpected request".into()),
+    };
+    if result.is_ok() {
+        LAST_CAPTURE
+            .lock()
+            .unwrap()
+            .insert(device.to_string(), chrono::Utc::now().to_rfc3339());
+    }
+    send_bytes(conn, seal_frame(&key, &Response { nonce, result })?).await
+}
+
+/// Hands a phone that scanned the code its id and key.
+async fn accept_pairing(
+    app: &AppHandle,
+    conn: &mut Connection,
+    pairing: &str,
+    name: String,
+) -> Result<(), String> {
+    let secret = match PAIRING.lock().unwrap().as_mut() {
+        Some(p) if p.id == pairing && p.until > Instant::now() && p.attempts < PAIRING_ATTEMPTS => {
+            p.attempts += 1;
+            Some(p.secret)
+        }
+        _ => None,
+    };
+    let Some(secret) = secret else {
+        return refuse(conn, "That code has expired; show a new one").await;
+    };
+    let device = hex::encode(random::<6>()?);
+    let key = random::<32>()?;
+    let invitation = Invitation {
+        device: device.clone(),
+        key: hex::encode(key),
+        name: lan_sync::device_name(&config::current(app).lan_sync),
+    };
+    send_plain(
+        conn,
+        &Plain::Invitation {
+            sealed: B64.encode(seal_frame(&secret, &invitation)?),
+        },
+    )
+    .await?;
+
+    // Only the phone that scanned the code can seal this.
+    let confirm: Request = open_frame(&key, &recv_bytes(conn).await?)?;
+    if !matches!(confirm.op, Op::Confirm) {
+        return Err("Unexpected request".into());
+    }
+    save_device_key(&device, key).await?;
+    let name = name.trim();
+    let paired = CompanionDevice {
+        device,
+        name: if name.is_empty() { "Phone" } else { name }.to_string(),
+        paired_at: chrono::Utc::now().timestamp_millis(),
+    };
+    config::update(app, |c| c.companion.devices.push(paired.clone()))?;
+    *PAIRING.lock().unwrap() = None;
+    send_plain(conn, &Plain::Paired).await?;
+    log::info!("Companion: paired with {} ({})", paired.name, paired.device);
+    app.emit_all("companion-paired", &paired).ok();
+    Ok(())
+}
+
+/// Serves until the companion is turned off or its port changes.
+async fn listen(app: &AppHandle, port: u16) -> Result<(), String> {
+    let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, port))
+        .await
+        .map_err(|e| format!("Could not listen on port {port}: {e}"))?;
+    let server = tauri::async_runtime::spawn(serve(app.clone(), listener));
+    LISTENING.store(true, Ordering::SeqCst);
+    *NETWORK_ERROR.lock().unwrap() = None;
+    log::info!("Companion: listening on port {}", port);
+
+    let mut ticks = tokio::time::interval(ENABLE_CHECK);
+    loop {
+        ticks.tick().await;
+        let cfg = config::current(app).companion;
+        if !cfg.enabled || cfg.port != port {
+            break;
+        }
+    }
+    server.abort();
+    LISTENING.store(false, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Runs `listen` whenever the companion is on.
+async fn network(app: AppHandle) {
+    loop {
+        tokio::time::sleep(ENABLE_CHECK).await;
+        let cfg = config::current(&app).companion;
+        if !cfg.enabled {
+            continue;
+        }
+        if let Err(e) = listen(&app, cfg.port).await {
+            log::warn!("Companion: {}", e);
+            *NETWORK_ERROR.lock().unwrap() = Some(e);
+            tokio::time::sleep(RETRY_AFTER).await;
+        }
+    }
+}
+
+fn address(port: u16) -> Option<String> {
+    mdns::local_ipv4().map(|ip| format!("{ip}:{port}"))
+}
+
+/// The link a phone scans to pair.
+fn pairing_link(app: &AppHandle, pairing: &Pairing, address: &str) -> Result<String, String> {
+    let name = lan_sync::device_name(&config::current(app).lan_sync);
+    reqwest::Url::parse_with_params(
+        "pinup://pair",
+        [
+            ("v", "1"),
+            ("host", address),
+            ("pairing", &pairing.id),
+            ("secret", &B64_URL.encode(pairing.secret)),
+            ("name", &name),
+        ],
+    )
+    .map(String::from)
+    .map_err(|e| e.to_string())
+}
+
+fn status(app: &AppHandle) -> Result<CompanionStatus, String> {
+    let cfg = config::current(app).companion;
+    let listening = LISTENING.load(Ordering::SeqCst);
+    let address = address(cfg.port).filter(|_| listening);
+    let pairing = match (PAIRING.lock().unwrap().as_ref(), &address) {
+        (Some(p), Some(address)) if p.until > Instant::now() && p.attempts < PAIRING_ATTEMPTS => {
+            let link = pairing_link(app, p, address)?;
+            Some(PairingCode {
+                qr_svg: QrCode::encode(&link)?.to_svg(),
+                link,
+                expires_at: p.expires_at,
+            })
+        }
+        _ => None,
+    };
+    let last = LAST_CAPTURE.lock().unwrap();
+    let devices = cfg
+        .devices
+        .iter()
+        .map(|device| DeviceStatus {
+            device: device.clone(),
+            last_capture: last.get(&device.device).cloned(),
+        })
+        .collect();
+    Ok(CompanionStatus {
+        enabled: cfg.enabled,
+        port: cfg.port,
+        address,
+        listening,
+        pairing,
+        devices,
+        network_error: NETWORK_ERROR.lock().unwrap().clone(),
+    })
+}
+
+/// Listens for paired phones while the companion is on.
+pub fn start(app: &AppHandle) {
+    tauri::async_runtime::spawn(network(app.clone()));
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn get_companion_status(app: AppHandle) -> Result<CompanionStatus, String> {
+    status(&app)
+}
+
+/// Turns the companion listener on or off, optionally on another port.
+#[tauri::command]
+pub fn set_companion(
+    app: AppHandle,
+    enabled: bool,
+    port: Option<u16>,
+) -> Result<CompanionStatus, String> {
+    if port.is_some_and(|p| p < 1024) {
+        return Err("Use a port from 1024 up".into());
+    }
+    config::update(&app, |c| {
+        c.companion.enabled = enabled;
+        if let Some(port) = port {
+            c.companion.port = port;
+        }
+    })?;
+    if !enabled {
+        *PAIRING.lock().unwrap() = None;
+    }
+    status(&app)
+}
+
+/// Shows a QR code a phone can pair with, for five minutes.
+#[tauri::command]
+pub fn start_companion_pairing(app: AppHandle) -> Result<CompanionStatus, String> {
+    if !config::current(&app).companion.enabled {
+        return Err("Turn on the mobile companion first".into());
+    }
+    if !LISTENING.load(Ordering::SeqCst) {
+        return Err(NETWORK_ERROR
+            .lock()
+            .unwrap()
+            .clone()
+            .unwrap_or_else(|| "Still starting; try again in a few seconds".into()));
+    }
+    *PAIRING.lock().unwrap() = Some(Pairing {
+        id: hex::encode(random::<8>()?),
+        secret: random()?,
+        until: Instant::now() + PAIRING_WINDOW,
+        expires_at: chrono::Utc::now().timestamp_millis() + PAIRING_WINDOW.as_millis() as i64,
+        attempts: 0,
+    });
+    let status = status(&app)?;
+    if status.pairing.is_none() {
+        *PAIRING.lock().unwrap() = None;
+        return Err("This computer has no network address phones can reach".into());
+    }
+    Ok(status)
+}
+
+#[tauri::command]
+pub fn cancel_companion_pairing(app: AppHandle) -> Result<CompanionStatus, String> {
+    *PAIRING.lock().unwrap() = None;
+    status(&app)
+}
+
+/// Unpairs a phone; it can no longer send captures.
+#[tauri::command]
+pub async fn remove_companion_device(
+    app: AppHandle,
+    device: String,
+) -> Result<CompanionStatus, String> {
+    config::update(&app, |c| c.companion.devices.retain(|d| d.device != device))?;
+    forget_device_key(&device).await?;
+    LAST_CAPTURE.lock().unwrap().remove(&device);
+    status(&app)
+}
@@ -0,0 +1,195 @@
+// Shell config — settings owned by the Rust layer.
+//
+// Persisted as JSON at data_dir()/shell-config.json, separate from the
+// backend's settings table. Each subsystem owns its own section struct; this
+// module only handles loading, merging partial updates from the frontend, and
+// saving. Sections in PROTECTED can't be patched from the frontend; their
+// own commands (set_app_lock, enable_database_encryption, set_deep_links)
+// check a change before making it.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use crate::app_lock::AppLockConfig;
+use crate::backend::BackendConfig;
+use crate::backups::BackupConfig;
+use crate::clipboard::ClipboardConfig;
+use crate::companion::CompanionConfig;
+use crate::dedupe::DedupeConfig;
+use crate::deep_link::DeepLinksConfig;
+use crate::email_in::EmailInConfig;
+use crate::encryption::EncryptionConfig;
+use crate::feeds::FeedsConfig;
+use crate::git_sync::GitSyncConfig;
+use crate::history::HistoryConfig;
+use crate::i18n::I18nConfig;
+use crate::lan_sync::LanSyncConfig;
+use crate::logs::LoggingConfig;
+use crate::meeting::MeetingConfig;
+use crate::native_host::NativeHostConfig;
+use crate::network::NetworkConfig;
+use crate::notifications::NotificationsConfig;
+use crate::obsidian::ObsidianConfig;
+use crate::ocr::OcrConfig;
+use crate::ollama::OllamaConfig;
+use crate::performance::PerformanceConfig;
+use crate::pipeline::PipelineConfig;
+use crate::power::PowerConfig;
+use crate::proxy::ProxyConfig;
+use crate::resources::ResourcesConfig;
+use crate::routing::RoutingConfig;
+use crate::s3::S3Config;
+use crate::selftest::SelfTestConfig;
+use crate::send_to::SendToConfig;
+use crate::telemetry::TelemetryConfig;
+use crate::tray::TrayConfig;
+use crate::updater::UpdaterConfig;
+use crate::watch_folders::WatchFoldersConfig;
+use crate::webdav::WebDavConfig;
+use crate::whisper::WhisperConfig;
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct ShellConfig {
+    pub backend: BackendConfig,
+    pub clipboard: ClipboardConfig,
+    pub meeting: MeetingConfig,
+    pub history: HistoryConfig,
+    pub pipeline: PipelineConfig,
+    pub routing: RoutingConfig,
+    pub ocr: OcrConfig,
+    pub performance: PerformanceConfig,
+    pub selftest: SelfTestConfig,
+    pub whisper: WhisperConfig,
+    pub ollama: OllamaConfig,
+    pub resources: ResourcesConfig,
+    pub power: PowerConfig,
+    pub network: NetworkConfig,
+    pub proxy: ProxyConfig,
+    pub updater: UpdaterConfig,
+    pub logging: LoggingConfig,
+    pub telemetry: TelemetryConfig,
+    pub backups: BackupConfig,
+    pub encryption: EncryptionConfig,
+    pub obsidian: ObsidianConfig,
+    pub dedupe: DedupeConfig,
+    pub watch_folders: WatchFoldersConfig,
+    pub git_sync: GitSyncConfig,
+    pub webdav: WebDavConfig,
+    pub s3: S3Config,
+    pub lan_sync: LanSyncConfig,
+    pub companion: CompanionConfig,
+    pub feeds: FeedsConfig,
+    pub email_in: EmailInConfig,
+    pub native_host: NativeHostConfig,
+    pub deep_links: DeepLinksConfig,
+    pub send_to: SendToConfig,
+    pub tray: TrayConfig,
+    pub notifications: NotificationsConfig,
+    pub app_lock: AppLockConfig,
+    pub i18n: I18nConfig,
+}
+
+pub struct ConfigState(pub Mutex<ShellConfig>);
+
+fn config_path() -> PathBuf {
+    crate::data_dir().join("shell-config.json")
+}
+
+pub fn load() -> ShellConfig {
+    match std::fs::read_to_string(config_path()) {
+        Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+            log::warn!("Ignoring unreadable shell config: {}", e);
+            ShellConfig::default()
+        }),
+        Err(_) => ShellConfig::default(),
+    }
+}
+
+fn save(config: &ShellConfig) -> Result<(), String> {
+    let path = config_path();
+    std::fs::create_dir_all(path.parent().unwrap()).ok();
+    let text = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    std::fs::write(&path, text).map_err(|e| format!("Could not save config: {e}"))
+}
+
+/// Snapshot of the current config.
+pub fn current(app: &AppHandle) -> ShellConfig {
+    app.state::<ConfigState>().0.lock().unwrap().clone()
+}
+
+/// Applies `change` to the config, saves it and notifies the frontend.
+pub fn update(
+    app: &AppHandle,
+    change: impl FnOnce(&mut ShellConfig),
+) -> Result<ShellConfig, String> {
+    let state = app.state::<ConfigState>();
+    let mut config = state.0.lock().unwrap();
+    let mut updated = config.clone();
+    change(&mut updated);
+    save(&updated)?;
+    *config = updated.clone();
+    drop(config);
+    app.emit_all("config-changed", &updated).ok();
+    Ok(updated)
+}
+
+/// Settings only their own commands may change, as "section.key" paths.
+const PROTECTED: &[&str] = &["app_lock", "encryption", "deep_links.callback_schemes"];
+
+/// Whether `patch` sets anything under `path`.
+fn touches(patch: &Value, path: &str) -> bool {
+    let mut value = patch;
+    for key in path.split('.') {
+        match value.get(key) {
+            Some(inner) => value = inner,
+            None => return false,
+        }
+    }
+    true
+}
+
+fn merge(base: &mut Value, patch: Value) {
+    match (base, patch) {
+        (Value::Object(base), Value::Object(patch)) => {
+            for (key, value) in patch {
+                merge(base.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (base, patch) => *base = patch,
+    }
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn get_shell_config(state: tauri::State<'_, ConfigState>) -> ShellConfig {
+    state.0.lock().unwrap().clone()
+}
+
+/// Deep-merges `patch` into the config, e.g. `{ "clipboard": { "enabled": true } }`.
+/// Patches to PROTECTED settings are refused.
+#[tauri::command]
+pub fn update_shell_config(
+    app: AppHandle,
+    state: tauri::State<'_, ConfigState>,
+    patch: Value,
+) -> Result<ShellConfig, String> {
+    if let Some(path) = PROTECTED.iter().find(|path| touches(&patch, path)) {
+        log::warn!("Config: refused a patch to {}", path);
+        return Err(format!("{path} can't be changed here"));
+    }
+    let mut config = state.0.lock().unwrap();
+    let mut value = serde_json::to_value(&*config).map_err(|e| e.to_string())?;
+    merge(&mut value, patch);
+    let updated: ShellConfig =
+        serde_json::from_value(value).map_err(|e| format!("Invalid config: {e}"))?;
+    save(&updated)?;
+    *config = updated.clone();
+    drop(config);
+    app.emit_all("config-changed", &updated).ok();
+    Ok(updated)
+}
@@ -0,0 +1,238 @@
+// Sync conflicts — snippets two devices changed without seeing each other's
+// change.
+//
+// Every sync backend still settles a snippet straight away, so a sync never
+// stalls: the sync log (WebDAV, S3, LAN) keeps the newer edit and Git keeps
+// the local side of a merge. Before that they check whether the two versions
+// diverged, each changed since the version both last had (the base a change
+// set records for each snippet, the merge base in Git). When they did, both
+// versions are kept here until the user resolves the conflict: keep one,
+// keep both (the other as a copy), or save a merged version. Resolving
+// writes to the library like any edit, so the next sync sends it everywhere.
+// One conflict is kept per snippet; a newer one replaces it.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{backend, encryption, exports};
+
+const FILE: &str = "sync-conflicts.json";
+
+/// Serializes read-modify-write of FILE between backends syncing at once.
+static LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Side {
+    Local,
+    Remote,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Conflict {
+    pub id: String,
+    pub snippet_id: String,
+    pub title: String,
+    /// "Git", "WebDAV", "S3", "LAN".
+    pub source: String,
+    /// Unix ms.
+    pub detected_at: i64,
+    /// This device's version, shaped like `exports::snippets_with_names`;
+    /// None when it was deleted here.
+    pub local: Option<Value>,
+    /// The other device's version; None when it was deleted there.
+    pub remote: Option<Value>,
+    /// The version the sync left in the library.
+    pub kept: Side,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Strategy {
+    KeepLocal,
+    KeepRemote,
+    /// The kept version stays; the other is added as a copy.
+    KeepBoth,
+    /// Saves the `merged` version passed along.
+    Merge,
+    /// Leaves the library as it is.
+    Dismiss,
+}
+
+impl Conflict {
+    pub fn new(
+        source: &str,
+        snippet_id: &str,
+        local: Option<Value>,
+        remote: Option<Value>,
+        kept: Side,
+    ) -> Self {
+        let detected_at = chrono::Utc::now().timestamp_millis();
+        let title = [&local, &remote]
+            .into_iter()
+            .flatten()
+            .find_map(|v| v["title"].as_str().filter(|t| !t.is_empty()))
+            .unwrap_or("Untitled")
+            .to_string();
+        Self {
+            id: format!("{snippet_id}@{detected_at}"),
+            snippet_id: snippet_id.to_string(),
+            title,
+            source: source.to_string(),
+            detected_at,
+            local,
+            remote,
+            kept,
+        }
+    }
+}
+
+fn load() -> Vec<Conflict> {
+    encryption::read_store(&crate::data_dir().join(FILE))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save(conflicts: &[Conflict]) -> Result<(), String> {
+    let path = crate::data_dir().join(FILE);
+    let text = serde_json::to_string(conflicts).map_err(|e| e.to_string())?;
+    encryption::write_store(&path, &text)
+}
+
+/// Keeps `found` for the user, replacing older conflicts on the same
+/// snippets.
+pub fn record(found: Vec<Conflict>) -> Result<(), String> {
+    if found.is_empty() {
+        return Ok(());
+    }
+    let _guard = LOCK.lock().unwrap();
+    let mut conflicts = load();
+    for conflict in found {
+        log::info!(
+            "{} sync: conflicting edits of {} kept for review",
+            conflict.source,
+            conflict.snippet_id
+        );
+        conflicts.retain(|c| c.snippet_id != conflict.snippet_id);
+        conflicts.push(conflict);
+    }
+    save(&conflicts)
+}
+
+/// Drops conflicts from `source` on snippets another device has since edited
+/// on top of this device's version: it saw both sides.
+pub fn settle(source: &str, snippet_ids: &[String]) -> Result<(), String> {
+    if snippet_ids.is_empty() {
+        return Ok(());
+    }
+    let _guard = LOCK.lock().unwrap();
+    let mut conflicts = load();
+    let count = conflicts.len();
+    conflicts.retain(|c| !(c.source == source && snippet_ids.contains(&c.snippet_id)));
+    if conflicts.len() == count {
+        return Ok(());
+    }
+    save(&conflicts)
+}
+
+/// Makes the library's copy of `snippet_id` match `version`; None deletes it.
+async fn write(snippet_id: &str, version: Option<&Value>) -> Result<(), String> {
+    let path = format!("/snippets/{snippet_id}");
+    let exists = match backend::get(&path).await {
+        Ok(_) => true,
+        Err(e) if backend::is_unreachable(&e) => return Err(e),
+        Err(_) => false,
+    };
+    match version {
+        None if exists => backend::delete(&path).await.map(drop),
+        None => Ok(()),
+        Some(version) if exists => backend::patch(&path, &exports::snippet_patch(version))
+            .await
+            .map(drop),
+        Some(version) => {
+            let mut version = version.clone();
+            version["id"] = json!(snippet_id);
+            import(version).await
+        }
+    }
+}
+
+async fn import(snippet: Value) -> Result<(), String> {
+    let json = serde_json::to_vec(&exports::bundle_with_names(vec![snippet]))
+        .map_err(|e| e.to_string())?;
+    exports::upload_json(&json).await.map(drop)
+}
+
+async fn resolve(
+    conflict: &Conflict,
+    strategy: Strategy,
+    merged: Option<Value>,
+) -> Result<(), String> {
+    let id = &conflict.snippet_id;
+    match strategy {
+        Strategy::KeepLocal => write(id, conflict.local.as_ref()).await,
+        Strategy::KeepRemote => write(id, conflict.remote.as_ref()).await,
+        Strategy::Merge => {
+            let merged = merged.ok_or("No merged version given")?;
+            if !merged.is_object() {
+                return Err("The merged version must be a snippet".into());
+            }
+            write(id, Some(&merged)).await
+        }
+        Strategy::Dismiss => Ok(()),
+        Strategy::KeepBoth => {
+            let (kept, other) = match conflict.kept {
+                Side::Local => (&conflict.local, &conflict.remote),
+                Side::Remote => (&conflict.remote, &conflict.local),
+            };
+            match (kept, other) {
+                // A deletion was kept: restoring the other version keeps both.
+                (None, Some(other)) => write(id, Some(other)).await,
+                (Some(_), Some(other)) => {
+                    // The id is taken, so the import gives the copy a new one.
+                    let mut copy = other.clone();
+                    copy["id"] = json!(id);
+                    copy["title"] = json!(format!("{} (conflict copy)", conflict.title));
+                    copy["updated_at"] = json!(chrono::Utc::now().timestamp_millis());
+                    import(copy).await
+                }
+                _ => Ok(()),
+            }
+        }
+    }
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Unresolved conflicts, newest first.
+#[tauri::command]
+pub fn list_conflicts() -> Vec<Conflict> {
+    let _guard = LOCK.lock().unwrap();
+    let mut conflicts = load();
+    conflicts.sort_by_key(|c| std::cmp::Reverse(c.detected_at));
+    conflicts
+}
+
+/// Settles conflict `id`; `merged` (shaped like the versions) is needed for
+/// the merge strategy. Returns the remaining conflicts.
+#[tauri::command]
+pub async fn resolve_conflict(
+    id: String,
+    strategy: Strategy,
+    merged: Option<Value>,
+) -> Result<Vec<Conflict>, String> {
+    let conflict = list_conflicts()
+        .into_iter()
+        .find(|c| c.id == id)
+        .ok_or("That conflict was already resolved")?;
+    resolve(&conflict, strategy, merged).await?;
+    {
+        let _guard = LOCK.lock().unwrap();
+        let mut conflicts = load();
+        conflicts.retain(|c| c.id != id);
+        save(&conflicts)?;
+    }
+    Ok(list_conflicts())
+}
@@ -0,0 +1,152 @@
+// Crash reports — a panic hook that leaves something to send us.
+//
+// Every panic writes app_dir()/crashes/crash-<time>.txt with the message,
+// location, backtrace, app and backend versions and the last
+// BACKEND_LOG_LINES backend log lines. A panic on the main thread takes the
+// event loop with it, so the hook then stops the sidecar and other children
+// and relaunches the app with `--crash-report <path>`; the new instance
+// offers to open the report in a native dialog. Panics on other threads
+// (caught by tokio or the thread) only leave the report. A second crash
+// within RESTART_GUARD of a relaunch exits without relaunching again, so a
+// crash at startup doesn't loop.
+//
+// Native crashes (segfaults, aborts) bypass the panic hook; no minidump is
+// written for them.
+
+use std::backtrace::Backtrace;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use once_cell::sync::OnceCell;
+use tauri::AppHandle;
+
+use crate::{i18n, logs, sidecar};
+
+const BACKEND_LOG_LINES: usize = 200;
+const REPORT_ARG: &str = "--crash-report";
+const RESTART_GUARD: Duration = Duration::from_secs(30);
+
+static APP: OnceCell<AppHandle> = OnceCell::new();
+
+fn crashes_dir() -> PathBuf {
+    crate::app_dir().join("crashes")
+}
+
+/// Report path passed to this instance by a crashed one.
+fn previous_report() -> Option<PathBuf> {
+    let mut args = std::env::args_os();
+    args.find(|a| a == REPORT_ARG)?;
+    args.next().map(PathBuf::from)
+}
+
+fn write_report(info: &dyn std::fmt::Display, backtrace: &Backtrace) -> std::io::Result<PathBuf> {
+    let dir = crashes_dir();
+    std::fs::create_dir_all(&dir)?;
+    let now = chrono::Local::now();
+    let path = dir.join(format!("crash-{}.txt", now.format("%Y%m%d-%H%M%S")));
+    let mut file = std::fs::File::create(&path)?;
+
+    let thread = std::thread::current();
+    let backend = APP
+        .get()
+        .map(sidecar::version)
+        .unwrap_or_else(|| "unknown".into());
+    writeln!(file, "Pin-Up AI crash report")?;
+    writeln!(file, "Time:            {}", now.to_rfc3339())?;
+    writeln!(file, "App version:     {}", env!("CARGO_PKG_VERSION"))?;
+    writeln!(file, "Backend version: {}", backend)?;
+    writeln!(
+        file,
+        "OS:              {} {}",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    )?;
+    writeln!(
+        file,
+        "Thread:          {}",
+        thread.name().unwrap_or("<unnamed>")
+    )?;
+    writeln!(file, "Uptime:          {:?}", crate::uptime())?;
+    writeln!(file, "\nPanic: {}", info)?;
+    writeln!(file, "\nBacktrace:\n{}", backtrace)?;
+
+    writeln!(file, "\nBackend log (last {} lines):", BACKEND_LOG_LINES)?;
+    let backend_lines: Vec<_> = logs::recent(usize::MAX)
+        .into_iter()
+        .filter(|l| l.message.starts_with("[backend]"))
+        .collect();
+    let skip = backend_lines.len().saturating_sub(BACKEND_LOG_LINES);
+    for line in &backend_lines[skip..] {
+        writeln!(file, "{} {:5} {}", line.ts, line.level, line.message)?;
+    }
+    Ok(path)
+}
+
+fn relaunch(report: &Path) {
+    // The sidecar, Ollama and whisper were all spawned through tauri's
+    // Command; exiting without this would orphan them.
+    tauri::api::process::kill_children();
+    let relaunched = std::env::current_exe().and_then(|exe| {
+        std::process::Command::new(exe)
+            .arg(REPORT_ARG)
+            .arg(report)
+            .spawn()
+    });
+    if let Err(e) = relaunched {
+        eprintln!("Could not relaunch after crash: {}", e);
+    }
+    std::process::exit(1);
+}
+
+/// Installs the panic hook; call before anything else in `run`.
+pub fn install() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+        let backtrace = Backtrace::force_capture();
+        let report = match write_report(info, &backtrace) {
+            Ok(path) => {
+                log::error!("Crash report written to {}", path.display());
+                Some(path)
+            }
+            Err(e) => {
+                log::error!("Could not write crash report: {}", e);
+                None
+            }
+        };
+        if std::thread::current().name() != Some("main") {
+            return;
+        }
+        let looping = previous_report().is_some() && crate::uptime() < RESTART_GUARD;
+        match report {
+            Some(path) if !looping => relaunch(&path),
+            _ => {
+                tauri::api::process::kill_children();
+                std::process::exit(1);
+            }
+        }
+    }));
+}
+
+/// Keeps the handle for the report and, after a crash relaunch, offers to
+/// open the previous instance's report.
+pub fn attach(app: &AppHandle) {
+    APP.set(app.clone()).ok();
+    let Some(report) = previous_report().filter(|p| p.is_file()) else {
+        return;
+    };
+    log::warn!("Restarted after a crash; report at {}", report.display());
+    tauri::api::dialog::ask(
+        None::<&tauri::Window>,
+        i18n::t("dialog.crash.title"),
+        i18n::t("dialog.crash.body"),
+        move |open| {
+            if open {
+                if let Err(e) = open::that(&report) {
+                    log::warn!("Could not open crash report: {}", e);
+                }
+            }
+        },
+    );
+}
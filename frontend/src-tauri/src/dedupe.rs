@@ -0,0 +1,310 @@
+// Dedupe — duplicate detection for imports.
+//
+// Every import (encrypted/archive/dropped JSON exports, Evernote, Notion,
+// bookmarks, dropped files) loads a `Dedupe` once: the library's snippets
+// keyed by a SHA-256 of their normalized content (body with whitespace
+// collapsed and case folded, or the title when the body is empty). Each
+// incoming item is checked against it, and against the items earlier in the
+// same import, and handled by the user's policy:
+//   skip    the item is left out (the default)
+//   merge   the item's tags and collections are added to the snippet it
+//           duplicates; nothing new is created
+//   flag    the item is imported anyway, tagged FLAG_TAG for review
+// The counts end up in each import's result as `duplicates`.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+
+use crate::capture::{self, Capture};
+use crate::{backend, config, exports};
+
+pub const FLAG_TAG: &str = "duplicate";
+/// Tag id used for FLAG_TAG inside a bundle; the backend merges tags by name.
+const FLAG_TAG_ID: &str = "import-duplicate";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicatePolicy {
+    #[default]
+    Skip,
+    Merge,
+    Flag,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct DedupeConfig {
+    pub policy: DuplicatePolicy,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct DuplicateCounts {
+    policy: DuplicatePolicy,
+    /// Incoming items that matched a snippet (or an earlier item).
+    found: usize,
+    skipped: usize,
+    merged: usize,
+    flagged: usize,
+}
+
+/// A snippet an incoming item can duplicate. `id` is empty for items sent
+/// earlier in this import whose id isn't known (queued captures).
+struct Known {
+    id: String,
+    tags: Vec<String>,
+    collections: Vec<String>,
+}
+
+pub struct Dedupe {
+    policy: DuplicatePolicy,
+    known: HashMap<String, Known>,
+    counts: DuplicateCounts,
+}
+
+/// Content key: whitespace-insensitive, case-insensitive.
+pub fn content_key(title: &str, body: &str) -> String {
+    let normalize = |text: &str| text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let body = normalize(body);
+    let text = if body.is_empty() {
+        format!("title:{}", normalize(title))
+    } else {
+        body
+    };
+    hex::encode(Sha256::digest(text.to_lowercase().as_bytes()))
+}
+
+fn snippet_key(snippet: &Value) -> String {
+    content_key(
+        snippet["title"].as_str().unwrap_or_default(),
+        snippet["body"].as_str().unwrap_or_default(),
+    )
+}
+
+fn union(into: &mut Vec<String>, from: &[String]) -> bool {
+    let before = into.len();
+    for name in from {
+        if !into.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+            into.push(name.clone());
+        }
+    }
+    into.len() != before
+}
+
+impl Dedupe {
+    /// Indexes the whole library, archived snippets included.
+    pub async fn load(app: &AppHandle) -> Result<Self, String> {
+        let policy = config::current(app).dedupe.policy;
+        let json = exports::fetch_export(None, None).await?;
+        let data: Value = serde_json::from_slice(&json).map_err(|e| e.to_string())?;
+        let tags = exports::joined(
+            &data,
+            "snippet_tags",
+            "tag_id",
+            &exports::names_by_id(&data, "tags"),
+        );
+        let collections = exports::joined(
+            &data,
+            "snippet_collections",
+            "collection_id",
+            &exports::names_by_id(&data, "collections"),
+        );
+        let mut known = HashMap::new();
+        for snippet in data["snippets"].as_array().into_iter().flatten() {
+            let id = snippet["id"].as_str().unwrap_or_default();
+            known.entry(snippet_key(snippet)).or_insert_with(|| Known {
+                id: id.into(),
+                tags: tags.get(id).cloned().unwrap_or_default(),
+                collections: collections.get(id).cloned().unwrap_or_default(),
+            });
+        }
+        log::info!(
+            "Dedupe: {} distinct snippets indexed, policy {:?}",
+            known.len(),
+            policy
+        );
+        Ok(Self {
+            policy,
+            known,
+            counts: DuplicateCounts {
+                policy,
+                ..Default::default()
+            },
+        })
+    }
+
+    pub fn counts(&self) -> DuplicateCounts {
+        self.counts.clone()
+    }
+
+    /// Items left out so far (skipped or merged).
+    pub fn removed(&self) -> usize {
+        self.counts.skipped + self.counts.merged
+    }
+
+    /// Dry run of `submit`: counts what the policy would do and returns
+    /// whether `capture` would be left out. Nothing is sent.
+    pub fn preview(&mut self, capture: &Capture) -> bool {
+        let key = content_key(capture.title.as_deref().unwrap_or_default(), &capture.body);
+        let Some(existing) = self.known.get(&key) else {
+            self.known.insert(
+                key,
+                Known {
+                    id: String::new(),
+                    tags: Vec::new(),
+                    collections: Vec::new(),
+                },
+            );
+            return false;
+        };
+        self.counts.found += 1;
+        match self.policy {
+            DuplicatePolicy::Merge if !existing.id.is_empty() => self.counts.merged += 1,
+            DuplicatePolicy::Skip | DuplicatePolicy::Merge => self.counts.skipped += 1,
+            DuplicatePolicy::Flag => {
+                self.counts.flagged += 1;
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Applies the policy to a v1 export bundle before it goes to /import:
+    /// skipped snippets are removed with their tag/collection rows, merged
+    /// ones leave their rows pointing at the snippet they duplicate (the
+    /// backend keeps ids it doesn't remap), flagged ones get FLAG_TAG.
+    pub fn apply_to_bundle(&mut self, bundle: &mut Value) {
+        let Some(snippets) = bundle.get_mut("snippets").and_then(Value::as_array_mut) else {
+            return;
+        };
+        // Incoming id -> existing id to merge into (None: dropped).
+        let mut replaced: HashMap<String, Option<String>> = HashMap::new();
+        let mut flagged = Vec::new();
+        snippets.retain(|snippet| {
+            let id = snippet["id"].as_str().unwrap_or_default().to_string();
+            let key = snippet_key(snippet);
+            let Some(existing) = self.known.get(&key) else {
+                self.known.insert(
+                    key,
+                    Known {
+                        id,
+                        tags: Vec::new(),
+                        collections: Vec::new(),
+                    },
+                );
+                return true;
+            };
+            self.counts.found += 1;
+            match self.policy {
+                DuplicatePolicy::Skip => {
+                    self.counts.skipped += 1;
+                    replaced.insert(id, None);
+                    false
+                }
+                DuplicatePolicy::Merge if !existing.id.is_empty() => {
+                    self.counts.merged += 1;
+                    replaced.insert(id, Some(existing.id.clone()));
+                    false
+                }
+                DuplicatePolicy::Merge => {
+                    self.counts.skipped += 1;
+                    replaced.insert(id, None);
+                    false
+                }
+                DuplicatePolicy::Flag => {
+                    self.counts.flagged += 1;
+                    flagged.push(id);
+                    true
+                }
+            }
+        });
+
+        for table in ["snippet_tags", "snippet_collections"] {
+            let Some(rows) = bundle.get_mut(table).and_then(Value::as_array_mut) else {
+                continue;
+            };
+            rows.retain_mut(|row| {
+                let replacement = row["snippet_id"].as_str().and_then(|id| replaced.get(id));
+                match replacement {
+                    None => true,
+                    Some(None) => false,
+                    Some(Some(existing)) => {
+                        row["snippet_id"] = json!(existing);
+                        true
+                    }
+                }
+            });
+        }
+        if flagged.is_empty() {
+            return;
+        }
+        if let Some(tags) = bundle.get_mut("tags").and_then(Value::as_array_mut) {
+            tags.push(json!({ "id": FLAG_TAG_ID, "name": FLAG_TAG }));
+        } else {
+            bundle["tags"] = json!([{ "id": FLAG_TAG_ID, "name": FLAG_TAG }]);
+        }
+        let rows: Vec<Value> = flagged
+            .iter()
+            .map(|id| json!({ "snippet_id": id, "tag_id": FLAG_TAG_ID }))
+            .collect();
+        match bundle.get_mut("snippet_tags").and_then(Value::as_array_mut) {
+            Some(existing) => existing.extend(rows),
+            None => bundle["snippet_tags"] = Value::Array(rows),
+        }
+    }
+
+    /// `capture::submit` with the policy applied. Ok(None) when nothing was
+    /// created: the capture was skipped or merged into an existing snippet.
+    pub async fn submit(
+        &mut self,
+        app: &AppHandle,
+        mut capture: Capture,
+    ) -> Result<Option<Value>, String> {
+        let key = content_key(capture.title.as_deref().unwrap_or_default(), &capture.body);
+        let Some(existing) = self.known.get_mut(&key) else {
+            let (tags, collections) = (capture.tags.clone(), capture.collections.clone());
+            let created = capture::submit(app, capture).await?;
+            self.known.insert(
+                key,
+                Known {
+                    id: created["id"].as_str().unwrap_or_default().into(),
+                    tags,
+                    collections,
+                },
+            );
+            return Ok(Some(created));
+        };
+        self.counts.found += 1;
+        match self.policy {
+            DuplicatePolicy::Merge if !existing.id.is_empty() => {
+                let tags_added = union(&mut existing.tags, &capture.tags);
+                let collections_added = union(&mut existing.collections, &capture.collections);
+                if tags_added || collections_added {
+                    backend::patch(
+                        &format!("/snippets/{}", existing.id),
+                        &json!({
+                            "tags": existing.tags,
+                            "collections": existing.collections,
+                        }),
+                    )
+                    .await?;
+                }
+                self.counts.merged += 1;
+                Ok(None)
+            }
+            DuplicatePolicy::Skip | DuplicatePolicy::Merge => {
+                self.counts.skipped += 1;
+                Ok(None)
+            }
+            DuplicatePolicy::Flag => {
+                capture.tags.push(FLAG_TAG.into());
+                let created = capture::submit(app, capture).await?;
+                self.counts.flagged += 1;
+                Ok(Some(created))
+            }
+        }
+    }
+}
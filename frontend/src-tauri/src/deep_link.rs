@@ -0,0 +1,771 @@
+// Deep links — pinup:// URLs for launchers (Raycast, Alfred, Wox) and scripts.
+//
+//   pinup://search?q=...&limit=10      matching snippets
+//   pinup://get?id=...                 one snippet, body included
+//   pinup://open?id=... | ?q=...       shows a snippet, or a search, in the app
+//   pinup://open                       the app's search box
+//   pinup://copy?id=...                its body to the clipboard
+//   pinup://paste?id=...               pasted into the frontmost app (see paste.rs)
+//   pinup://new?body=...&title=&tags=a,b&collection=
+//                                      a new snippet, once the user agrees
+//   pinup://new                        the app's editor, for a new snippet
+// The x-callback-url form (pinup://x-callback-url/search?...) works too.
+// Every link is answered with JSON: {"ok": true, "action", ...} or
+// {"ok": false, "code", "error"}; `format=alfred` shapes search results as
+// an Alfred script filter. The answer travels two ways:
+//   - x-success gets it as `result`; x-error gets `errorCode` and
+//     `errorMessage`. Callbacks go only to the schemes in
+//     `deep_links.callback_schemes`, never to the web (REFUSED_SCHEMES,
+//     checked again on every link), so a page that opens a pinup:// link
+//     can't read snippets back. Nor can it file one unasked: `new` with a
+//     body saves only after the user confirms a dialog showing it.
+//   - Running the binary with the link as its argument prints it on stdout
+//     and exits 0 or 1, for script filters and script commands.
+//
+// Registering the scheme: Info.plist on macOS, where links arrive as Apple
+// Events; HKCU\Software\Classes\pinup on Windows and an x-scheme-handler
+// desktop entry on Linux, both of which start this binary with the link,
+// which hands it to the running app (see instance.rs).
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::OnceCell;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::api::dialog::{MessageDialogBuilder, MessageDialogButtons};
+use tauri::{AppHandle, Manager};
+
+use crate::capture::{self, Capture};
+use crate::instance::{self, failure};
+use crate::{app_lock, backend, clipboard, config, i18n, jump_list, paste, tray};
+
+pub const SCHEME: &str = "pinup";
+/// Links opened while the app starts wait this long for the backend.
+const BACKEND_WAIT: Duration = Duration::from_secs(60);
+const MAX_RESULTS: u32 = 50;
+const SOURCE: &str = "launcher";
+/// Characters of a linked snippet's body shown when asking to save it.
+const PREVIEW_CHARS: usize = 300;
+/// Callback schemes that would hand answers to web pages or scripts.
+const REFUSED_SCHEMES: &[&str] = &["http", "https", "file", "javascript", "data"];
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct DeepLinksConfig {
+    pub enabled: bool,
+    /// URL schemes x-success and x-error may call back.
+    pub callback_schemes: Vec<String>,
+}
+
+impl Default for DeepLinksConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            callback_schemes: vec!["raycast".into(), "alfred".into(), "shortcuts".into()],
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DeepLinksStatus {
+    #[serde(flatten)]
+    config: DeepLinksConfig,
+    scheme: &'static str,
+    /// The OS sends pinup:// links to this app.
+    registered: bool,
+    error: Option<String>,
+}
+
+/// (code, message), as x-error's errorCode and errorMessage.
+type Failure = (&'static str, String);
+
+static APP: OnceCell<AppHandle> = OnceCell::new();
+/// Links that arrived before the app was ready.
+static PENDING: Mutex<Vec<String>> = Mutex::new(Vec::new());
+static ERROR: Mutex<Option<String>> = Mutex::new(None);
+
+fn invalid(message: impl Into<String>) -> Failure {
+    ("invalid", message.into())
+}
+
+fn from_backend(error: String) -> Failure {
+    if error.starts_with("Backend returned 404") {
+        ("not_found", "No such snippet".into())
+    } else {
+        ("backend", error)
+    }
+}
+
+// ── Helper side ────────────────────────────────────────────────────────────
+/// The pinup:// link this process was started with, if any.
+pub fn from_args() -> Option<String> {
+    std::env::args_os()
+        .skip(1)
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .find(|arg| {
+            arg.get(..SCHEME.len() + 1)
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(&format!("{SCHEME}:")))
+        })
+}
+
+/// Hands `url` to the running app, prints its answer and returns the exit
+/// code.
+pub fn forward(url: &str) -> i32 {
+    let reply =
+        instance::relay("deep_link", json!({ "url": url }), &mut false).unwrap_or_else(failure);
+    writeln!(std::io::stdout(), "{reply}").ok();
+    if reply["ok"].as_bool() == Some(true) {
+        0
+    } else {
+        1
+    }
+}
+
+// ── Actions ────────────────────────────────────────────────────────────────
+/// The action and query of a pinup:// link.
+fn parse(url: &str) -> Result<(String, HashMap<String, String>), Failure> {
+    let parsed = Url::parse(url.trim()).map_err(|e| invalid(format!("Invalid link: {e}")))?;
+    if !parsed.scheme().eq_ignore_ascii_case(SCHEME) {
+        return Err(invalid(format!("Not a {SCHEME}:// link")));
+    }
+    let host = parsed.host_str().unwrap_or_default();
+    let action = if host.is_empty() || host.eq_ignore_ascii_case("x-callback-url") {
+        parsed.path().trim_matches('/')
+    } else {
+        host
+    };
+    let params = parsed.query_pairs().into_owned().collect();
+    Ok((action.to_lowercase(), params))
+}
+
+fn param<'a>(params: &'a HashMap<String, String>, name: &str) -> Option<&'a str> {
+    params.get(name).map(|v| v.trim()).filter(|v| !v.is_empty())
+}
+
+/// Snippet ids go into backend paths, so only plain ones pass.
+fn snippet_id(params: &HashMap<String, String>) -> Result<&str, Failure> {
+    let id = param(params, "id").ok_or_else(|| invalid("Missing id"))?;
+    if !id
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(invalid("Invalid id"));
+    }
+    Ok(id)
+}
+
+/// A link back to a snippet, for launchers to open.
+pub fn open_link(id: &str) -> String {
+    format!("{SCHEME}://open?id={id}")
+}
+
+/// `backend::get`, waiting out a backend that is still starting.
+async fn backend_get(path: &str) -> Result<Value, Failure> {
+    let deadline = Instant::now() + BACKEND_WAIT;
+    loop {
+        match backend::get(path).await {
+            Err(e) if backend::is_unreachable(&e) && Instant::now() < deadline => {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+            result => return result.map_err(from_backend),
+        }
+    }
+}
+
+async fn snippet(params: &HashMap<String, String>) -> Result<Value, Failure> {
+    let id = snippet_id(params)?;
+    backend_get(&format!("/snippets/{id}")).await
+}
+
+/// Whether x-success / x-error may never call `scheme`, allowed or not.
+fn refused_scheme(scheme: &str) -> bool {
+    REFUSED_SCHEMES.contains(&scheme)
+        || !scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+/// Asks before a link files `capture`, since any web page can open one.
+async fn confirm_new(capture: &Capture) -> bool {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let title = capture
+        .title
+        .clone()
+        .unwrap_or_else(|| i18n::t("reminder.untitled"));
+    let mut preview: String = capture.body.chars().take(PREVIEW_CHARS).collect();
+    if preview.len() < capture.body.len() {
+        preview.push('…');
+    }
+    MessageDialogBuilder::new(
+        i18n::t("dialog.new_from_link.title"),
+        i18n::tf(
+            "dialog.new_from_link.body",
+            &[("title", &title), ("preview", &preview)],
+        ),
+    )
+    .buttons(MessageDialogButtons::OkCancelWithLabels(
+        i18n::t("dialog.new_from_link.save"),
+        i18n::t("dialog.new_from_link.cancel"),
+    ))
+    .show(move |save| {
+        tx.send(save).ok();
+    });
+    rx.await.unwrap_or(false)
+}
+
+fn names(list: &Value) -> Vec<&str> {
+    list.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|item| item["name"].as_str())
+        .collect()
+}
+
+fn body(snippet: &Value) -> Result<String, Failure> {
+    snippet["body"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| ("backend", "Snippet has no body".into()))
+}
+
+fn show(app: &AppHandle, payload: Value) {
+    if let Some(w) = app.get_window("main") {
+        w.show().ok();
+        w.unminimize().ok();
+        w.set_focus().ok();
+        w.emit("deep-link-open", payload).ok();
+    }
+}
+
+async fn search(params: &HashMap<String, String>) -> Result<Value, Failure> {
+    let q = param(params, "q").ok_or_else(|| invalid("Missing q"))?;
+    let limit = param(params, "limit")
+        .and_then(|l| l.parse::<u32>().ok())
+        .unwrap_or(10)
+        .clamp(1, MAX_RESULTS);
+    let query = Url::parse_with_params(
+        "http://localhost/search",
+        [("q", q), ("limit", &limit.to_string())],
+    )
+    .map_err(|e| invalid(e.to_string()))?;
+    let found = backend_get(&format!("/search?{}", query.query().unwrap_or_default())).await?;
+    let results: Vec<Value> = found["results"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|hit| {
+            let id = hit["id"].as_str().unwrap_or_default();
+            json!({
+                "id": id,
+                "title": hit["title"],
+                "preview": hit["preview"],
+                "tags": hit["tags"],
+                "collections": hit["collections"],
+                "url": open_link(id),
+            })
+        })
+        .collect();
+    Ok(json!({ "total": found["total"], "results": results }))
+}
+
+async fn act(
+    app: &AppHandle,
+    action: &str,
+    params: &HashMap<String, String>,
+) -> Result<Value, Failure> {
+    match action {
+        "search" => search(params).await,
+        "get" => {
+            let snippet = snippet(params).await?;
+            let id = snippet["id"].as_str().unwrap_or_default();
+            Ok(json!({
+                "snippet": {
+                    "id": id,
+                    "title": snippet["title"],
+                    "body": snippet["body"],
+                    "language": snippet["language"],
+                    "tags": names(&snippet["tags"]),
+                    "collections": names(&snippet["collections"]),
+                    "source_url": snippet["source_url"],
+                    "url": open_link(id),
+                }
+            }))
+        }
+        "open" => {
+            if let Some(q) = param(params, "q") {
+                show(app, json!({ "query": q }));
+                return Ok(json!({ "query": q }));
+            }
+            if param(params, "id").is_none() {
+                tray::menu_action(app, "search");
+                return Ok(json!({ "view": "search" }));
+            }
+            let snippet = snippet(params).await?;
+            show(app, json!({ "id": snippet["id"] }));
+            Ok(json!({ "id": snippet["id"], "title": snippet["title"] }))
+        }
+        "copy" => {
+            let snippet = snippet(params).await?;
+            clipboard::write_text(&body(&snippet)?).map_err(|e| ("clipboard", e))?;
+            Ok(json!({ "id": snippet["id"], "title": snippet["title"] }))
+        }
+        "paste" => {
+            let snippet = snippet(params).await?;
+            paste::paste_text(app, &body(&snippet)?)
+                .await
+                .map_err(|e| ("paste", e))?;
+            Ok(json!({ "id": snippet["id"], "title": snippet["title"] }))
+        }
+        "new" => {
+            let Some(body) = param(params, "body") else {
+                tray::menu_action(app, "new_snippet");
+                return Ok(json!({ "view": "editor" }));
+            };
+            let capture = Capture {
+                title: param(params, "title").map(str::to_string),
+                body: body.to_string(),
+                source: SOURCE.into(),
+                source_url: None,
+                tags: param(params, "tags")
+                    .into_iter()
+                    .flat_map(|tags| tags.split(','))
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty())
+                    .collect(),
+                collections: param(params, "collection")
+                    .map(str::to_string)
+                    .into_iter()
+                    .collect(),
+            };
+            if !confirm_new(&capture).await {
+                return Err(("cancelled", "The snippet was not saved".into()));
+            }
+            match capture::submit(app, capture).await {
+                Ok(snippet) => Ok(json!({ "id": snippet["id"], "title": snippet["title"] })),
+                // Queued in the outbox, so it is filed once the backend is back.
+                Err(e) if backend::is_unreachable(&e) => Ok(json!({ "queued": true })),
+                Err(e) => Err(("backend", e)),
+            }
+        }
+        "" => Err(invalid("Link has no action")),
+        other => Err(("unknown_action", format!("Unknown action {other:?}"))),
+    }
+}
+
+// ── Answers ────────────────────────────────────────────────────────────────
+/// Refuses callbacks outside the allowed schemes before anything runs.
+fn check_callbacks(cfg: &DeepLinksConfig, params: &HashMap<String, String>) -> Result<(), Failure> {
+    for key in ["x-success", "x-error", "x-cancel"] {
+        let Some(target) = param(params, key) else {
+            continue;
+        };
+        let scheme = Url::parse(target)
+            .map_err(|_| invalid(format!("Invalid {key}")))?
+            .scheme()
+            .to_lowercase();
+        if refused_scheme(&scheme) {
+            return Err((
+                "callback_refused",
+                format!("{scheme}: callbacks to the web or files are never allowed"),
+            ));
+        }
+        if !cfg
+            .callback_schemes
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(&scheme))
+        {
+            return Err((
+                "callback_refused",
+                format!(
+                    "{scheme}: callbacks are not allowed; add the scheme in Pin-Up AI's settings"
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn call_back(params: &HashMap<String, String>, response: &Value) {
+    let ok = response["ok"].as_bool() == Some(true);
+    let Some(target) = param(params, if ok { "x-success" } else { "x-error" }) else {
+        return;
+    };
+    let Ok(mut url) = Url::parse(target) else {
+        return;
+    };
+    if ok {
+        url.query_pairs_mut()
+            .append_pair("result", &response.to_string());
+    } else {
+        url.query_pairs_mut()
+            .append_pair("errorCode", response["code"].as_str().unwrap_or_default())
+            .append_pair(
+                "errorMessage",
+                response["error"].as_str().unwrap_or_default(),
+            );
+    }
+    if let Err(e) = open::that_detached(url.as_str()) {
+        log::warn!("Deep links: could not call back {}: {}", url.scheme(), e);
+    }
+}
+
+/// Search results as an Alfred script filter.
+fn alfred(response: &Value) -> Value {
+    let items: Vec<Value> = match response["results"].as_array() {
+        Some(results) => results
+            .iter()
+            .map(|hit| {
+                json!({
+                    "uid": hit["id"],
+                    "title": hit["title"],
+                    "subtitle": hit["preview"],
+                    "arg": hit["id"],
+                    "variables": { "url": hit["url"] },
+                })
+            })
+            .collect(),
+        None => vec![json!({
+            "title": response["error"].as_str().unwrap_or("No results"),
+            "valid": false,
+        })],
+    };
+    json!({ "ok": response["ok"], "items": items })
+}
+
+async fn open_url(app: &AppHandle, url: &str) -> Value {
+    if app_lock::locked() {
+        app_lock::prompt(app);
+        return json!({ "ok": false, "code": "locked", "error": "Pin-Up AI is locked" });
+    }
+    let cfg = config::current(app).deep_links;
+    let (action, params) = match parse(url) {
+        Ok(parsed) => parsed,
+        Err((code, error)) => return json!({ "ok": false, "code": code, "error": error }),
+    };
+    let checked = if cfg.enabled {
+        check_callbacks(&cfg, &params)
+    } else {
+        Err((
+            "disabled",
+            "Links are turned off in Pin-Up AI's settings".into(),
+        ))
+    };
+    let result = match checked {
+        Ok(()) => act(app, &action, &params).await,
+        Err(e) => Err(e),
+    };
+    log::info!(
+        "Deep links: {} from {}{}",
+        action,
+        param(&params, "x-source").unwrap_or("unknown source"),
+        result
+            .as_ref()
+            .err()
+            .map(|(code, _)| format!(" failed ({code})"))
+            .unwrap_or_default()
+    );
+    let callbacks_allowed = !matches!(&result, Err(("callback_refused" | "disabled", _)));
+    let response = match result {
+        Ok(mut answer) => {
+            answer["ok"] = json!(true);
+            answer["action"] = json!(action);
+            answer
+        }
+        Err((code, error)) => {
+            json!({ "ok": false, "action": action, "code": code, "error": error })
+        }
+    };
+    if callbacks_allowed {
+        call_back(&params, &response);
+    }
+    if action == "search" && param(&params, "format") == Some("alfred") {
+        return alfred(&response);
+    }
+    response
+}
+
+/// Answers a link relayed by a helper process.
+pub async fn handle(app: &AppHandle, message: Value) -> Value {
+    match message["url"].as_str() {
+        Some(url) => open_url(app, url).await,
+        None => failure("Missing url"),
+    }
+}
+
+/// A link the OS handed to this process directly.
+fn received(url: String) {
+    match APP.get() {
+        Some(app) => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                open_url(&app, &url).await;
+            });
+        }
+        None => PENDING.lock().unwrap().push(url),
+    }
+}
+
+// ── Registration ───────────────────────────────────────────────────────────
+#[cfg(target_os = "macos")]
+mod apple_events {
+    use std::ffi::CStr;
+    use std::os::raw::c_char;
+
+    use objc::declare::ClassDecl;
+    use objc::runtime::{Object, Sel};
+    use objc::{class, msg_send, sel, sel_impl};
+
+    /// kInternetEventClass and kAEGetURL are both 'GURL'.
+    const GET_URL: u32 = u32::from_be_bytes(*b"GURL");
+    const DIRECT_OBJECT: u32 = u32::from_be_bytes(*b"----");
+
+    extern "C" fn get_url(_this: &Object, _cmd: Sel, event: *mut Object, _reply: *mut Object) {
+        unsafe {
+            let descriptor: *mut Object =
+                msg_send![event, paramDescriptorForKeyword: DIRECT_OBJECT];
+            if descriptor.is_null() {
+                return;
+            }
+            let string: *mut Object = msg_send![descriptor, stringValue];
+            if string.is_null() {
+                return;
+            }
+            let utf8: *const c_char = msg_send![string, UTF8String];
+            if !utf8.is_null() {
+                super::received(CStr::from_ptr(utf8).to_string_lossy().into_owned());
+            }
+        }
+    }
+
+    /// Must run before the app finishes launching, or the link that
+    /// launched it is lost.
+    pub fn install() {
+        let Some(mut decl) = ClassDecl::new("PinUpDeepLinkHandler", class!(NSObject)) else {
+            return;
+        };
+        unsafe {
+            decl.add_method(
+                sel!(handleGetURLEvent:withReplyEvent:),
+                get_url as extern "C" fn(&Object, Sel, *mut Object, *mut Object),
+            );
+            let handler: *mut Object = msg_send![decl.register(), new];
+            let manager: *mut Object =
+                msg_send![class!(NSAppleEventManager), sharedAppleEventManager];
+            let _: () = msg_send![manager,
+                setEventHandler: handler
+                andSelector: sel!(handleGetURLEvent:withReplyEvent:)
+                forEventClass: GET_URL
+                andEventID: GET_URL];
+        }
+    }
+}
+
+/// Listens for links the OS hands over directly; call early in `run`.
+pub fn install() {
+    #[cfg(target_os = "macos")]
+    apple_events::install();
+}
+
+#[cfg(target_os = "linux")]
+const DESKTOP_FILE: &str = "pin-up-ai-url-handler.desktop";
+
+#[cfg(target_os = "linux")]
+fn desktop_path() -> Option<std::path::PathBuf> {
+    Some(dirs::data_dir()?.join("applications").join(DESKTOP_FILE))
+}
+
+#[cfg(target_os = "linux")]
+fn xdg_mime(args: &[&str]) -> Result<String, String> {
+    let output = tauri::api::process::Command::new("xdg-mime")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Could not run xdg-mime: {e}"))?;
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(output.stderr.trim().to_string())
+    }
+}
+
+/// A quoted Exec= argument (Desktop Entry spec, "The Exec key").
+#[cfg(target_os = "linux")]
+fn exec_arg(path: &std::path::Path) -> String {
+    let mut out = String::from("\"");
+    for c in path.to_string_lossy().chars() {
+        match c {
+            '\\' => out.push_str("\\\\\\\\"),
+            '"' | '`' | '$' => {
+                out.push_str("\\\\");
+                out.push(c);
+            }
+            '%' => out.push_str("%%"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(target_os = "linux")]
+fn register() -> Result<(), String> {
+    let path = desktop_path().ok_or("No data directory")?;
+    let entry = format!(
+        "[Desktop Entry]\nType=Application\nName=Pin-Up AI\nExec={} %u\nNoDisplay=true\n\
+         Terminal=false\nMimeType=x-scheme-handler/{SCHEME};\n",
+        exec_arg(&instance::exe_path()?)
+    );
+    let changed = instance::write_if_changed(&path, &entry)?;
+    let mime = format!("x-scheme-handler/{SCHEME}");
+    let current = xdg_mime(&["query", "default", &mime]).unwrap_or_default();
+    if changed || current.trim() != DESKTOP_FILE {
+        xdg_mime(&["default", DESKTOP_FILE, &mime])?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn unregister() -> Result<(), String> {
+    match desktop_path() {
+        Some(path) if path.is_file() => std::fs::remove_file(&path)
+            .map_err(|e| format!("Could not remove {}: {e}", path.display())),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn registered() -> bool {
+    desktop_path().is_some_and(|path| path.is_file())
+}
+
+#[cfg(windows)]
+fn key() -> String {
+    format!(r"HKCU\Software\Classes\{SCHEME}")
+}
+
+#[cfg(windows)]
+fn register() -> Result<(), String> {
+    let command = format!("\"{}\" \"%1\"", instance::exe_path()?.display());
+    let command_key = format!(r"{}\shell\open\command", key());
+    if instance::reg(&["query", &command_key, "/ve"]).is_ok_and(|out| out.contains(&command)) {
+        return Ok(());
+    }
+    instance::reg(&["add", &key(), "/ve", "/d", "URL:Pin-Up AI", "/f"])?;
+    instance::reg(&["add", &key(), "/v", "URL Protocol", "/d", "", "/f"])?;
+    instance::reg(&["add", &command_key, "/ve", "/d", &command, "/f"]).map(drop)
+}
+
+#[cfg(windows)]
+fn unregister() -> Result<(), String> {
+    if registered() {
+        instance::reg(&["delete", &key(), "/f"])?;
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn registered() -> bool {
+    instance::reg(&["query", &key()]).is_ok()
+}
+
+/// Info.plist declares the scheme; nothing to do at runtime.
+#[cfg(target_os = "macos")]
+fn register() -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn unregister() -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn registered() -> bool {
+    true
+}
+
+async fn apply(enabled: bool) -> Result<(), String> {
+    let result =
+        tauri::async_runtime::spawn_blocking(
+            move || {
+                if enabled {
+                    register()
+                } else {
+                    unregister()
+                }
+            },
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    *ERROR.lock().unwrap() = result.as_ref().err().cloned();
+    result
+}
+
+async fn status(app: &AppHandle) -> Result<DeepLinksStatus, String> {
+    let registered = tauri::async_runtime::spawn_blocking(registered)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(DeepLinksStatus {
+        config: config::current(app).deep_links,
+        scheme: SCHEME,
+        registered,
+        error: ERROR.lock().unwrap().clone(),
+    })
+}
+
+/// Registers the scheme and opens links that arrived during startup.
+pub fn start(app: &AppHandle) {
+    APP.set(app.clone()).ok();
+    for url in std::mem::take(&mut *PENDING.lock().unwrap()) {
+        received(url);
+    }
+    let enabled = config::current(app).deep_links.enabled;
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = apply(enabled).await {
+            log::warn!("Deep links: {}", e);
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub async fn get_deep_links(app: AppHandle) -> Result<DeepLinksStatus, String> {
+    status(&app).await
+}
+
+/// Turns pinup:// links on or off and sets which apps may be called back.
+#[tauri::command]
+pub async fn set_deep_links(
+    app: AppHandle,
+    enabled: bool,
+    callback_schemes: Option<Vec<String>>,
+) -> Result<DeepLinksStatus, String> {
+    let schemes = callback_schemes.map(|schemes| {
+        schemes
+            .iter()
+            .map(|s| {
+                s.trim()
+                    .trim_end_matches("://")
+                    .trim_end_matches(':')
+                    .to_lowercase()
+            })
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+    });
+    // Web and file callbacks would hand snippets to whatever page asks.
+    if let Some(bad) = schemes.iter().flatten().find(|s| refused_scheme(s)) {
+        return Err(format!("{bad:?} can't be a callback scheme"));
+    }
+    config::update(&app, |c| {
+        c.deep_links.enabled = enabled;
+        if let Some(schemes) = schemes {
+            c.deep_links.callback_schemes = schemes;
+        }
+    })?;
+    apply(enabled).await?;
+    jump_list::update(&app);
+    status(&app).await
+}
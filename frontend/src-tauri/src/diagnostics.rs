@@ -0,0 +1,175 @@
+// Diagnostics bundle — one zip to attach to a support ticket.
+//
+// `export_diagnostics` asks for a destination with the save dialog and
+// writes:
+//   app.log / backend.log  captured log lines (see logs.rs)
+//   shell-config.json      shell config with secrets redacted
+//   versions.json          app, backend and OS versions
+//   health.json            recent backend health checks (see health.rs)
+//   system.json            hardware, free disk space, power and network state
+// Config values under secret-looking keys are replaced, URL credentials are
+// stripped, and every text file then goes through secrets::redact_secrets.
+// Log lines also lose URL credentials and secret-looking query values
+// (`redact_log`, which support sessions use for their live tail too). No
+// snippet content is included: the config holds none, and log lines name
+// user content by id or count only.
+
+use std::io::Write;
+use std::path::Path;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{json, Value};
+use tauri::AppHandle;
+
+use crate::{config, disk, hardware, health, i18n, logs, secrets, sidecar};
+
+const SECRET_KEYS: [&str; 5] = ["token", "secret", "password", "api_key", "apikey"];
+
+/// "scheme://user:pass@" in free text.
+static URL_CREDENTIALS: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"([A-Za-z][A-Za-z0-9+.-]*://)[^/\s@]+@").unwrap());
+/// "token=...", "password=..." and the like.
+static SECRET_PARAMS: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b((?:access_|auth_|refresh_)?token|secret|password|api_?key)=[^\s&]+")
+        .unwrap()
+});
+
+/// A log message with credentials and secrets replaced.
+pub fn redact_log(message: &str) -> String {
+    let text = URL_CREDENTIALS.replace_all(message, "$1");
+    let text = SECRET_PARAMS.replace_all(&text, "$1=[REDACTED]");
+    secrets::redact_secrets(&text, &[]).0
+}
+
+/// Drops "user:pass@" from URLs.
+fn strip_credentials(text: &str) -> String {
+    match (text.find("://"), text.find('@')) {
+        (Some(scheme), Some(at)) if at > scheme => {
+            format!("{}{}", &text[..scheme + 3], &text[at + 1..])
+        }
+        _ => text.to_string(),
+    }
+}
+
+fn redact_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_lowercase();
+                if SECRET_KEYS.iter().any(|k| key.contains(k)) && !value.is_null() {
+                    *value = json!("[REDACTED]");
+                } else {
+                    redact_value(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_value),
+        Value::String(text) => *text = strip_credentials(text),
+        _ => {}
+    }
+}
+
+fn redacted_config(app: &AppHandle) -> Result<String, String> {
+    let mut value = serde_json::to_value(config::current(app)).map_err(|e| e.to_string())?;
+    redact_value(&mut value);
+    serde_json::to_string_pretty(&value).map_err(|e| e.to_string())
+}
+
+fn log_text(backend: bool) -> String {
+    logs::recent(usize::MAX)
+        .into_iter()
+        .filter(|l| l.message.starts_with("[backend]") == backend)
+        .map(|l| {
+            let message = redact_log(&l.message);
+            format!("{} {:5} {} {}\n", l.ts, l.level, l.target, message)
+        })
+        .collect()
+}
+
+fn versions(app: &AppHandle) -> Value {
+    let info = app.package_info();
+    json!({
+        "app": info.version.to_string(),
+        "backend": sidecar::version(app),
+        "backend_binary": if sidecar::binary().is_some() { "updated" } else { "bundled" },
+        "tauri": tauri::VERSION,
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "os_version": sysinfo::System::long_os_version(),
+        "kernel": sysinfo::System::kernel_version(),
+    })
+}
+
+async fn system(app: &AppHandle) -> Value {
+    let data = crate::data_dir();
+    json!({
+        "hardware": hardware::info(),
+        "data_dir_free_bytes": disk::free_space(&data),
+        "power": crate::power::get_power_profile(app.clone()).await,
+        "network": crate::network::get_network_status(app.clone()).await,
+    })
+}
+
+fn write_zip(dest: &Path, files: &[(&str, String)]) -> Result<(), String> {
+    let file = std::fs::File::create(dest).map_err(|e| format!("Could not create bundle: {e}"))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, text) in files {
+        zip.start_file(*name, options)
+            .and_then(|_| zip.write_all(text.as_bytes()).map_err(Into::into))
+            .map_err(|e| format!("Could not write {name}: {e}"))?;
+    }
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Writes the diagnostics zip to a path chosen in the save dialog. Returns
+/// the path, or None when the dialog was cancelled.
+#[tauri::command]
+pub async fn export_diagnostics(app: AppHandle) -> Result<Option<String>, String> {
+    use tauri::api::dialog::blocking::FileDialogBuilder;
+
+    let name = format!(
+        "pinup-diagnostics-{}.zip",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+    let Some(dest) = FileDialogBuilder::new()
+        .set_title(&i18n::t("dialog.export_diagnostics"))
+        .set_file_name(&name)
+        .add_filter("Zip archive", &["zip"])
+        .save_file()
+    else {
+        return Ok(None);
+    };
+
+    let files = [
+        ("app.log", log_text(false)),
+        ("backend.log", log_text(true)),
+        ("shell-config.json", redacted_config(&app)?),
+        (
+            "versions.json",
+            serde_json::to_string_pretty(&versions(&app)).unwrap_or_default(),
+        ),
+        (
+            "health.json",
+            serde_json::to_string_pretty(&health::history()).unwrap_or_default(),
+        ),
+        (
+            "system.json",
+            serde_json::to_string_pretty(&system(&app).await).unwrap_or_default(),
+        ),
+    ];
+    let files: Vec<(&str, String)> = files
+        .into_iter()
+        .map(|(name, text)| (name, secrets::redact_secrets(&text, &[]).0))
+        .collect();
+
+    let size: u64 = files.iter().map(|(_, text)| text.len() as u64).sum();
+    disk::ensure_space(&dest, size)?;
+    write_zip(&dest, &files)?;
+    log::info!("Diagnostics bundle written to {}", dest.display());
+    Ok(Some(dest.to_string_lossy().to_string()))
+}
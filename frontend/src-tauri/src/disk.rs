@@ -0,0 +1,121 @@
+// Disk space — preflight checks before writing large files.
+//
+// `ensure_space(dir, bytes)` runs before model downloads, attachment writes
+// (imports, screenshots, voice notes), theme exports and local restores, so
+// a full disk fails fast instead of leaving a truncated file behind. Its
+// error string is JSON in the backend's error shape:
+//   {"code":"INSUFFICIENT_SPACE","message":"…","path":"…","needed":N,"available":N}
+// so the frontend can show needed vs available bytes. Backups and exports
+// run in the backend; the frontend asks `check_disk_space` first.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::json;
+
+/// Free space kept in reserve beyond the file itself.
+const MARGIN: u64 = 64 * 1024 * 1024;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SpaceCheck {
+    path: String,
+    /// Bytes the operation needs, reserve included.
+    needed: u64,
+    /// None when the volume could not be determined.
+    available: Option<u64>,
+    ok: bool,
+}
+
+/// Free bytes on the volume holding `path`, or its nearest existing parent.
+pub fn free_space(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+    let path = std::fs::canonicalize(existing).ok()?;
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|d| path.starts_with(d.mount_point()))
+        .max_by_key(|d| d.mount_point().as_os_str().len())
+        .map(|d| d.available_space())
+}
+
+pub fn check(path: &Path, bytes: u64) -> SpaceCheck {
+    let needed = bytes.saturating_add(MARGIN);
+    let available = free_space(path);
+    SpaceCheck {
+        path: path.to_string_lossy().into_owned(),
+        needed,
+        available,
+        // Unknown volumes are let through rather than blocking writes.
+        ok: available.map_or(true, |free| free >= needed),
+    }
+}
+
+/// Fails with a structured INSUFFICIENT_SPACE error if `bytes` (plus the
+/// reserve) won't fit at `path`.
+pub fn ensure_space(path: &Path, bytes: u64) -> Result<(), String> {
+    let check = check(path, bytes);
+    if check.ok {
+        return Ok(());
+    }
+    let available = check.available.unwrap_or(0);
+    log::warn!(
+        "Not enough space at {}: {} needed, {} free",
+        check.path,
+        check.needed,
+        available
+    );
+    Err(json!({
+        "code": "INSUFFICIENT_SPACE",
+        "message": format!(
+            "Not enough disk space: {} MB needed, {} MB free",
+            check.needed / 1_000_000,
+            available / 1_000_000
+        ),
+        "path": check.path,
+        "needed": check.needed,
+        "available": available,
+    })
+    .to_string())
+}
+
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map_or(0, |m| m.len())
+}
+
+/// The database with its WAL, i.e. what a backup or export copies.
+fn database_size() -> u64 {
+    let db = crate::db_path();
+    let wal = PathBuf::from(format!("{}-wal", db.to_string_lossy()));
+    file_size(&db) + file_size(&wal)
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Preflight for `operation`:
+///   backup    database size, into data_dir()/backups
+///   export    database size, into `path`
+///   import    `bytes`, into the data dir
+///   download  `bytes`, into `path` (default app_dir()/models)
+#[tauri::command]
+pub fn check_disk_space(
+    operation: String,
+    path: Option<String>,
+    bytes: Option<u64>,
+) -> Result<SpaceCheck, String> {
+    let data_dir = crate::data_dir();
+    let (target, needed) = match operation.as_str() {
+        "backup" => (data_dir.join("backups"), database_size()),
+        "export" => (
+            path.map(PathBuf::from).ok_or("export needs a path")?,
+            bytes.unwrap_or_else(database_size),
+        ),
+        "import" => (data_dir, bytes.ok_or("import needs bytes")?),
+        "download" => (
+            path.map(PathBuf::from)
+                .unwrap_or_else(|| crate::app_dir().join("models")),
+            bytes.ok_or("download needs bytes")?,
+        ),
+        other => return Err(format!("Unknown operation {:?}", other)),
+    };
+    Ok(check(&target, needed))
+}
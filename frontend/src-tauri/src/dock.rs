@@ -0,0 +1,155 @@
+// Dock menu — quick actions on the app's Dock icon (macOS).
+//
+// Many users hide the menu-bar tray, so the Dock menu offers its actions
+// too: New Snippet, Search, Pause/Resume Capture (see clipboard.rs) and the
+// most recent snippets. The items go through the tray's own handler
+// (`menu_action` in tray.rs); a recent snippet opens like a pinup://open
+// link; the list comes from recent.rs. AppKit asks the app delegate for the
+// menu each time it opens, so the method is added to tao's delegate class.
+// Other platforms have no Dock; `start` does nothing there.
+
+use tauri::AppHandle;
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use std::ffi::CString;
+    use std::os::raw::c_char;
+
+    use objc::declare::ClassDecl;
+    use objc::runtime::{class_addMethod, object_getClass, Class, Imp, Object, Sel, BOOL, NO, YES};
+    use objc::{class, msg_send, sel, sel_impl};
+    use once_cell::sync::OnceCell;
+    use tauri::AppHandle;
+
+    use crate::i18n::t;
+    use crate::{clipboard, recent, tray};
+
+    const RECENT_COUNT: usize = 5;
+    /// Item tags; recent snippet i is RECENT_TAG + i.
+    const NEW_SNIPPET_TAG: isize = 1;
+    const SEARCH_TAG: isize = 2;
+    const PAUSE_TAG: isize = 3;
+    const RECENT_TAG: isize = 100;
+
+    static APP: OnceCell<AppHandle> = OnceCell::new();
+    /// The object menu items call back; an `*mut Object` as usize.
+    static TARGET: OnceCell<usize> = OnceCell::new();
+
+    fn act(app: &AppHandle, tag: isize) {
+        match tag {
+            NEW_SNIPPET_TAG => tray::menu_action(app, "new_snippet"),
+            SEARCH_TAG => tray::menu_action(app, "search"),
+            PAUSE_TAG => tray::menu_action(app, "pause_capture"),
+            tag if tag >= RECENT_TAG => {
+                if let Some(snippet) = recent::snippets().get((tag - RECENT_TAG) as usize) {
+                    tray::open_snippet(app, &snippet.id);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    unsafe fn ns_string(value: &str) -> *mut Object {
+        let c = CString::new(value).unwrap_or_default();
+        msg_send![class!(NSString), stringWithUTF8String: c.as_ptr()]
+    }
+
+    unsafe fn add_item(menu: *mut Object, title: &str, tag: isize, enabled: BOOL) {
+        let item: *mut Object = msg_send![class!(NSMenuItem), alloc];
+        let item: *mut Object = msg_send![item,
+            initWithTitle: ns_string(title)
+            action: sel!(dockAction:)
+            keyEquivalent: ns_string("")];
+        let target = *TARGET.get().unwrap() as *mut Object;
+        let _: () = msg_send![item, setTarget: target];
+        let _: () = msg_send![item, setTag: tag];
+        let _: () = msg_send![item, setEnabled: enabled];
+        let _: () = msg_send![menu, addItem: item];
+        let _: () = msg_send![item, release];
+    }
+
+    unsafe fn add_separator(menu: *mut Object) {
+        let item: *mut Object = msg_send![class!(NSMenuItem), separatorItem];
+        let _: () = msg_send![menu, addItem: item];
+    }
+
+    /// `- (void)dockAction:(NSMenuItem *)sender` on the target.
+    extern "C" fn dock_action(_this: &Object, _cmd: Sel, sender: *mut Object) {
+        let tag: isize = unsafe { msg_send![sender, tag] };
+        if let Some(app) = APP.get() {
+            act(app, tag);
+        }
+    }
+
+    /// `- (NSMenu *)applicationDockMenu:(NSApplication *)sender` on the
+    /// app delegate; the menu is autoreleased.
+    extern "C" fn dock_menu(_this: &Object, _cmd: Sel, _sender: *mut Object) -> *mut Object {
+        let recent: Vec<_> = recent::snippets().into_iter().take(RECENT_COUNT).collect();
+        unsafe {
+            let menu: *mut Object = msg_send![class!(NSMenu), alloc];
+            let menu: *mut Object = msg_send![menu, initWithTitle: ns_string("")];
+            // Keeps the "Recent" heading disabled.
+            let _: () = msg_send![menu, setAutoenablesItems: NO];
+            add_item(menu, &t("tray.new_snippet"), NEW_SNIPPET_TAG, YES);
+            add_item(menu, &t("tray.search"), SEARCH_TAG, YES);
+            let pause = t(if clipboard::paused() {
+                "tray.resume_capture"
+            } else {
+                "tray.pause_capture"
+            });
+            add_item(menu, &pause, PAUSE_TAG, YES);
+            if !recent.is_empty() {
+                add_separator(menu);
+                add_item(menu, &t("tray.recent"), 0, NO);
+                for (i, snippet) in recent.iter().enumerate() {
+                    add_item(menu, &snippet.label(), RECENT_TAG + i as isize, YES);
+                }
+            }
+            msg_send![menu, autorelease]
+        }
+    }
+
+    /// Adds the menu; runs on the main thread during setup, once tao has
+    /// set the app delegate.
+    pub fn start(app: &AppHandle) {
+        if APP.set(app.clone()).is_err() {
+            return;
+        }
+        let Some(mut decl) = ClassDecl::new("PinUpDockTarget", class!(NSObject)) else {
+            return;
+        };
+        unsafe {
+            decl.add_method(
+                sel!(dockAction:),
+                dock_action as extern "C" fn(&Object, Sel, *mut Object),
+            );
+            let target: *mut Object = msg_send![decl.register(), new];
+            TARGET.set(target as usize).ok();
+
+            let ns_app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+            let delegate: *mut Object = msg_send![ns_app, delegate];
+            if delegate.is_null() {
+                log::warn!("Dock: no app delegate");
+                return;
+            }
+            let imp: Imp = std::mem::transmute(
+                dock_menu as extern "C" fn(&Object, Sel, *mut Object) -> *mut Object,
+            );
+            class_addMethod(
+                object_getClass(delegate) as *mut Class,
+                sel!(applicationDockMenu:),
+                imp,
+                b"@@:@\0".as_ptr() as *const c_char,
+            );
+        }
+        log::info!("Dock: menu installed");
+    }
+}
+
+/// Adds quick actions to the Dock icon's menu (macOS).
+pub fn start(app: &AppHandle) {
+    #[cfg(target_os = "macos")]
+    imp::start(app);
+    #[cfg(not(target_os = "macos"))]
+    let _ = app;
+}
@@ -0,0 +1,308 @@
+// Model downloads — queued, resumable fetches of model and weights files.
+//
+// Jobs run one at a time in queue order. Data is written to `<dest>.part`;
+// when a .part file is already there (an earlier attempt failed or the app
+// quit), the request asks for `Range: bytes=<len>-` and appends if the server
+// answers 206, starting over otherwise. Before any bytes are written the
+// remaining size is checked against free space (see disk.rs). With a
+// sha256, the finished file is verified before it is renamed into place;
+// a mismatch deletes it so the next attempt starts clean.
+//
+// Progress is emitted as "model-download-progress" with the full job, at most
+// once per percent and on every state change. `queue_model_download` stores
+// files under app_dir()/models/; Whisper models and OCR language packs go
+// through `fetch`, which queues a job and waits for it.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use futures_util::StreamExt;
+use once_cell::sync::{Lazy, OnceCell};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::{disk, proxy};
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadState {
+    Queued,
+    Downloading,
+    Verifying,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Download {
+    id: u64,
+    url: String,
+    dest: String,
+    sha256: Option<String>,
+    state: DownloadState,
+    received: u64,
+    total: Option<u64>,
+    /// Bytes that were already on disk from an earlier attempt.
+    resumed_from: u64,
+    error: Option<String>,
+}
+
+type Waiter = oneshot::Sender<Result<PathBuf, String>>;
+
+static DOWNLOADS: Lazy<Mutex<Vec<Download>>> = Lazy::new(|| Mutex::new(Vec::new()));
+/// `fetch` callers waiting for their job, by id.
+static WAITERS: Lazy<Mutex<HashMap<u64, Waiter>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+static QUEUE: OnceCell<mpsc::UnboundedSender<u64>> = OnceCell::new();
+
+fn models_dir() -> PathBuf {
+    crate::app_dir().join("models")
+}
+
+fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).map_err(|e| e.to_string())?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn snapshot(id: u64) -> Option<Download> {
+    DOWNLOADS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|d| d.id == id)
+        .cloned()
+}
+
+/// Applies `f` to job `id` and emits the result.
+fn update(app: &AppHandle, id: u64, f: impl FnOnce(&mut Download)) -> Option<Download> {
+    let job = {
+        let mut downloads = DOWNLOADS.lock().unwrap();
+        let job = downloads.iter_mut().find(|d| d.id == id)?;
+        f(job);
+        job.clone()
+    };
+    app.emit_all("model-download-progress", &job).ok();
+    Some(job)
+}
+
+fn is_cancelled(id: u64) -> bool {
+    snapshot(id).map_or(true, |d| d.state == DownloadState::Cancelled)
+}
+
+async fn run(app: &AppHandle, id: u64) -> Result<PathBuf, String> {
+    let job = snapshot(id).ok_or("Download vanished")?;
+    let dest = PathBuf::from(&job.dest);
+    let part = part_path(&dest);
+    let dir = dest.parent().ok_or("Invalid destination")?;
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+
+    let existing = std::fs::metadata(&part).map(|m| m.len()).unwrap_or(0);
+    let client = proxy::client_builder(app)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut request = client.get(&job.url);
+    if existing > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", existing));
+    }
+    let response = request
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Download failed: {e}"))?;
+    let resumed = existing > 0 && response.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+    let offset = if resumed { existing } else { 0 };
+    let total = response.content_length().map(|len| len + offset);
+
+    if let Some(remaining) = response.content_length() {
+        disk::ensure_space(dir, remaining)?;
+    }
+
+    let mut file = if resumed {
+        log::info!("Resuming {} at {} bytes", job.url, offset);
+        tokio::fs::OpenOptions::new().append(true).open(&part).await
+    } else {
+        tokio::fs::File::create(&part).await
+    }
+    .map_err(|e| e.to_string())?;
+    update(app, id, |d| {
+        d.state = DownloadState::Downloading;
+        d.received = offset;
+        d.total = total;
+        d.resumed_from = offset;
+    });
+
+    let mut received = offset;
+    let mut last_percent = None;
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        if is_cancelled(id) {
+            drop(file);
+            std::fs::remove_file(&part).ok();
+            return Err("Download cancelled".into());
+        }
+        let chunk = chunk.map_err(|e| format!("Download failed: {e}"))?;
+        file.write_all(&chunk).await.map_err(|e| e.to_string())?;
+        received += chunk.len() as u64;
+        let percent = total.map(|t| received * 100 / t.max(1));
+        if percent != last_percent {
+            last_percent = percent;
+            update(app, id, |d| d.received = received);
+        }
+    }
+    file.flush().await.map_err(|e| e.to_string())?;
+    drop(file);
+    if total.is_some_and(|t| t != received) {
+        // Keep the .part so a retry can resume.
+        return Err("Download was interrupted".into());
+    }
+
+    if let Some(expected) = &job.sha256 {
+        update(app, id, |d| d.state = DownloadState::Verifying);
+        let path = part.clone();
+        let actual = tauri::async_runtime::spawn_blocking(move || sha256_file(&path))
+            .await
+            .map_err(|e| e.to_string())??;
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            std::fs::remove_file(&part).ok();
+            return Err(format!("Checksum mismatch: got {}", actual));
+        }
+    }
+    std::fs::rename(&part, &dest).map_err(|e| e.to_string())?;
+    Ok(dest)
+}
+
+async fn worker(app: AppHandle, mut rx: mpsc::UnboundedReceiver<u64>) {
+    while let Some(id) = rx.recv().await {
+        let result = if is_cancelled(id) {
+            Err("Download cancelled".to_string())
+        } else {
+            run(&app, id).await
+        };
+        update(&app, id, |d| match &result {
+            Ok(_) => d.state = DownloadState::Done,
+            Err(_) if d.state == DownloadState::Cancelled => {}
+            Err(e) => {
+                d.state = DownloadState::Failed;
+                d.error = Some(e.clone());
+            }
+        });
+        match &result {
+            Ok(dest) => log::info!("Downloaded {}", dest.display()),
+            Err(e) => log::warn!("Download {} failed: {}", id, e),
+        }
+        if let Some(waiter) = WAITERS.lock().unwrap().remove(&id) {
+            waiter.send(result).ok();
+        }
+    }
+}
+
+fn enqueue(
+    app: &AppHandle,
+    url: String,
+    dest: PathBuf,
+    sha256: Option<String>,
+    waiter: Option<Waiter>,
+) -> Download {
+    let job = {
+        let mut downloads = DOWNLOADS.lock().unwrap();
+        let job = Download {
+            id: downloads.last().map_or(1, |d| d.id + 1),
+            url,
+            dest: dest.to_string_lossy().into_owned(),
+            sha256,
+            state: DownloadState::Queued,
+            received: 0,
+            total: None,
+            resumed_from: 0,
+            error: None,
+        };
+        downloads.push(job.clone());
+        job
+    };
+    if let Some(waiter) = waiter {
+        WAITERS.lock().unwrap().insert(job.id, waiter);
+    }
+    let queue = QUEUE.get_or_init(|| {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tauri::async_runtime::spawn(worker(app.clone(), rx));
+        tx
+    });
+    queue.send(job.id).ok();
+    app.emit_all("model-download-progress", &job).ok();
+    job
+}
+
+/// Queues `url` for `dest` and waits until it is downloaded (and verified).
+pub async fn fetch(
+    app: &AppHandle,
+    url: String,
+    dest: PathBuf,
+    sha256: Option<String>,
+) -> Result<PathBuf, String> {
+    let (tx, rx) = oneshot::channel();
+    enqueue(app, url, dest, sha256, Some(tx));
+    rx.await
+        .map_err(|_| "Download worker stopped".to_string())?
+}
+
+fn check_name(name: &str) -> Result<(), String> {
+    let ok = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if ok {
+        Ok(())
+    } else {
+        Err(format!("Invalid file name {:?}", name))
+    }
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Queues a download into app_dir()/models/<name>. Returns immediately;
+/// follow it with "model-download-progress" or `list_model_downloads`.
+#[tauri::command]
+pub fn queue_model_download(
+    app: AppHandle,
+    url: String,
+    name: String,
+    sha256: Option<String>,
+) -> Result<Download, String> {
+    check_name(&name)?;
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        return Err("Only http(s) URLs can be downloaded".into());
+    }
+    Ok(enqueue(&app, url, models_dir().join(name), sha256, None))
+}
+
+/// All downloads of this session, oldest first.
+#[tauri::command]
+pub fn list_model_downloads() -> Vec<Download> {
+    DOWNLOADS.lock().unwrap().clone()
+}
+
+/// Cancels a queued or running download and drops its partial data.
+#[tauri::command]
+pub fn cancel_model_download(app: AppHandle, id: u64) -> Result<(), String> {
+    let job = snapshot(id).ok_or("No such download")?;
+    if !matches!(
+        job.state,
+        DownloadState::Queued | DownloadState::Downloading
+    ) {
+        return Err("Download already finished".into());
+    }
+    update(&app, id, |d| d.state = DownloadState::Cancelled);
+    Ok(())
+}
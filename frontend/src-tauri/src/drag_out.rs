@@ -0,0 +1,164 @@
+// Drag out — native drag source for dragging snippets into other apps.
+//
+// The frontend calls `start_snippet_drag` from a mousedown/dragstart handler.
+// Rust writes the snippet body to a temp file and starts an OS drag session
+// with it, so it can be dropped into Finder/Explorer (a file) or an email
+// client (an attachment). On macOS the snippet can also be dragged as plain
+// text.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde_json::Value;
+use tauri::Window;
+
+use crate::backend;
+
+/// Drag files older than this are cleaned up when a new drag starts.
+const STALE_AFTER: Duration = Duration::from_secs(60 * 60);
+
+fn drag_dir() -> PathBuf {
+    std::env::temp_dir().join("pin-up-ai-drag")
+}
+
+fn extension_for(language: Option<&str>) -> &'static str {
+    match language.unwrap_or("").to_lowercase().as_str() {
+        "python" | "py" => "py",
+        "javascript" | "js" => "js",
+        "typescript" | "ts" => "ts",
+        "rust" | "rs" => "rs",
+        "go" => "go",
+        "java" => "java",
+        "c" => "c",
+        "cpp" | "c++" => "cpp",
+        "csharp" | "c#" => "cs",
+        "ruby" | "rb" => "rb",
+        "shell" | "bash" | "sh" => "sh",
+        "sql" => "sql",
+        "json" => "json",
+        "yaml" | "yml" => "yaml",
+        "html" => "html",
+        "css" => "css",
+        "" | "markdown" | "md" => "md",
+        _ => "txt",
+    }
+}
+
+/// Title made safe for every file system, never empty.
+fn file_name(snippet: &Value) -> String {
+    let title: String = snippet["title"]
+        .as_str()
+        .unwrap_or("")
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .take(80)
+        .collect();
+    let title = title.trim().trim_matches('.').to_string();
+    let title = if title.is_empty() {
+        "snippet".to_string()
+    } else {
+        title
+    };
+    format!("{}.{}", title, extension_for(snippet["language"].as_str()))
+}
+
+fn remove_stale() {
+    let Ok(entries) = std::fs::read_dir(drag_dir()) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.elapsed().ok())
+            .is_some_and(|age| age > STALE_AFTER);
+        if stale {
+            std::fs::remove_dir_all(entry.path()).ok();
+        }
+    }
+}
+
+/// Writes the body to `<tmp>/pin-up-ai-drag/<id>/<title>.<ext>`.
+fn write_temp_file(id: &str, snippet: &Value, body: &str) -> Result<PathBuf, String> {
+    remove_stale();
+    let dir = drag_dir().join(id.replace(|c: char| !c.is_ascii_alphanumeric(), "_"));
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(file_name(snippet));
+    std::fs::write(&path, body).map_err(|e| format!("Could not write drag file: {e}"))?;
+    Ok(path)
+}
+
+/// Small solid badge used as the drag preview (macOS requires one).
+fn preview_image() -> Vec<u8> {
+    let image = image::RgbaImage::from_pixel(48, 48, image::Rgba([99, 102, 241, 230]));
+    let mut png = std::io::Cursor::new(Vec::new());
+    image.write_to(&mut png, image::ImageFormat::Png).ok();
+    png.into_inner()
+}
+
+fn begin(window: &Window, item: drag::DragItem) -> Result<(), String> {
+    let on_drop = |result: drag::DragResult| log::info!("Snippet drag ended: {:?}", result);
+    let image = drag::Image::Raw(preview_image());
+    #[cfg(target_os = "linux")]
+    let result = {
+        let gtk_window = window.gtk_window().map_err(|e| e.to_string())?;
+        drag::start_drag(&gtk_window, item, image, on_drop)
+    };
+    #[cfg(not(target_os = "linux"))]
+    let result = drag::start_drag(window, item, image, on_drop);
+    result.map_err(|e| format!("Could not start drag: {e}"))
+}
+
+#[cfg(target_os = "macos")]
+fn text_item(body: String) -> drag::DragItem {
+    drag::DragItem::Data {
+        provider: Box::new(move |_| Some(body.clone().into_bytes())),
+        types: vec!["public.utf8-plain-text".into()],
+    }
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Starts dragging snippet `id` out of `window`. `as_text` drags plain text
+/// where the platform supports it (macOS); otherwise a file is dragged.
+#[tauri::command]
+pub async fn start_snippet_drag(
+    window: Window,
+    id: String,
+    as_text: Option<bool>,
+) -> Result<(), String> {
+    let snippet = backend::get(&backend::snippet_path(&id)?).await?;
+    let body = snippet["body"]
+        .as_str()
+        .ok_or("Snippet has no body")?
+        .to_string();
+
+    #[cfg(target_os = "macos")]
+    if as_text.unwrap_or(false) {
+        let target = window.clone();
+        return window
+            .run_on_main_thread(move || {
+                if let Err(e) = begin(&target, text_item(body)) {
+                    log::warn!("{}", e);
+                }
+            })
+            .map_err(|e| e.to_string());
+    }
+    #[cfg(not(target_os = "macos"))]
+    let _ = as_text;
+
+    let path = write_temp_file(&id, &snippet, &body)?;
+    let target = window.clone();
+    // Drag sessions must start on the UI thread.
+    window
+        .run_on_main_thread(move || {
+            if let Err(e) = begin(&target, drag::DragItem::Files(vec![path])) {
+                log::warn!("{}", e);
+            }
+        })
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,707 @@
+// Email in — emails sent to a dedicated IMAP folder become snippets.
+//
+// The user points this at a mailbox and a folder (or Gmail label) that a
+// filter fills, e.g. mail sent to a "+pinup" address. While enabled, the
+// folder is checked every `interval_minutes` (times the poll factor) for
+// unread messages; each is parsed (see mime.rs) and goes through
+// `capture::submit` with source "email": the subject as title, the HTML body
+// converted to Markdown (the plain text one otherwise), and every attachment
+// stored and linked. Inline images (cid:) are stored too; remote images are
+// not fetched, so tracking pixels stay silent, and become plain links.
+// Imported messages are marked read, or deleted with delete_after_import.
+// The highest UID imported is also remembered (data_dir()/email-in.json,
+// per UIDVALIDITY), so a message whose flag couldn't be set isn't filed
+// twice.
+//
+// There is no IMAP crate in the shell, so this speaks the few commands it
+// needs itself (LOGIN, SELECT, UID SEARCH/FETCH/STORE, EXPUNGE) over TLS,
+// implicit or STARTTLS, never in the clear. The password (an app password,
+// usually) is kept in the OS keyring (see keyring.rs) under
+// "imap:<username>@<host>", never in the config.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_native_tls::{native_tls, TlsStream};
+
+use crate::capture::{self, Capture};
+use crate::sync_log::blocking;
+use crate::{attachments, backend, clipper, config, keyring, mime, performance, suspend};
+
+const SOURCE: &str = "email";
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const TIMEOUT: Duration = Duration::from_secs(60);
+const MAX_MESSAGE_BYTES: usize = 50 * 1024 * 1024;
+const MAX_PER_CHECK: usize = 50;
+
+static LITERAL: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{(\d+)\+?\}$").unwrap());
+static UIDVALIDITY: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[UIDVALIDITY (\d+)\]").unwrap());
+static FETCH_UID: Lazy<Regex> = Lazy::new(|| Regex::new(r"\bUID (\d+)").unwrap());
+static FETCH_SIZE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\bRFC822\.SIZE (\d+)").unwrap());
+static MD_IMAGE: Lazy<Regex> = Lazy::new(|| Regex::new(r"!\[([^\]]*)\]\(([^)\s]+)\)").unwrap());
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct EmailInConfig {
+    pub enabled: bool,
+    pub host: Option<String>,
+    pub port: u16,
+    /// STARTTLS on `port` rather than TLS from the start.
+    pub starttls: bool,
+    pub username: Option<String>,
+    pub folder: String,
+    pub interval_minutes: u64,
+    pub collection: Option<String>,
+    pub tags: Vec<String>,
+    /// Deletes imported messages rather than marking them read.
+    pub delete_after_import: bool,
+}
+
+impl Default for EmailInConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: None,
+            port: 993,
+            starttls: false,
+            username: None,
+            folder: "Pin-Up".into(),
+            interval_minutes: 5,
+            collection: None,
+            tags: Vec::new(),
+            delete_after_import: false,
+        }
+    }
+}
+
+/// What has been imported from the folder.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+struct Cursor {
+    /// Identifies the account and folder the UIDs belong to.
+    mailbox: String,
+    uidvalidity: u32,
+    last_uid: u32,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct EmailCheck {
+    imported: usize,
+    /// Too large, or unreadable.
+    skipped: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct EmailInStatus {
+    config: EmailInConfig,
+    /// The password is in the keyring.
+    has_password: bool,
+    checking: bool,
+    last_check: Option<EmailCheck>,
+    last_error: Option<String>,
+}
+
+static CHECKING: AtomicBool = AtomicBool::new(false);
+static LAST: Mutex<(Option<EmailCheck>, Option<String>)> = Mutex::new((None, None));
+static LAST_RUN: Mutex<Option<Instant>> = Mutex::new(None);
+
+fn account(username: &str, host: &str) -> String {
+    format!("imap:{username}@{host}")
+}
+
+async fn password(cfg: &EmailInConfig) -> Result<Option<String>, String> {
+    let (Some(username), Some(host)) = (&cfg.username, &cfg.host) else {
+        return Ok(None);
+    };
+    let account = account(username, host);
+    blocking(move || keyring::get(&account)).await
+}
+
+fn cursor_path() -> PathBuf {
+    crate::data_dir().join("email-in.json")
+}
+
+fn load_cursor() -> Cursor {
+    std::fs::read_to_string(cursor_path())
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save_cursor(cursor: &Cursor) {
+    let text = serde_json::to_string_pretty(cursor).unwrap_or_default();
+    std::fs::create_dir_all(crate::data_dir()).ok();
+    if let Err(e) = std::fs::write(cursor_path(), text) {
+        log::error!("Could not save email cursor: {}", e);
+    }
+}
+
+// ── IMAP ───────────────────────────────────────────────────────────────────
+/// One response line, with the literals ({n} followed by n bytes) it carried.
+struct Reply {
+    text: String,
+    literals: Vec<Vec<u8>>,
+}
+
+async fn read_reply<S>(stream: &mut BufReader<S>) -> Result<Reply, String>
+where
+    S: tokio::io::AsyncRead + Unpin,
+{
+    let mut reply = Reply {
+        text: String::new(),
+        literals: Vec::new(),
+    };
+    loop {
+        let mut line = Vec::new();
+        let read = stream
+            .read_until(b'\n', &mut line)
+            .await
+            .map_err(|e| format!("Mail server connection failed: {e}"))?;
+        if read == 0 {
+            return Err("The mail server closed the connection".into());
+        }
+        let line = String::from_utf8_lossy(&line);
+        let line = line.trim_end_matches(['\r', '\n']);
+        reply.text.push_str(line);
+        let Some(size) = LITERAL
+            .captures(line)
+            .and_then(|c| c[1].parse::<usize>().ok())
+        else {
+            return Ok(reply);
+        };
+        if size > MAX_MESSAGE_BYTES {
+            return Err(format!(
+                "The mail server sent more than {} MB",
+                MAX_MESSAGE_BYTES >> 20
+            ));
+        }
+        let mut literal = vec![0; size];
+        stream
+            .read_exact(&mut literal)
+            .await
+            .map_err(|e| format!("Mail server connection failed: {e}"))?;
+        reply.literals.push(literal);
+    }
+}
+
+/// An IMAP quoted string.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// A folder name in IMAP's modified UTF-7.
+fn mailbox_name(name: &str) -> String {
+    fn flush(pending: &mut Vec<u16>, out: &mut String) {
+        if pending.is_empty() {
+            return;
+        }
+        let bytes: Vec<u8> = pending.drain(..).flat_map(u16::to_be_bytes).collect();
+        let encoded = base64::engine::general_purpose::STANDARD_NO_PAD.encode(bytes);
+        out.push('&');
+        out.push_str(&encoded.replace('/', ","));
+        out.push('-');
+    }
+    let mut out = String::new();
+    let mut pending = Vec::new();
+    for c in name.chars() {
+        if (' '..='~').contains(&c) {
+            flush(&mut pending, &mut out);
+            match c {
+                '&' => out.push_str("&-"),
+                c => out.push(c),
+            }
+        } else {
+            pending.extend_from_slice(c.encode_utf16(&mut [0; 2]));
+        }
+    }
+    flush(&mut pending, &mut out);
+    out
+}
+
+struct Imap {
+    stream: BufReader<TlsStream<TcpStream>>,
+    tag: u32,
+}
+
+impl Imap {
+    async fn connect(host: &str, port: u16, starttls: bool) -> Result<Self, String> {
+        let tcp = tokio::time::timeout(TIMEOUT, TcpStream::connect((host, port)))
+            .await
+            .map_err(|_| format!("Timed out connecting to {host}"))?
+            .map_err(|e| format!("Could not connect to {host}:{port}: {e}"))?;
+        let connector = native_tls::TlsConnector::new().map_err(|e| e.to_string())?;
+        let connector = tokio_native_tls::TlsConnector::from(connector);
+        let tcp = if starttls {
+            let mut plain = BufReader::new(tcp);
+            greeting(&mut plain).await?;
+            plain
+                .get_mut()
+                .write_all(b"s STARTTLS\r\n")
+                .await
+                .map_err(|e| format!("Mail server connection failed: {e}"))?;
+            loop {
+                let reply = read_reply(&mut plain).await?;
+                if let Some(rest) = reply.text.strip_prefix("s ") {
+                    if !rest.starts_with("OK") {
+                        return Err(format!("{host} refused STARTTLS: {rest}"));
+                    }
+                    break;
+                }
+            }
+            plain.into_inner()
+        } else {
+            tcp
+        };
+        let tls = tokio::time::timeout(TIMEOUT, connector.connect(host, tcp))
+            .await
+            .map_err(|_| format!("Timed out connecting to {host}"))?
+            .map_err(|e| format!("Secure connection to {host} failed: {e}"))?;
+        let mut imap = Self {
+            stream: BufReader::new(tls),
+            tag: 0,
+        };
+        if !starttls {
+            greeting(&mut imap.stream).await?;
+        }
+        Ok(imap)
+    }
+
+    /// Runs `command` and returns its untagged replies.
+    async fn command(&mut self, command: &str) -> Result<Vec<Reply>, String> {
+        tokio::time::timeout(TIMEOUT, self.run(command))
+            .await
+            .map_err(|_| "The mail server stopped responding".to_string())?
+    }
+
+    async fn run(&mut self, command: &str) -> Result<Vec<Reply>, String> {
+        self.tag += 1;
+        let tag = format!("a{}", self.tag);
+        self.stream
+            .get_mut()
+            .write_all(format!("{tag} {command}\r\n").as_bytes())
+            .await
+            .map_err(|e| format!("Mail server connection failed: {e}"))?;
+        let mut untagged = Vec::new();
+        loop {
+            let reply = read_reply(&mut self.stream).await?;
+            if let Some(rest) = reply.text.strip_prefix(&format!("{tag} ")) {
+                if rest.starts_with("OK") {
+                    return Ok(untagged);
+                }
+                let verb = command.split(' ').next().unwrap_or_default();
+                return Err(format!("{verb} failed: {rest}"));
+            }
+            if reply.text.starts_with("* BYE") {
+                return Err(format!("The mail server hung up: {}", &reply.text[5..]));
+            }
+            untagged.push(reply);
+        }
+    }
+
+    async fn login(&mut self, username: &str, password: &str) -> Result<(), String> {
+        self.command(&format!("LOGIN {} {}", quote(username), quote(password)))
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("Could not sign in: {e}"))
+    }
+
+    /// Selects `folder` and returns its UIDVALIDITY.
+    async fn select(&mut self, folder: &str) -> Result<u32, String> {
+        let replies = self
+            .command(&format!("SELECT {}", quote(&mailbox_name(folder))))
+            .await
+            .map_err(|e| format!("Could not open the folder {folder:?}: {e}"))?;
+        Ok(replies
+            .iter()
+            .find_map(|r| UIDVALIDITY.captures(&r.text)?[1].parse().ok())
+            .unwrap_or(0))
+    }
+
+    async fn unread(&mut self) -> Result<Vec<u32>, String> {
+        let replies = self.command("UID SEARCH UNSEEN").await?;
+        let mut uids: Vec<u32> = replies
+            .iter()
+            .filter_map(|r| r.text.strip_prefix("* SEARCH"))
+            .flat_map(|rest| rest.split_whitespace().filter_map(|n| n.parse().ok()))
+            .collect();
+        uids.sort_unstable();
+        Ok(uids)
+    }
+
+    /// Sizes of the messages in `uids`.
+    async fn sizes(&mut self, uids: &[u32]) -> Result<Vec<(u32, usize)>, String> {
+        let set: Vec<String> = uids.iter().map(u32::to_string).collect();
+        let replies = self
+            .command(&format!("UID FETCH {} (RFC822.SIZE)", set.join(",")))
+            .await?;
+        Ok(replies
+            .iter()
+            .filter_map(|r| {
+                let uid = FETCH_UID.captures(&r.text)?[1].parse().ok()?;
+                let size = FETCH_SIZE.captures(&r.text)?[1].parse().ok()?;
+                Some((uid, size))
+            })
+            .collect())
+    }
+
+    /// The raw message, without marking it read.
+    async fn message(&mut self, uid: u32) -> Result<Vec<u8>, String> {
+        let replies = self
+            .command(&format!("UID FETCH {uid} (BODY.PEEK[])"))
+            .await?;
+        replies
+            .into_iter()
+            .find(|r| {
+                FETCH_UID
+                    .captures(&r.text)
+                    .is_some_and(|c| c[1] == uid.to_string())
+            })
+            .and_then(|r| r.literals.into_iter().next())
+            .ok_or_else(|| format!("The mail server sent no message {uid}"))
+    }
+
+    async fn done(&mut self, uid: u32, delete: bool) -> Result<(), String> {
+        let flags = if delete { "\\Seen \\Deleted" } else { "\\Seen" };
+        self.command(&format!("UID STORE {uid} +FLAGS.SILENT ({flags})"))
+            .await
+            .map(|_| ())
+    }
+}
+
+async fn greeting<S>(stream: &mut BufReader<S>) -> Result<(), String>
+where
+    S: tokio::io::AsyncRead + Unpin,
+{
+    let reply = tokio::time::timeout(TIMEOUT, read_reply(stream))
+        .await
+        .map_err(|_| "The mail server didn't greet".to_string())??;
+    if !reply.text.starts_with("* OK") {
+        return Err(format!("The mail server refused: {}", reply.text));
+    }
+    Ok(())
+}
+
+// ── Messages ───────────────────────────────────────────────────────────────
+fn extension(part: &mime::Part) -> String {
+    let from_name = part
+        .filename
+        .as_deref()
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, ext)| ext)
+        .filter(|ext| ext.len() <= 8 && ext.chars().all(|c| c.is_ascii_alphanumeric()));
+    let from_type = part.content_type.split('/').nth(1).map(|sub| match sub {
+        "jpeg" => "jpg",
+        "svg+xml" => "svg",
+        "plain" => "txt",
+        "rfc822" => "eml",
+        sub => sub,
+    });
+    from_name
+        .or(from_type.filter(|sub| sub.chars().all(|c| c.is_ascii_alphanumeric())))
+        .unwrap_or("bin")
+        .to_lowercase()
+}
+
+/// A snippet for `raw`: its body as Markdown, its parts as attachments.
+async fn to_capture(cfg: &EmailInConfig, raw: Vec<u8>) -> Result<Capture, String> {
+    let (message, stored) = blocking(move || {
+        let message = mime::parse(&raw);
+        let mut stored = Vec::new();
+        for part in &message.parts {
+            match attachments::store_bytes(&part.data, &extension(part), None) {
+                Ok(attachment) => stored.push(Some(attachment.hash)),
+                Err(e) => {
+                    log::warn!("Email in: could not store {:?}: {}", part.filename, e);
+                    stored.push(None);
+                }
+            }
+        }
+        Ok((message, stored))
+    })
+    .await?;
+
+    let mut linked = vec![false; message.parts.len()];
+    let mut body = match (&message.html, &message.text) {
+        (Some(html), _) => {
+            let base = reqwest::Url::parse("about:blank").unwrap();
+            let markdown = clipper::convert(html, &base);
+            MD_IMAGE
+                .replace_all(&markdown, |caps: &Captures| {
+                    let (alt, url) = (&caps[1], &caps[2]);
+                    if let Some(id) = url.strip_prefix("cid:") {
+                        let found = message
+                            .parts
+                            .iter()
+                            .position(|p| p.content_id.as_deref().is_some_and(|cid| cid == id));
+                        if let Some((i, Some(hash))) = found.map(|i| (i, &stored[i])) {
+                            linked[i] = true;
+                            return format!("![{alt}](attachment:{hash})");
+                        }
+                        return String::new();
+                    }
+                    // Not fetched: remote images in email track their readers.
+                    if alt.trim().is_empty() {
+                        String::new()
+                    } else {
+                        format!("[{alt}]({url})")
+                    }
+                })
+                .into_owned()
+        }
+        (None, Some(text)) => text.trim().to_string(),
+        (None, None) => String::new(),
+    };
+    let files: Vec<String> = message
+        .parts
+        .iter()
+        .zip(&stored)
+        .zip(&linked)
+        .filter(|(_, linked)| !**linked)
+        .filter_map(|((part, hash), _)| {
+            let hash = hash.as_ref()?;
+            let name = part
+                .filename
+                .clone()
+                .unwrap_or_else(|| format!("attachment.{}", extension(part)))
+                .replace(['[', ']'], "");
+            let bang = if part.content_type.starts_with("image/") {
+                "!"
+            } else {
+                ""
+            };
+            Some(format!("{bang}[{name}](attachment:{hash})"))
+        })
+        .collect();
+    if !files.is_empty() {
+        if !body.is_empty() {
+            body.push_str("\n\n");
+        }
+        body.push_str(&files.join("\n\n"));
+    }
+
+    let title = message.subject.clone().or_else(|| {
+        message
+            .from
+            .as_ref()
+            .map(|from| format!("Email from {from}"))
+    });
+    Ok(Capture {
+        title,
+        body,
+        source: SOURCE.into(),
+        source_url: None,
+        tags: cfg.tags.clone(),
+        collections: cfg.collection.iter().cloned().collect(),
+    })
+}
+
+async fn run(app: &AppHandle, cfg: &EmailInConfig) -> Result<EmailCheck, String> {
+    let (Some(host), Some(username)) = (&cfg.host, &cfg.username) else {
+        return Err("No mail server set up".into());
+    };
+    let password = password(cfg)
+        .await?
+        .ok_or("Enter the mail password again")?;
+    let mut imap = Imap::connect(host, cfg.port, cfg.starttls).await?;
+    imap.login(username, &password).await?;
+    let uidvalidity = imap.select(&cfg.folder).await?;
+
+    let mailbox = format!("{}/{}", account(username, host), cfg.folder);
+    let mut cursor = load_cursor();
+    if cursor.mailbox != mailbox || cursor.uidvalidity != uidvalidity {
+        cursor = Cursor {
+            mailbox,
+            uidvalidity,
+            last_uid: 0,
+        };
+    }
+    let uids: Vec<u32> = imap
+        .unread()
+        .await?
+        .into_iter()
+        .filter(|uid| *uid > cursor.last_uid)
+        .take(MAX_PER_CHECK)
+        .collect();
+
+    let mut check = EmailCheck::default();
+    if !uids.is_empty() {
+        for (uid, size) in imap.sizes(&uids).await? {
+            let raw = if size > MAX_MESSAGE_BYTES {
+                Err(format!("larger than {} MB", MAX_MESSAGE_BYTES >> 20))
+            } else {
+                imap.message(uid).await
+            };
+            let result = match raw {
+                Ok(raw) => match to_capture(cfg, raw).await {
+                    Ok(capture) => capture::submit(app, capture).await.map(|_| ()),
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => check.imported += 1,
+                // Queued in the outbox, so it is filed once the backend is back.
+                Err(e) if backend::is_unreachable(&e) => check.imported += 1,
+                Err(e) => {
+                    log::warn!("Email in: skipped message {}: {}", uid, e);
+                    check.skipped += 1;
+                }
+            }
+            cursor.last_uid = cursor.last_uid.max(uid);
+            save_cursor(&cursor);
+            if let Err(e) = imap.done(uid, cfg.delete_after_import).await {
+                log::warn!("Email in: could not mark message {} done: {}", uid, e);
+            }
+        }
+        if cfg.delete_after_import {
+            imap.command("EXPUNGE").await.ok();
+        }
+    }
+    imap.command("LOGOUT").await.ok();
+    Ok(check)
+}
+
+async fn check(app: &AppHandle) -> Result<EmailCheck, String> {
+    let cfg = config::current(app).email_in;
+    if CHECKING.swap(true, Ordering::SeqCst) {
+        return Err("Already checking for email".into());
+    }
+    let result = run(app, &cfg).await;
+    CHECKING.store(false, Ordering::SeqCst);
+    *LAST_RUN.lock().unwrap() = Some(Instant::now());
+
+    let mut last = LAST.lock().unwrap();
+    match &result {
+        Ok(check) => {
+            if check.imported + check.skipped > 0 {
+                log::info!(
+                    "Email in: {} imported, {} skipped",
+                    check.imported,
+                    check.skipped
+                );
+                app.emit_all("email-imported", check).ok();
+            }
+            *last = (Some(check.clone()), None);
+        }
+        Err(e) => {
+            log::warn!("Email in failed: {}", e);
+            last.1 = Some(e.clone());
+        }
+    }
+    result
+}
+
+async fn status(app: &AppHandle) -> EmailInStatus {
+    let cfg = config::current(app).email_in;
+    let has_password = password(&cfg).await.ok().flatten().is_some();
+    let (last_check, last_error) = LAST.lock().unwrap().clone();
+    EmailInStatus {
+        config: cfg,
+        has_password,
+        checking: CHECKING.load(Ordering::SeqCst),
+        last_check,
+        last_error,
+    }
+}
+
+/// Checks the folder on the configured interval while enabled.
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let cfg = config::current(&handle).email_in;
+            if !cfg.enabled || cfg.host.is_none() || suspend::paused() {
+                continue;
+            }
+            let factor = performance::capabilities(&handle).poll_factor as u32;
+            let interval = Duration::from_secs(cfg.interval_minutes.max(1) * 60) * factor;
+            let due = LAST_RUN
+                .lock()
+                .unwrap()
+                .map_or(true, |at| at.elapsed() >= interval);
+            if due && crate::BACKEND_PORT.load(Ordering::SeqCst) != 0 {
+                check(&handle).await.ok();
+            }
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub async fn get_email_in_status(app: AppHandle) -> EmailInStatus {
+    status(&app).await
+}
+
+/// Saves `settings` and turns email in on once the server accepts them. The
+/// password is saved to the keyring; leave it out to keep the saved one.
+#[tauri::command]
+pub async fn set_email_in(
+    app: AppHandle,
+    mut settings: EmailInConfig,
+    password: Option<String>,
+) -> Result<EmailInStatus, String> {
+    let host = settings.host.as_deref().map(str::trim).unwrap_or_default();
+    let username = settings
+        .username
+        .as_deref()
+        .map(str::trim)
+        .unwrap_or_default();
+    if host.is_empty() || username.is_empty() {
+        return Err("Enter the mail server and user name".into());
+    }
+    let (host, username) = (host.to_string(), username.to_string());
+    settings.folder = settings.folder.trim().to_string();
+    if settings.folder.is_empty() {
+        return Err("Choose the folder to import from".into());
+    }
+    let account = account(&username, &host);
+    let saved = {
+        let account = account.clone();
+        blocking(move || keyring::get(&account)).await?
+    };
+    let password = password
+        .filter(|p| !p.is_empty())
+        .or(saved)
+        .ok_or("Enter the mail password")?;
+
+    let mut imap = Imap::connect(&host, settings.port, settings.starttls).await?;
+    imap.login(&username, &password).await?;
+    imap.select(&settings.folder).await?;
+    imap.command("LOGOUT").await.ok();
+
+    blocking(move || keyring::set(&account, &password)).await?;
+    settings.enabled = true;
+    settings.host = Some(host);
+    settings.username = Some(username);
+    settings.tags.retain(|t| !t.trim().is_empty());
+    settings.collection = settings.collection.filter(|c| !c.trim().is_empty());
+    config::update(&app, |c| c.email_in = settings)?;
+    Ok(status(&app).await)
+}
+
+/// Turns email in off; `forget` also removes the saved password.
+#[tauri::command]
+pub async fn disable_email_in(app: AppHandle, forget: bool) -> Result<EmailInStatus, String> {
+    let cfg = config::current(&app).email_in;
+    config::update(&app, |c| c.email_in.enabled = false)?;
+    if let (true, Some(username), Some(host)) = (forget, cfg.username, cfg.host) {
+        let account = account(&username, &host);
+        blocking(move || keyring::delete(&account)).await?;
+    }
+    Ok(status(&app).await)
+}
+
+#[tauri::command]
+pub async fn check_email_now(app: AppHandle) -> Result<EmailCheck, String> {
+    check(&app).await
+}
@@ -0,0 +1,445 @@
+// Database encryption — opt-in SQLCipher keyed by a passphrase.
+//
+// `enable_database_encryption(passphrase)` derives a 32-byte key with
+// Argon2id (random salt; parameters fixed below), stores the salt and a key
+// check in `encryption` of the shell config, and restarts the sidecar with
+// the key in PINUP_DB_KEY. The backend then converts the plaintext database
+// with sqlcipher_export before opening it. The key itself never touches the
+// disk: on the next launch setup opens unlock.html instead of spawning the
+// sidecar, and `unlock_database(passphrase)` starts it once the passphrase
+// checks out. There is no recovery without the passphrase.
+//
+// Backups taken from then on are encrypted with the same key, and the
+// backend encrypts older ones and the pre-restore database as it starts
+// with the key. Plaintext copies it can't reach or convert (the sidecar's
+// pre-update copy, a half-finished restore) are listed in the status as
+// `plaintext_copies`; `purge_plaintext_copies` overwrites them with zeros
+// and deletes them.
+//
+// The shell's own stores that hold snippet content — version history, the
+// capture outbox and sync conflicts — go through `read_store`/`write_store`,
+// which seal them with XChaCha20-Poly1305 under a key derived from the
+// database key while it is in memory. Files written before encryption was
+// on are sealed when the database is unlocked; any that stay plain are
+// listed in `plaintext_copies` too, and purging seals them in place.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager, WindowBuilder, WindowUrl};
+
+use crate::{backups, config, i18n, sidecar};
+
+const WINDOW_LABEL: &str = "unlock";
+pub const MIN_PASSPHRASE: usize = 8;
+/// Argon2id: 64 MiB, 3 passes, 1 lane. Changing these locks users out.
+pub const ARGON2_MEMORY_KIB: u32 = 64 * 1024;
+pub const ARGON2_PASSES: u32 = 3;
+const SQLITE_MAGIC: &[u8; 16] = b"SQLite format 3\0";
+/// SQLCipher 4 page size; its header is ciphertext, so only the length can
+/// be checked.
+pub const CIPHER_PAGE_SIZE: u64 = 4096;
+/// Starts a store file sealed by `write_store`, before the nonce.
+const SEALED_MAGIC: &[u8; 8] = b"PINUPSL1";
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct EncryptionConfig {
+    pub enabled: bool,
+    /// Hex Argon2 salt.
+    pub salt: Option<String>,
+    /// Hex SHA-256 of the derived key, to reject a wrong passphrase before
+    /// the backend tries it.
+    pub key_check: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct EncryptionStatus {
+    enabled: bool,
+    unlocked: bool,
+    /// Whether pinup.db on disk is no longer plain SQLite.
+    database_encrypted: bool,
+    /// Unencrypted copies of the database, and unsealed history, outbox
+    /// and conflict files, still on disk.
+    plaintext_copies: Vec<String>,
+}
+
+/// Hex key while unlocked.
+static KEY: Mutex<Option<String>> = Mutex::new(None);
+
+/// Argon2id with one lane and a 32-byte output.
+pub fn argon2_key(
+    passphrase: &str,
+    salt: &[u8],
+    memory_kib: u32,
+    passes: u32,
+) -> Result<[u8; 32], String> {
+    let params = Params::new(memory_kib, passes, 1, Some(32)).map_err(|e| e.to_string())?;
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Key derivation failed: {e}"))?;
+    Ok(key)
+}
+
+fn derive(passphrase: &str, salt: &[u8]) -> Result<String, String> {
+    argon2_key(passphrase, salt, ARGON2_MEMORY_KIB, ARGON2_PASSES).map(hex::encode)
+}
+
+fn key_check(key: &str) -> String {
+    hex::encode(Sha256::digest(format!("pinup-db-key:{key}")))
+}
+
+/// Some(true) for plain SQLite, None when `path` can't be read.
+fn plaintext(path: &Path) -> Option<bool> {
+    let mut header = [0u8; 16];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .ok()
+        .map(|_| &header == SQLITE_MAGIC)
+}
+
+fn database_encrypted() -> bool {
+    plaintext(&crate::db_path()) == Some(false)
+}
+
+/// History, the capture outbox and sync conflicts: the shell's files with
+/// snippet content.
+fn store_files() -> Vec<PathBuf> {
+    let data = crate::data_dir();
+    let mut files = vec![data.join("outbox.json"), data.join("sync-conflicts.json")];
+    let history = std::fs::read_dir(data.join("history"))
+        .into_iter()
+        .flatten();
+    files.extend(
+        history
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json")),
+    );
+    files
+}
+
+/// Some(false) for a file sealed by `write_store`, None when it can't be
+/// read.
+fn store_plaintext(path: &Path) -> Option<bool> {
+    let mut header = [0u8; 8];
+    let mut file = std::fs::File::open(path).ok()?;
+    let n = file.read(&mut header).ok()?;
+    Some(&header[..n] != SEALED_MAGIC)
+}
+
+fn store_key() -> Option<[u8; 32]> {
+    let key = KEY.lock().unwrap();
+    let key = key.as_ref()?;
+    Some(Sha256::digest(format!("pinup-store-key:{key}")).into())
+}
+
+fn seal(key: &[u8; 32], text: &str) -> Result<Vec<u8>, String> {
+    let mut nonce = [0u8; 24];
+    getrandom::getrandom(&mut nonce).map_err(|e| format!("No random source: {e}"))?;
+    let sealed = XChaCha20Poly1305::new(key.into())
+        .encrypt(XNonce::from_slice(&nonce), text.as_bytes())
+        .map_err(|_| "Encryption failed".to_string())?;
+    Ok([SEALED_MAGIC.as_slice(), &nonce, &sealed].concat())
+}
+
+/// Reads a store file written by `write_store`. A plain file (written
+/// while encryption was off) is returned as it is; a sealed one needs the
+/// database unlocked.
+pub fn read_store(path: &Path) -> std::io::Result<String> {
+    let data = std::fs::read(path)?;
+    let invalid = |msg: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, msg);
+    let Some(rest) = data.strip_prefix(SEALED_MAGIC.as_slice()) else {
+        return String::from_utf8(data).map_err(|_| invalid("not UTF-8"));
+    };
+    let key = store_key().ok_or_else(|| invalid("the database is locked"))?;
+    if rest.len() < 24 {
+        return Err(invalid("truncated"));
+    }
+    let (nonce, sealed) = rest.split_at(24);
+    let plain = XChaCha20Poly1305::new((&key).into())
+        .decrypt(XNonce::from_slice(nonce), sealed)
+        .map_err(|_| invalid("sealed with another key"))?;
+    String::from_utf8(plain).map_err(|_| invalid("not UTF-8"))
+}
+
+/// Replaces `path` with `text` through a temporary file, sealed while
+/// encryption is active.
+pub fn write_store(path: &Path, text: &str) -> Result<(), String> {
+    let data = match store_key() {
+        Some(key) => seal(&key, text)?,
+        None => text.as_bytes().to_vec(),
+    };
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    std::fs::write(&tmp, data).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, path).map_err(|e| e.to_string())
+}
+
+/// Seals the store files still in plain text, zeroing the plain copy.
+fn seal_stores() -> Result<(), String> {
+    let Some(key) = store_key() else {
+        return Err("The database is locked".into());
+    };
+    for path in store_files() {
+        if store_plaintext(&path) != Some(true) {
+            continue;
+        }
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Could not read {}: {e}", path.display()))?;
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+        std::fs::write(&tmp, seal(&key, &text)?).map_err(|e| e.to_string())?;
+        shred(&path).map_err(|e| format!("Could not overwrite {}: {e}", path.display()))?;
+        std::fs::rename(&tmp, &path).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Database files left unencrypted next to an encrypted pinup.db, then
+/// unsealed store files.
+fn plaintext_copies() -> Vec<PathBuf> {
+    let mut pre_restore = crate::db_path().into_os_string();
+    pre_restore.push(".pre_restore");
+    let mut candidates = vec![PathBuf::from(pre_restore)];
+    for dir in [
+        backups::backups_dir(),
+        sidecar::db_backup_dir(),
+        crate::data_dir().join("restore-staging"),
+    ] {
+        for entry in std::fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                let inner = std::fs::read_dir(&path).into_iter().flatten().flatten();
+                candidates.extend(inner.map(|e| e.path()));
+            } else {
+                candidates.push(path);
+            }
+        }
+    }
+    let stores = store_files()
+        .into_iter()
+        .filter(|path| store_plaintext(path) == Some(true));
+    candidates
+        .into_iter()
+        .filter(|path| plaintext(path) == Some(true))
+        .chain(stores)
+        .collect()
+}
+
+/// Overwrites `path` with zeros before deleting it.
+fn shred(path: &Path) -> std::io::Result<()> {
+    let mut left = std::fs::metadata(path)?.len();
+    let mut file = std::fs::OpenOptions::new().write(true).open(path)?;
+    let zeros = [0u8; 64 * 1024];
+    while left > 0 {
+        let n = left.min(zeros.len() as u64) as usize;
+        file.write_all(&zeros[..n])?;
+        left -= n as u64;
+    }
+    file.sync_all()?;
+    drop(file);
+    std::fs::remove_file(path)
+}
+
+fn status(app: &AppHandle) -> EncryptionStatus {
+    let encrypted = database_encrypted();
+    EncryptionStatus {
+        enabled: config::current(app).encryption.enabled,
+        unlocked: active(),
+        database_encrypted: encrypted,
+        plaintext_copies: if encrypted {
+            plaintext_copies()
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect()
+        } else {
+            Vec::new()
+        },
+    }
+}
+
+/// True while the key is in memory (the database may be encrypted).
+pub fn active() -> bool {
+    KEY.lock().unwrap().is_some()
+}
+
+/// Encryption is on but nobody has entered the passphrase yet.
+pub fn locked(app: &AppHandle) -> bool {
+    config::current(app).encryption.enabled && !active()
+}
+
+/// PINUP_DB_KEY for the sidecar while unlocked.
+pub fn backend_env() -> Vec<(String, String)> {
+    match KEY.lock().unwrap().as_ref() {
+        Some(key) => vec![("PINUP_DB_KEY".into(), key.clone())],
+        None => Vec::new(),
+    }
+}
+
+/// Shows the passphrase prompt; the sidecar starts from `unlock_database`.
+pub fn open_unlock(app: &AppHandle) {
+    if let Some(window) = app.get_window(WINDOW_LABEL) {
+        window.set_focus().ok();
+        return;
+    }
+    let result = WindowBuilder::new(app, WINDOW_LABEL, WindowUrl::App("unlock.html".into()))
+        .title(i18n::t("window.unlock"))
+        .inner_size(400.0, 240.0)
+        .resizable(false)
+        .center()
+        .build();
+    if let Err(e) = result {
+        log::error!("Could not open unlock window: {}", e);
+    }
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn get_encryption_status(app: AppHandle) -> EncryptionStatus {
+    status(&app)
+}
+
+/// Checks the passphrase and starts the backend with the derived key.
+#[tauri::command]
+pub async fn unlock_database(app: AppHandle, passphrase: String) -> Result<(), String> {
+    let cfg = config::current(&app).encryption;
+    let (Some(salt), Some(check)) = (cfg.salt, cfg.key_check) else {
+        return Err("Encryption is not set up".into());
+    };
+    if active() {
+        return Ok(());
+    }
+    let salt = hex::decode(salt).map_err(|e| format!("Corrupt encryption settings: {e}"))?;
+    let key = tauri::async_runtime::spawn_blocking(move || derive(&passphrase, &salt))
+        .await
+        .map_err(|e| e.to_string())??;
+    if key_check(&key) != check {
+        log::warn!("Database unlock failed: wrong passphrase");
+        return Err("Wrong passphrase".into());
+    }
+    *KEY.lock().unwrap() = Some(key);
+    log::info!("Database unlocked");
+    tauri::async_runtime::spawn_blocking(|| {
+        if let Err(e) = seal_stores() {
+            log::error!("Could not seal history and outbox: {}", e);
+        }
+    });
+    if let Some(window) = app.get_window(WINDOW_LABEL) {
+        window.close().ok();
+    }
+    crate::launch_backend(&app);
+    Ok(())
+}
+
+/// Turns encryption on: the backend converts the database on restart.
+#[tauri::command]
+pub async fn enable_database_encryption(app: AppHandle, passphrase: String) -> Result<(), String> {
+    let cfg = config::current(&app);
+    if cfg.encryption.enabled {
+        return Err("The database is already encrypted".into());
+    }
+    if cfg.backend.external_port.is_some() {
+        return Err("Encryption applies to the bundled backend only".into());
+    }
+    if passphrase.chars().count() < MIN_PASSPHRASE {
+        return Err(format!(
+            "Use a passphrase of at least {} characters",
+            MIN_PASSPHRASE
+        ));
+    }
+    let mut salt = [0u8; 16];
+    getrandom::getrandom(&mut salt).map_err(|e| format!("No random source: {e}"))?;
+    let key = tauri::async_runtime::spawn_blocking(move || derive(&passphrase, &salt))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    // The conversion needs room for a second copy of the database.
+    let db = crate::db_path();
+    let size = std::fs::metadata(&db).map(|m| m.len()).unwrap_or(0);
+    crate::disk::ensure_space(&db, size)?;
+
+    let check = key_check(&key);
+    *KEY.lock().unwrap() = Some(key);
+    config::update(&app, |c| {
+        c.encryption = EncryptionConfig {
+            enabled: true,
+            salt: Some(hex::encode(salt)),
+            key_check: Some(check),
+        };
+    })?;
+    log::info!("Encrypting database");
+    match crate::respawn_backend(&app).await {
+        Ok(port) if database_encrypted() => {
+            log::info!("Database encrypted");
+            tauri::async_runtime::spawn_blocking(|| {
+                if let Err(e) = seal_stores() {
+                    log::error!("Could not seal history and outbox: {}", e);
+                }
+            });
+            app.emit_all("backend-ready", port).ok();
+            Ok(())
+        }
+        result => {
+            let reason = match result {
+                Ok(_) => "the database was not converted".to_string(),
+                Err(e) => e,
+            };
+            log::error!("Database encryption failed: {}", reason);
+            *KEY.lock().unwrap() = None;
+            config::update(&app, |c| c.encryption = EncryptionConfig::default())?;
+            if let Ok(port) = crate::respawn_backend(&app).await {
+                app.emit_all("backend-ready", port).ok();
+            }
+            Err(format!("Could not encrypt the database: {reason}"))
+        }
+    }
+}
+
+/// Overwrites and deletes the unencrypted database copies in
+/// `plaintext_copies`; a backup folder goes as a whole, a database with its
+/// WAL and SHM files. Unsealed store files are sealed in place.
+#[tauri::command]
+pub async fn purge_plaintext_copies(app: AppHandle) -> Result<EncryptionStatus, String> {
+    if !database_encrypted() {
+        return Err("The database isn't encrypted yet".into());
+    }
+    tauri::async_runtime::spawn_blocking(|| {
+        seal_stores()?;
+        let backups_dir = backups::backups_dir();
+        for copy in plaintext_copies() {
+            let folder = copy
+                .parent()
+                .filter(|dir| dir.parent() == Some(&backups_dir));
+            let files: Vec<PathBuf> = match folder {
+                Some(dir) => std::fs::read_dir(dir)
+                    .map_err(|e| format!("Could not read {}: {e}", dir.display()))?
+                    .flatten()
+                    .map(|e| e.path())
+                    .filter(|p| p.is_file())
+                    .collect(),
+                None => sidecar::db_files(&copy),
+            };
+            for file in files.iter().filter(|f| f.exists()) {
+                shred(file).map_err(|e| format!("Could not delete {}: {e}", file.display()))?;
+            }
+            if let Some(dir) = folder {
+                std::fs::remove_dir_all(dir).ok();
+            }
+            log::info!("Purged plaintext copy {}", copy.display());
+        }
+        Ok::<_, String>(())
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    Ok(status(&app))
+}
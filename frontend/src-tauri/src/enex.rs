@@ -0,0 +1,407 @@
+// Evernote — streaming import of .enex exports.
+//
+// The file is read with a pull parser on a blocking thread, one <note> at a
+// time: resources are base64-decoded straight into the attachment store
+// (matched to their <en-media> tags by MD5), ENML is converted to Markdown,
+// and notes are grouped into batches of BATCH_NOTES (or BATCH_BYTES of body)
+// that go to the backend's /import as v1 bundles. Only the current note and
+// batch are in memory, whatever the size of the export.
+//
+// Title, tags, created/updated and source URL are kept; every note lands in
+// a collection named after the file (Evernote exports one per notebook).
+// It runs as a task (see tasks.rs) whose progress counts bytes of the file
+// read; a cancel stops at the next note, keeping the batches already sent.
+// Each batch goes through the duplicate policy (see dedupe.rs) on its way.
+
+use std::collections::HashMap;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use md5::{Digest, Md5};
+use once_cell::sync::Lazy;
+use quick_xml::events::Event;
+use regex::{Captures, Regex};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::Sha256;
+use tauri::AppHandle;
+use tokio::sync::mpsc;
+
+use crate::dedupe::{Dedupe, DuplicateCounts};
+use crate::tasks::{self, Task};
+use crate::{attachments, disk, exports, notion};
+
+const BATCH_NOTES: usize = 50;
+const BATCH_BYTES: usize = 8 * 1024 * 1024;
+/// Larger resources are skipped (and their link left out).
+const MAX_RESOURCE_BYTES: usize = 100 * 1024 * 1024;
+
+static EN_MEDIA: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?is)<en-media\b([^>]*?)/?>(\s*</en-media>)?").unwrap());
+static EN_TODO: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?is)<en-todo\b([^>]*?)/?>(\s*</en-todo>)?").unwrap());
+static ATTR_HASH: Lazy<Regex> = Lazy::new(|| Regex::new(r#"hash="([0-9a-fA-F]{32})""#).unwrap());
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct EnexImport {
+    notes: usize,
+    imported: usize,
+    failed: usize,
+    attachments: usize,
+    /// Resources over the size limit or with undecodable data.
+    skipped_resources: usize,
+    duplicates: DuplicateCounts,
+}
+
+#[derive(Default)]
+struct Resource {
+    data: String,
+    oversized: bool,
+    mime: String,
+    file_name: String,
+}
+
+#[derive(Default)]
+struct Note {
+    title: String,
+    content: String,
+    created: String,
+    updated: String,
+    tags: Vec<String>,
+    source_url: String,
+    /// MD5 of the data -> (attachment hash, file name, mime).
+    media: HashMap<String, (String, String, String)>,
+}
+
+/// A v1 import bundle being filled.
+struct Batch {
+    collection: String,
+    snippets: Vec<Value>,
+    tags: HashMap<String, String>,
+    snippet_tags: Vec<Value>,
+    bytes: usize,
+}
+
+impl Batch {
+    fn new(collection: &str) -> Self {
+        Self {
+            collection: collection.to_string(),
+            snippets: Vec::new(),
+            tags: HashMap::new(),
+            snippet_tags: Vec::new(),
+            bytes: 0,
+        }
+    }
+
+    fn full(&self) -> bool {
+        self.snippets.len() >= BATCH_NOTES || self.bytes >= BATCH_BYTES
+    }
+
+    fn push(&mut self, snippet: Value, tags: &[String]) {
+        let id = snippet["id"].as_str().unwrap_or_default().to_string();
+        self.bytes += snippet["body"].as_str().map_or(0, str::len);
+        for tag in tags {
+            let next = format!("enex-tag-{}", self.tags.len());
+            let tag_id = self.tags.entry(tag.to_lowercase()).or_insert(next).clone();
+            self.snippet_tags
+                .push(json!({ "snippet_id": id, "tag_id": tag_id }));
+        }
+        self.snippets.push(snippet);
+    }
+
+    fn bundle(&self) -> Value {
+        let collection_id = "enex-collection";
+        json!({
+            "version": "1",
+            "exported_at": chrono::Utc::now().timestamp_millis(),
+            "snippets": self.snippets,
+            "tags": self.tags.iter().map(|(name, id)| json!({ "id": id, "name": name })).collect::<Vec<_>>(),
+            "collections": [{ "id": collection_id, "name": self.collection }],
+            "snippet_tags": self.snippet_tags,
+            "snippet_collections": self.snippets.iter()
+                .map(|s| json!({ "snippet_id": s["id"], "collection_id": collection_id }))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+fn uuid_v4() -> String {
+    let mut b = [0u8; 16];
+    getrandom::getrandom(&mut b).ok();
+    b[6] = (b[6] & 0x0f) | 0x40;
+    b[8] = (b[8] & 0x3f) | 0x80;
+    let hex = hex::encode(b);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// "20130730T205204Z" -> epoch ms.
+fn enex_time(value: &str) -> Option<i64> {
+    chrono::NaiveDateTime::parse_from_str(value.trim(), "%Y%m%dT%H%M%SZ")
+        .ok()
+        .map(|t| t.and_utc().timestamp_millis())
+}
+
+fn extension(file_name: &str, mime: &str) -> String {
+    match file_name.rsplit_once('.') {
+        Some((_, ext)) if !ext.is_empty() && ext.len() <= 8 => ext.to_lowercase(),
+        _ => mime
+            .rsplit('/')
+            .next()
+            .filter(|sub| sub.chars().all(|c| c.is_ascii_alphanumeric()))
+            .unwrap_or("bin")
+            .to_string(),
+    }
+}
+
+/// Decodes a resource into the attachment store; returns its MD5 (what
+/// <en-media hash> refers to) and the attachment hash.
+fn store_resource(resource: &Resource) -> Result<(String, String), String> {
+    if resource.oversized {
+        return Err("larger than the resource limit".into());
+    }
+    let cleaned: String = resource
+        .data
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace())
+        .collect();
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(cleaned)
+        .map_err(|e| format!("bad base64: {e}"))?;
+    let md5 = hex::encode(Md5::digest(&bytes));
+    let ext = extension(&resource.file_name, &resource.mime);
+    let attachment = attachments::store_bytes(&bytes, &ext, None)?;
+    Ok((md5, attachment.hash))
+}
+
+/// ENML (XHTML plus en-media/en-todo) to Markdown.
+fn enml_to_markdown(note: &Note) -> String {
+    let content = EN_TODO.replace_all(&note.content, |caps: &Captures| {
+        if caps[1].contains(r#"checked="true""#) {
+            "[x] "
+        } else {
+            "[ ] "
+        }
+    });
+    let content = EN_MEDIA.replace_all(&content, |caps: &Captures| {
+        let Some(md5) = ATTR_HASH.captures(&caps[1]).map(|h| h[1].to_lowercase()) else {
+            return String::new();
+        };
+        match note.media.get(&md5) {
+            Some((hash, name, mime)) if mime.starts_with("image/") => {
+                format!("<p>![{name}](attachment:{hash})</p>")
+            }
+            Some((hash, name, _)) => format!("<p>[{name}](attachment:{hash})</p>"),
+            None => String::new(),
+        }
+    });
+    notion::html_to_markdown(&content)
+}
+
+fn to_snippet(note: &Note) -> Value {
+    let body = enml_to_markdown(note);
+    let now = chrono::Utc::now().timestamp_millis();
+    let created = enex_time(&note.created).unwrap_or(now);
+    let title = note.title.trim();
+    json!({
+        "id": uuid_v4(),
+        "title": if title.is_empty() { "Untitled" } else { title },
+        "content_hash": &hex::encode(Sha256::digest(body.as_bytes()))[..16],
+        "body": body,
+        "source": "evernote",
+        "source_url": Some(note.source_url.trim()).filter(|u| !u.is_empty()),
+        "created_at": created,
+        "updated_at": enex_time(&note.updated).unwrap_or(created),
+    })
+}
+
+/// Blocking half: parses `path` and sends full batches down `tx`.
+fn parse(path: &Path, task: &Task, tx: mpsc::Sender<Batch>) -> Result<EnexImport, String> {
+    let total = std::fs::metadata(path).map(|m| m.len()).ok();
+    let file_name = path.file_name().map(|n| n.to_string_lossy().into_owned());
+    let file = std::fs::File::open(path).map_err(|e| format!("Could not open {:?}: {e}", path))?;
+    let mut reader = quick_xml::Reader::from_reader(BufReader::with_capacity(1 << 16, file));
+    reader.config_mut().check_end_names = false;
+
+    let collection = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "Evernote".into());
+    let mut summary = EnexImport::default();
+    let mut batch = Batch::new(&collection);
+    let mut stack: Vec<String> = Vec::new();
+    let mut note: Option<Note> = None;
+    let mut resource: Option<Resource> = None;
+    let mut text = String::new();
+    let mut buf = Vec::new();
+
+    loop {
+        let event = reader
+            .read_event_into(&mut buf)
+            .map_err(|e| format!("Invalid ENEX at byte {}: {e}", reader.buffer_position()))?;
+        match event {
+            Event::Start(start) => {
+                let name = String::from_utf8_lossy(start.local_name().as_ref()).into_owned();
+                match name.as_str() {
+                    "note" => note = Some(Note::default()),
+                    "resource" if note.is_some() => resource = Some(Resource::default()),
+                    _ => {}
+                }
+                stack.push(name);
+                text.clear();
+            }
+            Event::Text(t) => {
+                if let Ok(t) = t.decode() {
+                    text.push_str(&t);
+                }
+            }
+            Event::CData(t) => text.push_str(&String::from_utf8_lossy(&t.into_inner())),
+            Event::GeneralRef(r) => {
+                let resolved = match r.resolve_char_ref() {
+                    Ok(Some(c)) => Some(c),
+                    _ => match r.decode().as_deref() {
+                        Ok("amp") => Some('&'),
+                        Ok("lt") => Some('<'),
+                        Ok("gt") => Some('>'),
+                        Ok("quot") => Some('"'),
+                        Ok("apos") => Some('\''),
+                        _ => None,
+                    },
+                };
+                text.extend(resolved);
+            }
+            Event::End(_) => {
+                let Some(name) = stack.pop() else {
+                    continue;
+                };
+                let parent = stack.last().map(String::as_str).unwrap_or_default();
+                let value = std::mem::take(&mut text);
+                if let Some(res) = resource.as_mut() {
+                    match name.as_str() {
+                        "data" => {
+                            res.oversized = value.len() > MAX_RESOURCE_BYTES / 3 * 4 + 1024;
+                            if !res.oversized {
+                                res.data = value;
+                            }
+                        }
+                        "mime" => res.mime = value,
+                        "file-name" => res.file_name = value,
+                        "resource" => {
+                            let res = resource.take().unwrap();
+                            match store_resource(&res) {
+                                Ok((md5, hash)) => {
+                                    summary.attachments += 1;
+                                    if let Some(n) = note.as_mut() {
+                                        n.media.insert(md5, (hash, res.file_name, res.mime));
+                                    }
+                                }
+                                Err(e) => {
+                                    summary.skipped_resources += 1;
+                                    log::warn!("ENEX: skipped resource {:?}: {}", res.file_name, e);
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                } else if let Some(n) = note.as_mut() {
+                    match (name.as_str(), parent) {
+                        ("title", "note") => n.title = value,
+                        ("content", "note") => n.content = value,
+                        ("created", "note") => n.created = value,
+                        ("updated", "note") => n.updated = value,
+                        ("tag", "note") => n.tags.push(value),
+                        ("source-url", "note-attributes") => n.source_url = value,
+                        ("note", _) => {
+                            let n = note.take().unwrap();
+                            summary.notes += 1;
+                            batch.push(to_snippet(&n), &n.tags);
+                            task.progress(reader.buffer_position(), total, file_name.as_deref());
+                            if task.cancelled() {
+                                return Ok(summary);
+                            }
+                            if batch.full() {
+                                let full = std::mem::replace(&mut batch, Batch::new(&collection));
+                                if tx.blocking_send(full).is_err() {
+                                    return Ok(summary);
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        // Don't keep a huge resource's buffer around for the rest of the file.
+        if buf.capacity() > BATCH_BYTES {
+            buf = Vec::new();
+        } else {
+            buf.clear();
+        }
+    }
+    if !batch.snippets.is_empty() {
+        tx.blocking_send(batch).ok();
+    }
+    Ok(summary)
+}
+
+/// Used by `import_enex` and for .enex files dropped on the window.
+pub async fn import(task: &Task, path: &Path, dedupe: &mut Dedupe) -> Result<EnexImport, String> {
+    let total_bytes = std::fs::metadata(path).map_err(|e| e.to_string())?.len();
+    // Attachments dominate; decoded they are about 3/4 of the base64.
+    disk::ensure_space(&attachments::store_dir(), total_bytes / 4 * 3)?;
+
+    let (tx, mut rx) = mpsc::channel(2);
+    let source: PathBuf = path.to_path_buf();
+    let worker = task.clone();
+    let parser = tauri::async_runtime::spawn_blocking(move || parse(&source, &worker, tx));
+
+    let (mut imported, mut failed) = (0, 0);
+    while let Some(batch) = rx.recv().await {
+        if task.cancelled() {
+            break;
+        }
+        let count = batch.snippets.len();
+        let bundle = serde_json::to_vec(&batch.bundle()).map_err(|e| e.to_string())?;
+        let removed = dedupe.removed();
+        match exports::import_json(&bundle, dedupe).await {
+            Ok(_) => imported += count - (dedupe.removed() - removed),
+            Err(e) => {
+                failed += count;
+                log::warn!("ENEX batch of {} notes failed: {}", count, e);
+            }
+        }
+    }
+    drop(rx);
+    let mut summary = parser.await.map_err(|e| e.to_string())??;
+    summary.imported = imported;
+    summary.failed = failed;
+    summary.duplicates = dedupe.counts();
+    log::info!(
+        "ENEX import of {:?}: {} notes, {} imported, {} attachments",
+        path,
+        summary.notes,
+        summary.imported,
+        summary.attachments
+    );
+    Ok(summary)
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Imports an .enex file; returns the task id.
+#[tauri::command]
+pub fn import_enex(app: AppHandle, path: String) -> u64 {
+    let handle = app.clone();
+    tasks::spawn(&app, "import_enex", move |task| async move {
+        let mut dedupe = Dedupe::load(&handle).await?;
+        import(&task, Path::new(&path), &mut dedupe).await
+    })
+}
@@ -0,0 +1,960 @@
+// Exports — encrypted JSON exports and ZIP archives with attachments.
+//
+// Encrypted: `export_encrypted(passphrase, scope, ids)` asks the backend for
+// the usual JSON export, seals it with XChaCha20-Poly1305 under an Argon2id
+// key, and writes it where the user picks. `import_encrypted(passphrase)`
+// opens such a file and feeds the plaintext to the backend's /import. File
+// layout:
+//
+//   "PINUPENC" | version u8 | memory_kib u32 LE | passes u32 LE
+//   | salt [16] | nonce [24] | ciphertext + tag
+//
+// Everything before the ciphertext is authenticated as associated data, so
+// the KDF parameters can change later without breaking older files.
+//
+// Archives: `export_archive(path)` streams a ZIP holding the JSON export as
+// export.json, every attachment it links to (`attachment:<hash>`) under
+// attachments/, and manifest.json with the SHA-256 of each entry (for an
+// attachment that is its hash). `import_archive(path)` verifies every
+// checksum before storing anything, then stores the attachments and imports
+// the JSON.
+//
+// Markdown: `export_markdown_folder(path)` writes one `.md` per snippet into
+// a picked folder, named after its title, with YAML frontmatter (id, title,
+// RFC 3339 timestamps, source, tags, collections). Linked attachments are
+// copied to attachments/ and the links rewritten to point there; the hashes
+// stay in the frontmatter. Existing files are never overwritten.
+//
+// All of these run as tasks (see tasks.rs): the commands return a task id
+// (None when a dialog is cancelled) and report progress per attachment or
+// file. Cancelling an archive export removes the partial ZIP; cancelling a
+// Markdown export keeps the files already written.
+//
+// Both imports apply the user's duplicate policy to the bundle before it is
+// sent (see dedupe.rs) and report what it did as `duplicates`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use chrono::TimeZone;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tauri::api::dialog::blocking::FileDialogBuilder;
+use tauri::AppHandle;
+
+use crate::attachments::{self, Attachment};
+use crate::dedupe::{Dedupe, DuplicateCounts};
+use crate::tasks::{self, Task};
+use crate::{backend, disk, encryption, i18n};
+
+const MAGIC: &[u8; 8] = b"PINUPENC";
+const VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const HEADER_LEN: usize = MAGIC.len() + 1 + 4 + 4 + SALT_LEN + NONCE_LEN;
+/// Refuse KDF settings from a file that would exhaust memory (1 GiB).
+const MAX_MEMORY_KIB: u32 = 1024 * 1024;
+const EXTENSION: &str = "pinupenc";
+
+const ARCHIVE_VERSION: u32 = 1;
+const ARCHIVE_MANIFEST: &str = "manifest.json";
+const ARCHIVE_EXPORT: &str = "export.json";
+
+/// Imports can run side by side; each stages under its own name.
+static STAGED: AtomicU64 = AtomicU64::new(0);
+static ATTACHMENT_LINK: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"attachment:([0-9a-f]{64})").unwrap());
+
+#[derive(Serialize, Clone, Debug)]
+pub struct EncryptedExport {
+    path: String,
+    size_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct ArchiveManifest {
+    format_version: u32,
+    app_version: String,
+    /// RFC 3339.
+    created_at: String,
+    export_sha256: String,
+    export_size: u64,
+    attachments: Vec<ArchivedAttachment>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct ArchivedAttachment {
+    /// SHA-256 of the bytes.
+    hash: String,
+    ext: String,
+    size: u64,
+    parent: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ArchiveExport {
+    path: String,
+    size_bytes: u64,
+    attachments: usize,
+    /// Linked attachments missing from the store (left out of the archive).
+    missing: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct MarkdownExport {
+    path: String,
+    files: usize,
+    attachments: usize,
+    /// Linked attachments missing from the store (links left as they were).
+    missing: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ArchiveImport {
+    /// The backend's /import response.
+    imported: Value,
+    attachments: usize,
+    duplicates: DuplicateCounts,
+}
+
+fn random<const N: usize>() -> Result<[u8; N], String> {
+    let mut bytes = [0u8; N];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("No random source: {e}"))?;
+    Ok(bytes)
+}
+
+/// Encrypts `plaintext` into the PINUPENC layout above.
+pub fn seal(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    let salt: [u8; SALT_LEN] = random()?;
+    let nonce: [u8; NONCE_LEN] = random()?;
+    let memory_kib = encryption::ARGON2_MEMORY_KIB;
+    let passes = encryption::ARGON2_PASSES;
+
+    let mut out = Vec::with_capacity(HEADER_LEN + plaintext.len() + 16);
+    out.extend_from_slice(MAGIC);
+    out.push(VERSION);
+    out.extend_from_slice(&memory_kib.to_le_bytes());
+    out.extend_from_slice(&passes.to_le_bytes());
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+
+    let key = encryption::argon2_key(passphrase, &salt, memory_kib, passes)?;
+    let cipher = XChaCha20Poly1305::new(&key.into());
+    let sealed = cipher
+        .encrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad: &out,
+            },
+        )
+        .map_err(|_| "Encryption failed".to_string())?;
+    out.extend_from_slice(&sealed);
+    Ok(out)
+}
+
+pub fn open(data: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    if data.len() < HEADER_LEN || &data[..MAGIC.len()] != MAGIC {
+        return Err("Not an encrypted Pin-Up AI export".into());
+    }
+    let (header, sealed) = data.split_at(HEADER_LEN);
+    if header[8] != VERSION {
+        return Err(format!(
+            "Unsupported export version {}; update Pin-Up AI",
+            header[8]
+        ));
+    }
+    let memory_kib = u32::from_le_bytes(header[9..13].try_into().unwrap());
+    let passes = u32::from_le_bytes(header[13..17].try_into().unwrap());
+    if memory_kib > MAX_MEMORY_KIB {
+        return Err("Export uses unsupported key settings".into());
+    }
+    let salt = &header[17..17 + SALT_LEN];
+    let nonce = &header[17 + SALT_LEN..];
+
+    let key = encryption::argon2_key(passphrase, salt, memory_kib, passes)?;
+    XChaCha20Poly1305::new(&key.into())
+        .decrypt(
+            XNonce::from_slice(nonce),
+            Payload {
+                msg: sealed,
+                aad: header,
+            },
+        )
+        .map_err(|_| "Wrong passphrase or damaged file".to_string())
+}
+
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    let tmp = path.with_extension(format!("{EXTENSION}.tmp"));
+    std::fs::write(&tmp, bytes).map_err(|e| format!("Could not write export: {e}"))?;
+    std::fs::rename(&tmp, path).map_err(|e| {
+        std::fs::remove_file(&tmp).ok();
+        format!("Could not write export: {e}")
+    })
+}
+
+pub async fn fetch_export(
+    scope: Option<String>,
+    ids: Option<Vec<String>>,
+) -> Result<Vec<u8>, String> {
+    let data = backend::post(
+        "/export",
+        &json!({
+            "format": "json",
+            "scope": scope.unwrap_or_else(|| "all".into()),
+            "ids": ids.unwrap_or_default(),
+        }),
+    )
+    .await?;
+    serde_json::to_vec(&data).map_err(|e| e.to_string())
+}
+
+/// /import with `dedupe` applied to the bundle first.
+pub async fn import_json(json: &[u8], dedupe: &mut Dedupe) -> Result<Value, String> {
+    let mut bundle: Value =
+        serde_json::from_slice(json).map_err(|e| format!("Not a JSON export: {e}"))?;
+    dedupe.apply_to_bundle(&mut bundle);
+    upload_json(&serde_json::to_vec(&bundle).map_err(|e| e.to_string())?).await
+}
+
+/// /import takes a file upload; the JSON is staged in the wipeable runtime
+/// dir only for as long as the upload takes.
+pub async fn upload_json(json: &[u8]) -> Result<Value, String> {
+    let staged: PathBuf = crate::runtime_dir().join(format!(
+        "import-{}-{}.json",
+        std::process::id(),
+        STAGED.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::create_dir_all(crate::runtime_dir()).map_err(|e| e.to_string())?;
+    std::fs::write(&staged, json).map_err(|e| format!("Could not stage import: {e}"))?;
+    let result = backend::upload("/import", &staged).await;
+    std::fs::remove_file(&staged).ok();
+    result
+}
+
+/// Attachments linked from `text`, plus linked hashes missing from the store.
+pub fn linked_attachments(text: &str) -> (Vec<Attachment>, Vec<String>) {
+    let hashes: BTreeSet<&str> = ATTACHMENT_LINK
+        .captures_iter(text)
+        .filter_map(|c| c.get(1))
+        .map(|m| m.as_str())
+        .collect();
+    let mut linked = Vec::new();
+    let mut missing = Vec::new();
+    for hash in hashes {
+        match attachments::get(hash) {
+            Some(attachment) => linked.push(attachment),
+            None => missing.push(hash.to_string()),
+        }
+    }
+    (linked, missing)
+}
+
+// ── Archives ───────────────────────────────────────────────────────────────
+fn archive_name(hash: &str, ext: &str) -> String {
+    if ext.is_empty() {
+        format!("attachments/{hash}")
+    } else {
+        format!("attachments/{hash}.{ext}")
+    }
+}
+
+fn is_hash(value: &str) -> bool {
+    value.len() == 64 && value.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+fn write_archive(
+    dest: &Path,
+    json: &[u8],
+    linked: &[Attachment],
+    task: &Task,
+) -> Result<(), String> {
+    let file = std::fs::File::create(dest).map_err(|e| format!("Could not create archive: {e}"))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let deflated =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    // Attachments are mostly images and audio, already compressed.
+    let stored =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+
+    zip.start_file(ARCHIVE_EXPORT, deflated)
+        .and_then(|_| zip.write_all(json).map_err(Into::into))
+        .map_err(|e| format!("Could not write {ARCHIVE_EXPORT}: {e}"))?;
+    let total = linked.len() as u64;
+    for (done, attachment) in linked.iter().enumerate() {
+        if task.cancelled() {
+            return Err(format!(
+                "Export cancelled after {} of {} attachments",
+                done, total
+            ));
+        }
+        let name = archive_name(&attachment.hash, &attachment.ext);
+        task.progress(done as u64, Some(total), Some(&name));
+        let mut src = std::fs::File::open(attachments::path_of(attachment))
+            .map_err(|e| format!("Could not read attachment {}: {e}", attachment.hash))?;
+        zip.start_file(&name, stored.large_file(attachment.size >= u32::MAX as u64))
+            .map_err(|e| format!("Could not write {name}: {e}"))?;
+        std::io::copy(&mut src, &mut zip).map_err(|e| format!("Could not write {name}: {e}"))?;
+    }
+
+    let manifest = ArchiveManifest {
+        format_version: ARCHIVE_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Local::now().to_rfc3339(),
+        export_sha256: hex::encode(Sha256::digest(json)),
+        export_size: json.len() as u64,
+        attachments: linked
+            .iter()
+            .map(|a| ArchivedAttachment {
+                hash: a.hash.clone(),
+                ext: a.ext.clone(),
+                size: a.size,
+                parent: a.parent.clone(),
+            })
+            .collect(),
+    };
+    let text = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    zip.start_file(ARCHIVE_MANIFEST, deflated)
+        .and_then(|_| zip.write_all(&text).map_err(Into::into))
+        .map_err(|e| format!("Could not write {ARCHIVE_MANIFEST}: {e}"))?;
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+type Archive = zip::ZipArchive<std::fs::File>;
+
+fn read_entry(zip: &mut Archive, name: &str) -> Result<Vec<u8>, String> {
+    let mut entry = zip
+        .by_name(name)
+        .map_err(|_| format!("Archive is missing {name}"))?;
+    let mut bytes = Vec::with_capacity(entry.size() as usize);
+    entry
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Could not read {name}: {e}"))?;
+    Ok(bytes)
+}
+
+fn hash_entry(zip: &mut Archive, name: &str) -> Result<String, String> {
+    let mut entry = zip
+        .by_name(name)
+        .map_err(|_| format!("Archive is missing {name}"))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut entry, &mut hasher).map_err(|e| format!("Could not read {name}: {e}"))?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Verifies every checksum, then stores the attachments. Returns the JSON
+/// export and the number of attachments.
+/// Returns the JSON and how many attachments were stored (fewer than the
+/// archive holds if the task was cancelled while storing).
+fn unpack_archive(src: &Path, task: &Task) -> Result<(Vec<u8>, usize), String> {
+    let file = std::fs::File::open(src).map_err(|e| format!("Could not open archive: {e}"))?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| format!("Not a ZIP archive: {e}"))?;
+    let manifest: ArchiveManifest =
+        serde_json::from_slice(&read_entry(&mut zip, ARCHIVE_MANIFEST)?)
+            .map_err(|e| format!("Invalid archive manifest: {e}"))?;
+    if manifest.format_version > ARCHIVE_VERSION {
+        return Err(format!(
+            "Archive format {} is newer than this app; update Pin-Up AI",
+            manifest.format_version
+        ));
+    }
+
+    let json = read_entry(&mut zip, ARCHIVE_EXPORT)?;
+    if hex::encode(Sha256::digest(&json)) != manifest.export_sha256 {
+        return Err(format!("{ARCHIVE_EXPORT} is damaged (checksum mismatch)"));
+    }
+    let total = manifest.attachments.len() as u64;
+    for (done, attachment) in manifest.attachments.iter().enumerate() {
+        if task.cancelled() {
+            return Err("Import cancelled before anything was stored".into());
+        }
+        let valid_ext =
+            attachment.ext.len() <= 16 && attachment.ext.bytes().all(|b| b.is_ascii_alphanumeric());
+        if !is_hash(&attachment.hash) || !valid_ext {
+            return Err(format!("Invalid attachment entry {:?}", attachment.hash));
+        }
+        let name = archive_name(&attachment.hash, &attachment.ext);
+        task.progress(done as u64, Some(total), Some(&format!("Verifying {name}")));
+        if hash_entry(&mut zip, &name)? != attachment.hash {
+            return Err(format!("{name} is damaged (checksum mismatch)"));
+        }
+    }
+
+    let needed = manifest.attachments.iter().map(|a| a.size).sum();
+    disk::ensure_space(&attachments::store_dir(), needed)?;
+    let archived: BTreeSet<&str> = manifest
+        .attachments
+        .iter()
+        .map(|a| a.hash.as_str())
+        .collect();
+    // Earlier versions first so edit lineages can be linked up.
+    let mut pending: Vec<&ArchivedAttachment> = manifest.attachments.iter().collect();
+    pending.sort_by_key(|a| a.parent.is_some());
+    let mut stored = 0;
+    for attachment in pending {
+        if task.cancelled() {
+            break;
+        }
+        let name = archive_name(&attachment.hash, &attachment.ext);
+        task.progress(stored as u64, Some(total), Some(&name));
+        let bytes = read_entry(&mut zip, &name)?;
+        let parent = attachment
+            .parent
+            .as_deref()
+            .filter(|p| archived.contains(p) || attachments::get(p).is_some());
+        attachments::store_bytes(&bytes, &attachment.ext, parent)?;
+        stored += 1;
+    }
+    Ok((json, stored))
+}
+
+// ── Markdown folder ────────────────────────────────────────────────────────
+/// JSON strings are valid YAML double-quoted scalars.
+pub fn yaml(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+pub fn yaml_list(values: &[String]) -> String {
+    let items: Vec<String> = values.iter().map(|v| yaml(v)).collect();
+    format!("[{}]", items.join(", "))
+}
+
+fn timestamp(ms: Option<i64>) -> Option<String> {
+    chrono::Utc
+        .timestamp_millis_opt(ms?)
+        .single()
+        .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+}
+
+/// Names a snippet's file after its title, without clobbering `taken`
+/// (compared case-insensitively for macOS and Windows).
+pub fn file_name(title: &str, id: &str, taken: &mut BTreeSet<String>) -> String {
+    let cleaned: String = title
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => ' ',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect();
+    let mut stem: String = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    stem = stem.trim_matches('.').chars().take(80).collect();
+    if stem.is_empty() {
+        stem = id.to_string();
+    }
+    let mut name = format!("{stem}.md");
+    let mut n = 2;
+    while !taken.insert(name.to_lowercase()) {
+        name = format!("{stem} ({n}).md");
+        n += 1;
+    }
+    name
+}
+
+pub fn names_by_id(data: &Value, list: &str) -> BTreeMap<String, String> {
+    data[list]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|item| Some((item["id"].as_str()?.into(), item["name"].as_str()?.into())))
+        .collect()
+}
+
+/// snippet id -> names, from a join table like `snippet_tags`.
+pub fn joined(
+    data: &Value,
+    table: &str,
+    key: &str,
+    names: &BTreeMap<String, String>,
+) -> BTreeMap<String, Vec<String>> {
+    let mut out: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for row in data[table].as_array().into_iter().flatten() {
+        let (Some(snippet), Some(name)) = (
+            row["snippet_id"].as_str(),
+            row[key].as_str().and_then(|id| names.get(id)),
+        ) else {
+            continue;
+        };
+        out.entry(snippet.into()).or_default().push(name.clone());
+    }
+    out
+}
+
+/// The backend's content hash: the first 16 hex digits of SHA-256(body).
+pub fn content_hash(body: &str) -> String {
+    hex::encode(Sha256::digest(body.as_bytes()))[..16].to_string()
+}
+
+/// The strings in a JSON array; anything else gives none.
+pub fn string_list(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str().map(str::to_string))
+        .collect()
+}
+
+/// Every snippet of an export with its tag and collection names inlined as
+/// `tags` and `collections`.
+pub fn snippets_with_names(data: &Value) -> Vec<Value> {
+    let tags = joined(data, "snippet_tags", "tag_id", &names_by_id(data, "tags"));
+    let collections = joined(
+        data,
+        "snippet_collections",
+        "collection_id",
+        &names_by_id(data, "collections"),
+    );
+    data["snippets"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|snippet| {
+            let id = snippet["id"].as_str().unwrap_or_default();
+            let mut snippet = snippet.clone();
+            snippet["tags"] = json!(tags.get(id).cloned().unwrap_or_default());
+            snippet["collections"] = json!(collections.get(id).cloned().unwrap_or_default());
+            snippet
+        })
+        .collect()
+}
+
+/// The PATCH /snippets/{id} body that makes a snippet match `snippet`,
+/// shaped like `snippets_with_names`.
+pub fn snippet_patch(snippet: &Value) -> Value {
+    let mut patch = json!({
+        "body": snippet["body"],
+        "pinned": snippet["pinned"],
+        "archived": snippet["archived"],
+        "tags": string_list(&snippet["tags"]),
+        "collections": string_list(&snippet["collections"]),
+    });
+    for key in ["title", "language", "source", "source_url"] {
+        if snippet[key].is_string() {
+            patch[key] = snippet[key].clone();
+        }
+    }
+    patch
+}
+
+/// A v1 bundle from snippets shaped like `snippets_with_names`, keeping
+/// their ids. A missing content hash or timestamp is filled in.
+pub fn bundle_with_names(snippets: Vec<Value>) -> Value {
+    let now = json!(chrono::Utc::now().timestamp_millis());
+    let (mut tags, mut collections) = (BTreeSet::new(), BTreeSet::new());
+    let (mut snippet_tags, mut snippet_collections) = (Vec::new(), Vec::new());
+    let mut rows = Vec::new();
+    for mut snippet in snippets {
+        let id = snippet["id"].clone();
+        let Some(row) = snippet.as_object_mut() else {
+            continue;
+        };
+        for tag in string_list(&row.remove("tags").unwrap_or_default()) {
+            snippet_tags.push(json!({ "snippet_id": id, "tag_id": format!("tag:{tag}") }));
+            tags.insert(tag);
+        }
+        for collection in string_list(&row.remove("collections").unwrap_or_default()) {
+            snippet_collections.push(
+                json!({ "snippet_id": id, "collection_id": format!("collection:{collection}") }),
+            );
+            collections.insert(collection);
+        }
+        if !row.get("content_hash").is_some_and(Value::is_string) {
+            let hash = content_hash(row.get("body").and_then(Value::as_str).unwrap_or_default());
+            row.insert("content_hash".into(), json!(hash));
+        }
+        let created_at = row
+            .get("created_at")
+            .filter(|v| v.is_i64())
+            .cloned()
+            .unwrap_or_else(|| now.clone());
+        if !row.get("updated_at").is_some_and(Value::is_i64) {
+            row.insert("updated_at".into(), created_at.clone());
+        }
+        row.insert("created_at".into(), created_at);
+        rows.push(snippet);
+    }
+    json!({
+        "version": "1",
+        "snippets": rows,
+        "tags": tags
+            .iter()
+            .map(|t| json!({ "id": format!("tag:{t}"), "name": t }))
+            .collect::<Vec<_>>(),
+        "collections": collections
+            .iter()
+            .map(|c| json!({ "id": format!("collection:{c}"), "name": c }))
+            .collect::<Vec<_>>(),
+        "snippet_tags": snippet_tags,
+        "snippet_collections": snippet_collections,
+    })
+}
+
+pub fn snippet_markdown(
+    snippet: &Value,
+    tags: &[String],
+    collections: &[String],
+    files: &BTreeMap<String, String>,
+    assets: &str,
+) -> String {
+    let text = |key: &str| snippet[key].as_str().filter(|v| !v.is_empty());
+    let body = snippet["body"].as_str().unwrap_or_default();
+    let mut hashes = Vec::new();
+    let body = ATTACHMENT_LINK.replace_all(body, |caps: &regex::Captures| {
+        let hash = &caps[1];
+        if !hashes.iter().any(|h| h == hash) {
+            hashes.push(hash.to_string());
+        }
+        match files.get(hash) {
+            Some(file) => format!("{assets}/{file}"),
+            None => caps[0].to_string(),
+        }
+    });
+
+    let mut lines = vec!["---".to_string()];
+    lines.push(format!("id: {}", yaml(text("id").unwrap_or_default())));
+    lines.push(format!(
+        "title: {}",
+        yaml(text("title").unwrap_or_default())
+    ));
+    for key in ["created_at", "updated_at"] {
+        if let Some(at) = timestamp(snippet[key].as_i64()) {
+            lines.push(format!("{key}: {at}"));
+        }
+    }
+    for key in ["source", "source_url", "language"] {
+        if let Some(value) = text(key) {
+            lines.push(format!("{key}: {}", yaml(value)));
+        }
+    }
+    for key in ["pinned", "archived"] {
+        if snippet[key].as_bool() == Some(true) {
+            lines.push(format!("{key}: true"));
+        }
+    }
+    if !tags.is_empty() {
+        lines.push(format!("tags: {}", yaml_list(tags)));
+    }
+    if !collections.is_empty() {
+        lines.push(format!("collections: {}", yaml_list(collections)));
+    }
+    if !hashes.is_empty() {
+        lines.push(format!("attachments: {}", yaml_list(&hashes)));
+    }
+    lines.push("---".into());
+    lines.push(String::new());
+    lines.push(body.into_owned());
+    lines.join("\n")
+}
+
+/// Copies `linked` into `dir` (once; they never change) and returns
+/// hash -> file name.
+pub fn copy_attachments(
+    dir: &Path,
+    linked: &[Attachment],
+) -> Result<BTreeMap<String, String>, String> {
+    let mut files = BTreeMap::new();
+    if linked.is_empty() {
+        return Ok(files);
+    }
+    std::fs::create_dir_all(dir).map_err(|e| format!("Could not create {:?}: {e}", dir))?;
+    for attachment in linked {
+        let name = attachments::path_of(attachment)
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| attachment.hash.clone());
+        let dest = dir.join(&name);
+        if !dest.exists() {
+            std::fs::copy(attachments::path_of(attachment), &dest)
+                .map_err(|e| format!("Could not copy attachment {}: {e}", attachment.hash))?;
+        }
+        files.insert(attachment.hash.clone(), name);
+    }
+    Ok(files)
+}
+
+/// Lowercased names of the entries in `dir` (none when it doesn't exist).
+pub fn existing_names(dir: &Path) -> Result<BTreeSet<String>, String> {
+    match std::fs::read_dir(dir) {
+        Ok(entries) => Ok(entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().to_lowercase())
+            .collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeSet::new()),
+        Err(e) => Err(format!("Could not read {:?}: {e}", dir)),
+    }
+}
+
+/// Writes the export into `dir`; returns (files written, attachments copied).
+/// Stops early, keeping what was written, if the task is cancelled.
+fn write_markdown_folder(
+    dir: &Path,
+    data: &Value,
+    linked: &[Attachment],
+    task: &Task,
+) -> Result<(usize, usize), String> {
+    let files = copy_attachments(&dir.join("attachments"), linked)?;
+
+    let tags = joined(data, "snippet_tags", "tag_id", &names_by_id(data, "tags"));
+    let collections = joined(
+        data,
+        "snippet_collections",
+        "collection_id",
+        &names_by_id(data, "collections"),
+    );
+    let mut taken = existing_names(dir)?;
+    let mut written = 0;
+    let snippets = data["snippets"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    for snippet in snippets {
+        if task.cancelled() {
+            break;
+        }
+        let id = snippet["id"].as_str().unwrap_or_default();
+        let markdown = snippet_markdown(
+            snippet,
+            tags.get(id).map(Vec::as_slice).unwrap_or_default(),
+            collections.get(id).map(Vec::as_slice).unwrap_or_default(),
+            &files,
+            "attachments",
+        );
+        let name = file_name(
+            snippet["title"].as_str().unwrap_or_default(),
+            id,
+            &mut taken,
+        );
+        task.progress(written as u64, Some(snippets.len() as u64), Some(&name));
+        std::fs::write(dir.join(&name), markdown)
+            .map_err(|e| format!("Could not write {name}: {e}"))?;
+        written += 1;
+    }
+    Ok((written, files.len()))
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Exports as JSON and saves it encrypted. Returns the task id, or None when
+/// the dialog is cancelled.
+#[tauri::command]
+pub async fn export_encrypted(
+    app: AppHandle,
+    passphrase: String,
+    scope: Option<String>,
+    ids: Option<Vec<String>>,
+) -> Result<Option<u64>, String> {
+    if passphrase.chars().count() < encryption::MIN_PASSPHRASE {
+        return Err(format!(
+            "Use a passphrase of at least {} characters",
+            encryption::MIN_PASSPHRASE
+        ));
+    }
+    let Some(path) = FileDialogBuilder::new()
+        .set_title(&i18n::t("dialog.export_encrypted"))
+        .set_file_name(&format!("pinup-export.{EXTENSION}"))
+        .add_filter("Encrypted export", &[EXTENSION])
+        .save_file()
+    else {
+        return Ok(None);
+    };
+
+    let id = tasks::spawn(&app, "export_encrypted", move |task| async move {
+        task.progress(0, Some(3), Some("Fetching export"));
+        let plaintext = fetch_export(scope, ids).await?;
+        if task.cancelled() {
+            return Err("Export cancelled; nothing was written".into());
+        }
+        task.progress(1, Some(3), Some("Encrypting"));
+        let sealed = tauri::async_runtime::spawn_blocking(move || seal(&plaintext, &passphrase))
+            .await
+            .map_err(|e| e.to_string())??;
+        if task.cancelled() {
+            return Err("Export cancelled; nothing was written".into());
+        }
+
+        task.progress(2, Some(3), Some("Writing"));
+        disk::ensure_space(&path, sealed.len() as u64)?;
+        write_atomic(&path, &sealed)?;
+        log::info!("Encrypted export written to {:?}", path);
+        Ok(EncryptedExport {
+            path: path.to_string_lossy().to_string(),
+            size_bytes: sealed.len() as u64,
+        })
+    });
+    Ok(Some(id))
+}
+
+/// Decrypts a picked export and imports it. Returns the task id, or None
+/// when the dialog is cancelled.
+#[tauri::command]
+pub async fn import_encrypted(app: AppHandle, passphrase: String) -> Result<Option<u64>, String> {
+    let Some(path) = FileDialogBuilder::new()
+        .set_title(&i18n::t("dialog.import_encrypted"))
+        .add_filter("Encrypted export", &[EXTENSION])
+        .pick_file()
+    else {
+        return Ok(None);
+    };
+    let handle = app.clone();
+    let id = tasks::spawn(&app, "import_encrypted", move |task| async move {
+        task.progress(0, Some(2), Some("Decrypting"));
+        let data = std::fs::read(&path).map_err(|e| format!("Could not read {:?}: {e}", path))?;
+        let plaintext = tauri::async_runtime::spawn_blocking(move || open(&data, &passphrase))
+            .await
+            .map_err(|e| e.to_string())??;
+        if task.cancelled() {
+            return Err("Import cancelled; nothing was imported".into());
+        }
+        task.progress(1, Some(2), Some("Importing"));
+        let mut dedupe = Dedupe::load(&handle).await?;
+        let mut result = import_json(&plaintext, &mut dedupe).await?;
+        if let Some(fields) = result.as_object_mut() {
+            fields.insert("duplicates".into(), json!(dedupe.counts()));
+        }
+        log::info!("Imported encrypted export {:?}", path);
+        Ok(result)
+    });
+    Ok(Some(id))
+}
+
+/// Writes a ZIP of the JSON export plus the attachments it links to.
+/// Returns the task id.
+#[tauri::command]
+pub fn export_archive(
+    app: AppHandle,
+    path: String,
+    scope: Option<String>,
+    ids: Option<Vec<String>>,
+) -> u64 {
+    tasks::spawn(&app, "export_archive", move |task| async move {
+        let dest = PathBuf::from(&path);
+        let json = fetch_export(scope, ids).await?;
+        let (linked, missing) = linked_attachments(&String::from_utf8_lossy(&json));
+        if !missing.is_empty() {
+            log::warn!(
+                "Archive export: {} linked attachments not found",
+                missing.len()
+            );
+        }
+
+        let needed = json.len() as u64 + linked.iter().map(|a| a.size).sum::<u64>();
+        disk::ensure_space(&dest, needed)?;
+        let tmp = dest.with_extension("zip.tmp");
+        let count = linked.len();
+        let staged = tmp.clone();
+        let worker = task.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            write_archive(&staged, &json, &linked, &worker)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+        .and_then(|_| {
+            std::fs::rename(&tmp, &dest).map_err(|e| format!("Could not write archive: {e}"))
+        })
+        .map_err(|e| {
+            std::fs::remove_file(&tmp).ok();
+            e
+        })?;
+
+        let size_bytes = std::fs::metadata(&dest).map(|m| m.len()).unwrap_or(0);
+        log::info!(
+            "Archive export ({} attachments) written to {:?}",
+            count,
+            dest
+        );
+        Ok(ArchiveExport {
+            path,
+            size_bytes,
+            attachments: count,
+            missing,
+        })
+    })
+}
+
+/// Restores an archive from `export_archive`: attachments, then the JSON.
+/// Returns the task id. Cancelled while storing, the attachments stored so
+/// far are kept and the JSON is not imported.
+#[tauri::command]
+pub fn import_archive(app: AppHandle, path: String) -> u64 {
+    let handle = app.clone();
+    tasks::spawn(&app, "import_archive", move |task| async move {
+        let src = PathBuf::from(&path);
+        let worker = task.clone();
+        let (json, attachments) =
+            tauri::async_runtime::spawn_blocking(move || unpack_archive(&src, &worker))
+                .await
+                .map_err(|e| e.to_string())??;
+        if task.cancelled() {
+            return Ok(ArchiveImport {
+                imported: Value::Null,
+                attachments,
+                duplicates: DuplicateCounts::default(),
+            });
+        }
+        task.progress(
+            attachments as u64,
+            Some(attachments as u64),
+            Some(ARCHIVE_EXPORT),
+        );
+        let mut dedupe = Dedupe::load(&handle).await?;
+        let imported = import_json(&json, &mut dedupe).await?;
+        log::info!("Imported archive {:?} ({} attachments)", path, attachments);
+        Ok(ArchiveImport {
+            imported,
+            attachments,
+            duplicates: dedupe.counts(),
+        })
+    })
+}
+
+/// Writes each snippet as a Markdown file into the folder at `path`.
+/// Returns the task id.
+#[tauri::command]
+pub fn export_markdown_folder(
+    app: AppHandle,
+    path: String,
+    scope: Option<String>,
+    ids: Option<Vec<String>>,
+) -> Result<u64, String> {
+    let dir = PathBuf::from(&path);
+    if !dir.is_dir() {
+        return Err(format!("{path} is not a folder"));
+    }
+    Ok(tasks::spawn(
+        &app,
+        "export_markdown_folder",
+        move |task| async move {
+            let json = fetch_export(scope, ids).await?;
+            let data: Value = serde_json::from_slice(&json).map_err(|e| e.to_string())?;
+            let (linked, missing) = linked_attachments(&String::from_utf8_lossy(&json));
+            let needed = json.len() as u64 + linked.iter().map(|a| a.size).sum::<u64>();
+            disk::ensure_space(&dir, needed)?;
+
+            let (files, attachments) = tauri::async_runtime::spawn_blocking(move || {
+                write_markdown_folder(&dir, &data, &linked, &task)
+            })
+            .await
+            .map_err(|e| e.to_string())??;
+            log::info!(
+                "Markdown export: {} files, {} attachments into {:?}",
+                files,
+                attachments,
+                path
+            );
+            Ok(MarkdownExport {
+                path,
+                files,
+                attachments,
+                missing,
+            })
+        },
+    ))
+}
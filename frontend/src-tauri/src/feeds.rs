@@ -0,0 +1,773 @@
+// Feeds — RSS and Atom subscriptions filed into the library as snippets.
+//
+// The `feeds` config section lists the subscriptions, each with its own
+// rules: a collection overriding the default one, tags, its own interval,
+// and whether entries are fetched in full (each linked page through the web
+// clipper's readability pass, see clipper.rs) or kept as the feed carries
+// them. While enabled, feeds are checked every `interval_minutes` (times the
+// poll factor) with conditional requests, so an unchanged feed costs a 304.
+//
+// An entry is known by its guid/id, else its link, else a hash of its
+// title and content; keys already filed are kept per feed in
+// data_dir()/feeds.json (the last MAX_SEEN of them), so an entry is filed
+// once however often it reappears. A new subscription files only its
+// INITIAL_ENTRIES newest entries rather than the whole backlog, and each
+// check at most MAX_PER_CHECK, oldest first, the rest waiting for the next.
+// Entries go through `capture::submit` with source "feed".
+//
+// Subscribing to a web page finds its feed through <link rel="alternate">.
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use kuchikiki::traits::*;
+use once_cell::sync::Lazy;
+use quick_xml::events::{BytesStart, Event};
+use regex::Regex;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+
+use crate::capture::{self, Capture};
+use crate::{backend, clipper, config, performance, proxy, suspend};
+
+const SOURCE: &str = "feed";
+const TIMEOUT: Duration = Duration::from_secs(30);
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const MAX_FEED_BYTES: usize = 10 * 1024 * 1024;
+const MAX_SEEN: usize = 2000;
+const INITIAL_ENTRIES: usize = 5;
+const MAX_PER_CHECK: usize = 25;
+const USER_AGENT: &str = "Mozilla/5.0 (compatible; Pin-Up AI feed reader)";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct FeedsConfig {
+    pub enabled: bool,
+    pub interval_minutes: u64,
+    /// Where entries are filed unless their feed names another collection.
+    pub collection: String,
+    pub feeds: Vec<Feed>,
+}
+
+impl Default for FeedsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_minutes: 60,
+            collection: "Feeds".into(),
+            feeds: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Feed {
+    pub url: String,
+    /// The feed's own title unless the user renamed it.
+    pub title: Option<String>,
+    pub enabled: bool,
+    pub collection: Option<String>,
+    pub tags: Vec<String>,
+    /// Fetch each entry's page and keep its article, rather than the
+    /// (often shortened) text in the feed.
+    pub full_article: bool,
+    /// Overrides the section's interval.
+    pub interval_minutes: Option<u64>,
+}
+
+impl Default for Feed {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            title: None,
+            enabled: true,
+            collection: None,
+            tags: Vec::new(),
+            full_article: true,
+            interval_minutes: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+struct FeedState {
+    /// Keys of entries already filed, oldest first.
+    seen: Vec<String>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// Unix seconds.
+    checked_at: Option<i64>,
+    last_error: Option<String>,
+    imported: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct FeedStatus {
+    #[serde(flatten)]
+    feed: Feed,
+    checked_at: Option<i64>,
+    last_error: Option<String>,
+    imported: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct FeedsStatus {
+    enabled: bool,
+    interval_minutes: u64,
+    collection: String,
+    refreshing: bool,
+    feeds: Vec<FeedStatus>,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct FeedsRefresh {
+    checked: usize,
+    added: usize,
+    failed: usize,
+}
+
+static REFRESHING: AtomicBool = AtomicBool::new(false);
+static STATE: Lazy<Mutex<HashMap<String, FeedState>>> = Lazy::new(|| {
+    Mutex::new(
+        std::fs::read_to_string(state_path())
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default(),
+    )
+});
+
+fn state_path() -> PathBuf {
+    crate::data_dir().join("feeds.json")
+}
+
+fn state_of(url: &str) -> FeedState {
+    STATE.lock().unwrap().get(url).cloned().unwrap_or_default()
+}
+
+/// Stores `state` for `url`, or forgets the feed when None.
+fn save_state(url: &str, state: Option<FeedState>) {
+    let mut all = STATE.lock().unwrap();
+    match state {
+        Some(state) => all.insert(url.to_string(), state),
+        None => all.remove(url),
+    };
+    let text = serde_json::to_string(&*all).unwrap_or_default();
+    std::fs::create_dir_all(crate::data_dir()).ok();
+    if let Err(e) = std::fs::write(state_path(), text) {
+        log::error!("Could not save feed state: {}", e);
+    }
+}
+
+// ── Parsing ────────────────────────────────────────────────────────────────
+#[derive(Default, Debug)]
+struct Entry {
+    id: String,
+    title: String,
+    link: String,
+    /// HTML.
+    content: String,
+    summary: String,
+    /// Unix seconds.
+    published: Option<i64>,
+}
+
+impl Entry {
+    fn key(&self) -> String {
+        if !self.id.trim().is_empty() {
+            return self.id.trim().to_string();
+        }
+        if !self.link.trim().is_empty() {
+            return self.link.trim().to_string();
+        }
+        let digest = Sha256::digest(format!("{}\n{}", self.title, self.content).as_bytes());
+        hex::encode(&digest[..16])
+    }
+}
+
+#[derive(Default, Debug)]
+struct Parsed {
+    title: Option<String>,
+    entries: Vec<Entry>,
+}
+
+fn local_name(start: &BytesStart) -> String {
+    String::from_utf8_lossy(start.local_name().as_ref()).into_owned()
+}
+
+fn attribute(start: &BytesStart, name: &str) -> Option<String> {
+    let value = start.try_get_attribute(name).ok()??;
+    let value = value
+        .normalized_value(quick_xml::XmlVersion::Implicit1_0)
+        .ok()?;
+    Some(value.trim().to_string())
+}
+
+/// An element inside Atom xhtml content, written back out as markup.
+fn markup(start: &BytesStart, empty: bool) -> String {
+    let attrs = String::from_utf8_lossy(&start[start.name().as_ref().len()..]);
+    let close = if empty { "/" } else { "" };
+    format!(
+        "<{}{}{close}>",
+        local_name(start),
+        attrs.trim_end_matches('/')
+    )
+}
+
+fn date(text: &str) -> Option<i64> {
+    let text = text.trim();
+    chrono::DateTime::parse_from_rfc2822(text)
+        .or_else(|_| chrono::DateTime::parse_from_rfc3339(text))
+        .map(|d| d.timestamp())
+        .ok()
+}
+
+/// RSS 0.9x/2.0, RSS 1.0 (RDF) and Atom, whichever `xml` is.
+fn parse(xml: &str) -> Result<Parsed, String> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    reader.config_mut().check_end_names = false;
+
+    let mut parsed = Parsed::default();
+    let mut is_feed = false;
+    let mut stack: Vec<String> = Vec::new();
+    let mut entry: Option<Entry> = None;
+    let mut text = String::new();
+    // Stack depth of the Atom content element whose xhtml is being copied.
+    let mut xhtml: Option<usize> = None;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("Invalid feed at byte {}: {e}", reader.buffer_position()))?;
+        match event {
+            Event::Start(start) => {
+                let name = local_name(&start);
+                if xhtml.is_some() {
+                    text.push_str(&markup(&start, false));
+                    stack.push(name);
+                    continue;
+                }
+                match name.as_str() {
+                    "rss" | "feed" | "RDF" if stack.is_empty() => is_feed = true,
+                    "item" | "entry" => entry = Some(Entry::default()),
+                    "content" | "summary"
+                        if entry.is_some()
+                            && attribute(&start, "type").as_deref() == Some("xhtml") =>
+                    {
+                        xhtml = Some(stack.len() + 1);
+                    }
+                    "link" => atom_link(&start, entry.as_mut()),
+                    _ => {}
+                }
+                stack.push(name);
+                text.clear();
+            }
+            Event::Empty(start) => {
+                if xhtml.is_some() {
+                    text.push_str(&markup(&start, true));
+                } else if local_name(&start) == "link" {
+                    atom_link(&start, entry.as_mut());
+                }
+            }
+            Event::Text(t) => {
+                if let Ok(t) = t.decode() {
+                    text.push_str(&t);
+                }
+            }
+            Event::CData(t) => text.push_str(&String::from_utf8_lossy(&t.into_inner())),
+            Event::GeneralRef(r) => {
+                let name = r.decode().unwrap_or_default();
+                if xhtml.is_some() {
+                    // Still markup: left for the HTML parser to resolve.
+                    text.push_str(&format!("&{name};"));
+                    continue;
+                }
+                let resolved = match r.resolve_char_ref() {
+                    Ok(Some(c)) => Some(c),
+                    _ => match name.as_ref() {
+                        "amp" => Some('&'),
+                        "lt" => Some('<'),
+                        "gt" => Some('>'),
+                        "quot" => Some('"'),
+                        "apos" => Some('\''),
+                        _ => None,
+                    },
+                };
+                text.extend(resolved);
+            }
+            Event::End(_) => {
+                let Some(name) = stack.pop() else {
+                    continue;
+                };
+                if let Some(depth) = xhtml {
+                    if stack.len() >= depth {
+                        text.push_str(&format!("</{name}>"));
+                        continue;
+                    }
+                    xhtml = None;
+                }
+                let parent = stack.last().map(String::as_str).unwrap_or_default();
+                let value = std::mem::take(&mut text);
+                let value = value.trim();
+                if matches!(name.as_str(), "item" | "entry") {
+                    parsed.entries.extend(entry.take());
+                    continue;
+                }
+                match entry.as_mut() {
+                    Some(_) if !matches!(parent, "item" | "entry") => {}
+                    Some(e) => match name.as_str() {
+                        "title" => e.title = value.to_string(),
+                        "link" if e.link.is_empty() => e.link = value.to_string(),
+                        "guid" | "id" => e.id = value.to_string(),
+                        "description" | "summary" => e.summary = value.to_string(),
+                        "encoded" | "content" => e.content = value.to_string(),
+                        "pubDate" | "published" | "date" => e.published = date(value),
+                        "updated" if e.published.is_none() => e.published = date(value),
+                        _ => {}
+                    },
+                    None => {
+                        if name == "title"
+                            && matches!(parent, "channel" | "feed")
+                            && parsed.title.is_none()
+                            && !value.is_empty()
+                        {
+                            parsed.title = Some(value.to_string());
+                        }
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    if !is_feed {
+        return Err("Not an RSS or Atom feed".into());
+    }
+    Ok(parsed)
+}
+
+/// Atom keeps an entry's page in <link rel="alternate" href>.
+fn atom_link(start: &BytesStart, entry: Option<&mut Entry>) {
+    let (Some(entry), Some(href)) = (entry, attribute(start, "href")) else {
+        return;
+    };
+    let rel = attribute(start, "rel").unwrap_or_else(|| "alternate".into());
+    if rel == "alternate" && entry.link.is_empty() {
+        entry.link = href;
+    }
+}
+
+/// The feed a web page links to.
+fn discover(html: &str, base: &Url) -> Option<Url> {
+    let document = kuchikiki::parse_html().one(html);
+    let links = document.select(r#"link[rel~="alternate"][href]"#).ok()?;
+    links.into_iter().find_map(|link| {
+        let node = link.as_node();
+        let kind = clipper::attr(node, "type")?.to_lowercase();
+        if !(kind.contains("rss") || kind.contains("atom")) {
+            return None;
+        }
+        base.join(&clipper::attr(node, "href")?).ok()
+    })
+}
+
+/// The feed in `bytes`, honouring the encoding its XML declaration names.
+fn decode(bytes: &[u8], content_type: &str) -> String {
+    static XML_ENCODING: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r#"^\s*<\?xml[^>]*encoding=["']([\w.:-]+)"#).unwrap());
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(200)]);
+    match XML_ENCODING
+        .captures(head.trim_start_matches('\u{feff}'))
+        .and_then(|c| encoding_rs::Encoding::for_label(c[1].as_bytes()))
+    {
+        Some(encoding) => encoding.decode(bytes).0.into_owned(),
+        None => clipper::decode(bytes, content_type),
+    }
+}
+
+// ── Fetching ───────────────────────────────────────────────────────────────
+fn client(app: &AppHandle) -> Result<reqwest::Client, String> {
+    proxy::client_builder(app)
+        .timeout(TIMEOUT)
+        .user_agent(USER_AGENT)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+struct Fetched {
+    /// None when the feed hasn't changed since `state` was saved.
+    body: Option<(String, String)>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+async fn fetch(
+    client: &reqwest::Client,
+    url: &str,
+    state: Option<&FeedState>,
+) -> Result<Fetched, String> {
+    let mut request = client.get(url);
+    if let Some(state) = state {
+        if let Some(etag) = &state.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &state.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| format!("Could not fetch {url}: {e}"))?;
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v: &reqwest::header::HeaderValue| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let mut fetched = Fetched {
+        body: None,
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+    };
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(fetched);
+    }
+    let content_type = header(reqwest::header::CONTENT_TYPE)
+        .unwrap_or_default()
+        .to_lowercase();
+    response = response
+        .error_for_status()
+        .map_err(|e| format!("Could not fetch {url}: {e}"))?;
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Could not fetch {url}: {e}"))?
+    {
+        bytes.extend_from_slice(&chunk);
+        if bytes.len() > MAX_FEED_BYTES {
+            return Err(format!("{url} is larger than {} MB", MAX_FEED_BYTES >> 20));
+        }
+    }
+    let text = decode(&bytes, &content_type);
+    fetched.body = Some((text, content_type));
+    Ok(fetched)
+}
+
+/// The feed at `url`, or the one the page at `url` links to, with the URL
+/// it was found at.
+async fn resolve(client: &reqwest::Client, url: &Url) -> Result<(Url, Parsed), String> {
+    let fetched = fetch(client, url.as_str(), None).await?;
+    let (text, _) = fetched.body.unwrap_or_default();
+    let error = match parse(&text) {
+        Ok(parsed) => return Ok((url.clone(), parsed)),
+        Err(e) => e,
+    };
+    let Some(found) = discover(&text, url).filter(|found| found != url) else {
+        return Err(format!("{url}: {error}"));
+    };
+    let (text, _) = fetch(client, found.as_str(), None)
+        .await?
+        .body
+        .unwrap_or_default();
+    let parsed = parse(&text).map_err(|e| format!("{found}: {e}"))?;
+    Ok((found, parsed))
+}
+
+// ── Refreshing ─────────────────────────────────────────────────────────────
+/// A snippet for `entry`: the linked article when `full_article` and it can
+/// be clipped, else the feed's own content.
+async fn entry_capture(
+    app: &AppHandle,
+    cfg: &FeedsConfig,
+    feed: &Feed,
+    base: &Url,
+    entry: &Entry,
+) -> Capture {
+    let link = (!entry.link.is_empty())
+        .then(|| base.join(&entry.link).ok())
+        .flatten()
+        .filter(|link| matches!(link.scheme(), "http" | "https"));
+    let mut title = (!entry.title.is_empty()).then(|| entry.title.clone());
+    let mut body = String::new();
+    if let (true, Some(link)) = (feed.full_article, &link) {
+        match clipper::clip(app, link).await {
+            Ok(article) => {
+                body = article.markdown;
+                title = title.or(article.title);
+            }
+            Err(e) => log::warn!("Feeds: kept the feed's text for {}: {}", link, e),
+        }
+    }
+    if body.trim().is_empty() {
+        let html = if entry.content.is_empty() {
+            &entry.summary
+        } else {
+            &entry.content
+        };
+        let base = link.as_ref().unwrap_or(base);
+        body = clipper::fragment(app, html, base).await.unwrap_or_default();
+    }
+    if body.trim().is_empty() {
+        body = link
+            .as_ref()
+            .map(Url::to_string)
+            .or_else(|| title.clone())
+            .unwrap_or_default();
+    }
+    let collection = feed
+        .collection
+        .clone()
+        .filter(|c| !c.trim().is_empty())
+        .unwrap_or_else(|| cfg.collection.clone());
+    Capture {
+        title,
+        body,
+        source: SOURCE.into(),
+        source_url: link.map(|l| l.to_string()),
+        tags: feed.tags.clone(),
+        collections: [collection]
+            .into_iter()
+            .filter(|c| !c.trim().is_empty())
+            .collect(),
+    }
+}
+
+/// Checks `feed` and files its new entries; returns how many were filed.
+async fn refresh_feed(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    cfg: &FeedsConfig,
+    feed: &Feed,
+) -> Result<usize, String> {
+    let url = Url::parse(&feed.url).map_err(|e| format!("Invalid feed URL: {e}"))?;
+    let mut state = state_of(&feed.url);
+    let first = state.checked_at.is_none() && state.seen.is_empty();
+    let fetched = fetch(client, url.as_str(), Some(&state)).await?;
+    state.checked_at = Some(chrono::Utc::now().timestamp());
+    state.last_error = None;
+    state.etag = fetched.etag;
+    state.last_modified = fetched.last_modified;
+    let Some((text, _)) = fetched.body else {
+        save_state(&feed.url, Some(state));
+        return Ok(0);
+    };
+    let mut entries = parse(&text)?.entries;
+    // Newest first; undated entries keep their order after dated ones.
+    entries.sort_by_key(|e| std::cmp::Reverse(e.published.unwrap_or(i64::MIN)));
+
+    let seen: HashSet<String> = state.seen.iter().cloned().collect();
+    let mut keys = HashSet::new();
+    let mut new: Vec<&Entry> = entries
+        .iter()
+        .filter(|e| !seen.contains(&e.key()) && keys.insert(e.key()))
+        .collect();
+    if first {
+        // The backlog is marked as seen rather than filed.
+        for entry in new.drain(INITIAL_ENTRIES.min(new.len())..) {
+            state.seen.push(entry.key());
+        }
+    }
+    new.truncate(MAX_PER_CHECK);
+
+    let mut added = 0;
+    for entry in new.into_iter().rev() {
+        let capture = entry_capture(app, cfg, feed, &url, entry).await;
+        match capture::submit(app, capture).await {
+            Ok(_) => added += 1,
+            // Queued in the outbox, so it is filed once the backend is back.
+            Err(e) if backend::is_unreachable(&e) => added += 1,
+            Err(e) => log::warn!("Feeds: skipped an entry: {}", e),
+        }
+        state.seen.push(entry.key());
+    }
+    let excess = state.seen.len().saturating_sub(MAX_SEEN);
+    state.seen.drain(..excess);
+    state.imported += added;
+    save_state(&feed.url, Some(state));
+    Ok(added)
+}
+
+async fn refresh(app: &AppHandle, force: bool) -> Result<FeedsRefresh, String> {
+    if REFRESHING.swap(true, Ordering::SeqCst) {
+        return Err("Feeds are already refreshing".into());
+    }
+    let cfg = config::current(app).feeds;
+    let factor = performance::capabilities(app).poll_factor as i64;
+    let now = chrono::Utc::now().timestamp();
+    let mut report = FeedsRefresh::default();
+    let client = match client(app) {
+        Ok(client) => client,
+        Err(e) => {
+            REFRESHING.store(false, Ordering::SeqCst);
+            return Err(e);
+        }
+    };
+    for (n, feed) in cfg.feeds.iter().enumerate().filter(|(_, f)| f.enabled) {
+        let minutes = feed.interval_minutes.unwrap_or(cfg.interval_minutes).max(1) as i64;
+        let due = state_of(&feed.url)
+            .checked_at
+            .map_or(true, |at| now - at >= minutes * 60 * factor);
+        if !force && !due {
+            continue;
+        }
+        report.checked += 1;
+        match refresh_feed(app, &client, &cfg, feed).await {
+            Ok(added) => report.added += added,
+            Err(e) => {
+                log::warn!("Feeds: could not refresh feed {}: {}", n + 1, e);
+                report.failed += 1;
+                let mut state = state_of(&feed.url);
+                state.checked_at = Some(now);
+                state.last_error = Some(e);
+                save_state(&feed.url, Some(state));
+            }
+        }
+    }
+    REFRESHING.store(false, Ordering::SeqCst);
+    if report.checked > 0 {
+        log::info!(
+            "Feeds: checked {}, filed {} entries, {} failed",
+            report.checked,
+            report.added,
+            report.failed
+        );
+        app.emit_all("feeds-refreshed", &report).ok();
+    }
+    Ok(report)
+}
+
+fn status(app: &AppHandle) -> FeedsStatus {
+    let cfg = config::current(app).feeds;
+    let state = STATE.lock().unwrap();
+    FeedsStatus {
+        enabled: cfg.enabled,
+        interval_minutes: cfg.interval_minutes,
+        collection: cfg.collection,
+        refreshing: REFRESHING.load(Ordering::SeqCst),
+        feeds: cfg
+            .feeds
+            .into_iter()
+            .map(|feed| {
+                let state = state.get(&feed.url).cloned().unwrap_or_default();
+                FeedStatus {
+                    feed,
+                    checked_at: state.checked_at,
+                    last_error: state.last_error,
+                    imported: state.imported,
+                }
+            })
+            .collect(),
+    }
+}
+
+/// Checks feeds as they come due while enabled.
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let cfg = config::current(&handle).feeds;
+            if !cfg.enabled || cfg.feeds.is_empty() || suspend::paused() {
+                continue;
+            }
+            if crate::BACKEND_PORT.load(Ordering::SeqCst) != 0 {
+                refresh(&handle, false).await.ok();
+            }
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn get_feeds(app: AppHandle) -> FeedsStatus {
+    status(&app)
+}
+
+/// Section-wide settings; fields left out keep their current value.
+#[tauri::command]
+pub fn set_feeds(
+    app: AppHandle,
+    enabled: Option<bool>,
+    interval_minutes: Option<u64>,
+    collection: Option<String>,
+) -> Result<FeedsStatus, String> {
+    config::update(&app, |c| {
+        if let Some(enabled) = enabled {
+            c.feeds.enabled = enabled;
+        }
+        if let Some(minutes) = interval_minutes {
+            c.feeds.interval_minutes = minutes.max(1);
+        }
+        if let Some(collection) = collection {
+            c.feeds.collection = collection.trim().to_string();
+        }
+    })?;
+    Ok(status(&app))
+}
+
+/// Subscribes to `feed`, or replaces the subscription with the same URL. A
+/// new URL is checked first, and may be a web page that links to its feed.
+#[tauri::command]
+pub async fn set_feed(app: AppHandle, mut feed: Feed) -> Result<FeedsStatus, String> {
+    let url = feed.url.trim();
+    let url = if url.contains("://") {
+        url.to_string()
+    } else {
+        format!("https://{url}")
+    };
+    let known = config::current(&app)
+        .feeds
+        .feeds
+        .iter()
+        .any(|f| f.url == url);
+    feed.url = url;
+    if !known {
+        let parsed = Url::parse(&feed.url).map_err(|e| format!("Invalid URL: {e}"))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err("Only web feeds can be followed".into());
+        }
+        let (found, contents) = resolve(&client(&app)?, &parsed).await?;
+        feed.url = found.to_string();
+        feed.title = feed
+            .title
+            .filter(|t| !t.trim().is_empty())
+            .or(contents.title);
+    }
+    feed.tags = feed
+        .tags
+        .iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+    config::update(&app, |c| {
+        let feeds = &mut c.feeds.feeds;
+        match feeds.iter_mut().find(|f| f.url == feed.url) {
+            Some(existing) => *existing = feed,
+            None => feeds.push(feed),
+        }
+    })?;
+    Ok(status(&app))
+}
+
+#[tauri::command]
+pub fn remove_feed(app: AppHandle, url: String) -> Result<FeedsStatus, String> {
+    config::update(&app, |c| c.feeds.feeds.retain(|f| f.url != url))?;
+    save_state(&url, None);
+    Ok(status(&app))
+}
+
+/// Checks every enabled feed now, due or not.
+#[tauri::command]
+pub async fn refresh_feeds(app: AppHandle) -> Result<FeedsRefresh, String> {
+    refresh(&app, true).await
+}
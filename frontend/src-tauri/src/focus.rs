@@ -0,0 +1,190 @@
+// Focus — Do Not Disturb / Focus awareness for notifications.
+//
+// The OS state is read every POLL_INTERVAL:
+//   macOS    the Focus assertions in ~/Library/DoNotDisturb (Monterey and
+//            later), else the Notification Center `doNotDisturb` default
+//   Windows  SHQueryUserNotificationState: quiet hours, presentation mode
+//            and full-screen apps (Focus Assist itself has no public API)
+//   Linux    the notification server's `Inhibited` property (KDE), else
+//            GNOME's `show-banners` setting
+// While focus is on, notifications (see notifications.rs) are held back as
+// `notifications.during_focus` says: queued until it ends, dropped, or
+// downgraded to a badge count. Changes go out as "focus-changed".
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::notifications;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(20);
+
+/// What to do with a notification while focus is on.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FocusAction {
+    /// Show it once focus ends.
+    #[default]
+    Queue,
+    /// Drop it.
+    Suppress,
+    /// Only count it on the badge.
+    Badge,
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct FocusState {
+    pub active: bool,
+    /// What turned it on, e.g. "Focus" or "Presentation mode".
+    pub source: Option<String>,
+    /// False where the state can't be read.
+    pub supported: bool,
+}
+
+static CURRENT: Mutex<Option<FocusState>> = Mutex::new(None);
+
+fn on(source: &str) -> FocusState {
+    FocusState {
+        active: true,
+        source: Some(source.into()),
+        supported: true,
+    }
+}
+
+fn off() -> FocusState {
+    FocusState {
+        supported: true,
+        ..Default::default()
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn detect() -> FocusState {
+    let assertions = dirs::home_dir()
+        .map(|home| home.join("Library/DoNotDisturb/DB/Assertions.json"))
+        .and_then(|path| std::fs::read_to_string(path).ok());
+    if let Some(text) = assertions {
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(&text) else {
+            return FocusState::default();
+        };
+        let active = json["data"].as_array().into_iter().flatten().any(|d| {
+            d["storeAssertionRecords"]
+                .as_array()
+                .is_some_and(|records| !records.is_empty())
+        });
+        return if active { on("Focus") } else { off() };
+    }
+    let Ok(output) = tauri::api::process::Command::new("defaults")
+        .args([
+            "-currentHost",
+            "read",
+            "com.apple.notificationcenterui",
+            "doNotDisturb",
+        ])
+        .output()
+    else {
+        return FocusState::default();
+    };
+    match output.stdout.trim() {
+        "1" => on("Do Not Disturb"),
+        // Unset until it is first turned on.
+        _ => off(),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn detect() -> FocusState {
+    use windows::Win32::UI::Shell::{
+        SHQueryUserNotificationState, QUNS_BUSY, QUNS_PRESENTATION_MODE, QUNS_QUIET_TIME,
+        QUNS_RUNNING_D3D_FULL_SCREEN,
+    };
+
+    match unsafe { SHQueryUserNotificationState() } {
+        Ok(QUNS_QUIET_TIME) => on("Quiet hours"),
+        Ok(QUNS_PRESENTATION_MODE) => on("Presentation mode"),
+        Ok(QUNS_BUSY | QUNS_RUNNING_D3D_FULL_SCREEN) => on("Full-screen app"),
+        Ok(_) => off(),
+        Err(_) => FocusState::default(),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn detect() -> FocusState {
+    use zbus::blocking::{Connection, Proxy};
+
+    let inhibited = Connection::session().ok().and_then(|bus| {
+        let proxy = Proxy::new(
+            &bus,
+            "org.freedesktop.Notifications",
+            "/org/freedesktop/Notifications",
+            "org.freedesktop.Notifications",
+        )
+        .ok()?;
+        proxy.get_property::<bool>("Inhibited").ok()
+    });
+    match inhibited {
+        Some(true) => return on("Do Not Disturb"),
+        Some(false) => return off(),
+        None => {}
+    }
+    let Ok(output) = tauri::api::process::Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.notifications", "show-banners"])
+        .output()
+    else {
+        return FocusState::default();
+    };
+    match output.stdout.trim() {
+        "false" => on("Do Not Disturb"),
+        "true" => off(),
+        _ => FocusState::default(),
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn detect() -> FocusState {
+    FocusState::default()
+}
+
+/// The last state read; off until the first poll.
+pub fn current() -> FocusState {
+    CURRENT.lock().unwrap().clone().unwrap_or_default()
+}
+
+/// True while the user doesn't want to be disturbed.
+pub fn active() -> bool {
+    current().active
+}
+
+/// Reads the state, announcing a change and releasing held notifications
+/// when focus ends.
+fn poll(app: &AppHandle) -> FocusState {
+    let state = detect();
+    let previous = CURRENT.lock().unwrap().replace(state.clone());
+    if previous.as_ref() != Some(&state) {
+        log::info!("Focus: {:?}", state);
+        app.emit_all("focus-changed", &state).ok();
+        if previous.is_some_and(|p| p.active) && !state.active {
+            notifications::focus_ended(app);
+        }
+    }
+    state
+}
+
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    std::thread::spawn(move || loop {
+        poll(&handle);
+        std::thread::sleep(POLL_INTERVAL);
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Whether the OS is in Do Not Disturb / Focus, read fresh.
+#[tauri::command]
+pub async fn get_focus_state(app: AppHandle) -> FocusState {
+    tauri::async_runtime::spawn_blocking(move || poll(&app))
+        .await
+        .unwrap_or_default()
+}
@@ -0,0 +1,919 @@
+// Git sync — versioned, self-hosted sync through a Git repository.
+//
+// While enabled, every `interval_minutes` (times the poll factor) the library
+// is written into a local repository as one file per snippet, committed when
+// anything changed, merged with the remote branch and pushed. What the merge
+// brought in is applied to the library: new files are imported under their
+// own ids, changed ones patched, removed ones deleted. Where both sides
+// edited the same lines the local side wins (as with `git merge -X ours`,
+// and a snippet deleted on one side but edited on the other is kept); the
+// other version stays in history. A snippet both sides changed since the
+// merge base is also kept for review as a conflict (see conflicts.rs).
+// Without a remote it is a local, versioned copy.
+//
+// snippets/<id>.md holds frontmatter whose values are JSON (so it is also
+// valid YAML), then the body exactly as stored:
+//
+//   ---
+//   id: "3f2c..."
+//   title: "Deploy checklist"
+//   tags: ["ops","work"]
+//   ---
+//   body
+//
+// Fields that change on every save (updated_at, content_hash) are left out,
+// so applying a pulled change doesn't itself produce a commit. With `encrypt`
+// (the default) each file is sealed with the sync key instead (see
+// sync_key.rs), so the remote only holds ciphertext; sealing is
+// deterministic, which keeps that true. Sealed and plain files are both
+// read, so turning it on rewrites every file in one commit, but earlier
+// commits keep their plaintext: start a new repository to be rid of it. A pulled file
+// that can't be applied stays in the repository and is retried next sync
+// instead of being removed by the next export.
+//
+// Repository work goes through libgit2 (the git2 crate), so no git
+// installation is needed. Fetch and push use the user's own credentials
+// (the SSH agent, or the git credential helper for HTTPS) and never prompt.
+
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use git2::build::CheckoutBuilder;
+use git2::{
+    Commit, Cred, CredentialType, Delta, DiffOptions, FetchOptions, FileFavor, IndexAddOption,
+    MergeOptions, PushOptions, RemoteCallbacks, Repository, RepositoryInitOptions, Signature, Tree,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::conflicts::{Conflict, Side};
+use crate::sync_key::{self, Key};
+use crate::{backend, config, conflicts, exports, performance, suspend, tray_status};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const SNIPPETS: &str = "snippets";
+/// Stage bits of an index entry's flags; a resolved entry has none.
+const STAGE_MASK: u16 = 0x3000;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct GitSyncConfig {
+    pub enabled: bool,
+    /// Local repository; created if missing.
+    pub repo: Option<String>,
+    /// Pulled from and pushed to as `origin`; None keeps the repo local.
+    pub remote: Option<String>,
+    pub branch: String,
+    pub interval_minutes: u64,
+    pub author_name: String,
+    pub author_email: String,
+    /// Seal snippet files with the sync key.
+    pub encrypt: bool,
+}
+
+impl Default for GitSyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            repo: None,
+            remote: None,
+            branch: "main".into(),
+            interval_minutes: 15,
+            author_name: "Pin-Up AI".into(),
+            author_email: "pinup@localhost".into(),
+            encrypt: true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct SyncState {
+    /// Repository the state belongs to; a different one starts over.
+    repo: String,
+    /// Pulled snippets not applied yet.
+    pending: BTreeSet<String>,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct GitSyncReport {
+    /// RFC 3339.
+    at: String,
+    /// Short hash of HEAD afterwards.
+    commit: Option<String>,
+    /// Snippet files in the local commit (none when nothing changed).
+    committed: usize,
+    /// Pulled changes applied to the library.
+    created: usize,
+    updated: usize,
+    deleted: usize,
+    /// Pulled snippets that couldn't be applied; retried next sync.
+    pending: usize,
+    /// Snippets both sides changed, kept for review.
+    conflicts: usize,
+    pushed: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct GitSyncStatus {
+    config: GitSyncConfig,
+    syncing: bool,
+    last_sync: Option<GitSyncReport>,
+    last_error: Option<String>,
+}
+
+static SYNCING: AtomicBool = AtomicBool::new(false);
+static LAST: Mutex<(Option<GitSyncReport>, Option<String>)> = Mutex::new((None, None));
+static LAST_RUN: Mutex<Option<Instant>> = Mutex::new(None);
+
+fn state_path() -> PathBuf {
+    crate::data_dir().join("git-sync.json")
+}
+
+fn load_state() -> SyncState {
+    std::fs::read_to_string(state_path())
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &SyncState) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    let tmp = state_path().with_extension("json.tmp");
+    std::fs::write(&tmp, text).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, state_path()).map_err(|e| e.to_string())
+}
+
+// ── git ────────────────────────────────────────────────────────────────────
+fn failed(action: &'static str) -> impl Fn(git2::Error) -> String {
+    move |e| format!("git {action} failed: {}", e.message())
+}
+
+/// Fetch and push credentials: the SSH agent, or the user's git credential
+/// helper for HTTPS. Nothing prompts; a rejected credential is retried a few
+/// times before the operation fails.
+fn callbacks<'a>(repo: &Repository) -> RemoteCallbacks<'a> {
+    let config = repo.config().ok();
+    let mut tries = 0;
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username, allowed| {
+        tries += 1;
+        if tries > 3 {
+            return Err(git2::Error::from_str("authentication failed"));
+        }
+        let user = username.unwrap_or("git");
+        if allowed.contains(CredentialType::USERNAME) {
+            Cred::username(user)
+        } else if allowed.contains(CredentialType::SSH_KEY) {
+            Cred::ssh_key_from_agent(user)
+        } else if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            let config = config
+                .as_ref()
+                .ok_or_else(|| git2::Error::from_str("no git configuration"))?;
+            Cred::credential_helper(config, url, username)
+        } else {
+            Cred::default()
+        }
+    });
+    callbacks
+}
+
+fn repository(path: &Path) -> Result<Repository, String> {
+    Repository::open(path).map_err(failed("open"))
+}
+
+fn head(repo: &Repository) -> Option<Commit<'_>> {
+    repo.head().ok()?.peel_to_commit().ok()
+}
+
+fn signature(cfg: &GitSyncConfig) -> Result<Signature<'static>, String> {
+    Signature::now(&cfg.author_name, &cfg.author_email).map_err(failed("commit"))
+}
+
+/// Creates the repository if needed and points `origin` at the remote.
+fn open_repo(cfg: &GitSyncConfig) -> Result<PathBuf, String> {
+    let path = PathBuf::from(cfg.repo.as_ref().ok_or("No sync repository chosen")?);
+    std::fs::create_dir_all(path.join(SNIPPETS))
+        .map_err(|e| format!("Could not create {:?}: {e}", path))?;
+    let repo = if path.join(".git").exists() {
+        repository(&path)?
+    } else {
+        let repo = Repository::init_opts(
+            &path,
+            RepositoryInitOptions::new().initial_head(&cfg.branch),
+        )
+        .map_err(failed("init"))?;
+        log::info!("Git sync: initialized {:?}", path);
+        repo
+    };
+    repo.config()
+        .and_then(|mut config| config.set_bool("core.autocrlf", false))
+        .map_err(failed("config"))?;
+    let origin = repo
+        .find_remote("origin")
+        .ok()
+        .and_then(|remote| remote.url().map(str::to_string));
+    match (&cfg.remote, origin) {
+        (Some(url), None) => {
+            repo.remote("origin", url).map_err(failed("remote"))?;
+        }
+        (Some(url), Some(origin)) if *url != origin => {
+            repo.remote_set_url("origin", url)
+                .map_err(failed("remote"))?;
+        }
+        (None, Some(_)) => {
+            repo.remote_delete("origin").map_err(failed("remote"))?;
+        }
+        _ => {}
+    }
+    Ok(path)
+}
+
+/// (status letter, path) of the snippet files that differ between two
+/// trees; renames count as a removal and an addition.
+fn changes(
+    repo: &Repository,
+    old: Option<&Tree>,
+    new: Option<&Tree>,
+) -> Result<Vec<(char, String)>, String> {
+    let diff = repo
+        .diff_tree_to_tree(old, new, Some(DiffOptions::new().pathspec(SNIPPETS)))
+        .map_err(failed("diff"))?;
+    let changes = diff
+        .deltas()
+        .filter_map(|delta| {
+            let status = match delta.status() {
+                Delta::Added => 'A',
+                Delta::Deleted => 'D',
+                _ => 'M',
+            };
+            let file = delta.new_file().path().or(delta.old_file().path())?;
+            Some((status, file.to_str()?.to_string()))
+        })
+        .collect();
+    Ok(changes)
+}
+
+// ── Snippet files ──────────────────────────────────────────────────────────
+fn flag(value: &Value) -> bool {
+    value
+        .as_bool()
+        .unwrap_or_else(|| value.as_i64().unwrap_or(0) != 0)
+}
+
+fn render(snippet: &Value, tags: &[String], collections: &[String]) -> String {
+    // The backend stores tags lowercased.
+    let tags: BTreeSet<String> = tags.iter().map(|t| t.to_lowercase()).collect();
+    let collections: BTreeSet<&String> = collections.iter().collect();
+    let fields = [
+        ("id", snippet["id"].clone()),
+        ("title", snippet["title"].clone()),
+        ("language", snippet["language"].clone()),
+        ("source", snippet["source"].clone()),
+        ("source_url", snippet["source_url"].clone()),
+        ("pinned", json!(flag(&snippet["pinned"]))),
+        ("archived", json!(flag(&snippet["archived"]))),
+        ("created_at", snippet["created_at"].clone()),
+        ("tags", json!(tags)),
+        ("collections", json!(collections)),
+    ];
+    let mut out = String::from("---\n");
+    for (key, value) in fields {
+        out.push_str(&format!("{key}: {value}\n"));
+    }
+    out.push_str("---\n");
+    out.push_str(snippet["body"].as_str().unwrap_or_default());
+    out
+}
+
+/// Frontmatter fields and body of a snippet file.
+fn parse(text: &str) -> Option<(Map<String, Value>, String)> {
+    let rest = text.strip_prefix("---\n")?;
+    let end = rest.find("\n---\n")?;
+    let mut fields = Map::new();
+    for line in rest[..end].lines() {
+        let (key, value) = line.split_once(": ")?;
+        let value = serde_json::from_str(value.trim_end_matches('\r')).ok()?;
+        fields.insert(key.to_string(), value);
+    }
+    Some((fields, rest[end + 5..].to_string()))
+}
+
+/// A snippet file's text, unsealed when it is sealed.
+fn unseal(text: &str, key: Option<&Key>) -> Result<String, String> {
+    if !sync_key::is_sealed(text) {
+        return Ok(text.to_string());
+    }
+    sync_key::open_text(key.ok_or("it is sealed and there is no sync key")?, text)
+}
+
+/// Unsealed snippet file text as a snippet shaped like
+/// `exports::snippets_with_names`.
+fn file_snippet(id: &str, text: &str) -> Option<Value> {
+    let (fields, body) = parse(text)?;
+    let mut snippet = Value::Object(fields);
+    snippet["id"] = json!(id);
+    snippet["body"] = json!(body);
+    for key in ["pinned", "archived"] {
+        snippet[key] = json!(flag(&snippet[key]));
+    }
+    Some(snippet)
+}
+
+/// The snippet id of a path under snippets/.
+fn file_id(path: &str) -> Option<&str> {
+    let file = Path::new(path);
+    if file.extension().map_or(true, |ext| ext != "md") {
+        return None;
+    }
+    file.file_stem().and_then(|s| s.to_str())
+}
+
+fn safe_id(id: &str) -> bool {
+    !id.is_empty() && !id.contains(['/', '\\']) && id != "." && id != ".."
+}
+
+/// Writes every snippet to snippets/, sealed with `seal` if given, and
+/// removes the files of deleted ones, except those in `keep`. Unchanged files
+/// are left untouched.
+fn write_library(
+    repo: &Path,
+    data: &Value,
+    keep: &BTreeSet<String>,
+    seal: Option<&Key>,
+) -> Result<(), String> {
+    let dir = repo.join(SNIPPETS);
+    let tags = exports::joined(
+        data,
+        "snippet_tags",
+        "tag_id",
+        &exports::names_by_id(data, "tags"),
+    );
+    let collections = exports::joined(
+        data,
+        "snippet_collections",
+        "collection_id",
+        &exports::names_by_id(data, "collections"),
+    );
+    let mut ids = HashSet::new();
+    for snippet in data["snippets"].as_array().into_iter().flatten() {
+        let Some(id) = snippet["id"].as_str().filter(|id| safe_id(id)) else {
+            continue;
+        };
+        ids.insert(id.to_string());
+        let content = render(
+            snippet,
+            tags.get(id).map_or(&[], Vec::as_slice),
+            collections.get(id).map_or(&[], Vec::as_slice),
+        );
+        let content = match seal {
+            Some(key) => sync_key::seal_text(key, &content)?,
+            None => content,
+        };
+        let path = dir.join(format!("{id}.md"));
+        if std::fs::read(&path).ok().as_deref() != Some(content.as_bytes()) {
+            std::fs::write(&path, content)
+                .map_err(|e| format!("Could not write {:?}: {e}", path))?;
+        }
+    }
+    let entries = std::fs::read_dir(&dir).map_err(|e| e.to_string())?;
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.extension().is_some_and(|ext| ext == "md") {
+            let id = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            if !ids.contains(&id) && !keep.contains(&id) {
+                std::fs::remove_file(&path).ok();
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Commits snippets/ if anything changed; returns the number of files.
+fn commit(repo: &Repository, cfg: &GitSyncConfig) -> Result<usize, String> {
+    let mut index = repo.index().map_err(failed("add"))?;
+    index
+        .add_all([SNIPPETS], IndexAddOption::DEFAULT, None)
+        .and_then(|_| index.update_all([SNIPPETS], None))
+        .and_then(|_| index.write())
+        .map_err(failed("add"))?;
+    let parent = head(repo);
+    let old = parent.as_ref().map(tree).transpose()?;
+    let tree = index
+        .write_tree()
+        .and_then(|id| repo.find_tree(id))
+        .map_err(failed("commit"))?;
+    let changes = changes(repo, old.as_ref(), Some(&tree))?;
+    if changes.is_empty() {
+        return Ok(0);
+    }
+    let count = |status: char| changes.iter().filter(|(s, _)| *s == status).count();
+    let message = format!(
+        "Sync: {} added, {} changed, {} removed",
+        count('A'),
+        count('M'),
+        count('D')
+    );
+    let sig = signature(cfg)?;
+    let parents: Vec<&Commit> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &sig, &sig, &message, &tree, &parents)
+        .map_err(failed("commit"))?;
+    Ok(changes.len())
+}
+
+/// Merges `theirs` into `ours` as `git merge -X ours` would: where both
+/// sides changed the same lines the local side wins, and a file gone on one
+/// side but edited on the other is kept.
+fn merge<'r>(
+    repo: &'r Repository,
+    cfg: &GitSyncConfig,
+    ours: &Commit,
+    theirs: &Commit,
+) -> Result<Commit<'r>, String> {
+    // Snippet files share their frontmatter, so rename detection would take
+    // a deleted snippet and a new one for the same file.
+    let mut options = MergeOptions::new();
+    options.file_favor(FileFavor::Ours).find_renames(false);
+    let mut index = repo
+        .merge_commits(ours, theirs, Some(&options))
+        .map_err(failed("merge"))?;
+    let mut kept = Vec::new();
+    for conflict in index.conflicts().map_err(failed("merge"))? {
+        let conflict = conflict.map_err(failed("merge"))?;
+        // Gone locally but edited remotely: the remote copy is kept.
+        kept.extend(conflict.our.or(conflict.their));
+    }
+    for mut entry in kept {
+        let path = String::from_utf8_lossy(&entry.path).into_owned();
+        entry.flags &= !STAGE_MASK;
+        index
+            .remove_path(Path::new(&path))
+            .and_then(|_| index.add(&entry))
+            .map_err(failed("merge"))?;
+    }
+    let tree = index
+        .write_tree_to(repo)
+        .and_then(|id| repo.find_tree(id))
+        .map_err(failed("merge"))?;
+    let sig = signature(cfg)?;
+    let message = format!(
+        "Merge branch '{}' of {}",
+        cfg.branch,
+        cfg.remote.as_deref().unwrap_or("origin")
+    );
+    repo.commit(None, &sig, &sig, &message, &tree, &[ours, theirs])
+        .and_then(|id| repo.find_commit(id))
+        .map_err(failed("merge"))
+}
+
+/// Checks out `commit` and moves the current branch to it.
+fn move_head(repo: &Repository, commit: &Commit) -> Result<(), String> {
+    repo.checkout_tree(commit.as_object(), Some(CheckoutBuilder::new().force()))
+        .map_err(failed("checkout"))?;
+    let branch = repo
+        .find_reference("HEAD")
+        .ok()
+        .and_then(|head| head.symbolic_target().map(str::to_string));
+    match branch {
+        Some(branch) => repo
+            .reference(&branch, commit.id(), true, "sync: merge")
+            .map(|_| ()),
+        None => repo.set_head_detached(commit.id()),
+    }
+    .map_err(failed("checkout"))
+}
+
+/// A file in `tree`, unsealed, or None where it doesn't exist or can't be
+/// unsealed.
+fn show(repo: &Repository, tree: &Tree, path: &str, key: Option<&Key>) -> Option<String> {
+    let blob = tree.get_path(Path::new(path)).ok()?.to_object(repo).ok()?;
+    let text = std::str::from_utf8(blob.as_blob()?.content()).ok()?;
+    unseal(text, key).ok()
+}
+
+fn tree<'r>(commit: &Commit<'r>) -> Result<Tree<'r>, String> {
+    commit.tree().map_err(failed("diff"))
+}
+
+/// Snippet files both `ours` and `theirs` changed since they split.
+fn diverged(repo: &Repository, ours: &Commit, theirs: &Commit) -> Result<BTreeSet<String>, String> {
+    let (our_tree, their_tree) = (tree(ours)?, tree(theirs)?);
+    let Ok(base) = repo.merge_base(ours.id(), theirs.id()) else {
+        // Unrelated histories: every file they hold differently.
+        let changed = changes(repo, Some(&our_tree), Some(&their_tree))?;
+        return Ok(changed
+            .into_iter()
+            .filter(|(status, _)| *status == 'M')
+            .map(|(_, path)| path)
+            .collect());
+    };
+    let base = repo
+        .find_commit(base)
+        .and_then(|c| c.tree())
+        .map_err(failed("diff"))?;
+    let changed = |tree: &Tree| -> Result<BTreeSet<String>, String> {
+        let changed = changes(repo, Some(&base), Some(tree))?;
+        Ok(changed.into_iter().map(|(_, path)| path).collect())
+    };
+    let local = changed(&our_tree)?;
+    Ok(changed(&their_tree)?
+        .intersection(&local)
+        .cloned()
+        .collect())
+}
+
+/// Files a pull changed, and the snippets both sides changed.
+#[derive(Default)]
+struct Pulled {
+    /// (status letter, path).
+    changes: Vec<(char, String)>,
+    conflicts: Vec<Conflict>,
+}
+
+/// Fetches and merges the remote branch.
+fn pull(repo: &Repository, cfg: &GitSyncConfig, key: Option<&Key>) -> Result<Pulled, String> {
+    let tracking = format!("refs/remotes/origin/{}", cfg.branch);
+    let refspec = format!("+refs/heads/{}:{tracking}", cfg.branch);
+    let mut options = FetchOptions::new();
+    options.remote_callbacks(callbacks(repo));
+    repo.find_remote("origin")
+        .and_then(|mut remote| remote.fetch(&[&refspec], Some(&mut options), None))
+        .map_err(failed("fetch"))?;
+    let Ok(theirs) = repo
+        .find_reference(&tracking)
+        .and_then(|r| r.peel_to_commit())
+    else {
+        // Nothing pushed to the remote yet.
+        return Ok(Pulled::default());
+    };
+    let Some(before) = head(repo) else {
+        // Nothing committed here yet: the remote branch as it is.
+        move_head(repo, &theirs)?;
+        return Ok(Pulled {
+            changes: changes(repo, None, Some(&tree(&theirs)?))?,
+            conflicts: Vec::new(),
+        });
+    };
+    let contains = |commit: &Commit, other: &Commit| {
+        commit.id() == other.id()
+            || repo
+                .graph_descendant_of(commit.id(), other.id())
+                .unwrap_or(false)
+    };
+    if contains(&before, &theirs) {
+        return Ok(Pulled::default());
+    }
+    let (candidates, after) = if contains(&theirs, &before) {
+        (BTreeSet::new(), theirs.clone())
+    } else {
+        (
+            diverged(repo, &before, &theirs)?,
+            merge(repo, cfg, &before, &theirs)?,
+        )
+    };
+    move_head(repo, &after)?;
+    let (ours, remote, merged) = (tree(&before)?, tree(&theirs)?, tree(&after)?);
+    let changes = changes(repo, Some(&ours), Some(&merged))?;
+
+    let mut conflicts = Vec::new();
+    for path in candidates {
+        let Some(id) = file_id(&path) else {
+            continue;
+        };
+        let local = show(repo, &ours, &path, key);
+        let theirs = show(repo, &remote, &path, key);
+        if local == theirs {
+            continue;
+        }
+        let kept = if show(repo, &merged, &path, key) == theirs {
+            Side::Remote
+        } else {
+            Side::Local
+        };
+        let local = local.and_then(|text| file_snippet(id, &text));
+        let remote = theirs.and_then(|text| file_snippet(id, &text));
+        if local.is_some() || remote.is_some() {
+            conflicts.push(Conflict::new("Git", id, local, remote, kept));
+        }
+    }
+    Ok(Pulled { changes, conflicts })
+}
+
+/// Pushes the current branch to the remote branch; fails when the remote
+/// rejects it.
+fn push(repo: &Repository, cfg: &GitSyncConfig) -> Result<(), String> {
+    let head = repo
+        .head()
+        .ok()
+        .and_then(|head| head.name().map(str::to_string))
+        .ok_or("Nothing to push")?;
+    let refspec = format!("{head}:refs/heads/{}", cfg.branch);
+    let mut rejected = None;
+    {
+        let mut callbacks = callbacks(repo);
+        callbacks.push_update_reference(|_, status| {
+            rejected = status.map(str::to_string);
+            Ok(())
+        });
+        let mut options = PushOptions::new();
+        options.remote_callbacks(callbacks);
+        repo.find_remote("origin")
+            .and_then(|mut remote| remote.push(&[&refspec], Some(&mut options)))
+            .map_err(failed("push"))?;
+    }
+    match rejected {
+        Some(reason) => Err(format!("git push failed: {reason}")),
+        None => Ok(()),
+    }
+}
+
+// ── Library ────────────────────────────────────────────────────────────────
+async fn blocking<T: Send + 'static>(
+    work: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(work)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+async fn library() -> Result<Value, String> {
+    let json = exports::fetch_export(None, None).await?;
+    serde_json::from_slice(&json).map_err(|e| e.to_string())
+}
+
+fn ids(data: &Value) -> HashSet<String> {
+    data["snippets"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|s| s["id"].as_str().map(str::to_string))
+        .collect()
+}
+
+/// Applies pulled snippet files to the library; returns the ids that
+/// couldn't be applied.
+async fn apply(
+    repo: &Path,
+    changes: &[(char, String)],
+    known: &HashSet<String>,
+    key: Option<&Key>,
+    report: &mut GitSyncReport,
+) -> BTreeSet<String> {
+    let mut failed = BTreeSet::new();
+    let mut creates = Vec::new();
+    for (status, path) in changes {
+        let Some(id) = file_id(path) else {
+            continue;
+        };
+        if *status == 'D' {
+            if known.contains(id) {
+                match backend::delete(&format!("/snippets/{id}")).await {
+                    Ok(_) => report.deleted += 1,
+                    Err(e) => log::warn!("Git sync: could not delete {}: {}", id, e),
+                }
+            }
+            continue;
+        }
+        let text = std::fs::read_to_string(repo.join(path)).map_err(|e| e.to_string());
+        let parsed = match text.and_then(|text| unseal(&text, key)) {
+            Ok(text) => file_snippet(id, &text),
+            Err(e) => {
+                log::warn!("Git sync: can't read {}: {}", path, e);
+                failed.insert(id.to_string());
+                continue;
+            }
+        };
+        let Some(snippet) = parsed else {
+            log::warn!("Git sync: {} is not a snippet file", path);
+            failed.insert(id.to_string());
+            continue;
+        };
+        if !known.contains(id) {
+            creates.push(snippet);
+            continue;
+        }
+        let patch = exports::snippet_patch(&snippet);
+        match backend::patch(&format!("/snippets/{id}"), &patch).await {
+            Ok(_) => report.updated += 1,
+            Err(e) => {
+                log::warn!("Git sync: could not update {}: {}", id, e);
+                failed.insert(id.to_string());
+            }
+        }
+    }
+    if !creates.is_empty() {
+        let ids: Vec<String> = creates
+            .iter()
+            .filter_map(|s| s["id"].as_str().map(str::to_string))
+            .collect();
+        let json = serde_json::to_vec(&exports::bundle_with_names(creates)).unwrap_or_default();
+        match exports::upload_json(&json).await {
+            Ok(_) => report.created += ids.len(),
+            Err(e) => {
+                log::warn!("Git sync: could not import {} snippets: {}", ids.len(), e);
+                failed.extend(ids);
+            }
+        }
+    }
+    failed
+}
+
+async fn run(cfg: GitSyncConfig) -> Result<GitSyncReport, String> {
+    let mut report = GitSyncReport {
+        at: chrono::Local::now().to_rfc3339(),
+        ..Default::default()
+    };
+    let setup = cfg.clone();
+    let repo = blocking(move || open_repo(&setup)).await?;
+    let mut state = load_state();
+    let repo_key = repo.to_string_lossy().to_string();
+    if state.repo != repo_key {
+        state = SyncState {
+            repo: repo_key,
+            ..Default::default()
+        };
+    }
+
+    // Needed to read sealed files even while not sealing.
+    let key = match sync_key::derive("git").await {
+        Ok(key) => Some(key),
+        Err(e) if cfg.encrypt => return Err(e),
+        Err(_) => None,
+    };
+    let seal = key.filter(|_| cfg.encrypt);
+
+    let mut data = library().await?;
+    if !state.pending.is_empty() {
+        let retry: Vec<(char, String)> = state
+            .pending
+            .iter()
+            .map(|id| format!("{SNIPPETS}/{id}.md"))
+            .filter(|path| repo.join(path).is_file())
+            .map(|path| ('M', path))
+            .collect();
+        state.pending = apply(&repo, &retry, &ids(&data), key.as_ref(), &mut report).await;
+        data = library().await?;
+    }
+
+    let (dir, keep, local) = (repo.clone(), state.pending.clone(), cfg.clone());
+    report.committed = blocking(move || {
+        write_library(&dir, &data, &keep, seal.as_ref())?;
+        commit(&repository(&dir)?, &local)
+    })
+    .await?;
+
+    if cfg.remote.is_some() {
+        let (dir, remote) = (repo.clone(), cfg.clone());
+        let pulled = blocking(move || pull(&repository(&dir)?, &remote, key.as_ref())).await?;
+        if !pulled.changes.is_empty() {
+            let known = ids(&library().await?);
+            let failed = apply(&repo, &pulled.changes, &known, key.as_ref(), &mut report).await;
+            state.pending.extend(failed);
+        }
+        // Pulled changes made on top of the local version.
+        let settled: Vec<String> = pulled
+            .changes
+            .iter()
+            .filter_map(|(_, path)| file_id(path))
+            .filter(|id| !pulled.conflicts.iter().any(|c| c.snippet_id == *id))
+            .map(str::to_string)
+            .collect();
+        conflicts::settle("Git", &settled)?;
+        report.conflicts = pulled.conflicts.len();
+        conflicts::record(pulled.conflicts)?;
+        save_state(&state)?;
+        let (dir, remote) = (repo.clone(), cfg.clone());
+        blocking(move || push(&repository(&dir)?, &remote)).await?;
+        report.pushed = true;
+    } else {
+        save_state(&state)?;
+    }
+    report.pending = state.pending.len();
+    report.commit = repository(&repo)
+        .ok()
+        .and_then(|repo| head(&repo).map(|commit| commit.id().to_string()))
+        .map(|hash| hash.chars().take(7).collect());
+    Ok(report)
+}
+
+async fn sync(app: &AppHandle) -> Result<GitSyncReport, String> {
+    let cfg = config::current(app).git_sync;
+    if cfg.repo.is_none() {
+        return Err("No sync repository chosen".into());
+    }
+    if SYNCING.swap(true, Ordering::SeqCst) {
+        return Err("A sync is already running".into());
+    }
+    let result = run(cfg).await;
+    SYNCING.store(false, Ordering::SeqCst);
+    *LAST_RUN.lock().unwrap() = Some(Instant::now());
+
+    let mut last = LAST.lock().unwrap();
+    match &result {
+        Ok(report) => {
+            log::info!(
+                "Git sync: {} committed, {} created, {} updated, {} deleted, {} pending, {} conflicts",
+                report.committed,
+                report.created,
+                report.updated,
+                report.deleted,
+                report.pending,
+                report.conflicts
+            );
+            *last = (Some(report.clone()), None);
+            tray_status::synced();
+            app.emit_all("git-synced", report).ok();
+        }
+        Err(e) => {
+            log::warn!("Git sync failed: {}", e);
+            last.1 = Some(e.clone());
+        }
+    }
+    result
+}
+
+fn status(app: &AppHandle) -> GitSyncStatus {
+    let (last_sync, last_error) = LAST.lock().unwrap().clone();
+    GitSyncStatus {
+        config: config::current(app).git_sync,
+        syncing: SYNCING.load(Ordering::SeqCst),
+        last_sync,
+        last_error,
+    }
+}
+
+/// Syncs on the configured interval while enabled.
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let cfg = config::current(&handle).git_sync;
+            if !cfg.enabled || cfg.repo.is_none() || suspend::paused() {
+                continue;
+            }
+            let factor = performance::capabilities(&handle).poll_factor as u32;
+            let interval = Duration::from_secs(cfg.interval_minutes.max(1) * 60) * factor;
+            let due = LAST_RUN
+                .lock()
+                .unwrap()
+                .map_or(true, |at| at.elapsed() >= interval);
+            if due && crate::BACKEND_PORT.load(Ordering::SeqCst) != 0 {
+                sync(&handle).await.ok();
+            }
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn get_git_sync_status(app: AppHandle) -> GitSyncStatus {
+    status(&app)
+}
+
+/// Points sync at the repository folder `repo` (None turns it off), with an
+/// optional remote URL and branch, and syncs right away. `encrypt` left out
+/// keeps the current choice.
+#[tauri::command]
+pub async fn set_git_sync(
+    app: AppHandle,
+    repo: Option<String>,
+    remote: Option<String>,
+    branch: Option<String>,
+    encrypt: Option<bool>,
+) -> Result<GitSyncStatus, String> {
+    if let Some(path) = &repo {
+        if Path::new(path).is_file() {
+            return Err(format!("{path} is a file"));
+        }
+    }
+    let remote = remote
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty());
+    let enabled = repo.is_some();
+    config::update(&app, |c| {
+        c.git_sync.enabled = enabled;
+        c.git_sync.repo = repo;
+        c.git_sync.remote = remote;
+        if let Some(branch) = branch.filter(|b| !b.trim().is_empty()) {
+            c.git_sync.branch = branch.trim().to_string();
+        }
+        if let Some(encrypt) = encrypt {
+            c.git_sync.encrypt = encrypt;
+        }
+    })?;
+    if enabled {
+        sync(&app).await?;
+    }
+    Ok(status(&app))
+}
+
+#[tauri::command]
+pub async fn sync_git_now(app: AppHandle) -> Result<GitSyncReport, String> {
+    sync(&app).await
+}
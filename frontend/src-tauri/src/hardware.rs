@@ -0,0 +1,353 @@
+// Hardware — what this machine can run locally.
+//
+// CPU and RAM come from sysinfo. GPUs are probed per platform:
+//   macOS    system_profiler (Apple silicon shares system RAM as VRAM)
+//   Windows  Win32_VideoController via PowerShell
+//   Linux    /sys/class/drm
+// nvidia-smi, when present, overrides VRAM for NVIDIA cards (the other
+// sources cap or omit it). CUDA and Vulkan count as available when their
+// runtime library is installed; Metal means macOS.
+//
+// Probing spawns processes, so the result is cached until `refresh`.
+// `recommendation` turns it into local-vs-cloud advice and the accelerator
+// AI sidecars should be told to use.
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tauri::api::process::Command;
+
+const GB: u64 = 1024 * 1024 * 1024;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Gpu {
+    pub name: String,
+    /// "nvidia", "amd", "intel", "apple" or "unknown".
+    pub vendor: String,
+    pub vram_bytes: Option<u64>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Accelerator {
+    Cuda,
+    Metal,
+    Vulkan,
+    Cpu,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Recommendation {
+    /// "local" when small local models will run comfortably, else "cloud".
+    pub models: &'static str,
+    pub accelerator: Accelerator,
+    /// Rough upper bound for a local model's weights.
+    pub max_model_bytes: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct HardwareInfo {
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub cpu_brand: String,
+    pub physical_cores: Option<usize>,
+    pub logical_cores: usize,
+    pub total_memory: u64,
+    pub available_memory: u64,
+    pub gpus: Vec<Gpu>,
+    pub cuda: bool,
+    pub metal: bool,
+    pub vulkan: bool,
+    pub recommendation: Recommendation,
+}
+
+static CACHE: Lazy<Mutex<Option<HardwareInfo>>> = Lazy::new(|| Mutex::new(None));
+
+fn vendor_of(name: &str) -> &'static str {
+    let name = name.to_lowercase();
+    if name.contains("nvidia") || name.contains("geforce") || name.contains("quadro") {
+        "nvidia"
+    } else if name.contains("amd") || name.contains("radeon") || name.contains("ati ") {
+        "amd"
+    } else if name.contains("intel") {
+        "intel"
+    } else if name.contains("apple") {
+        "apple"
+    } else {
+        "unknown"
+    }
+}
+
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output.status.success().then_some(output.stdout)
+}
+
+/// NVIDIA cards as (name, VRAM) from nvidia-smi.
+fn nvidia_smi() -> Vec<(String, u64)> {
+    let Some(out) = run(
+        "nvidia-smi",
+        &[
+            "--query-gpu=name,memory.total",
+            "--format=csv,noheader,nounits",
+        ],
+    ) else {
+        return Vec::new();
+    };
+    out.lines()
+        .filter_map(|line| {
+            let (name, mib) = line.rsplit_once(',')?;
+            let mib: u64 = mib.trim().parse().ok()?;
+            Some((name.trim().to_string(), mib * 1024 * 1024))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn platform_gpus(total_memory: u64) -> Vec<Gpu> {
+    let Some(out) = run("system_profiler", &["SPDisplaysDataType", "-json"]) else {
+        return Vec::new();
+    };
+    let json: serde_json::Value = serde_json::from_str(&out).unwrap_or_default();
+    let Some(displays) = json["SPDisplaysDataType"].as_array() else {
+        return Vec::new();
+    };
+    displays
+        .iter()
+        .map(|d| {
+            let name = d["sppci_model"].as_str().unwrap_or("GPU").to_string();
+            let vendor = vendor_of(&name);
+            // "8 GB" / "1536 MB"; absent on Apple silicon (unified memory).
+            let vram = d["spdisplays_vram"]
+                .as_str()
+                .or(d["spdisplays_vram_shared"].as_str())
+                .and_then(parse_size)
+                .or((vendor == "apple").then_some(total_memory));
+            Gpu {
+                name,
+                vendor: vendor.into(),
+                vram_bytes: vram,
+            }
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn parse_size(s: &str) -> Option<u64> {
+    let (n, unit) = s.trim().split_once(' ')?;
+    let n: u64 = n.parse().ok()?;
+    match unit {
+        "GB" => Some(n * GB),
+        "MB" => Some(n * 1024 * 1024),
+        _ => None,
+    }
+}
+
+#[cfg(windows)]
+fn platform_gpus(_total_memory: u64) -> Vec<Gpu> {
+    let Some(out) = run(
+        "powershell",
+        &[
+            "-NoProfile",
+            "-Command",
+            "Get-CimInstance Win32_VideoController | \
+             Select-Object Name,AdapterRAM | ConvertTo-Json",
+        ],
+    ) else {
+        return Vec::new();
+    };
+    let json: serde_json::Value = serde_json::from_str(&out).unwrap_or_default();
+    // A single adapter serializes as an object, several as an array.
+    let adapters = match json {
+        serde_json::Value::Array(items) => items,
+        serde_json::Value::Null => Vec::new(),
+        other => vec![other],
+    };
+    adapters
+        .iter()
+        .filter_map(|a| {
+            let name = a["Name"].as_str()?.to_string();
+            Some(Gpu {
+                vendor: vendor_of(&name).into(),
+                // AdapterRAM is a uint32, so it tops out at 4 GB.
+                vram_bytes: a["AdapterRAM"].as_u64().filter(|&b| b > 0),
+                name,
+            })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn platform_gpus(_total_memory: u64) -> Vec<Gpu> {
+    let Ok(entries) = std::fs::read_dir("/sys/class/drm") else {
+        return Vec::new();
+    };
+    let mut cards: Vec<_> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            let name = p.file_name().unwrap_or_default().to_string_lossy();
+            name.starts_with("card") && !name.contains('-')
+        })
+        .collect();
+    cards.sort();
+    cards
+        .iter()
+        .filter_map(|card| {
+            let device = card.join("device");
+            let read = |f: &str| std::fs::read_to_string(device.join(f)).ok();
+            let name = read("product_name")
+                .or_else(|| read("label"))
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty());
+            let vendor = match read("vendor")?.trim() {
+                "0x10de" => "nvidia",
+                "0x1002" => "amd",
+                "0x8086" => "intel",
+                _ => name.as_deref().map_or("unknown", vendor_of),
+            };
+            let name = name.unwrap_or_else(|| format!("{} GPU", vendor));
+            Some(Gpu {
+                name,
+                vendor: vendor.into(),
+                vram_bytes: read("mem_info_vram_total").and_then(|s| s.trim().parse().ok()),
+            })
+        })
+        .collect()
+}
+
+#[cfg(not(any(target_os = "macos", windows, target_os = "linux")))]
+fn platform_gpus(_total_memory: u64) -> Vec<Gpu> {
+    Vec::new()
+}
+
+fn library_present(candidates: &[&str]) -> bool {
+    candidates.iter().any(|p| Path::new(p).exists())
+}
+
+fn cuda_available(gpus: &[Gpu]) -> bool {
+    if !gpus.iter().any(|g| g.vendor == "nvidia") {
+        return false;
+    }
+    if cfg!(windows) {
+        library_present(&["C:\\Windows\\System32\\nvcuda.dll"])
+    } else {
+        library_present(&[
+            "/usr/lib/x86_64-linux-gnu/libcuda.so.1",
+            "/usr/lib/aarch64-linux-gnu/libcuda.so.1",
+            "/usr/lib64/libcuda.so.1",
+            "/usr/lib/libcuda.so.1",
+        ])
+    }
+}
+
+fn vulkan_available() -> bool {
+    if cfg!(windows) {
+        library_present(&["C:\\Windows\\System32\\vulkan-1.dll"])
+    } else if cfg!(target_os = "macos") {
+        // Only through MoltenVK, e.g. from the Vulkan SDK or Homebrew.
+        library_present(&[
+            "/usr/local/lib/libvulkan.1.dylib",
+            "/opt/homebrew/lib/libvulkan.1.dylib",
+        ])
+    } else {
+        library_present(&[
+            "/usr/lib/x86_64-linux-gnu/libvulkan.so.1",
+            "/usr/lib/aarch64-linux-gnu/libvulkan.so.1",
+            "/usr/lib64/libvulkan.so.1",
+            "/usr/lib/libvulkan.so.1",
+        ])
+    }
+}
+
+fn recommend(info: &HardwareInfo) -> Recommendation {
+    let accelerator = if info.cuda {
+        Accelerator::Cuda
+    } else if info.metal {
+        Accelerator::Metal
+    } else if info.vulkan && !info.gpus.is_empty() {
+        Accelerator::Vulkan
+    } else {
+        Accelerator::Cpu
+    };
+    let vram = info.gpus.iter().filter_map(|g| g.vram_bytes).max();
+    // Weights should fit in VRAM on a GPU, or in half of RAM on the CPU.
+    let max_model_bytes = match (accelerator, vram) {
+        (Accelerator::Cpu, _) | (_, None) => info.total_memory / 2,
+        (_, Some(vram)) => vram * 8 / 10,
+    };
+    let local = max_model_bytes >= 4 * GB && info.logical_cores >= 4;
+    Recommendation {
+        models: if local { "local" } else { "cloud" },
+        accelerator,
+        max_model_bytes,
+    }
+}
+
+fn detect() -> HardwareInfo {
+    let mut sys = sysinfo::System::new();
+    sys.refresh_memory();
+    sys.refresh_cpu();
+    let total_memory = sys.total_memory();
+
+    let mut gpus = platform_gpus(total_memory);
+    for (name, vram) in nvidia_smi() {
+        match gpus
+            .iter_mut()
+            .find(|g| g.vendor == "nvidia" && g.vram_bytes.map_or(true, |v| v < vram))
+        {
+            Some(gpu) => gpu.vram_bytes = Some(vram),
+            None => gpus.push(Gpu {
+                name,
+                vendor: "nvidia".into(),
+                vram_bytes: Some(vram),
+            }),
+        }
+    }
+
+    let mut info = HardwareInfo {
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        cpu_brand: sys
+            .cpus()
+            .first()
+            .map(|c| c.brand().trim().to_string())
+            .unwrap_or_default(),
+        physical_cores: sys.physical_core_count(),
+        logical_cores: sys.cpus().len(),
+        total_memory,
+        available_memory: sys.available_memory(),
+        cuda: cuda_available(&gpus),
+        metal: cfg!(target_os = "macos"),
+        vulkan: vulkan_available(),
+        gpus,
+        recommendation: Recommendation {
+            models: "cloud",
+            accelerator: Accelerator::Cpu,
+            max_model_bytes: 0,
+        },
+    };
+    info.recommendation = recommend(&info);
+    info
+}
+
+/// Cached hardware info; probes on first use.
+pub fn info() -> HardwareInfo {
+    let mut cache = CACHE.lock().unwrap();
+    cache.get_or_insert_with(detect).clone()
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// GPU, accelerator runtimes, CPU and RAM. `refresh` re-probes instead of
+/// returning the cached result.
+#[tauri::command]
+pub async fn get_hardware_info(refresh: Option<bool>) -> Result<HardwareInfo, String> {
+    if refresh.unwrap_or(false) {
+        CACHE.lock().unwrap().take();
+    }
+    tauri::async_runtime::spawn_blocking(info)
+        .await
+        .map_err(|e| e.to_string())
+}
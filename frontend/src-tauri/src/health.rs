@@ -0,0 +1,195 @@
+// Backend health — uptime, restarts, crashes and health-check latency.
+//
+// Records what the backend has been doing since launch so the status page
+// doesn't have to reconstruct it from events: when the current backend was
+// spawned, how often it has been respawned, why it last died on its own, and
+// the last HISTORY_LIMIT health checks with their latency. Checks come from
+// startup and restarts (`wait_for_health`) plus a probe every PROBE_INTERVAL
+// (times the poll factor), paused around system sleep. `get_backend_stats`
+// returns all of it with the database size on disk. Each step also goes to
+// the tray icon (see tray_status.rs).
+
+use std::collections::VecDeque;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::{config, performance, sidecar, suspend, tray_status};
+
+const HISTORY_LIMIT: usize = 100;
+const PROBE_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Serialize, Clone, Debug)]
+pub struct HealthCheck {
+    /// RFC 3339.
+    at: String,
+    port: u16,
+    ok: bool,
+    attempts: u32,
+    /// Round trip of the successful request; None when the check failed.
+    latency_ms: Option<u64>,
+    detail: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BackendCrash {
+    /// RFC 3339.
+    at: String,
+    code: Option<i32>,
+    signal: Option<i32>,
+    /// Exit status plus the last line the backend wrote to stderr.
+    reason: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BackendStats {
+    port: u16,
+    external: bool,
+    pid: Option<u32>,
+    /// RFC 3339 spawn time of the running backend; None while it is down.
+    started_at: Option<String>,
+    uptime_secs: Option<u64>,
+    /// Spawns after the first one, whatever the cause.
+    restart_count: u32,
+    last_crash: Option<BackendCrash>,
+    /// Oldest first.
+    health_checks: Vec<HealthCheck>,
+    /// Database plus its WAL/SHM files.
+    db_size_bytes: u64,
+}
+
+struct State {
+    started: Option<(Instant, String)>,
+    spawns: u32,
+    last_crash: Option<BackendCrash>,
+    checks: VecDeque<HealthCheck>,
+}
+
+static STATE: Mutex<State> = Mutex::new(State {
+    started: None,
+    spawns: 0,
+    last_crash: None,
+    checks: VecDeque::new(),
+});
+
+/// Call whenever a backend is spawned (or an external one chosen).
+pub fn spawned() {
+    let mut state = STATE.lock().unwrap();
+    state.spawns += 1;
+    state.started = Some((Instant::now(), chrono::Local::now().to_rfc3339()));
+    drop(state);
+    tray_status::starting();
+}
+
+/// Call when the sidecar exits without us stopping it.
+pub fn crashed(code: Option<i32>, signal: Option<i32>, last_error: Option<&str>) {
+    let mut reason = match (code, signal) {
+        (Some(code), _) => format!("exited with code {code}"),
+        (None, Some(signal)) => format!("killed by signal {signal}"),
+        (None, None) => "exited".to_string(),
+    };
+    if let Some(line) = last_error.map(str::trim).filter(|l| !l.is_empty()) {
+        reason.push_str(": ");
+        reason.push_str(line);
+    }
+    let mut state = STATE.lock().unwrap();
+    state.started = None;
+    state.last_crash = Some(BackendCrash {
+        at: chrono::Local::now().to_rfc3339(),
+        code,
+        signal,
+        reason: reason.clone(),
+    });
+    drop(state);
+    tray_status::down(&reason);
+}
+
+/// Records the outcome of a backend health check.
+pub fn record(port: u16, ok: bool, attempts: u32, latency: Option<Duration>, detail: &str) {
+    let mut state = STATE.lock().unwrap();
+    if state.checks.len() == HISTORY_LIMIT {
+        state.checks.pop_front();
+    }
+    state.checks.push_back(HealthCheck {
+        at: chrono::Local::now().to_rfc3339(),
+        port,
+        ok,
+        attempts,
+        latency_ms: latency.map(|l| l.as_millis() as u64),
+        detail: detail.to_string(),
+    });
+    drop(state);
+    // Self-tests check a backend of their own.
+    if port == crate::BACKEND_PORT.load(Ordering::SeqCst) {
+        if ok {
+            tray_status::healthy();
+        } else {
+            tray_status::down(detail);
+        }
+    }
+}
+
+/// Recent health checks, oldest first.
+pub fn history() -> Vec<HealthCheck> {
+    STATE.lock().unwrap().checks.iter().cloned().collect()
+}
+
+async fn probe(client: &reqwest::Client, port: u16) {
+    let url = format!("http://127.0.0.1:{}/api/health", port);
+    let started = Instant::now();
+    match client.get(&url).send().await {
+        Ok(resp) if resp.status().is_success() => {
+            let latency = started.elapsed();
+            let body = resp.text().await.unwrap_or_default();
+            record(port, true, 1, Some(latency), &body);
+        }
+        Ok(resp) => record(port, false, 1, None, &format!("HTTP {}", resp.status())),
+        Err(e) => record(port, false, 1, None, &e.to_string()),
+    }
+}
+
+/// Probes the backend periodically so the latency history stays current.
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(2))
+            .build()
+            .unwrap();
+        loop {
+            let factor = performance::capabilities(&handle).poll_factor;
+            tokio::time::sleep(PROBE_INTERVAL * factor as u32).await;
+
+            let port = crate::BACKEND_PORT.load(Ordering::SeqCst);
+            if port == 0 || suspend::paused() {
+                continue;
+            }
+            probe(&client, port).await;
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn get_backend_stats(app: AppHandle) -> BackendStats {
+    let db_size_bytes = sidecar::db_files(&crate::db_path())
+        .iter()
+        .filter_map(|path| std::fs::metadata(path).ok())
+        .map(|meta| meta.len())
+        .sum();
+    let state = STATE.lock().unwrap();
+    BackendStats {
+        port: crate::BACKEND_PORT.load(Ordering::SeqCst),
+        external: config::current(&app).backend.external_port.is_some(),
+        pid: crate::backend_pid(&app),
+        started_at: state.started.as_ref().map(|(_, at)| at.clone()),
+        uptime_secs: state.started.as_ref().map(|(at, _)| at.elapsed().as_secs()),
+        restart_count: state.spawns.saturating_sub(1),
+        last_crash: state.last_crash.clone(),
+        health_checks: state.checks.iter().cloned().collect(),
+        db_size_bytes,
+    }
+}
@@ -0,0 +1,482 @@
+// Snippet history — local version store for snippet bodies.
+//
+// Independent of whatever versioning the backend does: every body the shell
+// sees (frontend `snippet-saved` events, shell captures) is recorded under
+// data_dir()/history/<id>.json, sealed while the database is encrypted (see
+// encryption.rs). Versions are line diffs against the previous body,
+// deflated and base64-encoded, with a full snapshot every KEYFRAME_EVERY
+// versions so reconstruction never replays a long chain.
+
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::Engine;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use similar::{DiffOp, TextDiff};
+use tauri::{AppHandle, Manager};
+
+use crate::{backend, config, encryption};
+
+const KEYFRAME_EVERY: usize = 20;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct HistoryConfig {
+    pub enabled: bool,
+    /// Oldest versions beyond this count are dropped.
+    pub max_versions: usize,
+    /// Versions older than this are dropped; 0 keeps them forever.
+    pub max_age_days: u64,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_versions: 200,
+            max_age_days: 90,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct VersionInfo {
+    pub version: u32,
+    pub ts: u64,
+    pub size: usize,
+}
+
+/// Line-level edit script turning the previous body into the next one.
+#[derive(Serialize, Deserialize)]
+enum Op {
+    Copy(usize),
+    Skip(usize),
+    Insert(String),
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Delta {
+    Full { data: String },
+    Patch { data: String },
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct StoredVersion {
+    version: u32,
+    ts: u64,
+    size: usize,
+    delta: Delta,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct SnippetHistory {
+    versions: Vec<StoredVersion>,
+}
+
+/// Serializes read-modify-write cycles on history files.
+static HISTORY_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn history_path(id: &str) -> Result<PathBuf, String> {
+    if id.is_empty()
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("Invalid snippet id: {}", id));
+    }
+    Ok(crate::data_dir()
+        .join("history")
+        .join(format!("{}.json", id)))
+}
+
+fn load(id: &str) -> Result<SnippetHistory, String> {
+    match encryption::read_store(&history_path(id)?) {
+        Ok(text) => serde_json::from_str(&text).map_err(|e| format!("Corrupt history: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(SnippetHistory::default()),
+        Err(e) => Err(format!("Could not read history: {e}")),
+    }
+}
+
+fn save(id: &str, history: &SnippetHistory) -> Result<(), String> {
+    let path = history_path(id)?;
+    std::fs::create_dir_all(path.parent().unwrap()).map_err(|e| e.to_string())?;
+    let text = serde_json::to_string(history).map_err(|e| e.to_string())?;
+    encryption::write_store(&path, &text)
+}
+
+// ── Encoding ───────────────────────────────────────────────────────────────
+fn compress(bytes: &[u8]) -> String {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes).ok();
+    let deflated = encoder.finish().unwrap_or_default();
+    base64::engine::general_purpose::STANDARD.encode(deflated)
+}
+
+fn decompress(data: &str) -> Result<Vec<u8>, String> {
+    let deflated = base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|e| e.to_string())?;
+    let mut out = Vec::new();
+    DeflateDecoder::new(&deflated[..])
+        .read_to_end(&mut out)
+        .map_err(|e| e.to_string())?;
+    Ok(out)
+}
+
+fn diff(old: &str, new: &str) -> Vec<Op> {
+    let diff = TextDiff::from_lines(old, new);
+    let new_lines = diff.new_slices();
+    let mut ops = Vec::new();
+    for op in diff.ops() {
+        match *op {
+            DiffOp::Equal { len, .. } => ops.push(Op::Copy(len)),
+            DiffOp::Delete { old_len, .. } => ops.push(Op::Skip(old_len)),
+            DiffOp::Insert {
+                new_index, new_len, ..
+            } => ops.push(Op::Insert(
+                new_lines[new_index..new_index + new_len].concat(),
+            )),
+            DiffOp::Replace {
+                old_len,
+                new_index,
+                new_len,
+                ..
+            } => {
+                ops.push(Op::Skip(old_len));
+                ops.push(Op::Insert(
+                    new_lines[new_index..new_index + new_len].concat(),
+                ));
+            }
+        }
+    }
+    ops
+}
+
+fn apply(old: &str, ops: &[Op]) -> Result<String, String> {
+    let mut lines = old.split_inclusive('\n');
+    let mut out = String::with_capacity(old.len());
+    for op in ops {
+        match op {
+            Op::Copy(n) => {
+                for _ in 0..*n {
+                    out.push_str(lines.next().ok_or("History patch out of range")?);
+                }
+            }
+            Op::Skip(n) => {
+                for _ in 0..*n {
+                    lines.next().ok_or("History patch out of range")?;
+                }
+            }
+            Op::Insert(text) => out.push_str(text),
+        }
+    }
+    Ok(out)
+}
+
+/// Reconstructs the body at `index`, starting from the nearest full snapshot.
+fn materialize(history: &SnippetHistory, index: usize) -> Result<String, String> {
+    let start = history.versions[..=index]
+        .iter()
+        .rposition(|v| matches!(v.delta, Delta::Full { .. }))
+        .ok_or("History has no base snapshot")?;
+    let mut body = String::new();
+    for stored in &history.versions[start..=index] {
+        body = match &stored.delta {
+            Delta::Full { data } => String::from_utf8_lossy(&decompress(data)?).into_owned(),
+            Delta::Patch { data } => {
+                let ops: Vec<Op> =
+                    serde_json::from_slice(&decompress(data)?).map_err(|e| e.to_string())?;
+                apply(&body, &ops)?
+            }
+        };
+    }
+    Ok(body)
+}
+
+// ── Retention ──────────────────────────────────────────────────────────────
+fn prune(history: &mut SnippetHistory, cfg: &HistoryConfig) -> Result<(), String> {
+    let len = history.versions.len();
+    let mut drop = len.saturating_sub(cfg.max_versions.max(1));
+    if cfg.max_age_days > 0 {
+        let cutoff = now_secs().saturating_sub(cfg.max_age_days * 24 * 60 * 60);
+        let expired = history
+            .versions
+            .iter()
+            .take_while(|v| v.ts < cutoff)
+            .count();
+        // The latest version is always kept.
+        drop = drop.max(expired.min(len - 1));
+    }
+    if drop == 0 {
+        return Ok(());
+    }
+    // The new oldest version must stand on its own.
+    let base = materialize(history, drop)?;
+    history.versions.drain(..drop);
+    history.versions[0].delta = Delta::Full {
+        data: compress(base.as_bytes()),
+    };
+    Ok(())
+}
+
+/// Adds `body` to `history` as a new version unless it matches the latest
+/// one.
+fn append(history: &mut SnippetHistory, body: &str) -> Result<Option<VersionInfo>, String> {
+    let previous = match history.versions.len() {
+        0 => None,
+        n => Some(materialize(history, n - 1)?),
+    };
+    if previous.as_deref() == Some(body) {
+        return Ok(None);
+    }
+    let since_keyframe = history
+        .versions
+        .iter()
+        .rev()
+        .take_while(|v| matches!(v.delta, Delta::Patch { .. }))
+        .count();
+    let delta = match previous {
+        Some(prev) if since_keyframe + 1 < KEYFRAME_EVERY => {
+            let ops = serde_json::to_vec(&diff(&prev, body)).map_err(|e| e.to_string())?;
+            Delta::Patch {
+                data: compress(&ops),
+            }
+        }
+        _ => Delta::Full {
+            data: compress(body.as_bytes()),
+        },
+    };
+    let info = VersionInfo {
+        version: history.versions.last().map_or(1, |v| v.version + 1),
+        ts: now_secs(),
+        size: body.len(),
+    };
+    history.versions.push(StoredVersion {
+        version: info.version,
+        ts: info.ts,
+        size: info.size,
+        delta,
+    });
+    Ok(Some(info))
+}
+
+/// Appends `body` as a new version unless it matches the latest one.
+pub fn record(app: &AppHandle, id: &str, body: &str) -> Result<Option<VersionInfo>, String> {
+    let cfg = config::current(app).history;
+    if !cfg.enabled {
+        return Ok(None);
+    }
+    let _guard = HISTORY_LOCK.lock().unwrap();
+    let mut history = load(id)?;
+    let Some(info) = append(&mut history, body)? else {
+        return Ok(None);
+    };
+    prune(&mut history, &cfg)?;
+    save(id, &history)?;
+    Ok(Some(info))
+}
+
+fn body_at(id: &str, version: u32) -> Result<String, String> {
+    let _guard = HISTORY_LOCK.lock().unwrap();
+    let history = load(id)?;
+    let index = history
+        .versions
+        .iter()
+        .position(|v| v.version == version)
+        .ok_or("Unknown version")?;
+    materialize(&history, index)
+}
+
+/// Id fields arrive as JSON strings or numbers depending on the caller.
+fn id_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Records a snippet the backend just returned (create or update).
+pub fn record_snippet(app: &AppHandle, snippet: &Value) {
+    let (Some(id), Some(body)) = (id_string(&snippet["id"]), snippet["body"].as_str()) else {
+        return;
+    };
+    if let Err(e) = record(app, &id, body) {
+        log::warn!("Could not record history for snippet {}: {}", id, e);
+    }
+}
+
+/// Listens for `snippet-saved` events (`{ id, body }`) from the frontend.
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    app.listen_global("snippet-saved", move |event| {
+        match event.payload().map(serde_json::from_str::<Value>) {
+            Some(Ok(snippet)) => record_snippet(&handle, &snippet),
+            _ => log::warn!("Ignoring malformed snippet-saved event"),
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Versions of a snippet, newest first.
+#[tauri::command]
+pub fn get_snippet_history(id: String) -> Result<Vec<VersionInfo>, String> {
+    let _guard = HISTORY_LOCK.lock().unwrap();
+    Ok(load(&id)?
+        .versions
+        .iter()
+        .rev()
+        .map(|v| VersionInfo {
+            version: v.version,
+            ts: v.ts,
+            size: v.size,
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub fn get_snippet_version(id: String, version: u32) -> Result<String, String> {
+    body_at(&id, version)
+}
+
+/// Writes an old body back through the backend; the restore itself becomes
+/// the newest version.
+#[tauri::command]
+pub async fn restore_version(app: AppHandle, id: String, version: u32) -> Result<Value, String> {
+    let body = body_at(&id, version)?;
+    let snippet = backend::patch(&backend::snippet_path(&id)?, &json!({ "body": body })).await?;
+    record(&app, &id, &body)?;
+    app.emit_all("snippet-restored", json!({ "id": id, "version": version }))
+        .ok();
+    Ok(snippet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Body of the `n`th test version: one line edited, one added each time.
+    fn body(n: usize) -> String {
+        (0..=n)
+            .map(|i| {
+                if i == n / 2 {
+                    format!("edit {n}\n")
+                } else {
+                    format!("line {i}\n")
+                }
+            })
+            .collect()
+    }
+
+    fn history_of(count: usize) -> SnippetHistory {
+        let mut history = SnippetHistory::default();
+        for n in 0..count {
+            append(&mut history, &body(n)).unwrap().unwrap();
+        }
+        history
+    }
+
+    fn is_full(version: &StoredVersion) -> bool {
+        matches!(version.delta, Delta::Full { .. })
+    }
+
+    #[test]
+    fn diff_apply_round_trips() {
+        let cases = [
+            ("", "one\ntwo\n"),
+            ("one\ntwo\n", ""),
+            ("one\ntwo\nthree\n", "one\n2\nthree\nfour\n"),
+            ("one\ntwo", "one\ntwo\n"),
+            ("one\ntwo\n", "one\ntwo"),
+            ("one\ntwo", "zero\none\nthree"),
+            ("a\r\nb\r\nc\r\n", "a\r\nB\r\nc\r\nd"),
+            ("a\r\nb\r\n", "a\nb\n"),
+            ("a\nb", "a\r\nb\r\n"),
+        ];
+        for (old, new) in cases {
+            assert_eq!(
+                apply(old, &diff(old, new)).unwrap(),
+                new,
+                "{old:?} -> {new:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn apply_rejects_a_patch_past_the_end() {
+        assert!(apply("one\n", &[Op::Copy(2)]).is_err());
+        assert!(apply("one\n", &[Op::Skip(2)]).is_err());
+    }
+
+    #[test]
+    fn keyframes_bound_reconstruction() {
+        let count = 2 * KEYFRAME_EVERY + 5;
+        let history = history_of(count);
+        assert_eq!(history.versions.len(), count);
+        for (i, version) in history.versions.iter().enumerate() {
+            assert_eq!(is_full(version), i % KEYFRAME_EVERY == 0, "version {i}");
+            assert_eq!(materialize(&history, i).unwrap(), body(i), "version {i}");
+        }
+    }
+
+    #[test]
+    fn unchanged_body_adds_no_version() {
+        let mut history = history_of(3);
+        assert!(append(&mut history, &body(2)).unwrap().is_none());
+        assert_eq!(history.versions.len(), 3);
+    }
+
+    #[test]
+    fn prune_rebases_the_oldest_version() {
+        let mut history = history_of(KEYFRAME_EVERY + 10);
+        let cfg = HistoryConfig {
+            enabled: true,
+            max_versions: 12,
+            max_age_days: 0,
+        };
+        let dropped = history.versions.len() - 12;
+        assert!(!is_full(&history.versions[dropped]));
+        prune(&mut history, &cfg).unwrap();
+        assert_eq!(history.versions.len(), 12);
+        assert!(is_full(&history.versions[0]));
+        for (i, version) in history.versions.iter().enumerate() {
+            assert_eq!(version.version as usize, dropped + i + 1);
+            assert_eq!(materialize(&history, i).unwrap(), body(dropped + i));
+        }
+    }
+
+    #[test]
+    fn prune_drops_expired_versions_but_the_latest() {
+        let cfg = HistoryConfig::default();
+        let mut history = history_of(8);
+        for version in &mut history.versions[..5] {
+            version.ts = 0;
+        }
+        prune(&mut history, &cfg).unwrap();
+        assert_eq!(history.versions.len(), 3);
+        assert!(is_full(&history.versions[0]));
+        assert_eq!(materialize(&history, 0).unwrap(), body(5));
+
+        for version in &mut history.versions {
+            version.ts = 0;
+        }
+        prune(&mut history, &cfg).unwrap();
+        assert_eq!(history.versions.len(), 1);
+        assert_eq!(materialize(&history, 0).unwrap(), body(7));
+    }
+}
@@ -0,0 +1,178 @@
+// I18n — translations for the shell's own text.
+//
+// Tray items and tooltip, dialog and window titles, and notifications are
+// looked up with `t` / `tf` in JSON bundles embedded from locales/ (one flat
+// key → text map per language; `{name}` placeholders). A key missing from a
+// bundle falls back to English, and one missing there shows as the key.
+// `i18n.locale` picks the language; unset, it follows the OS (LANG & co. on
+// Linux, AppleLanguages on macOS, the user locale on Windows). `set_locale`
+// rebuilds the tray and the jump list at once; the Dock menu, dialogs and
+// notifications pick the language up the next time they show. Error
+// messages stay in English, as logged.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::{config, jump_list, region, tray, tray_status};
+
+const FALLBACK: &str = "en";
+
+/// (code, name in that language, bundle)
+const BUNDLES: &[(&str, &str, &str)] = &[
+    ("en", "English", include_str!("../locales/en.json")),
+    ("de", "Deutsch", include_str!("../locales/de.json")),
+    ("es", "Español", include_str!("../locales/es.json")),
+    ("fr", "Français", include_str!("../locales/fr.json")),
+];
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct I18nConfig {
+    /// A code from BUNDLES; None follows the OS.
+    pub locale: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Language {
+    code: &'static str,
+    name: &'static str,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct LocaleStatus {
+    /// The chosen language; None follows the OS.
+    locale: Option<String>,
+    /// The language in use.
+    resolved: String,
+    available: Vec<Language>,
+}
+
+static TEXTS: Lazy<HashMap<&'static str, HashMap<String, String>>> = Lazy::new(|| {
+    BUNDLES
+        .iter()
+        .filter_map(|(code, _, json)| match serde_json::from_str(json) {
+            Ok(texts) => Some((*code, texts)),
+            Err(e) => {
+                log::error!("I18n: bad {} bundle: {}", code, e);
+                None
+            }
+        })
+        .collect()
+});
+
+static CURRENT: Lazy<RwLock<String>> =
+    Lazy::new(|| RwLock::new(resolve(config::load().i18n.locale.as_deref())));
+
+/// The OS locale as the OS spells it ("de-DE", "de_DE.UTF-8").
+#[cfg(target_os = "macos")]
+pub fn system_locale() -> Option<String> {
+    let output = tauri::api::process::Command::new("defaults")
+        .args(["read", "-g", "AppleLanguages"])
+        .output()
+        .ok()?;
+    // ( "de-DE", "en-US" )
+    let first = output.stdout.split('"').nth(1)?;
+    Some(first.to_string())
+}
+
+#[cfg(target_os = "windows")]
+pub fn system_locale() -> Option<String> {
+    use windows::Win32::Globalization::GetUserDefaultLocaleName;
+
+    let mut name = [0u16; 85];
+    let len = unsafe { GetUserDefaultLocaleName(&mut name) };
+    (len > 1).then(|| String::from_utf16_lossy(&name[..len as usize - 1]))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn system_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+}
+
+/// The bundle for `locale` ("de-DE", "pt_BR.UTF-8", "fr"), else for the OS
+/// locale, else English.
+fn resolve(locale: Option<&str>) -> String {
+    let supported = |locale: &str| {
+        let language = locale
+            .split(['-', '_', '.'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        BUNDLES
+            .iter()
+            .find(|(code, _, _)| *code == language)
+            .map(|(code, _, _)| code.to_string())
+    };
+    locale
+        .and_then(supported)
+        .or_else(|| system_locale().as_deref().and_then(supported))
+        .unwrap_or_else(|| FALLBACK.to_string())
+}
+
+/// The code of the language in use, e.g. "de".
+pub fn language() -> String {
+    CURRENT.read().unwrap().clone()
+}
+
+/// The text for `key` in the current language.
+pub fn t(key: &str) -> String {
+    let current = CURRENT.read().unwrap();
+    [current.as_str(), FALLBACK]
+        .iter()
+        .find_map(|code| TEXTS.get(code)?.get(key))
+        .cloned()
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// `t` with each `{name}` replaced by its value.
+pub fn tf(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter().fold(t(key), |text, (name, value)| {
+        text.replace(&format!("{{{name}}}"), &value.to_string())
+    })
+}
+
+fn status(app: &AppHandle) -> LocaleStatus {
+    LocaleStatus {
+        locale: config::current(app).i18n.locale,
+        resolved: CURRENT.read().unwrap().clone(),
+        available: BUNDLES
+            .iter()
+            .map(|(code, name, _)| Language { code, name })
+            .collect(),
+    }
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn get_locale(app: AppHandle) -> LocaleStatus {
+    status(&app)
+}
+
+/// Switches the shell's language; None follows the OS.
+#[tauri::command]
+pub fn set_locale(app: AppHandle, locale: Option<String>) -> Result<LocaleStatus, String> {
+    let locale = locale.filter(|l| !l.is_empty());
+    if let Some(locale) = &locale {
+        if !BUNDLES.iter().any(|(code, _, _)| code == locale) {
+            return Err(format!("No translation for {locale:?}"));
+        }
+    }
+    config::update(&app, |c| c.i18n.locale = locale.clone())?;
+    let resolved = resolve(locale.as_deref());
+    log::info!("Locale: {}", resolved);
+    *CURRENT.write().unwrap() = resolved.clone();
+    tray::refresh(&app);
+    tray_status::refresh();
+    jump_list::update(&app);
+    region::refresh(&app);
+    app.emit_all("locale-changed", &resolved).ok();
+    Ok(status(&app))
+}
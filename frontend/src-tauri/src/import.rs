@@ -0,0 +1,316 @@
+// Import — files dropped onto the main window are handled here, not in JS.
+//
+// Each dropped file is classified (JSON export, Evernote .enex, Markdown/text,
+// image, PDF), read and converted on a blocking thread, and sent to the
+// backend. Each drop runs as an "import_files" task (see tasks.rs) with one
+// progress step per file; the frontend picks it up from `task-started` and
+// never receives raw paths beyond file names. A cancel stops before the next
+// file. Items already in the library are handled by the duplicate policy
+// (see dedupe.rs); a dropped file left out that way ends as "duplicate".
+// Watch folders (see watch_folders.rs) feed their new files through the same
+// path with per-folder `ImportRules`.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, FileDropEvent, Manager, WindowEvent};
+
+use crate::attachments;
+use crate::capture::Capture;
+use crate::dedupe::{Dedupe, DuplicateCounts};
+use crate::disk;
+use crate::exports;
+use crate::ocr::{self, OcrConfig};
+use crate::tasks::{self, Task};
+
+/// Larger files are rejected before anything is read into memory (.enex
+/// files are streamed and exempt).
+const MAX_FILE_BYTES: u64 = 100 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileKind {
+    JsonExport,
+    Enex,
+    Markdown,
+    Image,
+    Pdf,
+    Unsupported,
+}
+
+#[derive(Serialize, Clone, Debug)]
+struct FileResult {
+    file: String,
+    kind: FileKind,
+    /// "done", "duplicate", "skipped" or "failed".
+    stage: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct DropImport {
+    total: usize,
+    imported: usize,
+    failed: usize,
+    duplicates: DuplicateCounts,
+    /// Files reached before a cancel, in drop order.
+    files: Vec<FileResult>,
+}
+
+impl DropImport {
+    pub fn imported(&self) -> usize {
+        self.imported
+    }
+
+    pub fn failed(&self) -> usize {
+        self.failed
+    }
+}
+
+/// How a batch of files is handled. Drops use the defaults.
+#[derive(Clone, Debug, Default)]
+pub struct ImportRules {
+    /// Added to snippets made from Markdown, image and PDF files.
+    pub tag: Option<String>,
+    /// Removes each file once it is imported or found to be a duplicate.
+    pub delete_after_import: bool,
+}
+
+// ── Detection ──────────────────────────────────────────────────────────────
+/// Sniffs the first bytes, falling back to the extension.
+pub fn detect(path: &Path) -> FileKind {
+    let mut head = [0u8; 16];
+    let n = std::fs::File::open(path)
+        .and_then(|mut f| f.read(&mut head))
+        .unwrap_or(0);
+    let head = &head[..n];
+    if head.starts_with(b"%PDF") {
+        return FileKind::Pdf;
+    }
+    if head.starts_with(b"\x89PNG")
+        || head.starts_with(b"\xFF\xD8\xFF")
+        || head.starts_with(b"GIF8")
+        || (head.starts_with(b"RIFF") && head.get(8..12) == Some(b"WEBP"))
+    {
+        return FileKind::Image;
+    }
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "json" => FileKind::JsonExport,
+        "enex" => FileKind::Enex,
+        "md" | "markdown" | "txt" => FileKind::Markdown,
+        "png" | "jpg" | "jpeg" | "gif" | "webp" | "svg" => FileKind::Image,
+        "pdf" => FileKind::Pdf,
+        _ => FileKind::Unsupported,
+    }
+}
+
+// ── Conversion ─────────────────────────────────────────────────────────────
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// First `# Heading` if there is one, otherwise the file name.
+fn markdown_title(body: &str, path: &Path) -> String {
+    body.lines()
+        .find_map(|l| l.strip_prefix("# "))
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| file_stem(path))
+}
+
+fn store_file(path: &Path) -> Result<attachments::Attachment, String> {
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    attachments::store_bytes(&bytes, &ext, None)
+}
+
+/// Text layer of a PDF. pdf-extract panics on some malformed files.
+fn pdf_text(path: &Path) -> String {
+    let path = path.to_path_buf();
+    std::panic::catch_unwind(move || pdf_extract::extract_text(&path))
+        .ok()
+        .and_then(Result::ok)
+        .map(|t| t.trim().to_string())
+        .unwrap_or_default()
+}
+
+/// Off-UI-thread half of an import: read and convert into a `Capture`.
+fn prepare(path: &Path, kind: FileKind, ocr: &OcrConfig) -> Result<Capture, String> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let capture = match kind {
+        FileKind::Markdown => {
+            let body =
+                std::fs::read_to_string(path).map_err(|e| format!("Not a UTF-8 text file: {e}"))?;
+            Capture {
+                title: Some(markdown_title(&body, path)),
+                body,
+                ..Default::default()
+            }
+        }
+        FileKind::Image => {
+            let attachment = store_file(path)?;
+            Capture {
+                title: Some(file_stem(path)),
+                body: match ocr::auto_text(ocr, path) {
+                    Some(text) => {
+                        format!("![{}](attachment:{})\n\n{}", name, attachment.hash, text)
+                    }
+                    None => format!("![{}](attachment:{})", name, attachment.hash),
+                },
+                ..Default::default()
+            }
+        }
+        FileKind::Pdf => {
+            let attachment = store_file(path)?;
+            let text = pdf_text(path);
+            let link = format!("[{}](attachment:{})", name, attachment.hash);
+            Capture {
+                title: Some(file_stem(path)),
+                body: if text.is_empty() {
+                    link
+                } else {
+                    format!("{}\n\n{}", text, link)
+                },
+                ..Default::default()
+            }
+        }
+        FileKind::JsonExport | FileKind::Enex | FileKind::Unsupported => {
+            return Err("Unsupported file type".into())
+        }
+    };
+    Ok(Capture {
+        source: "import".into(),
+        ..capture
+    })
+}
+
+/// Ok(None) when the file duplicates a snippet and was left out.
+async fn import_file(
+    app: &AppHandle,
+    task: &Task,
+    path: &Path,
+    kind: FileKind,
+    rules: &ImportRules,
+    dedupe: &mut Dedupe,
+) -> Result<Option<Value>, String> {
+    if kind == FileKind::Enex {
+        let summary = crate::enex::import(task, path, dedupe).await?;
+        return serde_json::to_value(summary)
+            .map(Some)
+            .map_err(|e| e.to_string());
+    }
+    let size = std::fs::metadata(path).map_err(|e| e.to_string())?.len();
+    if size > MAX_FILE_BYTES {
+        return Err(format!("File is larger than {} MB", MAX_FILE_BYTES >> 20));
+    }
+    if kind == FileKind::JsonExport {
+        disk::ensure_space(&crate::data_dir(), size)?;
+        let json = std::fs::read(path).map_err(|e| e.to_string())?;
+        return exports::import_json(&json, dedupe).await.map(Some);
+    }
+    let owned = path.to_path_buf();
+    let ocr = ocr::auto_config(app);
+    let mut capture = tauri::async_runtime::spawn_blocking(move || prepare(&owned, kind, &ocr))
+        .await
+        .map_err(|e| e.to_string())??;
+    capture.tags.extend(rules.tag.clone());
+    dedupe.submit(app, capture).await
+}
+
+pub async fn import_paths(
+    app: AppHandle,
+    task: Task,
+    paths: Vec<PathBuf>,
+    rules: ImportRules,
+) -> Result<DropImport, String> {
+    let mut summary = DropImport {
+        total: paths.len(),
+        ..Default::default()
+    };
+    if paths
+        .iter()
+        .all(|p| detect(p) == FileKind::Unsupported || p.is_dir())
+    {
+        return Ok(summary);
+    }
+    let mut dedupe = Dedupe::load(&app).await?;
+    for (index, path) in paths.iter().enumerate() {
+        if task.cancelled() {
+            break;
+        }
+        let kind = detect(path);
+        let mut file = FileResult {
+            file: path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            kind,
+            stage: "skipped",
+            error: None,
+            result: None,
+        };
+        task.progress(index as u64, Some(paths.len() as u64), Some(&file.file));
+        if kind == FileKind::Unsupported || path.is_dir() {
+            summary.files.push(file);
+            continue;
+        }
+
+        match import_file(&app, &task, path, kind, &rules, &mut dedupe).await {
+            Ok(Some(result)) => {
+                summary.imported += 1;
+                file.stage = "done";
+                file.result = Some(result);
+            }
+            Ok(None) => file.stage = "duplicate",
+            Err(e) => {
+                summary.failed += 1;
+                log::warn!("Import of {} failed: {}", file.file, e);
+                file.stage = "failed";
+                file.error = Some(e);
+            }
+        }
+        // A cancelled .enex import may have stopped partway through the file.
+        if rules.delete_after_import && file.stage != "failed" && !task.cancelled() {
+            if let Err(e) = std::fs::remove_file(path) {
+                log::warn!("Could not delete imported {}: {}", file.file, e);
+            }
+        }
+        summary.files.push(file);
+    }
+    summary.duplicates = dedupe.counts();
+    Ok(summary)
+}
+
+/// Hooks file drops on the main window.
+pub fn register_drop_handler(app: &AppHandle) {
+    let Some(window) = app.get_window("main") else {
+        return;
+    };
+    let handle = app.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::FileDrop(FileDropEvent::Dropped(paths)) = event {
+            let app = handle.clone();
+            let paths = paths.clone();
+            tasks::spawn(&handle, "import_files", move |task| {
+                import_paths(app, task, paths, ImportRules::default())
+            });
+        }
+    });
+}
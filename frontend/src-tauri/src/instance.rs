@@ -0,0 +1,274 @@
+// Instance — how processes other programs start reach the running app.
+//
+// Browsers start this binary as their native messaging host, Windows and
+// Linux start it to open a pinup:// link, and Explorer to send it files.
+// Such a process opens no window of its own: it hands its request to the
+// running app and relays the answer.
+// The app listens on a random port of 127.0.0.1 and writes the port and a
+// fresh token to app_dir()/instance.json; a request carries the token, so
+// only processes that can read the user's data directory get in. When the
+// app isn't running the helper starts it and waits up to LAUNCH_WAIT.
+//
+// A request is one JSON line, {token, kind, message}, answered by one JSON
+// line; `kind` picks the handler:
+//   native_host   a browser extension's message (see native_host.rs)
+//   deep_link     a pinup:// URL (see deep_link.rs)
+//   send_to       files or text from Explorer's "Send to" (see send_to.rs)
+// Failures are answered as {"ok": false, "error"}.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::Ipv4Addr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::AppHandle;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::lan_sync::random;
+use crate::{app_lock, deep_link, native_host, send_to};
+
+/// Browsers send at most 64 MiB; a full page can come close.
+const MAX_REQUEST: usize = 128 * 1024 * 1024;
+const RETRY_AFTER: Duration = Duration::from_secs(60);
+/// A page clip downloads its images.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const LAUNCH_WAIT: Duration = Duration::from_secs(30);
+
+/// Where helpers find the running app.
+#[derive(Serialize, Deserialize)]
+struct Endpoint {
+    port: u16,
+    token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Request {
+    token: String,
+    kind: String,
+    message: Value,
+}
+
+static LISTENING: AtomicBool = AtomicBool::new(false);
+
+fn endpoint_path() -> PathBuf {
+    crate::app_dir().join("instance.json")
+}
+
+pub fn failure(error: impl std::fmt::Display) -> Value {
+    json!({ "ok": false, "error": error.to_string() })
+}
+
+pub fn listening() -> bool {
+    LISTENING.load(Ordering::SeqCst)
+}
+
+// ── Helper side ────────────────────────────────────────────────────────────
+/// This binary; for an AppImage the image itself rather than its temporary
+/// mount, which changes every launch and goes away with the helper.
+pub fn exe_path() -> Result<PathBuf, String> {
+    if let Some(appimage) = std::env::var_os("APPIMAGE").filter(|_| cfg!(target_os = "linux")) {
+        return Ok(PathBuf::from(appimage));
+    }
+    std::env::current_exe().map_err(|e| format!("Could not locate Pin-Up AI: {e}"))
+}
+
+/// Starts the app without tying it to the helper's parent: browsers end
+/// the host (and on Windows everything in its job) when they are done.
+fn launch_app() -> Result<(), String> {
+    let mut command = std::process::Command::new(exe_path()?);
+    command
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const DETACHED_PROCESS: u32 = 0x0000_0008;
+        const CREATE_BREAKAWAY_FROM_JOB: u32 = 0x0100_0000;
+        command.creation_flags(DETACHED_PROCESS | CREATE_BREAKAWAY_FROM_JOB);
+        if command.spawn().is_ok() {
+            return Ok(());
+        }
+        // The parent's job may not allow breaking away.
+        command.creation_flags(DETACHED_PROCESS);
+    }
+    command
+        .spawn()
+        .map(drop)
+        .map_err(|e| format!("Could not start Pin-Up AI: {e}"))
+}
+
+fn connect() -> Option<(std::net::TcpStream, String)> {
+    let text = std::fs::read_to_string(endpoint_path()).ok()?;
+    let endpoint: Endpoint = serde_json::from_str(&text).ok()?;
+    let address = (Ipv4Addr::LOCALHOST, endpoint.port).into();
+    let stream = std::net::TcpStream::connect_timeout(&address, CONNECT_TIMEOUT).ok()?;
+    Some((stream, endpoint.token))
+}
+
+/// A connection to the app, starting it if it isn't running.
+fn connect_or_launch(launched: &mut bool) -> Result<(std::net::TcpStream, String), String> {
+    if let Some(connection) = connect() {
+        return Ok(connection);
+    }
+    if !*launched {
+        launch_app()?;
+        *launched = true;
+    }
+    let deadline = Instant::now() + LAUNCH_WAIT;
+    while Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(250));
+        if let Some(connection) = connect() {
+            return Ok(connection);
+        }
+    }
+    Err("Pin-Up AI did not start; open it and try again".into())
+}
+
+/// Hands `message` to the running app's `kind` handler and returns its
+/// answer. `launched` remembers across calls that the app was started.
+pub fn relay(kind: &str, message: Value, launched: &mut bool) -> Result<Value, String> {
+    let (stream, token) = connect_or_launch(launched)?;
+    stream
+        .set_read_timeout(Some(REQUEST_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    let request = Request {
+        token,
+        kind: kind.into(),
+        message,
+    };
+    let mut line = serde_json::to_vec(&request).map_err(|e| e.to_string())?;
+    line.push(b'\n');
+    (&stream)
+        .write_all(&line)
+        .map_err(|e| format!("Could not reach Pin-Up AI: {e}"))?;
+    let mut reply = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut reply)
+        .map_err(|e| format!("No answer from Pin-Up AI: {e}"))?;
+    serde_json::from_str(&reply).map_err(|_| "No answer from Pin-Up AI".to_string())
+}
+
+// ── Registration helpers ───────────────────────────────────────────────────
+/// Writes `text` unless the file already holds it; true when it wrote.
+pub fn write_if_changed(path: &Path, text: &str) -> Result<bool, String> {
+    if std::fs::read_to_string(path).is_ok_and(|old| old == text) {
+        return Ok(false);
+    }
+    std::fs::create_dir_all(path.parent().unwrap()).map_err(|e| e.to_string())?;
+    std::fs::write(path, text)
+        .map(|_| true)
+        .map_err(|e| format!("Could not write {}: {e}", path.display()))
+}
+
+/// Runs `reg` with `args`; its output on success.
+#[cfg(windows)]
+pub fn reg(args: &[&str]) -> Result<String, String> {
+    let output = tauri::api::process::Command::new("reg")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Could not run reg: {e}"))?;
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(output.stderr.trim().to_string())
+    }
+}
+
+// ── App side ───────────────────────────────────────────────────────────────
+async fn connection(app: AppHandle, stream: TcpStream, token: String) -> Result<(), String> {
+    let (read, mut write) = stream.into_split();
+    let mut line = Vec::new();
+    tokio::io::BufReader::new(read)
+        .take(MAX_REQUEST as u64)
+        .read_until(b'\n', &mut line)
+        .await
+        .map_err(|e| e.to_string())?;
+    let reply = match serde_json::from_slice::<Request>(&line) {
+        Ok(request) if request.token != token => {
+            log::warn!("Instance: refused a request with a wrong token");
+            failure("Not authorized")
+        }
+        Ok(_) if app_lock::locked() => failure("Pin-Up AI is locked"),
+        Ok(request) => match request.kind.as_str() {
+            "native_host" => native_host::handle(&app, request.message).await,
+            "deep_link" => deep_link::handle(&app, request.message).await,
+            "send_to" => send_to::handle(&app, request.message).await,
+            other => failure(format!("Unknown request kind {other:?}")),
+        },
+        Err(e) => failure(format!("Invalid request: {e}")),
+    };
+    let mut bytes = serde_json::to_vec(&reply).map_err(|e| e.to_string())?;
+    bytes.push(b'\n');
+    write.write_all(&bytes).await.map_err(|e| e.to_string())
+}
+
+async fn serve(app: AppHandle, listener: TcpListener, token: String) {
+    loop {
+        let Ok((stream, _)) = listener.accept().await else {
+            continue;
+        };
+        let (app, token) = (app.clone(), token.clone());
+        tauri::async_runtime::spawn(async move {
+            match tokio::time::timeout(REQUEST_TIMEOUT, connection(app, stream, token)).await {
+                Ok(Err(e)) => log::warn!("Instance: {}", e),
+                Err(_) => log::warn!("Instance: request timed out"),
+                Ok(Ok(())) => {}
+            }
+        });
+    }
+}
+
+fn write_endpoint(endpoint: &Endpoint) -> Result<(), String> {
+    let path = endpoint_path();
+    std::fs::create_dir_all(path.parent().unwrap()).map_err(|e| e.to_string())?;
+    let text = serde_json::to_string(endpoint).map_err(|e| e.to_string())?;
+    std::fs::write(&path, text).map_err(|e| format!("Could not write {}: {e}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+async fn listen(app: &AppHandle) -> Result<(), String> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .map_err(|e| format!("Could not listen on loopback: {e}"))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    let token = hex::encode(random::<32>()?);
+    write_endpoint(&Endpoint {
+        port,
+        token: token.clone(),
+    })?;
+    LISTENING.store(true, Ordering::SeqCst);
+    log::info!("Instance: listening on port {}", port);
+    serve(app.clone(), listener, token).await;
+    Ok(())
+}
+
+/// Listens for helpers for as long as the app runs.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = listen(&app).await {
+                log::warn!("Instance: {}", e);
+                LISTENING.store(false, Ordering::SeqCst);
+            }
+            tokio::time::sleep(RETRY_AFTER).await;
+        }
+    });
+}
@@ -0,0 +1,233 @@
+// Jobs — one registry of background jobs, fanned out as normalized events.
+//
+// Imports and exports (see tasks.rs) and the shell's AI work (transcription,
+// OCR, Ollama model pulls; see `begin`) all report here, and every window
+// follows them through the same three events carrying a `JobStatus`:
+// `job-started`, `job-progress` (at most every PROGRESS_INTERVAL per job)
+// and `job-finished`. `list_jobs` catches up a window that opens late, so
+// nothing has to poll. The backend keeps no job queue to poll or subscribe
+// to; work the frontend sees it finish is reported with a `job-completed`
+// event (`{ title, body?, snippet_id? }`) and goes out as a finished job of
+// kind "backend". The tray (see tray_status.rs) and notifications (see
+// notifications.rs) read their state from here. Finished jobs are kept until
+// MAX_FINISHED newer ones have finished.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::tasks::TaskState;
+use crate::{notifications, tray_status};
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+const MAX_FINISHED: usize = 50;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct JobStatus {
+    /// "task:<task id>" for tasks, "job:<n>" for the rest.
+    pub id: String,
+    /// e.g. "import_enex", "transcribe", "ocr", "ollama_pull", "backend".
+    pub kind: String,
+    /// What the user would call it, e.g. "Evernote import".
+    pub label: String,
+    pub state: TaskState,
+    /// AI work, which the tray marks with an activity dot.
+    pub ai: bool,
+    pub done: u64,
+    pub total: Option<u64>,
+    pub current: Option<String>,
+    pub error: Option<String>,
+    /// The snippet it produced, if any.
+    pub snippet_id: Option<String>,
+    /// RFC 3339.
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    #[serde(skip)]
+    emitted_at: Option<Instant>,
+}
+
+impl JobStatus {
+    pub fn new(id: String, kind: &str, label: &str, ai: bool) -> Self {
+        Self {
+            id,
+            kind: kind.to_string(),
+            label: label.to_string(),
+            state: TaskState::Running,
+            ai,
+            done: 0,
+            total: None,
+            current: None,
+            error: None,
+            snippet_id: None,
+            started_at: chrono::Local::now().to_rfc3339(),
+            finished_at: None,
+            emitted_at: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct JobCompleted {
+    title: String,
+    #[serde(default)]
+    body: String,
+    snippet_id: Option<String>,
+}
+
+static APP: OnceCell<AppHandle> = OnceCell::new();
+static JOBS: Mutex<Vec<JobStatus>> = Mutex::new(Vec::new());
+static NEXT: AtomicU64 = AtomicU64::new(1);
+
+fn emit(event: &str, status: &JobStatus) {
+    if let Some(app) = APP.get() {
+        app.emit_all(event, status).ok();
+    }
+}
+
+/// Drops the oldest finished jobs beyond MAX_FINISHED.
+fn prune(jobs: &mut Vec<JobStatus>) {
+    let finished = jobs
+        .iter()
+        .filter(|j| j.state != TaskState::Running)
+        .count();
+    let mut excess = finished.saturating_sub(MAX_FINISHED);
+    jobs.retain(|j| {
+        if excess > 0 && j.state != TaskState::Running {
+            excess -= 1;
+            return false;
+        }
+        true
+    });
+}
+
+// ── Reporting ──────────────────────────────────────────────────────────────
+/// Registers a running job.
+pub fn started(status: JobStatus) {
+    JOBS.lock().unwrap().push(status.clone());
+    emit("job-started", &status);
+    tray_status::refresh();
+}
+
+/// Applies `f` to running job `id` and emits the result, at most every
+/// PROGRESS_INTERVAL.
+pub fn progress(id: &str, f: impl FnOnce(&mut JobStatus)) {
+    let status = {
+        let mut jobs = JOBS.lock().unwrap();
+        let Some(job) = jobs
+            .iter_mut()
+            .find(|j| j.id == id && j.state == TaskState::Running)
+        else {
+            return;
+        };
+        f(job);
+        let now = Instant::now();
+        if job
+            .emitted_at
+            .is_some_and(|at| now.duration_since(at) < PROGRESS_INTERVAL)
+        {
+            return;
+        }
+        job.emitted_at = Some(now);
+        job.clone()
+    };
+    emit("job-progress", &status);
+}
+
+/// Ends job `id` in `state`.
+pub fn finished(id: &str, state: TaskState, error: Option<String>) {
+    let status = {
+        let mut jobs = JOBS.lock().unwrap();
+        let Some(job) = jobs
+            .iter_mut()
+            .find(|j| j.id == id && j.state == TaskState::Running)
+        else {
+            return;
+        };
+        job.state = state;
+        job.error = error;
+        job.finished_at = Some(chrono::Local::now().to_rfc3339());
+        let status = job.clone();
+        prune(&mut jobs);
+        status
+    };
+    emit("job-finished", &status);
+    tray_status::refresh();
+    if let Some(app) = APP.get() {
+        notifications::job_finished(app, &status);
+    }
+}
+
+/// Running jobs: (all, AI).
+pub fn running() -> (usize, usize) {
+    let jobs = JOBS.lock().unwrap();
+    let running = jobs.iter().filter(|j| j.state == TaskState::Running);
+    let ai = running.clone().filter(|j| j.ai).count();
+    (running.count(), ai)
+}
+
+/// A running AI job; dropping it without `end` marks it failed.
+pub struct Job {
+    id: String,
+}
+
+/// Starts tracking an AI job in the shell.
+pub fn begin(kind: &str, label: &str) -> Job {
+    let id = format!("job:{}", NEXT.fetch_add(1, Ordering::SeqCst));
+    started(JobStatus::new(id.clone(), kind, label, true));
+    Job { id }
+}
+
+impl Job {
+    pub fn progress(&self, done: u64, total: u64) {
+        progress(&self.id, |j| {
+            j.done = done;
+            j.total = Some(total);
+        });
+    }
+
+    /// Ends the job as `result` went.
+    pub fn end<T>(self, result: &Result<T, String>) {
+        match result {
+            Ok(_) => finished(&self.id, TaskState::Done, None),
+            Err(e) => finished(&self.id, TaskState::Failed, Some(e.clone())),
+        }
+    }
+}
+
+impl Drop for Job {
+    fn drop(&mut self) {
+        // A no-op after `end`, which leaves nothing running.
+        finished(&self.id, TaskState::Failed, None);
+    }
+}
+
+pub fn start(app: &AppHandle) {
+    if APP.set(app.clone()).is_err() {
+        return;
+    }
+    app.listen_global("job-completed", |event| {
+        let Some(job) = event
+            .payload()
+            .and_then(|p| serde_json::from_str::<JobCompleted>(p).ok())
+        else {
+            return;
+        };
+        let id = format!("job:{}", NEXT.fetch_add(1, Ordering::SeqCst));
+        let mut status = JobStatus::new(id.clone(), "backend", &job.title, false);
+        status.current = Some(job.body).filter(|b| !b.is_empty());
+        status.snippet_id = job.snippet_id;
+        started(status);
+        finished(&id, TaskState::Done, None);
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Running jobs and recently finished ones, oldest first.
+#[tauri::command]
+pub fn list_jobs() -> Vec<JobStatus> {
+    JOBS.lock().unwrap().clone()
+}
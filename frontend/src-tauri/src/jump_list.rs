@@ -0,0 +1,186 @@
+// Jump list — "New Snippet", "Search" and recent snippets on the Windows
+// taskbar button.
+//
+// Each entry starts this binary with a pinup:// link (see deep_link.rs),
+// which hands it to the running app: pinup://new opens the editor,
+// pinup://open the search box and pinup://open?id=… a snippet. The list is
+// rebuilt whenever the recent snippets or the language change (see recent.rs,
+// i18n.rs) and removed while deep links are turned off, since its entries
+// would only fail.
+// Snippets the user removed from the list stay out, as Windows requires.
+// Other platforms have no jump list; `update` does nothing there.
+
+use tauri::AppHandle;
+
+#[cfg(windows)]
+const RECENT_COUNT: usize = 5;
+
+#[cfg(windows)]
+mod imp {
+    use std::collections::HashSet;
+    use std::mem::ManuallyDrop;
+    use std::path::Path;
+
+    use windows::core::{ComInterface, HSTRING, PCWSTR, PWSTR};
+    use windows::Win32::Storage::EnhancedStorage::PKEY_Title;
+    use windows::Win32::System::Com::StructuredStorage::{
+        PROPVARIANT, PROPVARIANT_0, PROPVARIANT_0_0, PROPVARIANT_0_0_0,
+    };
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED,
+    };
+    use windows::Win32::System::Variant::VT_LPWSTR;
+    use windows::Win32::UI::Shell::Common::{IObjectArray, IObjectCollection};
+    use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
+    use windows::Win32::UI::Shell::{
+        DestinationList, EnumerableObjectCollection, ICustomDestinationList, IShellLinkW, ShellLink,
+    };
+
+    use crate::i18n;
+
+    pub struct Entry {
+        pub title: String,
+        pub link: String,
+    }
+
+    impl Entry {
+        fn arguments(&self) -> String {
+            format!("\"{}\"", self.link)
+        }
+    }
+
+    unsafe fn shell_link(exe: &Path, entry: &Entry) -> windows::core::Result<IShellLinkW> {
+        let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
+        link.SetPath(&HSTRING::from(exe))?;
+        link.SetArguments(&HSTRING::from(entry.arguments()))?;
+        link.SetIconLocation(&HSTRING::from(exe), 0)?;
+        link.SetDescription(&HSTRING::from(&entry.title))?;
+        // The label shown in the list; the store copies the string.
+        let mut title: Vec<u16> = entry.title.encode_utf16().chain(Some(0)).collect();
+        let value = PROPVARIANT {
+            Anonymous: PROPVARIANT_0 {
+                Anonymous: ManuallyDrop::new(PROPVARIANT_0_0 {
+                    vt: VT_LPWSTR,
+                    wReserved1: 0,
+                    wReserved2: 0,
+                    wReserved3: 0,
+                    Anonymous: PROPVARIANT_0_0_0 {
+                        pwszVal: PWSTR(title.as_mut_ptr()),
+                    },
+                }),
+            },
+        };
+        let store: IPropertyStore = link.cast()?;
+        store.SetValue(&PKEY_Title, &value)?;
+        store.Commit()?;
+        Ok(link)
+    }
+
+    /// The arguments of entries the user removed from the list.
+    unsafe fn removed(items: &IObjectArray) -> HashSet<String> {
+        let mut removed = HashSet::new();
+        for i in 0..items.GetCount().unwrap_or(0) {
+            let Ok(link) = items.GetAt::<IShellLinkW>(i) else {
+                continue;
+            };
+            let mut buffer = [0u16; 1024];
+            if link.GetArguments(&mut buffer).is_ok() {
+                let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+                removed.insert(String::from_utf16_lossy(&buffer[..len]));
+            }
+        }
+        removed
+    }
+
+    unsafe fn collection(
+        exe: &Path,
+        entries: &[Entry],
+        skip: &HashSet<String>,
+    ) -> windows::core::Result<IObjectCollection> {
+        let items: IObjectCollection =
+            CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)?;
+        for entry in entries.iter().filter(|e| !skip.contains(&e.arguments())) {
+            items.AddObject(&shell_link(exe, entry)?)?;
+        }
+        Ok(items)
+    }
+
+    unsafe fn destination_list() -> Result<ICustomDestinationList, String> {
+        // Already-initialized is fine; we only need COM on this thread.
+        CoInitializeEx(None, COINIT_MULTITHREADED).ok();
+        CoCreateInstance(&DestinationList, None, CLSCTX_INPROC_SERVER)
+            .map_err(|e| format!("Jump lists unavailable: {e}"))
+    }
+
+    pub fn build(exe: &Path, tasks: &[Entry], recent: &[Entry]) -> Result<(), String> {
+        unsafe {
+            let list = destination_list()?;
+            let mut slots = 0u32;
+            let gone: IObjectArray = list
+                .BeginList(&mut slots)
+                .map_err(|e| format!("Could not start the jump list: {e}"))?;
+            let skip = removed(&gone);
+            let result = (|| {
+                let items = collection(exe, recent, &skip)?;
+                if items.GetCount()? > 0 {
+                    list.AppendCategory(&HSTRING::from(i18n::t("jump_list.recent")), &items)?;
+                }
+                list.AddUserTasks(&collection(exe, tasks, &HashSet::new())?)?;
+                list.CommitList()
+            })();
+            if result.is_err() {
+                list.AbortList().ok();
+            }
+            result.map_err(|e| format!("Could not update the jump list: {e}"))
+        }
+    }
+
+    pub fn clear() -> Result<(), String> {
+        unsafe {
+            destination_list()?
+                .DeleteList(PCWSTR::null())
+                .map_err(|e| format!("Could not remove the jump list: {e}"))
+        }
+    }
+}
+
+/// Rebuilds the jump list from the recent snippets (Windows).
+pub fn update(app: &AppHandle) {
+    #[cfg(windows)]
+    {
+        use crate::{config, deep_link, i18n, instance, recent};
+
+        let enabled = config::current(app).deep_links.enabled;
+        tauri::async_runtime::spawn_blocking(move || {
+            let result = if enabled {
+                let entry = |title: &str, link: String| imp::Entry {
+                    title: title.to_string(),
+                    link,
+                };
+                let tasks = [
+                    entry(
+                        &i18n::t("tray.new_snippet"),
+                        format!("{}://new", deep_link::SCHEME),
+                    ),
+                    entry(
+                        &i18n::t("jump_list.search"),
+                        format!("{}://open", deep_link::SCHEME),
+                    ),
+                ];
+                let recent: Vec<_> = recent::snippets()
+                    .into_iter()
+                    .take(RECENT_COUNT)
+                    .map(|s| entry(&s.label(), deep_link::open_link(&s.id)))
+                    .collect();
+                instance::exe_path().and_then(|exe| imp::build(&exe, &tasks, &recent))
+            } else {
+                imp::clear()
+            };
+            if let Err(e) = result {
+                log::warn!("Jump list: {}", e);
+            }
+        });
+    }
+    #[cfg(not(windows))]
+    let _ = app;
+}
@@ -0,0 +1,321 @@
+// Keyring — secrets kept in the OS credential store instead of the config.
+//
+// Each secret is stored under SERVICE and an account name ("webdav:<url>",
+// ...): the Secret Service (GNOME Keyring, KWallet) over D-Bus on Linux,
+// the login keychain through `security` on macOS, and the Credential
+// Manager on Windows, where the target name is "<SERVICE>:<account>".
+// Each profile has a service of its own (see profiles.rs), so work and
+// personal secrets don't mix. Calls block; run them off the async runtime.
+// A locked keyring is reported as an error rather than prompting, and so
+// are reads while the app is locked (see app_lock.rs), until a passcode or
+// biometric unlock.
+
+use once_cell::sync::Lazy;
+
+use crate::profiles;
+
+/// The default profile's service.
+pub const APP_SERVICE: &str = "Pin-Up AI";
+
+static SERVICE: Lazy<String> = Lazy::new(profiles::keyring_service);
+
+#[cfg(target_os = "linux")]
+mod secret_service {
+    use std::collections::HashMap;
+
+    use zbus::blocking::{Connection, Proxy};
+    use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+
+    const DEST: &str = "org.freedesktop.secrets";
+    const DEFAULT_COLLECTION: &str = "/org/freedesktop/secrets/aliases/default";
+
+    /// (session, parameters, value, content type)
+    type Secret = (OwnedObjectPath, Vec<u8>, Vec<u8>, String);
+
+    fn proxy<'a>(
+        bus: &'a Connection,
+        path: &'a str,
+        interface: &'a str,
+    ) -> Result<Proxy<'a>, String> {
+        Proxy::new(bus, DEST, path, interface).map_err(|e| format!("No keyring available: {e}"))
+    }
+
+    fn attributes(account: &str) -> HashMap<&str, &str> {
+        HashMap::from([("service", super::SERVICE.as_str()), ("account", account)])
+    }
+
+    pub struct Keyring {
+        bus: Connection,
+    }
+
+    impl Keyring {
+        pub fn connect() -> Result<Self, String> {
+            let bus = Connection::session().map_err(|e| format!("No keyring available: {e}"))?;
+            Ok(Self { bus })
+        }
+
+        fn service(&self) -> Result<Proxy<'_>, String> {
+            proxy(
+                &self.bus,
+                "/org/freedesktop/secrets",
+                "org.freedesktop.Secret.Service",
+            )
+        }
+
+        /// Plain-text transfer; the bus is local to the session.
+        fn session(&self) -> Result<OwnedObjectPath, String> {
+            let (_, session): (OwnedValue, OwnedObjectPath) = self
+                .service()?
+                .call("OpenSession", &("plain", Value::from("")))
+                .map_err(|e| e.to_string())?;
+            Ok(session)
+        }
+
+        pub fn find(&self, account: &str) -> Result<Option<OwnedObjectPath>, String> {
+            let (unlocked, locked): (Vec<OwnedObjectPath>, Vec<OwnedObjectPath>) = self
+                .service()?
+                .call("SearchItems", &(attributes(account),))
+                .map_err(|e| e.to_string())?;
+            if let Some(item) = unlocked.into_iter().next() {
+                return Ok(Some(item));
+            }
+            let Some(item) = locked.into_iter().next() else {
+                return Ok(None);
+            };
+            let (unlocked, _prompt): (Vec<OwnedObjectPath>, OwnedObjectPath) = self
+                .service()?
+                .call("Unlock", &(vec![&item],))
+                .map_err(|e| e.to_string())?;
+            if unlocked.is_empty() {
+                return Err("The keyring is locked".into());
+            }
+            Ok(Some(item))
+        }
+
+        pub fn get(&self, account: &str) -> Result<Option<String>, String> {
+            let Some(item) = self.find(account)? else {
+                return Ok(None);
+            };
+            let session = self.session()?;
+            let (_, _, value, _): Secret =
+                proxy(&self.bus, item.as_str(), "org.freedesktop.Secret.Item")?
+                    .call("GetSecret", &(&session,))
+                    .map_err(|e| e.to_string())?;
+            String::from_utf8(value)
+                .map(Some)
+                .map_err(|e| e.to_string())
+        }
+
+        pub fn set(&self, account: &str, secret: &str) -> Result<(), String> {
+            let session = self.session()?;
+            let properties = HashMap::from([
+                (
+                    "org.freedesktop.Secret.Item.Label",
+                    Value::from(format!("{} ({account})", *super::SERVICE)),
+                ),
+                (
+                    "org.freedesktop.Secret.Item.Attributes",
+                    Value::from(attributes(account)),
+                ),
+            ]);
+            let secret: Secret = (
+                session,
+                Vec::new(),
+                secret.as_bytes().to_vec(),
+                "text/plain".into(),
+            );
+            let (item, _prompt): (OwnedObjectPath, OwnedObjectPath) = proxy(
+                &self.bus,
+                DEFAULT_COLLECTION,
+                "org.freedesktop.Secret.Collection",
+            )?
+            .call("CreateItem", &(properties, secret, true))
+            .map_err(|e| e.to_string())?;
+            // "/" means the collection wants a prompt: it is locked.
+            if item.as_str() == "/" {
+                return Err("The keyring is locked".into());
+            }
+            Ok(())
+        }
+
+        pub fn delete(&self, account: &str) -> Result<(), String> {
+            if let Some(item) = self.find(account)? {
+                let _prompt: OwnedObjectPath =
+                    proxy(&self.bus, item.as_str(), "org.freedesktop.Secret.Item")?
+                        .call("Delete", &())
+                        .map_err(|e| e.to_string())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read(account: &str) -> Result<Option<String>, String> {
+    secret_service::Keyring::connect()?.get(account)
+}
+
+#[cfg(target_os = "linux")]
+pub fn set(account: &str, secret: &str) -> Result<(), String> {
+    secret_service::Keyring::connect()?.set(account, secret)
+}
+
+#[cfg(target_os = "linux")]
+pub fn delete(account: &str) -> Result<(), String> {
+    secret_service::Keyring::connect()?.delete(account)
+}
+
+#[cfg(target_os = "macos")]
+fn security(args: &[&str]) -> Result<tauri::api::process::Output, String> {
+    tauri::api::process::Command::new("security")
+        .args(args)
+        .output()
+        .map_err(|e| format!("No keychain available: {e}"))
+}
+
+/// `security` exits with 44 when the item doesn't exist.
+#[cfg(target_os = "macos")]
+const ITEM_NOT_FOUND: i32 = 44;
+
+#[cfg(target_os = "macos")]
+fn read(account: &str) -> Result<Option<String>, String> {
+    let output = security(&[
+        "find-generic-password",
+        "-s",
+        SERVICE.as_str(),
+        "-a",
+        account,
+        "-w",
+    ])?;
+    match output.status.code() {
+        Some(0) => Ok(Some(output.stdout.trim_end_matches('\n').to_string())),
+        Some(ITEM_NOT_FOUND) => Ok(None),
+        _ => Err(format!("Keychain: {}", output.stderr.trim())),
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn set(account: &str, secret: &str) -> Result<(), String> {
+    let output = security(&[
+        "add-generic-password",
+        "-U",
+        "-s",
+        SERVICE.as_str(),
+        "-a",
+        account,
+        "-w",
+        secret,
+    ])?;
+    if !output.status.success() {
+        return Err(format!("Keychain: {}", output.stderr.trim()));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+pub fn delete(account: &str) -> Result<(), String> {
+    let output = security(&[
+        "delete-generic-password",
+        "-s",
+        SERVICE.as_str(),
+        "-a",
+        account,
+    ])?;
+    match output.status.code() {
+        Some(0) | Some(ITEM_NOT_FOUND) => Ok(()),
+        _ => Err(format!("Keychain: {}", output.stderr.trim())),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn target(account: &str) -> windows::core::HSTRING {
+    windows::core::HSTRING::from(format!("{}:{account}", *SERVICE))
+}
+
+#[cfg(target_os = "windows")]
+fn not_found(e: &windows::core::Error) -> bool {
+    e.code() == windows::Win32::Foundation::ERROR_NOT_FOUND.to_hresult()
+}
+
+#[cfg(target_os = "windows")]
+fn read(account: &str) -> Result<Option<String>, String> {
+    use windows::Win32::Security::Credentials::{
+        CredFree, CredReadW, CREDENTIALW, CRED_TYPE_GENERIC,
+    };
+
+    let mut credential: *mut CREDENTIALW = std::ptr::null_mut();
+    match unsafe { CredReadW(&target(account), CRED_TYPE_GENERIC, 0, &mut credential) } {
+        Err(e) if not_found(&e) => Ok(None),
+        Err(e) => Err(format!("Credential Manager: {e}")),
+        Ok(()) => unsafe {
+            let blob = std::slice::from_raw_parts(
+                (*credential).CredentialBlob,
+                (*credential).CredentialBlobSize as usize,
+            );
+            let secret = String::from_utf8(blob.to_vec()).map_err(|e| e.to_string());
+            CredFree(credential as *const std::ffi::c_void);
+            secret.map(Some)
+        },
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn set(account: &str, secret: &str) -> Result<(), String> {
+    use windows::core::PWSTR;
+    use windows::Win32::Security::Credentials::{
+        CredWriteW, CREDENTIALW, CRED_PERSIST_LOCAL_MACHINE, CRED_TYPE_GENERIC,
+    };
+
+    let mut name: Vec<u16> = target(account).as_wide().to_vec();
+    name.push(0);
+    let mut user: Vec<u16> = account.encode_utf16().chain([0]).collect();
+    let mut blob = secret.as_bytes().to_vec();
+    let credential = CREDENTIALW {
+        Type: CRED_TYPE_GENERIC,
+        TargetName: PWSTR(name.as_mut_ptr()),
+        UserName: PWSTR(user.as_mut_ptr()),
+        CredentialBlobSize: blob.len() as u32,
+        CredentialBlob: blob.as_mut_ptr(),
+        Persist: CRED_PERSIST_LOCAL_MACHINE,
+        ..Default::default()
+    };
+    unsafe { CredWriteW(&credential, 0) }.map_err(|e| format!("Credential Manager: {e}"))
+}
+
+#[cfg(target_os = "windows")]
+pub fn delete(account: &str) -> Result<(), String> {
+    use windows::Win32::Security::Credentials::{CredDeleteW, CRED_TYPE_GENERIC};
+
+    match unsafe { CredDeleteW(&target(account), CRED_TYPE_GENERIC, 0) } {
+        Err(e) if !not_found(&e) => Err(format!("Credential Manager: {e}")),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn read(_account: &str) -> Result<Option<String>, String> {
+    Err("No keyring on this platform".into())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn set(_account: &str, _secret: &str) -> Result<(), String> {
+    Err("No keyring on this platform".into())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn delete(_account: &str) -> Result<(), String> {
+    Ok(())
+}
+
+/// The secret saved for `account`, if any.
+pub fn get(account: &str) -> Result<Option<String>, String> {
+    if crate::app_lock::locked() {
+        return Err("Pin-Up AI is locked".into());
+    }
+    read(account)
+}
+
+/// Like `get`, but also while the app is locked; for the app lock itself.
+pub fn get_while_locked(account: &str) -> Result<Option<String>, String> {
+    read(account)
+}
@@ -0,0 +1,1175 @@
+// LAN sync — direct sync between paired machines, with no cloud in between.
+//
+// While on, the app listens on a random TCP port and advertises it over mDNS
+// (see mdns.rs) under its device id, with its name in the TXT record.
+// Pairing: one device shows a code (8 characters, valid for five minutes);
+// the other picks it among the devices found nearby and enters the code. The
+// first answers with an invitation sealed with the code like an encrypted
+// export (Argon2id, so a recorded pairing is impractical to brute-force),
+// holding a fresh key for the pair and the key of its log; the second proves
+// it opened it by replying under the pair key. Keys go to the OS keyring.
+//
+// The device that showed the code hosts the log: the sync log (see
+// sync_log.rs) in <data dir>/lan-sync, sealed with its log key. It syncs its
+// own library into that folder, and the devices paired with it sync against
+// the same folder over the network whenever they find the host on the LAN.
+// Every request is a new connection: a plain hello naming the device, a
+// random challenge, then the request and its response sealed with the pair
+// key and bound to the challenge; a file being put follows its request in a
+// frame of its own. Removing a device forgets its key, so it can't connect
+// again.
+//
+// Frames are capped at HANDSHAKE_FRAME until the request has opened under
+// the pair key, so a host on the LAN that isn't paired can't make the
+// server reserve room for a large frame; at most MAX_CONNECTIONS are served
+// at once and the rest are dropped.
+
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use futures_util::{SinkExt, StreamExt};
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Semaphore;
+use tokio_util::bytes::Bytes;
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+
+use crate::mdns::{Instance, Mdns, Message};
+use crate::sync_log::{self, blocking, Remote, SyncReport, Target};
+use crate::{config, exports, keyring, performance, suspend, tray_status};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// How often the network task notices LAN sync being turned on or off.
+const ENABLE_CHECK: Duration = Duration::from_secs(5);
+const BROWSE_INTERVAL: Duration = Duration::from_secs(60);
+/// Devices not heard from for this long are considered gone.
+const STALE_AFTER: Duration = Duration::from_secs(180);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+const PAIRING_WINDOW: Duration = Duration::from_secs(5 * 60);
+const PAIRING_ATTEMPTS: u32 = 5;
+/// Unambiguous characters: no 0/O or 1/I.
+const CODE_ALPHABET: &[u8] = b"23456789ABCDEFGHJKLMNPQRSTUVWXYZ";
+const CODE_LEN: usize = 8;
+/// Largest frame: a snapshot of a big library, base64-encoded.
+const MAX_FRAME: usize = 256 * 1024 * 1024;
+/// Largest frame before the other side has proven it holds the pair key:
+/// hellos, challenges, invitations and requests without file data.
+const HANDSHAKE_FRAME: usize = 16 * 1024;
+const MAX_CONNECTIONS: usize = 8;
+const SNAPSHOT_HOURS: u64 = 24;
+const KEEP_SNAPSHOTS: usize = 3;
+const RETENTION_DAYS: u64 = 30;
+const LOG_KEY_ACCOUNT: &str = "lan-sync:log";
+
+const B64: base64::engine::GeneralPurpose = base64::engine::general_purpose::STANDARD;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct LanSyncConfig {
+    pub enabled: bool,
+    /// Advertised over mDNS; made when LAN sync is first turned on.
+    pub device_id: Option<String>,
+    /// Shown to other devices; the host name when unset.
+    pub device_name: Option<String>,
+    pub interval_minutes: u64,
+    pub peers: Vec<LanPeer>,
+}
+
+impl Default for LanSyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            device_id: None,
+            device_name: None,
+            interval_minutes: 5,
+            peers: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LanPeer {
+    pub device: String,
+    pub name: String,
+    /// The peer hosts the log this device syncs with; otherwise the peer
+    /// syncs with ours.
+    pub host: bool,
+    /// Unix ms.
+    pub paired_at: i64,
+}
+
+/// Keyring entry for a peer, as JSON.
+#[derive(Serialize, Deserialize, Clone)]
+struct PeerKeys {
+    /// Hex; authenticates requests between the pair.
+    pair_key: String,
+    /// Hex; the host's log key, for hosts only.
+    log_key: Option<String>,
+}
+
+struct Pairing {
+    code: String,
+    until: Instant,
+    /// Unix ms, for the UI.
+    expires_at: i64,
+    attempts: u32,
+}
+
+struct Seen {
+    addr: SocketAddr,
+    name: String,
+    at: Instant,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PairingCode {
+    code: String,
+    /// Unix ms.
+    expires_at: i64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PeerStatus {
+    #[serde(flatten)]
+    peer: LanPeer,
+    /// Found on the network just now.
+    online: bool,
+    /// RFC 3339; when a guest last made a request.
+    last_contact: Option<String>,
+    /// Syncs with a host.
+    last_sync: Option<SyncReport>,
+    last_error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct NearbyDevice {
+    device: String,
+    name: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct LanSyncStatus {
+    enabled: bool,
+    device_id: Option<String>,
+    device_name: String,
+    /// Accepting connections and advertised.
+    listening: bool,
+    pairing: Option<PairingCode>,
+    peers: Vec<PeerStatus>,
+    /// Unpaired devices advertising LAN sync.
+    nearby: Vec<NearbyDevice>,
+    syncing: bool,
+    /// Syncs of this device's own log, when it hosts one.
+    last_sync: Option<SyncReport>,
+    last_error: Option<String>,
+    /// Why the network side isn't running.
+    network_error: Option<String>,
+}
+
+static PORT: AtomicU16 = AtomicU16::new(0);
+static SYNCING: AtomicBool = AtomicBool::new(false);
+/// A guest wrote to the hosted log since the last sync.
+static HOST_DIRTY: AtomicBool = AtomicBool::new(false);
+static PAIRING: Mutex<Option<Pairing>> = Mutex::new(None);
+static SEEN: Lazy<Mutex<HashMap<String, Seen>>> = Lazy::new(Default::default);
+static CONTACT: Lazy<Mutex<HashMap<String, String>>> = Lazy::new(Default::default);
+type LastSync = (Option<SyncReport>, Option<String>);
+
+/// Device -> last sync; this device's own id for the hosted log.
+static LAST: Lazy<Mutex<HashMap<String, LastSync>>> = Lazy::new(Default::default);
+static LAST_RUN: Mutex<Option<Instant>> = Mutex::new(None);
+static NETWORK_ERROR: Mutex<Option<String>> = Mutex::new(None);
+/// Keyring reads are slow on some platforms; every request needs the key.
+static KEYS: Lazy<Mutex<HashMap<String, PeerKeys>>> = Lazy::new(Default::default);
+
+pub fn random<const N: usize>() -> Result<[u8; N], String> {
+    let mut bytes = [0u8; N];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("No random source: {e}"))?;
+    Ok(bytes)
+}
+
+pub fn device_name(cfg: &LanSyncConfig) -> String {
+    cfg.device_name
+        .clone()
+        .filter(|n| !n.trim().is_empty())
+        .or_else(sysinfo::System::host_name)
+        .unwrap_or_else(|| "Pin-Up AI".into())
+}
+
+/// Uppercase, without separators.
+fn normalize_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+fn key_bytes(hex_key: &str) -> Result<[u8; 32], String> {
+    hex::decode(hex_key)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| "Damaged LAN sync key; pair again".to_string())
+}
+
+// ── Keys ───────────────────────────────────────────────────────────────────
+/// Two devices may each host a log for the other, with a pair key each way.
+fn account(device: &str, host: bool) -> String {
+    let role = if host { "host" } else { "guest" };
+    format!("lan-sync:{role}:{device}")
+}
+
+async fn peer_keys(device: &str, host: bool) -> Result<Option<PeerKeys>, String> {
+    let account = account(device, host);
+    if let Some(keys) = KEYS.lock().unwrap().get(&account) {
+        return Ok(Some(keys.clone()));
+    }
+    let lookup = account.clone();
+    let Some(text) = blocking(move || keyring::get(&lookup)).await? else {
+        return Ok(None);
+    };
+    let keys: PeerKeys =
+        serde_json::from_str(&text).map_err(|e| format!("Unreadable LAN sync key: {e}"))?;
+    KEYS.lock().unwrap().insert(account, keys.clone());
+    Ok(Some(keys))
+}
+
+async fn save_peer_keys(device: &str, host: bool, keys: PeerKeys) -> Result<(), String> {
+    let account = account(device, host);
+    let text = serde_json::to_string(&keys).map_err(|e| e.to_string())?;
+    let entry = account.clone();
+    blocking(move || keyring::set(&entry, &text)).await?;
+    KEYS.lock().unwrap().insert(account, keys);
+    Ok(())
+}
+
+async fn forget_peer_keys(device: &str, host: bool) -> Result<(), String> {
+    let account = account(device, host);
+    KEYS.lock().unwrap().remove(&account);
+    blocking(move || keyring::delete(&account)).await
+}
+
+/// Key of the log this device hosts, made on first use.
+async fn log_key() -> Result<String, String> {
+    blocking(|| {
+        if let Some(key) = keyring::get(LOG_KEY_ACCOUNT)? {
+            return Ok(key);
+        }
+        let key = hex::encode(random::<32>()?);
+        keyring::set(LOG_KEY_ACCOUNT, &key)?;
+        Ok(key)
+    })
+    .await
+}
+
+// ── Wire protocol ──────────────────────────────────────────────────────────
+pub type Connection = Framed<TcpStream, LengthDelimitedCodec>;
+
+/// Unsealed messages.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Plain {
+    Pair {
+        device: String,
+        name: String,
+    },
+    Hello {
+        device: String,
+    },
+    /// `Invitation` sealed with the code, base64.
+    Invitation {
+        sealed: String,
+    },
+    Challenge {
+        nonce: String,
+    },
+    Paired,
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct Invitation {
+    device: String,
+    name: String,
+    pair_key: String,
+    log_key: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum Op {
+    List {
+        dir: String,
+    },
+    Get {
+        path: String,
+    },
+    /// The file follows as an `Upload`.
+    Put {
+        path: String,
+    },
+    Delete {
+        path: String,
+    },
+    /// Ends a pairing: the invitation was opened.
+    Confirm,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Request {
+    nonce: String,
+    op: Op,
+}
+
+/// The file of a `Put`, bound to the same challenge.
+#[derive(Serialize, Deserialize)]
+struct Upload {
+    nonce: String,
+    /// Base64.
+    data: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Reply {
+    Names(Vec<String>),
+    /// Base64.
+    Data(String),
+    Done,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Response {
+    nonce: String,
+    result: Result<Reply, String>,
+}
+
+fn connection(stream: TcpStream) -> Connection {
+    LengthDelimitedCodec::builder()
+        .max_frame_length(HANDSHAKE_FRAME)
+        .new_framed(stream)
+}
+
+/// Lets file-sized frames through once the pair key has been proven.
+fn trust(conn: &mut Connection) {
+    conn.codec_mut().set_max_frame_length(MAX_FRAME);
+}
+
+pub async fn send_bytes(conn: &mut Connection, bytes: Vec<u8>) -> Result<(), String> {
+    conn.send(Bytes::from(bytes))
+        .await
+        .map_err(|e| format!("Connection lost: {e}"))
+}
+
+pub async fn recv_bytes(conn: &mut Connection) -> Result<Vec<u8>, String> {
+    match conn.next().await {
+        Some(Ok(frame)) => Ok(frame.to_vec()),
+        Some(Err(e)) => Err(format!("Connection lost: {e}")),
+        None => Err("The other device closed the connection".into()),
+    }
+}
+
+async fn send_plain(conn: &mut Connection, message: &Plain) -> Result<(), String> {
+    let bytes = serde_json::to_vec(message).map_err(|e| e.to_string())?;
+    send_bytes(conn, bytes).await
+}
+
+async fn recv_plain(conn: &mut Connection) -> Result<Plain, String> {
+    let bytes = recv_bytes(conn).await?;
+    serde_json::from_slice(&bytes).map_err(|e| format!("Unexpected message: {e}"))
+}
+
+/// Random nonce, then the XChaCha20-Poly1305 sealed JSON.
+pub fn seal_frame(key: &[u8; 32], value: &impl Serialize) -> Result<Vec<u8>, String> {
+    let nonce: [u8; 24] = random()?;
+    let plain = serde_json::to_vec(value).map_err(|e| e.to_string())?;
+    let sealed = XChaCha20Poly1305::new(key.into())
+        .encrypt(XNonce::from_slice(&nonce), plain.as_slice())
+        .map_err(|_| "Encryption failed".to_string())?;
+    Ok([nonce.as_slice(), &sealed].concat())
+}
+
+pub fn open_frame<T: DeserializeOwned>(key: &[u8; 32], data: &[u8]) -> Result<T, String> {
+    if data.len() < 24 {
+        return Err("Truncated message".into());
+    }
+    let (nonce, sealed) = data.split_at(24);
+    let plain = XChaCha20Poly1305::new(key.into())
+        .decrypt(XNonce::from_slice(nonce), sealed)
+        .map_err(|_| "The devices' keys don't match; pair them again".to_string())?;
+    serde_json::from_slice(&plain).map_err(|e| format!("Unexpected message: {e}"))
+}
+
+// ── Hosted log ─────────────────────────────────────────────────────────────
+/// The log this device hosts, in the data dir.
+pub struct Folder {
+    root: PathBuf,
+}
+
+impl Folder {
+    fn new() -> Self {
+        Self {
+            root: crate::data_dir().join("lan-sync"),
+        }
+    }
+
+    fn dir(&self, dir: &str) -> Result<PathBuf, String> {
+        let dir = dir.trim_end_matches('/');
+        if !matches!(dir, "changes" | "snapshots") {
+            return Err(format!("No such folder: {dir}"));
+        }
+        Ok(self.root.join(dir))
+    }
+
+    /// "<dir>/<name>" below the root; guests can't reach anything else.
+    fn file(&self, path: &str) -> Result<PathBuf, String> {
+        let (dir, name) = path.split_once('/').ok_or("Invalid path")?;
+        let valid = !name.is_empty()
+            && !name.starts_with('.')
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.');
+        if !valid {
+            return Err(format!("Invalid file name: {name}"));
+        }
+        Ok(self.dir(dir)?.join(name))
+    }
+
+    pub async fn list(&self, dir: &str) -> Result<Vec<String>, String> {
+        let mut entries = match tokio::fs::read_dir(self.dir(dir)?).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.to_string()),
+        };
+        let mut names = Vec::new();
+        while let Some(entry) = entries.next_entry().await.map_err(|e| e.to_string())? {
+            names.push(entry.file_name().to_string_lossy().into_owned());
+        }
+        Ok(names)
+    }
+
+    pub async fn get(&self, path: &str) -> Result<Vec<u8>, String> {
+        tokio::fs::read(self.file(path)?)
+            .await
+            .map_err(|e| format!("Could not read {path}: {e}"))
+    }
+
+    pub async fn put(&self, path: &str, bytes: Vec<u8>) -> Result<(), String> {
+        let file = self.file(path)?;
+        if let Some(parent) = file.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        let tmp = file.with_extension("part");
+        tokio::fs::write(&tmp, bytes)
+            .await
+            .map_err(|e| format!("Could not write {path}: {e}"))?;
+        tokio::fs::rename(&tmp, &file)
+            .await
+            .map_err(|e| format!("Could not write {path}: {e}"))
+    }
+
+    pub async fn delete(&self, path: &str) -> Result<(), String> {
+        match tokio::fs::remove_file(self.file(path)?).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+// ── Client ─────────────────────────────────────────────────────────────────
+/// A host's log, reached over the network.
+pub struct Peer {
+    addr: SocketAddr,
+    /// This device, as the host knows it.
+    device: String,
+    key: [u8; 32],
+}
+
+impl Peer {
+    /// `upload` is the file of a `Put`.
+    async fn call(&self, op: Op, upload: Option<Vec<u8>>) -> Result<Reply, String> {
+        let exchange = async {
+            let stream = TcpStream::connect(self.addr)
+                .await
+                .map_err(|e| format!("Could not reach the device: {e}"))?;
+            let mut conn = connection(stream);
+            let hello = Plain::Hello {
+                device: self.device.clone(),
+            };
+            send_plain(&mut conn, &hello).await?;
+            let nonce = match recv_plain(&mut conn).await? {
+                Plain::Challenge { nonce } => nonce,
+                Plain::Error { message } => return Err(message),
+                _ => return Err("Unexpected answer".into()),
+            };
+            let request = Request {
+                nonce: nonce.clone(),
+                op,
+            };
+            send_bytes(&mut conn, seal_frame(&self.key, &request)?).await?;
+            // The host challenged us as a paired device; its reply is still
+            // checked against the key before it is used.
+            trust(&mut conn);
+            if let Some(bytes) = upload {
+                let upload = Upload {
+                    nonce: nonce.clone(),
+                    data: B64.encode(bytes),
+                };
+                send_bytes(&mut conn, seal_frame(&self.key, &upload)?).await?;
+            }
+            let response: Response = open_frame(&self.key, &recv_bytes(&mut conn).await?)?;
+            if response.nonce != nonce {
+                return Err("Unexpected answer".into());
+            }
+            response.result
+        };
+        tokio::time::timeout(REQUEST_TIMEOUT, exchange)
+            .await
+            .map_err(|_| "The device stopped responding".to_string())?
+    }
+
+    pub async fn list(&self, dir: &str) -> Result<Vec<String>, String> {
+        match self.call(Op::List { dir: dir.into() }, None).await? {
+            Reply::Names(names) => Ok(names),
+            _ => Err("Unexpected answer".into()),
+        }
+    }
+
+    pub async fn get(&self, path: &str) -> Result<Vec<u8>, String> {
+        match self.call(Op::Get { path: path.into() }, None).await? {
+            Reply::Data(data) => B64.decode(data).map_err(|e| e.to_string()),
+            _ => Err("Unexpected answer".into()),
+        }
+    }
+
+    pub async fn put(&self, path: &str, bytes: Vec<u8>) -> Result<(), String> {
+        self.call(Op::Put { path: path.into() }, Some(bytes))
+            .await
+            .map(drop)
+    }
+
+    pub async fn delete(&self, path: &str) -> Result<(), String> {
+        self.call(Op::Delete { path: path.into() }, None)
+            .await
+            .map(drop)
+    }
+}
+
+// ── Server ─────────────────────────────────────────────────────────────────
+async fn serve(app: AppHandle, listener: TcpListener) {
+    let slots = std::sync::Arc::new(Semaphore::new(MAX_CONNECTIONS));
+    loop {
+        let Ok((stream, _)) = listener.accept().await else {
+            continue;
+        };
+        let Ok(slot) = slots.clone().try_acquire_owned() else {
+            log::debug!("LAN sync connection dropped: too many at once");
+            continue;
+        };
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let _slot = slot;
+            let result = tokio::time::timeout(REQUEST_TIMEOUT, handle(&app, stream))
+                .await
+                .unwrap_or_else(|_| Err("timed out".into()));
+            if let Err(e) = result {
+                log::debug!("LAN sync connection: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle(app: &AppHandle, stream: TcpStream) -> Result<(), String> {
+    let mut conn = connection(stream);
+    match recv_plain(&mut conn).await? {
+        Plain::Pair { device, name } => accept_pairing(app, &mut conn, device, name).await,
+        Plain::Hello { device } => answer(app, &mut conn, &device).await,
+        _ => Err("Unexpected message".into()),
+    }
+}
+
+async fn refuse(conn: &mut Connection, message: &str) -> Result<(), String> {
+    let error = Plain::Error {
+        message: message.into(),
+    };
+    send_plain(conn, &error).await.ok();
+    Err(message.into())
+}
+
+/// One request from a guest against the hosted log.
+async fn answer(app: &AppHandle, conn: &mut Connection, device: &str) -> Result<(), String> {
+    let guest = config::current(app)
+        .lan_sync
+        .peers
+        .iter()
+        .any(|p| p.device == device && !p.host);
+    let keys = if guest {
+        peer_keys(device, false).await?
+    } else {
+        None
+    };
+    let Some(keys) = keys else {
+        return refuse(conn, "This device isn't paired; pair it again").await;
+    };
+    let key = key_bytes(&keys.pair_key)?;
+    let nonce = hex::encode(random::<16>()?);
+    send_plain(
+        conn,
+        &Plain::Challenge {
+            nonce: nonce.clone(),
+        },
+    )
+    .await?;
+    let request: Request = open_frame(&key, &recv_bytes(conn).await?)?;
+    if request.nonce != nonce {
+        return Err("Stale request".into());
+    }
+    trust(conn);
+    CONTACT
+        .lock()
+        .unwrap()
+        .insert(device.to_string(), chrono::Utc::now().to_rfc3339());
+
+    let folder = Folder::new();
+    let result = match request.op {
+        Op::List { dir } => folder.list(&dir).await.map(Reply::Names),
+        Op::Get { path } => folder.get(&path).await.map(|b| Reply::Data(B64.encode(b))),
+        Op::Put { path } => {
+            let upload: Upload = open_frame(&key, &recv_bytes(conn).await?)?;
+            if upload.nonce != nonce {
+                return Err("Stale request".into());
+            }
+            match B64.decode(upload.data) {
+                Ok(bytes) => folder.put(&path, bytes).await.map(|()| {
+                    HOST_DIRTY.store(true, Ordering::SeqCst);
+                    Reply::Done
+                }),
+                Err(e) => Err(e.to_string()),
+            }
+        }
+        Op::Delete { path } => folder.delete(&path).await.map(|()| Reply::Done),
+        Op::Confirm => Err("Unexpected request".into()),
+    };
+    send_bytes(conn, seal_frame(&key, &Response { nonce, result })?).await
+}
+
+/// Hands a device that knows the code the keys to sync with this one.
+async fn accept_pairing(
+    app: &AppHandle,
+    conn: &mut Connection,
+    device: String,
+    name: String,
+) -> Result<(), String> {
+    let code = match PAIRING.lock().unwrap().as_mut() {
+        Some(p) if p.until > Instant::now() && p.attempts < PAIRING_ATTEMPTS => {
+            p.attempts += 1;
+            Some(p.code.clone())
+        }
+        _ => None,
+    };
+    let Some(code) = code else {
+        return refuse(conn, "That device isn't showing a pairing code").await;
+    };
+    let cfg = config::current(app).lan_sync;
+    let me = cfg.device_id.clone().ok_or("LAN sync is off")?;
+    let pair_key = hex::encode(random::<32>()?);
+    let invitation = Invitation {
+        device: me,
+        name: device_name(&cfg),
+        pair_key: pair_key.clone(),
+        log_key: log_key().await?,
+    };
+    let plain = serde_json::to_vec(&invitation).map_err(|e| e.to_string())?;
+    let sealed = blocking(move || exports::seal(&plain, &code)).await?;
+    send_plain(
+        conn,
+        &Plain::Invitation {
+            sealed: B64.encode(sealed),
+        },
+    )
+    .await?;
+
+    // Only a device that opened the invitation can seal this.
+    let confirm: Request = open_frame(&key_bytes(&pair_key)?, &recv_bytes(conn).await?)?;
+    if !matches!(confirm.op, Op::Confirm) {
+        return Err("Unexpected request".into());
+    }
+    save_peer_keys(
+        &device,
+        false,
+        PeerKeys {
+            pair_key,
+            log_key: None,
+        },
+    )
+    .await?;
+    let peer = LanPeer {
+        device,
+        name,
+        host: false,
+        paired_at: chrono::Utc::now().timestamp_millis(),
+    };
+    add_peer(app, peer.clone())?;
+    *PAIRING.lock().unwrap() = None;
+    send_plain(conn, &Plain::Paired).await?;
+    log::info!("LAN sync: paired with {} ({})", peer.name, peer.device);
+    app.emit_all("lan-paired", &peer).ok();
+    Ok(())
+}
+
+fn add_peer(app: &AppHandle, peer: LanPeer) -> Result<(), String> {
+    config::update(app, |c| {
+        let peers = &mut c.lan_sync.peers;
+        peers.retain(|p| !(p.device == peer.device && p.host == peer.host));
+        peers.push(peer);
+    })
+    .map(drop)
+}
+
+// ── Discovery ──────────────────────────────────────────────────────────────
+fn instance(app: &AppHandle, id: &str, port: u16) -> Instance {
+    let cfg = config::current(app).lan_sync;
+    Instance {
+        id: id.to_string(),
+        port,
+        txt: vec![("name".into(), device_name(&cfg)), ("v".into(), "1".into())],
+    }
+}
+
+/// Serves and advertises until LAN sync is turned off.
+async fn advertise(app: &AppHandle, id: &str) -> Result<(), String> {
+    let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .await
+        .map_err(|e| format!("Could not listen for LAN sync: {e}"))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    let mdns = Mdns::bind()?;
+    let server = tauri::async_runtime::spawn(serve(app.clone(), listener));
+    PORT.store(port, Ordering::SeqCst);
+    *NETWORK_ERROR.lock().unwrap() = None;
+    log::info!("LAN sync: listening on port {}", port);
+
+    let result = async {
+        mdns.announce(&instance(app, id, port)).await?;
+        mdns.query().await?;
+        let mut ticks = tokio::time::interval(ENABLE_CHECK);
+        let mut last_query = Instant::now();
+        loop {
+            tokio::select! {
+                message = mdns.recv() => match message? {
+                    Message::Query => mdns.announce(&instance(app, id, port)).await?,
+                    Message::Answers(found) => {
+                        let mut seen = SEEN.lock().unwrap();
+                        for found in found.into_iter().filter(|f| f.id != id) {
+                            let name = found.txt.get("name").cloned().unwrap_or_else(|| found.id.clone());
+                            seen.insert(found.id, Seen { addr: found.addr, name, at: Instant::now() });
+                        }
+                    }
+                },
+                _ = ticks.tick() => {
+                    let cfg = config::current(app).lan_sync;
+                    if !cfg.enabled || cfg.device_id.as_deref() != Some(id) {
+                        return Ok(());
+                    }
+                    if last_query.elapsed() >= BROWSE_INTERVAL {
+                        SEEN.lock().unwrap().retain(|_, s| s.at.elapsed() < STALE_AFTER);
+                        mdns.query().await?;
+                        last_query = Instant::now();
+                    }
+                }
+            }
+        }
+    }
+    .await;
+
+    server.abort();
+    PORT.store(0, Ordering::SeqCst);
+    SEEN.lock().unwrap().clear();
+    result
+}
+
+/// Runs `advertise` whenever LAN sync is on.
+async fn network(app: AppHandle) {
+    loop {
+        tokio::time::sleep(ENABLE_CHECK).await;
+        let cfg = config::current(&app).lan_sync;
+        let Some(id) = cfg.device_id.filter(|_| cfg.enabled) else {
+            continue;
+        };
+        if let Err(e) = advertise(&app, &id).await {
+            log::warn!("LAN sync: {}", e);
+            *NETWORK_ERROR.lock().unwrap() = Some(e);
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    }
+}
+
+// ── Sync ───────────────────────────────────────────────────────────────────
+fn target(key: String, state_file: String, passphrase: &str) -> Target<'_> {
+    Target {
+        key,
+        label: "LAN".into(),
+        state_file,
+        passphrase,
+        snapshot_hours: SNAPSHOT_HOURS,
+        keep_snapshots: KEEP_SNAPSHOTS,
+        retention_days: RETENTION_DAYS,
+        prune: true,
+    }
+}
+
+/// This device's library into the log it hosts.
+async fn sync_hosted(me: &str) -> Result<SyncReport, String> {
+    let log_key = log_key().await?;
+    let target = target(format!("lan:{me}"), "lan-sync-host.json".into(), &log_key);
+    sync_log::run(&Remote::Local(Folder::new()), &target).await
+}
+
+async fn sync_with_host(me: &str, host: &str, addr: SocketAddr) -> Result<SyncReport, String> {
+    let keys = peer_keys(host, true)
+        .await?
+        .ok_or("No key saved for this device; pair again")?;
+    let log_key = keys
+        .log_key
+        .ok_or("No log key saved for this device; pair again")?;
+    let peer = Peer {
+        addr,
+        device: me.to_string(),
+        key: key_bytes(&keys.pair_key)?,
+    };
+    let target = target(
+        format!("lan:{host}"),
+        format!("lan-sync-{host}.json"),
+        &log_key,
+    );
+    sync_log::run(&Remote::Lan(peer), &target).await
+}
+
+/// Syncs the hosted log, if any device syncs with it, and every host found on
+/// the network. Results are kept per device.
+async fn sync(app: &AppHandle) -> Result<(), String> {
+    let cfg = config::current(app).lan_sync;
+    let me = cfg
+        .device_id
+        .clone()
+        .filter(|_| cfg.enabled)
+        .ok_or("LAN sync is off")?;
+    if SYNCING.swap(true, Ordering::SeqCst) {
+        return Err("A sync is already running".into());
+    }
+    HOST_DIRTY.store(false, Ordering::SeqCst);
+
+    let mut results = Vec::new();
+    if cfg.peers.iter().any(|p| !p.host) {
+        results.push((
+            me.clone(),
+            "this device".to_string(),
+            sync_hosted(&me).await,
+        ));
+    }
+    for peer in cfg.peers.iter().filter(|p| p.host) {
+        let addr = SEEN.lock().unwrap().get(&peer.device).map(|s| s.addr);
+        // Hosts that are off the network are synced next time they're seen.
+        if let Some(addr) = addr {
+            let result = sync_with_host(&me, &peer.device, addr).await;
+            results.push((peer.device.clone(), peer.name.clone(), result));
+        }
+    }
+    SYNCING.store(false, Ordering::SeqCst);
+    *LAST_RUN.lock().unwrap() = Some(Instant::now());
+
+    let mut last = LAST.lock().unwrap();
+    for (device, name, result) in results {
+        match result {
+            Ok(report) => {
+                log::info!(
+                    "LAN sync with {}: {} uploaded, {} change sets applied ({} created, {} updated, {} deleted, {} skipped)",
+                    name,
+                    report.uploaded,
+                    report.applied,
+                    report.created,
+                    report.updated,
+                    report.deleted,
+                    report.skipped
+                );
+                tray_status::synced();
+                app.emit_all("lan-synced", &report).ok();
+                last.insert(device, (Some(report), None));
+            }
+            Err(e) => {
+                log::warn!("LAN sync with {} failed: {}", name, e);
+                last.entry(device).or_default().1 = Some(e);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn status(app: &AppHandle) -> LanSyncStatus {
+    let cfg = config::current(app).lan_sync;
+    let seen = SEEN.lock().unwrap();
+    let last = LAST.lock().unwrap();
+    let contact = CONTACT.lock().unwrap();
+    let peers = cfg
+        .peers
+        .iter()
+        .map(|peer| {
+            let (last_sync, last_error) = match peer.host {
+                true => last.get(&peer.device).cloned().unwrap_or_default(),
+                false => (None, None),
+            };
+            PeerStatus {
+                peer: peer.clone(),
+                online: seen.contains_key(&peer.device),
+                last_contact: contact.get(&peer.device).cloned(),
+                last_sync,
+                last_error,
+            }
+        })
+        .collect();
+    let mut nearby: Vec<NearbyDevice> = seen
+        .iter()
+        .filter(|(device, _)| !cfg.peers.iter().any(|p| &&p.device == device && p.host))
+        .map(|(device, s)| NearbyDevice {
+            device: device.clone(),
+            name: s.name.clone(),
+        })
+        .collect();
+    nearby.sort_by(|a, b| a.name.cmp(&b.name));
+    let pairing = PAIRING
+        .lock()
+        .unwrap()
+        .as_ref()
+        .filter(|p| p.until > Instant::now() && p.attempts < PAIRING_ATTEMPTS)
+        .map(|p| PairingCode {
+            code: format!("{}-{}", &p.code[..4], &p.code[4..]),
+            expires_at: p.expires_at,
+        });
+    let (last_sync, last_error) = cfg
+        .device_id
+        .as_ref()
+        .and_then(|id| last.get(id).cloned())
+        .unwrap_or_default();
+    LanSyncStatus {
+        enabled: cfg.enabled,
+        device_name: device_name(&cfg),
+        device_id: cfg.device_id,
+        listening: PORT.load(Ordering::SeqCst) != 0,
+        pairing,
+        peers,
+        nearby,
+        syncing: SYNCING.load(Ordering::SeqCst),
+        last_sync,
+        last_error,
+        network_error: NETWORK_ERROR.lock().unwrap().clone(),
+    }
+}
+
+/// Advertises and serves while enabled; syncs on the configured interval and
+/// soon after a guest writes to the hosted log.
+pub fn start(app: &AppHandle) {
+    tauri::async_runtime::spawn(network(app.clone()));
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let cfg = config::current(&handle).lan_sync;
+            if !cfg.enabled || cfg.peers.is_empty() || suspend::paused() {
+                continue;
+            }
+            let factor = performance::capabilities(&handle).poll_factor as u32;
+            let interval = Duration::from_secs(cfg.interval_minutes.max(1) * 60) * factor;
+            let due = HOST_DIRTY.load(Ordering::SeqCst)
+                || LAST_RUN
+                    .lock()
+                    .unwrap()
+                    .map_or(true, |at| at.elapsed() >= interval);
+            if due && crate::BACKEND_PORT.load(Ordering::SeqCst) != 0 {
+                sync(&handle).await.ok();
+            }
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn get_lan_sync_status(app: AppHandle) -> LanSyncStatus {
+    status(&app)
+}
+
+/// Turns LAN sync on or off; an empty `device_name` goes back to the host
+/// name.
+#[tauri::command]
+pub fn set_lan_sync(
+    app: AppHandle,
+    enabled: bool,
+    device_name: Option<String>,
+) -> Result<LanSyncStatus, String> {
+    let device_id = match config::current(&app).lan_sync.device_id {
+        Some(id) => id,
+        None => hex::encode(random::<6>()?),
+    };
+    config::update(&app, |c| {
+        c.lan_sync.enabled = enabled;
+        c.lan_sync.device_id = Some(device_id);
+        if let Some(name) = device_name {
+            let name = name.trim().to_string();
+            c.lan_sync.device_name = (!name.is_empty()).then_some(name);
+        }
+    })?;
+    if !enabled {
+        *PAIRING.lock().unwrap() = None;
+    }
+    Ok(status(&app))
+}
+
+/// Shows a code another device can pair with, for five minutes.
+#[tauri::command]
+pub fn start_lan_pairing(app: AppHandle) -> Result<LanSyncStatus, String> {
+    if !config::current(&app).lan_sync.enabled {
+        return Err("Turn on LAN sync first".into());
+    }
+    let bytes = random::<CODE_LEN>()?;
+    let code = bytes
+        .iter()
+        .map(|b| CODE_ALPHABET[*b as usize % CODE_ALPHABET.len()] as char)
+        .collect();
+    *PAIRING.lock().unwrap() = Some(Pairing {
+        code,
+        until: Instant::now() + PAIRING_WINDOW,
+        expires_at: chrono::Utc::now().timestamp_millis() + PAIRING_WINDOW.as_millis() as i64,
+        attempts: 0,
+    });
+    Ok(status(&app))
+}
+
+#[tauri::command]
+pub fn cancel_lan_pairing(app: AppHandle) -> LanSyncStatus {
+    *PAIRING.lock().unwrap() = None;
+    status(&app)
+}
+
+/// Pairs with `device`, found nearby, using the code it shows; this device
+/// then syncs with its log.
+#[tauri::command]
+pub async fn pair_lan_device(
+    app: AppHandle,
+    device: String,
+    code: String,
+) -> Result<LanSyncStatus, String> {
+    let cfg = config::current(&app).lan_sync;
+    let me = cfg
+        .device_id
+        .clone()
+        .filter(|_| cfg.enabled)
+        .ok_or("Turn on LAN sync first")?;
+    let code = normalize_code(&code);
+    if code.len() != CODE_LEN {
+        return Err("Enter the 8-character code shown on the other device".into());
+    }
+    let addr = SEEN
+        .lock()
+        .unwrap()
+        .get(&device)
+        .map(|s| s.addr)
+        .ok_or("That device is no longer on the network")?;
+
+    let exchange = async {
+        let stream = TcpStream::connect(addr)
+            .await
+            .map_err(|e| format!("Could not reach the device: {e}"))?;
+        let mut conn = connection(stream);
+        let hello = Plain::Pair {
+            device: me,
+            name: device_name(&cfg),
+        };
+        send_plain(&mut conn, &hello).await?;
+        let sealed = match recv_plain(&mut conn).await? {
+            Plain::Invitation { sealed } => B64.decode(sealed).map_err(|e| e.to_string())?,
+            Plain::Error { message } => return Err(message),
+            _ => return Err("Unexpected answer".into()),
+        };
+        let plain = blocking(move || exports::open(&sealed, &code))
+            .await
+            .map_err(|_| "Wrong pairing code".to_string())?;
+        let invitation: Invitation =
+            serde_json::from_slice(&plain).map_err(|e| format!("Unexpected answer: {e}"))?;
+        if invitation.device != device {
+            return Err("A different device answered".into());
+        }
+        let confirm = Request {
+            nonce: String::new(),
+            op: Op::Confirm,
+        };
+        let key = key_bytes(&invitation.pair_key)?;
+        send_bytes(&mut conn, seal_frame(&key, &confirm)?).await?;
+        match recv_plain(&mut conn).await? {
+            Plain::Paired => Ok(invitation),
+            Plain::Error { message } => Err(message),
+            _ => Err("Unexpected answer".into()),
+        }
+    };
+    let invitation = tokio::time::timeout(REQUEST_TIMEOUT, exchange)
+        .await
+        .map_err(|_| "The device stopped responding".to_string())??;
+
+    save_peer_keys(
+        &device,
+        true,
+        PeerKeys {
+            pair_key: invitation.pair_key,
+            log_key: Some(invitation.log_key),
+        },
+    )
+    .await?;
+    add_peer(
+        &app,
+        LanPeer {
+            device,
+            name: invitation.name,
+            host: true,
+            paired_at: chrono::Utc::now().timestamp_millis(),
+        },
+    )?;
+    // Failures show on the peer; the pairing itself went through.
+    sync(&app).await.ok();
+    Ok(status(&app))
+}
+
+/// Unpairs a device; it can no longer sync with this one.
+#[tauri::command]
+pub async fn remove_lan_peer(
+    app: AppHandle,
+    device: String,
+    host: bool,
+) -> Result<LanSyncStatus, String> {
+    config::update(&app, |c| {
+        c.lan_sync
+            .peers
+            .retain(|p| !(p.device == device && p.host == host));
+    })?;
+    forget_peer_keys(&device, host).await?;
+    if host {
+        LAST.lock().unwrap().remove(&device);
+        std::fs::remove_file(crate::data_dir().join(format!("lan-sync-{device}.json"))).ok();
+    }
+    Ok(status(&app))
+}
+
+#[tauri::command]
+pub async fn sync_lan_now(app: AppHandle) -> Result<LanSyncStatus, String> {
+    sync(&app).await?;
+    Ok(status(&app))
+}
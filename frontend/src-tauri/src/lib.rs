@@ -2,27 +2,218 @@
 //
 // Sidecar management:  spawn FastAPI backend, health-check, auto-restart.
 // IPC commands:        bootstrap config, data dir, file dialogs, restart.
-// System tray:         open, new snippet, search, quit.
+// System tray:         open, new snippet, search, meeting mode, recent snippets, quit (see tray.rs).
+// Tray status:         backend state icon and live tooltip (see tray_status.rs).
+// Badge:               count of items awaiting review on Dock, taskbar and tray (see badge.rs).
+// Notifications:       native notifications when background jobs finish (see notifications.rs).
+// Reminders:           remind-me times on snippets, fired as notifications (see reminders.rs).
+// Focus:               Do Not Disturb / Focus holds notifications back (see focus.rs).
+// Autostart:           launch at login, optionally hidden in the tray (see autostart.rs).
+// App lock:            passcode lock on IPC and windows, by hand or when idle (see app_lock.rs).
+// Support sessions:    opt-in diagnostics relay (see support.rs).
+// Capture:             accessibility text under cursor (see accessibility.rs).
+// Pipeline:            post-processing for every capture (see pipeline.rs).
+// Routing:             file captures into notebooks by foreground app (see routing.rs).
+// Screenshots:         full-screen and region capture to attachments (see screenshot.rs).
+// OCR:                 searchable text for screenshots and images (see ocr.rs).
+// Clipboard:           watcher, privacy filters, rich copy, image paste (see clipboard.rs).
+// Config:              shell-side settings (see config.rs).
+// Downloads:           queued, resumable model downloads (see downloads.rs).
+// Disk space:          preflight checks before large writes (see disk.rs).
+// Paste:               paste a snippet into the frontmost app (see paste.rs).
+// Meeting mode:        periodic window snapshots -> one snippet (see meeting.rs).
+// Attachments:         content-addressed store + external edits (see attachments.rs).
+// History:             local snippet body versions (see history.rs).
+// Backups:             scheduled database snapshots with retention (see backups.rs).
+// Encryption:          opt-in SQLCipher database behind a passphrase (see encryption.rs).
+// Tasks:               progress and cancellation for imports/exports (see tasks.rs).
+// Jobs:                every background job in one registry, with normalized events (see jobs.rs).
+// Exports:             encrypted JSON, ZIP archives, Markdown folders (see exports.rs).
+// Obsidian:            continuous mirror into a vault folder (see obsidian.rs).
+// Import:              drag-and-drop file import (see import.rs).
+// Notion:              workspace export ZIP import with dry run (see notion.rs).
+// Evernote:            streaming .enex import in batches (see enex.rs).
+// Bookmarks:           Chrome/Edge/Firefox bookmarks as link snippets (see bookmarks.rs).
+// Web clipper:         fetch a page, keep its article as Markdown (see clipper.rs).
+// Link previews:       title, description, icon and image cards for links (see unfurl.rs).
+// Feeds:               RSS/Atom subscriptions filed into a collection (see feeds.rs).
+// Email in:            unread mail in an IMAP folder as snippets (see email_in.rs, mime.rs).
+// Browser extension:   native messaging host for selections, links and pages (see native_host.rs).
+// Deep links:          pinup:// links with x-callback answers for launchers (see deep_link.rs).
+// Instance:            helper processes hand requests to the running app (see instance.rs).
+// Dock menu:           New Snippet, Search, Pause Capture and recent snippets on the Dock icon (see dock.rs).
+// Jump list:           New Snippet, Search and recent snippets on the Windows taskbar (see jump_list.rs).
+// Recent snippets:     the most recently used snippets for those menus (see recent.rs).
+// Services:            "Add Selection to Pin-Up AI" in the macOS Services menu (see services.rs).
+// Send to:             "Send to Pin-Up AI" in Windows Explorer (see send_to.rs).
+// Dedupe:              skip/merge/flag duplicates during imports (see dedupe.rs).
+// Watch folders:       auto-import new files from chosen folders (see watch_folders.rs).
+// Git sync:            versioned sync through a Git repository (see git_sync.rs).
+// WebDAV sync:         encrypted change log and snapshots on WebDAV (see webdav.rs).
+// S3 sync:             the same change log in S3-compatible storage (see s3.rs).
+// LAN sync:            paired peer-to-peer sync found over mDNS (see lan_sync.rs, mdns.rs).
+// Sync log:            change-set protocol shared by WebDAV, S3 and LAN (see sync_log.rs).
+// Sync conflicts:      diverged edits kept for review and resolution (see conflicts.rs).
+// Sync key:            user-held key and recovery phrase sealing all sync data (see sync_key.rs).
+// Mobile companion:    phone paired by QR code sends captures over the LAN (see companion.rs, qr.rs).
+// QR sharing:          a snippet's body or link as a QR code PNG (see qr.rs).
+// Drag out:            drag snippets into other apps (see drag_out.rs).
+// Local search:        clipboard, activity log, outbox (see local_search.rs).
+// Voice notes:         microphone recording to attachments (see recording.rs).
+// Transcription:       optional whisper.cpp sidecar (see whisper.rs).
+// Local LLM:           Ollama detection and lifecycle (see ollama.rs).
+// Hardware:            GPU/CPU/RAM detection and model advice (see hardware.rs).
+// Performance:         lite/balanced/full profiles (see performance.rs).
+// Network:             online/offline monitor for cloud vs local AI (see network.rs).
+// Proxy:               system proxy for downloads and the sidecar (see proxy.rs).
+// Certificates:        user-supplied CA roots for private gateways (see certs.rs).
+// Sleep:               health recovery after suspend/resume (see suspend.rs).
+// Power:               battery-aware throttling (see power.rs).
+// Priority:            sidecar nice level / priority class (see priority.rs).
+// Resources:           sidecar CPU/RAM monitor and memory ceiling (see resources.rs).
+// Recovery:            guided fixes when the backend fails to start (see recovery.rs).
+// Self-test:           nightly end-to-end check on a temp profile (see selftest.rs).
+// Updates:             stable/beta channels, deferred install (see updater.rs).
+// Sidecar updates:     signed backend binaries with rollback (see sidecar.rs).
+// Crashes:             panic reports and relaunch (see crash.rs).
+// Diagnostics:         redacted support bundle export (see diagnostics.rs).
+// Backend health:      uptime, restarts, crashes, check latency (see health.rs).
+// Startup:             launch phase timings and history (see startup.rs).
+// Telemetry:           opt-in anonymous launch/crash/latency counters (see telemetry.rs).
+// Release notes:       cached GitHub release notes for "What's new" (see release_notes.rs).
+// Theme:               OS light/dark changes forwarded to every window (see theme.rs).
+// Theme tokens:        palette export as JSON/CSS/Tailwind tokens, OS color picker (see theme_tokens.rs).
+// I18n:                tray, dialogs and notifications in the chosen language (see i18n.rs).
+// Region:              locale and time zone passed on to the backend (see region.rs).
+// Profiles:            separate workspaces, each with its own database (see profiles.rs).
+// Data folder:         PINUP_DATA_DIR, and moving a profile's data to another drive (see relocate.rs).
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod accessibility;
+mod app_context;
+mod app_lock;
+mod attachments;
+mod autostart;
+mod backend;
+mod backups;
+mod badge;
+mod bookmarks;
+mod capture;
+mod certs;
+mod clipboard;
+mod clipper;
+mod companion;
+mod config;
+mod conflicts;
+mod crash;
+mod dedupe;
+mod deep_link;
+mod diagnostics;
+mod disk;
+mod downloads;
+mod dock;
+mod drag_out;
+mod email_in;
+mod encryption;
+mod enex;
+mod exports;
+mod feeds;
+mod focus;
+mod git_sync;
+mod hardware;
+mod health;
+mod history;
+mod i18n;
+mod import;
+mod instance;
+mod jobs;
+mod jump_list;
+mod keyring;
+mod lan_sync;
+mod local_search;
+mod logs;
+mod mdns;
+mod meeting;
+mod mime;
+mod native_host;
+mod network;
+mod notifications;
+mod notion;
+mod obsidian;
+mod ocr;
+mod ollama;
+mod paste;
+mod performance;
+mod pipeline;
+mod power;
+mod priority;
+mod profiles;
+mod proxy;
+mod qr;
+mod recording;
+mod recovery;
+mod recent;
+mod region;
+mod relocate;
+mod release_notes;
+mod reminders;
+mod resources;
+mod richtext;
+mod routing;
+mod s3;
+mod screenshot;
+mod secrets;
+mod selftest;
+mod send_to;
+mod services;
+mod sidecar;
+mod startup;
+mod support;
+mod suspend;
+mod sync_key;
+mod sync_log;
+mod tasks;
+mod telemetry;
+mod theme;
+mod theme_tokens;
+mod tray;
+mod tray_status;
+mod unfurl;
+mod updater;
+mod watch_folders;
+mod webdav;
+mod whisper;
+
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU16, Ordering};
 use std::sync::Mutex;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use once_cell::sync::Lazy;
 use serde::Serialize;
 use tauri::{
     api::process::{Command, CommandChild, CommandEvent},
-    AppHandle, CustomMenuItem, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu,
-    SystemTrayMenuItem,
+    AppHandle, Manager,
 };
 
+/// Starts in the tray without showing the main window.
+const MINIMIZED_ARG: &str = "--minimized";
+
 // ── Shared state ───────────────────────────────────────────────────────────
 static BACKEND_PORT: AtomicU16 = AtomicU16::new(0);
+static LAUNCHED: Lazy<Instant> = Lazy::new(Instant::now);
+/// Sidecar PIDs we killed on purpose, so their exit isn't reported as a crash.
+static STOPPED_PIDS: Mutex<Option<HashSet<u32>>> = Mutex::new(None);
 
 struct SidecarState(Mutex<Option<CommandChild>>);
 
+/// Time since the process started.
+fn uptime() -> Duration {
+    LAUNCHED.elapsed()
+}
+
 // ── Bootstrap response sent to frontend ────────────────────────────────────
 #[derive(Serialize, Clone)]
 struct BootstrapConfig {
@@ -32,16 +223,31 @@ struct BootstrapConfig {
 }
 
 // ── Data dir helper ────────────────────────────────────────────────────────
+/// Shared by all profiles: models, sidecar updates, crash reports.
+/// PINUP_DATA_DIR puts it elsewhere, e.g. for a portable install.
+fn app_dir() -> PathBuf {
+    match std::env::var_os("PINUP_DATA_DIR") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("pin-up-ai"),
+    }
+}
+
+/// The active profile's data: database, attachments, settings.
 fn data_dir() -> PathBuf {
-    dirs::data_local_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("pin-up-ai")
+    profiles::dir()
 }
 
 fn db_path() -> PathBuf {
     data_dir().join("pinup.db")
 }
 
+/// Scratch space for the sidecar (unpacked runtime, temp exports). Safe to wipe.
+fn runtime_dir() -> PathBuf {
+    data_dir().join("runtime")
+}
+
 // ── Sidecar spawn ──────────────────────────────────────────────────────────
 fn spawn_backend(app: &AppHandle) -> Result<CommandChild, String> {
     let port = portpicker::pick_unused_port().unwrap_or(8111);
@@ -49,29 +255,73 @@ fn spawn_backend(app: &AppHandle) -> Result<CommandChild, String> {
 
     let db = db_path();
     std::fs::create_dir_all(db.parent().unwrap()).ok();
+    let runtime = runtime_dir().to_string_lossy().to_string();
+    std::fs::create_dir_all(&runtime).ok();
+    let mut env = HashMap::from([
+        ("PINUP_PORT".into(), port.to_string()),
+        ("PINUP_DB".into(), db.to_string_lossy().to_string()),
+        (
+            "PINUP_BACKUP_DIR".into(),
+            backups::backups_dir().to_string_lossy().to_string(),
+        ),
+        ("PINUP_HOST".into(), "127.0.0.1".into()),
+        ("TMPDIR".into(), runtime.clone()),
+        ("TEMP".into(), runtime.clone()),
+        ("TMP".into(), runtime),
+    ]);
+    let shell = config::current(app);
+    if shell.encryption.enabled && !encryption::active() {
+        return Err("The database is locked".into());
+    }
+    env.extend(encryption::backend_env());
+    env.extend(performance::backend_env(shell.performance.profile));
+    env.extend(ollama::backend_env(&shell.ollama));
+    env.extend(proxy::backend_env(&shell.proxy));
+    env.extend(certs::backend_env());
+    env.extend(logs::backend_env(&shell.logging));
+    env.extend(region::backend_env());
 
     log::info!("Spawning sidecar on port {} with db {:?}", port, db);
 
-    let (mut rx, child) = Command::new_sidecar("pinup-backend")
-        .map_err(|e| format!("Sidecar binary not found: {e}"))?
+    let command = match sidecar::binary() {
+        Some(path) => Command::new(path.to_string_lossy()),
+        None => Command::new_sidecar("pinup-backend")
+            .map_err(|e| format!("Sidecar binary not found: {e}"))?,
+    };
+    let (mut rx, child) = command
         .args(["--port", &port.to_string()])
-        .envs([
-            ("PINUP_PORT".into(), port.to_string()),
-            ("PINUP_DB".into(), db.to_string_lossy().to_string()),
-            ("PINUP_HOST".into(), "127.0.0.1".into()),
-        ])
+        .envs(env)
         .spawn()
         .map_err(|e| format!("Failed to spawn sidecar: {e}"))?;
+    startup::mark(startup::Mark::BackendSpawned);
+    health::spawned();
+    if let Err(e) = priority::apply(child.pid(), shell.backend.priority) {
+        log::warn!("{}", e);
+    }
 
     // Drain sidecar stdout/stderr to log
     let handle = app.clone();
+    let pid = child.pid();
     tauri::async_runtime::spawn(async move {
+        let mut last_error = None;
         while let Some(event) = rx.recv().await {
             match event {
                 CommandEvent::Stdout(line) => log::info!("[backend] {}", line),
-                CommandEvent::Stderr(line) => log::warn!("[backend] {}", line),
+                CommandEvent::Stderr(line) => {
+                    log::warn!("[backend] {}", line);
+                    last_error = Some(line);
+                }
                 CommandEvent::Terminated(payload) => {
                     log::error!("[backend] terminated: {:?}", payload);
+                    let stopped = STOPPED_PIDS
+                        .lock()
+                        .unwrap()
+                        .as_mut()
+                        .is_some_and(|pids| pids.remove(&pid));
+                    if !stopped {
+                        health::crashed(payload.code, payload.signal, last_error.as_deref());
+                        telemetry::sidecar_crash(&handle, payload.code);
+                    }
                     // Attempt auto-restart (max 3 times handled in setup)
                     handle.emit_all("backend-crashed", ()).ok();
                     break;
@@ -93,10 +343,13 @@ async fn wait_for_health(port: u16, retries: u32, delay_ms: u64) -> Result<Strin
 
     let url = format!("http://127.0.0.1:{}/api/health", port);
     for i in 0..retries {
+        let started = Instant::now();
         match client.get(&url).send().await {
             Ok(resp) if resp.status().is_success() => {
+                let latency = started.elapsed();
                 let body = resp.text().await.unwrap_or_default();
                 log::info!("Backend healthy after {} attempts", i + 1);
+                health::record(port, true, i + 1, Some(latency), &body);
                 return Ok(body);
             }
             Ok(resp) => {
@@ -108,7 +361,56 @@ async fn wait_for_health(port: u16, retries: u32, delay_ms: u64) -> Result<Strin
         }
         tokio::time::sleep(Duration::from_millis(delay_ms)).await;
     }
-    Err(format!("Backend did not become healthy after {} attempts", retries))
+    let error = format!("Backend did not become healthy after {} attempts", retries);
+    health::record(port, false, retries, None, &error);
+    Err(error)
+}
+
+/// Waits for the startup health check; repeated failures open recovery.
+async fn await_backend(app: &AppHandle) {
+    let port = BACKEND_PORT.load(Ordering::SeqCst);
+    match wait_for_health(port, 15, 500).await {
+        Ok(_) => {
+            log::info!("Backend ready, notifying frontend");
+            sidecar::mark_healthy();
+            startup::mark(startup::Mark::BackendHealthy);
+            telemetry::startup_latency(app);
+            app.emit_all("backend-ready", port).ok();
+            capture::flush_outbox(app).await;
+        }
+        Err(e) => {
+            log::error!("Backend failed to start: {}", e);
+            if let Some(Ok(port)) = sidecar::recover_update(app, &e).await {
+                app.emit_all("backend-ready", port).ok();
+                capture::flush_outbox(app).await;
+                return;
+            }
+            app.emit_all("backend-error", &e).ok();
+            recovery::open(app, e);
+        }
+    }
+}
+
+/// Spawns the sidecar and waits for it in the background.
+fn launch_backend(app: &AppHandle) {
+    match spawn_backend(app) {
+        Ok(child) => {
+            app.state::<SidecarState>().0.lock().unwrap().replace(child);
+
+            // Wait for health in background, then notify frontend
+            let h2 = app.clone();
+            tauri::async_runtime::spawn(async move { await_backend(&h2).await });
+        }
+        Err(e) => {
+            log::error!("Could not spawn sidecar: {}", e);
+            // In dev mode, backend may be running externally
+            if cfg!(debug_assertions) {
+                log::warn!("Dev mode — assuming external backend");
+            } else {
+                recovery::open(app, e);
+            }
+        }
+    }
 }
 
 // ── Extract install token from health or startup logs ──────────────────────
@@ -144,19 +446,43 @@ fn get_data_dir() -> String {
     data_dir().to_string_lossy().to_string()
 }
 
-#[tauri::command]
-async fn restart_backend(app: AppHandle, state: tauri::State<'_, SidecarState>) -> Result<String, String> {
-    // Kill existing
-    if let Some(child) = state.0.lock().unwrap().take() {
+fn stop_backend(app: &AppHandle) {
+    if let Some(child) = app.state::<SidecarState>().0.lock().unwrap().take() {
+        STOPPED_PIDS
+            .lock()
+            .unwrap()
+            .get_or_insert_with(HashSet::new)
+            .insert(child.pid());
         child.kill().ok();
     }
+}
+
+/// PID of the sidecar we spawned; None for an external backend.
+fn backend_pid(app: &AppHandle) -> Option<u32> {
+    app.state::<SidecarState>()
+        .0
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(CommandChild::pid)
+}
+
+/// Kills the sidecar (if any), spawns a fresh one and waits for it.
+async fn respawn_backend(app: &AppHandle) -> Result<u16, String> {
+    stop_backend(app);
     tokio::time::sleep(Duration::from_millis(500)).await;
 
-    let child = spawn_backend(&app)?;
-    *state.0.lock().unwrap() = Some(child);
+    let child = spawn_backend(app)?;
+    *app.state::<SidecarState>().0.lock().unwrap() = Some(child);
 
     let port = BACKEND_PORT.load(Ordering::SeqCst);
     wait_for_health(port, 10, 500).await?;
+    Ok(port)
+}
+
+#[tauri::command]
+async fn restart_backend(app: AppHandle) -> Result<String, String> {
+    let port = respawn_backend(&app).await?;
     Ok(format!("Backend restarted on port {}", port))
 }
 
@@ -164,131 +490,357 @@ async fn restart_backend(app: AppHandle, state: tauri::State<'_, SidecarState>)
 async fn show_open_dialog(app: AppHandle) -> Result<Option<String>, String> {
     use tauri::api::dialog::blocking::FileDialogBuilder;
     let path = FileDialogBuilder::new()
-        .set_title("Import Snippets")
+        .set_title(&i18n::t("dialog.import_snippets"))
         .add_filter("JSON", &["json"])
         .pick_file();
     Ok(path.map(|p| p.to_string_lossy().to_string()))
 }
 
+/// `folder: true` picks a destination folder instead (Markdown export).
 #[tauri::command]
-async fn show_save_dialog(app: AppHandle) -> Result<Option<String>, String> {
+async fn show_save_dialog(app: AppHandle, folder: Option<bool>) -> Result<Option<String>, String> {
     use tauri::api::dialog::blocking::FileDialogBuilder;
-    let path = FileDialogBuilder::new()
-        .set_title("Export Snippets")
-        .set_file_name("pinup-export.json")
-        .add_filter("JSON", &["json"])
-        .save_file();
+    let path = if folder.unwrap_or(false) {
+        FileDialogBuilder::new()
+            .set_title(&i18n::t("dialog.export_folder"))
+            .pick_folder()
+    } else {
+        FileDialogBuilder::new()
+            .set_title(&i18n::t("dialog.export_snippets"))
+            .set_file_name("pinup-export.json")
+            .add_filter("JSON", &["json"])
+            .save_file()
+    };
     Ok(path.map(|p| p.to_string_lossy().to_string()))
 }
 
-// ── System Tray ────────────────────────────────────────────────────────────
-fn build_tray() -> SystemTray {
-    let menu = SystemTrayMenu::new()
-        .add_item(CustomMenuItem::new("open", "Open Pin-Up AI"))
-        .add_item(CustomMenuItem::new("new_snippet", "New Snippet"))
-        .add_item(CustomMenuItem::new("search", "Search..."))
-        .add_native_item(SystemTrayMenuItem::Separator)
-        .add_item(CustomMenuItem::new("quit", "Quit"));
-    SystemTray::new().with_menu(menu)
-}
-
-fn handle_tray_event(app: &AppHandle, event: SystemTrayEvent) {
-    match event {
-        SystemTrayEvent::DoubleClick { .. } => {
-            if let Some(w) = app.get_window("main") {
-                w.show().ok();
-                w.set_focus().ok();
-            }
-        }
-        SystemTrayEvent::MenuItemClick { id, .. } => match id.as_str() {
-            "open" => {
-                if let Some(w) = app.get_window("main") {
-                    w.show().ok();
-                    w.set_focus().ok();
-                }
-            }
-            "new_snippet" => {
-                if let Some(w) = app.get_window("main") {
-                    w.show().ok();
-                    w.set_focus().ok();
-                    w.emit("tray-new-snippet", ()).ok();
-                }
-            }
-            "search" => {
-                if let Some(w) = app.get_window("main") {
-                    w.show().ok();
-                    w.set_focus().ok();
-                    w.emit("tray-search", ()).ok();
-                }
-            }
-            "quit" => {
-                app.exit(0);
-            }
-            _ => {}
-        },
-        _ => {}
-    }
-}
-
 // ── App entry ──────────────────────────────────────────────────────────────
 pub fn run() {
-    env_logger::init();
+    // A browser starting us as its extension's host gets no window
+    if native_host::launched_by_browser() {
+        native_host::serve_stdio();
+        return;
+    }
+    // So does a pinup:// link (or a launcher script) on Windows and Linux
+    if let Some(url) = deep_link::from_args() {
+        std::process::exit(deep_link::forward(&url));
+    }
+    // And Explorer's "Send to Pin-Up AI"
+    if let Some(paths) = send_to::from_args() {
+        std::process::exit(send_to::forward(paths));
+    }
+    deep_link::install();
+    Lazy::force(&LAUNCHED);
+    crash::install();
+    logs::init();
+    // --minimized, autostart's --hidden or `tray.start_minimized` keep the
+    // main window hidden until the tray (or a link) opens it.
+    let minimized = std::env::args_os().skip(1).any(|arg| arg == MINIMIZED_ARG)
+        || autostart::launched_hidden()
+        || config::load().tray.start_minimized;
+    if minimized {
+        log::info!("Starting minimized to the tray");
+    }
 
     tauri::Builder::default()
         .manage(SidecarState(Mutex::new(None)))
-        .system_tray(build_tray())
-        .on_system_tray_event(handle_tray_event)
-        .invoke_handler(tauri::generate_handler![
+        .manage(support::SupportState::default())
+        .manage(config::ConfigState(Mutex::new(config::load())))
+        .manage(meeting::MeetingState::default())
+        .system_tray(tray::build())
+        .on_system_tray_event(tray::handle_event)
+        .invoke_handler(app_lock::guard(tauri::generate_handler![
             get_bootstrap,
             get_backend_port,
             get_data_dir,
             restart_backend,
             show_open_dialog,
             show_save_dialog,
-        ])
-        .setup(|app| {
+            support::start_support_session,
+            support::stop_support_session,
+            support::get_support_session,
+            accessibility::capture_element_text,
+            config::get_shell_config,
+            config::update_shell_config,
+            tray::get_tray_settings,
+            tray::set_tray_recent,
+            tray::set_tray_clicks,
+            tray::set_tray_start_minimized,
+            tray::set_close_action,
+            badge::get_badge_count,
+            badge::set_badge_count,
+            notifications::get_notification_settings,
+            notifications::set_notifications,
+            notifications::set_notifications_during_focus,
+            focus::get_focus_state,
+            autostart::get_autostart,
+            autostart::set_autostart,
+            app_lock::get_app_lock_status,
+            app_lock::set_app_lock,
+            app_lock::lock_app,
+            app_lock::unlock_app,
+            app_lock::unlock_with_biometrics,
+            app_lock::set_app_lock_biometrics,
+            reminders::list_reminders,
+            reminders::set_reminder,
+            reminders::snooze_reminder,
+            reminders::dismiss_reminder,
+            clipboard::get_clipboard_history,
+            clipboard::clear_clipboard_history,
+            clipboard::copy_rich,
+            clipboard::capture_clipboard_image,
+            screenshot::capture_screen,
+            screenshot::capture_region,
+            screenshot::finish_region_selection,
+            screenshot::cancel_region_selection,
+            ocr::ocr_image,
+            ocr::get_ocr_status,
+            ocr::install_ocr_language,
+            ocr::remove_ocr_language,
+            theme::get_system_theme,
+            theme_tokens::export_theme_tokens,
+            theme_tokens::pick_color,
+            i18n::get_locale,
+            i18n::set_locale,
+            profiles::list_profiles,
+            profiles::create_profile,
+            profiles::switch_profile,
+            profiles::delete_profile,
+            relocate::get_data_location,
+            relocate::move_data_dir,
+            relocate::set_data_dir,
+            recording::start_recording,
+            recording::stop_recording,
+            recording::get_recording_status,
+            whisper::transcribe,
+            whisper::get_whisper_status,
+            whisper::install_whisper_model,
+            whisper::install_whisper_binary,
+            disk::check_disk_space,
+            downloads::queue_model_download,
+            downloads::list_model_downloads,
+            downloads::cancel_model_download,
+            hardware::get_hardware_info,
+            ollama::get_local_llm_status,
+            ollama::start_ollama,
+            ollama::stop_ollama,
+            ollama::pull_ollama_model,
+            performance::set_performance_profile,
+            performance::get_build_features,
+            selftest::run_self_test,
+            selftest::get_last_self_test,
+            pipeline::test_pipeline,
+            routing::evaluate_routing,
+            paste::paste_snippet,
+            accessibility::list_capture_windows,
+            meeting::start_meeting_mode,
+            meeting::stop_meeting_mode,
+            meeting::get_meeting_status,
+            meeting::close_meeting_picker,
+            attachments::edit_attachment_externally,
+            attachments::end_attachment_edit,
+            attachments::list_attachment_edits,
+            attachments::get_attachment_history,
+            history::get_snippet_history,
+            history::get_snippet_version,
+            history::restore_version,
+            local_search::search_local,
+            drag_out::start_snippet_drag,
+            logs::get_recent_logs,
+            logs::get_log_level,
+            logs::set_log_level,
+            resources::get_backend_resources,
+            priority::set_backend_priority,
+            power::get_power_profile,
+            power::set_power_override,
+            network::get_network_status,
+            proxy::get_proxy_settings,
+            certs::list_ca_certificates,
+            updater::get_update_status,
+            updater::check_for_updates,
+            updater::set_update_channel,
+            updater::download_update,
+            updater::install_update,
+            sidecar::check_backend_update,
+            sidecar::update_backend,
+            release_notes::get_release_notes,
+            diagnostics::export_diagnostics,
+            health::get_backend_stats,
+            backups::list_backups,
+            backups::create_backup_now,
+            backups::restore_backup,
+            backups::check_db_integrity,
+            backups::optimize_database,
+            backups::delete_backup,
+            encryption::get_encryption_status,
+            encryption::unlock_database,
+            encryption::enable_database_encryption,
+            encryption::purge_plaintext_copies,
+            tasks::get_task,
+            tasks::list_tasks,
+            tasks::cancel_task,
+            jobs::list_jobs,
+            exports::export_encrypted,
+            exports::import_encrypted,
+            exports::export_archive,
+            exports::import_archive,
+            exports::export_markdown_folder,
+            obsidian::get_obsidian_status,
+            obsidian::set_obsidian_vault,
+            obsidian::sync_obsidian_now,
+            notion::import_notion_export,
+            enex::import_enex,
+            bookmarks::import_bookmarks,
+            clipper::clip_url,
+            unfurl::unfurl_url,
+            feeds::get_feeds,
+            feeds::set_feeds,
+            feeds::set_feed,
+            feeds::remove_feed,
+            feeds::refresh_feeds,
+            email_in::get_email_in_status,
+            email_in::set_email_in,
+            email_in::disable_email_in,
+            email_in::check_email_now,
+            native_host::get_native_host_status,
+            native_host::set_native_host,
+            deep_link::get_deep_links,
+            deep_link::set_deep_links,
+            send_to::get_send_to_status,
+            send_to::set_send_to,
+            watch_folders::get_watch_folders,
+            watch_folders::set_watch_folder,
+            watch_folders::remove_watch_folder,
+            git_sync::get_git_sync_status,
+            git_sync::set_git_sync,
+            git_sync::sync_git_now,
+            webdav::get_webdav_status,
+            webdav::set_webdav,
+            webdav::disable_webdav,
+            webdav::sync_now,
+            s3::get_s3_status,
+            s3::set_s3_sync,
+            s3::disable_s3_sync,
+            s3::sync_s3_now,
+            lan_sync::get_lan_sync_status,
+            lan_sync::set_lan_sync,
+            lan_sync::start_lan_pairing,
+            lan_sync::cancel_lan_pairing,
+            lan_sync::pair_lan_device,
+            lan_sync::remove_lan_peer,
+            lan_sync::sync_lan_now,
+            conflicts::list_conflicts,
+            conflicts::resolve_conflict,
+            sync_key::get_sync_key_status,
+            sync_key::create_sync_key,
+            sync_key::get_recovery_phrase,
+            sync_key::restore_sync_key,
+            sync_key::backup_sync_key,
+            sync_key::restore_sync_key_backup,
+            sync_key::forget_sync_key,
+            companion::get_companion_status,
+            companion::set_companion,
+            companion::start_companion_pairing,
+            companion::cancel_companion_pairing,
+            companion::remove_companion_device,
+            qr::snippet_to_qr,
+            telemetry::get_telemetry_status,
+            telemetry::set_telemetry_enabled,
+            telemetry::get_telemetry_events,
+            startup::mark_first_paint,
+            startup::get_startup_metrics,
+            recovery::get_recovery_status,
+            recovery::run_integrity_check,
+            recovery::list_local_backups,
+            recovery::restore_local_backup,
+            recovery::use_external_backend,
+            recovery::reset_runtime_dir,
+            recovery::retry_backend,
+        ]))
+        .setup(move |app| {
             let handle = app.handle();
 
-            // Spawn sidecar backend
-            match spawn_backend(&handle) {
-                Ok(child) => {
-                    app.state::<SidecarState>().0.lock().unwrap().replace(child);
-
-                    // Wait for health in background, then notify frontend
-                    let h2 = handle.clone();
-                    tauri::async_runtime::spawn(async move {
-                        let port = BACKEND_PORT.load(Ordering::SeqCst);
-                        match wait_for_health(port, 15, 500).await {
-                            Ok(_) => {
-                                log::info!("Backend ready, notifying frontend");
-                                h2.emit_all("backend-ready", port).ok();
-                            }
-                            Err(e) => {
-                                log::error!("Backend failed to start: {}", e);
-                                h2.emit_all("backend-error", e).ok();
-                            }
-                        }
-                    });
-                }
-                Err(e) => {
-                    log::error!("Could not spawn sidecar: {}", e);
-                    // In dev mode, backend may be running externally
-                    if cfg!(debug_assertions) {
-                        log::warn!("Dev mode — assuming external backend");
-                    }
+            startup::mark(startup::Mark::Setup);
+            app_lock::start(&handle);
+            // The window starts hidden (see tauri.conf.json), and stays so
+            // behind the lock until it is unlocked.
+            if !minimized {
+                if let Some(main) = app.get_window("main") {
+                    app_lock::show(&main);
                 }
             }
+            crash::attach(&handle);
+            logs::apply_config(&config::current(&handle).logging);
+            jobs::start(&handle);
+            accessibility::register_shortcut(&handle);
+            screenshot::register_shortcut(&handle);
+            clipboard::start_watcher(&handle);
+            history::start(&handle);
+            backups::start(&handle);
+            obsidian::start(&handle);
+            selftest::start(&handle);
+            resources::start(&handle);
+            health::start(&handle);
+            power::start(&handle);
+            suspend::start(&handle);
+            network::start(&handle);
+            region::start(&handle);
+            telemetry::start(&handle);
+            updater::start(&handle);
+            ollama::autostart(&handle);
+            import::register_drop_handler(&handle);
+            watch_folders::start(&handle);
+            git_sync::start(&handle);
+            webdav::start(&handle);
+            s3::start(&handle);
+            lan_sync::start(&handle);
+            companion::start(&handle);
+            feeds::start(&handle);
+            email_in::start(&handle);
+            instance::start(&handle);
+            native_host::start(&handle);
+            deep_link::start(&handle);
+            services::start(&handle);
+            dock::start(&handle);
+            recent::start(&handle);
+            send_to::start(&handle);
+            tray_status::start(&handle);
+            badge::start(&handle);
+            notifications::start(&handle);
+            focus::start(&handle);
+            autostart::start(&handle);
+            theme::start(&handle);
+            reminders::start(&handle);
+
+            // An external backend chosen from the recovery window replaces the sidecar
+            if let Some(port) = config::current(&handle).backend.external_port {
+                log::info!("Using external backend on port {}", port);
+                BACKEND_PORT.store(port, Ordering::SeqCst);
+                startup::mark(startup::Mark::BackendSpawned);
+                health::spawned();
+                let h2 = handle.clone();
+                tauri::async_runtime::spawn(async move { await_backend(&h2).await });
+                return Ok(());
+            }
+
+            // An encrypted database waits for the passphrase (see encryption.rs)
+            if encryption::locked(&handle) {
+                encryption::open_unlock(&handle);
+                return Ok(());
+            }
+            launch_backend(&handle);
 
             Ok(())
         })
+        .on_page_load(|window, _| {
+            if window.label() == "main" {
+                startup::mark(startup::Mark::WebviewReady);
+            }
+        })
         .on_window_event(|event| {
-            // Hide window instead of closing (tray keeps running)
+            // Hide to the tray or quit, as the user chose (see tray.rs)
             if let tauri::WindowEvent::CloseRequested { api, .. } = event.event() {
-                #[cfg(not(debug_assertions))]
-                {
-                    event.window().hide().ok();
-                    api.prevent_close();
-                }
+                tray::close_requested(event.window(), api);
             }
         })
         .run(tauri::generate_context!())
@@ -0,0 +1,148 @@
+// Local search — searches stores the shell owns, without the backend.
+//
+// Stores: clipboard history, the activity log (captured app/backend log
+// lines) and captures still waiting in the outbox. Everything is in memory
+// or a small JSON file, so each query scans the stores directly; results
+// stay available while the backend is down.
+
+use serde::Serialize;
+
+use crate::{capture, clipboard, logs};
+
+const STORES: &[&str] = &["clipboard", "activity", "unsynced"];
+const MAX_RESULTS: usize = 50;
+const EXCERPT_CHARS: usize = 120;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct LocalResult {
+    pub store: &'static str,
+    /// Store-specific key: timestamp for clipboard/activity, outbox id for unsynced.
+    pub id: String,
+    pub title: String,
+    pub excerpt: String,
+    pub ts: u64,
+    pub score: usize,
+}
+
+struct Doc {
+    store: &'static str,
+    id: String,
+    title: String,
+    text: String,
+    ts: u64,
+}
+
+fn collect(store: &str) -> Vec<Doc> {
+    match store {
+        "clipboard" => clipboard::get_clipboard_history()
+            .into_iter()
+            .map(|e| Doc {
+                store: "clipboard",
+                id: e.ts.to_string(),
+                title: e.app.unwrap_or_else(|| "Clipboard".into()),
+                text: e.text,
+                ts: e.ts / 1000,
+            })
+            .collect(),
+        "activity" => logs::recent(usize::MAX)
+            .into_iter()
+            .map(|l| Doc {
+                store: "activity",
+                id: l.ts.to_string(),
+                title: format!("{} {}", l.level, l.target),
+                text: l.message,
+                ts: l.ts / 1000,
+            })
+            .collect(),
+        "unsynced" => capture::pending()
+            .into_iter()
+            .map(|p| Doc {
+                store: "unsynced",
+                id: p.id.to_string(),
+                title: p
+                    .capture
+                    .title
+                    .unwrap_or_else(|| format!("Unsynced {} capture", p.capture.source)),
+                text: p.capture.body,
+                ts: p.queued_at,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Every term must appear; more occurrences and a whole-phrase hit rank higher.
+fn score(doc: &Doc, query: &str, terms: &[String]) -> Option<usize> {
+    let haystack = format!("{}\n{}", doc.title, doc.text).to_lowercase();
+    let mut score = 0;
+    for term in terms {
+        match haystack.matches(term.as_str()).count() {
+            0 => return None,
+            n => score += n,
+        }
+    }
+    if terms.len() > 1 && haystack.contains(query) {
+        score += 10;
+    }
+    Some(score)
+}
+
+fn excerpt(text: &str, terms: &[String]) -> String {
+    let lower = text.to_lowercase();
+    // Lowercasing can change byte lengths; only trust the offset if it maps.
+    let at = terms
+        .first()
+        .and_then(|t| lower.find(t.as_str()))
+        .filter(|i| lower.len() == text.len() && text.is_char_boundary(*i))
+        .unwrap_or(0);
+    let start = text[..at]
+        .char_indices()
+        .rev()
+        .nth(EXCERPT_CHARS / 3)
+        .map_or(0, |(i, _)| i);
+    let mut out: String = text[start..].chars().take(EXCERPT_CHARS).collect();
+    out = out.split_whitespace().collect::<Vec<_>>().join(" ");
+    if start > 0 {
+        out.insert(0, '…');
+    }
+    out
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Searches the given stores (all of them when empty), best match first.
+#[tauri::command]
+pub fn search_local(
+    query: String,
+    stores: Option<Vec<String>>,
+) -> Result<Vec<LocalResult>, String> {
+    let query = query.trim().to_lowercase();
+    let terms: Vec<String> = query.split_whitespace().map(String::from).collect();
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+    let stores = stores
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| STORES.iter().map(|s| s.to_string()).collect());
+    if let Some(unknown) = stores.iter().find(|s| !STORES.contains(&s.as_str())) {
+        return Err(format!("Unknown store: {}", unknown));
+    }
+
+    let mut results: Vec<LocalResult> = stores
+        .iter()
+        .flat_map(|s| collect(s))
+        .filter_map(|doc| {
+            let score = score(&doc, &query, &terms)?;
+            Some(LocalResult {
+                store: doc.store,
+                excerpt: excerpt(&doc.text, &terms),
+                id: doc.id,
+                title: doc.title,
+                ts: doc.ts,
+                score,
+            })
+        })
+        .collect();
+    results.sort_by(|a, b| b.score.cmp(&a.score).then(b.ts.cmp(&a.ts)));
+    results.truncate(MAX_RESULTS);
+    Ok(results)
+}
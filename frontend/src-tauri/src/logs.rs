@@ -0,0 +1,262 @@
+// Log capture — keeps recent app + backend log lines in memory.
+//
+// Wraps env_logger so stderr output is unchanged, and tees every record that
+// passes the filter into a bounded ring buffer plus a broadcast channel for
+// live tailing (support sessions, diagnostics).
+//
+// RUST_LOG sets the startup filter. `set_log_level` overrides it at runtime
+// for the app's own modules (dependencies keep following RUST_LOG), saves the
+// choice as `logging.level`, and forwards it to the sidecar — immediately
+// via POST /logging and as PINUP_LOG_LEVEL whenever it is spawned.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::LevelFilter;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::AppHandle;
+use tokio::sync::broadcast;
+
+use crate::{backend, config};
+
+const CAPACITY: usize = 2000;
+/// OVERRIDE value while RUST_LOG applies.
+const NO_OVERRIDE: usize = usize::MAX;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn filter(self) -> LevelFilter {
+        match self {
+            LogLevel::Error => LevelFilter::Error,
+            LogLevel::Warn => LevelFilter::Warn,
+            LogLevel::Info => LevelFilter::Info,
+            LogLevel::Debug => LevelFilter::Debug,
+            LogLevel::Trace => LevelFilter::Trace,
+        }
+    }
+
+    /// Python logging's name for the level.
+    fn backend_name(self) -> &'static str {
+        match self {
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARNING",
+            LogLevel::Info => "INFO",
+            LogLevel::Debug | LogLevel::Trace => "DEBUG",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct LoggingConfig {
+    /// None follows RUST_LOG (shell) and the backend's default.
+    pub level: Option<LogLevel>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct LogLevelStatus {
+    level: Option<LogLevel>,
+    /// Most verbose level any module currently logs at.
+    effective: String,
+    /// The sidecar's root level, when it answered.
+    backend: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct LogLine {
+    pub ts: u64,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+struct LogBuffer {
+    lines: Mutex<VecDeque<LogLine>>,
+    tx: broadcast::Sender<LogLine>,
+}
+
+static BUFFER: Lazy<LogBuffer> = Lazy::new(|| LogBuffer {
+    lines: Mutex::new(VecDeque::with_capacity(CAPACITY)),
+    tx: broadcast::channel(256).0,
+});
+
+fn push(line: LogLine) {
+    let mut lines = BUFFER.lines.lock().unwrap();
+    if lines.len() == CAPACITY {
+        lines.pop_front();
+    }
+    lines.push_back(line.clone());
+    drop(lines);
+    // No receivers is the common case — ignore the error.
+    BUFFER.tx.send(line).ok();
+}
+
+/// Most recent `n` captured lines, oldest first.
+pub fn recent(n: usize) -> Vec<LogLine> {
+    let lines = BUFFER.lines.lock().unwrap();
+    lines
+        .iter()
+        .skip(lines.len().saturating_sub(n))
+        .cloned()
+        .collect()
+}
+
+/// Live feed of new lines as they are logged.
+pub fn subscribe() -> broadcast::Receiver<LogLine> {
+    BUFFER.tx.subscribe()
+}
+
+// ── Logger ─────────────────────────────────────────────────────────────────
+/// `LevelFilter as usize` set with `set_log_level`, or NO_OVERRIDE.
+static OVERRIDE: AtomicUsize = AtomicUsize::new(NO_OVERRIDE);
+/// Max level of the RUST_LOG filter.
+static ENV_MAX: AtomicUsize = AtomicUsize::new(0);
+
+fn level_override() -> Option<LevelFilter> {
+    LevelFilter::iter().nth(OVERRIDE.load(Ordering::SeqCst))
+}
+
+struct TeeLogger {
+    /// RUST_LOG filter.
+    filter: env_logger::Logger,
+    /// Formats and writes everything it is given.
+    writer: env_logger::Logger,
+}
+
+impl TeeLogger {
+    fn allows(&self, metadata: &log::Metadata) -> bool {
+        let ours = metadata
+            .target()
+            .starts_with(module_path!().split("::").next().unwrap());
+        match level_override() {
+            Some(level) if ours => metadata.level() <= level,
+            _ => log::Log::enabled(&self.filter, metadata),
+        }
+    }
+}
+
+impl log::Log for TeeLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.allows(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.allows(record.metadata()) {
+            return;
+        }
+        push(LogLine {
+            ts: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+            level: record.level().to_string(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+        });
+        self.writer.log(record);
+    }
+
+    fn flush(&self) {
+        self.writer.flush();
+    }
+}
+
+/// Drop-in replacement for `env_logger::init()`.
+pub fn init() {
+    let filter = env_logger::Builder::from_default_env().build();
+    let writer =
+        env_logger::Builder::from_env(env_logger::Env::new().write_style("RUST_LOG_STYLE"))
+            .filter_level(LevelFilter::Trace)
+            .build();
+    let max = filter.filter();
+    ENV_MAX.store(max as usize, Ordering::SeqCst);
+    if log::set_boxed_logger(Box::new(TeeLogger { filter, writer })).is_ok() {
+        log::set_max_level(max);
+    }
+}
+
+/// Applies `level` to the app's modules; None returns to RUST_LOG.
+fn apply_level(level: Option<LogLevel>) {
+    let env_max = LevelFilter::iter()
+        .nth(ENV_MAX.load(Ordering::SeqCst))
+        .unwrap_or(LevelFilter::Error);
+    match level {
+        Some(level) => {
+            OVERRIDE.store(level.filter() as usize, Ordering::SeqCst);
+            log::set_max_level(env_max.max(level.filter()));
+        }
+        None => {
+            OVERRIDE.store(NO_OVERRIDE, Ordering::SeqCst);
+            log::set_max_level(env_max);
+        }
+    }
+}
+
+/// Restores the saved level at startup.
+pub fn apply_config(cfg: &LoggingConfig) {
+    apply_level(cfg.level);
+}
+
+/// Sidecar environment carrying the saved level.
+pub fn backend_env(cfg: &LoggingConfig) -> Vec<(String, String)> {
+    cfg.level
+        .map(|level| {
+            (
+                "PINUP_LOG_LEVEL".to_string(),
+                level.backend_name().to_string(),
+            )
+        })
+        .into_iter()
+        .collect()
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn get_recent_logs(limit: Option<usize>) -> Vec<LogLine> {
+    recent(limit.unwrap_or(500))
+}
+
+#[tauri::command]
+pub async fn get_log_level(app: AppHandle) -> LogLevelStatus {
+    let backend = backend::get("/logging")
+        .await
+        .ok()
+        .and_then(|v| v["level"].as_str().map(str::to_string));
+    LogLevelStatus {
+        level: config::current(&app).logging.level,
+        effective: log::max_level().to_string().to_lowercase(),
+        backend,
+    }
+}
+
+/// Switches the shell and the running sidecar to `level` (None resets to
+/// the defaults) and keeps it for later launches.
+#[tauri::command]
+pub async fn set_log_level(
+    app: AppHandle,
+    level: Option<LogLevel>,
+) -> Result<LogLevelStatus, String> {
+    config::update(&app, |c| c.logging.level = level)?;
+    apply_level(level);
+    log::info!("Log level set to {:?}", level);
+    let backend_level = level.map_or("INFO", LogLevel::backend_name);
+    if let Err(e) = backend::post("/logging", &json!({ "level": backend_level })).await {
+        if !backend::is_unreachable(&e) {
+            log::warn!("Could not change the backend log level: {}", e);
+        }
+    }
+    Ok(get_log_level(app).await)
+}
@@ -0,0 +1,290 @@
+// mDNS — a minimal DNS-SD responder and browser for one service type.
+//
+// Just enough of RFC 6762/6763 for LAN sync (see lan_sync.rs) to find other
+// instances without any server: an instance answers PTR queries for SERVICE
+// with its PTR, SRV, TXT and A records, announces itself the same way when it
+// starts, and browses by sending that query. The socket shares port 5353
+// with the system responder (Avahi, Bonjour) rather than replacing it.
+// Browsers address a peer by the source of its answer, not its A record, so
+// machines with several interfaces are reached on the one that answered.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4};
+
+use socket2::{Domain, Protocol, Socket, Type};
+use tokio::net::UdpSocket;
+
+pub const SERVICE: &str = "_pinup-sync._tcp.local";
+const GROUP: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+const PORT: u16 = 5353;
+/// Seconds; peers forget an instance that stops answering.
+const TTL: u32 = 120;
+
+const TYPE_A: u16 = 1;
+const TYPE_PTR: u16 = 12;
+const TYPE_TXT: u16 = 16;
+const TYPE_SRV: u16 = 33;
+const TYPE_ANY: u16 = 255;
+const CLASS_IN: u16 = 1;
+/// Set on records only this instance owns.
+const CACHE_FLUSH: u16 = 0x8000;
+
+/// What this instance advertises.
+pub struct Instance {
+    /// A single DNS label.
+    pub id: String,
+    pub port: u16,
+    pub txt: Vec<(String, String)>,
+}
+
+/// An instance that answered.
+#[derive(Clone, Debug)]
+pub struct Found {
+    pub id: String,
+    pub addr: SocketAddr,
+    pub txt: HashMap<String, String>,
+}
+
+pub enum Message {
+    /// Someone is browsing for SERVICE.
+    Query,
+    Answers(Vec<Found>),
+}
+
+pub struct Mdns {
+    socket: UdpSocket,
+}
+
+impl Mdns {
+    pub fn bind() -> Result<Self, String> {
+        let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))
+            .map_err(|e| format!("mDNS socket: {e}"))?;
+        socket
+            .set_reuse_address(true)
+            .map_err(|e| format!("mDNS socket: {e}"))?;
+        #[cfg(unix)]
+        socket
+            .set_reuse_port(true)
+            .map_err(|e| format!("mDNS socket: {e}"))?;
+        socket
+            .bind(&SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, PORT).into())
+            .map_err(|e| format!("Could not listen for mDNS: {e}"))?;
+        socket
+            .join_multicast_v4(&GROUP, &Ipv4Addr::UNSPECIFIED)
+            .map_err(|e| format!("Could not join the mDNS group: {e}"))?;
+        socket
+            .set_nonblocking(true)
+            .map_err(|e| format!("mDNS socket: {e}"))?;
+        let socket = UdpSocket::from_std(socket.into()).map_err(|e| e.to_string())?;
+        Ok(Self { socket })
+    }
+
+    async fn send(&self, packet: &[u8]) -> Result<(), String> {
+        self.socket
+            .send_to(packet, (GROUP, PORT))
+            .await
+            .map(drop)
+            .map_err(|e| format!("mDNS send failed: {e}"))
+    }
+
+    /// Asks every instance on the network to answer.
+    pub async fn query(&self) -> Result<(), String> {
+        let mut packet = header(0, 1, 0);
+        write_name(&mut packet, SERVICE);
+        packet.extend_from_slice(&TYPE_PTR.to_be_bytes());
+        packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+        self.send(&packet).await
+    }
+
+    /// Sends this instance's records, as an answer or an announcement.
+    pub async fn announce(&self, instance: &Instance) -> Result<(), String> {
+        let name = format!("{}.{SERVICE}", instance.id);
+        let host = format!("{}.local", instance.id);
+        let ip = local_ipv4();
+        let count = if ip.is_some() { 4 } else { 3 };
+        let mut packet = header(0x8400, 0, count);
+
+        let mut data = Vec::new();
+        write_name(&mut data, &name);
+        write_record(&mut packet, SERVICE, TYPE_PTR, CLASS_IN, &data);
+
+        let mut data = vec![0, 0, 0, 0];
+        data.extend_from_slice(&instance.port.to_be_bytes());
+        write_name(&mut data, &host);
+        write_record(&mut packet, &name, TYPE_SRV, CLASS_IN | CACHE_FLUSH, &data);
+
+        let mut data = Vec::new();
+        for (key, value) in &instance.txt {
+            let entry = format!("{key}={value}");
+            let entry = &entry.as_bytes()[..entry.len().min(255)];
+            data.push(entry.len() as u8);
+            data.extend_from_slice(entry);
+        }
+        if data.is_empty() {
+            data.push(0);
+        }
+        write_record(&mut packet, &name, TYPE_TXT, CLASS_IN | CACHE_FLUSH, &data);
+
+        if let Some(ip) = ip {
+            write_record(
+                &mut packet,
+                &host,
+                TYPE_A,
+                CLASS_IN | CACHE_FLUSH,
+                &ip.octets(),
+            );
+        }
+        self.send(&packet).await
+    }
+
+    /// Waits for the next packet about SERVICE.
+    pub async fn recv(&self) -> Result<Message, String> {
+        let mut buffer = vec![0u8; 9000];
+        loop {
+            let (len, from) = self
+                .socket
+                .recv_from(&mut buffer)
+                .await
+                .map_err(|e| format!("mDNS receive failed: {e}"))?;
+            if let Some(message) = parse(&buffer[..len], from.ip()) {
+                return Ok(message);
+            }
+        }
+    }
+}
+
+/// Address the system would send multicast from.
+pub fn local_ipv4() -> Option<Ipv4Addr> {
+    let socket = std::net::UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((GROUP, PORT)).ok()?;
+    match socket.local_addr().ok()?.ip() {
+        IpAddr::V4(ip) if !ip.is_unspecified() => Some(ip),
+        _ => None,
+    }
+}
+
+// ── Wire format ────────────────────────────────────────────────────────────
+fn header(flags: u16, questions: u16, answers: u16) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(512);
+    packet.extend_from_slice(&0u16.to_be_bytes());
+    packet.extend_from_slice(&flags.to_be_bytes());
+    packet.extend_from_slice(&questions.to_be_bytes());
+    packet.extend_from_slice(&answers.to_be_bytes());
+    packet.extend_from_slice(&[0, 0, 0, 0]);
+    packet
+}
+
+fn write_name(out: &mut Vec<u8>, name: &str) {
+    for label in name.split('.').filter(|l| !l.is_empty()) {
+        let label = &label.as_bytes()[..label.len().min(63)];
+        out.push(label.len() as u8);
+        out.extend_from_slice(label);
+    }
+    out.push(0);
+}
+
+fn write_record(out: &mut Vec<u8>, name: &str, rtype: u16, class: u16, data: &[u8]) {
+    write_name(out, name);
+    out.extend_from_slice(&rtype.to_be_bytes());
+    out.extend_from_slice(&class.to_be_bytes());
+    out.extend_from_slice(&TTL.to_be_bytes());
+    out.extend_from_slice(&(data.len() as u16).to_be_bytes());
+    out.extend_from_slice(data);
+}
+
+fn read_u16(packet: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(
+        packet.get(pos..pos + 2)?.try_into().ok()?,
+    ))
+}
+
+/// The name at `pos`, following compression pointers, and where it ends.
+fn read_name(packet: &[u8], mut pos: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut end = None;
+    // Bounds pointer loops in malformed packets.
+    for _ in 0..128 {
+        let len = *packet.get(pos)? as usize;
+        if len & 0xc0 == 0xc0 {
+            end.get_or_insert(pos + 2);
+            pos = (len & 0x3f) << 8 | *packet.get(pos + 1)? as usize;
+        } else if len == 0 {
+            return Some((labels.join("."), end.unwrap_or(pos + 1)));
+        } else {
+            let label = packet.get(pos + 1..pos + 1 + len)?;
+            labels.push(String::from_utf8_lossy(label).into_owned());
+            pos += 1 + len;
+        }
+    }
+    None
+}
+
+fn parse(packet: &[u8], from: IpAddr) -> Option<Message> {
+    let flags = read_u16(packet, 2)?;
+    let questions = read_u16(packet, 4)?;
+    let records = read_u16(packet, 6)? as usize
+        + read_u16(packet, 8)? as usize
+        + read_u16(packet, 10)? as usize;
+    let mut pos = 12;
+
+    let mut asked = false;
+    for _ in 0..questions {
+        let (name, end) = read_name(packet, pos)?;
+        let qtype = read_u16(packet, end)?;
+        pos = end + 4;
+        asked |= name.eq_ignore_ascii_case(SERVICE) && matches!(qtype, TYPE_PTR | TYPE_ANY);
+    }
+    if flags & 0x8000 == 0 {
+        return asked.then_some(Message::Query);
+    }
+
+    let suffix = format!(".{SERVICE}");
+    let mut instances = Vec::new();
+    let mut ports = HashMap::new();
+    let mut txts: HashMap<String, HashMap<String, String>> = HashMap::new();
+    for _ in 0..records {
+        let (name, end) = read_name(packet, pos)?;
+        let rtype = read_u16(packet, end)?;
+        let len = read_u16(packet, end + 8)? as usize;
+        let data_at = end + 10;
+        let data = packet.get(data_at..data_at + len)?;
+        pos = data_at + len;
+        match rtype {
+            TYPE_PTR if name.eq_ignore_ascii_case(SERVICE) => {
+                instances.push(read_name(packet, data_at)?.0);
+            }
+            TYPE_SRV if data.len() > 6 => {
+                ports.insert(name, u16::from_be_bytes([data[4], data[5]]));
+            }
+            TYPE_TXT => {
+                let mut entries = HashMap::new();
+                let mut at = 0;
+                while let Some(&len) = data.get(at) {
+                    let Some(entry) = data.get(at + 1..at + 1 + len as usize) else {
+                        break;
+                    };
+                    let entry = String::from_utf8_lossy(entry);
+                    if let Some((key, value)) = entry.split_once('=') {
+                        entries.insert(key.to_string(), value.to_string());
+                    }
+                    at += 1 + len as usize;
+                }
+                txts.insert(name, entries);
+            }
+            _ => {}
+        }
+    }
+
+    let found: Vec<Found> = instances
+        .into_iter()
+        .filter_map(|instance| {
+            let id = instance.strip_suffix(&suffix)?.to_string();
+            Some(Found {
+                addr: SocketAddr::new(from, *ports.get(&instance)?),
+                txt: txts.remove(&instance).unwrap_or_default(),
+                id,
+            })
+        })
+        .collect();
+    (!found.is_empty()).then_some(Message::Answers(found))
+}
@@ -0,0 +1,267 @@
+// Meeting mode — periodic accessibility snapshots of one window.
+//
+// While active, the chosen window's text is read every `interval_secs`.
+// Identical consecutive snapshots are dropped and only lines that were not in
+// the previous snapshot are kept, so a live transcript or shared doc turns
+// into a running log. When the mode ends (from the tray, via IPC, or at the
+// scheduled end) the log is filed as a single snippet.
+//
+// The window picker is a small always-on-top overlay (meeting-picker.html)
+// that lists windows via `list_capture_windows`.
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, WindowBuilder, WindowUrl};
+use tokio::sync::oneshot;
+
+use crate::accessibility::{self, WindowInfo};
+use crate::capture::{self, Capture};
+use crate::config;
+use crate::performance;
+use crate::{i18n, tray};
+
+const PICKER_LABEL: &str = "meeting-picker";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct MeetingConfig {
+    pub interval_secs: u64,
+    pub default_minutes: u64,
+}
+
+impl Default for MeetingConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 30,
+            default_minutes: 60,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct MeetingStatus {
+    active: bool,
+    window: Option<WindowInfo>,
+    started_at: i64,
+    ends_at: i64,
+}
+
+struct Meeting {
+    status: MeetingStatus,
+    stop: oneshot::Sender<()>,
+}
+
+#[derive(Default)]
+pub struct MeetingState(Mutex<Option<Meeting>>);
+
+struct Section {
+    at: DateTime<Local>,
+    lines: Vec<String>,
+}
+
+// ── Recording ──────────────────────────────────────────────────────────────
+async fn record(
+    app: &AppHandle,
+    window: &WindowInfo,
+    interval: Duration,
+    run_for: Duration,
+    mut stop: oneshot::Receiver<()>,
+) -> Vec<Section> {
+    let deadline = tokio::time::sleep(run_for);
+    tokio::pin!(deadline);
+    let mut ticker = tokio::time::interval(interval);
+    let mut previous: HashSet<String> = HashSet::new();
+    let mut sections = Vec::new();
+
+    loop {
+        tokio::select! {
+            _ = &mut deadline => break,
+            _ = &mut stop => break,
+            _ = ticker.tick() => {
+                let text = match accessibility::window_text(window.id.clone()).await {
+                    Ok(text) => text,
+                    Err(e) => {
+                        log::warn!("Meeting snapshot failed: {}", e);
+                        continue;
+                    }
+                };
+                let lines: Vec<String> = text
+                    .lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty())
+                    .map(String::from)
+                    .collect();
+                let fresh: Vec<String> = lines
+                    .iter()
+                    .filter(|l| !previous.contains(*l))
+                    .cloned()
+                    .collect();
+                previous = lines.into_iter().collect();
+                if fresh.is_empty() {
+                    continue;
+                }
+                sections.push(Section { at: Local::now(), lines: fresh });
+                app.emit_all("meeting-snapshot", sections.len()).ok();
+            }
+        }
+    }
+    sections
+}
+
+fn compile(window: &WindowInfo, started: DateTime<Local>, sections: &[Section]) -> Capture {
+    let body = sections
+        .iter()
+        .map(|s| format!("[{}]\n{}", s.at.format("%H:%M:%S"), s.lines.join("\n")))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    Capture {
+        title: Some(format!(
+            "Meeting notes — {} ({})",
+            window.title,
+            started.format("%Y-%m-%d %H:%M")
+        )),
+        body,
+        source: "meeting".into(),
+        tags: vec!["meeting".into()],
+        ..Default::default()
+    }
+}
+
+// ── Tray / picker ──────────────────────────────────────────────────────────
+/// Whether a meeting is being recorded, for the tray's Start/Stop item.
+pub fn active(app: &AppHandle) -> bool {
+    app.try_state::<MeetingState>()
+        .is_some_and(|state| state.0.lock().unwrap().is_some())
+}
+
+pub fn open_picker(app: &AppHandle) {
+    if let Some(w) = app.get_window(PICKER_LABEL) {
+        w.set_focus().ok();
+        return;
+    }
+    let result = WindowBuilder::new(
+        app,
+        PICKER_LABEL,
+        WindowUrl::App("meeting-picker.html".into()),
+    )
+    .title(i18n::t("window.meeting_picker"))
+    .inner_size(440.0, 520.0)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .center()
+    .build();
+    if let Err(e) = result {
+        log::error!("Could not open meeting picker: {}", e);
+    }
+}
+
+/// Tray entry point: stop if recording, otherwise ask which window to record.
+pub fn toggle_from_tray(app: &AppHandle) {
+    let stopped = match app.state::<MeetingState>().0.lock().unwrap().take() {
+        Some(meeting) => meeting.stop.send(()).is_ok(),
+        None => false,
+    };
+    if !stopped {
+        open_picker(app);
+    }
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub async fn start_meeting_mode(
+    app: AppHandle,
+    window_id: String,
+    minutes: Option<u64>,
+) -> Result<MeetingStatus, String> {
+    let window = accessibility::list_windows()
+        .await?
+        .into_iter()
+        .find(|w| w.id == window_id)
+        .ok_or("Window is no longer open")?;
+    let cfg = config::current(&app).meeting;
+    let minutes = minutes.unwrap_or(cfg.default_minutes).max(1);
+    let started = Local::now();
+
+    let (stop_tx, stop_rx) = oneshot::channel();
+    let status = MeetingStatus {
+        active: true,
+        window: Some(window.clone()),
+        started_at: started.timestamp(),
+        ends_at: started.timestamp() + (minutes * 60) as i64,
+    };
+    {
+        let state = app.state::<MeetingState>();
+        let mut slot = state.0.lock().unwrap();
+        if slot.is_some() {
+            return Err("Meeting mode is already running".into());
+        }
+        *slot = Some(Meeting {
+            status: status.clone(),
+            stop: stop_tx,
+        });
+    }
+
+    if let Some(w) = app.get_window(PICKER_LABEL) {
+        w.close().ok();
+    }
+    tray::refresh(&app);
+    app.emit_all("meeting-mode-started", &status).ok();
+    log::info!("Meeting mode started for window {}", window.id);
+
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let factor = performance::capabilities(&handle).poll_factor;
+        let interval = Duration::from_secs(cfg.interval_secs.max(5) * factor);
+        let run_for = Duration::from_secs(minutes * 60);
+        let sections = record(&handle, &window, interval, run_for, stop_rx).await;
+
+        handle.state::<MeetingState>().0.lock().unwrap().take();
+        tray::refresh(&handle);
+
+        let snippet = if sections.is_empty() {
+            None
+        } else {
+            match capture::submit(&handle, compile(&window, started, &sections)).await {
+                Ok(snippet) => Some(snippet),
+                Err(e) => {
+                    log::error!("Could not save meeting notes: {}", e);
+                    None
+                }
+            }
+        };
+        handle.emit_all("meeting-mode-ended", snippet).ok();
+    });
+
+    Ok(status)
+}
+
+#[tauri::command]
+pub fn stop_meeting_mode(state: tauri::State<'_, MeetingState>) -> bool {
+    match state.0.lock().unwrap().take() {
+        Some(meeting) => meeting.stop.send(()).is_ok(),
+        None => false,
+    }
+}
+
+#[tauri::command]
+pub fn get_meeting_status(state: tauri::State<'_, MeetingState>) -> MeetingStatus {
+    state
+        .0
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|m| m.status.clone())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn close_meeting_picker(app: AppHandle) {
+    if let Some(w) = app.get_window(PICKER_LABEL) {
+        w.close().ok();
+    }
+}
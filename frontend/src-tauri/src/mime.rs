@@ -0,0 +1,372 @@
+// MIME — just enough of RFC 5322 and 2045-2047 to read an email.
+//
+// A message is split into its headers and body; multipart bodies are walked
+// part by part (nested up to MAX_DEPTH), each decoded from base64 or
+// quoted-printable and, for text, from its charset. The first plain and
+// HTML bodies become the message's text (later inline ones are appended,
+// as Apple Mail splits HTML around inline images); every other part, inline
+// images included, is kept as a `Part`. Encoded words in headers
+// (=?utf-8?B?...?=) and RFC 2231 file names are decoded.
+
+use std::collections::HashMap;
+
+use base64::Engine;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+const MAX_DEPTH: usize = 10;
+
+static ENCODED_WORD: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"=\?([^?\s]+)\?([bBqQ])\?([^?\s]*)\?=").unwrap());
+/// Whitespace between two encoded words doesn't count.
+static BETWEEN_WORDS: Lazy<Regex> = Lazy::new(|| Regex::new(r"\?=\s+=\?").unwrap());
+
+pub struct Message {
+    pub subject: Option<String>,
+    pub from: Option<String>,
+    pub text: Option<String>,
+    pub html: Option<String>,
+    /// Attachments and inline images.
+    pub parts: Vec<Part>,
+}
+
+pub struct Part {
+    /// Lowercase, e.g. "image/png".
+    pub content_type: String,
+    pub filename: Option<String>,
+    /// Without the angle brackets; what `cid:` URLs refer to.
+    pub content_id: Option<String>,
+    pub data: Vec<u8>,
+}
+
+// ── Headers ────────────────────────────────────────────────────────────────
+type Headers = Vec<(String, String)>;
+
+/// Unfolded headers (names lowercased) and the body after them.
+fn split(raw: &[u8]) -> (Headers, &[u8]) {
+    let (head, body) = match find(raw, b"\r\n\r\n") {
+        Some(at) => (&raw[..at], &raw[at + 4..]),
+        None => match find(raw, b"\n\n") {
+            Some(at) => (&raw[..at], &raw[at + 2..]),
+            None => (raw, &raw[raw.len()..]),
+        },
+    };
+    let mut headers: Headers = Vec::new();
+    for line in String::from_utf8_lossy(head).lines() {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+        }
+    }
+    (headers, body)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn header<'a>(headers: &'a Headers, name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.as_str())
+}
+
+/// Text in `charset`, UTF-8 when it is missing or unknown.
+pub fn decode_charset(bytes: &[u8], charset: Option<&str>) -> String {
+    let encoding = charset
+        .and_then(|c| encoding_rs::Encoding::for_label(c.trim().as_bytes()))
+        .unwrap_or(encoding_rs::UTF_8);
+    encoding.decode(bytes).0.into_owned()
+}
+
+/// A header value with its encoded words decoded.
+pub fn decode_words(value: &str) -> String {
+    let value = BETWEEN_WORDS.replace_all(value, "?==?");
+    ENCODED_WORD
+        .replace_all(&value, |caps: &Captures| {
+            let bytes = if caps[2].eq_ignore_ascii_case("b") {
+                base64::engine::general_purpose::STANDARD_NO_PAD
+                    .decode(caps[3].trim_end_matches('='))
+                    .ok()
+            } else {
+                Some(quoted_printable(caps[3].replace('_', " ").as_bytes()))
+            };
+            // RFC 2231 allows a language after the charset: utf-8*en.
+            let charset = caps[1].split('*').next();
+            match bytes {
+                Some(bytes) => decode_charset(&bytes, charset),
+                None => caps[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+/// `type/subtype; name=value; ...` as the lowercased first part and its
+/// parameters, RFC 2231 continuations and charsets included.
+fn parameters(value: &str) -> (String, HashMap<String, String>) {
+    let mut parts = split_unquoted(value, ';').into_iter();
+    let main = parts.next().unwrap_or_default().trim().to_lowercase();
+    let mut params = HashMap::new();
+    // name* pieces by name, in order of their index.
+    let mut extended: HashMap<String, Vec<(usize, bool, String)>> = HashMap::new();
+    for part in parts {
+        let Some((name, value)) = part.split_once('=') else {
+            continue;
+        };
+        let name = name.trim().to_lowercase();
+        let value = value.trim();
+        let value = match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+            Some(quoted) => quoted.replace("\\\"", "\"").replace("\\\\", "\\"),
+            None => value.to_string(),
+        };
+        match name.split_once('*') {
+            Some((base, rest)) => {
+                let encoded = rest.ends_with('*') || rest.is_empty();
+                let index = rest.trim_end_matches('*').parse().unwrap_or(0);
+                extended
+                    .entry(base.to_string())
+                    .or_default()
+                    .push((index, encoded, value));
+            }
+            None => {
+                params.insert(name, value);
+            }
+        }
+    }
+    for (name, mut pieces) in extended {
+        pieces.sort_by_key(|(index, _, _)| *index);
+        let mut charset = None;
+        let mut bytes = Vec::new();
+        for (index, encoded, value) in pieces {
+            if !encoded {
+                bytes.extend_from_slice(value.as_bytes());
+                continue;
+            }
+            let mut value = value.as_str();
+            if index == 0 {
+                // charset'language'text
+                let mut fields = value.splitn(3, '\'');
+                if let (Some(cs), Some(_), Some(text)) =
+                    (fields.next(), fields.next(), fields.next())
+                {
+                    charset = Some(cs.to_string()).filter(|c| !c.is_empty());
+                    value = text;
+                }
+            }
+            bytes.extend(percent_decode(value));
+        }
+        params.insert(name, decode_charset(&bytes, charset.as_deref()));
+    }
+    (main, params)
+}
+
+fn split_unquoted(value: &str, separator: char) -> Vec<String> {
+    let mut out = vec![String::new()];
+    let mut quoted = false;
+    let mut escaped = false;
+    for c in value.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => {
+                out.push(String::new());
+                continue;
+            }
+            _ => {}
+        }
+        out.last_mut().unwrap().push(c);
+    }
+    out
+}
+
+fn percent_decode(value: &str) -> Vec<u8> {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Some(byte) = value
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    out
+}
+
+// ── Bodies ─────────────────────────────────────────────────────────────────
+fn quoted_printable(bytes: &[u8]) -> Vec<u8> {
+    let hex = |b: u8| (b as char).to_digit(16).map(|d| d as u8);
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'=' {
+            out.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+        match (bytes.get(i + 1), bytes.get(i + 2)) {
+            // Soft line break.
+            (Some(b'\r'), Some(b'\n')) => i += 3,
+            (Some(b'\n'), _) => i += 2,
+            (Some(&a), Some(&b)) if hex(a).is_some() && hex(b).is_some() => {
+                out.push((hex(a).unwrap() << 4) | hex(b).unwrap());
+                i += 3;
+            }
+            _ => {
+                out.push(b'=');
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+fn decode_body(body: &[u8], encoding: &str) -> Vec<u8> {
+    match encoding {
+        "base64" => {
+            let clean: Vec<u8> = body
+                .iter()
+                .copied()
+                .filter(|b| !b.is_ascii_whitespace())
+                .collect();
+            base64::engine::general_purpose::STANDARD
+                .decode(&clean)
+                .or_else(|_| {
+                    let trimmed: Vec<u8> = clean.into_iter().filter(|&b| b != b'=').collect();
+                    base64::engine::general_purpose::STANDARD_NO_PAD.decode(trimmed)
+                })
+                .unwrap_or_default()
+        }
+        "quoted-printable" => quoted_printable(body),
+        _ => body.to_vec(),
+    }
+}
+
+/// The parts of a multipart body, between its `--boundary` lines.
+fn multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{boundary}");
+    let mut parts = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut pos = 0;
+    while pos < body.len() {
+        let end = body[pos..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(body.len(), |i| pos + i + 1);
+        let mut line = &body[pos..end];
+        while let [rest @ .., last] = line {
+            if !last.is_ascii_whitespace() {
+                break;
+            }
+            line = rest;
+        }
+        if let Some(rest) = line.strip_prefix(delimiter.as_bytes()) {
+            if rest.is_empty() || rest == b"--" {
+                if let Some(start) = start {
+                    // The line break before a delimiter belongs to it.
+                    let mut stop = pos;
+                    if stop > start && body[stop - 1] == b'\n' {
+                        stop -= 1;
+                    }
+                    if stop > start && body[stop - 1] == b'\r' {
+                        stop -= 1;
+                    }
+                    parts.push(&body[start..stop]);
+                }
+                if rest == b"--" {
+                    return parts;
+                }
+                start = Some(end);
+            }
+        }
+        pos = end;
+    }
+    // No closing delimiter: the last part runs to the end.
+    parts.extend(start.map(|start| &body[start..]));
+    parts
+}
+
+fn append(field: &mut Option<String>, text: String) {
+    match field {
+        Some(existing) => {
+            existing.push_str("\n\n");
+            existing.push_str(&text);
+        }
+        None => *field = Some(text),
+    }
+}
+
+fn walk(raw: &[u8], message: &mut Message, depth: usize) {
+    let (headers, body) = split(raw);
+    let (kind, params) = parameters(header(&headers, "content-type").unwrap_or("text/plain"));
+    if kind.starts_with("multipart/") && depth < MAX_DEPTH {
+        if let Some(boundary) = params.get("boundary") {
+            for part in multipart(body, boundary) {
+                walk(part, message, depth + 1);
+            }
+            return;
+        }
+    }
+    let encoding = header(&headers, "content-transfer-encoding")
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase();
+    let (disposition, disposition_params) =
+        parameters(header(&headers, "content-disposition").unwrap_or_default());
+    let filename = disposition_params
+        .get("filename")
+        .or_else(|| params.get("name"))
+        .map(|name| decode_words(name))
+        .filter(|name| !name.trim().is_empty());
+    let data = decode_body(body, &encoding);
+    let attachment = disposition == "attachment";
+    let charset = params.get("charset").map(String::as_str);
+    match kind.as_str() {
+        "text/plain" if !attachment => append(&mut message.text, decode_charset(&data, charset)),
+        "text/html" if !attachment => append(&mut message.html, decode_charset(&data, charset)),
+        _ if data.is_empty() => {}
+        _ => message.parts.push(Part {
+            content_type: kind,
+            filename,
+            content_id: header(&headers, "content-id").map(|id| {
+                id.trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            }),
+            data,
+        }),
+    }
+}
+
+pub fn parse(raw: &[u8]) -> Message {
+    let (headers, _) = split(raw);
+    let text = |name| {
+        header(&headers, name)
+            .map(decode_words)
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let mut message = Message {
+        subject: text("subject"),
+        from: text("from"),
+        text: None,
+        html: None,
+        parts: Vec::new(),
+    };
+    walk(raw, &mut message, 0);
+    message
+}
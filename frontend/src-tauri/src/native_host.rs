@@ -0,0 +1,602 @@
+// Native messaging — the host a companion browser extension talks to.
+//
+// Chrome, Edge, Brave, Vivaldi and Firefox start a native messaging host as
+// a child process and exchange JSON with it over stdio, each message
+// prefixed with its length as a native-endian u32. The app binary is its
+// own host: `run` checks `launched_by_browser` first and, when a browser
+// started it, serves stdio instead of opening a window.
+//
+// The host holds no state: it relays each message to the running app,
+// starting it if needed (see instance.rs).
+//
+// Messages are objects with a "type":
+//   ping       {}                                  -> {"ok": true, "version"}
+//   selection  {text, html?, url?, title?}         selected text, HTML kept as Markdown
+//   url        {url, title?}                       a link snippet, as bookmarks make
+//   page       {url, html?, title?}                the page's article (see clipper.rs);
+//                                                  without html it is fetched
+// plus optional `tags` and `collection`. Replies are {"ok": true, "id",
+// "title"}, {"ok": true, "queued": true} while the backend is down, or
+// {"ok": false, "error"}.
+//
+// Browsers find the host through a manifest naming this binary and the
+// extensions allowed to use it: a file in each browser's
+// NativeMessagingHosts folder on Linux and macOS, a registry key pointing at
+// a file in app_dir() on Windows. Manifests are rewritten at startup, as
+// the binary may have moved with an update, and removed when turned off.
+
+use std::ffi::OsStr;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::AppHandle;
+
+use crate::capture::{self, Capture};
+use crate::instance::{self, failure};
+use crate::{backend, bookmarks, clipper, config};
+
+/// Also the manifest's file name, without ".json".
+const HOST_NAME: &str = "com.pinupai.app";
+const DESCRIPTION: &str = "Pin-Up AI";
+/// Browsers send at most 64 MiB; a full page can come close.
+const MAX_MESSAGE: usize = 64 * 1024 * 1024;
+/// Browsers accept at most 1 MiB from a host.
+const MAX_REPLY: usize = 1024 * 1024;
+const SOURCE: &str = "browser";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct NativeHostConfig {
+    pub enabled: bool,
+    /// Chrome, Edge, Brave and Vivaldi extension IDs allowed to connect.
+    pub chrome_extensions: Vec<String>,
+    /// Firefox add-on IDs allowed to connect.
+    pub firefox_extensions: Vec<String>,
+}
+
+impl Default for NativeHostConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            chrome_extensions: Vec::new(),
+            firefox_extensions: vec!["browser@pinupai.app".into()],
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BrowserStatus {
+    name: &'static str,
+    /// The browser looks installed for this user.
+    installed: bool,
+    registered: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct NativeHostStatus {
+    #[serde(flatten)]
+    config: NativeHostConfig,
+    host_name: &'static str,
+    listening: bool,
+    browsers: Vec<BrowserStatus>,
+    /// RFC 3339.
+    last_message: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Message {
+    #[serde(rename = "type")]
+    kind: String,
+    text: Option<String>,
+    html: Option<String>,
+    url: Option<String>,
+    title: Option<String>,
+    tags: Vec<String>,
+    collection: Option<String>,
+}
+
+static LAST_MESSAGE: Mutex<Option<String>> = Mutex::new(None);
+static ERROR: Mutex<Option<String>> = Mutex::new(None);
+
+// ── Host ───────────────────────────────────────────────────────────────────
+/// Whether a browser started this process as its native messaging host.
+/// Chrome passes the calling extension's origin; Firefox passes the path of
+/// our manifest, then the add-on's ID.
+pub fn launched_by_browser() -> bool {
+    let manifest = format!("{HOST_NAME}.json");
+    let mut args = std::env::args_os().skip(1);
+    args.next().is_some_and(|first| {
+        first.to_string_lossy().starts_with("chrome-extension://")
+            || Path::new(&first).file_name() == Some(OsStr::new(&manifest))
+    })
+}
+
+/// One message from the browser; None once it closes the pipe.
+fn read_message(input: &mut impl Read) -> std::io::Result<Option<Result<Vec<u8>, String>>> {
+    let mut len = [0u8; 4];
+    match input.read_exact(&mut len) {
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        result => result?,
+    }
+    let len = u32::from_ne_bytes(len) as usize;
+    if len > MAX_MESSAGE {
+        std::io::copy(&mut input.take(len as u64), &mut std::io::sink())?;
+        return Ok(Some(Err(format!("Message over {MAX_MESSAGE} bytes"))));
+    }
+    let mut message = vec![0; len];
+    input.read_exact(&mut message)?;
+    Ok(Some(Ok(message)))
+}
+
+fn write_message(output: &mut impl Write, reply: &Value) -> std::io::Result<()> {
+    let mut bytes = serde_json::to_vec(reply)?;
+    if bytes.len() > MAX_REPLY {
+        bytes = serde_json::to_vec(&failure("Reply too large"))?;
+    }
+    output.write_all(&(bytes.len() as u32).to_ne_bytes())?;
+    output.write_all(&bytes)?;
+    output.flush()
+}
+
+/// Serves the browser on stdio until it disconnects.
+pub fn serve_stdio() {
+    let mut input = std::io::stdin().lock();
+    let mut output = std::io::stdout().lock();
+    let mut launched = false;
+    loop {
+        let reply = match read_message(&mut input) {
+            Ok(None) | Err(_) => return,
+            Ok(Some(Err(e))) => failure(e),
+            Ok(Some(Ok(bytes))) => match serde_json::from_slice(&bytes) {
+                Ok(message) => {
+                    instance::relay("native_host", message, &mut launched).unwrap_or_else(failure)
+                }
+                Err(e) => failure(format!("Invalid message: {e}")),
+            },
+        };
+        if write_message(&mut output, &reply).is_err() {
+            return;
+        }
+    }
+}
+
+// ── App side ───────────────────────────────────────────────────────────────
+fn filing(message: &Message, capture: Capture) -> Capture {
+    Capture {
+        tags: message
+            .tags
+            .iter()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect(),
+        collections: message
+            .collection
+            .iter()
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect(),
+        source: SOURCE.into(),
+        ..capture
+    }
+}
+
+fn web_url(url: Option<&str>) -> Result<Url, String> {
+    let url = url
+        .map(str::trim)
+        .filter(|u| !u.is_empty())
+        .ok_or("Missing url")?;
+    let parsed = Url::parse(url).map_err(|e| format!("Invalid URL: {e}"))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("Only web pages can be clipped".into());
+    }
+    Ok(parsed)
+}
+
+async fn to_capture(app: &AppHandle, message: &Message) -> Result<Capture, String> {
+    let title = message
+        .title
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string);
+    let capture = match message.kind.as_str() {
+        "selection" => {
+            let base = message.url.as_deref().and_then(|u| Url::parse(u).ok());
+            let html = match (&message.html, &base) {
+                (Some(html), Some(base)) if !html.trim().is_empty() => {
+                    clipper::fragment(app, html, base).await?
+                }
+                _ => String::new(),
+            };
+            let body = if html.trim().is_empty() {
+                message.text.clone().unwrap_or_default()
+            } else {
+                html
+            };
+            Capture {
+                title,
+                body,
+                source_url: base.map(String::from),
+                ..Default::default()
+            }
+        }
+        "url" => {
+            let url = web_url(message.url.as_deref())?;
+            let title = title.unwrap_or_else(|| url.to_string());
+            Capture {
+                body: bookmarks::link(&title, url.as_str()),
+                title: Some(title),
+                source_url: Some(url.into()),
+                ..Default::default()
+            }
+        }
+        "page" => {
+            let url = web_url(message.url.as_deref())?;
+            let article = match &message.html {
+                Some(html) if !html.trim().is_empty() => {
+                    clipper::clip_html(app, html, &url).await?
+                }
+                _ => clipper::clip(app, &url).await?,
+            };
+            Capture {
+                title: article.title.or(title),
+                body: article.markdown,
+                source_url: Some(url.into()),
+                ..Default::default()
+            }
+        }
+        "" => return Err("Message has no type".into()),
+        other => return Err(format!("Unknown message type {other:?}")),
+    };
+    Ok(filing(message, capture))
+}
+
+/// Answers a message relayed by the host.
+pub async fn handle(app: &AppHandle, message: Value) -> Value {
+    if !config::current(app).native_host.enabled {
+        return failure("Browser extensions are turned off in Pin-Up AI's settings");
+    }
+    let message: Message = match serde_json::from_value(message) {
+        Ok(message) => message,
+        Err(e) => return failure(format!("Invalid message: {e}")),
+    };
+    if message.kind == "ping" {
+        return json!({ "ok": true, "version": app.package_info().version.to_string() });
+    }
+    let capture = match to_capture(app, &message).await {
+        Ok(capture) => capture,
+        Err(e) => return failure(e),
+    };
+    *LAST_MESSAGE.lock().unwrap() = Some(chrono::Utc::now().to_rfc3339());
+    match capture::submit(app, capture).await {
+        Ok(snippet) => json!({ "ok": true, "id": snippet["id"], "title": snippet["title"] }),
+        // Queued in the outbox, so it is filed once the backend is back.
+        Err(e) if backend::is_unreachable(&e) => json!({ "ok": true, "queued": true }),
+        Err(e) => {
+            log::warn!("Native messaging: {} failed: {}", message.kind, e);
+            failure(e)
+        }
+    }
+}
+
+// ── Manifests ──────────────────────────────────────────────────────────────
+struct Browser {
+    name: &'static str,
+    firefox: bool,
+    /// The NativeMessagingHosts folder under the home directory; on
+    /// Windows, the registry key under HKCU.
+    location: &'static str,
+}
+
+const fn browser(name: &'static str, firefox: bool, location: &'static str) -> Browser {
+    Browser {
+        name,
+        firefox,
+        location,
+    }
+}
+
+#[cfg(target_os = "linux")]
+const BROWSERS: &[Browser] = &[
+    browser(
+        "Chrome",
+        false,
+        ".config/google-chrome/NativeMessagingHosts",
+    ),
+    browser("Chromium", false, ".config/chromium/NativeMessagingHosts"),
+    browser("Edge", false, ".config/microsoft-edge/NativeMessagingHosts"),
+    browser(
+        "Brave",
+        false,
+        ".config/BraveSoftware/Brave-Browser/NativeMessagingHosts",
+    ),
+    browser("Vivaldi", false, ".config/vivaldi/NativeMessagingHosts"),
+    browser("Firefox", true, ".mozilla/native-messaging-hosts"),
+];
+
+#[cfg(target_os = "macos")]
+const BROWSERS: &[Browser] = &[
+    browser(
+        "Chrome",
+        false,
+        "Library/Application Support/Google/Chrome/NativeMessagingHosts",
+    ),
+    browser(
+        "Chromium",
+        false,
+        "Library/Application Support/Chromium/NativeMessagingHosts",
+    ),
+    browser(
+        "Edge",
+        false,
+        "Library/Application Support/Microsoft Edge/NativeMessagingHosts",
+    ),
+    browser(
+        "Brave",
+        false,
+        "Library/Application Support/BraveSoftware/Brave-Browser/NativeMessagingHosts",
+    ),
+    browser(
+        "Vivaldi",
+        false,
+        "Library/Application Support/Vivaldi/NativeMessagingHosts",
+    ),
+    browser(
+        "Firefox",
+        true,
+        "Library/Application Support/Mozilla/NativeMessagingHosts",
+    ),
+];
+
+// Brave and Vivaldi read Chrome's key.
+#[cfg(target_os = "windows")]
+const BROWSERS: &[Browser] = &[
+    browser(
+        "Chrome",
+        false,
+        r"Software\Google\Chrome\NativeMessagingHosts",
+    ),
+    browser("Chromium", false, r"Software\Chromium\NativeMessagingHosts"),
+    browser(
+        "Edge",
+        false,
+        r"Software\Microsoft\Edge\NativeMessagingHosts",
+    ),
+    browser("Firefox", true, r"Software\Mozilla\NativeMessagingHosts"),
+];
+
+fn manifest(cfg: &NativeHostConfig, firefox: bool) -> Result<Option<String>, String> {
+    let (key, allowed): (&str, Vec<String>) = if firefox {
+        ("allowed_extensions", cfg.firefox_extensions.clone())
+    } else {
+        let origins = cfg
+            .chrome_extensions
+            .iter()
+            .map(|id| format!("chrome-extension://{id}/"))
+            .collect();
+        ("allowed_origins", origins)
+    };
+    if allowed.is_empty() {
+        return Ok(None);
+    }
+    let mut manifest = json!({
+        "name": HOST_NAME,
+        "description": DESCRIPTION,
+        "path": instance::exe_path()?,
+        "type": "stdio",
+    });
+    manifest[key] = json!(allowed);
+    serde_json::to_string_pretty(&manifest)
+        .map(Some)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(unix)]
+fn manifest_path(browser: &Browser) -> Option<PathBuf> {
+    Some(
+        dirs::home_dir()?
+            .join(browser.location)
+            .join(format!("{HOST_NAME}.json")),
+    )
+}
+
+#[cfg(unix)]
+fn installed(browser: &Browser) -> bool {
+    manifest_path(browser)
+        .and_then(|path| path.parent()?.parent().map(Path::is_dir))
+        .unwrap_or(false)
+}
+
+#[cfg(unix)]
+fn registered(browser: &Browser) -> bool {
+    manifest_path(browser).is_some_and(|path| path.is_file())
+}
+
+#[cfg(unix)]
+fn register(browser: &Browser, manifest: &str) -> Result<(), String> {
+    let path = manifest_path(browser).ok_or("No home directory")?;
+    instance::write_if_changed(&path, manifest).map(drop)
+}
+
+#[cfg(unix)]
+fn unregister(browser: &Browser) -> Result<(), String> {
+    match manifest_path(browser) {
+        Some(path) if path.is_file() => std::fs::remove_file(&path)
+            .map_err(|e| format!("Could not remove {}: {e}", path.display())),
+        _ => Ok(()),
+    }
+}
+
+/// The manifest the registry points at; Chrome-family browsers share one.
+#[cfg(windows)]
+fn manifest_path(browser: &Browser) -> PathBuf {
+    let family = if browser.firefox { "firefox" } else { "chrome" };
+    crate::app_dir()
+        .join("native-host")
+        .join(family)
+        .join(format!("{HOST_NAME}.json"))
+}
+
+#[cfg(windows)]
+fn key(browser: &Browser) -> String {
+    format!(r"HKCU\{}\{HOST_NAME}", browser.location)
+}
+
+#[cfg(windows)]
+fn installed(_browser: &Browser) -> bool {
+    true
+}
+
+#[cfg(windows)]
+fn registered(browser: &Browser) -> bool {
+    instance::reg(&["query", &key(browser), "/ve"]).is_ok()
+}
+
+#[cfg(windows)]
+fn register(browser: &Browser, manifest: &str) -> Result<(), String> {
+    let path = manifest_path(browser);
+    instance::write_if_changed(&path, manifest)?;
+    let path = path.to_string_lossy();
+    instance::reg(&[
+        "add",
+        &key(browser),
+        "/ve",
+        "/t",
+        "REG_SZ",
+        "/d",
+        &path,
+        "/f",
+    ])
+    .map(drop)
+}
+
+#[cfg(windows)]
+fn unregister(browser: &Browser) -> Result<(), String> {
+    if registered(browser) {
+        instance::reg(&["delete", &key(browser), "/f"])?;
+    }
+    std::fs::remove_file(manifest_path(browser)).ok();
+    Ok(())
+}
+
+/// Brings every browser's registration in line with the settings.
+fn sync_manifests(cfg: &NativeHostConfig) -> Result<(), String> {
+    let chrome = manifest(cfg, false)?;
+    let firefox = manifest(cfg, true)?;
+    let mut errors = Vec::new();
+    for browser in BROWSERS {
+        let manifest = if browser.firefox { &firefox } else { &chrome };
+        let result = match manifest {
+            Some(manifest) if cfg.enabled && installed(browser) => register(browser, manifest),
+            _ => unregister(browser),
+        };
+        if let Err(e) = result {
+            errors.push(format!("{}: {e}", browser.name));
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+async fn apply(cfg: NativeHostConfig) -> Result<(), String> {
+    let result = tauri::async_runtime::spawn_blocking(move || sync_manifests(&cfg))
+        .await
+        .map_err(|e| e.to_string())?;
+    *ERROR.lock().unwrap() = result.as_ref().err().cloned();
+    result
+}
+
+async fn status(app: &AppHandle) -> Result<NativeHostStatus, String> {
+    let browsers = tauri::async_runtime::spawn_blocking(|| {
+        BROWSERS
+            .iter()
+            .map(|browser| BrowserStatus {
+                name: browser.name,
+                installed: installed(browser),
+                registered: registered(browser),
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(NativeHostStatus {
+        config: config::current(app).native_host,
+        host_name: HOST_NAME,
+        listening: instance::listening(),
+        browsers,
+        last_message: LAST_MESSAGE.lock().unwrap().clone(),
+        error: ERROR.lock().unwrap().clone(),
+    })
+}
+
+/// Registers the manifests, or removes them when turned off.
+pub fn start(app: &AppHandle) {
+    let cfg = config::current(app).native_host;
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = apply(cfg).await {
+            log::warn!("Native messaging: {}", e);
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub async fn get_native_host_status(app: AppHandle) -> Result<NativeHostStatus, String> {
+    status(&app).await
+}
+
+/// Turns native messaging on or off and sets which extensions may use it.
+#[tauri::command]
+pub async fn set_native_host(
+    app: AppHandle,
+    enabled: bool,
+    chrome_extensions: Option<Vec<String>>,
+    firefox_extensions: Option<Vec<String>>,
+) -> Result<NativeHostStatus, String> {
+    let clean = |ids: Vec<String>| -> Vec<String> {
+        let mut ids: Vec<String> = ids
+            .iter()
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty())
+            .collect();
+        ids.dedup();
+        ids
+    };
+    let chrome_extensions = chrome_extensions.map(clean);
+    let firefox_extensions = firefox_extensions.map(clean);
+    // Chrome IDs are 32 letters from a to p.
+    if let Some(bad) = chrome_extensions
+        .iter()
+        .flatten()
+        .find(|id| id.len() != 32 || !id.bytes().all(|b| (b'a'..=b'p').contains(&b)))
+    {
+        return Err(format!("{bad:?} is not a Chrome extension ID"));
+    }
+    if let Some(bad) = firefox_extensions
+        .iter()
+        .flatten()
+        .find(|id| id.contains(['"', '\\']) || id.chars().any(char::is_whitespace))
+    {
+        return Err(format!("{bad:?} is not a Firefox add-on ID"));
+    }
+    config::update(&app, |c| {
+        c.native_host.enabled = enabled;
+        if let Some(ids) = chrome_extensions {
+            c.native_host.chrome_extensions = ids;
+        }
+        if let Some(ids) = firefox_extensions {
+            c.native_host.firefox_extensions = ids;
+        }
+    })?;
+    apply(config::current(&app).native_host).await?;
+    status(&app).await
+}
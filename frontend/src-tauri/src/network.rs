@@ -0,0 +1,126 @@
+// Network — online/offline detection.
+//
+// Every `network.interval_secs` (slowed by the performance and power poll
+// factors) the monitor opens a TCP connection to each of
+// `network.probe_hosts` until one succeeds; any success counts as online.
+// Transitions are emitted as "network-online" / "network-offline" and
+// posted to the backend's /network endpoint, which is also told again after
+// the backend restarts; the backend holds off license revalidation while
+// offline instead of waiting out the request timeout.
+
+use std::net::ToSocketAddrs;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Manager};
+
+use crate::{backend, config, performance};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct NetworkConfig {
+    pub interval_secs: u64,
+    /// host:port pairs reachable from any normal connection.
+    pub probe_hosts: Vec<String>,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 15,
+            probe_hosts: vec!["1.1.1.1:443".into(), "8.8.8.8:443".into()],
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct NetworkStatus {
+    online: bool,
+    /// Probe that answered, if any.
+    via: Option<String>,
+    /// RFC 3339 time of the last transition.
+    since: String,
+}
+
+static STATUS: Mutex<Option<NetworkStatus>> = Mutex::new(None);
+/// Backend port that last received the online flag.
+static NOTIFIED_PORT: AtomicU16 = AtomicU16::new(0);
+
+fn probe(hosts: &[String]) -> Option<String> {
+    hosts.iter().find_map(|host| {
+        let addr = host.to_socket_addrs().ok()?.next()?;
+        std::net::TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)
+            .ok()
+            .map(|_| host.clone())
+    })
+}
+
+async fn notify_backend(online: bool, changed: bool) {
+    let port = crate::BACKEND_PORT.load(Ordering::SeqCst);
+    if port == 0 || (!changed && NOTIFIED_PORT.load(Ordering::SeqCst) == port) {
+        return;
+    }
+    match backend::post("/network", &json!({ "online": online })).await {
+        Ok(_) => NOTIFIED_PORT.store(port, Ordering::SeqCst),
+        Err(e) => log::debug!("Could not send network state to backend: {}", e),
+    }
+}
+
+async fn check(app: &AppHandle) -> NetworkStatus {
+    let hosts = config::current(app).network.probe_hosts;
+    let via = tauri::async_runtime::spawn_blocking(move || probe(&hosts))
+        .await
+        .unwrap_or_default();
+    let online = via.is_some();
+
+    let (status, changed) = {
+        let mut current = STATUS.lock().unwrap();
+        let changed = current.as_ref().map(|s| s.online) != Some(online);
+        let since = match current.as_ref() {
+            Some(s) if !changed => s.since.clone(),
+            _ => chrono::Local::now().to_rfc3339(),
+        };
+        let status = NetworkStatus {
+            online,
+            via,
+            since,
+        };
+        *current = Some(status.clone());
+        (status, changed)
+    };
+    if changed {
+        log::info!("Network {}", if online { "online" } else { "offline" });
+        let event = if online {
+            "network-online"
+        } else {
+            "network-offline"
+        };
+        app.emit_all(event, &status).ok();
+    }
+    notify_backend(online, changed).await;
+    status
+}
+
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            check(&handle).await;
+            let interval = config::current(&handle).network.interval_secs.max(5);
+            let factor = performance::capabilities(&handle).poll_factor;
+            tokio::time::sleep(Duration::from_secs(interval * factor)).await;
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Probes now instead of returning the monitor's last result.
+#[tauri::command]
+pub async fn get_network_status(app: AppHandle) -> NetworkStatus {
+    check(&app).await
+}
@@ -0,0 +1,436 @@
+// Notifications — native notifications when background jobs finish.
+//
+// Jobs outlive a hidden window. Each job that finishes in the registry (see
+// jobs.rs) is announced: imports and exports (watched folders excepted),
+// Ollama model pulls, and backend work the frontend relays with
+// `job-completed` (summaries, embedding backfills). Voice-note transcripts
+// get their own notification that opens the snippet (see whisper.rs); OCR
+// runs inline and isn't announced. A notification goes out only while the
+// main window isn't focused. Clicking it opens the snippet it names, or just
+// the window. While the OS is in Do Not Disturb / Focus (see focus.rs) they
+// are held back as `during_focus` says; queued ones come out when it ends,
+// at most MAX_AT_ONCE and a summary of the rest.
+//
+// Tauri's own notifications can't report clicks, so each platform is driven
+// directly: NSUserNotificationCenter on macOS (it needs the app bundle; a
+// bare binary gets none), toasts on Windows under the installer's
+// AppUserModelID (PowerShell's for dev builds, as Tauri does) and the
+// freedesktop Notifications service on Linux.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::focus::{self, FocusAction};
+use crate::jobs::JobStatus;
+use crate::tasks::TaskState;
+use crate::{badge, config, i18n, tray};
+
+/// Clicks on notifications older than this many are ignored.
+const MAX_PENDING: usize = 50;
+const MAX_AT_ONCE: usize = 3;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    pub enabled: bool,
+    pub during_focus: FocusAction,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            during_focus: FocusAction::Queue,
+        }
+    }
+}
+
+/// A notification held back until focus ends.
+struct Held {
+    title: String,
+    body: String,
+    snippet: Option<String>,
+}
+
+static APP: OnceCell<AppHandle> = OnceCell::new();
+static NEXT: AtomicU64 = AtomicU64::new(1);
+/// What each shown notification opens, by key; None is the main window.
+static TARGETS: Lazy<Mutex<HashMap<u64, Option<String>>>> = Lazy::new(Default::default);
+static QUEUED: Mutex<Vec<Held>> = Mutex::new(Vec::new());
+
+/// A notification was clicked.
+fn activated(key: u64) {
+    let Some(app) = APP.get() else {
+        return;
+    };
+    match TARGETS.lock().unwrap().remove(&key) {
+        Some(Some(snippet)) => tray::open_snippet(app, &snippet),
+        Some(None) => tray::menu_action(app, "open"),
+        None => {}
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use std::ffi::{CStr, CString};
+    use std::os::raw::c_char;
+
+    use objc::declare::ClassDecl;
+    use objc::runtime::{Object, Sel, BOOL, YES};
+    use objc::{class, msg_send, sel, sel_impl};
+    use tauri::AppHandle;
+
+    unsafe fn ns_string(value: &str) -> *mut Object {
+        let c = CString::new(value).unwrap_or_default();
+        msg_send![class!(NSString), stringWithUTF8String: c.as_ptr()]
+    }
+
+    unsafe fn center() -> *mut Object {
+        msg_send![
+            class!(NSUserNotificationCenter),
+            defaultUserNotificationCenter
+        ]
+    }
+
+    /// `- userNotificationCenter:didActivateNotification:`
+    extern "C" fn did_activate(
+        _this: &Object,
+        _cmd: Sel,
+        center: *mut Object,
+        notification: *mut Object,
+    ) {
+        unsafe {
+            let identifier: *mut Object = msg_send![notification, identifier];
+            if !identifier.is_null() {
+                let utf8: *const c_char = msg_send![identifier, UTF8String];
+                if let Ok(key) = CStr::from_ptr(utf8).to_string_lossy().parse() {
+                    super::activated(key);
+                }
+            }
+            let _: () = msg_send![center, removeDeliveredNotification: notification];
+        }
+    }
+
+    /// `- userNotificationCenter:shouldPresentNotification:`; banners
+    /// show even while the app is active.
+    extern "C" fn should_present(
+        _this: &Object,
+        _cmd: Sel,
+        _center: *mut Object,
+        _notification: *mut Object,
+    ) -> BOOL {
+        YES
+    }
+
+    /// Sets the center's delegate; runs on the main thread during setup.
+    pub fn start(_app: &AppHandle) {
+        let Some(mut decl) = ClassDecl::new("PinUpNotificationDelegate", class!(NSObject)) else {
+            return;
+        };
+        unsafe {
+            decl.add_method(
+                sel!(userNotificationCenter:didActivateNotification:),
+                did_activate as extern "C" fn(&Object, Sel, *mut Object, *mut Object),
+            );
+            decl.add_method(
+                sel!(userNotificationCenter:shouldPresentNotification:),
+                should_present as extern "C" fn(&Object, Sel, *mut Object, *mut Object) -> BOOL,
+            );
+            let center = center();
+            if center.is_null() {
+                log::info!("Notifications: unavailable outside the app bundle");
+                return;
+            }
+            let delegate: *mut Object = msg_send![decl.register(), new];
+            let _: () = msg_send![center, setDelegate: delegate];
+        }
+    }
+
+    pub fn show(app: &AppHandle, key: u64, title: String, body: String) -> Result<(), String> {
+        app.run_on_main_thread(move || unsafe {
+            let center = center();
+            if center.is_null() {
+                return;
+            }
+            let notification: *mut Object = msg_send![class!(NSUserNotification), new];
+            let _: () = msg_send![notification, setTitle: ns_string(&title)];
+            let _: () = msg_send![notification, setInformativeText: ns_string(&body)];
+            let _: () = msg_send![notification, setIdentifier: ns_string(&key.to_string())];
+            let _: () = msg_send![center, deliverNotification: notification];
+            let _: () = msg_send![notification, release];
+        })
+        .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use std::path::MAIN_SEPARATOR as SEP;
+
+    use tauri::AppHandle;
+    use windows::core::HSTRING;
+    use windows::Data::Xml::Dom::XmlDocument;
+    use windows::Foundation::TypedEventHandler;
+    use windows::Win32::System::Com::{CoInitializeEx, COINIT_MULTITHREADED};
+    use windows::UI::Notifications::{ToastNotification, ToastNotificationManager};
+
+    /// Windows PowerShell's AppUserModelID, which any process may use.
+    const POWERSHELL_ID: &str =
+        r"{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\WindowsPowerShell\v1.0\powershell.exe";
+
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&apos;")
+    }
+
+    /// The installer registers the bundle identifier; dev builds have none.
+    fn app_id(app: &AppHandle) -> String {
+        let dev = std::env::current_exe().ok().and_then(|exe| {
+            let dir = exe.parent()?.display().to_string();
+            Some(
+                dir.ends_with(&format!("{SEP}target{SEP}debug"))
+                    || dir.ends_with(&format!("{SEP}target{SEP}release")),
+            )
+        });
+        if dev.unwrap_or(false) {
+            POWERSHELL_ID.into()
+        } else {
+            app.config().tauri.bundle.identifier.clone()
+        }
+    }
+
+    pub fn start(_app: &AppHandle) {}
+
+    pub fn show(app: &AppHandle, key: u64, title: String, body: String) -> Result<(), String> {
+        let app_id = app_id(app);
+        tauri::async_runtime::spawn_blocking(move || {
+            let result = (|| unsafe {
+                // Already-initialized is fine; we only need COM on this thread.
+                CoInitializeEx(None, COINIT_MULTITHREADED).ok();
+                let xml = XmlDocument::new()?;
+                xml.LoadXml(&HSTRING::from(format!(
+                    "<toast><visual><binding template=\"ToastGeneric\">\
+                     <text>{}</text><text>{}</text></binding></visual></toast>",
+                    escape(&title),
+                    escape(&body)
+                )))?;
+                let toast = ToastNotification::CreateToastNotification(&xml)?;
+                toast.Activated(&TypedEventHandler::new(move |_, _| {
+                    super::activated(key);
+                    Ok(())
+                }))?;
+                ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(app_id))?
+                    .Show(&toast)
+            })();
+            if let Err(e) = result {
+                log::warn!("Notifications: {}", e);
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use once_cell::sync::{Lazy, OnceCell};
+    use tauri::AppHandle;
+    use zbus::blocking::{Connection, Proxy};
+    use zbus::zvariant::Value;
+
+    use crate::i18n;
+
+    static BUS: OnceCell<Connection> = OnceCell::new();
+    /// The server's notification ids, to our keys.
+    static SHOWN: Lazy<Mutex<HashMap<u32, u64>>> = Lazy::new(Default::default);
+
+    fn proxy(bus: &Connection) -> zbus::Result<Proxy<'_>> {
+        Proxy::new(
+            bus,
+            "org.freedesktop.Notifications",
+            "/org/freedesktop/Notifications",
+            "org.freedesktop.Notifications",
+        )
+    }
+
+    fn listen() -> Result<(), String> {
+        let bus = Connection::session().map_err(|e| e.to_string())?;
+        let bus = BUS.get_or_init(|| bus);
+        let signals = proxy(bus)
+            .and_then(|p| p.receive_signal("ActionInvoked"))
+            .map_err(|e| e.to_string())?;
+        for message in signals {
+            let Ok((id, action)) = message.body::<(u32, String)>() else {
+                continue;
+            };
+            let key = SHOWN.lock().unwrap().remove(&id);
+            if let (Some(key), "default") = (key, action.as_str()) {
+                super::activated(key);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn start(_app: &AppHandle) {
+        std::thread::spawn(|| {
+            if let Err(e) = listen() {
+                log::info!("Notifications unavailable: {}", e);
+            }
+        });
+    }
+
+    pub fn show(_app: &AppHandle, key: u64, title: String, body: String) -> Result<(), String> {
+        let bus = BUS.get().ok_or("No session bus")?;
+        let hints: HashMap<&str, Value> = HashMap::new();
+        let id: u32 = proxy(bus)
+            .and_then(|p| {
+                p.call(
+                    "Notify",
+                    &(
+                        "Pin-Up AI",
+                        0u32,
+                        "",
+                        title,
+                        body,
+                        vec!["default".to_string(), i18n::t("notification.open")],
+                        hints,
+                        -1i32,
+                    ),
+                )
+            })
+            .map_err(|e| e.to_string())?;
+        SHOWN.lock().unwrap().insert(id, key);
+        Ok(())
+    }
+}
+
+/// Shows a notification unless they're off or the user is in the window.
+/// Clicking it opens `snippet`, or the main window.
+pub fn notify(app: &AppHandle, title: &str, body: &str, snippet: Option<&str>) {
+    if config::current(app).notifications.enabled && !badge::window_focused(app) {
+        deliver(app, title, body, snippet, false);
+    }
+}
+
+/// Shows a notification the user asked for (a reminder) whatever the
+/// settings but focus; the caller counts it on the badge.
+pub fn alert(app: &AppHandle, title: &str, body: &str, snippet: Option<&str>) {
+    deliver(app, title, body, snippet, true);
+}
+
+/// Shows a notification, or holds it back while focus is on. `counted`: it
+/// is on the badge already.
+fn deliver(app: &AppHandle, title: &str, body: &str, snippet: Option<&str>, counted: bool) {
+    if !focus::active() {
+        show(app, title, body, snippet);
+        return;
+    }
+    match config::current(app).notifications.during_focus {
+        FocusAction::Queue => {
+            let mut queued = QUEUED.lock().unwrap();
+            queued.push(Held {
+                title: title.to_string(),
+                body: body.to_string(),
+                snippet: snippet.map(str::to_string),
+            });
+            if queued.len() > MAX_PENDING {
+                queued.remove(0);
+            }
+        }
+        FocusAction::Badge if !counted => badge::arrived(app),
+        FocusAction::Badge | FocusAction::Suppress => {}
+    }
+}
+
+/// Shows what was queued while focus was on.
+pub fn focus_ended(app: &AppHandle) {
+    let queued = std::mem::take(&mut *QUEUED.lock().unwrap());
+    for held in queued.iter().take(MAX_AT_ONCE) {
+        show(app, &held.title, &held.body, held.snippet.as_deref());
+    }
+    match queued.len().saturating_sub(MAX_AT_ONCE) {
+        0 => {}
+        1 => show(app, &i18n::t("notification.more_one"), "", None),
+        n => show(
+            app,
+            &i18n::tf("notification.more_many", &[("count", &n)]),
+            "",
+            None,
+        ),
+    }
+}
+
+fn show(app: &AppHandle, title: &str, body: &str, snippet: Option<&str>) {
+    let key = NEXT.fetch_add(1, Ordering::SeqCst);
+    {
+        let mut targets = TARGETS.lock().unwrap();
+        targets.insert(key, snippet.map(str::to_string));
+        targets.retain(|k, _| k + MAX_PENDING as u64 > key);
+    }
+    if let Err(e) = imp::show(app, key, title.to_string(), body.to_string()) {
+        log::warn!("Notifications: {}", e);
+    }
+}
+
+/// Announces a finished job.
+pub fn job_finished(app: &AppHandle, job: &JobStatus) {
+    match (job.kind.as_str(), job.state) {
+        // Watched folders import on their own; that's not news.
+        ("import_watched" | "ocr" | "transcribe", _) => {}
+        ("backend", _) => notify(
+            app,
+            &job.label,
+            job.current.as_deref().unwrap_or_default(),
+            job.snippet_id.as_deref(),
+        ),
+        (_, TaskState::Done) => notify(
+            app,
+            &i18n::tf("notification.job_finished", &[("label", &job.label)]),
+            "",
+            None,
+        ),
+        (_, TaskState::Failed) => notify(
+            app,
+            &i18n::tf("notification.job_failed", &[("label", &job.label)]),
+            job.error.as_deref().unwrap_or_default(),
+            None,
+        ),
+        (_, TaskState::Running | TaskState::Cancelled) => {}
+    }
+}
+
+pub fn start(app: &AppHandle) {
+    if APP.set(app.clone()).is_ok() {
+        imp::start(app);
+    }
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn get_notification_settings(app: AppHandle) -> NotificationsConfig {
+    config::current(&app).notifications
+}
+
+#[tauri::command]
+pub fn set_notifications(app: AppHandle, enabled: bool) -> Result<NotificationsConfig, String> {
+    Ok(config::update(&app, |c| c.notifications.enabled = enabled)?.notifications)
+}
+
+/// What happens to notifications during Do Not Disturb / Focus.
+#[tauri::command]
+pub fn set_notifications_during_focus(
+    app: AppHandle,
+    action: FocusAction,
+) -> Result<NotificationsConfig, String> {
+    Ok(config::update(&app, |c| c.notifications.during_focus = action)?.notifications)
+}
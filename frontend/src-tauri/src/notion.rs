@@ -0,0 +1,537 @@
+// Notion — imports a Notion workspace export ZIP (Markdown or HTML + CSV).
+//
+// `import_notion_export(path, dry_run)` starts a task (see tasks.rs) that
+// walks the archive on a blocking thread and hands pages one at a time to
+// the async side, which submits each as a capture (source "notion") and
+// reports it as progress. Nothing is held beyond the page in flight; a
+// cancel stops after that page. With `dry_run` nothing is stored or sent and
+// the result lists what would be imported. Duplicates of existing snippets
+// are handled by the user's policy (see dedupe.rs), dry runs included.
+//
+// Mapping:
+//   page (.md/.html)       snippet; title from the file name minus Notion's id
+//   database (.csv)        tag on every row page in its folder, plus the
+//                          row's "Tags" column when there is one
+//   images/files linked    attachments, links rewritten to attachment:<hash>
+//   links to other pages   plain text (the page is imported on its own)
+//
+// Newer exports wrap the content in Part-N.zip files; those are unpacked to
+// the runtime dir first.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::Serialize;
+use tauri::AppHandle;
+use tokio::sync::mpsc;
+
+use crate::attachments;
+use crate::capture::Capture;
+use crate::dedupe::{Dedupe, DuplicateCounts};
+use crate::tasks::{self, Task};
+
+const MAX_PAGE_BYTES: u64 = 10 * 1024 * 1024;
+const MAX_ATTACHMENT_BYTES: u64 = 100 * 1024 * 1024;
+/// Dry runs list at most this many pages.
+const PREVIEW_LIMIT: usize = 500;
+
+static NOTION_ID: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s+[0-9a-f]{32}$").unwrap());
+static MD_LINK: Lazy<Regex> = Lazy::new(|| Regex::new(r"(!?)\[([^\]]*)\]\(([^)\s]+)\)").unwrap());
+static HTML_DROP: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?is)<(head|style|script)\b.*?</(head|style|script)>").unwrap());
+static HTML_IMG: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?is)<img\b[^>]*?\bsrc="([^"]*)"[^>]*>"#).unwrap());
+static HTML_LINK: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?is)<a\b[^>]*?\bhref="([^"]*)"[^>]*>(.*?)</a>"#).unwrap());
+static HTML_BLOCK: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)<(/?)(h[1-6]|p|div|ul|ol|li|br|table|tr|blockquote|pre|hr|figure)\b[^>]*>")
+        .unwrap()
+});
+static HTML_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<[^>]*>").unwrap());
+static BLANK_LINES: Lazy<Regex> = Lazy::new(|| Regex::new(r"\n[ \t]*(\n[ \t]*){2,}").unwrap());
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PreviewEntry {
+    title: String,
+    tags: Vec<String>,
+    attachments: usize,
+    chars: usize,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct NotionImport {
+    dry_run: bool,
+    pages: usize,
+    databases: Vec<String>,
+    imported: usize,
+    failed: usize,
+    attachments: usize,
+    duplicates: DuplicateCounts,
+    /// Dry run only: what would be imported.
+    entries: Vec<PreviewEntry>,
+}
+
+/// One page ready to send, or why it couldn't be read.
+struct Prepared {
+    index: usize,
+    total: usize,
+    entry: PreviewEntry,
+    result: Result<Capture, String>,
+}
+
+struct Database {
+    name: String,
+    /// Row title -> "Tags" column values.
+    tags: HashMap<String, Vec<String>>,
+}
+
+type Archive = zip::ZipArchive<std::fs::File>;
+
+// ── Parsing ────────────────────────────────────────────────────────────────
+/// "Meeting notes 0f1e…" -> "Meeting notes".
+fn clean_name(name: &str) -> String {
+    let stem = name.rsplit('/').next().unwrap_or(name);
+    let stem = stem.rsplit_once('.').map_or(stem, |(stem, _)| stem);
+    NOTION_ID.replace(stem, "").trim().to_string()
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Resolves a relative link against the page's folder inside the archive.
+fn resolve(dir: &str, target: &str) -> String {
+    let mut parts: Vec<&str> = dir.split('/').filter(|p| !p.is_empty()).collect();
+    let target = percent_decode(target.split(['#', '?']).next().unwrap_or(target));
+    for part in target.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+/// RFC 4180 records (quoted fields, doubled quotes, embedded newlines).
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', _) => quoted = !quoted,
+            (',', false) => row.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            (c, _) => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+fn read_database(name: &str, text: &str) -> Database {
+    let rows = parse_csv(text);
+    let tags_column = rows.first().and_then(|header| {
+        header
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case("tags"))
+    });
+    let mut tags = HashMap::new();
+    if let Some(column) = tags_column {
+        for row in rows.iter().skip(1) {
+            let (Some(title), Some(values)) = (row.first(), row.get(column)) else {
+                continue;
+            };
+            let values: Vec<String> = values
+                .split(',')
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect();
+            tags.insert(title.trim().to_string(), values);
+        }
+    }
+    Database {
+        name: name.to_string(),
+        tags,
+    }
+}
+
+pub fn decode_entities(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Good enough for Notion's HTML: headings, paragraphs, lists, links and
+/// images survive; other markup is dropped.
+pub fn html_to_markdown(html: &str) -> String {
+    let html = HTML_DROP.replace_all(html, "");
+    let html = HTML_IMG.replace_all(&html, "![]($1)");
+    let html = HTML_LINK.replace_all(&html, |caps: &Captures| {
+        let text = HTML_TAG.replace_all(&caps[2], "");
+        format!("[{}]({})", text.trim(), &caps[1])
+    });
+    let html = HTML_BLOCK.replace_all(&html, |caps: &Captures| {
+        let closing = !caps[1].is_empty();
+        match (caps[2].to_ascii_lowercase().as_str(), closing) {
+            ("br", _) => "\n".to_string(),
+            ("hr", _) => "\n\n---\n\n".to_string(),
+            ("li", false) => "\n- ".to_string(),
+            ("li", true) => String::new(),
+            (tag, false) if tag.starts_with('h') => {
+                let level = tag[1..].parse::<usize>().unwrap_or(1);
+                format!("\n\n{} ", "#".repeat(level))
+            }
+            ("blockquote", false) => "\n\n> ".to_string(),
+            ("pre", _) => "\n```\n".to_string(),
+            _ => "\n\n".to_string(),
+        }
+    });
+    let text = decode_entities(&HTML_TAG.replace_all(&html, ""));
+    BLANK_LINES.replace_all(&text, "\n\n").trim().to_string()
+}
+
+fn read_entry(zip: &mut Archive, name: &str, limit: u64) -> Result<Vec<u8>, String> {
+    let entry = zip
+        .by_name(name)
+        .map_err(|e| format!("Could not read {name}: {e}"))?;
+    if entry.size() > limit {
+        return Err(format!("{name} is larger than {} MB", limit >> 20));
+    }
+    let mut bytes = Vec::with_capacity(entry.size() as usize);
+    entry
+        .take(limit)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Could not read {name}: {e}"))?;
+    Ok(bytes)
+}
+
+fn is_page(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    lower.ends_with(".md") || lower.ends_with(".html")
+}
+
+/// Rewrites the links of one page: files become attachments (counted, and
+/// stored unless `dry_run`), links to pages or CSVs become their text.
+fn rewrite_links(
+    zip: &mut Archive,
+    names: &BTreeMap<String, usize>,
+    dir: &str,
+    body: &str,
+    dry_run: bool,
+    stored: &mut usize,
+) -> String {
+    MD_LINK
+        .replace_all(body, |caps: &Captures| {
+            let (bang, text, target) = (&caps[1], &caps[2], &caps[3]);
+            if target.contains("://") || target.starts_with('#') || target.starts_with("mailto:") {
+                return caps[0].to_string();
+            }
+            let name = resolve(dir, target);
+            if !names.contains_key(&name) {
+                return caps[0].to_string();
+            }
+            if is_page(&name) || name.to_lowercase().ends_with(".csv") {
+                return text.to_string();
+            }
+            *stored += 1;
+            if dry_run {
+                return caps[0].to_string();
+            }
+            let ext = name.rsplit_once('.').map(|(_, e)| e).unwrap_or_default();
+            let attachment = read_entry(zip, &name, MAX_ATTACHMENT_BYTES)
+                .and_then(|bytes| attachments::store_bytes(&bytes, ext, None));
+            match attachment {
+                Ok(a) => format!("{bang}[{text}](attachment:{})", a.hash),
+                Err(e) => {
+                    log::warn!("Notion import: {}", e);
+                    caps[0].to_string()
+                }
+            }
+        })
+        .into_owned()
+}
+
+fn prepare_page(
+    zip: &mut Archive,
+    names: &BTreeMap<String, usize>,
+    name: &str,
+    database: Option<&Database>,
+    dry_run: bool,
+    entry: &mut PreviewEntry,
+) -> Result<Capture, String> {
+    let bytes = read_entry(zip, name, MAX_PAGE_BYTES)?;
+    let text = String::from_utf8(bytes).map_err(|_| format!("{name} is not UTF-8"))?;
+    let mut body = if name.to_lowercase().ends_with(".html") {
+        html_to_markdown(&text)
+    } else {
+        text
+    };
+    // Notion repeats the title as the first heading.
+    if let Some(rest) = body.strip_prefix(&format!("# {}", entry.title)) {
+        body = rest.trim_start().to_string();
+    }
+    let dir = name.rsplit_once('/').map_or("", |(dir, _)| dir);
+    let body = rewrite_links(zip, names, dir, &body, dry_run, &mut entry.attachments);
+
+    if let Some(db) = database {
+        entry.tags.push(db.name.clone());
+        for tag in db.tags.get(&entry.title).into_iter().flatten() {
+            if !entry.tags.contains(tag) {
+                entry.tags.push(tag.clone());
+            }
+        }
+    }
+    entry.chars = body.chars().count();
+    if body.trim().is_empty() {
+        return Err("Empty page".into());
+    }
+    Ok(Capture {
+        title: Some(entry.title.clone()),
+        body,
+        source: "notion".into(),
+        tags: entry.tags.clone(),
+        ..Default::default()
+    })
+}
+
+/// Unpacks Part-N.zip wrappers into the runtime dir; the plain export is
+/// returned as is. The bool marks temporary files.
+fn parts(path: &Path) -> Result<Vec<(PathBuf, bool)>, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("Could not open export: {e}"))?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| format!("Not a ZIP archive: {e}"))?;
+    let names: Vec<String> = zip
+        .file_names()
+        .filter(|n| !n.ends_with('/'))
+        .map(String::from)
+        .collect();
+    if names.is_empty() || !names.iter().all(|n| n.to_lowercase().ends_with(".zip")) {
+        return Ok(vec![(path.to_path_buf(), false)]);
+    }
+    std::fs::create_dir_all(crate::runtime_dir()).map_err(|e| e.to_string())?;
+    let mut out = Vec::new();
+    for (i, name) in names.iter().enumerate() {
+        let dest = crate::runtime_dir().join(format!("notion-{}-{i}.zip", std::process::id()));
+        let copied = zip
+            .by_name(name)
+            .map_err(|e| e.to_string())
+            .and_then(|mut entry| {
+                let mut file = std::fs::File::create(&dest).map_err(|e| e.to_string())?;
+                std::io::copy(&mut entry, &mut file).map_err(|e| e.to_string())
+            });
+        out.push((dest, true));
+        if let Err(e) = copied {
+            cleanup(&out);
+            return Err(format!("Could not unpack {name}: {e}"));
+        }
+    }
+    Ok(out)
+}
+
+fn cleanup(parts: &[(PathBuf, bool)]) {
+    for (path, temporary) in parts {
+        if *temporary {
+            std::fs::remove_file(path).ok();
+        }
+    }
+}
+
+/// Blocking half: reads every part and sends pages down `tx` as they are
+/// prepared. Returns the database names.
+fn walk(path: &Path, dry_run: bool, tx: mpsc::Sender<Prepared>) -> Result<Vec<String>, String> {
+    let parts = parts(path)?;
+    let mut archives = Vec::new();
+    for (part, _) in &parts {
+        let opened = std::fs::File::open(part)
+            .map_err(|e| e.to_string())
+            .and_then(|f| zip::ZipArchive::new(f).map_err(|e| format!("Not a ZIP archive: {e}")));
+        match opened {
+            Ok(zip) => archives.push(zip),
+            Err(e) => {
+                cleanup(&parts);
+                return Err(e);
+            }
+        }
+    }
+
+    // Index first so totals and databases are known before any page goes out.
+    let mut indexed = Vec::new();
+    let mut databases: HashMap<String, Database> = HashMap::new();
+    for zip in archives.iter_mut() {
+        let names: BTreeMap<String, usize> = (0..zip.len())
+            .filter_map(|i| zip.by_index(i).ok().map(|e| (e.name().to_string(), i)))
+            .filter(|(name, _)| !name.ends_with('/'))
+            .collect();
+        let csvs: Vec<String> = names
+            .keys()
+            .filter(|n| n.to_lowercase().ends_with(".csv"))
+            .cloned()
+            .collect();
+        for csv in csvs {
+            // "DB id_all.csv" lists every row; "DB id.csv" only the current view.
+            let folder = csv[..csv.len() - 4].trim_end_matches("_all").to_string();
+            if databases.contains_key(&folder) && !csv.ends_with("_all.csv") {
+                continue;
+            }
+            let text = read_entry(zip, &csv, MAX_PAGE_BYTES)
+                .map(|b| String::from_utf8_lossy(&b).into_owned())
+                .unwrap_or_default();
+            let db = read_database(&clean_name(&folder), &text);
+            databases.insert(folder, db);
+        }
+        let pages: Vec<String> = names
+            .keys()
+            .filter(|n| is_page(n) && n.as_str() != "index.html")
+            .cloned()
+            .collect();
+        indexed.push((names, pages));
+    }
+
+    let total = indexed.iter().map(|(_, pages)| pages.len()).sum();
+    let mut index = 0;
+    for (zip, (names, pages)) in archives.iter_mut().zip(&indexed) {
+        for name in pages {
+            let folder = name.rsplit_once('/').map(|(dir, _)| dir);
+            let database = folder.and_then(|f| databases.get(f));
+            let mut entry = PreviewEntry {
+                title: clean_name(name),
+                tags: Vec::new(),
+                attachments: 0,
+                chars: 0,
+            };
+            let result = prepare_page(zip, names, name, database, dry_run, &mut entry);
+            let prepared = Prepared {
+                index,
+                total,
+                entry,
+                result,
+            };
+            index += 1;
+            if tx.blocking_send(prepared).is_err() {
+                break;
+            }
+        }
+    }
+    cleanup(&parts);
+    let mut names: Vec<String> = databases.into_values().map(|db| db.name).collect();
+    names.sort();
+    Ok(names)
+}
+
+async fn import(
+    app: AppHandle,
+    task: Task,
+    path: String,
+    dry_run: bool,
+) -> Result<NotionImport, String> {
+    let mut dedupe = Dedupe::load(&app).await?;
+    let (tx, mut rx) = mpsc::channel(4);
+    let source = PathBuf::from(&path);
+    let walker = tauri::async_runtime::spawn_blocking(move || walk(&source, dry_run, tx));
+
+    let mut summary = NotionImport {
+        dry_run,
+        ..Default::default()
+    };
+    while let Some(prepared) = rx.recv().await {
+        if task.cancelled() {
+            break;
+        }
+        summary.pages += 1;
+        task.progress(
+            prepared.index as u64,
+            Some(prepared.total as u64),
+            Some(&prepared.entry.title),
+        );
+        let result = match prepared.result {
+            Ok(capture) if dry_run => Ok(!dedupe.preview(&capture)),
+            Ok(capture) => dedupe.submit(&app, capture).await.map(|c| c.is_some()),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(false) => {}
+            Ok(true) => {
+                summary.imported += 1;
+                summary.attachments += prepared.entry.attachments;
+                if dry_run && summary.entries.len() < PREVIEW_LIMIT {
+                    summary.entries.push(prepared.entry);
+                }
+            }
+            Err(e) if e == "Empty page" => {}
+            Err(e) => {
+                log::warn!(
+                    "Notion import of page {} of {} failed: {}",
+                    prepared.index + 1,
+                    prepared.total,
+                    e
+                );
+                summary.failed += 1;
+            }
+        }
+    }
+    // Lets the walker's next send fail so it stops.
+    drop(rx);
+    summary.databases = walker.await.map_err(|e| e.to_string())??;
+    summary.duplicates = dedupe.counts();
+    log::info!(
+        "Notion import{}: {} pages, {} imported, {} failed",
+        if dry_run { " (dry run)" } else { "" },
+        summary.pages,
+        summary.imported,
+        summary.failed
+    );
+    Ok(summary)
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Imports a Notion export ZIP, or with `dry_run` only reports what would be
+/// imported. Returns the task id.
+#[tauri::command]
+pub fn import_notion_export(app: AppHandle, path: String, dry_run: Option<bool>) -> u64 {
+    let dry_run = dry_run.unwrap_or(false);
+    let handle = app.clone();
+    tasks::spawn(&app, "import_notion_export", move |task| {
+        import(handle, task, path, dry_run)
+    })
+}
@@ -0,0 +1,428 @@
+// Obsidian — mirrors snippets into a folder of an Obsidian vault.
+//
+// Every `interval_minutes` (times the poll factor) the JSON export is
+// rendered like the Markdown folder export into `<vault>/<folder>/`, with
+// attachments under assets/ and a note per collection in Collections/ that
+// wikilinks its snippets (each snippet links back to its collections).
+//
+// The vault is the user's: obsidian-sync.json in the data dir records the
+// SHA-256 of every file as last written. A file whose content no longer
+// matches was edited in Obsidian and is left alone (reported as a conflict
+// until the edit is undone or the file deleted, which brings the app's copy
+// back). Notes for deleted snippets are removed only when unedited. Names
+// are picked once per snippet, so retitling doesn't move a note.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+
+use crate::{config, exports, performance, suspend};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const ASSETS: &str = "assets";
+const COLLECTIONS: &str = "Collections";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ObsidianConfig {
+    pub enabled: bool,
+    /// Vault root.
+    pub vault: Option<String>,
+    /// Folder inside the vault that the app owns.
+    pub folder: String,
+    pub interval_minutes: u64,
+}
+
+impl Default for ObsidianConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            vault: None,
+            folder: "Pin-Up AI".into(),
+            interval_minutes: 5,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Note {
+    /// Relative to the mirror folder, `/`-separated.
+    path: String,
+    sha256: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct SyncState {
+    /// Mirror folder the notes belong to; a different one starts over.
+    root: String,
+    /// Snippet id, or `collection:<name>`, to the note written for it.
+    notes: BTreeMap<String, Note>,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct SyncReport {
+    /// RFC 3339.
+    at: String,
+    written: usize,
+    unchanged: usize,
+    removed: usize,
+    /// Notes edited in Obsidian and therefore not updated.
+    conflicts: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ObsidianStatus {
+    config: ObsidianConfig,
+    /// Resolved `<vault>/<folder>`.
+    root: Option<String>,
+    syncing: bool,
+    last_sync: Option<SyncReport>,
+    last_error: Option<String>,
+}
+
+static SYNCING: AtomicBool = AtomicBool::new(false);
+static LAST: Mutex<(Option<SyncReport>, Option<String>)> = Mutex::new((None, None));
+static LAST_RUN: Mutex<Option<std::time::Instant>> = Mutex::new(None);
+
+fn state_path() -> PathBuf {
+    crate::data_dir().join("obsidian-sync.json")
+}
+
+fn load_state() -> SyncState {
+    std::fs::read_to_string(state_path())
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &SyncState) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    let tmp = state_path().with_extension("json.tmp");
+    std::fs::write(&tmp, text).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, state_path()).map_err(|e| e.to_string())
+}
+
+fn root_of(cfg: &ObsidianConfig) -> Option<PathBuf> {
+    let folder = cfg.folder.trim().trim_matches(['/', '\\']);
+    let vault = PathBuf::from(cfg.vault.as_ref()?);
+    Some(if folder.is_empty() {
+        vault
+    } else {
+        vault.join(folder)
+    })
+}
+
+fn sha256(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+fn stem(path: &str) -> &str {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    name.strip_suffix(".md").unwrap_or(name)
+}
+
+/// Writes `content` to the note for `key` unless the file was edited since
+/// the last sync.
+fn write_note(root: &Path, key: &str, note: &mut Note, content: &str, report: &mut SyncReport) {
+    let full = root.join(&note.path);
+    let new_hash = sha256(content.as_bytes());
+    if let Ok(current) = std::fs::read(&full) {
+        let current = sha256(&current);
+        if current == new_hash {
+            note.sha256 = new_hash;
+            report.unchanged += 1;
+            return;
+        }
+        if current != note.sha256 {
+            log::debug!(
+                "Obsidian: {} edited in the vault, skipping {}",
+                note.path,
+                key
+            );
+            report.conflicts.push(note.path.clone());
+            return;
+        }
+    }
+    if let Some(parent) = full.parent() {
+        std::fs::create_dir_all(parent).ok();
+    }
+    let tmp = full.with_extension("md.tmp");
+    let written = std::fs::write(&tmp, content).and_then(|_| std::fs::rename(&tmp, &full));
+    match written {
+        Ok(()) => {
+            note.sha256 = new_hash;
+            report.written += 1;
+        }
+        Err(e) => {
+            std::fs::remove_file(&tmp).ok();
+            log::warn!("Obsidian: could not write {}: {}", note.path, e);
+        }
+    }
+}
+
+fn collection_note(name: &str, snippets: &[String]) -> String {
+    let mut lines = vec![
+        "---".to_string(),
+        format!("title: {}", exports::yaml(name)),
+        "---".into(),
+        String::new(),
+        format!("# {name}"),
+        String::new(),
+    ];
+    lines.extend(snippets.iter().map(|stem| format!("- [[{stem}]]")));
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+fn mirror(
+    root: &Path,
+    data: &Value,
+    files: &BTreeMap<String, String>,
+) -> Result<SyncReport, String> {
+    let mut state = load_state();
+    let root_key = root.to_string_lossy().to_string();
+    if state.root != root_key {
+        state = SyncState {
+            root: root_key,
+            ..Default::default()
+        };
+    }
+    let mut report = SyncReport {
+        at: chrono::Local::now().to_rfc3339(),
+        ..Default::default()
+    };
+
+    let tags = exports::joined(
+        data,
+        "snippet_tags",
+        "tag_id",
+        &exports::names_by_id(data, "tags"),
+    );
+    let collections = exports::joined(
+        data,
+        "snippet_collections",
+        "collection_id",
+        &exports::names_by_id(data, "collections"),
+    );
+    let snippets: Vec<&Value> = data["snippets"].as_array().into_iter().flatten().collect();
+
+    // Names first: notes link to each other by file name. One set for both
+    // folders keeps wikilinks unambiguous.
+    let mut taken = exports::existing_names(root)?;
+    taken.extend(exports::existing_names(&root.join(COLLECTIONS))?);
+    let mut assign = |key: &str, dir: Option<&str>, title: &str, state: &mut SyncState| {
+        state.notes.entry(key.to_string()).or_insert_with(|| {
+            let name = exports::file_name(title, key, &mut taken);
+            Note {
+                path: dir.map_or(name.clone(), |dir| format!("{dir}/{name}")),
+                sha256: String::new(),
+            }
+        });
+    };
+    let mut members: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for snippet in &snippets {
+        let id = snippet["id"].as_str().unwrap_or_default();
+        assign(
+            id,
+            None,
+            snippet["title"].as_str().unwrap_or_default(),
+            &mut state,
+        );
+        for name in collections.get(id).into_iter().flatten() {
+            members
+                .entry(name)
+                .or_default()
+                .push(stem(&state.notes[id].path).to_string());
+        }
+    }
+    for name in members.keys() {
+        assign(
+            &format!("collection:{name}"),
+            Some(COLLECTIONS),
+            name,
+            &mut state,
+        );
+    }
+
+    let mut live = BTreeSet::new();
+    for snippet in &snippets {
+        let id = snippet["id"].as_str().unwrap_or_default();
+        let names = collections.get(id).map(Vec::as_slice).unwrap_or_default();
+        let mut content = exports::snippet_markdown(
+            snippet,
+            tags.get(id).map(Vec::as_slice).unwrap_or_default(),
+            names,
+            files,
+            ASSETS,
+        );
+        if !names.is_empty() {
+            let links: Vec<String> = names
+                .iter()
+                .map(|name| {
+                    format!(
+                        "[[{}]]",
+                        stem(&state.notes[&format!("collection:{name}")].path)
+                    )
+                })
+                .collect();
+            content.push_str(&format!("\n\n---\nCollections: {}\n", links.join(" · ")));
+        }
+        let note = state.notes.get_mut(id).unwrap();
+        write_note(root, id, note, &content, &mut report);
+        live.insert(id.to_string());
+    }
+    for (name, stems) in &members {
+        let key = format!("collection:{name}");
+        let note = state.notes.get_mut(&key).unwrap();
+        write_note(root, &key, note, &collection_note(name, stems), &mut report);
+        live.insert(key);
+    }
+
+    // Gone from the app: delete unless edited in the vault.
+    let stale: Vec<String> = state
+        .notes
+        .keys()
+        .filter(|k| !live.contains(*k))
+        .cloned()
+        .collect();
+    for key in stale {
+        let note = state.notes.remove(&key).unwrap();
+        let full = root.join(&note.path);
+        match std::fs::read(&full) {
+            Ok(bytes) if sha256(&bytes) == note.sha256 => {
+                if std::fs::remove_file(&full).is_ok() {
+                    report.removed += 1;
+                }
+            }
+            Ok(_) => log::info!("Obsidian: keeping edited {} of a deleted item", note.path),
+            Err(_) => {}
+        }
+    }
+
+    save_state(&state)?;
+    Ok(report)
+}
+
+async fn sync(app: &AppHandle) -> Result<SyncReport, String> {
+    let cfg = config::current(app).obsidian;
+    let root = root_of(&cfg).ok_or("No Obsidian vault chosen")?;
+    if SYNCING.swap(true, Ordering::SeqCst) {
+        return Err("A sync is already running".into());
+    }
+    let result = async {
+        let json = exports::fetch_export(None, None).await?;
+        let data: Value = serde_json::from_slice(&json).map_err(|e| e.to_string())?;
+        let (linked, _) = exports::linked_attachments(&String::from_utf8_lossy(&json));
+        tauri::async_runtime::spawn_blocking(move || {
+            let files = exports::copy_attachments(&root.join(ASSETS), &linked)?;
+            mirror(&root, &data, &files)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+    .await;
+    SYNCING.store(false, Ordering::SeqCst);
+    *LAST_RUN.lock().unwrap() = Some(std::time::Instant::now());
+
+    let mut last = LAST.lock().unwrap();
+    match &result {
+        Ok(report) => {
+            log::info!(
+                "Obsidian sync: {} written, {} unchanged, {} removed, {} conflicts",
+                report.written,
+                report.unchanged,
+                report.removed,
+                report.conflicts.len()
+            );
+            *last = (Some(report.clone()), None);
+            app.emit_all("obsidian-synced", report).ok();
+        }
+        Err(e) => {
+            log::warn!("Obsidian sync failed: {}", e);
+            last.1 = Some(e.clone());
+        }
+    }
+    result
+}
+
+fn status(app: &AppHandle) -> ObsidianStatus {
+    let cfg = config::current(app).obsidian;
+    let (last_sync, last_error) = LAST.lock().unwrap().clone();
+    ObsidianStatus {
+        root: root_of(&cfg).map(|p| p.to_string_lossy().to_string()),
+        config: cfg,
+        syncing: SYNCING.load(Ordering::SeqCst),
+        last_sync,
+        last_error,
+    }
+}
+
+/// Mirrors on the configured interval while enabled.
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let cfg = config::current(&handle).obsidian;
+            if !cfg.enabled || cfg.vault.is_none() || suspend::paused() {
+                continue;
+            }
+            let factor = performance::capabilities(&handle).poll_factor as u32;
+            let interval = Duration::from_secs(cfg.interval_minutes.max(1) * 60) * factor;
+            let due = LAST_RUN
+                .lock()
+                .unwrap()
+                .map_or(true, |at| at.elapsed() >= interval);
+            if due && crate::BACKEND_PORT.load(Ordering::SeqCst) != 0 {
+                sync(&handle).await.ok();
+            }
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn get_obsidian_status(app: AppHandle) -> ObsidianStatus {
+    status(&app)
+}
+
+/// Points the mirror at `vault` (None turns it off) and syncs right away.
+#[tauri::command]
+pub async fn set_obsidian_vault(
+    app: AppHandle,
+    vault: Option<String>,
+) -> Result<ObsidianStatus, String> {
+    if let Some(path) = &vault {
+        let dir = Path::new(path);
+        if !dir.is_dir() {
+            return Err(format!("{path} is not a folder"));
+        }
+        if !dir.join(".obsidian").is_dir() {
+            log::info!(
+                "Obsidian: {:?} has no .obsidian folder; using it anyway",
+                dir
+            );
+        }
+    }
+    let enabled = vault.is_some();
+    config::update(&app, |c| {
+        c.obsidian.enabled = enabled;
+        c.obsidian.vault = vault;
+    })?;
+    if enabled {
+        sync(&app).await?;
+    }
+    Ok(status(&app))
+}
+
+#[tauri::command]
+pub async fn sync_obsidian_now(app: AppHandle) -> Result<SyncReport, String> {
+    sync(&app).await
+}
@@ -0,0 +1,269 @@
+// OCR — text recognition for screenshots and imported images.
+//
+// Runs the Tesseract CLI (found via `ocr.tesseract_path`, PATH or the usual
+// install locations) with language packs kept in app_dir()/ocr/tessdata/.
+// Packs missing there fall back to the system tessdata, so a distro install
+// works out of the box. `install_ocr_language` fetches packs from the
+// tessdata_fast repository via the download manager.
+//
+// Screenshots and image imports append the recognized text below the image
+// so it becomes searchable; without Tesseract they are filed as before.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::api::process::Command;
+use tauri::AppHandle;
+
+use crate::{attachments, downloads, i18n, jobs, performance, power};
+
+const TESSDATA_URL: &str = "https://github.com/tesseract-ocr/tessdata_fast/raw/main";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct OcrConfig {
+    /// Append recognized text to screenshot and image-import snippets.
+    pub auto: bool,
+    /// Tesseract language codes, tried together ("eng", "deu", "chi_sim").
+    pub languages: Vec<String>,
+    /// Explicit path to the tesseract binary.
+    pub tesseract_path: Option<String>,
+}
+
+impl Default for OcrConfig {
+    fn default() -> Self {
+        Self {
+            auto: true,
+            languages: vec!["eng".into()],
+            tesseract_path: None,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct OcrResult {
+    pub text: String,
+    pub languages: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct OcrLanguage {
+    code: String,
+    size: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct OcrStatus {
+    tesseract: Option<String>,
+    version: Option<String>,
+    tessdata_dir: String,
+    installed: Vec<OcrLanguage>,
+}
+
+pub fn tessdata_dir() -> PathBuf {
+    crate::app_dir().join("ocr").join("tessdata")
+}
+
+fn pack_path(code: &str) -> PathBuf {
+    tessdata_dir().join(format!("{}.traineddata", code))
+}
+
+/// Language codes become file names and CLI arguments; keep them plain.
+fn check_code(code: &str) -> Result<(), String> {
+    if !code.is_empty() && code.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        Ok(())
+    } else {
+        Err(format!("Invalid OCR language code {:?}", code))
+    }
+}
+
+fn find_tesseract(cfg: &OcrConfig) -> Option<PathBuf> {
+    if let Some(path) = &cfg.tesseract_path {
+        return Some(PathBuf::from(path)).filter(|p| p.is_file());
+    }
+    let exe = if cfg!(windows) {
+        "tesseract.exe"
+    } else {
+        "tesseract"
+    };
+    let mut dirs: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|p| std::env::split_paths(&p).collect())
+        .unwrap_or_default();
+    dirs.extend(
+        [
+            "/opt/homebrew/bin",
+            "/usr/local/bin",
+            "/usr/bin",
+            r"C:\Program Files\Tesseract-OCR",
+            r"C:\Program Files (x86)\Tesseract-OCR",
+        ]
+        .iter()
+        .map(PathBuf::from),
+    );
+    dirs.into_iter().map(|d| d.join(exe)).find(|p| p.is_file())
+}
+
+fn run(tesseract: &Path, args: Vec<String>) -> Result<String, String> {
+    let output = Command::new(tesseract.to_string_lossy())
+        .args(args)
+        .output()
+        .map_err(|e| format!("Could not run tesseract: {e}"))?;
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(format!("tesseract failed: {}", output.stderr.trim()))
+    }
+}
+
+/// Recognizes text in the image at `path`. Blocking; call off the main
+/// thread.
+pub fn recognize(cfg: &OcrConfig, path: &Path, languages: &[String]) -> Result<OcrResult, String> {
+    let tesseract = find_tesseract(cfg).ok_or("Tesseract is not installed")?;
+    let languages = if languages.is_empty() {
+        &cfg.languages
+    } else {
+        languages
+    };
+    for code in languages {
+        check_code(code)?;
+    }
+    let job = jobs::begin("ocr", &i18n::t("job.ocr"));
+
+    let mut args = vec![path.to_string_lossy().into_owned(), "stdout".into()];
+    if !languages.is_empty() {
+        args.extend(["-l".into(), languages.join("+")]);
+    }
+    // Only point at our tessdata when it has every requested pack; otherwise
+    // let tesseract use its own.
+    if !languages.is_empty() && languages.iter().all(|code| pack_path(code).is_file()) {
+        args.extend([
+            "--tessdata-dir".into(),
+            tessdata_dir().to_string_lossy().into_owned(),
+        ]);
+    }
+    let text = run(&tesseract, args);
+    job.end(&text);
+    Ok(OcrResult {
+        text: text?.trim().to_string(),
+        languages: languages.to_vec(),
+    })
+}
+
+/// Recognized text for `path`, or `None` if automatic OCR is off,
+/// unavailable or found nothing. Failures are logged, never surfaced: OCR is
+/// a bonus on top of a capture that already succeeded. Blocking.
+pub fn auto_text(cfg: &OcrConfig, path: &Path) -> Option<String> {
+    if !cfg.auto {
+        return None;
+    }
+    match recognize(cfg, path, &[]) {
+        Ok(result) if !result.text.is_empty() => Some(result.text),
+        Ok(_) => None,
+        Err(e) => {
+            log::info!("OCR skipped for {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// The OCR config for automatic runs; `auto` is off under the lite
+/// performance profile and in power saver mode.
+pub fn auto_config(app: &AppHandle) -> OcrConfig {
+    let mut cfg = crate::config::current(app).ocr;
+    cfg.auto &= performance::capabilities(app).ocr && !power::saving();
+    cfg
+}
+
+/// `auto_text` for a stored attachment, off the async runtime.
+pub async fn attachment_text(app: &AppHandle, hash: &str) -> Option<String> {
+    let cfg = auto_config(app);
+    let path = attachments::path_of(&attachments::get(hash)?);
+    tauri::async_runtime::spawn_blocking(move || auto_text(&cfg, &path))
+        .await
+        .ok()
+        .flatten()
+}
+
+fn installed() -> Vec<OcrLanguage> {
+    let Ok(entries) = std::fs::read_dir(tessdata_dir()) else {
+        return Vec::new();
+    };
+    let mut languages: Vec<OcrLanguage> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "traineddata" {
+                return None;
+            }
+            Some(OcrLanguage {
+                code: path.file_stem()?.to_string_lossy().into_owned(),
+                size: entry.metadata().ok()?.len(),
+            })
+        })
+        .collect();
+    languages.sort_by(|a, b| a.code.cmp(&b.code));
+    languages
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// OCR on a file path or a stored attachment (by hash).
+#[tauri::command]
+pub async fn ocr_image(
+    app: AppHandle,
+    path: Option<String>,
+    attachment: Option<String>,
+    languages: Option<Vec<String>>,
+) -> Result<OcrResult, String> {
+    let path = match (path, attachment) {
+        (Some(path), _) => PathBuf::from(path),
+        (None, Some(hash)) => {
+            attachments::path_of(&attachments::get(&hash).ok_or("Unknown attachment")?)
+        }
+        (None, None) => return Err("Pass a path or an attachment".into()),
+    };
+    let cfg = crate::config::current(&app).ocr;
+    let languages = languages.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || recognize(&cfg, &path, &languages))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn get_ocr_status(app: AppHandle) -> OcrStatus {
+    let cfg = crate::config::current(&app).ocr;
+    let tesseract = find_tesseract(&cfg);
+    let version = match &tesseract {
+        Some(path) => {
+            let path = path.clone();
+            tauri::async_runtime::spawn_blocking(move || run(&path, vec!["--version".into()]))
+                .await
+                .ok()
+                .and_then(Result::ok)
+                .and_then(|out| out.lines().next().map(str::to_string))
+        }
+        None => None,
+    };
+    OcrStatus {
+        tesseract: tesseract.map(|p| p.to_string_lossy().into_owned()),
+        version,
+        tessdata_dir: tessdata_dir().to_string_lossy().into_owned(),
+        installed: installed(),
+    }
+}
+
+/// Downloads a language pack through the download manager.
+#[tauri::command]
+pub async fn install_ocr_language(app: AppHandle, code: String) -> Result<OcrLanguage, String> {
+    check_code(&code)?;
+    let url = format!("{}/{}.traineddata", TESSDATA_URL, code);
+    let path = downloads::fetch(&app, url, pack_path(&code), None).await?;
+    let size = std::fs::metadata(&path).map_or(0, |m| m.len());
+    log::info!("Installed OCR language {} ({} bytes)", code, size);
+    Ok(OcrLanguage { code, size })
+}
+
+#[tauri::command]
+pub fn remove_ocr_language(code: String) -> Result<(), String> {
+    check_code(&code)?;
+    std::fs::remove_file(pack_path(&code)).map_err(|e| format!("Could not remove {}: {}", code, e))
+}
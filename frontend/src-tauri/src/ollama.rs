@@ -0,0 +1,297 @@
+// Ollama — detect, start/stop and talk to a local Ollama server.
+//
+// Detection looks for the `ollama` binary (config, PATH, the macOS app
+// bundle, the Windows per-user install) and probes `<host>/api/version`.
+// `start_ollama` runs `ollama serve` only when nothing is listening yet, and
+// `stop_ollama` only stops a server Pin-Up started itself (Tauri also kills
+// it on exit). The endpoint is handed to the backend as PINUP_OLLAMA_URL
+// when Ollama is enabled.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::api::process::{Command, CommandChild, CommandEvent};
+use tauri::{AppHandle, Manager};
+
+use crate::{certs, config, i18n, jobs, power};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct OllamaConfig {
+    pub enabled: bool,
+    pub host: String,
+    pub binary_path: Option<String>,
+    /// Start `ollama serve` with the app if it isn't running.
+    pub autostart: bool,
+}
+
+impl Default for OllamaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            host: "http://127.0.0.1:11434".into(),
+            binary_path: None,
+            autostart: false,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct LocalLlmStatus {
+    enabled: bool,
+    host: String,
+    binary: Option<String>,
+    running: bool,
+    /// True when the running server was started by Pin-Up.
+    managed: bool,
+    version: Option<String>,
+    models: Vec<Value>,
+}
+
+/// `ollama serve` processes started by us.
+static CHILD: Lazy<Mutex<Option<CommandChild>>> = Lazy::new(|| Mutex::new(None));
+
+static CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    // `ollama.host` may be a gateway behind a private CA.
+    certs::add_roots(reqwest::Client::builder())
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap()
+});
+
+fn find_binary(cfg: &OllamaConfig) -> Option<PathBuf> {
+    if let Some(path) = &cfg.binary_path {
+        return Some(PathBuf::from(path)).filter(|p| p.is_file());
+    }
+    let exe = if cfg!(windows) {
+        "ollama.exe"
+    } else {
+        "ollama"
+    };
+    let mut candidates: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|p| std::env::split_paths(&p).map(|d| d.join(exe)).collect())
+        .unwrap_or_default();
+    candidates.extend(
+        [
+            "/usr/local/bin/ollama",
+            "/opt/homebrew/bin/ollama",
+            "/Applications/Ollama.app/Contents/Resources/ollama",
+        ]
+        .map(PathBuf::from),
+    );
+    if let Some(local) = dirs::data_local_dir() {
+        candidates.push(local.join("Programs").join("Ollama").join(exe));
+    }
+    candidates.into_iter().find(|p| p.is_file())
+}
+
+fn url(cfg: &OllamaConfig, path: &str) -> String {
+    format!("{}{}", cfg.host.trim_end_matches('/'), path)
+}
+
+async fn version(cfg: &OllamaConfig) -> Option<String> {
+    let resp = CLIENT.get(url(cfg, "/api/version")).send().await.ok()?;
+    let body: Value = resp.error_for_status().ok()?.json().await.ok()?;
+    body["version"].as_str().map(str::to_string)
+}
+
+async fn models(cfg: &OllamaConfig) -> Result<Vec<Value>, String> {
+    let body: Value = CLIENT
+        .get(url(cfg, "/api/tags"))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Ollama request failed: {e}"))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid Ollama response: {e}"))?;
+    Ok(body["models"].as_array().cloned().unwrap_or_default())
+}
+
+/// Sidecar environment pointing the backend at Ollama.
+pub fn backend_env(cfg: &OllamaConfig) -> Vec<(String, String)> {
+    if cfg.enabled {
+        vec![("PINUP_OLLAMA_URL".into(), cfg.host.clone())]
+    } else {
+        Vec::new()
+    }
+}
+
+async fn start(app: &AppHandle) -> Result<String, String> {
+    let cfg = config::current(app).ollama;
+    if let Some(version) = version(&cfg).await {
+        return Ok(version);
+    }
+    let binary = find_binary(&cfg).ok_or("Ollama is not installed")?;
+    let host = cfg
+        .host
+        .trim_start_matches("http://")
+        .trim_start_matches("https://")
+        .trim_end_matches('/')
+        .to_string();
+    let (mut rx, child) = Command::new(binary.to_string_lossy())
+        .args(["serve"])
+        .envs([("OLLAMA_HOST".to_string(), host)].into())
+        .spawn()
+        .map_err(|e| format!("Could not start Ollama: {e}"))?;
+    *CHILD.lock().unwrap() = Some(child);
+
+    tauri::async_runtime::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            match event {
+                CommandEvent::Stdout(line) | CommandEvent::Stderr(line) => {
+                    log::debug!("[ollama] {}", line)
+                }
+                CommandEvent::Terminated(payload) => {
+                    log::info!("[ollama] exited: {:?}", payload.code);
+                    CHILD.lock().unwrap().take();
+                    break;
+                }
+                _ => {}
+            }
+        }
+    });
+
+    for _ in 0..20 {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        if let Some(version) = version(&cfg).await {
+            log::info!("Ollama {} started at {}", version, cfg.host);
+            app.emit_all("ollama-started", &version).ok();
+            return Ok(version);
+        }
+    }
+    Err("Ollama did not come up within 10 s".into())
+}
+
+/// Starts Ollama at launch when `ollama.autostart` is set, once power
+/// saver mode is off.
+pub fn autostart(app: &AppHandle) {
+    let cfg = config::current(app).ollama;
+    if !(cfg.enabled && cfg.autostart) {
+        return;
+    }
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        while power::saving() {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        }
+        if let Err(e) = start(&handle).await {
+            log::warn!("Ollama autostart failed: {}", e);
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub async fn get_local_llm_status(app: AppHandle) -> LocalLlmStatus {
+    let cfg = config::current(&app).ollama;
+    let version = if cfg.enabled {
+        version(&cfg).await
+    } else {
+        None
+    };
+    let models = match version {
+        Some(_) => models(&cfg).await.unwrap_or_else(|e| {
+            log::warn!("{}", e);
+            Vec::new()
+        }),
+        None => Vec::new(),
+    };
+    LocalLlmStatus {
+        enabled: cfg.enabled,
+        binary: find_binary(&cfg).map(|p| p.to_string_lossy().into_owned()),
+        running: version.is_some(),
+        managed: CHILD.lock().unwrap().is_some(),
+        host: cfg.host,
+        version,
+        models,
+    }
+}
+
+#[tauri::command]
+pub async fn start_ollama(app: AppHandle) -> Result<String, String> {
+    start(&app).await
+}
+
+#[tauri::command]
+pub fn stop_ollama(app: AppHandle) -> Result<(), String> {
+    let child = CHILD
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or("Ollama was not started by Pin-Up")?;
+    child.kill().map_err(|e| e.to_string())?;
+    app.emit_all("ollama-stopped", ()).ok();
+    Ok(())
+}
+
+/// Relays a pull's status lines until Ollama closes the stream.
+async fn stream_pull(
+    app: &AppHandle,
+    job: &jobs::Job,
+    model: &str,
+    resp: reqwest::Response,
+) -> Result<(), String> {
+    let mut stream = resp.bytes_stream();
+    let mut buffer = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        buffer.extend_from_slice(&chunk.map_err(|e| format!("Ollama pull failed: {e}"))?);
+        while let Some(pos) = buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=pos).collect();
+            let Ok(status) = serde_json::from_slice::<Value>(&line) else {
+                continue;
+            };
+            if let Some(error) = status["error"].as_str() {
+                return Err(format!("Ollama pull failed: {}", error));
+            }
+            if let (Some(completed), Some(total)) =
+                (status["completed"].as_u64(), status["total"].as_u64())
+            {
+                job.progress(completed, total);
+            }
+            app.emit_all(
+                "ollama-pull-progress",
+                json!({
+                    "model": model,
+                    "status": status["status"],
+                    "completed": status["completed"],
+                    "total": status["total"],
+                }),
+            )
+            .ok();
+        }
+    }
+    Ok(())
+}
+
+/// Pulls `model`, streaming Ollama's status lines as "ollama-pull-progress".
+#[tauri::command]
+pub async fn pull_ollama_model(app: AppHandle, model: String) -> Result<(), String> {
+    let cfg = config::current(&app).ollama;
+    // Pulls can take far longer than CLIENT's timeout.
+    let client = certs::add_roots(reqwest::Client::builder())
+        .build()
+        .map_err(|e| e.to_string())?;
+    let resp = client
+        .post(url(&cfg, "/api/pull"))
+        .json(&json!({ "name": model, "stream": true }))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Ollama pull failed: {e}"))?;
+
+    let job = jobs::begin(
+        "ollama_pull",
+        &i18n::tf("job.ollama_pull", &[("model", &model)]),
+    );
+    let result = stream_pull(&app, &job, &model, resp).await;
+    job.end(&result);
+    result?;
+    log::info!("Pulled Ollama model {}", model);
+    Ok(())
+}
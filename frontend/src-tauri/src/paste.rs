@@ -0,0 +1,59 @@
+// Paste — put a snippet into whatever app the user was working in.
+//
+// Copies the snippet body to the clipboard, hides our windows so focus falls
+// back to the previous app, then synthesizes the platform paste shortcut.
+
+use std::time::Duration;
+
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use tauri::{AppHandle, Manager};
+
+use crate::{backend, clipboard};
+
+/// Time for the OS to hand focus back before we send keystrokes.
+const FOCUS_SETTLE_MS: u64 = 200;
+
+fn hide_windows(app: &AppHandle) {
+    for window in app.windows().values() {
+        window.hide().ok();
+    }
+    // Hiding windows alone doesn't reactivate the previous app on macOS.
+    #[cfg(target_os = "macos")]
+    app.hide().ok();
+}
+
+fn send_paste_keystroke() -> Result<(), String> {
+    let modifier = if cfg!(target_os = "macos") {
+        Key::Meta
+    } else {
+        Key::Control
+    };
+    let mut enigo = Enigo::new(&Settings::default())
+        .map_err(|e| format!("Keyboard synthesis unavailable: {e}"))?;
+    enigo
+        .key(modifier, Direction::Press)
+        .and_then(|_| enigo.key(Key::Unicode('v'), Direction::Click))
+        .and_then(|_| enigo.key(modifier, Direction::Release))
+        .map_err(|e| format!("Could not send paste keystroke: {e}"))
+}
+
+/// Copies `text`, hides our windows, and pastes into the frontmost app.
+pub async fn paste_text(app: &AppHandle, text: &str) -> Result<(), String> {
+    clipboard::write_text(text)?;
+    hide_windows(app);
+    tokio::time::sleep(Duration::from_millis(FOCUS_SETTLE_MS)).await;
+    tauri::async_runtime::spawn_blocking(send_paste_keystroke)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub async fn paste_snippet(app: AppHandle, id: String) -> Result<(), String> {
+    let snippet = backend::get(&backend::snippet_path(&id)?).await?;
+    let body = snippet["body"]
+        .as_str()
+        .ok_or("Snippet has no body")?
+        .to_string();
+    paste_text(&app, &body).await
+}
@@ -0,0 +1,148 @@
+// Performance profiles — trade features for memory on old hardware.
+//
+//   lite      no OCR, palette pre-warm, preview cache or thumbnails; slower
+//             polling; a small backend thread pool
+//   balanced  everything on; backend thread pool capped at 16 (default)
+//   full      everything on; backend pool left at its own default
+//
+// Shell-side subsystems read `capabilities()` directly; frontend-only
+// features (palette pre-warm, preview cache, thumbnails) follow the flags
+// reported by `get_build_features`. Backend limits are passed as sidecar
+// env, so changing them respawns the sidecar.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::{config, power};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PerformanceProfile {
+    Lite,
+    #[default]
+    Balanced,
+    Full,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct PerformanceConfig {
+    pub profile: PerformanceProfile,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Capabilities {
+    pub profile: PerformanceProfile,
+    pub ocr: bool,
+    pub prewarm_palette: bool,
+    pub preview_cache: bool,
+    pub thumbnails: bool,
+    /// Multiplier applied to watcher and snapshot intervals.
+    pub poll_factor: u64,
+    /// Backend request threads; 0 leaves the backend default.
+    pub backend_threads: u32,
+}
+
+/// Shell subsystems compiled into every build.
+const FEATURES: &[&str] = &[
+    "accessibility",
+    "clipboard",
+    "rich_copy",
+    "screenshots",
+    "region_capture",
+    "ocr",
+    "voice_notes",
+    "import",
+    "drag_out",
+    "history",
+];
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BuildFeatures {
+    version: &'static str,
+    os: &'static str,
+    arch: &'static str,
+    debug: bool,
+    features: Vec<&'static str>,
+    performance: Capabilities,
+}
+
+pub fn capabilities_for(profile: PerformanceProfile) -> Capabilities {
+    let lite = profile == PerformanceProfile::Lite;
+    Capabilities {
+        profile,
+        ocr: !lite,
+        prewarm_palette: !lite,
+        preview_cache: !lite,
+        thumbnails: !lite,
+        poll_factor: match profile {
+            PerformanceProfile::Lite => 3,
+            PerformanceProfile::Balanced | PerformanceProfile::Full => 1,
+        },
+        backend_threads: match profile {
+            PerformanceProfile::Lite => 4,
+            PerformanceProfile::Balanced => 16,
+            PerformanceProfile::Full => 0,
+        },
+    }
+}
+
+/// Capabilities of the configured profile; polling slows further in power
+/// saver mode.
+pub fn capabilities(app: &AppHandle) -> Capabilities {
+    let mut caps = capabilities_for(config::current(app).performance.profile);
+    caps.poll_factor *= power::poll_factor();
+    caps
+}
+
+/// Extra sidecar environment for `profile`.
+pub fn backend_env(profile: PerformanceProfile) -> Vec<(String, String)> {
+    let caps = capabilities_for(profile);
+    let mut env = Vec::new();
+    if caps.backend_threads > 0 {
+        env.push((
+            "PINUP_WORKER_THREADS".into(),
+            caps.backend_threads.to_string(),
+        ));
+    }
+    if profile == PerformanceProfile::Lite {
+        for var in ["OMP_NUM_THREADS", "OPENBLAS_NUM_THREADS", "MKL_NUM_THREADS"] {
+            env.push((var.into(), "1".into()));
+        }
+    }
+    env
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Switches profile at runtime. Emits "performance-profile-changed"; if the
+/// backend limits differ and the sidecar is ours, it is respawned.
+#[tauri::command]
+pub async fn set_performance_profile(
+    app: AppHandle,
+    profile: PerformanceProfile,
+) -> Result<Capabilities, String> {
+    let before = config::current(&app);
+    let updated = config::update(&app, |c| c.performance.profile = profile)?;
+    let caps = capabilities_for(profile);
+    app.emit_all("performance-profile-changed", &caps).ok();
+    log::info!("Performance profile set to {:?}", profile);
+
+    let env_changed = backend_env(before.performance.profile) != backend_env(profile);
+    if env_changed && updated.backend.external_port.is_none() {
+        let port = crate::respawn_backend(&app).await?;
+        app.emit_all("backend-ready", port).ok();
+    }
+    Ok(caps)
+}
+
+#[tauri::command]
+pub fn get_build_features(app: AppHandle) -> BuildFeatures {
+    BuildFeatures {
+        version: env!("CARGO_PKG_VERSION"),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        debug: cfg!(debug_assertions),
+        features: FEATURES.to_vec(),
+        performance: capabilities(&app),
+    }
+}
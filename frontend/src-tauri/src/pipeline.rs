@@ -0,0 +1,252 @@
+// Capture pipeline — post-processing applied to every capture before it
+// reaches the backend (see capture::submit).
+//
+// Stages run in the configured order and can be toggled individually:
+//   trim_whitespace      strip trailing spaces, collapse runs of blank lines
+//   strip_tracking       drop utm_*, fbclid, gclid, ... from URLs
+//   smart_quotes         “ ” ‘ ’ → " '
+//   replacements         user regex → replacement rules, in order
+//   secret_scan          redact or block credentials (see secrets.rs)
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::capture::Capture;
+use crate::secrets;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StageKind {
+    TrimWhitespace,
+    StripTracking,
+    SmartQuotes,
+    Replacements,
+    SecretScan,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Stage {
+    pub kind: StageKind,
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Replacement {
+    pub pattern: String,
+    pub replacement: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretAction {
+    /// Replace each secret with a `[REDACTED:<kind>]` marker.
+    Redact,
+    /// Refuse the capture entirely.
+    Block,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct PipelineConfig {
+    pub stages: Vec<Stage>,
+    pub replacements: Vec<Replacement>,
+    pub secret_action: SecretAction,
+    /// Extra secret regexes, on top of the built-in set.
+    pub secret_patterns: Vec<String>,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        let stage = |kind| Stage {
+            kind,
+            enabled: true,
+        };
+        Self {
+            stages: vec![
+                stage(StageKind::TrimWhitespace),
+                stage(StageKind::StripTracking),
+                Stage {
+                    kind: StageKind::SmartQuotes,
+                    enabled: false,
+                },
+                stage(StageKind::Replacements),
+                stage(StageKind::SecretScan),
+            ],
+            replacements: Vec::new(),
+            secret_action: SecretAction::Redact,
+            secret_patterns: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct StageResult {
+    kind: StageKind,
+    changed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PipelineResult {
+    pub output: String,
+    /// Set when a stage refused the text; `output` is then the last good state.
+    pub blocked: Option<String>,
+    pub stages: Vec<StageResult>,
+}
+
+// ── Stages ─────────────────────────────────────────────────────────────────
+static BLANK_RUNS: Lazy<Regex> = Lazy::new(|| Regex::new(r"\n{3,}").unwrap());
+static URL: Lazy<Regex> = Lazy::new(|| Regex::new(r#"https?://[^\s<>"')\]]+"#).unwrap());
+
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "msclkid", "mc_cid", "mc_eid", "igshid", "yclid", "_hsenc",
+    "_hsmi", "ref_src", "si",
+];
+
+fn trim_whitespace(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    BLANK_RUNS
+        .replace_all(lines.join("\n").trim(), "\n\n")
+        .into_owned()
+}
+
+fn is_tracking(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    key.starts_with("utm_") || TRACKING_PARAMS.contains(&key.as_str())
+}
+
+pub fn strip_tracking_url(url: &str) -> String {
+    let (base, fragment) = match url.split_once('#') {
+        Some((base, fragment)) => (base, Some(fragment)),
+        None => (url, None),
+    };
+    let Some((path, query)) = base.split_once('?') else {
+        return url.to_string();
+    };
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty() && !is_tracking(pair.split('=').next().unwrap_or("")))
+        .collect();
+    let mut out = path.to_string();
+    if !kept.is_empty() {
+        out.push('?');
+        out.push_str(&kept.join("&"));
+    }
+    if let Some(fragment) = fragment {
+        out.push('#');
+        out.push_str(fragment);
+    }
+    out
+}
+
+fn strip_tracking(text: &str) -> String {
+    URL.replace_all(text, |caps: &regex::Captures| strip_tracking_url(&caps[0]))
+        .into_owned()
+}
+
+fn smart_quotes(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{00AB}' | '\u{00BB}' => '"',
+            '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{2032}' => '\'',
+            c => c,
+        })
+        .collect()
+}
+
+fn replacements(text: &str, rules: &[Replacement]) -> (String, Option<String>) {
+    let mut out = text.to_string();
+    let mut invalid = Vec::new();
+    for rule in rules {
+        match Regex::new(&rule.pattern) {
+            Ok(re) => out = re.replace_all(&out, rule.replacement.as_str()).into_owned(),
+            Err(_) => invalid.push(rule.pattern.clone()),
+        }
+    }
+    let note = (!invalid.is_empty()).then(|| format!("invalid patterns: {}", invalid.join(", ")));
+    (out, note)
+}
+
+// ── Runner ─────────────────────────────────────────────────────────────────
+pub fn run(cfg: &PipelineConfig, text: &str) -> PipelineResult {
+    let mut output = text.to_string();
+    let mut stages = Vec::new();
+    for stage in cfg.stages.iter().filter(|s| s.enabled) {
+        let mut note = None;
+        let next = match stage.kind {
+            StageKind::TrimWhitespace => trim_whitespace(&output),
+            StageKind::StripTracking => strip_tracking(&output),
+            StageKind::SmartQuotes => smart_quotes(&output),
+            StageKind::Replacements => {
+                let (next, invalid) = replacements(&output, &cfg.replacements);
+                note = invalid;
+                next
+            }
+            StageKind::SecretScan => {
+                let (redacted, kinds) = secrets::redact_secrets(&output, &cfg.secret_patterns);
+                if !kinds.is_empty() {
+                    note = Some(kinds.join(", "));
+                    if cfg.secret_action == SecretAction::Block {
+                        stages.push(StageResult {
+                            kind: stage.kind,
+                            changed: false,
+                            note: note.clone(),
+                        });
+                        return PipelineResult {
+                            output,
+                            blocked: Some(format!("secret:{}", kinds.join(","))),
+                            stages,
+                        };
+                    }
+                }
+                redacted
+            }
+        };
+        stages.push(StageResult {
+            kind: stage.kind,
+            changed: next != output,
+            note,
+        });
+        output = next;
+    }
+    PipelineResult {
+        output,
+        blocked: None,
+        stages,
+    }
+}
+
+/// Runs the pipeline over a capture's body and source URL.
+pub fn apply(cfg: &PipelineConfig, capture: Capture) -> Result<Capture, String> {
+    let result = run(cfg, &capture.body);
+    if let Some(reason) = result.blocked {
+        return Err(format!("Capture blocked by pipeline ({})", reason));
+    }
+    let strip_urls = cfg
+        .stages
+        .iter()
+        .any(|s| s.enabled && s.kind == StageKind::StripTracking);
+    Ok(Capture {
+        body: result.output,
+        source_url: match capture.source_url {
+            Some(url) if strip_urls => Some(strip_tracking_url(&url)),
+            other => other,
+        },
+        ..capture
+    })
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Runs `sample` through the pipeline (the saved config, or `config` if
+/// given) and reports what each stage did.
+#[tauri::command]
+pub fn test_pipeline(
+    app: tauri::AppHandle,
+    sample: String,
+    config: Option<PipelineConfig>,
+) -> PipelineResult {
+    let cfg = config.unwrap_or_else(|| crate::config::current(&app).pipeline);
+    run(&cfg, &sample)
+}
@@ -0,0 +1,255 @@
+// Power — throttle background work on battery.
+//
+// The battery is read every POLL_INTERVAL:
+//   Linux    /sys/class/power_supply
+//   macOS    `pmset -g batt`
+//   Windows  GetSystemPowerStatus
+// Saver mode starts when running on battery at or below
+// `power.battery_threshold` percent (100 = any battery level), unless
+// `set_power_override` forces a mode. While saving:
+//   - the backend is told via POST /power and shrinks its worker pool
+//   - shell polling (clipboard, meeting snapshots, resource checks) slows down
+//   - AI work waits: automatic voice-note transcription, Ollama autostart;
+//     automatic OCR is off
+//   - scheduled backups and the nightly self-test skip
+//   - the frontend polls health at `health_interval_ms`
+// Changes are emitted as "power-profile-changed".
+
+use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Manager};
+
+use crate::{backend, config};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+const HEALTH_INTERVAL_MS: u64 = 30_000;
+const SAVER_HEALTH_INTERVAL_MS: u64 = 120_000;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PowerMode {
+    /// Follow the battery.
+    #[default]
+    Auto,
+    Normal,
+    Saver,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct PowerConfig {
+    pub throttle_on_battery: bool,
+    /// Only throttle at or below this charge, in percent.
+    pub battery_threshold: u8,
+    /// Manual override set with `set_power_override`.
+    pub mode: PowerMode,
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        Self {
+            throttle_on_battery: true,
+            battery_threshold: 100,
+            mode: PowerMode::Auto,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct BatteryState {
+    /// False on desktops and whenever the battery can't be read.
+    pub present: bool,
+    pub on_battery: bool,
+    pub percent: Option<u8>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct PowerProfile {
+    saver: bool,
+    mode: PowerMode,
+    battery: BatteryState,
+    health_interval_ms: u64,
+}
+
+static SAVER: AtomicBool = AtomicBool::new(false);
+static CURRENT: Mutex<Option<PowerProfile>> = Mutex::new(None);
+/// Backend port that last received the saver flag.
+static NOTIFIED_PORT: AtomicU16 = AtomicU16::new(0);
+
+#[cfg(target_os = "linux")]
+fn battery() -> BatteryState {
+    let mut state = BatteryState::default();
+    let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else {
+        return state;
+    };
+    let mut mains_online = false;
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        let read = |f: &str| {
+            std::fs::read_to_string(dir.join(f))
+                .map(|s| s.trim().to_string())
+                .unwrap_or_default()
+        };
+        match read("type").as_str() {
+            "Mains" | "USB" => mains_online |= read("online") == "1",
+            // Peripheral batteries (mice, headsets) report scope "Device".
+            "Battery" if read("scope") != "Device" => {
+                state.present = true;
+                state.on_battery |= read("status") == "Discharging";
+                state.percent = read("capacity").parse().ok().or(state.percent);
+            }
+            _ => {}
+        }
+    }
+    state.on_battery &= !mains_online;
+    state
+}
+
+#[cfg(target_os = "macos")]
+fn battery() -> BatteryState {
+    let Ok(output) = tauri::api::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+    else {
+        return BatteryState::default();
+    };
+    let text = output.stdout;
+    // Now drawing from 'Battery Power'
+    //  -InternalBattery-0 (id=...)	85%; discharging; 4:12 remaining
+    let percent = text
+        .split(|c: char| c.is_whitespace() || c == ';')
+        .find_map(|word| word.strip_suffix('%')?.parse().ok());
+    BatteryState {
+        present: text.contains("InternalBattery"),
+        on_battery: text.contains("'Battery Power'"),
+        percent,
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn battery() -> BatteryState {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status = SYSTEM_POWER_STATUS::default();
+    if unsafe { GetSystemPowerStatus(&mut status) }.is_err() {
+        return BatteryState::default();
+    }
+    // BatteryFlag 128 = no system battery, 255 = unknown.
+    let present = status.BatteryFlag & 128 == 0 && status.BatteryFlag != 255;
+    BatteryState {
+        present,
+        on_battery: present && status.ACLineStatus == 0,
+        percent: (status.BatteryLifePercent <= 100).then_some(status.BatteryLifePercent),
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn battery() -> BatteryState {
+    BatteryState::default()
+}
+
+fn profile(cfg: &PowerConfig, battery: BatteryState) -> PowerProfile {
+    let saver = match cfg.mode {
+        PowerMode::Normal => false,
+        PowerMode::Saver => true,
+        PowerMode::Auto => {
+            cfg.throttle_on_battery
+                && battery.on_battery
+                && battery.percent.map_or(true, |p| p <= cfg.battery_threshold)
+        }
+    };
+    PowerProfile {
+        saver,
+        mode: cfg.mode,
+        battery,
+        health_interval_ms: if saver {
+            SAVER_HEALTH_INTERVAL_MS
+        } else {
+            HEALTH_INTERVAL_MS
+        },
+    }
+}
+
+/// True while background work should hold back.
+pub fn saving() -> bool {
+    SAVER.load(Ordering::SeqCst)
+}
+
+/// Factor applied to shell polling intervals.
+pub fn poll_factor() -> u64 {
+    if saving() {
+        2
+    } else {
+        1
+    }
+}
+
+/// Sends the saver flag to the backend when it changed or the backend did.
+async fn notify_backend(saver: bool, changed: bool) {
+    let port = crate::BACKEND_PORT.load(Ordering::SeqCst);
+    if port == 0 || (!changed && NOTIFIED_PORT.load(Ordering::SeqCst) == port) {
+        return;
+    }
+    match backend::post("/power", &json!({ "saver": saver })).await {
+        Ok(_) => NOTIFIED_PORT.store(port, Ordering::SeqCst),
+        Err(e) => log::debug!("Could not send power state to backend: {}", e),
+    }
+}
+
+/// Re-reads the battery and publishes the profile if it changed.
+async fn refresh(app: &AppHandle) -> PowerProfile {
+    let cfg = config::current(app).power;
+    let next = profile(
+        &cfg,
+        tauri::async_runtime::spawn_blocking(battery)
+            .await
+            .unwrap_or_default(),
+    );
+    let changed = {
+        let mut current = CURRENT.lock().unwrap();
+        let changed = current.as_ref() != Some(&next);
+        *current = Some(next.clone());
+        changed
+    };
+    let saver_changed = SAVER.swap(next.saver, Ordering::SeqCst) != next.saver;
+    if saver_changed {
+        log::info!(
+            "Power saver {} (on battery: {}, {:?}%)",
+            if next.saver { "on" } else { "off" },
+            next.battery.on_battery,
+            next.battery.percent
+        );
+    }
+    if changed {
+        app.emit_all("power-profile-changed", &next).ok();
+    }
+    notify_backend(next.saver, saver_changed).await;
+    next
+}
+
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            refresh(&handle).await;
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub async fn get_power_profile(app: AppHandle) -> PowerProfile {
+    refresh(&app).await
+}
+
+/// Forces saver or normal mode; `auto` follows the battery again.
+#[tauri::command]
+pub async fn set_power_override(app: AppHandle, mode: PowerMode) -> Result<PowerProfile, String> {
+    config::update(&app, |c| c.power.mode = mode)?;
+    Ok(refresh(&app).await)
+}
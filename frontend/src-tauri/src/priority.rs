@@ -0,0 +1,96 @@
+// Backend priority — run the sidecar below the UI so indexing and embedding
+// jobs don't compete with typing.
+//
+//   normal  nice 0   / NORMAL_PRIORITY_CLASS
+//   low     nice 10  / BELOW_NORMAL_PRIORITY_CLASS (default)
+//   idle    nice 19  / IDLE_PRIORITY_CLASS
+//
+// The level is applied to the sidecar right after spawn; processes it starts
+// later inherit it. `set_backend_priority` also re-applies it to the running
+// tree. Unix only lets unprivileged processes lower priority, so raising it
+// again respawns the sidecar instead.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::{config, resources};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendPriority {
+    Normal,
+    #[default]
+    Low,
+    Idle,
+}
+
+#[cfg(unix)]
+mod platform {
+    use super::BackendPriority;
+
+    pub fn set(pid: u32, level: BackendPriority) -> Result<(), String> {
+        let nice = match level {
+            BackendPriority::Normal => 0,
+            BackendPriority::Low => 10,
+            BackendPriority::Idle => 19,
+        };
+        // SAFETY: setpriority only reads its integer arguments.
+        let rc = unsafe { libc::setpriority(libc::PRIO_PROCESS as _, pid as _, nice) };
+        if rc == 0 {
+            Ok(())
+        } else {
+            Err(std::io::Error::last_os_error().to_string())
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::BackendPriority;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, SetPriorityClass, BELOW_NORMAL_PRIORITY_CLASS, IDLE_PRIORITY_CLASS,
+        NORMAL_PRIORITY_CLASS, PROCESS_SET_INFORMATION,
+    };
+
+    pub fn set(pid: u32, level: BackendPriority) -> Result<(), String> {
+        let class = match level {
+            BackendPriority::Normal => NORMAL_PRIORITY_CLASS,
+            BackendPriority::Low => BELOW_NORMAL_PRIORITY_CLASS,
+            BackendPriority::Idle => IDLE_PRIORITY_CLASS,
+        };
+        unsafe {
+            let process =
+                OpenProcess(PROCESS_SET_INFORMATION, false, pid).map_err(|e| e.to_string())?;
+            let result = SetPriorityClass(process, class).map_err(|e| e.to_string());
+            CloseHandle(process).ok();
+            result
+        }
+    }
+}
+
+/// Applies `level` to `pid` and every process below it.
+pub fn apply(pid: u32, level: BackendPriority) -> Result<(), String> {
+    for pid in resources::process_tree(pid) {
+        platform::set(pid, level).map_err(|e| format!("Could not set priority of {pid}: {e}"))?;
+    }
+    Ok(())
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Saves `level` and applies it to the running sidecar (respawning it when
+/// the OS refuses to raise priority).
+#[tauri::command]
+pub async fn set_backend_priority(app: AppHandle, level: BackendPriority) -> Result<(), String> {
+    config::update(&app, |c| c.backend.priority = level)?;
+    let Some(pid) = crate::backend_pid(&app) else {
+        return Ok(());
+    };
+    if let Err(e) = apply(pid, level) {
+        log::info!("{}; respawning the backend at {:?} priority", e, level);
+        let port = crate::respawn_backend(&app).await?;
+        app.emit_all("backend-ready", port).ok();
+    }
+    log::info!("Backend priority set to {:?}", level);
+    Ok(())
+}
@@ -0,0 +1,288 @@
+// Profiles — separate workspaces, each with its own database.
+//
+// A profile is a data directory (see `data_dir` in lib.rs): the default one
+// is app_dir() itself, as before profiles existed, and the others live in
+// app_dir()/profiles/<id>/, unless moved elsewhere (see relocate.rs, which
+// records the new folder as the profile's `path`). Everything about the
+// notes follows the profile: the database and its backups, attachments,
+// history, reminders, the outbox, sync state and the shell settings (see
+// config.rs), with their secrets under a keyring service of their own (see
+// keyring.rs). Downloaded models, sidecar updates, crash reports and the
+// instance endpoint stay shared in app_dir(). The list and the active
+// profile are kept in app_dir()/profiles.json.
+//
+// The profile is fixed for the life of the process, since the shell caches
+// its data, so `switch_profile` stops the sidecar and relaunches the app
+// against the selected profile. The tray lists the profiles in a "Profile"
+// submenu once there is more than one.
+
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::{jobs, keyring, tray};
+
+const DEFAULT: &str = "default";
+const MAX_NAME: usize = 40;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Profile {
+    /// Its directory name; "default" for app_dir() itself.
+    pub id: String,
+    pub name: String,
+    /// Its data folder when moved away from home(id).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct Registry {
+    active: String,
+    profiles: Vec<Profile>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ProfileInfo {
+    #[serde(flatten)]
+    profile: Profile,
+    path: String,
+    /// Moved out of its original folder; deleting it keeps that folder.
+    moved: bool,
+    active: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ProfilesStatus {
+    active: String,
+    profiles: Vec<ProfileInfo>,
+}
+
+/// The profile this process runs on.
+static ACTIVE: Lazy<Profile> = Lazy::new(|| {
+    let registry = load();
+    let by_id = |id: &str| registry.profiles.iter().find(|p| p.id == id).cloned();
+    by_id(&registry.active)
+        .or_else(|| by_id(DEFAULT))
+        .expect("the default profile is always listed")
+});
+
+fn registry_path() -> PathBuf {
+    crate::app_dir().join("profiles.json")
+}
+
+/// The saved list, always starting with the default profile.
+fn load() -> Registry {
+    let mut registry: Registry = std::fs::read_to_string(registry_path())
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default();
+    if !registry.profiles.iter().any(|p| p.id == DEFAULT) {
+        registry.profiles.insert(
+            0,
+            Profile {
+                id: DEFAULT.into(),
+                name: "Default".into(),
+                path: None,
+            },
+        );
+    }
+    registry
+}
+
+fn save(registry: &Registry) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(registry).map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(crate::app_dir()).ok();
+    std::fs::write(registry_path(), text).map_err(|e| format!("Could not save profiles: {e}"))
+}
+
+/// Where a profile's data lives unless it has been moved.
+fn home(id: &str) -> PathBuf {
+    if id == DEFAULT {
+        crate::app_dir()
+    } else {
+        crate::app_dir().join("profiles").join(id)
+    }
+}
+
+fn dir_of(profile: &Profile) -> PathBuf {
+    profile.path.clone().unwrap_or_else(|| home(&profile.id))
+}
+
+/// The active profile's data directory.
+pub fn dir() -> PathBuf {
+    dir_of(&ACTIVE)
+}
+
+/// The active profile's data directory before any move.
+pub fn home_dir() -> PathBuf {
+    home(active())
+}
+
+pub fn active() -> &'static str {
+    &ACTIVE.id
+}
+
+/// Records `dir` as the active profile's data folder, from the next launch.
+pub fn set_dir(dir: &Path) -> Result<(), String> {
+    let mut registry = load();
+    let profile = registry
+        .profiles
+        .iter_mut()
+        .find(|p| p.id == active())
+        .ok_or("No such profile")?;
+    profile.path = (dir != home(active())).then(|| dir.to_path_buf());
+    save(&registry)
+}
+
+/// The keyring service for the active profile's secrets; the default
+/// profile keeps the one from before profiles.
+pub fn keyring_service() -> String {
+    if active() == DEFAULT {
+        keyring::APP_SERVICE.to_string()
+    } else {
+        format!("{} ({})", keyring::APP_SERVICE, active())
+    }
+}
+
+pub fn list() -> Vec<Profile> {
+    load().profiles
+}
+
+/// "Work Notes" → "work-notes".
+fn slug(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// A profile by id or by name.
+fn find<'a>(registry: &'a Registry, name: &str) -> Option<&'a Profile> {
+    registry
+        .profiles
+        .iter()
+        .find(|p| p.id == name || p.name.eq_ignore_ascii_case(name.trim()))
+}
+
+fn status() -> ProfilesStatus {
+    ProfilesStatus {
+        active: active().to_string(),
+        profiles: list()
+            .into_iter()
+            .map(|profile| ProfileInfo {
+                path: dir_of(&profile).to_string_lossy().to_string(),
+                moved: profile.path.is_some(),
+                active: profile.id == active(),
+                profile,
+            })
+            .collect(),
+    }
+}
+
+/// Saves `name` as the active profile, stops the sidecar and relaunches.
+pub fn switch(app: &AppHandle, name: &str) -> Result<(), String> {
+    let mut registry = load();
+    let profile = find(&registry, name).ok_or("No such profile")?.clone();
+    let id = profile.id.clone();
+    if id == active() {
+        return Ok(());
+    }
+    if jobs::running().0 > 0 {
+        return Err("Wait for background jobs to finish before switching profiles".into());
+    }
+    std::fs::create_dir_all(dir_of(&profile))
+        .map_err(|e| format!("Could not create the profile folder: {e}"))?;
+    registry.active = id.clone();
+    save(&registry)?;
+    log::info!("Switching to profile {:?}", id);
+    crate::stop_backend(app);
+    app.restart();
+    Ok(())
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn list_profiles() -> ProfilesStatus {
+    status()
+}
+
+/// Adds an empty profile; switch to it to start using it.
+#[tauri::command]
+pub fn create_profile(app: AppHandle, name: String) -> Result<ProfilesStatus, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() || name.chars().count() > MAX_NAME {
+        return Err(format!("A profile name is 1 to {MAX_NAME} characters"));
+    }
+    let id = slug(&name);
+    if id.is_empty() {
+        return Err("A profile name needs a letter or a digit".into());
+    }
+    let mut registry = load();
+    if id == DEFAULT || find(&registry, &name).is_some() || find(&registry, &id).is_some() {
+        return Err(format!("There is already a profile called {name:?}"));
+    }
+    std::fs::create_dir_all(home(&id))
+        .map_err(|e| format!("Could not create the profile folder: {e}"))?;
+    registry.profiles.push(Profile {
+        id: id.clone(),
+        name,
+        path: None,
+    });
+    save(&registry)?;
+    log::info!("Profile {:?} created", id);
+    tray::refresh(&app);
+    Ok(status())
+}
+
+/// Stops the sidecar and relaunches the app on profile `name` (or id).
+#[tauri::command]
+pub fn switch_profile(app: AppHandle, name: String) -> Result<(), String> {
+    switch(&app, &name)
+}
+
+/// Removes a profile and its data folder. Not the default or active one.
+/// A moved profile's folder (see relocate.rs) is left where it is: it may be
+/// any folder of the user's that holds a pinup.db, such as a synced one, so
+/// only the profile's original folder goes.
+#[tauri::command]
+pub async fn delete_profile(app: AppHandle, id: String) -> Result<ProfilesStatus, String> {
+    let mut registry = load();
+    if id == DEFAULT || id == active() {
+        return Err("The default and the active profile can't be deleted".into());
+    }
+    let profile = registry
+        .profiles
+        .iter()
+        .find(|p| p.id == id)
+        .cloned()
+        .ok_or("No such profile")?;
+    registry.profiles.retain(|p| p.id != id);
+    save(&registry)?;
+    if let Some(moved) = &profile.path {
+        log::info!(
+            "Profile {:?} removed; its data stays in {}",
+            id,
+            moved.display()
+        );
+    }
+    let dir = home(&profile.id);
+    tauri::async_runtime::spawn_blocking(move || match std::fs::remove_dir_all(&dir) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(format!("Could not delete {}: {e}", dir.display()))
+        }
+        _ => Ok(()),
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    log::info!("Profile {:?} deleted", id);
+    tray::refresh(&app);
+    Ok(status())
+}
@@ -0,0 +1,303 @@
+// Proxy — pass the system HTTP(S) proxy to our clients and the sidecar.
+//
+// Detection order: HTTP(S)_PROXY in our own environment, then the OS
+// setting:
+//   macOS    `scutil --proxy`
+//   Windows  Internet Settings in HKCU (via `reg query`)
+//   Linux    GNOME's org.gnome.system.proxy (manual mode only)
+// PAC files and auto-detect are not evaluated. `proxy.mode` overrides it:
+// "manual" uses `proxy.http` / `proxy.https` / `proxy.no_proxy`, "none"
+// disables proxies. The result goes to outbound reqwest clients
+// (`client_builder`) and to the sidecar as HTTP_PROXY/HTTPS_PROXY/NO_PROXY;
+// loopback is always exempt so the shell can still reach the backend.
+// Detection runs once at startup and again on `get_proxy_settings(refresh)`.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::{certs, config};
+
+const LOOPBACK: &str = "127.0.0.1,localhost,::1";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyMode {
+    #[default]
+    System,
+    Manual,
+    None,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ProxyConfig {
+    pub mode: ProxyMode,
+    /// Proxy URLs for manual mode, e.g. "http://proxy.corp:8080".
+    pub http: Option<String>,
+    pub https: Option<String>,
+    /// Comma-separated hosts/domains that bypass the proxy.
+    pub no_proxy: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct ProxySettings {
+    http: Option<String>,
+    https: Option<String>,
+    no_proxy: Option<String>,
+    /// "env", "system", "manual" or "none".
+    source: &'static str,
+}
+
+static DETECTED: Mutex<Option<ProxySettings>> = Mutex::new(None);
+
+fn proxy_url(host: &str, port: &str) -> Option<String> {
+    let host = host.trim();
+    if host.is_empty() {
+        return None;
+    }
+    let port = port.trim();
+    let url = if host.contains("://") {
+        host.to_string()
+    } else {
+        format!("http://{}", host)
+    };
+    Some(if port.is_empty() || port == "0" {
+        url
+    } else {
+        format!("{}:{}", url, port)
+    })
+}
+
+fn from_env() -> Option<ProxySettings> {
+    let var = |names: [&str; 2]| {
+        names
+            .iter()
+            .find_map(|n| std::env::var(n).ok().filter(|v| !v.is_empty()))
+    };
+    let http = var(["HTTP_PROXY", "http_proxy"]);
+    let https = var(["HTTPS_PROXY", "https_proxy"]);
+    (http.is_some() || https.is_some()).then(|| ProxySettings {
+        http,
+        https,
+        no_proxy: var(["NO_PROXY", "no_proxy"]),
+        source: "env",
+    })
+}
+
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = tauri::api::process::Command::new(program)
+        .args(args)
+        .output()
+        .ok()?;
+    output.status.success().then_some(output.stdout)
+}
+
+#[cfg(target_os = "macos")]
+fn from_os() -> Option<ProxySettings> {
+    let out = run("scutil", &["--proxy"])?;
+    let mut values = std::collections::HashMap::new();
+    let mut exceptions = Vec::new();
+    let mut in_exceptions = false;
+    for line in out.lines() {
+        let line = line.trim();
+        if line.starts_with("ExceptionsList") {
+            in_exceptions = true;
+        } else if in_exceptions && line == "}" {
+            in_exceptions = false;
+        } else if let Some((key, value)) = line.split_once(" : ") {
+            if in_exceptions {
+                exceptions.push(value.trim().to_string());
+            } else {
+                values.insert(key.trim().to_string(), value.trim().to_string());
+            }
+        }
+    }
+    let get = |k: &str| values.get(k).map(String::as_str).unwrap_or("");
+    let http = (get("HTTPEnable") == "1")
+        .then(|| proxy_url(get("HTTPProxy"), get("HTTPPort")))
+        .flatten();
+    let https = (get("HTTPSEnable") == "1")
+        .then(|| proxy_url(get("HTTPSProxy"), get("HTTPSPort")))
+        .flatten();
+    (http.is_some() || https.is_some()).then(|| ProxySettings {
+        http,
+        https,
+        no_proxy: (!exceptions.is_empty()).then(|| exceptions.join(",")),
+        source: "system",
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn from_os() -> Option<ProxySettings> {
+    let out = run(
+        "reg",
+        &[
+            "query",
+            "HKCU\\Software\\Microsoft\\Windows\\CurrentVersion\\Internet Settings",
+        ],
+    )?;
+    // "    ProxyServer    REG_SZ    proxy:8080"
+    let value = |name: &str| {
+        out.lines().find_map(|line| {
+            let mut parts = line.split_whitespace();
+            (parts.next()? == name).then(|| parts.skip(1).collect::<Vec<_>>().join(" "))
+        })
+    };
+    if value("ProxyEnable").as_deref() != Some("0x1") {
+        return None;
+    }
+    let server = value("ProxyServer")?;
+    // Either "host:port" for everything or "http=host:port;https=host:port".
+    let (mut http, mut https) = (None, None);
+    if server.contains('=') {
+        for part in server.split(';') {
+            match part.split_once('=') {
+                Some(("http", addr)) => http = proxy_url(addr, ""),
+                Some(("https", addr)) => https = proxy_url(addr, ""),
+                _ => {}
+            }
+        }
+    } else {
+        http = proxy_url(&server, "");
+        https = http.clone();
+    }
+    let no_proxy = value("ProxyOverride").map(|list| {
+        list.split(';')
+            .map(|h| if h == "<local>" { "localhost" } else { h })
+            .collect::<Vec<_>>()
+            .join(",")
+    });
+    Some(ProxySettings {
+        http,
+        https,
+        no_proxy,
+        source: "system",
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn from_os() -> Option<ProxySettings> {
+    let get = |schema: &str, key: &str| {
+        run("gsettings", &["get", schema, key]).map(|v| v.trim().trim_matches('\'').to_string())
+    };
+    if get("org.gnome.system.proxy", "mode")? != "manual" {
+        return None;
+    }
+    let server = |kind: &str| {
+        let schema = format!("org.gnome.system.proxy.{}", kind);
+        proxy_url(&get(&schema, "host")?, &get(&schema, "port")?)
+    };
+    let ignore = get("org.gnome.system.proxy", "ignore-hosts").map(|list| {
+        list.trim_matches(|c| c == '[' || c == ']')
+            .split(',')
+            .map(|h| h.trim().trim_matches('\''))
+            .filter(|h| !h.is_empty())
+            .collect::<Vec<_>>()
+            .join(",")
+    });
+    Some(ProxySettings {
+        http: server("http"),
+        https: server("https"),
+        no_proxy: ignore.filter(|s| !s.is_empty()),
+        source: "system",
+    })
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn from_os() -> Option<ProxySettings> {
+    None
+}
+
+fn detect() -> ProxySettings {
+    from_env().or_else(from_os).unwrap_or(ProxySettings {
+        source: "none",
+        ..Default::default()
+    })
+}
+
+fn detected() -> ProxySettings {
+    DETECTED.lock().unwrap().get_or_insert_with(detect).clone()
+}
+
+/// Proxy settings after applying `proxy.mode`.
+pub fn effective(cfg: &ProxyConfig) -> ProxySettings {
+    match cfg.mode {
+        ProxyMode::System => detected(),
+        ProxyMode::Manual => ProxySettings {
+            http: cfg.http.clone(),
+            https: cfg.https.clone(),
+            no_proxy: cfg.no_proxy.clone(),
+            source: "manual",
+        },
+        ProxyMode::None => ProxySettings {
+            source: "none",
+            ..Default::default()
+        },
+    }
+}
+
+fn no_proxy_list(settings: &ProxySettings) -> String {
+    match &settings.no_proxy {
+        Some(list) => format!("{},{}", LOOPBACK, list),
+        None => LOOPBACK.to_string(),
+    }
+}
+
+/// A reqwest builder for requests leaving the machine, with the custom CAs
+/// from `certs` trusted as well.
+pub fn client_builder(app: &AppHandle) -> reqwest::ClientBuilder {
+    let settings = effective(&config::current(app).proxy);
+    let mut builder = certs::add_roots(reqwest::Client::builder()).no_proxy();
+    let no_proxy = reqwest::NoProxy::from_string(&no_proxy_list(&settings));
+    if let Some(url) = &settings.http {
+        match reqwest::Proxy::http(url) {
+            Ok(proxy) => builder = builder.proxy(proxy.no_proxy(no_proxy.clone())),
+            Err(e) => log::warn!("Ignoring invalid HTTP proxy {}: {}", url, e),
+        }
+    }
+    if let Some(url) = &settings.https {
+        match reqwest::Proxy::https(url) {
+            Ok(proxy) => builder = builder.proxy(proxy.no_proxy(no_proxy)),
+            Err(e) => log::warn!("Ignoring invalid HTTPS proxy {}: {}", url, e),
+        }
+    }
+    builder
+}
+
+/// Sidecar environment carrying the proxy (both spellings, as tools differ).
+pub fn backend_env(cfg: &ProxyConfig) -> Vec<(String, String)> {
+    let settings = effective(cfg);
+    let mut env = Vec::new();
+    for (name, value) in [
+        ("HTTP_PROXY", &settings.http),
+        ("HTTPS_PROXY", &settings.https),
+    ] {
+        if let Some(value) = value {
+            env.push((name.to_string(), value.clone()));
+            env.push((name.to_lowercase(), value.clone()));
+        }
+    }
+    let no_proxy = no_proxy_list(&settings);
+    env.push(("NO_PROXY".into(), no_proxy.clone()));
+    env.push(("no_proxy".into(), no_proxy));
+    env
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Effective proxy settings; `refresh` re-reads the OS configuration.
+/// Changes apply to new downloads at once and to the sidecar on restart.
+#[tauri::command]
+pub async fn get_proxy_settings(
+    app: AppHandle,
+    refresh: Option<bool>,
+) -> Result<ProxySettings, String> {
+    if refresh.unwrap_or(false) {
+        let fresh = tauri::async_runtime::spawn_blocking(detect)
+            .await
+            .map_err(|e| e.to_string())?;
+        *DETECTED.lock().unwrap() = Some(fresh);
+    }
+    Ok(effective(&config::current(&app).proxy))
+}
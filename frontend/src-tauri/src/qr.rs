@@ -0,0 +1,485 @@
+// QR codes — a minimal encoder for pairing codes and shared snippets.
+//
+// Byte mode at error correction level M, any version (up to 2331 bytes), per
+// ISO/IEC 18004: data split into blocks with Reed-Solomon error correction
+// over GF(256), interleaved into the symbol around the function patterns,
+// then each of the eight masks scored by the standard penalty rules and the
+// lowest kept. Rendered as SVG for the frontend to show as is, or as PNG.
+//
+// A snippet can be shared as a QR code: its body when it fits, otherwise its
+// source URL, so a short note or a Wi-Fi password reaches a phone's camera
+// without any sync set up.
+
+use base64::Engine;
+use serde::Serialize;
+
+use crate::backend;
+
+const MAX_VERSION: usize = 40;
+/// Error correction codewords per block at level M, by version.
+const EC_PER_BLOCK: [usize; MAX_VERSION + 1] = [
+    0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28, 28,
+    28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+];
+/// Blocks at level M, by version.
+const BLOCKS: [usize; MAX_VERSION + 1] = [
+    0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21, 23,
+    25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+];
+/// Format bits for level M.
+const LEVEL_M: u32 = 0;
+/// Light modules around the symbol, as the standard asks.
+const QUIET_ZONE: usize = 4;
+/// Pixels per module in shared PNGs.
+const PNG_SCALE: u32 = 8;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SnippetQr {
+    /// Base64.
+    png: String,
+    /// "body", or "link" when the body didn't fit.
+    content: &'static str,
+}
+
+pub struct QrCode {
+    size: usize,
+    modules: Vec<bool>,
+    /// Finder, timing, alignment, format and version modules.
+    function: Vec<bool>,
+}
+
+/// Modules that can hold data in a symbol of `version`.
+fn raw_modules(version: usize) -> usize {
+    let mut count = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let align = version / 7 + 2;
+        count -= (25 * align - 10) * align - 55;
+        if version >= 7 {
+            count -= 36;
+        }
+    }
+    count
+}
+
+fn data_codewords(version: usize) -> usize {
+    raw_modules(version) / 8 - EC_PER_BLOCK[version] * BLOCKS[version]
+}
+
+fn alignment_positions(version: usize) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+    let align = version / 7 + 2;
+    let step = (version * 8 + align * 3 + 5) / (align * 4 - 4) * 2;
+    let last = version * 4 + 17 - 7;
+    let mut positions = vec![6];
+    positions.extend((0..align - 1).rev().map(|i| last - i * step));
+    positions
+}
+
+// ── Reed-Solomon ───────────────────────────────────────────────────────────
+fn gf_mul(x: u8, y: u8) -> u8 {
+    let mut z: u32 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x11D);
+        z ^= ((y as u32 >> i) & 1) * x as u32;
+    }
+    z as u8
+}
+
+fn rs_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0u8; degree];
+    result[degree - 1] = 1;
+    let mut root = 1u8;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_mul(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_mul(root, 0x02);
+    }
+    result
+}
+
+fn rs_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u8; divisor.len()];
+    for &byte in data {
+        let factor = byte ^ result.remove(0);
+        result.push(0);
+        for (r, &d) in result.iter_mut().zip(divisor) {
+            *r ^= gf_mul(d, factor);
+        }
+    }
+    result
+}
+
+// ── Encoding ───────────────────────────────────────────────────────────────
+fn push_bits(bits: &mut Vec<bool>, value: u32, count: usize) {
+    for i in (0..count).rev() {
+        bits.push((value >> i) & 1 != 0);
+    }
+}
+
+/// Data codewords: mode, length, bytes, terminator and padding.
+fn encode_data(text: &[u8], version: usize) -> Vec<u8> {
+    let capacity = data_codewords(version);
+    let mut bits = Vec::with_capacity(capacity * 8);
+    push_bits(&mut bits, 0b0100, 4);
+    let length_bits = if version <= 9 { 8 } else { 16 };
+    push_bits(&mut bits, text.len() as u32, length_bits);
+    for &byte in text {
+        push_bits(&mut bits, byte as u32, 8);
+    }
+    let terminator = (capacity * 8 - bits.len()).min(4);
+    push_bits(&mut bits, 0, terminator);
+    let padding = (8 - bits.len() % 8) % 8;
+    push_bits(&mut bits, 0, padding);
+    let mut bytes: Vec<u8> = bits
+        .chunks(8)
+        .map(|chunk| chunk.iter().fold(0u8, |b, &bit| b << 1 | bit as u8))
+        .collect();
+    for pad in [0xEC, 0x11].into_iter().cycle() {
+        if bytes.len() >= capacity {
+            break;
+        }
+        bytes.push(pad);
+    }
+    bytes
+}
+
+/// Splits data into blocks, adds error correction and interleaves.
+fn add_error_correction(data: &[u8], version: usize) -> Vec<u8> {
+    let blocks = BLOCKS[version];
+    let ec_len = EC_PER_BLOCK[version];
+    let raw = raw_modules(version) / 8;
+    let short_blocks = blocks - raw % blocks;
+    let short_len = raw / blocks;
+    let divisor = rs_divisor(ec_len);
+
+    let mut split = Vec::with_capacity(blocks);
+    let mut at = 0;
+    for i in 0..blocks {
+        let len = short_len - ec_len + usize::from(i >= short_blocks);
+        let mut block = data[at..at + len].to_vec();
+        at += len;
+        let ec = rs_remainder(&block, &divisor);
+        if i < short_blocks {
+            // Keeps columns aligned with the long blocks; skipped below.
+            block.push(0);
+        }
+        block.extend(ec);
+        split.push(block);
+    }
+
+    let mut out = Vec::with_capacity(raw);
+    for i in 0..split[0].len() {
+        for (j, block) in split.iter().enumerate() {
+            if i != short_len - ec_len || j >= short_blocks {
+                out.push(block[i]);
+            }
+        }
+    }
+    out
+}
+
+impl QrCode {
+    /// Encodes `text` in the smallest version that holds it.
+    pub fn encode(text: &str) -> Result<Self, String> {
+        let bytes = text.as_bytes();
+        let version = (1..=MAX_VERSION)
+            .find(|&v| {
+                let header = 4 + if v <= 9 { 8 } else { 16 };
+                header + bytes.len() * 8 <= data_codewords(v) * 8
+            })
+            .ok_or("Too much data for a QR code")?;
+        let codewords = add_error_correction(&encode_data(bytes, version), version);
+
+        let size = version * 4 + 17;
+        let mut qr = QrCode {
+            size,
+            modules: vec![false; size * size],
+            function: vec![false; size * size],
+        };
+        qr.draw_function_patterns(version);
+        qr.draw_codewords(&codewords);
+        let best = (0..8)
+            .min_by_key(|&mask| {
+                qr.apply_mask(mask);
+                qr.draw_format(mask);
+                let score = qr.penalty();
+                qr.apply_mask(mask);
+                score
+            })
+            .unwrap_or(0);
+        qr.apply_mask(best);
+        qr.draw_format(best);
+        Ok(qr)
+    }
+
+    fn get(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.function[y * self.size + x] = true;
+    }
+
+    fn draw_function_patterns(&mut self, version: usize) {
+        let size = self.size;
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+        for (cx, cy) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            for dy in -4i32..=4 {
+                for dx in -4i32..=4 {
+                    let (x, y) = (cx as i32 + dx, cy as i32 + dy);
+                    if (0..size as i32).contains(&x) && (0..size as i32).contains(&y) {
+                        let dist = dx.abs().max(dy.abs());
+                        self.set_function(x as usize, y as usize, dist != 2 && dist != 4);
+                    }
+                }
+            }
+        }
+        let positions = alignment_positions(version);
+        let last = positions.len().saturating_sub(1);
+        for (i, &cx) in positions.iter().enumerate() {
+            for (j, &cy) in positions.iter().enumerate() {
+                // Those overlapping the finder patterns are left out.
+                if [(0, 0), (0, last), (last, 0)].contains(&(i, j)) {
+                    continue;
+                }
+                for dy in -2i32..=2 {
+                    for dx in -2i32..=2 {
+                        let dark = dx.abs().max(dy.abs()) != 1;
+                        self.set_function(
+                            (cx as i32 + dx) as usize,
+                            (cy as i32 + dy) as usize,
+                            dark,
+                        );
+                    }
+                }
+            }
+        }
+        // Reserves the format areas; drawn for real once the mask is known.
+        self.draw_format(0);
+        if version >= 7 {
+            let mut rem = version as u32;
+            for _ in 0..12 {
+                rem = (rem << 1) ^ ((rem >> 11) * 0x1F25);
+            }
+            let bits = (version as u32) << 12 | rem;
+            for i in 0..18 {
+                let dark = (bits >> i) & 1 != 0;
+                let (a, b) = (size - 11 + i % 3, i / 3);
+                self.set_function(a, b, dark);
+                self.set_function(b, a, dark);
+            }
+        }
+    }
+
+    fn draw_format(&mut self, mask: u8) {
+        let data = LEVEL_M << 3 | mask as u32;
+        let mut rem = data;
+        for _ in 0..10 {
+            rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+        }
+        let bits = (data << 10 | rem) ^ 0x5412;
+        let bit = |i: usize| (bits >> i) & 1 != 0;
+        let size = self.size;
+        for i in 0..=5 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    /// Fills the non-function modules in the standard zigzag, two columns at
+    /// a time from the bottom right.
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let size = self.size;
+        let mut i = 0;
+        let mut right = size - 1;
+        loop {
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+            for vert in 0..size {
+                let y = if upward { size - 1 - vert } else { vert };
+                for x in [right, right - 1] {
+                    if !self.function[y * size + x] && i < codewords.len() * 8 {
+                        self.modules[y * size + x] = (codewords[i >> 3] >> (7 - (i & 7))) & 1 != 0;
+                        i += 1;
+                    }
+                }
+            }
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    /// XORs mask `mask` over the data modules; applying it twice undoes it.
+    fn apply_mask(&mut self, mask: u8) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let flip = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                if flip && !self.function[y * self.size + x] {
+                    self.modules[y * self.size + x] ^= true;
+                }
+            }
+        }
+    }
+
+    /// Standard penalty score; lower scans more reliably.
+    fn penalty(&self) -> u32 {
+        let size = self.size;
+        let mut score = 0;
+        let finder = [true, false, true, true, true, false, true];
+        for horizontal in [true, false] {
+            for a in 0..size {
+                let line: Vec<bool> = (0..size)
+                    .map(|b| {
+                        if horizontal {
+                            self.get(b, a)
+                        } else {
+                            self.get(a, b)
+                        }
+                    })
+                    .collect();
+                let mut run = 1;
+                for b in 1..=size {
+                    if b < size && line[b] == line[b - 1] {
+                        run += 1;
+                        continue;
+                    }
+                    if run >= 5 {
+                        score += 3 + (run - 5) as u32;
+                    }
+                    run = 1;
+                }
+                // A finder-like pattern with four light modules on either side.
+                for b in 0..size.saturating_sub(6) {
+                    if line[b..b + 7] != finder {
+                        continue;
+                    }
+                    let light = |from: isize| {
+                        (from..from + 4).all(|i| i < 0 || i >= size as isize || !line[i as usize])
+                    };
+                    if light(b as isize - 4) || light(b as isize + 7) {
+                        score += 40;
+                    }
+                }
+            }
+        }
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let dark = self.get(x, y);
+                if dark == self.get(x + 1, y)
+                    && dark == self.get(x, y + 1)
+                    && dark == self.get(x + 1, y + 1)
+                {
+                    score += 3;
+                }
+            }
+        }
+        let dark = self.modules.iter().filter(|&&m| m).count();
+        let total = size * size;
+        let deviation = (dark * 20).abs_diff(total * 10);
+        score += (deviation / total) as u32 * 10;
+        score
+    }
+
+    /// Most bytes a QR code holds.
+    pub fn capacity() -> usize {
+        data_codewords(MAX_VERSION) - 3
+    }
+
+    /// A PNG, dark modules on white, `scale` pixels per module.
+    pub fn to_png(&self, scale: u32) -> Result<Vec<u8>, String> {
+        let full = (self.size + QUIET_ZONE * 2) as u32 * scale;
+        let image = image::GrayImage::from_fn(full, full, |px, py| {
+            let (x, y) = ((px / scale) as usize, (py / scale) as usize);
+            let inside = (QUIET_ZONE..QUIET_ZONE + self.size).contains(&x)
+                && (QUIET_ZONE..QUIET_ZONE + self.size).contains(&y);
+            let dark = inside && self.get(x - QUIET_ZONE, y - QUIET_ZONE);
+            image::Luma([if dark { 0 } else { 255 }])
+        });
+        let mut png = std::io::Cursor::new(Vec::new());
+        image
+            .write_to(&mut png, image::ImageFormat::Png)
+            .map_err(|e| format!("Could not draw the QR code: {e}"))?;
+        Ok(png.into_inner())
+    }
+
+    /// An SVG drawing, dark modules on white, one unit per module.
+    pub fn to_svg(&self) -> String {
+        let full = self.size + QUIET_ZONE * 2;
+        let mut path = String::new();
+        for y in 0..self.size {
+            for x in 0..self.size {
+                if self.get(x, y) {
+                    path.push_str(&format!("M{},{}h1v1h-1z", x + QUIET_ZONE, y + QUIET_ZONE));
+                }
+            }
+        }
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {full} {full}\" \
+             shape-rendering=\"crispEdges\"><rect width=\"100%\" height=\"100%\" fill=\"#fff\"/>\
+             <path d=\"{path}\" fill=\"#000\"/></svg>"
+        )
+    }
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// A QR code of snippet `id`: its body, or its source URL when the body is
+/// too long for one.
+#[tauri::command]
+pub async fn snippet_to_qr(id: String) -> Result<SnippetQr, String> {
+    let snippet = backend::get(&backend::snippet_path(&id)?).await?;
+    let body = snippet["body"].as_str().unwrap_or_default().trim();
+    let link = snippet["source_url"].as_str().unwrap_or_default().trim();
+    let (text, content) = if !body.is_empty() && body.len() <= QrCode::capacity() {
+        (body, "body")
+    } else if !link.is_empty() {
+        (link, "link")
+    } else if body.is_empty() {
+        return Err("This snippet is empty".into());
+    } else {
+        return Err(format!(
+            "Too long for a QR code ({} bytes; at most {})",
+            body.len(),
+            QrCode::capacity()
+        ));
+    };
+    let png = QrCode::encode(text)?.to_png(PNG_SCALE)?;
+    Ok(SnippetQr {
+        png: base64::engine::general_purpose::STANDARD.encode(png),
+        content,
+    })
+}
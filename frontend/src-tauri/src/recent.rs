@@ -0,0 +1,108 @@
+// Recent snippets — the few most recently used snippets, for shell menus.
+//
+// The tray (see tray.rs), the Dock menu (see dock.rs) and the Windows jump
+// list (see jump_list.rs) offer them. "Used" is the backend's last edit time (recent_activity in
+// /stats). The list is fetched at startup and refreshed when a capture is
+// filed, when the frontend reports a change (`snippets-changed`, sent after
+// it creates, edits, pins or deletes a snippet) and every REFRESH_EVERY for
+// changes made elsewhere (sync, another client). The menus are rebuilt only
+// when the list actually changes.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::{backend, jump_list, suspend, tray};
+
+/// As many as /stats lists.
+pub const MAX: usize = 10;
+const MAX_TITLE: usize = 40;
+const REFRESH_EVERY: Duration = Duration::from_secs(300);
+/// Until the backend answers for the first time.
+const RETRY_AFTER: Duration = Duration::from_secs(10);
+/// Coalesces a burst of changes (an import) into one fetch.
+const DEBOUNCE: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecentSnippet {
+    pub id: String,
+    pub title: String,
+}
+
+impl RecentSnippet {
+    /// The title for a menu item, truncated.
+    pub fn label(&self) -> String {
+        let title = self.title.trim();
+        if title.is_empty() {
+            return "Untitled".into();
+        }
+        match title.char_indices().nth(MAX_TITLE) {
+            Some((end, _)) => format!("{}…", &title[..end]),
+            None => title.to_string(),
+        }
+    }
+}
+
+static RECENT: Mutex<Vec<RecentSnippet>> = Mutex::new(Vec::new());
+static PENDING: AtomicBool = AtomicBool::new(false);
+
+/// Newest first.
+pub fn snippets() -> Vec<RecentSnippet> {
+    RECENT.lock().unwrap().clone()
+}
+
+async fn refresh(app: &AppHandle) -> Result<(), String> {
+    let stats = backend::get("/stats").await?;
+    let list: Vec<RecentSnippet> = stats["recent_activity"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|s| {
+            Some(RecentSnippet {
+                id: s["id"].as_str()?.to_string(),
+                title: s["title"].as_str().unwrap_or_default().to_string(),
+            })
+        })
+        .take(MAX)
+        .collect();
+    let changed = {
+        let mut recent = RECENT.lock().unwrap();
+        let changed = *recent != list;
+        *recent = list;
+        changed
+    };
+    if changed {
+        tray::refresh(app);
+        jump_list::update(app);
+    }
+    Ok(())
+}
+
+/// Refetches the list shortly; calls within DEBOUNCE share one fetch.
+pub fn changed(app: &AppHandle) {
+    if PENDING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(DEBOUNCE).await;
+        PENDING.store(false, Ordering::SeqCst);
+        if let Err(e) = refresh(&app).await {
+            log::debug!("Recent snippets: {}", e);
+        }
+    });
+}
+
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    app.listen_global("snippets-changed", move |_| changed(&handle));
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let fetched = suspend::paused() || refresh(&app).await.is_ok();
+            tokio::time::sleep(if fetched { REFRESH_EVERY } else { RETRY_AFTER }).await;
+        }
+    });
+}
@@ -0,0 +1,301 @@
+// Voice notes — microphone recording via cpal.
+//
+// `start_recording` opens the default input device on a dedicated thread
+// (cpal streams are not Send) and writes 16-bit WAV to
+// data_dir()/recordings/. While recording, "recording-level" events carry
+// the RMS and peak of the last interval for a level meter.
+// `stop_recording` finalizes the file, moves it into the attachment store
+// and files a voice-note snippet linking to it (transcribed later if
+// `whisper.auto_transcribe` is on).
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, SizedSample};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::attachments;
+use crate::capture::{self, Capture};
+use crate::whisper;
+
+const LEVEL_INTERVAL: Duration = Duration::from_millis(100);
+
+type WavWriter = hound::WavWriter<std::io::BufWriter<std::fs::File>>;
+
+/// Running signal statistics, reset every LEVEL_INTERVAL.
+#[derive(Default)]
+struct Meter {
+    sum_squares: f64,
+    peak: f32,
+    samples: u64,
+}
+
+struct Recording {
+    path: PathBuf,
+    started: Instant,
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<Result<(), String>>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RecordingStatus {
+    recording: bool,
+    elapsed_ms: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct VoiceNote {
+    attachment: String,
+    duration_ms: u64,
+    size: u64,
+    snippet: Value,
+}
+
+static RECORDING: Lazy<Mutex<Option<Recording>>> = Lazy::new(|| Mutex::new(None));
+
+fn recordings_dir() -> PathBuf {
+    crate::data_dir().join("recordings")
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    writer: Arc<Mutex<Option<WavWriter>>>,
+    meter: Arc<Mutex<Meter>>,
+) -> Result<cpal::Stream, String>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], _| {
+                let mut meter = meter.lock().unwrap();
+                let mut writer = writer.lock().unwrap();
+                for &sample in data {
+                    let value = f32::from_sample_(sample).clamp(-1.0, 1.0);
+                    meter.sum_squares += (value * value) as f64;
+                    meter.peak = meter.peak.max(value.abs());
+                    meter.samples += 1;
+                    if let Some(writer) = writer.as_mut() {
+                        writer.write_sample((value * i16::MAX as f32) as i16).ok();
+                    }
+                }
+            },
+            |e| log::warn!("Microphone stream error: {}", e),
+            None,
+        )
+        .map_err(|e| format!("Cannot open microphone: {e}"))
+}
+
+/// An open input stream and where its samples go.
+struct Recorder {
+    stream: cpal::Stream,
+    writer: Arc<Mutex<Option<WavWriter>>>,
+    meter: Arc<Mutex<Meter>>,
+}
+
+fn open(path: &Path) -> Result<Recorder, String> {
+    let device = cpal::default_host()
+        .default_input_device()
+        .ok_or("No microphone found")?;
+    let supported = device
+        .default_input_config()
+        .map_err(|e| format!("Microphone unavailable: {e}"))?;
+    let spec = hound::WavSpec {
+        channels: supported.channels(),
+        sample_rate: supported.sample_rate().0,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let writer = hound::WavWriter::create(path, spec)
+        .map_err(|e| format!("Cannot create {}: {}", path.display(), e))?;
+    let writer = Arc::new(Mutex::new(Some(writer)));
+    let meter = Arc::new(Mutex::new(Meter::default()));
+
+    let config = supported.config();
+    let (w, m) = (writer.clone(), meter.clone());
+    let stream = match supported.sample_format() {
+        cpal::SampleFormat::I8 => build_stream::<i8>(&device, &config, w, m),
+        cpal::SampleFormat::I16 => build_stream::<i16>(&device, &config, w, m),
+        cpal::SampleFormat::I32 => build_stream::<i32>(&device, &config, w, m),
+        cpal::SampleFormat::U8 => build_stream::<u8>(&device, &config, w, m),
+        cpal::SampleFormat::U16 => build_stream::<u16>(&device, &config, w, m),
+        cpal::SampleFormat::U32 => build_stream::<u32>(&device, &config, w, m),
+        cpal::SampleFormat::F32 => build_stream::<f32>(&device, &config, w, m),
+        cpal::SampleFormat::F64 => build_stream::<f64>(&device, &config, w, m),
+        other => Err(format!("Unsupported sample format {other}")),
+    }?;
+    stream
+        .play()
+        .map_err(|e| format!("Cannot start microphone: {e}"))?;
+    Ok(Recorder {
+        stream,
+        writer,
+        meter,
+    })
+}
+
+/// Records until `stop` is set. Reports whether the microphone opened on
+/// `ready` before entering the level loop.
+fn record(
+    app: AppHandle,
+    path: PathBuf,
+    stop: Arc<AtomicBool>,
+    ready: mpsc::Sender<Result<(), String>>,
+) -> Result<(), String> {
+    let recorder = match open(&path) {
+        Ok(recorder) => {
+            ready.send(Ok(())).ok();
+            recorder
+        }
+        Err(e) => {
+            std::fs::remove_file(&path).ok();
+            ready.send(Err(e.clone())).ok();
+            return Err(e);
+        }
+    };
+
+    let started = Instant::now();
+    let mut max_peak = 0f32;
+    while !stop.load(Ordering::SeqCst) {
+        std::thread::sleep(LEVEL_INTERVAL);
+        let meter = std::mem::take(&mut *recorder.meter.lock().unwrap());
+        let rms = if meter.samples > 0 {
+            (meter.sum_squares / meter.samples as f64).sqrt() as f32
+        } else {
+            0.0
+        };
+        max_peak = max_peak.max(meter.peak);
+        app.emit_all(
+            "recording-level",
+            json!({
+                "rms": rms,
+                "peak": meter.peak,
+                "elapsed_ms": started.elapsed().as_millis() as u64,
+            }),
+        )
+        .ok();
+    }
+
+    drop(recorder.stream);
+    let writer = recorder.writer.lock().unwrap().take();
+    if let Some(writer) = writer {
+        writer
+            .finalize()
+            .map_err(|e| format!("Could not finish recording: {e}"))?;
+    }
+    log::info!(
+        "Recording stopped after {:?} (peak {:.2})",
+        started.elapsed(),
+        max_peak
+    );
+    Ok(())
+}
+
+fn format_duration(ms: u64) -> String {
+    let secs = ms / 1000;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn start_recording(app: AppHandle) -> Result<(), String> {
+    let mut current = RECORDING.lock().unwrap();
+    if current.is_some() {
+        return Err("Already recording".into());
+    }
+    std::fs::create_dir_all(recordings_dir()).map_err(|e| e.to_string())?;
+    let path = recordings_dir().join(format!(
+        "{}.wav",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let (ready_tx, ready_rx) = mpsc::channel();
+    let thread = {
+        let (app, path, stop) = (app.clone(), path.clone(), stop.clone());
+        std::thread::spawn(move || record(app, path, stop, ready_tx))
+    };
+    ready_rx
+        .recv()
+        .map_err(|_| "Recorder thread exited".to_string())??;
+
+    *current = Some(Recording {
+        path,
+        started: Instant::now(),
+        stop,
+        thread,
+    });
+    app.emit_all("recording-started", ()).ok();
+    Ok(())
+}
+
+/// Stops recording and files the voice note. With `discard`, the audio is
+/// deleted instead.
+#[tauri::command]
+pub async fn stop_recording(
+    app: AppHandle,
+    discard: Option<bool>,
+) -> Result<Option<VoiceNote>, String> {
+    let recording = RECORDING.lock().unwrap().take().ok_or("Not recording")?;
+    let duration_ms = recording.started.elapsed().as_millis() as u64;
+    recording.stop.store(true, Ordering::SeqCst);
+    let path = recording.path.clone();
+    let finished = tauri::async_runtime::spawn_blocking(move || recording.thread.join())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|_| "Recorder thread panicked".to_string())?;
+    app.emit_all("recording-stopped", ()).ok();
+    finished?;
+
+    if discard.unwrap_or(false) {
+        std::fs::remove_file(&path).ok();
+        return Ok(None);
+    }
+
+    let bytes = std::fs::read(&path).map_err(|e| e.to_string())?;
+    let attachment = attachments::store_bytes(&bytes, "wav", None)?;
+    std::fs::remove_file(&path).ok();
+
+    let label = format!("Voice note ({})", format_duration(duration_ms));
+    let capture = Capture {
+        title: Some(format!(
+            "Voice note {}",
+            chrono::Local::now().format("%Y-%m-%d %H:%M")
+        )),
+        body: format!("[{}](attachment:{})", label, attachment.hash),
+        source: "voice".into(),
+        tags: vec!["voice-note".into()],
+        ..Default::default()
+    };
+    let snippet = capture::submit(&app, capture).await?;
+    if let (Some(id), Some(body)) = (snippet["id"].as_str(), snippet["body"].as_str()) {
+        whisper::auto_transcribe(&app, id.into(), body.into(), attachment.hash.clone());
+    }
+    Ok(Some(VoiceNote {
+        attachment: attachment.hash,
+        duration_ms,
+        size: attachment.size,
+        snippet,
+    }))
+}
+
+#[tauri::command]
+pub fn get_recording_status() -> RecordingStatus {
+    let current = RECORDING.lock().unwrap();
+    RecordingStatus {
+        recording: current.is_some(),
+        elapsed_ms: current
+            .as_ref()
+            .map_or(0, |r| r.started.elapsed().as_millis() as u64),
+    }
+}
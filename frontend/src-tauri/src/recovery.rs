@@ -0,0 +1,349 @@
+// Recovery — guided fixes when the backend won't come up.
+//
+// If the sidecar can't be spawned, or its startup health checks all fail,
+// `open` shows a dedicated recovery window (recovery.html). It talks only to
+// the shell, never to the backend or the main frontend, and offers:
+//   - view logs                 (logs::get_recent_logs)
+//   - run integrity check       (offline checks of the data dir and database)
+//   - restore backup            (copies a backup over the database, restarts)
+//   - switch to external backend
+//   - reset runtime dir         (wipes the sidecar's scratch dir, restarts)
+//   - retry
+
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, WindowBuilder, WindowUrl};
+
+use crate::{config, disk, encryption, i18n};
+
+const WINDOW_LABEL: &str = "recovery";
+const SQLITE_MAGIC: &[u8; 16] = b"SQLite format 3\0";
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RecoveryStatus {
+    reason: String,
+    failures: u32,
+    data_dir: String,
+    db_path: String,
+    external_port: Option<u16>,
+}
+
+#[derive(Default)]
+struct Failures {
+    count: u32,
+    last: String,
+}
+
+static FAILURES: Mutex<Failures> = Mutex::new(Failures {
+    count: 0,
+    last: String::new(),
+});
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Check {
+    name: &'static str,
+    ok: bool,
+    detail: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct LocalBackup {
+    name: String,
+    created_at: u64,
+    db_size_bytes: u64,
+}
+
+fn backups_dir() -> PathBuf {
+    crate::data_dir().join("backups")
+}
+
+/// Records a startup failure and shows the recovery window.
+pub fn open(app: &AppHandle, reason: String) {
+    {
+        let mut failures = FAILURES.lock().unwrap();
+        failures.count += 1;
+        failures.last = reason;
+    }
+    if let Some(window) = app.get_window(WINDOW_LABEL) {
+        window.emit("recovery-updated", status(app)).ok();
+        window.set_focus().ok();
+        return;
+    }
+    let result = WindowBuilder::new(app, WINDOW_LABEL, WindowUrl::App("recovery.html".into()))
+        .title(i18n::t("window.recovery"))
+        .inner_size(560.0, 520.0)
+        .resizable(true)
+        .center()
+        .build();
+    if let Err(e) = result {
+        log::error!("Could not open recovery window: {}", e);
+    }
+}
+
+fn status(app: &AppHandle) -> RecoveryStatus {
+    let failures = FAILURES.lock().unwrap();
+    RecoveryStatus {
+        reason: failures.last.clone(),
+        failures: failures.count,
+        data_dir: crate::data_dir().to_string_lossy().to_string(),
+        db_path: crate::db_path().to_string_lossy().to_string(),
+        external_port: config::current(app).backend.external_port,
+    }
+}
+
+/// Backend is back: clear the failure state and hand over to the main window.
+fn recovered(app: &AppHandle, port: u16) {
+    *FAILURES.lock().unwrap() = Failures::default();
+    app.emit_all("backend-ready", port).ok();
+    if let Some(window) = app.get_window(WINDOW_LABEL) {
+        window.close().ok();
+    }
+    if let Some(main) = app.get_window("main") {
+        main.show().ok();
+        main.set_focus().ok();
+    }
+}
+
+/// Restarts the sidecar, or re-checks the external backend if one is set.
+async fn restart(app: &AppHandle) -> Result<u16, String> {
+    let result = match config::current(app).backend.external_port {
+        Some(port) => crate::wait_for_health(port, 3, 300).await.map(|_| port),
+        None => crate::respawn_backend(app).await,
+    };
+    match result {
+        Ok(port) => {
+            recovered(app, port);
+            crate::capture::flush_outbox(app).await;
+            Ok(port)
+        }
+        Err(e) => {
+            let mut failures = FAILURES.lock().unwrap();
+            failures.count += 1;
+            failures.last = e.clone();
+            Err(e)
+        }
+    }
+}
+
+// ── Integrity check ────────────────────────────────────────────────────────
+fn check_data_dir() -> Check {
+    let probe = crate::data_dir().join(".write-test");
+    let result = std::fs::create_dir_all(crate::data_dir())
+        .and_then(|_| std::fs::write(&probe, b"ok"))
+        .and_then(|_| std::fs::remove_file(&probe));
+    Check {
+        name: "data_dir_writable",
+        ok: result.is_ok(),
+        detail: match result {
+            Ok(_) => crate::data_dir().to_string_lossy().to_string(),
+            Err(e) => e.to_string(),
+        },
+    }
+}
+
+/// Header sanity: magic string, power-of-two page size, whole pages.
+fn check_database() -> Check {
+    let path = crate::db_path();
+    let inspect = || -> Result<String, String> {
+        let mut file = std::fs::File::open(&path).map_err(|e| format!("Cannot open: {e}"))?;
+        let size = file.metadata().map_err(|e| e.to_string())?.len();
+        let mut header = [0u8; 100];
+        file.read_exact(&mut header)
+            .map_err(|_| format!("File too small ({} bytes)", size))?;
+        if &header[..16] != SQLITE_MAGIC {
+            if encryption::active() && size % encryption::CIPHER_PAGE_SIZE == 0 {
+                return Ok(format!("{} bytes, encrypted", size));
+            }
+            return Err("Not an SQLite database (bad header)".into());
+        }
+        let page_size = match u16::from_be_bytes([header[16], header[17]]) {
+            1 => 65536,
+            n => n as u64,
+        };
+        if page_size < 512 || !page_size.is_power_of_two() {
+            return Err(format!("Invalid page size {}", page_size));
+        }
+        if size % page_size != 0 {
+            return Err(format!(
+                "Size {} is not a multiple of the page size {}",
+                size, page_size
+            ));
+        }
+        Ok(format!("{} bytes, page size {}", size, page_size))
+    };
+    match inspect() {
+        Ok(detail) => Check {
+            name: "database_header",
+            ok: true,
+            detail,
+        },
+        Err(detail) => Check {
+            name: "database_header",
+            ok: false,
+            detail,
+        },
+    }
+}
+
+fn check_wal() -> Check {
+    let wal = PathBuf::from(format!("{}-wal", crate::db_path().to_string_lossy()));
+    let size = std::fs::metadata(&wal).map(|m| m.len()).unwrap_or(0);
+    Check {
+        name: "write_ahead_log",
+        // A leftover WAL is normal after a crash; SQLite replays it on open.
+        ok: true,
+        detail: if size == 0 {
+            "none".into()
+        } else {
+            format!("{} bytes pending", size)
+        },
+    }
+}
+
+fn check_sidecar() -> Check {
+    let name = if cfg!(windows) {
+        "pinup-backend.exe"
+    } else {
+        "pinup-backend"
+    };
+    let path = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(name)));
+    let ok = path.as_ref().is_some_and(|p| p.is_file());
+    Check {
+        name: "sidecar_binary",
+        ok,
+        detail: path
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+fn check_backups() -> Check {
+    let count = local_backups().len();
+    Check {
+        name: "backups",
+        ok: count > 0,
+        detail: format!("{} available", count),
+    }
+}
+
+fn local_backups() -> Vec<LocalBackup> {
+    let Ok(entries) = std::fs::read_dir(backups_dir()) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<LocalBackup> = entries
+        .flatten()
+        .filter(|e| e.path().join("pinup.db").is_file())
+        .map(|e| {
+            let meta: serde_json::Value = std::fs::read_to_string(e.path().join("backup.json"))
+                .ok()
+                .and_then(|text| serde_json::from_str(&text).ok())
+                .unwrap_or_default();
+            LocalBackup {
+                name: e.file_name().to_string_lossy().to_string(),
+                created_at: meta["created_at"].as_u64().unwrap_or(0),
+                db_size_bytes: std::fs::metadata(e.path().join("pinup.db"))
+                    .map(|m| m.len())
+                    .unwrap_or(0),
+            }
+        })
+        .collect();
+    backups.sort_by(|a, b| b.name.cmp(&a.name));
+    backups
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn get_recovery_status(app: AppHandle) -> RecoveryStatus {
+    status(&app)
+}
+
+#[tauri::command]
+pub fn run_integrity_check() -> Vec<Check> {
+    vec![
+        check_data_dir(),
+        check_database(),
+        check_wal(),
+        check_sidecar(),
+        check_backups(),
+    ]
+}
+
+#[tauri::command]
+pub fn list_local_backups() -> Vec<LocalBackup> {
+    local_backups()
+}
+
+/// Offline equivalent of the backend's restore: keeps the current database
+/// as `pinup.db.pre_restore`, copies the backup over it and restarts.
+#[tauri::command]
+pub async fn restore_local_backup(app: AppHandle, name: String) -> Result<u16, String> {
+    if name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err("Invalid backup name".into());
+    }
+    let source = backups_dir().join(&name).join("pinup.db");
+    if !source.is_file() {
+        return Err(format!("Backup {} not found", name));
+    }
+    let db = crate::db_path();
+    // Room for the pre_restore copy and the restored database.
+    let needed = [&db, &source]
+        .iter()
+        .map(|p| std::fs::metadata(p).map_or(0, |m| m.len()))
+        .sum();
+    disk::ensure_space(&crate::data_dir(), needed)?;
+    crate::stop_backend(&app);
+    let db_str = db.to_string_lossy().to_string();
+    if db.is_file() {
+        std::fs::copy(&db, format!("{}.pre_restore", db_str))
+            .map_err(|e| format!("Could not save current database: {e}"))?;
+    }
+    std::fs::copy(&source, &db).map_err(|e| format!("Restore failed: {e}"))?;
+    for ext in ["-wal", "-shm"] {
+        std::fs::remove_file(format!("{}{}", db_str, ext)).ok();
+    }
+    log::info!("Restored database from backup {}", name);
+    restart(&app).await
+}
+
+/// Uses a backend the user runs themselves on `port` instead of the sidecar.
+/// The choice persists until `port` is cleared (None) again.
+#[tauri::command]
+pub async fn use_external_backend(app: AppHandle, port: Option<u16>) -> Result<u16, String> {
+    let Some(port) = port else {
+        config::update(&app, |c| c.backend.external_port = None)?;
+        return restart(&app).await;
+    };
+    crate::wait_for_health(port, 3, 300)
+        .await
+        .map_err(|_| format!("No healthy backend on port {}", port))?;
+    crate::stop_backend(&app);
+    crate::BACKEND_PORT.store(port, Ordering::SeqCst);
+    config::update(&app, |c| c.backend.external_port = Some(port))?;
+    log::info!("Switched to external backend on port {}", port);
+    recovered(&app, port);
+    Ok(port)
+}
+
+/// Deletes the sidecar's scratch dir (unpacked runtime, temp files) and restarts.
+#[tauri::command]
+pub async fn reset_runtime_dir(app: AppHandle) -> Result<u16, String> {
+    crate::stop_backend(&app);
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    let dir = crate::runtime_dir();
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).map_err(|e| format!("Could not reset runtime dir: {e}"))?;
+    }
+    log::info!("Runtime dir reset");
+    restart(&app).await
+}
+
+#[tauri::command]
+pub async fn retry_backend(app: AppHandle) -> Result<u16, String> {
+    restart(&app).await
+}
@@ -0,0 +1,135 @@
+// Region — the user's locale and time zone, handed to the backend.
+//
+// The OS locale (as a BCP 47 tag, "de-DE"), the app language (see i18n.rs)
+// and the IANA time zone ("Europe/Berlin") reach the sidecar as
+// PINUP_LOCALE, PINUP_LANGUAGE and PINUP_TIMEZONE, so search date filters
+// and backup names there follow the user rather than the machine's C locale
+// or UTC. They are read again every POLL_INTERVAL and after a
+// language change, and posted to the backend's /locale endpoint when they
+// change (a laptop crossing time zones) or the backend has restarted.
+// Emits "region-changed" with the new values.
+
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::json;
+use tauri::{AppHandle, Manager};
+
+use crate::{backend, i18n};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+const DEFAULT_LOCALE: &str = "en-US";
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Region {
+    /// BCP 47, e.g. "pt-BR".
+    locale: String,
+    /// The app language, e.g. "pt".
+    language: String,
+    /// IANA name; "UTC" when the OS doesn't say.
+    timezone: String,
+}
+
+static CURRENT: Mutex<Option<Region>> = Mutex::new(None);
+/// Backend port that last received the region.
+static NOTIFIED_PORT: AtomicU16 = AtomicU16::new(0);
+
+/// "de_DE.UTF-8@euro" → "de-DE"; None for "C" and "POSIX".
+fn bcp47(locale: &str) -> Option<String> {
+    let tag = locale.split(['.', '@']).next()?.replace('_', "-");
+    let mut parts = tag.split('-').filter(|part| !part.is_empty());
+    let language = parts.next()?.to_lowercase();
+    if !language.chars().all(|c| c.is_ascii_alphabetic()) || language.len() < 2 {
+        return None;
+    }
+    let rest = parts.map(|part| match part.len() {
+        2 => part.to_uppercase(),
+        _ => part.to_string(),
+    });
+    Some(
+        std::iter::once(language)
+            .chain(rest)
+            .collect::<Vec<_>>()
+            .join("-"),
+    )
+}
+
+fn read() -> Region {
+    Region {
+        locale: i18n::system_locale()
+            .as_deref()
+            .and_then(bcp47)
+            .unwrap_or_else(|| DEFAULT_LOCALE.to_string()),
+        language: i18n::language(),
+        timezone: iana_time_zone::get_timezone().unwrap_or_else(|e| {
+            log::debug!("Region: no time zone ({})", e);
+            "UTC".to_string()
+        }),
+    }
+}
+
+/// The region as last read, reading it if it hasn't been yet.
+pub fn current() -> Region {
+    CURRENT.lock().unwrap().get_or_insert_with(read).clone()
+}
+
+/// Sidecar environment carrying the region.
+pub fn backend_env() -> Vec<(String, String)> {
+    let region = current();
+    vec![
+        ("PINUP_LOCALE".into(), region.locale),
+        ("PINUP_LANGUAGE".into(), region.language),
+        ("PINUP_TIMEZONE".into(), region.timezone),
+    ]
+}
+
+async fn notify_backend(region: &Region, changed: bool) {
+    let port = crate::BACKEND_PORT.load(Ordering::SeqCst);
+    if port == 0 || (!changed && NOTIFIED_PORT.load(Ordering::SeqCst) == port) {
+        return;
+    }
+    match backend::post("/locale", &json!(region)).await {
+        Ok(_) => NOTIFIED_PORT.store(port, Ordering::SeqCst),
+        Err(e) => log::debug!("Could not send the region to the backend: {}", e),
+    }
+}
+
+async fn check(app: &AppHandle) {
+    let region = tauri::async_runtime::spawn_blocking(read)
+        .await
+        .unwrap_or_else(|_| current());
+    let changed = {
+        let mut current = CURRENT.lock().unwrap();
+        let changed = current.as_ref() != Some(&region);
+        *current = Some(region.clone());
+        changed
+    };
+    if changed {
+        log::info!(
+            "Region: {} ({}), {}",
+            region.locale,
+            region.language,
+            region.timezone
+        );
+        app.emit_all("region-changed", &region).ok();
+    }
+    notify_backend(&region, changed).await;
+}
+
+/// Reads the region now, e.g. after the language changed.
+pub fn refresh(app: &AppHandle) {
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move { check(&handle).await });
+}
+
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            check(&handle).await;
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
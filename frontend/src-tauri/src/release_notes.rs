@@ -0,0 +1,184 @@
+// Release notes — "What's new" without network code in the webview.
+//
+// Notes come from the GitHub releases of the app repository and are cached
+// in app_dir()/cache/release-notes.json for CACHE_TTL. When GitHub can't be
+// reached the cache is used however old it is, and without a cache the
+// CHANGELOG.md compiled into the app, so the running version always has
+// notes offline.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::proxy;
+
+const RELEASES_URL: &str =
+    "https://api.github.com/repos/holeyfield33-art/pin-up-ai/releases?per_page=30";
+const CACHE_TTL: Duration = Duration::from_secs(6 * 3600);
+const BUNDLED_CHANGELOG: &str = include_str!("../../../CHANGELOG.md");
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReleaseNotes {
+    version: String,
+    name: Option<String>,
+    date: Option<String>,
+    /// Markdown.
+    body: String,
+    prerelease: bool,
+    url: Option<String>,
+    /// "github", "cache" or "bundled".
+    #[serde(default)]
+    source: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Cache {
+    /// Unix seconds.
+    fetched_at: i64,
+    releases: Vec<ReleaseNotes>,
+}
+
+fn cache_path() -> PathBuf {
+    crate::app_dir().join("cache").join("release-notes.json")
+}
+
+fn read_cache() -> Option<Cache> {
+    let text = std::fs::read_to_string(cache_path()).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+fn write_cache(cache: &Cache) {
+    let path = cache_path();
+    std::fs::create_dir_all(path.parent().unwrap()).ok();
+    if let Ok(text) = serde_json::to_string_pretty(cache) {
+        std::fs::write(&path, text).ok();
+    }
+}
+
+fn normalize(version: &str) -> &str {
+    version.trim().trim_start_matches('v')
+}
+
+/// "## [0.1.0] — 2026-02-16" sections of the bundled changelog.
+fn bundled() -> Vec<ReleaseNotes> {
+    let mut releases: Vec<ReleaseNotes> = Vec::new();
+    for line in BUNDLED_CHANGELOG.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            let (version, date) = match heading.split_once(" — ") {
+                Some((v, d)) => (v, Some(d.trim().to_string())),
+                None => (heading, None),
+            };
+            releases.push(ReleaseNotes {
+                version: version.trim_matches(|c| c == '[' || c == ']').to_string(),
+                name: None,
+                date,
+                body: String::new(),
+                prerelease: false,
+                url: None,
+                source: "bundled".into(),
+            });
+        } else if let Some(current) = releases.last_mut() {
+            current.body.push_str(line);
+            current.body.push('\n');
+        }
+    }
+    for release in &mut releases {
+        release.body = release.body.trim().to_string();
+    }
+    releases
+}
+
+async fn fetch(app: &AppHandle) -> Result<Vec<ReleaseNotes>, String> {
+    let client = proxy::client_builder(app)
+        .timeout(Duration::from_secs(10))
+        .user_agent("pin-up-ai")
+        .build()
+        .map_err(|e| e.to_string())?;
+    let releases: Vec<Value> = client
+        .get(RELEASES_URL)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Could not fetch release notes: {e}"))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid release list: {e}"))?;
+    let text = |r: &Value, key: &str| r[key].as_str().map(str::to_string);
+    Ok(releases
+        .iter()
+        .filter(|r| !r["draft"].as_bool().unwrap_or(false))
+        .filter_map(|r| {
+            Some(ReleaseNotes {
+                version: normalize(r["tag_name"].as_str()?).to_string(),
+                name: text(r, "name"),
+                date: text(r, "published_at"),
+                body: text(r, "body").unwrap_or_default(),
+                prerelease: r["prerelease"].as_bool().unwrap_or(false),
+                url: text(r, "html_url"),
+                source: "github".into(),
+            })
+        })
+        .collect())
+}
+
+/// Fresh cache, else GitHub, else stale cache, else the bundled changelog.
+async fn releases(app: &AppHandle, refresh: bool) -> Vec<ReleaseNotes> {
+    let cache = read_cache();
+    let now = chrono::Utc::now().timestamp();
+    let fresh = cache
+        .as_ref()
+        .is_some_and(|c| now - c.fetched_at < CACHE_TTL.as_secs() as i64);
+    let cached = |cache: Cache| -> Vec<ReleaseNotes> {
+        cache
+            .releases
+            .into_iter()
+            .map(|r| ReleaseNotes {
+                source: "cache".into(),
+                ..r
+            })
+            .collect()
+    };
+    if fresh && !refresh {
+        return cached(cache.unwrap());
+    }
+    match fetch(app).await {
+        Ok(releases) => {
+            write_cache(&Cache {
+                fetched_at: now,
+                releases: releases.clone(),
+            });
+            releases
+        }
+        Err(e) => {
+            log::info!("{}; using cached release notes", e);
+            match cache {
+                Some(cache) if !cache.releases.is_empty() => cached(cache),
+                _ => bundled(),
+            }
+        }
+    }
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Notes for `version` (default: the running app). `refresh` skips the
+/// cache when online.
+#[tauri::command]
+pub async fn get_release_notes(
+    app: AppHandle,
+    version: Option<String>,
+    refresh: Option<bool>,
+) -> Result<ReleaseNotes, String> {
+    let wanted = version.unwrap_or_else(|| app.package_info().version.to_string());
+    let wanted = normalize(&wanted);
+    let all = releases(&app, refresh.unwrap_or(false)).await;
+    let found = all
+        .into_iter()
+        .find(|r| r.version == wanted)
+        // GitHub may not have it (yet); the bundled changelog might.
+        .or_else(|| bundled().into_iter().find(|r| r.version == wanted));
+    found.ok_or_else(|| format!("No release notes for version {}", wanted))
+}
@@ -0,0 +1,358 @@
+// Relocate — the profile's data folder on another drive, moved with checks.
+//
+// PINUP_DATA_DIR points the whole app folder (every profile, models and
+// caches) somewhere else for the process it's set on, e.g. a portable
+// install. Each profile's own folder is a setting, its `path` in
+// profiles.json: `set_data_dir(path)` points the profile at a folder that
+// already holds its data (or an empty one), or back at its original folder,
+// and relaunches without copying anything. A move of the notes is
+// `move_data_dir(path)`,
+// run as a task: with the destination checked (empty, or the profile's
+// original folder, and big enough), it stops the sidecar, copies the
+// database, attachments, recordings, backups and settings file by file, and
+// compares each copy's SHA-256 with the original. Only then is the folder
+// recorded as the profile's `path` (see profiles.rs), the originals removed
+// and the app relaunched on the new location. A failure or a cancel removes
+// the copy and brings the sidecar back on the data where it was. Files that
+// belong to every profile (SHARED) or are scratch space stay behind, but
+// the profile's own entries inside them (NESTED) go along.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::tasks::{self, Task};
+use crate::{config, disk, downloads, i18n, jobs, profiles};
+
+/// Entries of a data folder that aren't the profile's to move.
+const SHARED: &[&str] = &[
+    "profiles",
+    "profiles.json",
+    "models",
+    "backend",
+    "whisper",
+    "ocr",
+    "certs",
+    "cache",
+    "crashes",
+    "telemetry",
+    "native-host",
+    "instance.json",
+    "startup-metrics.json",
+    "runtime",
+    "restore-staging",
+    "selftest.tmp",
+    ".write-test",
+];
+/// The profile's entries inside SHARED ones: the sidecar's pre-update copy
+/// of the database (see sidecar.rs).
+const NESTED: &[&str] = &["backend/db-backup"];
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DataLocation {
+    /// The active profile's data folder.
+    path: String,
+    /// Where it lives unless moved.
+    home: String,
+    moved: bool,
+    /// PINUP_DATA_DIR, when it overrides the app folder.
+    env_override: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct MoveResult {
+    path: String,
+    files: u64,
+    size_bytes: u64,
+}
+
+/// A file to copy, relative to the data folder.
+struct Entry {
+    path: PathBuf,
+    size: u64,
+}
+
+fn location() -> DataLocation {
+    let path = crate::data_dir();
+    let home = profiles::home_dir();
+    DataLocation {
+        moved: path != home,
+        path: path.to_string_lossy().to_string(),
+        home: home.to_string_lossy().to_string(),
+        env_override: std::env::var("PINUP_DATA_DIR")
+            .ok()
+            .filter(|dir| !dir.is_empty()),
+    }
+}
+
+/// The entries of `dir` that belong to the profile, relative to it.
+fn profile_entries(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("Could not read {}: {e}", dir.display()))?;
+    let mut names: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.file_name())
+        .filter(|name| !SHARED.iter().any(|shared| name == shared))
+        .map(PathBuf::from)
+        .collect();
+    names.extend(
+        NESTED
+            .iter()
+            .map(PathBuf::from)
+            .filter(|nested| dir.join(nested).exists()),
+    );
+    Ok(names)
+}
+
+/// Every file under `path`, relative to `root`. Symlinks are left out.
+fn walk(root: &Path, path: &Path, files: &mut Vec<Entry>) -> Result<(), String> {
+    let meta = std::fs::symlink_metadata(path)
+        .map_err(|e| format!("Could not read {}: {e}", path.display()))?;
+    if meta.is_dir() {
+        let entries = std::fs::read_dir(path)
+            .map_err(|e| format!("Could not read {}: {e}", path.display()))?;
+        for entry in entries.flatten() {
+            walk(root, &entry.path(), files)?;
+        }
+    } else if meta.is_file() {
+        files.push(Entry {
+            path: path.strip_prefix(root).unwrap_or(path).to_path_buf(),
+            size: meta.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Creates `target` and makes sure the profile's data can go there.
+fn prepare_target(source: &Path, target: &Path, names: &[PathBuf]) -> Result<(), String> {
+    if !target.is_absolute() {
+        return Err("Choose a full folder path".into());
+    }
+    let created = !target.exists();
+    std::fs::create_dir_all(target)
+        .map_err(|e| format!("Could not create {}: {e}", target.display()))?;
+    let canonical = |path: &Path| std::fs::canonicalize(path).unwrap_or(path.to_path_buf());
+    let check = || {
+        let (source, resolved) = (canonical(source), canonical(target));
+        if resolved == source {
+            return Err("Your data is already in that folder".to_string());
+        }
+        if resolved.starts_with(&source) {
+            return Err("Choose a folder outside the current data folder".into());
+        }
+        // The original folder may hold shared files; anywhere else starts empty.
+        if resolved != canonical(&profiles::home_dir()) {
+            let mut entries = std::fs::read_dir(target)
+                .map_err(|e| format!("Could not read {}: {e}", target.display()))?;
+            if entries.next().is_some() {
+                return Err("Choose an empty folder".into());
+            }
+        } else if let Some(name) = names.iter().find(|name| target.join(name).exists()) {
+            return Err(format!(
+                "{} already has {}",
+                target.display(),
+                name.display()
+            ));
+        }
+        Ok(())
+    };
+    check().map_err(|e| {
+        if created {
+            std::fs::remove_dir(target).ok();
+        }
+        e
+    })
+}
+
+fn copy_file(source: &Path, target: &Path) -> Result<(), String> {
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Could not create {}: {e}", parent.display()))?;
+    }
+    std::fs::copy(source, target)
+        .map_err(|e| format!("Could not copy {}: {e}", source.display()))?;
+    if downloads::sha256_file(source)? != downloads::sha256_file(target)? {
+        return Err(format!(
+            "The copy of {} doesn't match the original",
+            source.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Copies and verifies every file, reporting progress in bytes.
+fn copy_all(task: &Task, source: &Path, target: &Path, files: &[Entry]) -> Result<(), String> {
+    let total = files.iter().map(|f| f.size).sum();
+    let mut done = 0;
+    for file in files {
+        if task.cancelled() {
+            return Err("Moving the data folder was cancelled".into());
+        }
+        let name = file.path.to_string_lossy();
+        task.progress(done, Some(total), Some(&name));
+        copy_file(&source.join(&file.path), &target.join(&file.path))?;
+        done += file.size;
+    }
+    task.progress(done, Some(total), None);
+    Ok(())
+}
+
+/// Removes `names` from `dir`, and the folders they leave empty; what can't
+/// be removed is only logged.
+fn remove_entries(dir: &Path, names: &[PathBuf]) {
+    for name in names {
+        let path = dir.join(name);
+        if !path.exists() {
+            continue;
+        }
+        let removed = if path.is_dir() {
+            std::fs::remove_dir_all(&path)
+        } else {
+            std::fs::remove_file(&path)
+        };
+        if let Err(e) = removed {
+            log::warn!("Could not remove {}: {}", path.display(), e);
+            continue;
+        }
+        for parent in path.ancestors().skip(1).take_while(|p| *p != dir) {
+            if std::fs::remove_dir(parent).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+async fn relocate(
+    app: AppHandle,
+    task: Task,
+    source: PathBuf,
+    target: PathBuf,
+    names: Vec<PathBuf>,
+    files: Vec<Entry>,
+) -> Result<MoveResult, String> {
+    crate::stop_backend(&app);
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let result = MoveResult {
+        path: target.to_string_lossy().to_string(),
+        files: files.len() as u64,
+        size_bytes: files.iter().map(|f| f.size).sum(),
+    };
+    let copied = {
+        let (source, target) = (source.clone(), target.clone());
+        tauri::async_runtime::spawn_blocking(move || {
+            copy_all(&task, &source, &target, &files)?;
+            profiles::set_dir(&target)
+        })
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|copied| copied)
+    };
+    if let Err(e) = copied {
+        remove_entries(&target, &names);
+        if let Err(e) = crate::respawn_backend(&app).await {
+            log::error!("Backend did not come back after a failed move: {}", e);
+        }
+        return Err(e);
+    }
+    log::info!(
+        "Moved {} files ({} bytes) from {} to {}",
+        result.files,
+        result.size_bytes,
+        source.display(),
+        target.display()
+    );
+    remove_entries(&source, &names);
+    if source != crate::app_dir() {
+        // Only goes if nothing was left behind.
+        std::fs::remove_dir(&source).ok();
+    }
+    app.restart();
+    Ok(result)
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn get_data_location() -> DataLocation {
+    location()
+}
+
+/// Moves the active profile's data to `path` (picked in a dialog when None)
+/// and relaunches. Returns the task id, or None if the dialog was cancelled.
+#[tauri::command]
+pub async fn move_data_dir(app: AppHandle, path: Option<String>) -> Result<Option<u64>, String> {
+    if config::current(&app).backend.external_port.is_some() {
+        return Err("The data folder can't be moved while an external backend is in use".into());
+    }
+    if jobs::running().0 > 0 {
+        return Err("Wait for background jobs to finish before moving the data folder".into());
+    }
+    let target = match path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            use tauri::api::dialog::blocking::FileDialogBuilder;
+            let picked = FileDialogBuilder::new()
+                .set_title(&i18n::t("dialog.move_data"))
+                .pick_folder();
+            match picked {
+                Some(path) => path,
+                None => return Ok(None),
+            }
+        }
+    };
+    let source = crate::data_dir();
+    let (target, names, files) = tauri::async_runtime::spawn_blocking(move || {
+        let names = profile_entries(&source)?;
+        prepare_target(&source, &target, &names)?;
+        let mut files = Vec::new();
+        for name in &names {
+            walk(&source, &source.join(name), &mut files)?;
+        }
+        disk::ensure_space(&target, files.iter().map(|f| f.size).sum())?;
+        Ok::<_, String>((target, names, files))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    log::info!("Moving the data folder to {}", target.display());
+    let source = crate::data_dir();
+    let handle = app.clone();
+    let id = tasks::spawn(&app, "move_data_dir", move |task| {
+        relocate(handle, task, source, target, names, files)
+    });
+    Ok(Some(id))
+}
+
+/// Points the active profile at `path`, an empty folder or one holding its
+/// data (its original folder when None), and relaunches. Nothing is copied.
+#[tauri::command]
+pub fn set_data_dir(app: AppHandle, path: Option<String>) -> Result<DataLocation, String> {
+    let target = path.map_or_else(profiles::home_dir, PathBuf::from);
+    if !target.is_absolute() {
+        return Err("Choose a full folder path".into());
+    }
+    if target == crate::data_dir() {
+        return Ok(location());
+    }
+    if jobs::running().0 > 0 {
+        return Err("Wait for background jobs to finish before changing the data folder".into());
+    }
+    std::fs::create_dir_all(&target)
+        .map_err(|e| format!("Could not create {}: {e}", target.display()))?;
+    let empty = std::fs::read_dir(&target)
+        .map_err(|e| format!("Could not read {}: {e}", target.display()))?
+        .next()
+        .is_none();
+    // The original folder may hold shared files, and a fresh start is fine there.
+    if !empty && target != profiles::home_dir() && !target.join("pinup.db").is_file() {
+        return Err(format!(
+            "{} holds no Pin-Up AI data; choose an empty folder or one with pinup.db",
+            target.display()
+        ));
+    }
+    profiles::set_dir(&target)?;
+    log::info!("Data folder set to {}", target.display());
+    crate::stop_backend(&app);
+    app.restart();
+    Ok(location())
+}
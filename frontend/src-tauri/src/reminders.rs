@@ -0,0 +1,258 @@
+// Reminders — "remind me" times on snippets, resurfaced as notifications.
+//
+// Reminders live in data_dir()/reminders.json, so they survive restarts.
+// A scheduler checks them every TICK against the wall clock, and right after
+// the machine wakes (see suspend.rs), so one that came due while the app was
+// closed or the machine slept fires on the next check instead of being lost;
+// one over LATE past due says when it was due. A check shows at most
+// MAX_AT_ONCE notifications and rolls the rest into one summary.
+//
+// A reminder that fired stays listed as due until it is snoozed (due again
+// in `minutes`, SNOOZE_MINUTES by default) or dismissed (gone). Firing shows
+// a native notification that opens the snippet (regardless of the job
+// notifications setting, but held back during Focus; see focus.rs), adds to
+// the badge (see badge.rs) and emits "reminder-due"; every change goes out
+// as "reminders-changed" with the full list.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::{badge, i18n, notifications};
+
+const TICK: Duration = Duration::from_secs(15);
+/// Past due by more than this many minutes, a reminder says when it was due.
+const LATE: i64 = 5;
+const MAX_AT_ONCE: usize = 3;
+const SNOOZE_MINUTES: u32 = 10;
+const MAX_NOTE: usize = 500;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReminderState {
+    Scheduled,
+    /// Fired and waiting to be snoozed or dismissed.
+    Due,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Reminder {
+    pub id: String,
+    pub snippet_id: String,
+    pub title: String,
+    #[serde(default)]
+    pub note: String,
+    /// RFC 3339.
+    pub due_at: String,
+    pub state: ReminderState,
+    #[serde(default)]
+    pub snoozes: u32,
+}
+
+impl Reminder {
+    fn due(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.due_at)
+            .map(|at| at.with_timezone(&Utc))
+            .ok()
+    }
+}
+
+static REMINDERS: Lazy<Mutex<Vec<Reminder>>> = Lazy::new(|| {
+    Mutex::new(
+        std::fs::read_to_string(path())
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default(),
+    )
+});
+static NEXT: AtomicU64 = AtomicU64::new(1);
+
+fn path() -> PathBuf {
+    crate::data_dir().join("reminders.json")
+}
+
+fn save(reminders: &[Reminder]) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(reminders).map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(crate::data_dir()).ok();
+    std::fs::write(path(), text).map_err(|e| format!("Could not save reminders: {e}"))
+}
+
+/// Applies `f` to the list, saves it and announces the result.
+fn change<T>(
+    app: &AppHandle,
+    f: impl FnOnce(&mut Vec<Reminder>) -> Result<T, String>,
+) -> Result<T, String> {
+    let (value, list) = {
+        let mut reminders = REMINDERS.lock().unwrap();
+        let value = f(&mut reminders)?;
+        reminders.sort_by(|a, b| a.due_at.cmp(&b.due_at));
+        save(&reminders)?;
+        (value, reminders.clone())
+    };
+    app.emit_all("reminders-changed", &list).ok();
+    Ok(value)
+}
+
+fn new_id() -> String {
+    format!(
+        "{}-{}",
+        Utc::now().timestamp_millis(),
+        NEXT.fetch_add(1, Ordering::SeqCst)
+    )
+}
+
+// ── Scheduler ──────────────────────────────────────────────────────────────
+fn announce(app: &AppHandle, reminder: &Reminder, now: DateTime<Utc>) {
+    let mut body = reminder.note.clone();
+    if let Some(due) = reminder
+        .due()
+        .filter(|due| (now - *due).num_minutes() > LATE)
+    {
+        let due = due.with_timezone(&Local);
+        let when = if due.date_naive() == Local::now().date_naive() {
+            due.format("%H:%M").to_string()
+        } else {
+            due.format("%b %-d, %H:%M").to_string()
+        };
+        let was_due = i18n::tf("reminder.was_due", &[("time", &when)]);
+        body = format!("{was_due} {body}").trim().to_string();
+    }
+    let title = match reminder.title.trim() {
+        "" => i18n::t("reminder.untitled"),
+        title => title.to_string(),
+    };
+    notifications::alert(app, &title, &body, Some(&reminder.snippet_id));
+}
+
+/// Fires every reminder that has come due.
+pub fn check(app: &AppHandle) {
+    let now = Utc::now();
+    let is_due =
+        |r: &Reminder| r.state == ReminderState::Scheduled && r.due().is_some_and(|due| due <= now);
+    if !REMINDERS.lock().unwrap().iter().any(is_due) {
+        return;
+    }
+    let fired = change(app, |reminders| {
+        let mut fired = Vec::new();
+        for reminder in reminders.iter_mut().filter(|r| is_due(r)) {
+            reminder.state = ReminderState::Due;
+            fired.push(reminder.clone());
+        }
+        Ok(fired)
+    });
+    let fired = match fired {
+        Ok(fired) if !fired.is_empty() => fired,
+        Ok(_) => return,
+        Err(e) => {
+            log::error!("{}", e);
+            return;
+        }
+    };
+    log::info!("{} reminder(s) due", fired.len());
+    for reminder in fired.iter().take(MAX_AT_ONCE) {
+        announce(app, reminder, now);
+    }
+    if fired.len() > MAX_AT_ONCE {
+        let more = fired.len() - MAX_AT_ONCE;
+        let title = if more == 1 {
+            i18n::t("reminder.more_one")
+        } else {
+            i18n::tf("reminder.more_many", &[("count", &more)])
+        };
+        notifications::alert(app, &title, "", None);
+    }
+    for reminder in &fired {
+        badge::arrived(app);
+        app.emit_all("reminder-due", reminder).ok();
+    }
+}
+
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            check(&handle);
+            tokio::time::sleep(TICK).await;
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Scheduled and due reminders, soonest first.
+#[tauri::command]
+pub fn list_reminders() -> Vec<Reminder> {
+    REMINDERS.lock().unwrap().clone()
+}
+
+/// Reminds about a snippet at `due_at` (RFC 3339); a time already past
+/// fires on the next check.
+#[tauri::command]
+pub fn set_reminder(
+    app: AppHandle,
+    snippet_id: String,
+    title: String,
+    due_at: String,
+    note: Option<String>,
+) -> Result<Reminder, String> {
+    let due = DateTime::parse_from_rfc3339(&due_at)
+        .map_err(|e| format!("Invalid reminder time {due_at}: {e}"))?;
+    let note = note.unwrap_or_default();
+    let note = match note.trim().char_indices().nth(MAX_NOTE) {
+        Some((end, _)) => note.trim()[..end].to_string(),
+        None => note.trim().to_string(),
+    };
+    let reminder = Reminder {
+        id: new_id(),
+        snippet_id,
+        title,
+        note,
+        due_at: due.with_timezone(&Utc).to_rfc3339(),
+        state: ReminderState::Scheduled,
+        snoozes: 0,
+    };
+    change(&app, |reminders| {
+        reminders.push(reminder.clone());
+        Ok(())
+    })?;
+    check(&app);
+    Ok(reminder)
+}
+
+/// Puts a reminder off for `minutes` (SNOOZE_MINUTES by default) from now.
+#[tauri::command]
+pub fn snooze_reminder(
+    app: AppHandle,
+    id: String,
+    minutes: Option<u32>,
+) -> Result<Reminder, String> {
+    let minutes = minutes.unwrap_or(SNOOZE_MINUTES).max(1);
+    change(&app, |reminders| {
+        let reminder = reminders
+            .iter_mut()
+            .find(|r| r.id == id)
+            .ok_or("No such reminder")?;
+        reminder.due_at = (Utc::now() + chrono::Duration::minutes(minutes as i64)).to_rfc3339();
+        reminder.state = ReminderState::Scheduled;
+        reminder.snoozes += 1;
+        Ok(reminder.clone())
+    })
+}
+
+/// Removes a reminder, whether it has fired or not.
+#[tauri::command]
+pub fn dismiss_reminder(app: AppHandle, id: String) -> Result<(), String> {
+    change(&app, |reminders| {
+        let before = reminders.len();
+        reminders.retain(|r| r.id != id);
+        if reminders.len() == before {
+            return Err("No such reminder".into());
+        }
+        Ok(())
+    })
+}
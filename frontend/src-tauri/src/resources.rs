@@ -0,0 +1,162 @@
+// Backend resources — CPU and memory of the sidecar process tree.
+//
+// The packaged backend unpacks itself and runs as a child of the process we
+// spawn, so usage is summed over the sidecar and all its descendants. A
+// monitor samples every `resources.interval_secs` (times the performance
+// profile's poll factor) and emits "backend-resources". When memory crosses
+// `resources.memory_limit_mb`, "backend-memory-warning" fires once per
+// excursion; with `auto_restart` the sidecar is respawned as well. External
+// backends are not ours to measure or restart, and sampling pauses around
+// system sleep (see suspend.rs).
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, System};
+use tauri::{AppHandle, Manager};
+
+use crate::{config, performance, suspend};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ResourcesConfig {
+    pub interval_secs: u64,
+    /// Resident memory ceiling for the backend; 0 disables the check.
+    pub memory_limit_mb: u64,
+    /// Respawn the sidecar when it stays above the ceiling.
+    pub auto_restart: bool,
+}
+
+impl Default for ResourcesConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 10,
+            memory_limit_mb: 0,
+            auto_restart: false,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BackendResources {
+    pid: u32,
+    /// Sidecar plus descendants.
+    processes: usize,
+    /// Percent of one core, summed; can exceed 100.
+    cpu_percent: f32,
+    memory_bytes: u64,
+    virtual_memory_bytes: u64,
+    uptime_secs: u64,
+    memory_limit_bytes: Option<u64>,
+}
+
+/// Kept between samples: CPU usage is measured against the previous refresh.
+static SYSTEM: Lazy<Mutex<System>> = Lazy::new(|| Mutex::new(System::new()));
+
+/// `root` followed by all of its descendants.
+fn descendants(sys: &System, root: Pid) -> Vec<Pid> {
+    let mut tree = vec![root];
+    let mut i = 0;
+    while i < tree.len() {
+        let parent = tree[i];
+        tree.extend(
+            sys.processes()
+                .iter()
+                .filter(|(_, p)| p.parent() == Some(parent))
+                .map(|(child, _)| *child),
+        );
+        i += 1;
+    }
+    tree
+}
+
+/// PIDs of `pid` and every process below it.
+pub fn process_tree(pid: u32) -> Vec<u32> {
+    let mut sys = SYSTEM.lock().unwrap();
+    sys.refresh_processes();
+    descendants(&sys, Pid::from_u32(pid))
+        .iter()
+        .map(|p| p.as_u32())
+        .collect()
+}
+
+fn sample(app: &AppHandle) -> Option<BackendResources> {
+    let pid = crate::backend_pid(app)?;
+    let mut sys = SYSTEM.lock().unwrap();
+    sys.refresh_processes();
+    let root = sys.process(Pid::from_u32(pid))?;
+    let processes: Vec<_> = descendants(&sys, Pid::from_u32(pid))
+        .iter()
+        .filter_map(|p| sys.process(*p))
+        .collect();
+
+    let limit = config::current(app).resources.memory_limit_mb;
+    Some(BackendResources {
+        pid,
+        processes: processes.len(),
+        cpu_percent: processes.iter().map(|p| p.cpu_usage()).sum(),
+        memory_bytes: processes.iter().map(|p| p.memory()).sum(),
+        virtual_memory_bytes: processes.iter().map(|p| p.virtual_memory()).sum(),
+        uptime_secs: root.run_time(),
+        memory_limit_bytes: (limit > 0).then_some(limit * 1024 * 1024),
+    })
+}
+
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut over_limit = false;
+        loop {
+            let cfg = config::current(&handle).resources;
+            let factor = performance::capabilities(&handle).poll_factor;
+            tokio::time::sleep(Duration::from_secs(cfg.interval_secs.max(2) * factor)).await;
+
+            if suspend::paused() {
+                continue;
+            }
+            let Some(usage) = sample(&handle) else {
+                over_limit = false;
+                continue;
+            };
+            handle.emit_all("backend-resources", &usage).ok();
+
+            let exceeded = usage
+                .memory_limit_bytes
+                .is_some_and(|limit| usage.memory_bytes > limit);
+            if !exceeded {
+                over_limit = false;
+                continue;
+            }
+            if over_limit {
+                continue;
+            }
+            over_limit = true;
+            log::warn!(
+                "Backend using {} MB, above the {} MB limit",
+                usage.memory_bytes >> 20,
+                cfg.memory_limit_mb
+            );
+            handle.emit_all("backend-memory-warning", &usage).ok();
+            if cfg.auto_restart {
+                match crate::respawn_backend(&handle).await {
+                    Ok(port) => {
+                        log::info!("Backend restarted after exceeding memory limit");
+                        handle.emit_all("backend-ready", port).ok();
+                        over_limit = false;
+                    }
+                    Err(e) => log::error!("Backend restart failed: {}", e),
+                }
+            }
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Current usage of the sidecar, or None when no sidecar is running (or the
+/// backend is external).
+#[tauri::command]
+pub fn get_backend_resources(app: AppHandle) -> Option<BackendResources> {
+    sample(&app)
+}
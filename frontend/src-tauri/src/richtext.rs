@@ -0,0 +1,106 @@
+// Rich text — render a snippet body as HTML and RTF for multi-format copy.
+//
+// Bodies are treated as Markdown; snippets with a `language` are rendered as
+// a single monospace code block instead.
+
+use pulldown_cmark::{html, Event, HeadingLevel, Parser, Tag};
+
+pub struct RichText {
+    pub plain: String,
+    pub html: String,
+    /// Not offered on Linux, where arboard can't serve extra targets.
+    #[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
+    pub rtf: String,
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn escape_rtf(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '\\' | '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\line "),
+            c if c.is_ascii() => out.push(c),
+            // RTF wants signed 16-bit code units, each with an ASCII fallback.
+            c => {
+                let mut buf = [0u16; 2];
+                for unit in c.encode_utf16(&mut buf) {
+                    out.push_str(&format!("\\u{}?", *unit as i16));
+                }
+            }
+        }
+    }
+}
+
+const RTF_HEADER: &str =
+    "{\\rtf1\\ansi\\deff0{\\fonttbl{\\f0\\fswiss Helvetica;}{\\f1\\fmodern Courier New;}}\\fs22 ";
+
+fn markdown_to_rtf(markdown: &str) -> String {
+    let mut out = String::from(RTF_HEADER);
+    for event in Parser::new(markdown) {
+        match event {
+            Event::Start(Tag::Heading(level, ..)) => {
+                let size = match level {
+                    HeadingLevel::H1 => 36,
+                    HeadingLevel::H2 => 30,
+                    _ => 26,
+                };
+                out.push_str(&format!("{{\\b\\fs{} ", size));
+            }
+            Event::End(Tag::Heading(..)) => out.push_str("}\\par\\par "),
+            Event::Start(Tag::Strong) => out.push_str("{\\b "),
+            Event::Start(Tag::Emphasis) => out.push_str("{\\i "),
+            Event::Start(Tag::Strikethrough) => out.push_str("{\\strike "),
+            Event::End(Tag::Strong | Tag::Emphasis | Tag::Strikethrough) => out.push('}'),
+            Event::Start(Tag::CodeBlock(_)) => out.push_str("{\\f1\\fs20 "),
+            Event::End(Tag::CodeBlock(_)) => out.push_str("}\\par "),
+            Event::Start(Tag::Item) => out.push_str("\\bullet  "),
+            Event::End(Tag::Item) => out.push_str("\\par "),
+            Event::End(Tag::Paragraph) => out.push_str("\\par\\par "),
+            Event::Code(code) => {
+                out.push_str("{\\f1 ");
+                escape_rtf(&code, &mut out);
+                out.push('}');
+            }
+            Event::Text(text) => escape_rtf(&text, &mut out),
+            Event::SoftBreak => out.push(' '),
+            Event::HardBreak => out.push_str("\\line "),
+            Event::Rule => out.push_str("\\par ---\\par "),
+            _ => {}
+        }
+    }
+    out.push('}');
+    out
+}
+
+pub fn render(body: &str, language: Option<&str>) -> RichText {
+    let (html, rtf) = match language.filter(|l| !l.is_empty()) {
+        Some(lang) => {
+            let html = format!(
+                "<pre><code class=\"language-{}\">{}</code></pre>",
+                escape_html(lang),
+                escape_html(body)
+            );
+            // Reuse the Markdown path so code gets the same RTF styling.
+            let fenced = format!("```\n{}\n```", body);
+            (html, markdown_to_rtf(&fenced))
+        }
+        None => {
+            let mut html = String::new();
+            html::push_html(&mut html, Parser::new(body));
+            (html, markdown_to_rtf(body))
+        }
+    };
+    RichText {
+        plain: body.to_string(),
+        html,
+        rtf,
+    }
+}
@@ -0,0 +1,234 @@
+// Routing — file captures into notebooks (collections) by app context.
+//
+// Each rule matches on the foreground app, a window-title regex, the capture
+// source and/or a time window; the highest-priority matching rule adds its
+// collection and tags to the capture. `evaluate_routing` dry-runs the rules
+// and explains why each one did or did not match.
+
+use chrono::{DateTime, Datelike, Local, NaiveTime};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::app_context::{self, AppContext};
+use crate::capture::Capture;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct RoutingConfig {
+    pub enabled: bool,
+    pub rules: Vec<RoutingRule>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct RoutingRule {
+    pub name: String,
+    pub enabled: bool,
+    /// Higher wins; ties go to the rule listed first.
+    pub priority: i32,
+    /// App name or id substring, e.g. "code" or "com.microsoft.VSCode".
+    pub app: Option<String>,
+    /// Regex matched against the window title.
+    pub title_pattern: Option<String>,
+    /// Capture sources this rule applies to; empty means all.
+    pub sources: Vec<String>,
+    pub hours: Option<Hours>,
+    /// Collection (notebook) name the capture is filed into.
+    pub collection: String,
+    pub tags: Vec<String>,
+}
+
+impl Default for RoutingRule {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            enabled: true,
+            priority: 0,
+            app: None,
+            title_pattern: None,
+            sources: Vec::new(),
+            hours: None,
+            collection: String::new(),
+            tags: Vec::new(),
+        }
+    }
+}
+
+/// Local time window, e.g. 09:00–18:00 on weekdays. `end` before `start`
+/// wraps past midnight.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Hours {
+    pub start: String,
+    pub end: String,
+    /// ISO weekdays (1 = Monday … 7 = Sunday); empty means every day.
+    #[serde(default)]
+    pub days: Vec<u32>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RuleTrace {
+    rule: String,
+    matched: bool,
+    /// Why the rule did not match, if it didn't.
+    reason: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RoutingDecision {
+    context: Option<AppContext>,
+    rule: Option<RoutingRule>,
+    trace: Vec<RuleTrace>,
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value, "%H:%M").map_err(|_| format!("bad time {:?}", value))
+}
+
+fn check_hours(hours: &Hours, now: DateTime<Local>) -> Result<(), String> {
+    let day = now.weekday().number_from_monday();
+    if !hours.days.is_empty() && !hours.days.contains(&day) {
+        return Err(format!("not on day {}", day));
+    }
+    let (start, end, time) = (
+        parse_time(&hours.start)?,
+        parse_time(&hours.end)?,
+        now.time(),
+    );
+    let inside = if start <= end {
+        time >= start && time < end
+    } else {
+        time >= start || time < end
+    };
+    if inside {
+        Ok(())
+    } else {
+        Err(format!("outside {}–{}", hours.start, hours.end))
+    }
+}
+
+fn check(
+    rule: &RoutingRule,
+    source: &str,
+    context: Option<&AppContext>,
+    now: DateTime<Local>,
+) -> Result<(), String> {
+    if !rule.enabled {
+        return Err("disabled".into());
+    }
+    if !rule.sources.is_empty() && !rule.sources.iter().any(|s| s == source) {
+        return Err(format!("source {:?} not listed", source));
+    }
+    if let Some(pattern) = &rule.app {
+        match context {
+            Some(ctx) if ctx.matches(pattern) => {}
+            Some(ctx) => return Err(format!("app {:?} does not match", ctx.name)),
+            None => return Err("no foreground app".into()),
+        }
+    }
+    if let Some(pattern) = &rule.title_pattern {
+        let re = Regex::new(pattern).map_err(|e| format!("invalid title pattern: {e}"))?;
+        let title = context
+            .and_then(|c| c.window_title.as_deref())
+            .unwrap_or("");
+        if !re.is_match(title) {
+            return Err("window title does not match".into());
+        }
+    }
+    if let Some(hours) = &rule.hours {
+        check_hours(hours, now)?;
+    }
+    Ok(())
+}
+
+pub fn evaluate(
+    cfg: &RoutingConfig,
+    source: &str,
+    context: Option<AppContext>,
+    now: DateTime<Local>,
+) -> RoutingDecision {
+    let mut order: Vec<&RoutingRule> = cfg.rules.iter().collect();
+    // Stable sort keeps list order among equal priorities.
+    order.sort_by_key(|r| std::cmp::Reverse(r.priority));
+
+    let mut trace = Vec::new();
+    let mut chosen = None;
+    for rule in order {
+        let result = check(rule, source, context.as_ref(), now);
+        trace.push(RuleTrace {
+            rule: rule.name.clone(),
+            matched: result.is_ok(),
+            reason: result.err(),
+        });
+        if trace.last().is_some_and(|t| t.matched) && chosen.is_none() {
+            chosen = Some(rule.clone());
+        }
+    }
+    RoutingDecision {
+        context,
+        rule: chosen,
+        trace,
+    }
+}
+
+/// Files `capture` according to the first matching rule, if routing is on.
+pub fn route(cfg: &RoutingConfig, capture: Capture) -> Capture {
+    if !cfg.enabled || cfg.rules.is_empty() {
+        return capture;
+    }
+    let context = app_context::frontmost();
+    let Some(rule) = evaluate(cfg, &capture.source, context, Local::now()).rule else {
+        return capture;
+    };
+    log::info!(
+        "Capture routed by rule {:?} to {:?}",
+        rule.name,
+        rule.collection
+    );
+    let mut capture = capture;
+    if !rule.collection.is_empty() && !capture.collections.contains(&rule.collection) {
+        capture.collections.push(rule.collection);
+    }
+    for tag in rule.tags {
+        if !capture.tags.contains(&tag) {
+            capture.tags.push(tag);
+        }
+    }
+    capture
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Dry run: which rule would handle a capture right now? Any field left out
+/// falls back to the live foreground app, the current time, or the saved
+/// rules; `at` is an RFC 3339 timestamp.
+#[tauri::command]
+pub fn evaluate_routing(
+    app: tauri::AppHandle,
+    source: Option<String>,
+    app_name: Option<String>,
+    window_title: Option<String>,
+    at: Option<String>,
+    config: Option<RoutingConfig>,
+) -> Result<RoutingDecision, String> {
+    let now = match at {
+        Some(at) => DateTime::parse_from_rfc3339(&at)
+            .map_err(|e| format!("Invalid time {:?}: {}", at, e))?
+            .with_timezone(&Local),
+        None => Local::now(),
+    };
+    let cfg = config.unwrap_or_else(|| crate::config::current(&app).routing);
+    let context = if app_name.is_some() || window_title.is_some() {
+        Some(AppContext {
+            name: app_name.clone().unwrap_or_default(),
+            id: app_name.unwrap_or_default(),
+            window_title,
+        })
+    } else {
+        app_context::frontmost()
+    };
+    Ok(evaluate(
+        &cfg,
+        source.as_deref().unwrap_or("manual"),
+        context,
+        now,
+    ))
+}
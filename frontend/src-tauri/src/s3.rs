@@ -0,0 +1,562 @@
+// S3 sync — the sync log (see sync_log.rs) in an S3-compatible bucket.
+//
+// Works with AWS S3, MinIO, Backblaze B2, Cloudflare R2 and the like: an
+// endpoint, region and bucket, with every object under `prefix`. Requests
+// are signed with AWS Signature Version 4. Addressing is path-style
+// (endpoint/bucket/key) unless `path_style` is off (bucket.endpoint/key),
+// since most servers other than AWS expect it.
+//
+// Object names suit bucket lifecycle rules: change sets are under
+// <prefix>changes/ and snapshots under <prefix>snapshots/, each named
+// <ms>-<device>.pinupenc and never rewritten, so rules can expire change sets
+// or move snapshots to colder storage by prefix and age. With
+// `lifecycle_cleanup` the app leaves old change sets to such a rule; it
+// should expire them after `retention_days`.
+//
+// Objects are sealed with a passphrase derived from the sync key (see
+// sync_key.rs), or one the user chose. The access key pair and any such
+// passphrase are kept in the OS keyring (see keyring.rs) under
+// "s3:<endpoint>/<bucket>/<prefix>".
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+
+use crate::sync_log::{self, blocking, Remote, SyncReport, Target};
+use crate::{config, keyring, performance, proxy, suspend, sync_key, tray_status};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+const SIGNED_HEADERS: &str = "host;x-amz-content-sha256;x-amz-date";
+
+static KEY: Lazy<Regex> = Lazy::new(|| Regex::new(r"<Key>([^<]*)</Key>").unwrap());
+static NEXT_TOKEN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"<NextContinuationToken>([^<]*)</NextContinuationToken>").unwrap());
+static ERROR_MESSAGE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"<Message>([^<]*)</Message>").unwrap());
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct S3Config {
+    pub enabled: bool,
+    /// e.g. https://s3.us-west-004.backblazeb2.com or http://localhost:9000.
+    pub endpoint: Option<String>,
+    pub region: String,
+    pub bucket: Option<String>,
+    /// Put before every object name; empty uses the bucket root.
+    pub prefix: String,
+    pub path_style: bool,
+    pub interval_minutes: u64,
+    pub snapshot_hours: u64,
+    /// Snapshots kept per device.
+    pub keep_snapshots: usize,
+    /// Change sets older than this are removed (or left to a lifecycle rule).
+    pub retention_days: u64,
+    pub lifecycle_cleanup: bool,
+}
+
+impl Default for S3Config {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: None,
+            region: "us-east-1".into(),
+            bucket: None,
+            prefix: "pin-up-ai/".into(),
+            path_style: true,
+            interval_minutes: 30,
+            snapshot_hours: 24,
+            keep_snapshots: 3,
+            retention_days: 30,
+            lifecycle_cleanup: false,
+        }
+    }
+}
+
+/// Keyring entry, as JSON.
+#[derive(Serialize, Deserialize)]
+struct Credentials {
+    access_key_id: String,
+    secret_access_key: String,
+    /// Empty when the sync key is used.
+    #[serde(default)]
+    passphrase: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct S3Status {
+    config: S3Config,
+    /// Keys and passphrase are in the keyring.
+    has_credentials: bool,
+    syncing: bool,
+    last_sync: Option<SyncReport>,
+    last_error: Option<String>,
+}
+
+static SYNCING: AtomicBool = AtomicBool::new(false);
+static LAST: Mutex<(Option<SyncReport>, Option<String>)> = Mutex::new((None, None));
+static LAST_RUN: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Identifies the bucket and prefix, for the keyring and the sync state.
+fn target_key(cfg: &S3Config) -> Option<String> {
+    Some(format!(
+        "{}/{}/{}",
+        cfg.endpoint.as_deref()?.trim_end_matches('/'),
+        cfg.bucket.as_deref()?,
+        cfg.prefix
+    ))
+}
+
+async fn credentials(key: &str) -> Result<Option<Credentials>, String> {
+    let account = format!("s3:{key}");
+    let Some(text) = blocking(move || keyring::get(&account)).await? else {
+        return Ok(None);
+    };
+    serde_json::from_str(&text)
+        .map(Some)
+        .map_err(|e| format!("Unreadable S3 credentials: {e}"))
+}
+
+// ── Signing ────────────────────────────────────────────────────────────────
+/// HMAC-SHA256 (RFC 2104).
+pub fn hmac(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; 64];
+    if key.len() > block.len() {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let inner = Sha256::new()
+        .chain_update(block.map(|b| b ^ 0x36))
+        .chain_update(data)
+        .finalize();
+    Sha256::new()
+        .chain_update(block.map(|b| b ^ 0x5c))
+        .chain_update(inner)
+        .finalize()
+        .into()
+}
+
+/// SigV4 URI encoding; `/` is kept in paths.
+fn uri_encode(value: &str, keep_slash: bool) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            b'/' if keep_slash => out.push('/'),
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+// ── Bucket ─────────────────────────────────────────────────────────────────
+pub struct Bucket {
+    client: reqwest::Client,
+    scheme: String,
+    /// Host header as signed: host[:port], with the bucket in front unless
+    /// path-style.
+    host: String,
+    /// Path up to the object names: "/<bucket>" path-style, else empty.
+    root: String,
+    region: String,
+    prefix: String,
+    access_key_id: String,
+    secret_access_key: String,
+}
+
+impl Bucket {
+    fn new(app: &AppHandle, cfg: &S3Config, credentials: &Credentials) -> Result<Self, String> {
+        let endpoint = cfg.endpoint.as_deref().ok_or("No S3 endpoint set")?;
+        let bucket = cfg.bucket.as_deref().ok_or("No S3 bucket set")?;
+        let url = reqwest::Url::parse(endpoint).map_err(|e| format!("Invalid endpoint: {e}"))?;
+        let host = url.host_str().ok_or("The endpoint has no host")?;
+        let authority = match url.port() {
+            Some(port) => format!("{host}:{port}"),
+            None => host.to_string(),
+        };
+        let base = url.path().trim_end_matches('/');
+        let (host, root) = if cfg.path_style {
+            (authority, format!("{base}/{}", uri_encode(bucket, false)))
+        } else {
+            (format!("{bucket}.{authority}"), base.to_string())
+        };
+        let client = proxy::client_builder(app)
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            client,
+            scheme: url.scheme().to_string(),
+            host,
+            root,
+            region: cfg.region.clone(),
+            prefix: cfg.prefix.clone(),
+            access_key_id: credentials.access_key_id.clone(),
+            secret_access_key: credentials.secret_access_key.clone(),
+        })
+    }
+
+    /// A signed request for the object `name` (under the prefix), or for the
+    /// bucket itself when `name` is None. Any status but an auth failure.
+    async fn send(
+        &self,
+        method: Method,
+        name: Option<&str>,
+        query: &[(&str, &str)],
+        body: Vec<u8>,
+    ) -> Result<reqwest::Response, String> {
+        let path = match name {
+            Some(name) => format!(
+                "{}/{}",
+                self.root,
+                uri_encode(&format!("{}{name}", self.prefix), true)
+            ),
+            None => format!("{}/", self.root),
+        };
+        let mut query: Vec<String> = query
+            .iter()
+            .map(|(k, v)| format!("{}={}", uri_encode(k, false), uri_encode(v, false)))
+            .collect();
+        query.sort();
+        let query = query.join("&");
+
+        let amz_date = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let date = &amz_date[..8];
+        let payload_hash = hex::encode(Sha256::digest(&body));
+        let canonical_request = format!(
+            "{method}\n{path}\n{query}\nhost:{}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\n{SIGNED_HEADERS}\n{payload_hash}",
+            self.host
+        );
+        let scope = format!("{date}/{}/s3/aws4_request", self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+        let mut key = hmac(
+            format!("AWS4{}", self.secret_access_key).as_bytes(),
+            date.as_bytes(),
+        );
+        for part in [self.region.as_str(), "s3", "aws4_request"] {
+            key = hmac(&key, part.as_bytes());
+        }
+        let signature = hex::encode(hmac(&key, string_to_sign.as_bytes()));
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={SIGNED_HEADERS}, Signature={signature}",
+            self.access_key_id
+        );
+
+        let mut url = format!("{}://{}{path}", self.scheme, self.host);
+        if !query.is_empty() {
+            url = format!("{url}?{query}");
+        }
+        let verb = method.to_string();
+        let response = self
+            .client
+            .request(method, url)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("authorization", authorization)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| format!("S3 {verb} failed: {e}"))?;
+        if response.status() == StatusCode::FORBIDDEN {
+            let text = response.text().await.unwrap_or_default();
+            let message = ERROR_MESSAGE
+                .captures(&text)
+                .map_or("access denied".to_string(), |c| c[1].to_string());
+            return Err(format!("S3 refused the request: {message}"));
+        }
+        Ok(response)
+    }
+
+    async fn request(
+        &self,
+        method: Method,
+        name: Option<&str>,
+        query: &[(&str, &str)],
+        body: Vec<u8>,
+    ) -> Result<reqwest::Response, String> {
+        let verb = method.to_string();
+        let response = self.send(method, name, query, body).await?;
+        match response.status() {
+            status if status.is_success() => Ok(response),
+            status => {
+                let text = response.text().await.unwrap_or_default();
+                let message = ERROR_MESSAGE
+                    .captures(&text)
+                    .map_or(status.to_string(), |c| c[1].to_string());
+                Err(format!(
+                    "S3 {verb} {} failed: {message}",
+                    name.unwrap_or("")
+                ))
+            }
+        }
+    }
+
+    /// Names of the objects under `dir` (relative to the prefix).
+    pub async fn list(&self, dir: &str) -> Result<Vec<String>, String> {
+        let prefix = format!("{}{dir}", self.prefix);
+        let mut names = Vec::new();
+        let mut token: Option<String> = None;
+        loop {
+            let mut query = vec![("list-type", "2"), ("prefix", prefix.as_str())];
+            if let Some(token) = &token {
+                query.push(("continuation-token", token));
+            }
+            let response = self.request(Method::GET, None, &query, Vec::new()).await?;
+            let text = response.text().await.map_err(|e| e.to_string())?;
+            names.extend(
+                KEY.captures_iter(&text)
+                    .filter_map(|c| c[1].rsplit('/').next().map(str::to_string)),
+            );
+            match NEXT_TOKEN.captures(&text) {
+                Some(next) => token = Some(next[1].replace("&amp;", "&")),
+                None => return Ok(names),
+            }
+        }
+    }
+
+    pub async fn get(&self, name: &str) -> Result<Vec<u8>, String> {
+        let response = self
+            .request(Method::GET, Some(name), &[], Vec::new())
+            .await?;
+        let bytes = response.bytes().await.map_err(|e| e.to_string())?;
+        Ok(bytes.to_vec())
+    }
+
+    pub async fn put(&self, name: &str, bytes: Vec<u8>) -> Result<(), String> {
+        self.request(Method::PUT, Some(name), &[], bytes)
+            .await
+            .map(drop)
+    }
+
+    pub async fn delete(&self, name: &str) -> Result<(), String> {
+        let response = self
+            .send(Method::DELETE, Some(name), &[], Vec::new())
+            .await?;
+        match response.status() {
+            status if status.is_success() || status == StatusCode::NOT_FOUND => Ok(()),
+            status => Err(format!("S3 DELETE {name} failed: {status}")),
+        }
+    }
+}
+
+async fn run(app: &AppHandle, cfg: &S3Config) -> Result<SyncReport, String> {
+    let key = target_key(cfg).ok_or("No S3 bucket set")?;
+    let secrets = credentials(&key)
+        .await?
+        .ok_or("No S3 keys saved; set up sync again")?;
+    let bucket = Bucket::new(app, cfg, &secrets)?;
+    let passphrase = match secrets.passphrase.as_str() {
+        "" => sync_key::passphrase("s3").await?,
+        own => own.to_string(),
+    };
+    let target = Target {
+        key,
+        label: "S3".into(),
+        state_file: "s3-sync.json".into(),
+        passphrase: &passphrase,
+        snapshot_hours: cfg.snapshot_hours,
+        keep_snapshots: cfg.keep_snapshots,
+        retention_days: cfg.retention_days,
+        prune: !cfg.lifecycle_cleanup,
+    };
+    sync_log::run(&Remote::S3(bucket), &target).await
+}
+
+async fn sync(app: &AppHandle) -> Result<SyncReport, String> {
+    let cfg = config::current(app).s3;
+    if target_key(&cfg).is_none() {
+        return Err("No S3 bucket set".into());
+    }
+    if SYNCING.swap(true, Ordering::SeqCst) {
+        return Err("A sync is already running".into());
+    }
+    let result = run(app, &cfg).await;
+    SYNCING.store(false, Ordering::SeqCst);
+    *LAST_RUN.lock().unwrap() = Some(Instant::now());
+
+    let mut last = LAST.lock().unwrap();
+    match &result {
+        Ok(report) => {
+            log::info!(
+                "S3 sync: {} uploaded, {} change sets applied ({} created, {} updated, {} deleted, {} skipped)",
+                report.uploaded,
+                report.applied,
+                report.created,
+                report.updated,
+                report.deleted,
+                report.skipped
+            );
+            *last = (Some(report.clone()), None);
+            tray_status::synced();
+            app.emit_all("s3-synced", report).ok();
+        }
+        Err(e) => {
+            log::warn!("S3 sync failed: {}", e);
+            last.1 = Some(e.clone());
+        }
+    }
+    result
+}
+
+async fn status(app: &AppHandle) -> S3Status {
+    let cfg = config::current(app).s3;
+    let has_credentials = match target_key(&cfg) {
+        Some(key) => credentials(&key).await.is_ok_and(|c| c.is_some()),
+        None => false,
+    };
+    let (last_sync, last_error) = LAST.lock().unwrap().clone();
+    S3Status {
+        config: cfg,
+        has_credentials,
+        syncing: SYNCING.load(Ordering::SeqCst),
+        last_sync,
+        last_error,
+    }
+}
+
+/// Syncs on the configured interval while enabled.
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let cfg = config::current(&handle).s3;
+            if !cfg.enabled || target_key(&cfg).is_none() || suspend::paused() {
+                continue;
+            }
+            let factor = performance::capabilities(&handle).poll_factor as u32;
+            let interval = Duration::from_secs(cfg.interval_minutes.max(1) * 60) * factor;
+            let due = LAST_RUN
+                .lock()
+                .unwrap()
+                .map_or(true, |at| at.elapsed() >= interval);
+            if due && crate::BACKEND_PORT.load(Ordering::SeqCst) != 0 {
+                sync(&handle).await.ok();
+            }
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub async fn get_s3_status(app: AppHandle) -> S3Status {
+    status(&app).await
+}
+
+/// Turns sync on for `bucket` at `endpoint`. The keys and passphrase are
+/// saved to the keyring; secrets left out keep the saved ones. With no
+/// passphrase at all, objects are sealed with the sync key.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn set_s3_sync(
+    app: AppHandle,
+    endpoint: String,
+    region: Option<String>,
+    bucket: String,
+    prefix: Option<String>,
+    path_style: Option<bool>,
+    access_key_id: Option<String>,
+    secret_access_key: Option<String>,
+    passphrase: Option<String>,
+) -> Result<S3Status, String> {
+    let endpoint = endpoint.trim().trim_end_matches('/').to_string();
+    let parsed = reqwest::Url::parse(&endpoint).map_err(|e| format!("Invalid endpoint: {e}"))?;
+    if !matches!(parsed.scheme(), "https" | "http") {
+        return Err("The endpoint must start with https://".into());
+    }
+    let bucket = bucket.trim().to_string();
+    if bucket.is_empty() || bucket.contains('/') {
+        return Err("Enter a bucket name".into());
+    }
+    let mut cfg = config::current(&app).s3;
+    cfg.endpoint = Some(endpoint);
+    cfg.bucket = Some(bucket);
+    if let Some(region) = region
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+    {
+        cfg.region = region;
+    }
+    if let Some(prefix) = prefix {
+        let prefix = prefix.trim().trim_matches('/');
+        cfg.prefix = if prefix.is_empty() {
+            String::new()
+        } else {
+            format!("{prefix}/")
+        };
+    }
+    if let Some(path_style) = path_style {
+        cfg.path_style = path_style;
+    }
+    cfg.enabled = true;
+
+    let key = target_key(&cfg).ok_or("No S3 bucket set")?;
+    let saved = credentials(&key).await.ok().flatten();
+    let keep = |given: Option<String>, saved: Option<String>| {
+        given
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .or(saved)
+    };
+    let access_key_id = keep(
+        access_key_id,
+        saved.as_ref().map(|c| c.access_key_id.clone()),
+    )
+    .ok_or("Enter the access key ID")?;
+    let secret_access_key = keep(
+        secret_access_key,
+        saved.as_ref().map(|c| c.secret_access_key.clone()),
+    )
+    .ok_or("Enter the secret access key")?;
+    let passphrase = passphrase
+        .filter(|p| !p.is_empty())
+        .or_else(|| saved.map(|c| c.passphrase))
+        .unwrap_or_default();
+    if passphrase.is_empty() {
+        sync_key::passphrase("s3").await?;
+    } else if passphrase.chars().count() < 8 {
+        return Err("The passphrase must be at least 8 characters".into());
+    }
+    let secret = serde_json::to_string(&Credentials {
+        access_key_id,
+        secret_access_key,
+        passphrase,
+    })
+    .map_err(|e| e.to_string())?;
+    let account = format!("s3:{key}");
+    blocking(move || keyring::set(&account, &secret)).await?;
+
+    config::update(&app, |c| c.s3 = cfg)?;
+    sync(&app).await?;
+    Ok(status(&app).await)
+}
+
+/// Turns sync off; `forget` also removes the saved keys and passphrase.
+#[tauri::command]
+pub async fn disable_s3_sync(app: AppHandle, forget: bool) -> Result<S3Status, String> {
+    let key = target_key(&config::current(&app).s3);
+    config::update(&app, |c| c.s3.enabled = false)?;
+    if let (true, Some(key)) = (forget, key) {
+        let account = format!("s3:{key}");
+        blocking(move || keyring::delete(&account)).await?;
+    }
+    Ok(status(&app).await)
+}
+
+#[tauri::command]
+pub async fn sync_s3_now(app: AppHandle) -> Result<SyncReport, String> {
+    sync(&app).await
+}
@@ -0,0 +1,338 @@
+// Screenshot — full-screen and region capture into the attachment store.
+//
+// `capture_screen` grabs every display (or one, by id), stores each image as
+// a PNG attachment and returns its metadata. SCREENSHOT_SHORTCUT does the
+// same and files the result as a snippet in one keystroke.
+//
+// `capture_region` covers each display with a transparent overlay window
+// (region.html) and waits for the user to drag a rectangle on one of them.
+// The overlay reports the selection in CSS pixels together with its own
+// viewport size, so the crop is computed as a fraction of the captured
+// image and never depends on how a platform scales display coordinates.
+// REGION_SHORTCUT files the clipped region as a snippet.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, GlobalShortcutManager, Manager, Window, WindowBuilder, WindowUrl};
+use tokio::sync::oneshot;
+
+use crate::attachments;
+use crate::capture::{self, Capture};
+use crate::i18n;
+use crate::ocr;
+
+pub const SCREENSHOT_SHORTCUT: &str = "CmdOrCtrl+Shift+S";
+pub const REGION_SHORTCUT: &str = "CmdOrCtrl+Alt+S";
+
+const OVERLAY_PREFIX: &str = "region-";
+/// Time for the compositor to remove the overlays before the grab.
+const OVERLAY_SETTLE: Duration = Duration::from_millis(150);
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Screenshot {
+    /// Attachment hash of the PNG.
+    pub attachment: String,
+    pub monitor: String,
+    pub primary: bool,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub size: u64,
+}
+
+/// A rectangle dragged on an overlay, in the overlay's CSS pixels.
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct Selection {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub viewport_width: f64,
+    pub viewport_height: f64,
+}
+
+/// Display geometry as xcap reports it, copied out so it can cross awaits.
+struct Display {
+    id: u32,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+/// Monitor id and rectangle, or `None` when the user cancelled.
+type RegionResult = Option<(u32, Selection)>;
+
+/// The in-flight `capture_region` call, resolved by the overlay.
+static PENDING: Lazy<Mutex<Option<oneshot::Sender<RegionResult>>>> = Lazy::new(|| Mutex::new(None));
+
+fn store(monitor: &xcap::Monitor, image: &xcap::image::RgbaImage) -> Result<Screenshot, String> {
+    let mut png = std::io::Cursor::new(Vec::new());
+    image
+        .write_to(&mut png, xcap::image::ImageFormat::Png)
+        .map_err(|e| format!("Could not encode screenshot: {e}"))?;
+    let attachment = attachments::store_bytes(png.get_ref(), "png", None)?;
+    Ok(Screenshot {
+        attachment: attachment.hash,
+        monitor: monitor.name().to_string(),
+        primary: monitor.is_primary(),
+        x: monitor.x(),
+        y: monitor.y(),
+        width: image.width(),
+        height: image.height(),
+        size: attachment.size,
+    })
+}
+
+fn grab(monitor_id: Option<u32>) -> Result<Vec<Screenshot>, String> {
+    let monitors = xcap::Monitor::all().map_err(|e| format!("Cannot list displays: {e}"))?;
+    let monitors: Vec<_> = monitors
+        .into_iter()
+        .filter(|m| monitor_id.map_or(true, |id| m.id() == id))
+        .collect();
+    if monitors.is_empty() {
+        return Err("No matching display".into());
+    }
+
+    monitors
+        .iter()
+        .map(|monitor| {
+            let image = monitor
+                .capture_image()
+                .map_err(|e| format!("Screen capture failed: {e}"))?;
+            store(monitor, &image)
+        })
+        .collect()
+}
+
+fn grab_region(monitor_id: u32, sel: Selection) -> Result<Screenshot, String> {
+    let monitor = xcap::Monitor::all()
+        .map_err(|e| format!("Cannot list displays: {e}"))?
+        .into_iter()
+        .find(|m| m.id() == monitor_id)
+        .ok_or("Display went away")?;
+    let image = monitor
+        .capture_image()
+        .map_err(|e| format!("Screen capture failed: {e}"))?;
+
+    let scale_x = image.width() as f64 / sel.viewport_width.max(1.0);
+    let scale_y = image.height() as f64 / sel.viewport_height.max(1.0);
+    let x = ((sel.x * scale_x).round().max(0.0) as u32).min(image.width() - 1);
+    let y = ((sel.y * scale_y).round().max(0.0) as u32).min(image.height() - 1);
+    let width = ((sel.width * scale_x).round() as u32).clamp(1, image.width() - x);
+    let height = ((sel.height * scale_y).round() as u32).clamp(1, image.height() - y);
+    let cropped = xcap::image::imageops::crop_imm(&image, x, y, width, height).to_image();
+    store(&monitor, &cropped)
+}
+
+async fn capture_screens(monitor_id: Option<u32>) -> Result<Vec<Screenshot>, String> {
+    tauri::async_runtime::spawn_blocking(move || grab(monitor_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// ── Region overlay ─────────────────────────────────────────────────────────
+fn displays() -> Result<Vec<Display>, String> {
+    let monitors = xcap::Monitor::all().map_err(|e| format!("Cannot list displays: {e}"))?;
+    Ok(monitors
+        .iter()
+        .map(|m| Display {
+            id: m.id(),
+            x: m.x(),
+            y: m.y(),
+            width: m.width(),
+            height: m.height(),
+        })
+        .collect())
+}
+
+/// xcap reports physical pixels on Windows and logical points elsewhere.
+fn place(window: &Window, display: &Display) -> tauri::Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+        window.set_position(tauri::PhysicalPosition::new(display.x, display.y))?;
+        window.set_size(tauri::PhysicalSize::new(display.width, display.height))
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        window.set_position(tauri::LogicalPosition::new(display.x, display.y))?;
+        window.set_size(tauri::LogicalSize::new(display.width, display.height))
+    }
+}
+
+fn open_overlay(app: &AppHandle, display: &Display) -> Result<(), String> {
+    let window = WindowBuilder::new(
+        app,
+        format!("{}{}", OVERLAY_PREFIX, display.id),
+        WindowUrl::App("region.html".into()),
+    )
+    .title(i18n::t("window.select_region"))
+    .decorations(false)
+    .resizable(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .transparent(true)
+    .visible(false)
+    .build()
+    .map_err(|e| format!("Could not open selection overlay: {e}"))?;
+    place(&window, display).map_err(|e| e.to_string())?;
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().ok();
+    Ok(())
+}
+
+fn close_overlays(app: &AppHandle) {
+    for (label, window) in app.windows() {
+        if label.starts_with(OVERLAY_PREFIX) {
+            window.close().ok();
+        }
+    }
+}
+
+fn resolve(result: RegionResult) {
+    if let Some(tx) = PENDING.lock().unwrap().take() {
+        tx.send(result).ok();
+    }
+}
+
+/// Shows the overlays and waits for a selection; `None` if cancelled.
+async fn select_and_grab(app: &AppHandle) -> Result<Option<Screenshot>, String> {
+    let rx = {
+        let mut pending = PENDING.lock().unwrap();
+        // A stale sender whose overlays were closed some other way (Alt+F4)
+        // must not block the next selection.
+        let overlays_open = app.windows().keys().any(|l| l.starts_with(OVERLAY_PREFIX));
+        if overlays_open && pending.as_ref().is_some_and(|tx| !tx.is_closed()) {
+            return Err("A region selection is already in progress".into());
+        }
+        let (tx, rx) = oneshot::channel();
+        *pending = Some(tx);
+        rx
+    };
+
+    let opened = displays().and_then(|displays| {
+        displays
+            .iter()
+            .try_for_each(|display| open_overlay(app, display))
+    });
+    if let Err(e) = opened {
+        PENDING.lock().unwrap().take();
+        close_overlays(app);
+        return Err(e);
+    }
+
+    let selection = rx.await.ok().flatten();
+    close_overlays(app);
+    let Some((monitor_id, sel)) = selection else {
+        return Ok(None);
+    };
+    if sel.width < 2.0 || sel.height < 2.0 {
+        return Ok(None);
+    }
+    tokio::time::sleep(OVERLAY_SETTLE).await;
+    tauri::async_runtime::spawn_blocking(move || grab_region(monitor_id, sel))
+        .await
+        .map_err(|e| e.to_string())?
+        .map(Some)
+}
+
+// ── Hotkeys ────────────────────────────────────────────────────────────────
+async fn file_screenshots(app: &AppHandle, shots: &[Screenshot]) -> Result<(), String> {
+    let mut parts = Vec::new();
+    for shot in shots {
+        parts.push(format!(
+            "![{}](attachment:{})",
+            shot.monitor, shot.attachment
+        ));
+        if let Some(text) = ocr::attachment_text(app, &shot.attachment).await {
+            parts.push(text);
+        }
+    }
+    let body = parts.join("\n\n");
+    let capture = Capture {
+        title: Some(format!(
+            "Screenshot {}",
+            chrono::Local::now().format("%Y-%m-%d %H:%M")
+        )),
+        body,
+        source: "screenshot".into(),
+        ..Default::default()
+    };
+    capture::submit(app, capture).await.map(|_| ())
+}
+
+pub fn register_shortcut(app: &AppHandle) {
+    let mut shortcuts = app.global_shortcut_manager();
+
+    let handle = app.clone();
+    let result = shortcuts.register(SCREENSHOT_SHORTCUT, move || {
+        let handle = handle.clone();
+        tauri::async_runtime::spawn(async move {
+            let result = match capture_screens(None).await {
+                Ok(shots) => file_screenshots(&handle, &shots).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                log::warn!("Screenshot capture failed: {}", e);
+            }
+        });
+    });
+    if let Err(e) = result {
+        log::warn!("Could not register {}: {}", SCREENSHOT_SHORTCUT, e);
+    }
+
+    let handle = app.clone();
+    let result = shortcuts.register(REGION_SHORTCUT, move || {
+        let handle = handle.clone();
+        tauri::async_runtime::spawn(async move {
+            let result = match select_and_grab(&handle).await {
+                Ok(Some(shot)) => file_screenshots(&handle, &[shot]).await,
+                Ok(None) => Ok(()),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                log::warn!("Region capture failed: {}", e);
+            }
+        });
+    });
+    if let Err(e) = result {
+        log::warn!("Could not register {}: {}", REGION_SHORTCUT, e);
+    }
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Captures all displays, or only `monitor` (an xcap display id).
+#[tauri::command]
+pub async fn capture_screen(monitor: Option<u32>) -> Result<Vec<Screenshot>, String> {
+    capture_screens(monitor).await
+}
+
+/// Lets the user drag a rectangle on any display and returns the clipped
+/// image, or `None` if the selection was cancelled. For region captures
+/// `width`/`height` are the clipped size; `x`/`y` stay the display origin.
+#[tauri::command]
+pub async fn capture_region(app: AppHandle) -> Result<Option<Screenshot>, String> {
+    select_and_grab(&app).await
+}
+
+/// Called by region.html when the mouse is released.
+#[tauri::command]
+pub fn finish_region_selection(window: Window, selection: Selection) -> Result<(), String> {
+    let monitor_id = window
+        .label()
+        .strip_prefix(OVERLAY_PREFIX)
+        .and_then(|id| id.parse().ok())
+        .ok_or("Not a selection overlay")?;
+    resolve(Some((monitor_id, selection)));
+    Ok(())
+}
+
+/// Called by region.html on Escape or right-click.
+#[tauri::command]
+pub fn cancel_region_selection() {
+    resolve(None);
+}
@@ -0,0 +1,73 @@
+// Secret detection — regexes for credentials that should never be captured.
+//
+// Built-in patterns cover common API keys and private key blocks; users can
+// add their own. Matching reports only the kind of secret, never the text;
+// redaction replaces each match with a `[REDACTED:<kind>]` marker.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+const BUILTIN: &[(&str, &str)] = &[
+    ("aws_access_key", r"\b(AKIA|ASIA)[0-9A-Z]{16}\b"),
+    ("github_token", r"\bgh[pousr]_[A-Za-z0-9]{36,}\b"),
+    ("openai_key", r"\bsk-[A-Za-z0-9_-]{20,}"),
+    ("slack_token", r"\bxox[abprs]-[A-Za-z0-9-]{10,}"),
+    ("google_api_key", r"\bAIza[0-9A-Za-z_-]{35}\b"),
+    ("stripe_key", r"\b[rs]k_live_[0-9A-Za-z]{24,}\b"),
+    (
+        "private_key",
+        r"-----BEGIN [A-Z ]*PRIVATE KEY-----(?s:.*?)(?:-----END [A-Z ]*PRIVATE KEY-----|\z)",
+    ),
+    (
+        "jwt",
+        r"\beyJ[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}",
+    ),
+];
+
+static PATTERNS: Lazy<Vec<(&'static str, Regex)>> = Lazy::new(|| {
+    BUILTIN
+        .iter()
+        .map(|(kind, re)| (*kind, Regex::new(re).unwrap()))
+        .collect()
+});
+
+/// Returns the kind of the first secret found in `text`, if any. `extra` are
+/// user-supplied patterns; invalid ones are skipped.
+pub fn find_secret(text: &str, extra: &[String]) -> Option<String> {
+    for (kind, re) in PATTERNS.iter() {
+        if re.is_match(text) {
+            return Some((*kind).to_string());
+        }
+    }
+    for pattern in extra {
+        match Regex::new(pattern) {
+            Ok(re) if re.is_match(text) => return Some("custom".into()),
+            Ok(_) => {}
+            Err(e) => log::warn!("Invalid secret pattern {:?}: {}", pattern, e),
+        }
+    }
+    None
+}
+
+/// Replaces every secret in `text` and returns the kinds that were found.
+pub fn redact_secrets(text: &str, extra: &[String]) -> (String, Vec<String>) {
+    let mut out = text.to_string();
+    let mut kinds = Vec::new();
+    let custom: Vec<(&str, Regex)> = extra
+        .iter()
+        .filter_map(|p| Regex::new(p).ok().map(|re| ("custom", re)))
+        .collect();
+    for (kind, re) in PATTERNS
+        .iter()
+        .map(|(k, re)| (*k, re))
+        .chain(custom.iter().map(|(k, re)| (*k, re)))
+    {
+        if re.is_match(&out) {
+            out = re
+                .replace_all(&out, format!("[REDACTED:{}]", kind).as_str())
+                .into_owned();
+            kinds.push(kind.to_string());
+        }
+    }
+    (out, kinds)
+}
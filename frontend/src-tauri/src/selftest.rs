@@ -0,0 +1,481 @@
+// Self-test — exercise the critical paths against a throwaway profile.
+//
+// `run_self_test` (or the nightly maintenance window) starts a second
+// sidecar on its own port with a temp database under runtime_dir(), then
+// checks, in order:
+//   data_dir   write/read/delete 1 MiB in the real data dir
+//   spawn      the sidecar binary starts
+//   health     /api/health answers
+//   token      the first-run install token is printed and accepted
+//   capture    POST /snippets
+//   search     the snippet is found through full-text search
+//   delete     DELETE /snippets/{id}, then GET returns 404
+//   backup     POST /backup/run writes a valid SQLite copy + backup.json
+//   cleanup    the sidecar stops and the temp profile is removed
+// A failed step marks the steps that depend on it as skipped. The user's
+// own backend and database are never touched. The last report is kept in
+// data_dir()/selftest.json.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use chrono::{Local, Timelike};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::api::process::{Command, CommandChild, CommandEvent};
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+
+use crate::{config, power};
+
+const SQLITE_MAGIC: &[u8; 16] = b"SQLite format 3\0";
+/// How often the maintenance loop checks whether the window has opened.
+const SCHEDULE_TICK: Duration = Duration::from_secs(10 * 60);
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static TOKEN_LINE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"INSTALL TOKEN \(save this\): ([A-Za-z0-9_-]+)").unwrap());
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct SelfTestConfig {
+    /// Run once a night during the maintenance window.
+    pub nightly: bool,
+    /// Local hour (0–23) the maintenance window opens.
+    pub hour: u32,
+}
+
+impl Default for SelfTestConfig {
+    fn default() -> Self {
+        Self {
+            nightly: true,
+            hour: 3,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Pass,
+    Fail,
+    Skip,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CheckResult {
+    pub name: String,
+    pub outcome: Outcome,
+    pub duration_ms: u64,
+    pub detail: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SelfTestReport {
+    pub started_at: String,
+    pub trigger: String,
+    pub duration_ms: u64,
+    pub passed: bool,
+    pub checks: Vec<CheckResult>,
+}
+
+fn report_path() -> PathBuf {
+    crate::data_dir().join("selftest.json")
+}
+
+fn last_report() -> Option<SelfTestReport> {
+    let text = std::fs::read_to_string(report_path()).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+// ── Runner ─────────────────────────────────────────────────────────────────
+#[derive(Default)]
+struct Matrix {
+    checks: Vec<CheckResult>,
+}
+
+impl Matrix {
+    /// Records `result` under `name`; returns the value on success.
+    fn record<T>(
+        &mut self,
+        name: &str,
+        started: Instant,
+        result: Result<(T, String), String>,
+    ) -> Option<T> {
+        let duration_ms = started.elapsed().as_millis() as u64;
+        let (outcome, detail, value) = match result {
+            Ok((value, detail)) => (Outcome::Pass, detail, Some(value)),
+            Err(e) => (Outcome::Fail, e, None),
+        };
+        self.checks.push(CheckResult {
+            name: name.into(),
+            outcome,
+            duration_ms,
+            detail,
+        });
+        value
+    }
+
+    fn skip(&mut self, names: &[&str], reason: &str) {
+        for name in names {
+            self.checks.push(CheckResult {
+                name: (*name).into(),
+                outcome: Outcome::Skip,
+                duration_ms: 0,
+                detail: format!("skipped: {}", reason),
+            });
+        }
+    }
+}
+
+fn check_data_dir() -> Result<((), String), String> {
+    let path = crate::data_dir().join("selftest.tmp");
+    let payload: Vec<u8> = (0..1024 * 1024).map(|i| (i % 251) as u8).collect();
+    std::fs::write(&path, &payload).map_err(|e| format!("write failed: {e}"))?;
+    let read = std::fs::read(&path).map_err(|e| format!("read failed: {e}"));
+    std::fs::remove_file(&path).map_err(|e| format!("delete failed: {e}"))?;
+    if read? != payload {
+        return Err("read back different bytes".into());
+    }
+    Ok(((), "1 MiB round-trip".into()))
+}
+
+struct Sidecar {
+    child: CommandChild,
+    port: u16,
+    token: oneshot::Receiver<String>,
+}
+
+fn spawn_sidecar(profile: &Path) -> Result<(Sidecar, String), String> {
+    let port = portpicker::pick_unused_port().ok_or("No free port")?;
+    let dir = profile.to_string_lossy().to_string();
+    let (mut rx, child) = Command::new_sidecar("pinup-backend")
+        .map_err(|e| format!("Sidecar binary not found: {e}"))?
+        .args(["--port", &port.to_string()])
+        .envs(HashMap::from([
+            ("PINUP_PORT".into(), port.to_string()),
+            (
+                "PINUP_DB".into(),
+                profile.join("pinup.db").to_string_lossy().to_string(),
+            ),
+            ("PINUP_HOST".into(), "127.0.0.1".into()),
+            (
+                "PINUP_BACKUP_DIR".into(),
+                profile.join("backups").to_string_lossy().to_string(),
+            ),
+            ("TMPDIR".into(), dir.clone()),
+            ("TEMP".into(), dir.clone()),
+            ("TMP".into(), dir),
+        ]))
+        .spawn()
+        .map_err(|e| format!("Failed to spawn sidecar: {e}"))?;
+
+    let (token_tx, token) = oneshot::channel();
+    tauri::async_runtime::spawn(async move {
+        let mut token_tx = Some(token_tx);
+        while let Some(event) = rx.recv().await {
+            match event {
+                CommandEvent::Stdout(line) | CommandEvent::Stderr(line) => {
+                    if let Some(caps) = TOKEN_LINE.captures(&line) {
+                        if let Some(tx) = token_tx.take() {
+                            tx.send(caps[1].to_string()).ok();
+                        }
+                        continue;
+                    }
+                    log::debug!("[selftest-backend] {}", line);
+                }
+                CommandEvent::Terminated(_) => break,
+                _ => {}
+            }
+        }
+    });
+    let detail = format!("pid {} on port {}", child.pid(), port);
+    Ok((Sidecar { child, port, token }, detail))
+}
+
+struct Api {
+    client: reqwest::Client,
+    base: String,
+    token: String,
+}
+
+impl Api {
+    /// Returns the status and parsed body (Null if empty or not JSON).
+    async fn call(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<(u16, Value), String> {
+        let mut req = self
+            .client
+            .request(method, format!("{}{}", self.base, path))
+            .bearer_auth(&self.token);
+        if let Some(body) = body {
+            req = req.json(&body);
+        }
+        let resp = req
+            .send()
+            .await
+            .map_err(|e| format!("request failed: {e}"))?;
+        let status = resp.status().as_u16();
+        let text = resp.text().await.unwrap_or_default();
+        Ok((status, serde_json::from_str(&text).unwrap_or(Value::Null)))
+    }
+
+    async fn expect(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<Value, String> {
+        match self.call(method, path, body).await? {
+            (status, value) if (200..300).contains(&status) => Ok(value),
+            (status, value) => Err(format!("HTTP {}: {}", status, value)),
+        }
+    }
+}
+
+fn verify_backup(profile: &Path, info: &Value) -> Result<((), String), String> {
+    let name = info["name"].as_str().ok_or("no backup name in response")?;
+    let dir = profile.join("backups").join(name);
+    let db = std::fs::read(dir.join("pinup.db")).map_err(|e| format!("backup db missing: {e}"))?;
+    if db.len() < 100 || &db[..16] != SQLITE_MAGIC {
+        return Err("backup is not a SQLite database".into());
+    }
+    if info["db_size_bytes"].as_u64() != Some(db.len() as u64) {
+        return Err("backup size does not match the reported size".into());
+    }
+    let meta = std::fs::read_to_string(dir.join("backup.json"))
+        .map_err(|e| format!("backup.json missing: {e}"))?;
+    serde_json::from_str::<Value>(&meta).map_err(|e| format!("backup.json invalid: {e}"))?;
+    Ok(((), format!("{} ({} KB)", name, db.len() / 1024)))
+}
+
+/// Removes the temp profile; Windows may hold the db briefly after exit.
+async fn remove_profile(profile: &Path) -> Result<(), String> {
+    let mut last = None;
+    for _ in 0..10 {
+        match std::fs::remove_dir_all(profile) {
+            Ok(()) => return Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => last = Some(e),
+        }
+        tokio::time::sleep(Duration::from_millis(300)).await;
+    }
+    Err(format!("could not remove temp profile: {}", last.unwrap()))
+}
+
+async fn exercise(matrix: &mut Matrix, profile: &Path) -> Option<Sidecar> {
+    let started = Instant::now();
+    let mut sidecar = matrix.record("spawn", started, spawn_sidecar(profile))?;
+
+    let started = Instant::now();
+    let health = crate::wait_for_health(sidecar.port, 40, 500)
+        .await
+        .map(|_| ((), "healthy".to_string()));
+    if matrix.record("health", started, health).is_none() {
+        matrix.skip(
+            &["token", "capture", "search", "delete", "backup"],
+            "backend unhealthy",
+        );
+        return Some(sidecar);
+    }
+
+    let started = Instant::now();
+    let token = match tokio::time::timeout(Duration::from_secs(10), &mut sidecar.token).await {
+        Ok(Ok(token)) => Ok(token),
+        _ => Err("install token was not printed".to_string()),
+    };
+    let api = match token {
+        Ok(token) => Api {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(15))
+                .build()
+                .unwrap(),
+            base: format!("http://127.0.0.1:{}/api", sidecar.port),
+            token,
+        },
+        Err(e) => {
+            matrix.record::<()>("token", started, Err(e));
+            matrix.skip(&["capture", "search", "delete", "backup"], "no token");
+            return Some(sidecar);
+        }
+    };
+    let accepted = api
+        .expect(reqwest::Method::GET, "/snippets?limit=1", None)
+        .await
+        .map(|_| ((), "accepted".to_string()));
+    if matrix.record("token", started, accepted).is_none() {
+        matrix.skip(&["capture", "search", "delete", "backup"], "token rejected");
+        return Some(sidecar);
+    }
+
+    let marker = format!("selftest{}", Local::now().timestamp_millis());
+    let started = Instant::now();
+    let created = api
+        .expect(
+            reqwest::Method::POST,
+            "/snippets",
+            Some(json!({
+                "title": "Pin-Up self-test",
+                "body": format!("Self-test snippet {}", marker),
+                "source": "selftest",
+            })),
+        )
+        .await
+        .and_then(|v| {
+            v["id"]
+                .as_str()
+                .map(str::to_string)
+                .ok_or("no id in response".into())
+        })
+        .map(|id| (id.clone(), id));
+    let id = matrix.record("capture", started, created);
+
+    if let Some(id) = id {
+        let started = Instant::now();
+        let found = api
+            .expect(reqwest::Method::GET, &format!("/search?q={}", marker), None)
+            .await
+            .and_then(|v| {
+                let hits = v["results"].as_array().cloned().unwrap_or_default();
+                if hits.iter().any(|r| r["id"] == id.as_str()) {
+                    Ok(((), format!("{} hit(s)", hits.len())))
+                } else {
+                    Err("snippet not found by search".into())
+                }
+            });
+        matrix.record("search", started, found);
+
+        let started = Instant::now();
+        let path = format!("/snippets/{}", id);
+        let deleted = match api.expect(reqwest::Method::DELETE, &path, None).await {
+            Ok(_) => match api.call(reqwest::Method::GET, &path, None).await {
+                Ok((404, _)) => Ok(((), "gone".to_string())),
+                Ok((status, _)) => Err(format!("still readable after delete (HTTP {})", status)),
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        };
+        matrix.record("delete", started, deleted);
+    } else {
+        matrix.skip(&["search", "delete"], "capture failed");
+    }
+
+    let started = Instant::now();
+    let backup = match api.expect(reqwest::Method::POST, "/backup/run", None).await {
+        Ok(info) => verify_backup(profile, &info),
+        Err(e) => Err(e),
+    };
+    matrix.record("backup", started, backup);
+    Some(sidecar)
+}
+
+pub async fn run(app: &AppHandle, trigger: &str) -> Result<SelfTestReport, String> {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("A self-test is already running".into());
+    }
+    let started_at = Local::now();
+    let started = Instant::now();
+    let mut matrix = Matrix::default();
+    app.emit_all("self-test-started", trigger).ok();
+
+    let step = Instant::now();
+    matrix.record("data_dir", step, check_data_dir());
+
+    let profile =
+        crate::runtime_dir().join(format!("selftest-{}", started_at.format("%Y%m%d-%H%M%S")));
+    let sidecar = match std::fs::create_dir_all(&profile) {
+        Ok(()) => exercise(&mut matrix, &profile).await,
+        Err(e) => {
+            let reason = format!("cannot create temp profile: {e}");
+            matrix.skip(
+                &[
+                    "spawn", "health", "token", "capture", "search", "delete", "backup",
+                ],
+                &reason,
+            );
+            None
+        }
+    };
+
+    let step = Instant::now();
+    if let Some(sidecar) = sidecar {
+        sidecar.child.kill().ok();
+    }
+    let cleanup = remove_profile(&profile)
+        .await
+        .map(|_| ((), "removed".to_string()));
+    matrix.record("cleanup", step, cleanup);
+
+    let passed = matrix.checks.iter().all(|c| c.outcome == Outcome::Pass);
+    let report = SelfTestReport {
+        started_at: started_at.to_rfc3339(),
+        trigger: trigger.into(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        passed,
+        checks: matrix.checks,
+    };
+    match serde_json::to_string_pretty(&report) {
+        Ok(text) => {
+            if let Err(e) = std::fs::write(report_path(), text) {
+                log::warn!("Could not save self-test report: {}", e);
+            }
+        }
+        Err(e) => log::warn!("Could not serialize self-test report: {}", e),
+    }
+    if passed {
+        log::info!("Self-test passed in {} ms", report.duration_ms);
+    } else {
+        let failed: Vec<_> = report
+            .checks
+            .iter()
+            .filter(|c| c.outcome == Outcome::Fail)
+            .map(|c| format!("{} ({})", c.name, c.detail))
+            .collect();
+        log::warn!("Self-test failed: {}", failed.join("; "));
+    }
+    app.emit_all("self-test-finished", &report).ok();
+    RUNNING.store(false, Ordering::SeqCst);
+    Ok(report)
+}
+
+// ── Maintenance window ─────────────────────────────────────────────────────
+/// Runs the self-test once per night at `selftest.hour`, local time.
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(SCHEDULE_TICK).await;
+            let cfg = config::current(&handle).selftest;
+            let now = Local::now();
+            if !cfg.nightly || now.hour() != cfg.hour || power::saving() {
+                continue;
+            }
+            let today = now.format("%Y-%m-%d").to_string();
+            let ran_today = last_report().is_some_and(|r| r.started_at.starts_with(&today));
+            if ran_today {
+                continue;
+            }
+            if let Err(e) = run(&handle, "nightly").await {
+                log::warn!("Nightly self-test did not run: {}", e);
+            }
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub async fn run_self_test(app: AppHandle) -> Result<SelfTestReport, String> {
+    run(&app, "manual").await
+}
+
+#[tauri::command]
+pub fn get_last_self_test() -> Option<SelfTestReport> {
+    last_report()
+}
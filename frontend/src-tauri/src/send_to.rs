@@ -0,0 +1,308 @@
+// Send to — "Send to Pin-Up AI" in Windows Explorer.
+//
+// When turned on, two entries start this binary with `--send-to` and the
+// selected files: a verb on every file's context menu
+// (HKCU\Software\Classes\*\shell\PinUpAI; under "Show more options" on
+// Windows 11) and a shortcut in the user's SendTo folder. The verb starts
+// one process per file, the shortcut one for the whole selection. Either
+// way the process hands the paths to the running app (see instance.rs),
+// which imports them like a drop on the main window (see import.rs): text
+// and Markdown files become text snippets, images and PDFs attachments.
+// With no paths the process sends what is piped to it as one text snippet,
+// so `type notes.txt | "Pin-Up AI.exe" --send-to` works from a console.
+// Other platforms have other ways in (Services on macOS, deep links).
+
+use std::io::{IsTerminal, Read, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::capture::{self, Capture};
+use crate::import::{self, ImportRules};
+use crate::instance::{self, failure};
+use crate::{backend, config, tasks};
+
+const ARG: &str = "--send-to";
+const SOURCE: &str = "send_to";
+/// Piped text beyond this is cut off.
+const MAX_TEXT: u64 = 16 * 1024 * 1024;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct SendToConfig {
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SendToStatus {
+    #[serde(flatten)]
+    config: SendToConfig,
+    /// Explorer has these entries on Windows only.
+    supported: bool,
+    registered: bool,
+    error: Option<String>,
+}
+
+static ERROR: Mutex<Option<String>> = Mutex::new(None);
+
+// ── Helper side ────────────────────────────────────────────────────────────
+/// The paths after `--send-to`, if this process was started with it.
+pub fn from_args() -> Option<Vec<PathBuf>> {
+    let mut args = std::env::args_os().skip(1);
+    if args.next()? != ARG {
+        return None;
+    }
+    let cwd = std::env::current_dir().unwrap_or_default();
+    Some(args.map(|arg| cwd.join(arg)).collect())
+}
+
+fn piped_text() -> Option<String> {
+    let stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        return None;
+    }
+    let mut text = String::new();
+    stdin.lock().take(MAX_TEXT).read_to_string(&mut text).ok()?;
+    Some(text).filter(|t| !t.trim().is_empty())
+}
+
+/// Hands the files (or piped text) to the running app and returns the exit
+/// code. Explorer shows no console, so failures also get a message box.
+pub fn forward(paths: Vec<PathBuf>) -> i32 {
+    let message = if !paths.is_empty() {
+        Ok(json!({ "paths": paths }))
+    } else {
+        piped_text()
+            .map(|text| json!({ "text": text }))
+            .ok_or_else(|| "Nothing to send: no files and no piped text".to_string())
+    };
+    let reply = message
+        .and_then(|message| instance::relay("send_to", message, &mut false))
+        .unwrap_or_else(failure);
+    writeln!(std::io::stdout(), "{reply}").ok();
+    if reply["ok"].as_bool() == Some(true) {
+        return 0;
+    }
+    #[cfg(windows)]
+    tauri::api::dialog::blocking::message(
+        None::<&tauri::Window>,
+        "Pin-Up AI",
+        reply["error"]
+            .as_str()
+            .unwrap_or("Could not send to Pin-Up AI"),
+    );
+    1
+}
+
+// ── App side ───────────────────────────────────────────────────────────────
+async fn add_text(app: &AppHandle, text: String) -> Value {
+    let capture = Capture {
+        body: text,
+        source: SOURCE.into(),
+        ..Default::default()
+    };
+    match capture::submit(app, capture).await {
+        Ok(snippet) => json!({ "ok": true, "id": snippet["id"], "title": snippet["title"] }),
+        Err(e) if backend::is_unreachable(&e) => json!({ "ok": true, "queued": true }),
+        Err(e) => failure(e),
+    }
+}
+
+fn import_files(app: &AppHandle, paths: Vec<PathBuf>) -> Value {
+    if let Some(missing) = paths.iter().find(|p| !p.is_absolute() || !p.exists()) {
+        return failure(format!("{} was not found", missing.display()));
+    }
+    // The import's progress shows in the main window.
+    if let Some(w) = app.get_window("main") {
+        w.show().ok();
+        w.unminimize().ok();
+        w.set_focus().ok();
+    }
+    let handle = app.clone();
+    let task = tasks::spawn(app, "import_files", move |task| {
+        import::import_paths(handle, task, paths, ImportRules::default())
+    });
+    json!({ "ok": true, "task": task })
+}
+
+/// Answers files or text relayed by a `--send-to` process.
+pub async fn handle(app: &AppHandle, message: Value) -> Value {
+    if !config::current(app).send_to.enabled {
+        return failure("Send to is turned off in Pin-Up AI's settings");
+    }
+    if let Some(text) = message["text"].as_str() {
+        return add_text(app, text.to_string()).await;
+    }
+    match serde_json::from_value::<Vec<PathBuf>>(message["paths"].clone()) {
+        Ok(paths) if !paths.is_empty() => import_files(app, paths),
+        _ => failure("Missing paths"),
+    }
+}
+
+// ── Registration ───────────────────────────────────────────────────────────
+#[cfg(windows)]
+const VERB_KEY: &str = r"HKCU\Software\Classes\*\shell\PinUpAI";
+#[cfg(windows)]
+const LABEL: &str = "Send to Pin-Up AI";
+
+#[cfg(windows)]
+fn shortcut_path() -> Option<PathBuf> {
+    Some(
+        dirs::data_dir()?
+            .join(r"Microsoft\Windows\SendTo")
+            .join("Pin-Up AI.lnk"),
+    )
+}
+
+/// A PowerShell single-quoted string.
+#[cfg(windows)]
+fn ps_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Creates the SendTo shortcut through the WScript.Shell COM object.
+#[cfg(windows)]
+fn write_shortcut(path: &std::path::Path, exe: &std::path::Path) -> Result<(), String> {
+    let script = format!(
+        "$s = (New-Object -ComObject WScript.Shell).CreateShortcut({}); \
+         $s.TargetPath = {}; $s.Arguments = '{ARG}'; $s.IconLocation = {}; \
+         $s.Description = {}; $s.Save()",
+        ps_quote(&path.to_string_lossy()),
+        ps_quote(&exe.to_string_lossy()),
+        ps_quote(&format!("{},0", exe.display())),
+        ps_quote(LABEL),
+    );
+    let output = tauri::api::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .output()
+        .map_err(|e| format!("Could not run PowerShell: {e}"))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Could not create the Send to shortcut: {}",
+            output.stderr.trim()
+        ))
+    }
+}
+
+#[cfg(windows)]
+fn register() -> Result<(), String> {
+    let exe = instance::exe_path()?;
+    let command = format!("\"{}\" {ARG} \"%1\"", exe.display());
+    let command_key = format!(r"{VERB_KEY}\command");
+    let current =
+        instance::reg(&["query", &command_key, "/ve"]).is_ok_and(|out| out.contains(&command));
+    let shortcut = shortcut_path().ok_or("No data directory")?;
+    if current && shortcut.is_file() {
+        return Ok(());
+    }
+    instance::reg(&["add", VERB_KEY, "/ve", "/d", LABEL, "/f"])?;
+    let icon = format!("{},0", exe.display());
+    instance::reg(&["add", VERB_KEY, "/v", "Icon", "/d", &icon, "/f"])?;
+    // Explorer offers "Document" verbs for at most 15 selected files.
+    instance::reg(&[
+        "add",
+        VERB_KEY,
+        "/v",
+        "MultiSelectModel",
+        "/d",
+        "Player",
+        "/f",
+    ])?;
+    instance::reg(&["add", &command_key, "/ve", "/d", &command, "/f"])?;
+    write_shortcut(&shortcut, &exe)
+}
+
+#[cfg(windows)]
+fn unregister() -> Result<(), String> {
+    if instance::reg(&["query", VERB_KEY]).is_ok() {
+        instance::reg(&["delete", VERB_KEY, "/f"])?;
+    }
+    match shortcut_path() {
+        Some(path) if path.is_file() => std::fs::remove_file(&path)
+            .map_err(|e| format!("Could not remove {}: {e}", path.display())),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(windows)]
+fn registered() -> bool {
+    instance::reg(&["query", VERB_KEY]).is_ok() && shortcut_path().is_some_and(|p| p.is_file())
+}
+
+#[cfg(not(windows))]
+fn register() -> Result<(), String> {
+    Err("Send to is only available on Windows".into())
+}
+
+#[cfg(not(windows))]
+fn unregister() -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn registered() -> bool {
+    false
+}
+
+async fn apply(enabled: bool) -> Result<(), String> {
+    let result =
+        tauri::async_runtime::spawn_blocking(
+            move || {
+                if enabled {
+                    register()
+                } else {
+                    unregister()
+                }
+            },
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    *ERROR.lock().unwrap() = result.as_ref().err().cloned();
+    result
+}
+
+async fn status(app: &AppHandle) -> Result<SendToStatus, String> {
+    let registered = tauri::async_runtime::spawn_blocking(registered)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(SendToStatus {
+        config: config::current(app).send_to,
+        supported: cfg!(windows),
+        registered,
+        error: ERROR.lock().unwrap().clone(),
+    })
+}
+
+/// Keeps the Explorer entries pointing at this binary (it moves on update).
+pub fn start(app: &AppHandle) {
+    if !cfg!(windows) || !config::current(app).send_to.enabled {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = apply(true).await {
+            log::warn!("Send to: {}", e);
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub async fn get_send_to_status(app: AppHandle) -> Result<SendToStatus, String> {
+    status(&app).await
+}
+
+/// Adds or removes the Explorer entries.
+#[tauri::command]
+pub async fn set_send_to(app: AppHandle, enabled: bool) -> Result<SendToStatus, String> {
+    if enabled && !cfg!(windows) {
+        return Err("Send to is only available on Windows".into());
+    }
+    apply(enabled).await?;
+    config::update(&app, |c| c.send_to.enabled = enabled)?;
+    status(&app).await
+}
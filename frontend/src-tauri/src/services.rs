@@ -0,0 +1,105 @@
+// Services — "Add Selection to Pin-Up AI" in the macOS Services menu.
+//
+// Info.plist declares the service (NSServices): any app's selected text,
+// from the app menu's Services submenu, the context menu or ⇧⌘U (which
+// users can change under Keyboard Shortcuts > Services). macOS hands the
+// selection to the object registered here as the app's services provider,
+// and it becomes a snippet through `capture::submit` like any other quick
+// capture. Other platforms have no Services menu; `start` does nothing
+// there.
+
+use tauri::AppHandle;
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use std::ffi::{c_void, CStr, CString};
+    use std::os::raw::c_char;
+
+    use objc::declare::ClassDecl;
+    use objc::runtime::{Object, Sel};
+    use objc::{class, msg_send, sel, sel_impl};
+    use once_cell::sync::OnceCell;
+    use tauri::AppHandle;
+
+    use crate::capture::{self, Capture};
+
+    const SOURCE: &str = "services";
+
+    static APP: OnceCell<AppHandle> = OnceCell::new();
+
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" {
+        fn NSUpdateDynamicServices();
+    }
+
+    unsafe fn ns_string(value: &str) -> *mut Object {
+        let c = CString::new(value).unwrap_or_default();
+        msg_send![class!(NSString), stringWithUTF8String: c.as_ptr()]
+    }
+
+    /// `- (void)addSelection:(NSPasteboard *)pboard userData:(NSString *)data
+    /// error:(NSString **)error`, the NSMessage in Info.plist.
+    extern "C" fn add_selection(
+        _this: &Object,
+        _cmd: Sel,
+        pasteboard: *mut Object,
+        _user_data: *mut Object,
+        _error: *mut c_void,
+    ) {
+        let text = unsafe {
+            let string: *mut Object =
+                msg_send![pasteboard, stringForType: ns_string("public.utf8-plain-text")];
+            if string.is_null() {
+                return;
+            }
+            let utf8: *const c_char = msg_send![string, UTF8String];
+            if utf8.is_null() {
+                return;
+            }
+            CStr::from_ptr(utf8).to_string_lossy().into_owned()
+        };
+        let Some(app) = APP.get().cloned() else {
+            return;
+        };
+        tauri::async_runtime::spawn(async move {
+            let capture = Capture {
+                body: text,
+                source: SOURCE.into(),
+                ..Default::default()
+            };
+            if let Err(e) = capture::submit(&app, capture).await {
+                log::warn!("Services: {}", e);
+            }
+        });
+    }
+
+    /// Registers the provider; runs on the main thread during setup.
+    pub fn start(app: &AppHandle) {
+        if APP.set(app.clone()).is_err() {
+            return;
+        }
+        let Some(mut decl) = ClassDecl::new("PinUpServicesProvider", class!(NSObject)) else {
+            return;
+        };
+        unsafe {
+            decl.add_method(
+                sel!(addSelection:userData:error:),
+                add_selection as extern "C" fn(&Object, Sel, *mut Object, *mut Object, *mut c_void),
+            );
+            let provider: *mut Object = msg_send![decl.register(), new];
+            let ns_app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+            let _: () = msg_send![ns_app, setServicesProvider: provider];
+            // Picks up the service on first launch without logging out.
+            NSUpdateDynamicServices();
+        }
+        log::info!("Services: provider registered");
+    }
+}
+
+/// Offers "Add Selection to Pin-Up AI" in the Services menu (macOS).
+pub fn start(app: &AppHandle) {
+    #[cfg(target_os = "macos")]
+    imp::start(app);
+    #[cfg(not(target_os = "macos"))]
+    let _ = app;
+}
@@ -0,0 +1,454 @@
+// Sidecar updates — ship backend fixes without a full desktop release.
+//
+// Each release channel publishes backend.json next to the app's latest.json:
+//   { "version": "0.3.1", "notes": "...", "min_shell_version": "0.1.0",
+//     "platforms": { "linux-x86_64": { "url": "...", "signature": "..." } } }
+// Signatures use the app updater's minisign key and format (`tauri signer
+// sign`), so releases need no second key. `update_backend` downloads the
+// binary through the download queue into app_dir()/backend/, verifies it
+// against the updater pubkey, swaps it into place by renaming (the replaced
+// binary is kept as pinup-backend.prev) and restarts the sidecar.
+// `spawn_backend` runs this binary when present, else the bundled one.
+// Emits "backend-updated" after a successful swap.
+//
+// Rollback: before the swap the database (with its WAL files) is copied to
+// data_dir()/backend/db-backup/. A new binary counts as unproven until it
+// passes a health check; if it fails twice in a row (health check, or a
+// migration that kills it on startup), the last known-good binary — .prev,
+// or the bundled one — and the database copy are restored, the sidecar is
+// restarted and "backend-rolled-back" is emitted with the errors.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::updater::UpdateChannel;
+use crate::{backups, config, disk, downloads, proxy};
+
+const STABLE_MANIFEST: &str =
+    "https://github.com/holeyfield33-art/pin-up-ai/releases/latest/download/backend.json";
+const BETA_MANIFEST: &str =
+    "https://github.com/holeyfield33-art/pin-up-ai/releases/download/beta/backend.json";
+
+#[derive(Deserialize, Clone, Debug)]
+struct Manifest {
+    version: String,
+    notes: Option<String>,
+    min_shell_version: Option<String>,
+    platforms: std::collections::HashMap<String, PlatformBinary>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct PlatformBinary {
+    url: String,
+    signature: String,
+}
+
+/// app_dir()/backend/version.json
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+struct Installed {
+    version: String,
+    /// Version of pinup-backend.prev, if any ("bundled" for the app's own).
+    previous: Option<String>,
+    /// Passed a health check since it was installed.
+    #[serde(default)]
+    proven: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BackendUpdate {
+    current: String,
+    latest: String,
+    available: bool,
+    notes: Option<String>,
+    /// Set when the update exists but can't be installed here.
+    reason: Option<String>,
+}
+
+fn backend_dir() -> PathBuf {
+    crate::app_dir().join("backend")
+}
+
+fn exe_name(stem: &str) -> String {
+    if cfg!(windows) {
+        format!("{}.exe", stem)
+    } else {
+        stem.to_string()
+    }
+}
+
+fn installed_path() -> PathBuf {
+    backend_dir().join(exe_name("pinup-backend"))
+}
+
+fn previous_path() -> PathBuf {
+    backend_dir().join(exe_name("pinup-backend.prev"))
+}
+
+fn read_installed() -> Option<Installed> {
+    let text = std::fs::read_to_string(backend_dir().join("version.json")).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+fn write_installed(installed: &Installed) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(installed).map_err(|e| e.to_string())?;
+    std::fs::write(backend_dir().join("version.json"), text)
+        .map_err(|e| format!("Could not record backend version: {e}"))
+}
+
+/// In the profile's own folder: the update migrates the active profile's
+/// database only.
+pub fn db_backup_dir() -> PathBuf {
+    crate::data_dir().join("backend").join("db-backup")
+}
+
+/// The database and its WAL/SHM companions.
+pub fn db_files(db: &Path) -> Vec<PathBuf> {
+    ["", "-wal", "-shm"]
+        .iter()
+        .map(|suffix| {
+            let mut name = db.as_os_str().to_owned();
+            name.push(suffix);
+            PathBuf::from(name)
+        })
+        .collect()
+}
+
+/// Copies the (stopped) backend's database aside before an update.
+fn backup_db() -> Result<(), String> {
+    let db = crate::db_path();
+    let size: u64 = db_files(&db)
+        .iter()
+        .filter_map(|f| std::fs::metadata(f).ok())
+        .map(|m| m.len())
+        .sum();
+    let dir = db_backup_dir();
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create backup dir: {e}"))?;
+    disk::ensure_space(&dir, size)?;
+    for file in db_files(&db).into_iter().filter(|f| f.is_file()) {
+        let target = dir.join(file.file_name().unwrap());
+        std::fs::copy(&file, &target).map_err(|e| format!("Database backup failed: {e}"))?;
+    }
+    Ok(())
+}
+
+fn restore_db() -> Result<(), String> {
+    let db = crate::db_path();
+    let dir = db_backup_dir();
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for file in db_files(&db) {
+        let saved = dir.join(file.file_name().unwrap());
+        if saved.is_file() {
+            std::fs::copy(&saved, &file).map_err(|e| format!("Database restore failed: {e}"))?;
+        } else {
+            // A WAL written by the failed backend must not be replayed.
+            std::fs::remove_file(&file).ok();
+        }
+    }
+    Ok(())
+}
+
+/// Updated backend binary, if one has been installed.
+pub fn binary() -> Option<PathBuf> {
+    Some(installed_path()).filter(|p| p.is_file())
+}
+
+/// Version of the backend `spawn_backend` will run.
+pub fn version(app: &AppHandle) -> String {
+    match (binary(), read_installed()) {
+        (Some(_), Some(installed)) => installed.version,
+        _ => app.package_info().version.to_string(),
+    }
+}
+
+/// Tauri's updater target naming: darwin/linux/windows plus the arch.
+fn platform_key() -> String {
+    let os = match std::env::consts::OS {
+        "macos" => "darwin",
+        os => os,
+    };
+    format!("{}-{}", os, std::env::consts::ARCH)
+}
+
+async fn fetch_manifest(app: &AppHandle) -> Result<Manifest, String> {
+    let url = match config::current(app).updater.channel {
+        UpdateChannel::Stable => STABLE_MANIFEST,
+        UpdateChannel::Beta => BETA_MANIFEST,
+    };
+    let client = proxy::client_builder(app)
+        .build()
+        .map_err(|e| e.to_string())?;
+    client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Could not fetch backend manifest: {e}"))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid backend manifest: {e}"))
+}
+
+fn status(app: &AppHandle, manifest: &Manifest) -> BackendUpdate {
+    let current = version(app);
+    let newer = tauri::api::version::is_greater(&current, &manifest.version).unwrap_or(false);
+    let shell = app.package_info().version.to_string();
+    let reason = if !manifest.platforms.contains_key(&platform_key()) {
+        Some(format!("No build for {}", platform_key()))
+    } else {
+        manifest
+            .min_shell_version
+            .as_deref()
+            .filter(|min| tauri::api::version::is_greater(&shell, min).unwrap_or(false))
+            .map(|min| format!("Requires app version {} or later", min))
+    };
+    BackendUpdate {
+        current,
+        latest: manifest.version.clone(),
+        available: newer && reason.is_none(),
+        notes: manifest.notes.clone(),
+        reason: reason.filter(|_| newer),
+    }
+}
+
+/// Checks `data` against a base64 minisign signature and the updater pubkey.
+fn verify(app: &AppHandle, data: &[u8], signature: &str) -> Result<(), String> {
+    let pubkey = app.config().tauri.updater.pubkey.trim().to_string();
+    if pubkey.is_empty() {
+        return Err("Updater public key is not configured; refusing unsigned binaries".into());
+    }
+    let decode = |b64: &str| {
+        base64::engine::general_purpose::STANDARD
+            .decode(b64.trim())
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+    };
+    let key = decode(&pubkey)
+        .and_then(|k| minisign_verify::PublicKey::decode(&k).ok())
+        .ok_or("Invalid updater public key")?;
+    let signature = decode(signature)
+        .and_then(|s| minisign_verify::Signature::decode(&s).ok())
+        .ok_or("Invalid backend signature")?;
+    key.verify(data, &signature, true)
+        .map_err(|e| format!("Backend signature check failed: {e}"))
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+/// Moves `new` over the installed binary. With `keep`, the old one becomes
+/// .prev; otherwise the existing .prev stays the fallback. Renames stay
+/// within backend_dir(), so each step is atomic.
+fn swap(new: &Path, keep: bool) -> Result<(), String> {
+    let current = installed_path();
+    let previous = previous_path();
+    if keep {
+        std::fs::remove_file(&previous).ok();
+    }
+    let moved = keep && current.exists();
+    if moved {
+        std::fs::rename(&current, &previous)
+            .map_err(|e| format!("Could not move the old backend aside: {e}"))?;
+    }
+    if let Err(e) = std::fs::rename(new, &current) {
+        if moved {
+            std::fs::rename(&previous, &current).ok();
+        }
+        return Err(format!("Could not install the new backend: {e}"));
+    }
+    Ok(())
+}
+
+/// Backs up the database and swaps `path` in as `version`.
+fn install(path: &Path, version: &str) -> Result<(), String> {
+    backup_db()?;
+    // An unproven binary is never kept as the fallback.
+    let (previous, keep) = match read_installed().filter(|_| binary().is_some()) {
+        Some(installed) if installed.proven => (Some(installed.version), true),
+        Some(installed) => (installed.previous, false),
+        None => (Some("bundled".into()), false),
+    };
+    swap(path, keep)?;
+    write_installed(&Installed {
+        version: version.to_string(),
+        previous,
+        proven: false,
+    })
+}
+
+fn unproven() -> bool {
+    binary().is_some() && read_installed().is_some_and(|i| !i.proven)
+}
+
+/// Records that the installed binary passed a health check.
+pub fn mark_healthy() {
+    match read_installed() {
+        Some(installed) if !installed.proven && binary().is_some() => {
+            let proven = Installed {
+                proven: true,
+                ..installed
+            };
+            if let Err(e) = write_installed(&proven) {
+                log::warn!("{}", e);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Puts the last known-good binary and the database copy back. Returns the
+/// version now installed.
+fn roll_back(app: &AppHandle) -> Result<String, String> {
+    let installed = read_installed().unwrap_or_default();
+    let restored = match installed.previous.as_deref() {
+        Some(version) if version != "bundled" && previous_path().is_file() => {
+            std::fs::rename(previous_path(), installed_path())
+                .map_err(|e| format!("Could not restore the previous backend: {e}"))?;
+            write_installed(&Installed {
+                version: version.to_string(),
+                previous: None,
+                proven: true,
+            })?;
+            version.to_string()
+        }
+        _ => {
+            std::fs::remove_file(installed_path())
+                .map_err(|e| format!("Could not remove the failed backend: {e}"))?;
+            std::fs::remove_file(backend_dir().join("version.json")).ok();
+            app.package_info().version.to_string()
+        }
+    };
+    restore_db()?;
+    Ok(restored)
+}
+
+/// Handles a failed health check. A freshly updated backend that has never
+/// been healthy is restarted once more and, if that fails too, rolled back.
+/// Returns None when there was no update to blame, else the outcome.
+pub async fn recover_update(app: &AppHandle, error: &str) -> Option<Result<u16, String>> {
+    if !unproven() || config::current(app).backend.external_port.is_some() {
+        return None;
+    }
+    let failed = version(app);
+    log::warn!(
+        "Updated backend {} failed ({}), retrying once",
+        failed,
+        error
+    );
+    let retry = match crate::respawn_backend(app).await {
+        Ok(port) => {
+            mark_healthy();
+            return Some(Ok(port));
+        }
+        Err(e) => e,
+    };
+
+    log::error!(
+        "Updated backend {} failed again ({}), rolling back",
+        failed,
+        retry
+    );
+    crate::stop_backend(app);
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    let restored = match roll_back(app) {
+        Ok(version) => version,
+        Err(e) => return Some(Err(format!("Rollback failed: {e}"))),
+    };
+    let result = crate::respawn_backend(app).await;
+    app.emit_all(
+        "backend-rolled-back",
+        serde_json::json!({
+            "failed_version": failed,
+            "restored_version": restored,
+            "errors": [error, retry],
+        }),
+    )
+    .ok();
+    Some(result)
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub async fn check_backend_update(app: AppHandle) -> Result<BackendUpdate, String> {
+    let manifest = fetch_manifest(&app).await?;
+    Ok(status(&app, &manifest))
+}
+
+/// Downloads, verifies and installs the channel's backend, then restarts the
+/// sidecar. Returns the installed version.
+#[tauri::command]
+pub async fn update_backend(app: AppHandle) -> Result<String, String> {
+    let manifest = fetch_manifest(&app).await?;
+    let update = status(&app, &manifest);
+    if let Some(reason) = update.reason {
+        return Err(reason);
+    }
+    if !update.available {
+        return Ok(update.current);
+    }
+    let build = &manifest.platforms[&platform_key()];
+
+    std::fs::create_dir_all(backend_dir())
+        .map_err(|e| format!("Failed to create backend dir: {e}"))?;
+    let download = backend_dir().join(exe_name("pinup-backend.download"));
+    let path = downloads::fetch(&app, build.url.clone(), download, None).await?;
+    let verified = std::fs::read(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|data| verify(&app, &data, &build.signature))
+        .and_then(|_| make_executable(&path));
+    if let Err(e) = verified {
+        std::fs::remove_file(&path).ok();
+        return Err(e);
+    }
+
+    let ours = config::current(&app).backend.external_port.is_none()
+        && crate::BACKEND_PORT.load(Ordering::SeqCst) != 0;
+    if ours {
+        backups::before_update(&app, &format!("pre-backend-update-{}", manifest.version)).await;
+        crate::stop_backend(&app);
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+    if let Err(e) = install(&path, &manifest.version) {
+        if ours {
+            crate::respawn_backend(&app).await.ok();
+        }
+        return Err(e);
+    }
+    log::info!("Backend updated {} -> {}", update.current, manifest.version);
+
+    if ours {
+        let port = match crate::respawn_backend(&app).await {
+            Ok(port) => port,
+            Err(e) => recover_update(&app, &e).await.unwrap_or(Err(e))?,
+        };
+        mark_healthy();
+        app.emit_all("backend-ready", port).ok();
+        if version(&app) != manifest.version {
+            return Err(format!(
+                "Backend {} failed its health check and was rolled back",
+                manifest.version
+            ));
+        }
+    }
+    app.emit_all(
+        "backend-updated",
+        serde_json::json!({ "version": manifest.version, "previous": update.current }),
+    )
+    .ok();
+    Ok(manifest.version)
+}
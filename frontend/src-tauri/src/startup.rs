@@ -0,0 +1,120 @@
+// Startup metrics — where launch time goes, kept across launches.
+//
+// All times are ms since the process started:
+//   setup_ms            Tauri setup hook entered
+//   backend_spawn_ms    sidecar spawned (or external backend chosen)
+//   backend_healthy_ms  first successful health check
+//   webview_ready_ms    main window finished loading the page
+//   first_paint_ms      frontend reported its first frame (`mark_first_paint`)
+// plus spawn_to_healthy_ms between the two backend marks. Each launch is
+// saved to app_dir()/startup-metrics.json as its marks arrive; the last
+// HISTORY_LIMIT launches are kept.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+const HISTORY_LIMIT: usize = 50;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct StartupMetrics {
+    /// RFC 3339 launch time; identifies the entry in the history.
+    started_at: String,
+    app_version: String,
+    setup_ms: Option<u64>,
+    backend_spawn_ms: Option<u64>,
+    backend_healthy_ms: Option<u64>,
+    spawn_to_healthy_ms: Option<u64>,
+    webview_ready_ms: Option<u64>,
+    first_paint_ms: Option<u64>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct StartupReport {
+    current: StartupMetrics,
+    /// Previous launches, oldest first (the current one excluded).
+    history: Vec<StartupMetrics>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Mark {
+    Setup,
+    BackendSpawned,
+    BackendHealthy,
+    WebviewReady,
+    FirstPaint,
+}
+
+static CURRENT: Mutex<Option<StartupMetrics>> = Mutex::new(None);
+
+fn history_path() -> PathBuf {
+    crate::app_dir().join("startup-metrics.json")
+}
+
+fn read_history() -> Vec<StartupMetrics> {
+    std::fs::read_to_string(history_path())
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save(current: &StartupMetrics) {
+    let mut history = read_history();
+    history.retain(|m| m.started_at != current.started_at);
+    history.push(current.clone());
+    let skip = history.len().saturating_sub(HISTORY_LIMIT);
+    std::fs::create_dir_all(crate::app_dir()).ok();
+    match serde_json::to_string_pretty(&history[skip..]) {
+        Ok(text) => {
+            if let Err(e) = std::fs::write(history_path(), text) {
+                log::debug!("Could not save startup metrics: {}", e);
+            }
+        }
+        Err(e) => log::debug!("Could not save startup metrics: {}", e),
+    }
+}
+
+/// Records `mark` for this launch; later repeats (restarts) are ignored.
+pub fn mark(mark: Mark) {
+    let ms = crate::uptime().as_millis() as u64;
+    let mut current = CURRENT.lock().unwrap();
+    let metrics = current.get_or_insert_with(|| StartupMetrics {
+        started_at: (chrono::Local::now() - chrono::Duration::milliseconds(ms as i64)).to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        ..Default::default()
+    });
+    let slot = match mark {
+        Mark::Setup => &mut metrics.setup_ms,
+        Mark::BackendSpawned => &mut metrics.backend_spawn_ms,
+        Mark::BackendHealthy => &mut metrics.backend_healthy_ms,
+        Mark::WebviewReady => &mut metrics.webview_ready_ms,
+        Mark::FirstPaint => &mut metrics.first_paint_ms,
+    };
+    if slot.is_some() {
+        return;
+    }
+    *slot = Some(ms);
+    if let (Some(spawn), Some(healthy)) = (metrics.backend_spawn_ms, metrics.backend_healthy_ms) {
+        metrics.spawn_to_healthy_ms = Some(healthy.saturating_sub(spawn));
+    }
+    log::debug!("Startup {:?} at {} ms", mark, ms);
+    save(metrics);
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Called by the frontend once its first frame is on screen.
+#[tauri::command]
+pub fn mark_first_paint() {
+    mark(Mark::FirstPaint);
+}
+
+#[tauri::command]
+pub fn get_startup_metrics() -> StartupReport {
+    let current = CURRENT.lock().unwrap().clone().unwrap_or_default();
+    let history = read_history()
+        .into_iter()
+        .filter(|m| m.started_at != current.started_at)
+        .collect();
+    StartupReport { current, history }
+}
@@ -0,0 +1,315 @@
+// Support sessions — opt-in, time-limited diagnostics relay.
+//
+// `start_support_session(code)` dials out to the support relay over TLS and
+// streams captured log lines. The support engineer on the other end can only
+// run the read-only diagnostics listed in `run_diagnostic`, none of which
+// read snippet content. Log lines are redacted the way the diagnostics
+// bundle redacts them (see diagnostics.rs), which strips credentials and
+// secrets but not content, so the shell logs snippets, imported pages and
+// entries, feeds and windows by id, position or count, never by title, URL
+// or body. An always-on-top indicator window is shown for the lifetime of
+// the session, closing it ends the session, and every session is torn down
+// at a hard expiry regardless of what the relay does. PINUP_SUPPORT_RELAY
+// may point at another relay, but only a wss:// one.
+
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, WindowBuilder, WindowUrl};
+use tokio::sync::oneshot;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::{diagnostics, i18n, logs};
+
+const DEFAULT_RELAY_URL: &str = "wss://relay.pinupai.app/support";
+const DEFAULT_MINUTES: u64 = 30;
+const MAX_MINUTES: u64 = 60;
+const INDICATOR_LABEL: &str = "support-indicator";
+
+// ── State ──────────────────────────────────────────────────────────────────
+#[derive(Serialize, Clone)]
+pub struct SupportStatus {
+    active: bool,
+    code: Option<String>,
+    started_at: u64,
+    expires_at: u64,
+}
+
+struct Session {
+    status: SupportStatus,
+    stop: oneshot::Sender<()>,
+}
+
+#[derive(Default)]
+pub struct SupportState(Mutex<Option<Session>>);
+
+// ── Relay protocol ─────────────────────────────────────────────────────────
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Inbound {
+    Diagnostic { id: String, command: String },
+    End,
+}
+
+fn relay_url(code: &str) -> Result<String, String> {
+    let base = std::env::var("PINUP_SUPPORT_RELAY").unwrap_or_else(|_| DEFAULT_RELAY_URL.into());
+    if !base
+        .get(..6)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("wss://"))
+    {
+        return Err("The support relay must be a wss:// address".into());
+    }
+    Ok(format!("{}?code={}", base, code))
+}
+
+/// A log line as it may leave the machine.
+fn redacted(mut line: logs::LogLine) -> logs::LogLine {
+    line.message = diagnostics::redact_log(&line.message);
+    line
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn valid_code(code: &str) -> bool {
+    (6..=16).contains(&code.len()) && code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+// ── Diagnostics (read-only, no snippet content) ────────────────────────────
+async fn run_diagnostic(app: &AppHandle, command: &str) -> Result<Value, String> {
+    match command {
+        "ping" => Ok(json!("pong")),
+        "app_info" => {
+            let info = app.package_info();
+            Ok(json!({
+                "name": info.name,
+                "version": info.version.to_string(),
+                "os": std::env::consts::OS,
+                "arch": std::env::consts::ARCH,
+            }))
+        }
+        "backend_status" => {
+            let port = crate::BACKEND_PORT.load(Ordering::SeqCst);
+            let running = app
+                .state::<crate::SidecarState>()
+                .0
+                .lock()
+                .unwrap()
+                .is_some();
+            Ok(json!({ "port": port, "running": running }))
+        }
+        "health" => {
+            let port = crate::BACKEND_PORT.load(Ordering::SeqCst);
+            let body = crate::wait_for_health(port, 1, 0).await?;
+            Ok(serde_json::from_str(&body).unwrap_or(Value::String(body)))
+        }
+        "recent_logs" => Ok(json!(logs::recent(500)
+            .into_iter()
+            .map(redacted)
+            .collect::<Vec<_>>())),
+        "data_dir_usage" => {
+            let mut files = Vec::new();
+            if let Ok(entries) = std::fs::read_dir(crate::data_dir()) {
+                for entry in entries.flatten() {
+                    if let Ok(meta) = entry.metadata() {
+                        files.push(json!({
+                            "name": entry.file_name().to_string_lossy(),
+                            "bytes": meta.len(),
+                            "dir": meta.is_dir(),
+                        }));
+                    }
+                }
+            }
+            Ok(json!(files))
+        }
+        other => Err(format!("Unknown diagnostic: {}", other)),
+    }
+}
+
+// ── Session loop ───────────────────────────────────────────────────────────
+async fn run_session(
+    app: AppHandle,
+    code: String,
+    expires_in: Duration,
+    mut stop: oneshot::Receiver<()>,
+) -> Result<(), String> {
+    let (ws, _) = tokio_tungstenite::connect_async(relay_url(&code)?)
+        .await
+        .map_err(|e| format!("Could not reach support relay: {e}"))?;
+    let (mut tx, mut rx) = ws.split();
+    let mut log_rx = logs::subscribe();
+    let expiry = tokio::time::sleep(expires_in);
+    tokio::pin!(expiry);
+
+    log::info!("Support session connected (expires in {:?})", expires_in);
+
+    // Seed the engineer's view with recent history before the live tail.
+    for line in logs::recent(200) {
+        let frame = json!({ "type": "log", "line": redacted(line) }).to_string();
+        tx.send(Message::Text(frame))
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    loop {
+        tokio::select! {
+            _ = &mut expiry => {
+                log::info!("Support session expired");
+                break;
+            }
+            _ = &mut stop => {
+                log::info!("Support session stopped by user");
+                break;
+            }
+            line = log_rx.recv() => {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(_) => continue,
+                };
+                let frame = json!({ "type": "log", "line": redacted(line) }).to_string();
+                tx.send(Message::Text(frame)).await.map_err(|e| e.to_string())?;
+            }
+            msg = rx.next() => {
+                let text = match msg {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | None => {
+                        log::info!("Support relay closed the session");
+                        break;
+                    }
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => return Err(format!("Support relay error: {e}")),
+                };
+                match serde_json::from_str::<Inbound>(&text) {
+                    Ok(Inbound::Diagnostic { id, command }) => {
+                        log::info!("Support diagnostic requested: {}", command);
+                        let reply = match run_diagnostic(&app, &command).await {
+                            Ok(result) => json!({ "type": "result", "id": id, "ok": true, "result": result }),
+                            Err(e) => json!({ "type": "result", "id": id, "ok": false, "error": e }),
+                        };
+                        tx.send(Message::Text(reply.to_string())).await.map_err(|e| e.to_string())?;
+                    }
+                    Ok(Inbound::End) => break,
+                    Err(e) => log::warn!("Ignoring malformed relay frame: {}", e),
+                }
+            }
+        }
+    }
+
+    tx.send(Message::Close(None)).await.ok();
+    Ok(())
+}
+
+fn finish_session(app: &AppHandle) {
+    app.state::<SupportState>().0.lock().unwrap().take();
+    if let Some(w) = app.get_window(INDICATOR_LABEL) {
+        w.close().ok();
+    }
+    app.emit_all("support-session-ended", ()).ok();
+}
+
+fn open_indicator(app: &AppHandle, expires_at: u64) -> Result<(), String> {
+    let window = WindowBuilder::new(
+        app,
+        INDICATOR_LABEL,
+        WindowUrl::App(format!("support.html?expires={}", expires_at).into()),
+    )
+    .title(i18n::t("window.support"))
+    .inner_size(340.0, 130.0)
+    .resizable(false)
+    .always_on_top(true)
+    .build()
+    .map_err(|e| format!("Could not open support indicator: {e}"))?;
+
+    // Closing the indicator is the user's "end session" button.
+    let handle = app.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::Destroyed = event {
+            if let Some(session) = handle.state::<SupportState>().0.lock().unwrap().take() {
+                session.stop.send(()).ok();
+            }
+        }
+    });
+    Ok(())
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn start_support_session(
+    app: AppHandle,
+    state: tauri::State<'_, SupportState>,
+    code: String,
+    minutes: Option<u64>,
+) -> Result<SupportStatus, String> {
+    let code = code.trim().to_uppercase();
+    if !valid_code(&code) {
+        return Err("Invalid support code".into());
+    }
+    relay_url(&code)?;
+    let mut slot = state.0.lock().unwrap();
+    if slot.is_some() {
+        return Err("A support session is already active".into());
+    }
+
+    let minutes = minutes.unwrap_or(DEFAULT_MINUTES).clamp(1, MAX_MINUTES);
+    let started_at = now_secs();
+    let status = SupportStatus {
+        active: true,
+        code: Some(code.clone()),
+        started_at,
+        expires_at: started_at + minutes * 60,
+    };
+    let (stop_tx, stop_rx) = oneshot::channel();
+    *slot = Some(Session {
+        status: status.clone(),
+        stop: stop_tx,
+    });
+    drop(slot);
+
+    // Without the indicator there is no way to see or end the session.
+    if let Err(e) = open_indicator(&app, status.expires_at) {
+        state.0.lock().unwrap().take();
+        return Err(e);
+    }
+    app.emit_all("support-session-started", status.clone()).ok();
+
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let expires_in = Duration::from_secs(minutes * 60);
+        if let Err(e) = run_session(handle.clone(), code, expires_in, stop_rx).await {
+            log::error!("Support session failed: {}", e);
+            handle.emit_all("support-session-error", e).ok();
+        }
+        finish_session(&handle);
+    });
+
+    Ok(status)
+}
+
+#[tauri::command]
+pub fn stop_support_session(state: tauri::State<'_, SupportState>) -> bool {
+    match state.0.lock().unwrap().take() {
+        Some(session) => session.stop.send(()).is_ok(),
+        None => false,
+    }
+}
+
+#[tauri::command]
+pub fn get_support_session(state: tauri::State<'_, SupportState>) -> SupportStatus {
+    match state.0.lock().unwrap().as_ref() {
+        Some(session) => session.status.clone(),
+        None => SupportStatus {
+            active: false,
+            code: None,
+            started_at: 0,
+            expires_at: 0,
+        },
+    }
+}
@@ -0,0 +1,154 @@
+// Suspend/resume — recover a sidecar that comes back wedged after sleep.
+//
+// On Linux, logind's PrepareForSleep signal reports both edges. Everywhere
+// (and as a fallback on Linux) a ticker notices resume when the wall clock
+// jumps well past its tick. While asleep or recovering, `paused()` is true
+// and watchdogs (resource monitor) hold off. After resume the backend gets a
+// short health check; if it doesn't answer, the sidecar is respawned.
+// Reminders that came due during sleep fire on resume (see reminders.rs).
+// Emits "system-suspending", "system-resumed" and, after a restart,
+// "backend-ready".
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use tauri::{AppHandle, Manager};
+
+use crate::{config, reminders};
+
+const TICK: Duration = Duration::from_secs(5);
+/// A tick this late means the machine was asleep.
+const GAP: Duration = Duration::from_secs(30);
+/// Lets Wi-Fi and the loopback stack settle before the health check.
+const SETTLE: Duration = Duration::from_secs(3);
+
+static ASLEEP: AtomicBool = AtomicBool::new(false);
+static RECOVERING: AtomicBool = AtomicBool::new(false);
+/// When the last resume was handled, so both detectors don't fire for one.
+static LAST_RESUME: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// True while the machine sleeps or the backend is being checked after resume.
+pub fn paused() -> bool {
+    ASLEEP.load(Ordering::SeqCst) || RECOVERING.load(Ordering::SeqCst)
+}
+
+#[cfg(target_os = "linux")]
+fn suspending(app: &AppHandle) {
+    if !ASLEEP.swap(true, Ordering::SeqCst) {
+        log::info!("System suspending");
+        app.emit_all("system-suspending", ()).ok();
+    }
+}
+
+fn resumed(app: &AppHandle, slept: Option<Duration>) {
+    ASLEEP.store(false, Ordering::SeqCst);
+    {
+        let mut last = LAST_RESUME.lock().unwrap();
+        if last.is_some_and(|t| t.elapsed() < GAP) {
+            return;
+        }
+        *last = Some(Instant::now());
+    }
+    log::info!("System resumed (asleep for {:?})", slept);
+    app.emit_all("system-resumed", slept.map(|d| d.as_secs()))
+        .ok();
+    reminders::check(app);
+
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        RECOVERING.store(true, Ordering::SeqCst);
+        recover(&handle).await;
+        RECOVERING.store(false, Ordering::SeqCst);
+    });
+}
+
+async fn recover(app: &AppHandle) {
+    tokio::time::sleep(SETTLE).await;
+    let port = crate::BACKEND_PORT.load(Ordering::SeqCst);
+    if port == 0 {
+        return;
+    }
+    let Err(e) = crate::wait_for_health(port, 3, 1000).await else {
+        log::info!("Backend healthy after resume");
+        return;
+    };
+    let ours =
+        config::current(app).backend.external_port.is_none() && crate::backend_pid(app).is_some();
+    if !ours {
+        log::warn!("External backend unresponsive after resume: {}", e);
+        return;
+    }
+    log::warn!("Backend unresponsive after resume, restarting: {}", e);
+    match crate::respawn_backend(app).await {
+        Ok(port) => {
+            app.emit_all("backend-ready", port).ok();
+            crate::capture::flush_outbox(app).await;
+        }
+        Err(e) => {
+            log::error!("Backend restart after resume failed: {}", e);
+            crate::recovery::open(app, e);
+        }
+    }
+}
+
+/// Resume detection from wall-clock gaps; works on every platform.
+fn watch_clock(app: AppHandle) {
+    let mut last = SystemTime::now();
+    loop {
+        std::thread::sleep(TICK);
+        let now = SystemTime::now();
+        let elapsed = now.duration_since(last).unwrap_or_default();
+        last = now;
+        if elapsed > TICK + GAP {
+            resumed(&app, Some(elapsed));
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn watch_logind(app: AppHandle) -> Result<(), String> {
+    use zbus::blocking::{Connection, Proxy};
+
+    let bus = Connection::system().map_err(|e| e.to_string())?;
+    let proxy = Proxy::new(
+        &bus,
+        "org.freedesktop.login1",
+        "/org/freedesktop/login1",
+        "org.freedesktop.login1.Manager",
+    )
+    .map_err(|e| e.to_string())?;
+    let signals = proxy
+        .receive_signal("PrepareForSleep")
+        .map_err(|e| e.to_string())?;
+    let mut since = None;
+    for message in signals {
+        match message.body::<bool>() {
+            Ok(true) => {
+                since = Some(SystemTime::now());
+                suspending(&app);
+            }
+            Ok(false) => {
+                let slept = since.take().and_then(|t| t.elapsed().ok());
+                resumed(&app, slept);
+            }
+            Err(e) => log::debug!("Bad PrepareForSleep signal: {}", e),
+        }
+    }
+    Ok(())
+}
+
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    std::thread::spawn(move || watch_clock(handle));
+
+    #[cfg(target_os = "linux")]
+    {
+        let handle = app.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = watch_logind(handle) {
+                log::info!("logind sleep signals unavailable: {}", e);
+            }
+        });
+    }
+}
@@ -0,0 +1,344 @@
+// Sync key — the user-held key behind everything sync sends.
+//
+// One random 256-bit key per user, made on the first device and carried to
+// the others as a recovery phrase: the key and a 16-bit checksum in Crockford
+// base32, 55 characters in groups of five. Each kind of sync derives its own
+// key from it (HMAC-SHA256 over a label), so no two share a key:
+//   - WebDAV and S3 seal change sets and snapshots with a passphrase derived
+//     from it, unless the user chose one of their own (see sync_log.rs);
+//   - Git sync seals each snippet file before it is committed (see
+//     git_sync.rs). The nonce is derived from the plaintext, so an unchanged
+//     snippet keeps its ciphertext and commits only hold real edits.
+// LAN sync involves no provider; its log is sealed under keys exchanged when
+// pairing (see lan_sync.rs).
+//
+// The key is kept in the OS keyring (see keyring.rs) as "sync-key". A backup
+// file holds the recovery phrase sealed under a passphrase, like an encrypted
+// export. Replacing the key makes what was synced under the old one
+// unreadable: every device needs the new phrase and a fresh sync target.
+
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::api::dialog::blocking::FileDialogBuilder;
+
+use crate::s3::hmac;
+use crate::sync_log::blocking;
+use crate::{encryption, exports, i18n, keyring};
+
+const ACCOUNT: &str = "sync-key";
+/// Crockford base32: no I, L, O or U.
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const GROUP: usize = 5;
+/// Starts a file sealed by `seal_text`.
+const SEALED: &str = "pinup-sealed:1:";
+const NONCE_LEN: usize = 24;
+const BACKUP_EXTENSION: &str = "pinupkey";
+
+const B64: base64::engine::GeneralPurpose = base64::engine::general_purpose::STANDARD;
+
+pub type Key = [u8; 32];
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SyncKeyStatus {
+    has_key: bool,
+    /// Short fingerprint, the same on every device holding the key.
+    key_id: Option<String>,
+}
+
+fn key_id(key: &Key) -> String {
+    hex::encode(&Sha256::digest(key)[..4])
+}
+
+fn checksum(key: &Key) -> [u8; 2] {
+    let digest = Sha256::digest(key);
+    [digest[0], digest[1]]
+}
+
+fn phrase(key: &Key) -> String {
+    let mut bytes = key.to_vec();
+    bytes.extend_from_slice(&checksum(key));
+    let mut chars = Vec::new();
+    let (mut buffer, mut bits) = (0u32, 0);
+    for byte in bytes {
+        buffer = buffer << 8 | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            chars.push(ALPHABET[(buffer >> bits) as usize & 31] as char);
+        }
+    }
+    if bits > 0 {
+        chars.push(ALPHABET[(buffer << (5 - bits)) as usize & 31] as char);
+    }
+    chars
+        .chunks(GROUP)
+        .map(|group| group.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn parse_phrase(phrase: &str) -> Result<Key, String> {
+    let mut bytes = Vec::new();
+    let (mut buffer, mut bits) = (0u32, 0);
+    for c in phrase.chars().filter(|c| !c.is_whitespace() && *c != '-') {
+        let c = match c.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            c => c,
+        };
+        let value = ALPHABET
+            .iter()
+            .position(|&a| a as char == c)
+            .ok_or_else(|| format!("\"{c}\" is not part of a recovery phrase"))?;
+        buffer = buffer << 5 | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    if bytes.len() != 34 {
+        return Err("A recovery phrase has 55 characters".into());
+    }
+    let key: Key = bytes[..32].try_into().unwrap();
+    // The last character's low bits are padding, always zero.
+    if bytes[32..] != checksum(&key) || buffer & ((1 << bits) - 1) != 0 {
+        return Err("That recovery phrase has a typo".into());
+    }
+    Ok(key)
+}
+
+async fn load() -> Result<Option<Key>, String> {
+    let Some(text) = blocking(|| keyring::get(ACCOUNT)).await? else {
+        return Ok(None);
+    };
+    let bytes = hex::decode(text.trim()).map_err(|e| format!("Unreadable sync key: {e}"))?;
+    bytes
+        .try_into()
+        .map(Some)
+        .map_err(|_| "Unreadable sync key".to_string())
+}
+
+async fn store(key: Key) -> Result<(), String> {
+    blocking(move || keyring::set(ACCOUNT, &hex::encode(key))).await
+}
+
+async fn current() -> Result<Key, String> {
+    load().await?.ok_or_else(|| {
+        "No sync key on this device; create one or enter your recovery phrase in Settings"
+            .to_string()
+    })
+}
+
+/// The key for one kind of sync.
+pub async fn derive(label: &str) -> Result<Key, String> {
+    Ok(hmac(
+        &current().await?,
+        format!("pin-up-ai sync:{label}").as_bytes(),
+    ))
+}
+
+/// A passphrase for `exports::seal`, for one kind of sync.
+pub async fn passphrase(label: &str) -> Result<String, String> {
+    derive(label).await.map(hex::encode)
+}
+
+// ── Sealed text ────────────────────────────────────────────────────────────
+/// Encrypts `plaintext` into one line of text. Equal plaintexts give equal
+/// lines; nothing else about them shows.
+pub fn seal_text(key: &Key, plaintext: &str) -> Result<String, String> {
+    let nonce = &hmac(&hmac(key, b"nonce"), plaintext.as_bytes())[..NONCE_LEN];
+    let sealed = XChaCha20Poly1305::new(key.into())
+        .encrypt(XNonce::from_slice(nonce), plaintext.as_bytes())
+        .map_err(|_| "Encryption failed".to_string())?;
+    Ok(format!(
+        "{SEALED}{}\n",
+        B64.encode([nonce, &sealed].concat())
+    ))
+}
+
+pub fn is_sealed(text: &str) -> bool {
+    text.starts_with(SEALED)
+}
+
+pub fn open_text(key: &Key, text: &str) -> Result<String, String> {
+    let encoded = text.strip_prefix(SEALED).ok_or("Not a sealed file")?;
+    let bytes = B64
+        .decode(encoded.trim())
+        .map_err(|_| "Damaged sealed file".to_string())?;
+    if bytes.len() < NONCE_LEN {
+        return Err("Damaged sealed file".into());
+    }
+    let (nonce, sealed) = bytes.split_at(NONCE_LEN);
+    let plaintext = XChaCha20Poly1305::new(key.into())
+        .decrypt(XNonce::from_slice(nonce), sealed)
+        .map_err(|_| "Sealed with another sync key, or damaged".to_string())?;
+    String::from_utf8(plaintext).map_err(|e| e.to_string())
+}
+
+async fn status() -> Result<SyncKeyStatus, String> {
+    let key = load().await?;
+    Ok(SyncKeyStatus {
+        has_key: key.is_some(),
+        key_id: key.as_ref().map(key_id),
+    })
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub async fn get_sync_key_status() -> Result<SyncKeyStatus, String> {
+    status().await
+}
+
+/// Makes a new sync key and returns its recovery phrase. An existing key is
+/// only replaced when `replace` is set.
+#[tauri::command]
+pub async fn create_sync_key(replace: bool) -> Result<String, String> {
+    if !replace && load().await?.is_some() {
+        return Err("This device already has a sync key".into());
+    }
+    let mut key = [0u8; 32];
+    getrandom::getrandom(&mut key).map_err(|e| format!("No random source: {e}"))?;
+    store(key).await?;
+    log::info!("Sync key {} created", key_id(&key));
+    Ok(phrase(&key))
+}
+
+/// The recovery phrase of the key on this device, to write down again.
+#[tauri::command]
+pub async fn get_recovery_phrase() -> Result<String, String> {
+    current().await.map(|key| phrase(&key))
+}
+
+/// Saves the key a recovery phrase stands for, replacing any other.
+#[tauri::command]
+pub async fn restore_sync_key(phrase: String) -> Result<SyncKeyStatus, String> {
+    let key = parse_phrase(&phrase)?;
+    store(key).await?;
+    log::info!(
+        "Sync key {} restored from its recovery phrase",
+        key_id(&key)
+    );
+    status().await
+}
+
+/// Saves the recovery phrase sealed under `passphrase` where the user picks.
+/// Returns the path, or None when the dialog is cancelled.
+#[tauri::command]
+pub async fn backup_sync_key(passphrase: String) -> Result<Option<String>, String> {
+    if passphrase.chars().count() < encryption::MIN_PASSPHRASE {
+        return Err(format!(
+            "Use a passphrase of at least {} characters",
+            encryption::MIN_PASSPHRASE
+        ));
+    }
+    let key = current().await?;
+    let Some(path) = FileDialogBuilder::new()
+        .set_title(&i18n::t("dialog.backup_sync_key"))
+        .set_file_name(&format!("pinup-sync-key.{BACKUP_EXTENSION}"))
+        .add_filter("Sync key backup", &[BACKUP_EXTENSION])
+        .save_file()
+    else {
+        return Ok(None);
+    };
+    let phrase = phrase(&key);
+    let sealed = blocking(move || exports::seal(phrase.as_bytes(), &passphrase)).await?;
+    std::fs::write(&path, sealed).map_err(|e| format!("Could not write the backup: {e}"))?;
+    Ok(Some(path.to_string_lossy().to_string()))
+}
+
+/// Restores the key from a backup file the user picks. Returns None when
+/// the dialog is cancelled.
+#[tauri::command]
+pub async fn restore_sync_key_backup(passphrase: String) -> Result<Option<SyncKeyStatus>, String> {
+    let Some(path) = FileDialogBuilder::new()
+        .set_title(&i18n::t("dialog.restore_sync_key"))
+        .add_filter("Sync key backup", &[BACKUP_EXTENSION])
+        .pick_file()
+    else {
+        return Ok(None);
+    };
+    let sealed = std::fs::read(&path).map_err(|e| format!("Could not read {:?}: {e}", path))?;
+    let plaintext = blocking(move || exports::open(&sealed, &passphrase)).await?;
+    let phrase = String::from_utf8(plaintext).map_err(|_| "Not a sync key backup".to_string())?;
+    restore_sync_key(phrase).await.map(Some)
+}
+
+/// Removes the key from this device; its recovery phrase still restores it.
+#[tauri::command]
+pub async fn forget_sync_key() -> Result<SyncKeyStatus, String> {
+    blocking(|| keyring::delete(ACCOUNT)).await?;
+    status().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn random_key() -> Key {
+        let mut key = [0u8; 32];
+        getrandom::getrandom(&mut key).unwrap();
+        key
+    }
+
+    #[test]
+    fn phrase_round_trips() {
+        for _ in 0..200 {
+            let key = random_key();
+            let text = phrase(&key);
+            assert_eq!(text.len(), 55 + 10);
+            assert_eq!(parse_phrase(&text), Ok(key));
+        }
+    }
+
+    #[test]
+    fn phrase_accepts_lookalikes_and_spacing() {
+        let key = [0u8; 32];
+        let text = phrase(&key);
+        assert!(text.starts_with("00000-"));
+        let typed = text
+            .to_lowercase()
+            .replacen('0', "o", 3)
+            .replacen('0', "O", 1);
+        assert_eq!(parse_phrase(&typed), Ok(key));
+
+        let key = [0xFF; 32];
+        let text = phrase(&key);
+        let spaced = text.replace('-', " \n ");
+        assert_eq!(parse_phrase(&spaced), Ok(key));
+        assert_eq!(parse_phrase(&text.replace('-', "")), Ok(key));
+
+        let key = random_key();
+        let text = phrase(&key);
+        let ones = text.replace('1', "i");
+        assert_eq!(parse_phrase(&ones), Ok(key));
+        let ones = text.replace('1', "L");
+        assert_eq!(parse_phrase(&ones), Ok(key));
+    }
+
+    #[test]
+    fn phrase_rejects_a_typo() {
+        let key: Key = core::array::from_fn(|i| (i * 37 + 11) as u8);
+        let text = phrase(&key);
+        for (i, c) in text.char_indices().filter(|(_, c)| *c != '-') {
+            let at = ALPHABET.iter().position(|&a| a as char == c).unwrap();
+            let typo = ALPHABET[(at + 1) % ALPHABET.len()] as char;
+            let mut typed = text.clone();
+            typed.replace_range(i..i + 1, &typo.to_string());
+            assert!(parse_phrase(&typed).is_err(), "{typed} was accepted");
+        }
+        assert!(parse_phrase(&text.replacen('-', "U", 1)).is_err());
+    }
+
+    #[test]
+    fn phrase_rejects_wrong_lengths() {
+        let text = phrase(&random_key()).replace('-', "");
+        assert!(parse_phrase("").is_err());
+        assert!(parse_phrase(&text[..54]).is_err());
+        assert!(parse_phrase(&format!("{text}0")).is_err());
+        assert!(parse_phrase(&format!("{text}{text}")).is_err());
+    }
+}
@@ -0,0 +1,617 @@
+// Sync log — the encrypted change-log protocol shared by WebDAV, S3 and LAN
+// sync.
+//
+// Every device that syncs points at the same remote folder (a WebDAV
+// collection, an S3 prefix, a LAN host's folder). A sync:
+//   1. compares the library with what it last synced and, if anything
+//      changed, uploads changes/<ms>-<device>.pinupenc: the snippets created
+//      or edited since (with their tag and collection names) and the ids
+//      deleted;
+//   2. applies the other devices' change files it hasn't seen, oldest first.
+//      An edit or delete made on top of the local version applies. One made
+//      on another version diverged (see conflicts.rs): both are kept for
+//      review and the newer wins for now, so a delete spares snippets edited
+//      after it;
+//   3. every `snapshot_hours` uploads snapshots/<ms>-<device>.pinupenc, the
+//      whole library as a JSON export, keeping `keep_snapshots` per device;
+//   4. removes change files older than `retention_days`, unless the
+//      target leaves that to the remote (an S3 lifecycle rule).
+// A device syncing for the first time, or after more than `retention_days`
+// away, first restores the newest snapshot of another device, then applies
+// the changes made after it. Names start with a zero-padded time, so they
+// sort by age.
+//
+// Files are sealed like encrypted exports (see exports.rs) with the target's
+// passphrase, derived from the sync key (see sync_key.rs) unless the user
+// chose one, so the remote only sees ciphertext. A snapshot sealed with the
+// user's own passphrase also opens with "Import encrypted export".
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::conflicts::{Conflict, Side};
+use crate::lan_sync::{Folder, Peer};
+use crate::s3::Bucket;
+use crate::webdav::Dav;
+use crate::{backend, conflicts, exports};
+
+const CHANGES: &str = "changes/";
+const SNAPSHOTS: &str = "snapshots/";
+const EXTENSION: &str = "pinupenc";
+const CHANGESET_VERSION: u32 = 1;
+const HOUR_MS: i64 = 60 * 60 * 1000;
+
+/// Where a target keeps its files.
+pub enum Remote {
+    WebDav(Dav),
+    S3(Bucket),
+    /// The log this device hosts for LAN sync.
+    Local(Folder),
+    /// A LAN sync host's log.
+    Lan(Peer),
+}
+
+/// One sync target and its settings.
+pub struct Target<'a> {
+    /// Identifies the remote folder; state kept for another one is dropped.
+    pub key: String,
+    /// Names the target in conflicts.
+    pub label: String,
+    /// In the data dir.
+    pub state_file: String,
+    pub passphrase: &'a str,
+    pub snapshot_hours: u64,
+    /// Snapshots kept per device.
+    pub keep_snapshots: usize,
+    /// Change files older than this are removed.
+    pub retention_days: u64,
+    /// Off when the remote expires old change files itself.
+    pub prune: bool,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct SyncState {
+    /// `Target::key` the state belongs to.
+    target: String,
+    /// Names this device's files.
+    device: String,
+    /// Snippet id -> fingerprint as last synced.
+    synced: HashMap<String, String>,
+    /// Snippet id -> `content` as last synced, kept for deletions too: the
+    /// base of the next change to it.
+    #[serde(default)]
+    contents: HashMap<String, String>,
+    /// Snippet id -> when this device uploaded its deletion (Unix ms).
+    deleted: HashMap<String, i64>,
+    /// Change files applied here, or written here.
+    applied: BTreeSet<String>,
+    last_snapshot: Option<i64>,
+    last_sync: Option<i64>,
+}
+
+/// Contents of a change file.
+#[derive(Serialize, Deserialize)]
+struct ChangeSet {
+    version: u32,
+    device: String,
+    /// Unix ms.
+    at: i64,
+    /// Shaped like `exports::snippets_with_names`.
+    upserts: Vec<Value>,
+    deletes: Vec<String>,
+    /// Snippet id -> `content` of the version the change was made on, for
+    /// the snippets upserted or deleted that the writer had synced before.
+    #[serde(default)]
+    bases: HashMap<String, String>,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct SyncReport {
+    /// RFC 3339.
+    pub at: String,
+    /// Local edits and deletions uploaded.
+    pub uploaded: usize,
+    /// Change files from other devices applied.
+    pub applied: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    /// Incoming edits older than the local copy.
+    pub skipped: usize,
+    /// Diverged edits kept for review.
+    pub conflicts: usize,
+    pub snapshot: bool,
+    /// Snapshot this sync started from.
+    pub restored_from: Option<String>,
+}
+
+impl Remote {
+    async fn prepare(&self) -> Result<(), String> {
+        match self {
+            Remote::WebDav(dav) => {
+                for dir in ["", CHANGES, SNAPSHOTS] {
+                    dav.mkcol(dir).await?;
+                }
+                Ok(())
+            }
+            // Prefixes need no creating; folders are made on write.
+            Remote::S3(_) | Remote::Local(_) | Remote::Lan(_) => Ok(()),
+        }
+    }
+
+    /// Names of the sealed files in `dir`, oldest first.
+    async fn list(&self, dir: &str) -> Result<Vec<String>, String> {
+        let mut names = match self {
+            Remote::WebDav(dav) => dav.list(dir).await?,
+            Remote::S3(bucket) => bucket.list(dir).await?,
+            Remote::Local(folder) => folder.list(dir).await?,
+            Remote::Lan(peer) => peer.list(dir).await?,
+        };
+        let suffix = format!(".{EXTENSION}");
+        names.retain(|name| name.ends_with(&suffix));
+        names.sort();
+        Ok(names)
+    }
+
+    async fn get(&self, path: &str) -> Result<Vec<u8>, String> {
+        match self {
+            Remote::WebDav(dav) => dav.get(path).await,
+            Remote::S3(bucket) => bucket.get(path).await,
+            Remote::Local(folder) => folder.get(path).await,
+            Remote::Lan(peer) => peer.get(path).await,
+        }
+    }
+
+    async fn put(&self, path: &str, bytes: Vec<u8>) -> Result<(), String> {
+        match self {
+            Remote::WebDav(dav) => dav.put(path, bytes).await,
+            Remote::S3(bucket) => bucket.put(path, bytes).await,
+            Remote::Local(folder) => folder.put(path, bytes).await,
+            Remote::Lan(peer) => peer.put(path, bytes).await,
+        }
+    }
+
+    async fn delete(&self, path: &str) -> Result<(), String> {
+        match self {
+            Remote::WebDav(dav) => dav.delete(path).await,
+            Remote::S3(bucket) => bucket.delete(path).await,
+            Remote::Local(folder) => folder.delete(path).await,
+            Remote::Lan(peer) => peer.delete(path).await,
+        }
+    }
+}
+
+fn load_state(file: &str) -> SyncState {
+    std::fs::read_to_string(crate::data_dir().join(file))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(file: &str, state: &SyncState) -> Result<(), String> {
+    let path = crate::data_dir().join(file);
+    let text = serde_json::to_string(state).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, text).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, path).map_err(|e| e.to_string())
+}
+
+pub async fn blocking<T: Send + 'static>(
+    work: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(work)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn file_name(at: i64, device: &str) -> String {
+    format!("{at:013}-{device}.{EXTENSION}")
+}
+
+fn file_time(name: &str) -> i64 {
+    name.split('-')
+        .next()
+        .and_then(|t| t.parse().ok())
+        .unwrap_or(0)
+}
+
+fn file_device(name: &str) -> &str {
+    name.split_once('-')
+        .and_then(|(_, rest)| rest.strip_suffix(&format!(".{EXTENSION}")))
+        .unwrap_or_default()
+}
+
+async fn seal(plaintext: Vec<u8>, passphrase: &str) -> Result<Vec<u8>, String> {
+    let passphrase = passphrase.to_string();
+    blocking(move || exports::seal(&plaintext, &passphrase)).await
+}
+
+async fn open<T: DeserializeOwned>(sealed: Vec<u8>, passphrase: &str) -> Result<T, String> {
+    let passphrase = passphrase.to_string();
+    let plaintext = blocking(move || exports::open(&sealed, &passphrase)).await?;
+    serde_json::from_slice(&plaintext).map_err(|e| format!("Unreadable sync file: {e}"))
+}
+
+// ── Library ────────────────────────────────────────────────────────────────
+fn millis(value: &Value) -> i64 {
+    value
+        .as_i64()
+        .or_else(|| value.as_f64().map(|f| f as i64))
+        .unwrap_or(0)
+}
+
+/// Everything a sync compares: the snippet without the fields the backend
+/// updates on every save.
+fn fingerprint(snippet: &Value) -> String {
+    let mut snippet = snippet.clone();
+    if let Some(row) = snippet.as_object_mut() {
+        row.remove("updated_at");
+        row.remove("content_hash");
+        for list in ["tags", "collections"] {
+            let mut names = exports::string_list(&row[list]);
+            names.sort();
+            row.insert(list.into(), json!(names));
+        }
+    }
+    hex::encode(Sha256::digest(snippet.to_string().as_bytes()))
+}
+
+/// What a change sets: the synced fields only, comparable across devices.
+fn content(snippet: &Value) -> String {
+    fingerprint(&exports::snippet_patch(snippet))
+}
+
+async fn library() -> Result<Vec<Value>, String> {
+    let json = exports::fetch_export(None, None).await?;
+    let data: Value = serde_json::from_slice(&json).map_err(|e| e.to_string())?;
+    Ok(exports::snippets_with_names(&data))
+}
+
+fn fingerprints(snippets: &[Value]) -> HashMap<String, String> {
+    snippets
+        .iter()
+        .filter_map(|s| Some((s["id"].as_str()?.to_string(), fingerprint(s))))
+        .collect()
+}
+
+/// What `apply` works against.
+struct Local<'a> {
+    /// Id -> the library's version, kept current as changes apply.
+    snippets: HashMap<String, Value>,
+    /// Ids this device uploaded changes to in this sync.
+    edited: &'a BTreeSet<String>,
+    /// `SyncState::deleted`.
+    deleted: &'a HashMap<String, i64>,
+    /// `SyncState::contents`.
+    contents: &'a HashMap<String, String>,
+    /// Diverged changes found.
+    found: Vec<Conflict>,
+    /// Ids changed on top of the local version.
+    settled: Vec<String>,
+}
+
+impl Local<'_> {
+    /// Whether a change made on `base` diverged from the local version.
+    /// Without a base (a snapshot, an older device), only a change crossing
+    /// one uploaded in this sync is known to.
+    fn diverged(&mut self, id: &str, base: Option<&String>, current: &str) -> bool {
+        if base.is_some_and(|base| base == current) {
+            self.settled.push(id.to_string());
+            return false;
+        }
+        base.is_some() || self.edited.contains(id)
+    }
+}
+
+/// Applies another device's changes; returns the ids it touched.
+async fn apply(
+    changes: &ChangeSet,
+    local: &mut Local<'_>,
+    label: &str,
+    report: &mut SyncReport,
+) -> Result<Vec<String>, String> {
+    let mut touched = Vec::new();
+    let mut creates = Vec::new();
+    for snippet in &changes.upserts {
+        let Some(id) = snippet["id"].as_str() else {
+            continue;
+        };
+        let incoming = content(snippet);
+        let updated_at = millis(&snippet["updated_at"]);
+        let base = changes.bases.get(id);
+        let apply = match local.snippets.get(id).cloned() {
+            Some(current) => {
+                let current_content = content(&current);
+                if incoming == current_content {
+                    continue;
+                }
+                let newer = updated_at > millis(&current["updated_at"]);
+                if local.diverged(id, base, &current_content) {
+                    let kept = if newer { Side::Remote } else { Side::Local };
+                    local.found.push(Conflict::new(
+                        label,
+                        id,
+                        Some(current),
+                        Some(snippet.clone()),
+                        kept,
+                    ));
+                    newer
+                } else {
+                    newer || base.is_some()
+                }
+            }
+            None => match local.deleted.get(id) {
+                // Deleted here: the version deleted coming back is no news.
+                Some(_) if local.contents.get(id) == Some(&incoming) => continue,
+                Some(&at) => {
+                    let newer = updated_at > at;
+                    let kept = if newer { Side::Remote } else { Side::Local };
+                    local
+                        .found
+                        .push(Conflict::new(label, id, None, Some(snippet.clone()), kept));
+                    newer
+                }
+                None => true,
+            },
+        };
+        if !apply {
+            report.skipped += 1;
+            continue;
+        }
+        if local.snippets.contains_key(id) {
+            backend::patch(&format!("/snippets/{id}"), &exports::snippet_patch(snippet)).await?;
+            report.updated += 1;
+        } else {
+            creates.push(snippet.clone());
+        }
+        local.snippets.insert(id.to_string(), snippet.clone());
+        touched.push(id.to_string());
+    }
+    if !creates.is_empty() {
+        let count = creates.len();
+        let json =
+            serde_json::to_vec(&exports::bundle_with_names(creates)).map_err(|e| e.to_string())?;
+        exports::upload_json(&json).await?;
+        report.created += count;
+    }
+    for id in &changes.deletes {
+        let Some(current) = local.snippets.get(id).cloned() else {
+            continue;
+        };
+        let older = millis(&current["updated_at"]) <= changes.at;
+        let apply = if local.diverged(id, changes.bases.get(id), &content(&current)) {
+            let kept = if older { Side::Remote } else { Side::Local };
+            local
+                .found
+                .push(Conflict::new(label, id, Some(current), None, kept));
+            older
+        } else {
+            older || changes.bases.contains_key(id)
+        };
+        if apply {
+            backend::delete(&format!("/snippets/{id}")).await?;
+            local.snippets.remove(id);
+            report.deleted += 1;
+            touched.push(id.clone());
+        }
+    }
+    Ok(touched)
+}
+
+fn new_device() -> Result<String, String> {
+    let mut bytes = [0u8; 6];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("No random source: {e}"))?;
+    Ok(hex::encode(bytes))
+}
+
+pub async fn run(remote: &Remote, target: &Target<'_>) -> Result<SyncReport, String> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut report = SyncReport {
+        at: chrono::Local::now().to_rfc3339(),
+        ..Default::default()
+    };
+    let mut state = load_state(&target.state_file);
+    if state.target != target.key || state.device.is_empty() {
+        state = SyncState {
+            target: target.key.clone(),
+            device: new_device()?,
+            ..Default::default()
+        };
+    }
+    let retention = target.retention_days.max(1) as i64 * 24 * HOUR_MS;
+    if target.passphrase.is_empty() {
+        return Err("No key to encrypt synced data with".into());
+    }
+
+    remote.prepare().await?;
+    let listed = remote.list(CHANGES).await?;
+    state.applied.retain(|name| listed.contains(name));
+    state.deleted.retain(|_, at| now - *at <= retention);
+
+    // Local changes since the last sync.
+    let snippets = library().await?;
+    let current = fingerprints(&snippets);
+    let by_id: HashMap<String, Value> = snippets
+        .iter()
+        .filter_map(|s| Some((s["id"].as_str()?.to_string(), s.clone())))
+        .collect();
+    let upserts: Vec<Value> = snippets
+        .into_iter()
+        .filter(|s| {
+            let id = s["id"].as_str().unwrap_or_default();
+            state.synced.get(id) != current.get(id)
+        })
+        .collect();
+    let deletes: Vec<String> = state
+        .synced
+        .keys()
+        .filter(|id| !current.contains_key(*id))
+        .cloned()
+        .collect();
+    let edited: BTreeSet<String> = upserts
+        .iter()
+        .filter_map(|s| s["id"].as_str().map(String::from))
+        .chain(deletes.iter().cloned())
+        .collect();
+    if !edited.is_empty() {
+        report.uploaded = edited.len();
+        let name = file_name(now, &state.device);
+        let bases = edited
+            .iter()
+            .filter_map(|id| Some((id.clone(), state.contents.get(id)?.clone())))
+            .collect();
+        let changes = ChangeSet {
+            version: CHANGESET_VERSION,
+            device: state.device.clone(),
+            at: now,
+            upserts,
+            deletes,
+            bases,
+        };
+        let json = serde_json::to_vec(&changes).map_err(|e| e.to_string())?;
+        remote
+            .put(
+                &format!("{CHANGES}{name}"),
+                seal(json, target.passphrase).await?,
+            )
+            .await?;
+        for id in &changes.deletes {
+            state.deleted.insert(id.clone(), now);
+        }
+        state.applied.insert(name);
+    }
+    state.synced = current;
+    let deleted = &state.deleted;
+    state
+        .contents
+        .retain(|id, _| by_id.contains_key(id) || deleted.contains_key(id));
+    for (id, snippet) in &by_id {
+        state.contents.insert(id.clone(), content(snippet));
+    }
+    save_state(&target.state_file, &state)?;
+
+    // Other devices' changes, from a snapshot when starting over.
+    let mut local = Local {
+        snippets: by_id,
+        edited: &edited,
+        deleted: &state.deleted,
+        contents: &state.contents,
+        found: Vec::new(),
+        settled: Vec::new(),
+    };
+    let mut touched = Vec::new();
+    let mut after = 0;
+    if state.last_sync.map_or(true, |at| now - at > retention) {
+        let snapshots = remote.list(SNAPSHOTS).await?;
+        let latest = snapshots
+            .iter()
+            .rev()
+            .find(|name| file_device(name) != state.device);
+        if let Some(latest) = latest {
+            let data: Value = open(
+                remote.get(&format!("{SNAPSHOTS}{latest}")).await?,
+                target.passphrase,
+            )
+            .await?;
+            let restore = ChangeSet {
+                version: CHANGESET_VERSION,
+                device: file_device(latest).to_string(),
+                at: file_time(latest),
+                upserts: exports::snippets_with_names(&data),
+                deletes: Vec::new(),
+                bases: HashMap::new(),
+            };
+            touched.extend(apply(&restore, &mut local, &target.label, &mut report).await?);
+            // Files named just before the snapshot may not be in it yet; applying
+            // one twice is harmless.
+            after = restore.at - HOUR_MS;
+            report.restored_from = Some(latest.clone());
+        }
+    }
+    let mut applied = Vec::new();
+    for name in &listed {
+        if state.applied.contains(name) || file_device(name) == state.device {
+            continue;
+        }
+        if file_time(name) > after {
+            let changes: ChangeSet = open(
+                remote.get(&format!("{CHANGES}{name}")).await?,
+                target.passphrase,
+            )
+            .await?;
+            if changes.version > CHANGESET_VERSION {
+                return Err("Another device syncs with a newer version; update Pin-Up AI".into());
+            }
+            touched.extend(apply(&changes, &mut local, &target.label, &mut report).await?);
+            report.applied += 1;
+        }
+        applied.push(name.clone());
+    }
+    let Local { found, settled, .. } = local;
+    state.applied.extend(applied);
+    report.conflicts = found.len();
+    conflicts::settle(&target.label, &settled)?;
+    conflicts::record(found)?;
+    if !touched.is_empty() {
+        // Applied changes aren't local edits to send back.
+        let fresh: HashMap<String, Value> = library()
+            .await?
+            .into_iter()
+            .filter_map(|s| Some((s["id"].as_str()?.to_string(), s)))
+            .collect();
+        for id in touched {
+            match fresh.get(&id) {
+                Some(snippet) => {
+                    state.synced.insert(id.clone(), fingerprint(snippet));
+                    state.contents.insert(id, content(snippet));
+                }
+                None => {
+                    state.synced.remove(&id);
+                }
+            }
+        }
+    }
+    state.last_sync = Some(now);
+    save_state(&target.state_file, &state)?;
+
+    // Housekeeping.
+    let snapshot_due = state.last_snapshot.map_or(true, |at| {
+        now - at >= target.snapshot_hours.max(1) as i64 * HOUR_MS
+    });
+    if snapshot_due {
+        let json = exports::fetch_export(None, None).await?;
+        let name = file_name(now, &state.device);
+        remote
+            .put(
+                &format!("{SNAPSHOTS}{name}"),
+                seal(json, target.passphrase).await?,
+            )
+            .await?;
+        let own: Vec<String> = remote
+            .list(SNAPSHOTS)
+            .await?
+            .into_iter()
+            .filter(|name| file_device(name) == state.device)
+            .collect();
+        let excess = own.len().saturating_sub(target.keep_snapshots.max(1));
+        for old in &own[..excess] {
+            remote.delete(&format!("{SNAPSHOTS}{old}")).await.ok();
+        }
+        state.last_snapshot = Some(now);
+        report.snapshot = true;
+    }
+    let expired = listed
+        .iter()
+        .filter(|n| target.prune && now - file_time(n) > retention);
+    for name in expired {
+        if remote.delete(&format!("{CHANGES}{name}")).await.is_ok() {
+            state.applied.remove(name);
+        }
+    }
+    save_state(&target.state_file, &state)?;
+    Ok(report)
+}
@@ -0,0 +1,249 @@
+// Tasks — ids, progress and cancellation for long-running imports/exports.
+//
+// A command that starts one calls `spawn(app, kind, work)` and returns the
+// task id straight away; `work` runs in the background with a `Task` handle.
+// It reports `progress(done, total, current)` (items done out of total, and
+// the file or title in hand) and checks `cancelled()` between items. Every
+// change goes out as `task-progress:{id}` with the full status, progress at
+// most every PROGRESS_INTERVAL. `task-started` announces each new task, so
+// the frontend can follow ones it didn't start (drag-and-drop imports).
+// Each task is also a job in the registry (see jobs.rs), as "task:{id}".
+//
+// `cancel_task(id)` only raises a flag. The work stops at its next check,
+// removes anything it would leave half-written and returns what it has done
+// so far; the task then ends as "cancelled" with that partial result (or an
+// error saying where it stopped). Finished tasks keep their final status for
+// `get_task`, in case a listener subscribed late, until MAX_FINISHED newer
+// ones have finished.
+
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use crate::i18n;
+use crate::jobs::{self, JobStatus};
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+const MAX_FINISHED: usize = 50;
+
+/// What the user would call a task of `kind`.
+fn label(kind: &str) -> String {
+    match kind {
+        "import_files"
+        | "import_archive"
+        | "import_bookmarks"
+        | "import_encrypted"
+        | "import_enex"
+        | "import_notion_export"
+        | "import_watched"
+        | "export_archive"
+        | "export_encrypted"
+        | "export_markdown_folder"
+        | "move_data_dir" => i18n::t(&format!("job.{kind}")),
+        k if k.starts_with("export") => i18n::t("job.export"),
+        _ => i18n::t("job.import"),
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskState {
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TaskStatus {
+    id: u64,
+    /// The command that started it, e.g. "import_enex".
+    kind: &'static str,
+    state: TaskState,
+    done: u64,
+    total: Option<u64>,
+    /// File or item being worked on.
+    current: Option<String>,
+    cancel_requested: bool,
+    /// What the command returns; partial when cancelled.
+    result: Option<Value>,
+    error: Option<String>,
+    #[serde(skip)]
+    emitted_at: Option<Instant>,
+}
+
+static TASKS: Lazy<Mutex<Vec<TaskStatus>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Handed to the work of one task.
+#[derive(Clone)]
+pub struct Task {
+    app: AppHandle,
+    id: u64,
+}
+
+fn event(id: u64) -> String {
+    format!("task-progress:{}", id)
+}
+
+/// Applies `f` to task `id`; emits the result unless `throttle` is set and
+/// the last emit was under PROGRESS_INTERVAL ago.
+fn update(app: &AppHandle, id: u64, throttle: bool, f: impl FnOnce(&mut TaskStatus)) {
+    let status = {
+        let mut tasks = TASKS.lock().unwrap();
+        let Some(task) = tasks.iter_mut().find(|t| t.id == id) else {
+            return;
+        };
+        f(task);
+        let now = Instant::now();
+        if throttle
+            && task
+                .emitted_at
+                .is_some_and(|at| now.duration_since(at) < PROGRESS_INTERVAL)
+        {
+            return;
+        }
+        task.emitted_at = Some(now);
+        task.clone()
+    };
+    app.emit_all(&event(id), &status).ok();
+}
+
+impl Task {
+    pub fn cancelled(&self) -> bool {
+        TASKS
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|t| t.id == self.id)
+            .map_or(true, |t| t.cancel_requested)
+    }
+
+    pub fn progress(&self, done: u64, total: Option<u64>, current: Option<&str>) {
+        update(&self.app, self.id, true, |t| {
+            t.done = done;
+            t.total = total;
+            t.current = current.map(str::to_string);
+        });
+        jobs::progress(&format!("task:{}", self.id), |j| {
+            j.done = done;
+            j.total = total;
+            j.current = current.map(str::to_string);
+        });
+    }
+}
+
+/// Runs `work` in the background as a new task and returns its id.
+pub fn spawn<T, F, Fut>(app: &AppHandle, kind: &'static str, work: F) -> u64
+where
+    T: Serialize,
+    F: FnOnce(Task) -> Fut,
+    Fut: Future<Output = Result<T, String>> + Send + 'static,
+{
+    let status = {
+        let mut tasks = TASKS.lock().unwrap();
+        let status = TaskStatus {
+            id: tasks.last().map_or(1, |t| t.id + 1),
+            kind,
+            state: TaskState::Running,
+            done: 0,
+            total: None,
+            current: None,
+            cancel_requested: false,
+            result: None,
+            error: None,
+            emitted_at: None,
+        };
+        tasks.push(status.clone());
+        status
+    };
+    let id = status.id;
+    app.emit_all("task-started", &status).ok();
+    jobs::started(JobStatus::new(
+        format!("task:{id}"),
+        kind,
+        &label(kind),
+        false,
+    ));
+
+    let task = Task {
+        app: app.clone(),
+        id,
+    };
+    let future = work(task.clone());
+    tauri::async_runtime::spawn(async move {
+        let outcome = future
+            .await
+            .and_then(|value| serde_json::to_value(value).map_err(|e| e.to_string()));
+        let cancelled = task.cancelled();
+        match &outcome {
+            Ok(_) if cancelled => log::info!("Task {} ({}) cancelled", id, kind),
+            Ok(_) => log::info!("Task {} ({}) done", id, kind),
+            Err(e) => log::warn!("Task {} ({}) ended: {}", id, kind, e),
+        }
+        let state = match (&outcome, cancelled) {
+            (_, true) => TaskState::Cancelled,
+            (Ok(_), false) => TaskState::Done,
+            (Err(_), false) => TaskState::Failed,
+        };
+        jobs::finished(
+            &format!("task:{id}"),
+            state,
+            outcome.as_ref().err().cloned(),
+        );
+        update(&task.app, id, false, |t| {
+            t.state = state;
+            match outcome {
+                Ok(value) => t.result = Some(value),
+                Err(e) => t.error = Some(e),
+            }
+        });
+        prune();
+    });
+    id
+}
+
+/// Drops the oldest finished tasks beyond MAX_FINISHED.
+fn prune() {
+    let mut tasks = TASKS.lock().unwrap();
+    let finished = tasks
+        .iter()
+        .filter(|t| t.state != TaskState::Running)
+        .count();
+    let mut excess = finished.saturating_sub(MAX_FINISHED);
+    tasks.retain(|t| {
+        if excess > 0 && t.state != TaskState::Running {
+            excess -= 1;
+            return false;
+        }
+        true
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Current status of a task, or None once it has been pruned.
+#[tauri::command]
+pub fn get_task(id: u64) -> Option<TaskStatus> {
+    TASKS.lock().unwrap().iter().find(|t| t.id == id).cloned()
+}
+
+/// Running tasks and recently finished ones, oldest first.
+#[tauri::command]
+pub fn list_tasks() -> Vec<TaskStatus> {
+    TASKS.lock().unwrap().clone()
+}
+
+/// Asks a running task to stop; it ends as "cancelled" with a partial result.
+#[tauri::command]
+pub fn cancel_task(app: AppHandle, id: u64) -> Result<(), String> {
+    let state = get_task(id).ok_or("No such task")?.state;
+    if state != TaskState::Running {
+        return Err("Task already finished".into());
+    }
+    update(&app, id, false, |t| t.cancel_requested = true);
+    Ok(())
+}
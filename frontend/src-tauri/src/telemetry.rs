@@ -0,0 +1,261 @@
+// Telemetry — opt-in, anonymous usage counters.
+//
+// Off until the user calls `set_telemetry_enabled(true)`; while off nothing
+// is recorded at all. Recorded events:
+//   app_launch        app version, OS, arch
+//   startup_latency   ms from process start to the first healthy backend
+//   sidecar_crash     exit code of a backend that died on its own
+// No snippet content, paths, hostnames or user identifiers — only a random
+// install id created at opt-in and discarded at opt-out.
+//
+// Events are appended to app_dir()/telemetry/queue.jsonl and every
+// UPLOAD_INTERVAL the queue is posted as one batch to `telemetry.endpoint`,
+// then cleared. events.jsonl keeps everything ever recorded (up to
+// LOG_LIMIT lines) for `get_telemetry_events`, so users can see exactly
+// what was sent.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+
+use crate::{config, proxy};
+
+const DEFAULT_ENDPOINT: &str = "https://telemetry.pinupai.app/v1/events";
+const UPLOAD_INTERVAL: Duration = Duration::from_secs(3600);
+const LOG_LIMIT: usize = 5000;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    pub enabled: bool,
+    /// Overrides DEFAULT_ENDPOINT.
+    pub endpoint: Option<String>,
+    /// Random id created at opt-in.
+    pub install_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TelemetryEvent {
+    name: String,
+    /// RFC 3339.
+    at: String,
+    props: Value,
+    /// Whether it has been uploaded (events.jsonl only).
+    #[serde(default)]
+    sent: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TelemetryStatus {
+    enabled: bool,
+    install_id: Option<String>,
+    endpoint: String,
+    queued: usize,
+}
+
+static LATENCY_RECORDED: AtomicBool = AtomicBool::new(false);
+/// Serializes writes to the queue and log files.
+static FILES: Mutex<()> = Mutex::new(());
+
+fn telemetry_dir() -> PathBuf {
+    crate::app_dir().join("telemetry")
+}
+
+fn queue_path() -> PathBuf {
+    telemetry_dir().join("queue.jsonl")
+}
+
+fn log_path() -> PathBuf {
+    telemetry_dir().join("events.jsonl")
+}
+
+fn read_events(path: &PathBuf) -> Vec<TelemetryEvent> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+fn write_events(path: &PathBuf, events: &[TelemetryEvent]) -> std::io::Result<()> {
+    let mut text = String::new();
+    for event in events {
+        text.push_str(&serde_json::to_string(event).unwrap_or_default());
+        text.push('\n');
+    }
+    std::fs::write(path, text)
+}
+
+fn new_install_id() -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(
+        chrono::Utc::now()
+            .timestamp_nanos_opt()
+            .unwrap_or(0)
+            .to_le_bytes(),
+    );
+    hasher.update(std::process::id().to_le_bytes());
+    hasher.update(crate::app_dir().to_string_lossy().as_bytes());
+    hex::encode(&hasher.finalize()[..16])
+}
+
+/// Queues an event when telemetry is enabled; a no-op otherwise.
+pub fn record(app: &AppHandle, name: &str, props: Value) {
+    if !config::current(app).telemetry.enabled {
+        return;
+    }
+    let event = TelemetryEvent {
+        name: name.to_string(),
+        at: chrono::Utc::now().to_rfc3339(),
+        props,
+        sent: false,
+    };
+    let _guard = FILES.lock().unwrap();
+    let written = std::fs::create_dir_all(telemetry_dir()).and_then(|_| {
+        let line = serde_json::to_string(&event).unwrap_or_default();
+        for path in [queue_path(), log_path()] {
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?;
+            writeln!(file, "{}", line)?;
+        }
+        Ok(())
+    });
+    if let Err(e) = written {
+        log::debug!("Could not record telemetry event: {}", e);
+    }
+}
+
+/// Records the time to the first healthy backend; once per launch.
+pub fn startup_latency(app: &AppHandle) {
+    if LATENCY_RECORDED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    record(
+        app,
+        "startup_latency",
+        json!({ "ms": crate::uptime().as_millis() as u64 }),
+    );
+}
+
+pub fn sidecar_crash(app: &AppHandle, code: Option<i32>) {
+    record(app, "sidecar_crash", json!({ "code": code }));
+}
+
+/// Uploads the queue as one batch; keeps it on failure.
+async fn upload(app: &AppHandle) -> Result<usize, String> {
+    let cfg = config::current(app).telemetry;
+    if !cfg.enabled {
+        return Ok(0);
+    }
+    let queued = {
+        let _guard = FILES.lock().unwrap();
+        read_events(&queue_path())
+    };
+    if queued.is_empty() {
+        return Ok(0);
+    }
+    let endpoint = cfg.endpoint.as_deref().unwrap_or(DEFAULT_ENDPOINT);
+    let client = proxy::client_builder(app)
+        .timeout(Duration::from_secs(15))
+        .build()
+        .map_err(|e| e.to_string())?;
+    client
+        .post(endpoint)
+        .json(&json!({ "install_id": cfg.install_id, "events": queued }))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Telemetry upload failed: {e}"))?;
+
+    let _guard = FILES.lock().unwrap();
+    // Keep events queued while the upload was in flight.
+    let remaining: Vec<TelemetryEvent> = read_events(&queue_path())
+        .into_iter()
+        .skip(queued.len())
+        .collect();
+    write_events(&queue_path(), &remaining).map_err(|e| e.to_string())?;
+    let mut log = read_events(&log_path());
+    let unsent: Vec<&mut TelemetryEvent> = log.iter_mut().filter(|e| !e.sent).collect();
+    for event in unsent.into_iter().take(queued.len()) {
+        event.sent = true;
+    }
+    let skip = log.len().saturating_sub(LOG_LIMIT);
+    write_events(&log_path(), &log[skip..]).map_err(|e| e.to_string())?;
+    Ok(queued.len())
+}
+
+/// Records the launch and uploads the queue periodically.
+pub fn start(app: &AppHandle) {
+    let info = app.package_info();
+    record(
+        app,
+        "app_launch",
+        json!({
+            "version": info.version.to_string(),
+            "os": std::env::consts::OS,
+            "arch": std::env::consts::ARCH,
+        }),
+    );
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(UPLOAD_INTERVAL).await;
+            match upload(&handle).await {
+                Ok(0) => {}
+                Ok(n) => log::debug!("Uploaded {} telemetry events", n),
+                Err(e) => log::debug!("{}", e),
+            }
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn get_telemetry_status(app: AppHandle) -> TelemetryStatus {
+    let cfg = config::current(&app).telemetry;
+    let _guard = FILES.lock().unwrap();
+    TelemetryStatus {
+        enabled: cfg.enabled,
+        install_id: cfg.install_id,
+        endpoint: cfg.endpoint.unwrap_or_else(|| DEFAULT_ENDPOINT.into()),
+        queued: read_events(&queue_path()).len(),
+    }
+}
+
+/// Opts in (creating an install id) or out (deleting the id, queue and log).
+#[tauri::command]
+pub fn set_telemetry_enabled(app: AppHandle, enabled: bool) -> Result<TelemetryStatus, String> {
+    config::update(&app, |c| {
+        c.telemetry.enabled = enabled;
+        c.telemetry.install_id = if enabled {
+            c.telemetry
+                .install_id
+                .take()
+                .or_else(|| Some(new_install_id()))
+        } else {
+            None
+        };
+    })?;
+    if !enabled {
+        let _guard = FILES.lock().unwrap();
+        std::fs::remove_dir_all(telemetry_dir()).ok();
+    }
+    log::info!("Telemetry {}", if enabled { "enabled" } else { "disabled" });
+    Ok(get_telemetry_status(app))
+}
+
+/// Every event recorded on this machine, newest last, with its upload state.
+#[tauri::command]
+pub fn get_telemetry_events() -> Vec<TelemetryEvent> {
+    let _guard = FILES.lock().unwrap();
+    read_events(&log_path())
+}
@@ -0,0 +1,250 @@
+// Theme — follow the OS light/dark appearance.
+//
+// The appearance comes from:
+//   macOS, Windows  the main window's theme and its ThemeChanged events
+//   Linux           the desktop portal's `color-scheme` and its
+//                   SettingChanged signal (WebKitGTK's prefers-color-scheme
+//                   doesn't follow the desktop), else GNOME's settings,
+//                   read every POLL_INTERVAL
+// Every change goes out to all windows as "theme-changed" ("light" or
+// "dark"), so windows in "system" mode switch without a restart, and
+// redraws the tray icon (see tray_status.rs). The Windows taskbar has a
+// light/dark setting of its own, read separately (see `taskbar`).
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::tray_status;
+
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SystemTheme {
+    #[default]
+    Light,
+    Dark,
+}
+
+impl From<tauri::Theme> for SystemTheme {
+    fn from(theme: tauri::Theme) -> Self {
+        match theme {
+            tauri::Theme::Dark => SystemTheme::Dark,
+            _ => SystemTheme::Light,
+        }
+    }
+}
+
+static CURRENT: Mutex<Option<SystemTheme>> = Mutex::new(None);
+
+/// The OS appearance; light until it is first read.
+pub fn current() -> SystemTheme {
+    CURRENT.lock().unwrap().unwrap_or_default()
+}
+
+/// Records the appearance, announcing it if it changed.
+fn set(app: &AppHandle, theme: SystemTheme) {
+    let previous = CURRENT.lock().unwrap().replace(theme);
+    if previous != Some(theme) {
+        log::info!("System theme: {:?}", theme);
+        app.emit_all("theme-changed", theme).ok();
+        tray_status::refresh();
+    }
+}
+
+#[cfg(windows)]
+mod windows_taskbar {
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use windows::core::w;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD};
+
+    use super::SystemTheme;
+    use crate::tray_status;
+
+    const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+    static CURRENT: Mutex<Option<SystemTheme>> = Mutex::new(None);
+
+    /// SystemUsesLightTheme; missing before Windows 10 1903, whose taskbar
+    /// is always dark.
+    fn read() -> SystemTheme {
+        let mut value = 0u32;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        let result = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                w!("Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize"),
+                w!("SystemUsesLightTheme"),
+                RRF_RT_REG_DWORD,
+                None,
+                Some(&mut value as *mut u32 as *mut _),
+                Some(&mut size),
+            )
+        };
+        match result {
+            Ok(()) if value != 0 => SystemTheme::Light,
+            _ => SystemTheme::Dark,
+        }
+    }
+
+    pub fn current() -> SystemTheme {
+        CURRENT.lock().unwrap().unwrap_or(SystemTheme::Dark)
+    }
+
+    /// Re-reads the setting, redrawing the tray if it changed.
+    pub fn check() {
+        let theme = read();
+        if CURRENT.lock().unwrap().replace(theme) != Some(theme) {
+            log::info!("Taskbar theme: {:?}", theme);
+            tray_status::refresh();
+        }
+    }
+
+    /// Changing only the taskbar's setting sends no ThemeChanged.
+    pub fn watch() {
+        loop {
+            check();
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+/// What the tray sits on: the taskbar's own setting on Windows, the OS
+/// appearance elsewhere.
+pub fn taskbar() -> SystemTheme {
+    #[cfg(windows)]
+    return windows_taskbar::current();
+    #[cfg(not(windows))]
+    current()
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::time::Duration;
+
+    use zbus::blocking::{Connection, Proxy};
+    use zbus::zvariant::{OwnedValue, Value};
+
+    use super::SystemTheme;
+
+    const POLL_INTERVAL: Duration = Duration::from_secs(10);
+    const NAMESPACE: &str = "org.freedesktop.appearance";
+    const KEY: &str = "color-scheme";
+
+    /// 1 is "prefer dark"; 0 (no preference) and 2 are light. `Read` wraps
+    /// the value in one more variant than `ReadOne`.
+    fn scheme(value: &Value) -> Option<SystemTheme> {
+        match value {
+            Value::Value(inner) => scheme(inner),
+            Value::U32(1) => Some(SystemTheme::Dark),
+            Value::U32(_) => Some(SystemTheme::Light),
+            _ => None,
+        }
+    }
+
+    fn portal(bus: &Connection) -> zbus::Result<Proxy<'_>> {
+        Proxy::new(
+            bus,
+            "org.freedesktop.portal.Desktop",
+            "/org/freedesktop/portal/desktop",
+            "org.freedesktop.portal.Settings",
+        )
+    }
+
+    fn gsettings(key: &str) -> Option<String> {
+        let output = tauri::api::process::Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.interface", key])
+            .output()
+            .ok()?;
+        Some(output.stdout.trim().trim_matches('\'').to_lowercase())
+    }
+
+    pub fn read() -> Option<SystemTheme> {
+        let from_portal = Connection::session().ok().and_then(|bus| {
+            let value: OwnedValue = portal(&bus).ok()?.call("Read", &(NAMESPACE, KEY)).ok()?;
+            scheme(&value)
+        });
+        if from_portal.is_some() {
+            return from_portal;
+        }
+        match gsettings("color-scheme").as_deref() {
+            Some("prefer-dark") => return Some(SystemTheme::Dark),
+            Some("prefer-light") => return Some(SystemTheme::Light),
+            _ => {}
+        }
+        let theme = gsettings("gtk-theme")?;
+        Some(if theme.ends_with("-dark") {
+            SystemTheme::Dark
+        } else {
+            SystemTheme::Light
+        })
+    }
+
+    /// Calls `changed` on every portal change; polls without a portal.
+    pub fn watch(changed: impl Fn(SystemTheme)) {
+        let result = Connection::session().and_then(|bus| {
+            let proxy = portal(&bus)?;
+            for signal in proxy.receive_signal("SettingChanged")? {
+                let Ok((namespace, key, value)) = signal.body::<(String, String, OwnedValue)>()
+                else {
+                    continue;
+                };
+                if namespace == NAMESPACE && key == KEY {
+                    if let Some(theme) = scheme(&value) {
+                        changed(theme);
+                    }
+                }
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            log::info!("System theme: no desktop portal ({}), polling", e);
+        }
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            if let Some(theme) = read() {
+                changed(theme);
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    std::thread::spawn(move || {
+        if let Some(theme) = linux::read() {
+            set(&handle, theme);
+        }
+        linux::watch(|theme| set(&handle, theme));
+    });
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn start(app: &AppHandle) {
+    let Some(window) = app.get_window("main") else {
+        return;
+    };
+    if let Ok(theme) = window.theme() {
+        set(app, theme.into());
+    }
+    #[cfg(windows)]
+    std::thread::spawn(windows_taskbar::watch);
+    let handle = app.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::ThemeChanged(theme) = event {
+            set(&handle, (*theme).into());
+            #[cfg(windows)]
+            windows_taskbar::check();
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// The OS appearance, "light" or "dark".
+#[tauri::command]
+pub fn get_system_theme() -> SystemTheme {
+    current()
+}
@@ -0,0 +1,380 @@
+// Theme tokens — export the app palette and user colors as design tokens.
+//
+// Sources, in output order:
+//   brand     the brand scale from tailwind.config.js (plus the active mode)
+//   tags      every tag that has a color
+//   palette   colors found in snippets tagged PALETTE_TAG, one group per
+//             snippet; `name: #hex` / `--name: rgb(...)` lines keep their
+//             name, bare colors are numbered
+//
+// Formats: W3C design-tokens JSON, CSS custom properties, or a Tailwind
+// config fragment.
+//
+// `pick_color` opens the OS color dialog for colors outside the presets:
+//   Windows  ChooseColor
+//   macOS    AppleScript `choose color` (NSColorPanel)
+//   Linux    zenity, then kdialog
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tauri::Window;
+
+use crate::theme::{self, SystemTheme};
+use crate::{backend, disk};
+
+pub const PALETTE_TAG: &str = "palette";
+
+/// Keep in sync with `theme.extend.colors.brand` in tailwind.config.js.
+const BRAND: &[(&str, &str)] = &[
+    ("50", "#eff6ff"),
+    ("100", "#dbeafe"),
+    ("200", "#bfdbfe"),
+    ("500", "#3b82f6"),
+    ("600", "#2563eb"),
+    ("700", "#1d4ed8"),
+];
+
+static COLOR: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)#(?:[0-9a-f]{8}|[0-9a-f]{6}|[0-9a-f]{3,4})\b|\b(?:rgba?|hsla?)\([^)]*\)")
+        .unwrap()
+});
+static NAMED: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*(?:[-*]\s*)?(?:--)?([A-Za-z][\w .-]*?)\s*[:=]\s*(.+)$").unwrap());
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenFormat {
+    Json,
+    Css,
+    Tailwind,
+}
+
+/// Group name → token name → CSS color.
+type Tokens = BTreeMap<String, BTreeMap<String, String>>;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TokenExport {
+    path: String,
+    mode: String,
+    groups: usize,
+    tokens: usize,
+}
+
+fn slug(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            out.push(c.to_ascii_lowercase());
+        } else if !out.ends_with('-') && !out.is_empty() {
+            out.push('-');
+        }
+    }
+    out.trim_end_matches('-').to_string()
+}
+
+/// Colors in a palette snippet body, named where the line names them.
+fn parse_palette(body: &str) -> BTreeMap<String, String> {
+    let mut colors = BTreeMap::new();
+    let mut unnamed = 0;
+    for line in body.lines() {
+        if let Some(caps) = NAMED.captures(line) {
+            if let Some(color) = COLOR.find(&caps[2]) {
+                colors.insert(slug(&caps[1]), color.as_str().to_lowercase());
+                continue;
+            }
+        }
+        for color in COLOR.find_iter(line) {
+            unnamed += 1;
+            colors.insert(unnamed.to_string(), color.as_str().to_lowercase());
+        }
+    }
+    colors
+}
+
+async fn collect(tokens: &mut Tokens) -> Result<(), String> {
+    let tags = backend::get("/tags").await?;
+    let tags = tags["items"].as_array().cloned().unwrap_or_default();
+    let tag_colors: BTreeMap<String, String> = tags
+        .iter()
+        .filter_map(|t| Some((slug(t["name"].as_str()?), t["color"].as_str()?.to_string())))
+        .filter(|(name, color)| !name.is_empty() && COLOR.is_match(color))
+        .collect();
+    if !tag_colors.is_empty() {
+        tokens.insert("tags".into(), tag_colors);
+    }
+
+    let Some(palette_tag) = tags
+        .iter()
+        .find(|t| {
+            t["name"]
+                .as_str()
+                .is_some_and(|n| n.eq_ignore_ascii_case(PALETTE_TAG))
+        })
+        .and_then(|t| t["id"].as_str())
+    else {
+        return Ok(());
+    };
+    let mut offset = 0;
+    loop {
+        let page = backend::get(&format!(
+            "/snippets?tag_id={}&limit=200&offset={}",
+            palette_tag, offset
+        ))
+        .await?;
+        let items = page["items"].as_array().cloned().unwrap_or_default();
+        for snippet in &items {
+            let colors = parse_palette(snippet["body"].as_str().unwrap_or(""));
+            if colors.is_empty() {
+                continue;
+            }
+            let title = snippet["title"].as_str().unwrap_or("");
+            let mut group = format!("palette-{}", slug(title));
+            if group == "palette-" || tokens.contains_key(&group) {
+                group = format!("palette-{}", snippet["id"].as_str().unwrap_or("x"));
+            }
+            tokens.insert(group, colors);
+        }
+        offset += items.len();
+        if items.is_empty() || offset as u64 >= page["total"].as_u64().unwrap_or(0) {
+            return Ok(());
+        }
+    }
+}
+
+fn render(tokens: &Tokens, mode: &str, format: TokenFormat) -> String {
+    match format {
+        TokenFormat::Json => {
+            let mut root = Map::new();
+            root.insert(
+                "$description".into(),
+                json!(format!("Pin-Up AI theme tokens ({} mode)", mode)),
+            );
+            for (group, colors) in tokens {
+                let entries: Map<String, Value> = colors
+                    .iter()
+                    .map(|(name, value)| {
+                        (name.clone(), json!({ "$type": "color", "$value": value }))
+                    })
+                    .collect();
+                root.insert(group.clone(), Value::Object(entries));
+            }
+            serde_json::to_string_pretty(&root).unwrap_or_default() + "\n"
+        }
+        TokenFormat::Css => {
+            let mut out = format!(
+                "/* Pin-Up AI theme tokens */\n:root {{\n  color-scheme: {};\n",
+                mode
+            );
+            for (group, colors) in tokens {
+                for (name, value) in colors {
+                    out.push_str(&format!("  --{}-{}: {};\n", group, name, value));
+                }
+            }
+            out.push_str("}\n");
+            out
+        }
+        TokenFormat::Tailwind => {
+            let colors: Map<String, Value> = tokens
+                .iter()
+                .map(|(group, colors)| (group.clone(), json!(colors)))
+                .collect();
+            let colors = serde_json::to_string_pretty(&Value::Object(colors)).unwrap_or_default();
+            format!(
+                "/** Pin-Up AI theme tokens ({} mode) */\nmodule.exports = {{\n  theme: {{\n    extend: {{\n      colors: {}\n    }}\n  }}\n}};\n",
+                mode,
+                colors.replace('\n', "\n      ")
+            )
+        }
+    }
+}
+
+// ── Color picker ───────────────────────────────────────────────────────────
+/// `#rrggbb` → (r, g, b).
+fn rgb(color: &str) -> Option<(u8, u8, u8)> {
+    let digits = color.strip_prefix('#').filter(|d| d.len() == 6)?;
+    let channel = |i: usize| u8::from_str_radix(digits.get(i..i + 2)?, 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+#[cfg(windows)]
+fn pick(initial: (u8, u8, u8), window: Window) -> Result<Option<String>, String> {
+    use windows::Win32::Foundation::{COLORREF, HWND};
+    use windows::Win32::UI::Controls::Dialogs::{
+        ChooseColorW, CC_FULLOPEN, CC_RGBINIT, CHOOSECOLORW,
+    };
+
+    let (r, g, b) = initial;
+    let mut custom = [COLORREF(0x00ff_ffff); 16];
+    let mut dialog = CHOOSECOLORW {
+        lStructSize: std::mem::size_of::<CHOOSECOLORW>() as u32,
+        hwndOwner: HWND(window.hwnd().map_or(0, |h| h.0)),
+        rgbResult: COLORREF(u32::from(r) | u32::from(g) << 8 | u32::from(b) << 16),
+        lpCustColors: custom.as_mut_ptr(),
+        Flags: CC_FULLOPEN | CC_RGBINIT,
+        ..Default::default()
+    };
+    // FALSE for both cancel and failure; either way there is no color.
+    if !unsafe { ChooseColorW(&mut dialog) }.as_bool() {
+        return Ok(None);
+    }
+    let c = dialog.rgbResult.0;
+    Ok(Some(hex((c as u8, (c >> 8) as u8, (c >> 16) as u8))))
+}
+
+#[cfg(target_os = "macos")]
+fn pick(initial: (u8, u8, u8), _window: Window) -> Result<Option<String>, String> {
+    // AppleScript colors are 16 bits per channel.
+    let (r, g, b) = initial;
+    let script = format!(
+        "choose color default color {{{}, {}, {}}}",
+        u32::from(r) * 257,
+        u32::from(g) * 257,
+        u32::from(b) * 257
+    );
+    let output = tauri::api::process::Command::new("osascript")
+        .args(["-e", &script])
+        .output()
+        .map_err(|e| format!("Could not open the color picker: {e}"))?;
+    // Cancel exits with error -128.
+    if !output.status.success() {
+        return Ok(None);
+    }
+    let channels: Vec<u32> = output
+        .stdout
+        .split(',')
+        .filter_map(|c| c.trim().parse().ok())
+        .collect();
+    match channels[..] {
+        [r, g, b] => Ok(Some(hex((
+            (r / 257) as u8,
+            (g / 257) as u8,
+            (b / 257) as u8,
+        )))),
+        _ => Err(format!(
+            "Unexpected color picker output: {}",
+            output.stdout.trim()
+        )),
+    }
+}
+
+/// zenity prints `rgb(r,g,b)`, or `#rrrrggggbbbb` in older versions;
+/// kdialog prints `#rrggbb`.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn parse_picked(text: &str) -> Option<String> {
+    if let Some(digits) = text.strip_prefix('#').filter(|d| d.len() == 12) {
+        let channel = |i: usize| u8::from_str_radix(digits.get(i..i + 2)?, 16).ok();
+        return Some(hex((channel(0)?, channel(4)?, channel(8)?)));
+    }
+    if text.starts_with('#') {
+        return rgb(text).map(hex);
+    }
+    let inner = text
+        .strip_prefix("rgba(")
+        .or_else(|| text.strip_prefix("rgb("))?
+        .strip_suffix(')')?;
+    let channels: Vec<u8> = inner
+        .split(',')
+        .take(3)
+        .filter_map(|c| c.trim().parse().ok())
+        .collect();
+    match channels[..] {
+        [r, g, b] => Some(hex((r, g, b))),
+        _ => None,
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn pick(initial: (u8, u8, u8), _window: Window) -> Result<Option<String>, String> {
+    let initial = hex(initial);
+    let pickers = [
+        (
+            "zenity",
+            vec![
+                "--color-selection".to_string(),
+                format!("--color={initial}"),
+            ],
+        ),
+        (
+            "kdialog",
+            vec!["--getcolor".into(), "--default".into(), initial.clone()],
+        ),
+    ];
+    for (program, args) in pickers {
+        // Not installed: try the next one.
+        let Ok(output) = tauri::api::process::Command::new(program)
+            .args(args)
+            .output()
+        else {
+            continue;
+        };
+        if !output.status.success() {
+            return Ok(None);
+        }
+        let text = output.stdout.trim();
+        return parse_picked(text)
+            .map(Some)
+            .ok_or_else(|| format!("Unexpected output from {program}: {text}"));
+    }
+    Err("No color picker found; install zenity or kdialog".into())
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Opens the OS color dialog at `initial` (`#rrggbb`) over the calling
+/// window; None if the user cancelled.
+#[tauri::command]
+pub async fn pick_color(window: Window, initial: Option<String>) -> Result<Option<String>, String> {
+    let initial = initial
+        .as_deref()
+        .and_then(rgb)
+        .unwrap_or((0x63, 0x66, 0xf1));
+    tauri::async_runtime::spawn_blocking(move || pick(initial, window))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Writes design tokens to `path`. `mode` is the app's light/dark setting;
+/// without it the OS appearance (see theme.rs) is used. Tags and palette
+/// snippets are skipped, with a warning, if the backend is down.
+#[tauri::command]
+pub async fn export_theme_tokens(
+    path: String,
+    format: TokenFormat,
+    mode: Option<String>,
+) -> Result<TokenExport, String> {
+    let mode = mode.unwrap_or_else(|| match theme::current() {
+        SystemTheme::Dark => "dark".into(),
+        SystemTheme::Light => "light".into(),
+    });
+
+    let mut tokens = Tokens::new();
+    tokens.insert(
+        "brand".into(),
+        BRAND
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+    );
+    if let Err(e) = collect(&mut tokens).await {
+        log::warn!("Theme export without tags/palettes: {}", e);
+    }
+
+    let rendered = render(&tokens, &mode, format);
+    let target = Path::new(&path);
+    disk::ensure_space(target.parent().unwrap_or(target), rendered.len() as u64)?;
+    std::fs::write(&path, rendered).map_err(|e| format!("Could not write {}: {}", path, e))?;
+    Ok(TokenExport {
+        path,
+        mode,
+        groups: tokens.len(),
+        tokens: tokens.values().map(BTreeMap::len).sum(),
+    })
+}
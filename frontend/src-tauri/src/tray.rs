@@ -0,0 +1,464 @@
+// Tray — the system tray icon and its menu.
+//
+// Open, New Snippet, Search, Meeting Mode, Pause Capture, a "Recent"
+// submenu with the last `recent_count` snippets (see recent.rs), titles
+// truncated, and a "Profile" submenu to switch profiles (see profiles.rs). Clicking one copies it as rich text (see clipboard.rs) or opens
+// it in the main window, per `recent_action`. The backend has no change
+// feed, so the menu is rebuilt when recent.rs sees the list change, when
+// capture is paused or resumed, when a meeting starts or ends and when these
+// settings or the language (see i18n.rs) change. The Dock menu (see dock.rs)
+// and deep links reuse the actions through `menu_action`.
+//
+// A click and a double click on the icon each run a configurable action:
+// show the window, file the clipboard text as a snippet, paste the most
+// recent snippet into the frontmost app, or open the menu. Only macOS can
+// open the menu on a click, and only as the tray is created, so that one
+// takes effect on the next launch. Windows opens it on a right click; Linux
+// trays report no clicks at all, only menu items. Tauri's tray reports no
+// middle clicks on any platform.
+//
+// With `start_minimized` the app launches to the tray without showing the
+// main window (see `run` in lib.rs), as with the --minimized flag.
+//
+// Closing the main window hides it to the tray or quits, per `on_close`.
+// Until the user has picked one, the first close asks and remembers the
+// answer.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::api::dialog::{MessageDialogBuilder, MessageDialogButtons};
+use tauri::{
+    AppHandle, CloseRequestApi, CustomMenuItem, Manager, SystemTray, SystemTrayEvent,
+    SystemTrayMenu, SystemTrayMenuItem, SystemTraySubmenu, Window,
+};
+
+use crate::capture::{self, Capture};
+use crate::i18n::t;
+use crate::profiles::{self, Profile};
+use crate::recent::{self, RecentSnippet};
+use crate::{app_lock, clipboard, config, meeting, paste};
+
+/// Ids of recent snippet items: the prefix, then the snippet id.
+const RECENT_PREFIX: &str = "recent:";
+/// Ids of profile items: the prefix, then the profile id.
+const PROFILE_PREFIX: &str = "profile:";
+
+/// While the close prompt is up, so a second click doesn't stack another.
+static ASKING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecentAction {
+    #[default]
+    Copy,
+    Open,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClickAction {
+    #[serde(rename = "none")]
+    Nothing,
+    ShowWindow,
+    QuickCapture,
+    PasteLast,
+    ShowMenu,
+}
+
+/// What the main window's close button does.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseAction {
+    /// Not chosen yet; the next close asks.
+    #[default]
+    Ask,
+    HideToTray,
+    Quit,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct TrayConfig {
+    /// Snippets in the "Recent" submenu; 0 hides it.
+    pub recent_count: usize,
+    pub recent_action: RecentAction,
+    pub click: ClickAction,
+    pub double_click: ClickAction,
+    /// Launch without showing the main window, like `--minimized`.
+    pub start_minimized: bool,
+    pub on_close: CloseAction,
+}
+
+impl Default for TrayConfig {
+    fn default() -> Self {
+        Self {
+            recent_count: 5,
+            recent_action: RecentAction::Copy,
+            // What each platform's tray does out of the box.
+            click: if cfg!(windows) {
+                ClickAction::ShowWindow
+            } else {
+                ClickAction::ShowMenu
+            },
+            double_click: ClickAction::ShowWindow,
+            start_minimized: false,
+            on_close: CloseAction::Ask,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TraySettings {
+    #[serde(flatten)]
+    config: TrayConfig,
+    /// False on Linux, whose trays report no clicks.
+    clicks_supported: bool,
+    /// Actions `click` can take here; `double_click` can't open the menu.
+    click_actions: Vec<ClickAction>,
+}
+
+const ACTIONS: [ClickAction; 4] = [
+    ClickAction::Nothing,
+    ClickAction::ShowWindow,
+    ClickAction::QuickCapture,
+    ClickAction::PasteLast,
+];
+
+fn click_actions() -> Vec<ClickAction> {
+    let mut actions = ACTIONS.to_vec();
+    if cfg!(target_os = "macos") {
+        actions.push(ClickAction::ShowMenu);
+    }
+    actions
+}
+
+fn settings(config: TrayConfig) -> TraySettings {
+    TraySettings {
+        config,
+        clicks_supported: !cfg!(target_os = "linux"),
+        click_actions: click_actions(),
+    }
+}
+
+fn menu(
+    recent: &[RecentSnippet],
+    paused: bool,
+    meeting: bool,
+    lockable: bool,
+    profiles: &[Profile],
+) -> SystemTrayMenu {
+    let pause = t(if paused {
+        "tray.resume_capture"
+    } else {
+        "tray.pause_capture"
+    });
+    let meeting = t(if meeting {
+        "tray.meeting_stop"
+    } else {
+        "tray.meeting_start"
+    });
+    let mut menu = SystemTrayMenu::new()
+        .add_item(CustomMenuItem::new("open", t("tray.open")))
+        .add_item(CustomMenuItem::new("new_snippet", t("tray.new_snippet")))
+        .add_item(CustomMenuItem::new("search", t("tray.search")))
+        .add_item(CustomMenuItem::new("meeting", meeting))
+        .add_item(CustomMenuItem::new("pause_capture", pause));
+    if !recent.is_empty() {
+        let items = recent.iter().fold(SystemTrayMenu::new(), |items, snippet| {
+            items.add_item(CustomMenuItem::new(
+                format!("{RECENT_PREFIX}{}", snippet.id),
+                snippet.label(),
+            ))
+        });
+        menu = menu
+            .add_native_item(SystemTrayMenuItem::Separator)
+            .add_submenu(SystemTraySubmenu::new(t("tray.recent"), items));
+    }
+    if profiles.len() > 1 {
+        let items = profiles
+            .iter()
+            .fold(SystemTrayMenu::new(), |items, profile| {
+                let item =
+                    CustomMenuItem::new(format!("{PROFILE_PREFIX}{}", profile.id), &profile.name);
+                items.add_item(if profile.id == profiles::active() {
+                    item.selected()
+                } else {
+                    item
+                })
+            });
+        menu = menu
+            .add_native_item(SystemTrayMenuItem::Separator)
+            .add_submenu(SystemTraySubmenu::new(t("tray.profile"), items));
+    }
+    menu = menu.add_native_item(SystemTrayMenuItem::Separator);
+    if lockable {
+        menu = menu.add_item(CustomMenuItem::new("lock", t("tray.lock")));
+    }
+    menu.add_item(CustomMenuItem::new("quit", t("tray.quit")))
+}
+
+/// The tray as the app starts, before recent snippets are known.
+pub fn build() -> SystemTray {
+    let tray = SystemTray::new().with_menu(menu(
+        &[],
+        false,
+        false,
+        config::load().app_lock.has_passcode,
+        &profiles::list(),
+    ));
+    #[cfg(target_os = "macos")]
+    let tray = tray.with_menu_on_left_click(config::load().tray.click == ClickAction::ShowMenu);
+    tray
+}
+
+/// Rebuilds the menu from the current recent snippets and settings.
+pub fn refresh(app: &AppHandle) {
+    let count = config::current(app).tray.recent_count;
+    let recent: Vec<_> = recent::snippets().into_iter().take(count).collect();
+    let menu = menu(
+        &recent,
+        clipboard::paused(),
+        meeting::active(app),
+        app_lock::lockable(app),
+        &profiles::list(),
+    );
+    if let Err(e) = app.tray_handle().set_menu(menu) {
+        log::warn!("Tray: {}", e);
+    }
+}
+
+/// Shows a snippet in the main window, like a pinup://open link.
+pub fn open_snippet(app: &AppHandle, id: &str) {
+    if let Some(w) = app.get_window("main") {
+        w.show().ok();
+        w.unminimize().ok();
+        w.set_focus().ok();
+        w.emit("deep-link-open", json!({ "id": id })).ok();
+    }
+}
+
+fn recent_clicked(app: &AppHandle, id: &str) {
+    match config::current(app).tray.recent_action {
+        RecentAction::Copy => {
+            let id = id.to_string();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = clipboard::copy_rich(id).await {
+                    log::warn!("Tray: could not copy snippet: {}", e);
+                }
+            });
+        }
+        RecentAction::Open => open_snippet(app, id),
+    }
+}
+
+fn show_window(app: &AppHandle) {
+    if let Some(w) = app.get_window("main") {
+        w.show().ok();
+        w.unminimize().ok();
+        w.set_focus().ok();
+    }
+}
+
+fn close(window: &Window, action: CloseAction) {
+    match action {
+        CloseAction::HideToTray => {
+            window.hide().ok();
+        }
+        CloseAction::Quit => window.app_handle().exit(0),
+        CloseAction::Ask => {}
+    }
+}
+
+/// The main window's close button; call from the window event handler.
+pub fn close_requested(window: &Window, api: &CloseRequestApi) {
+    if window.label() != "main" {
+        return;
+    }
+    api.prevent_close();
+    let app = window.app_handle();
+    let action = config::current(&app).tray.on_close;
+    if action != CloseAction::Ask {
+        close(window, action);
+        return;
+    }
+    if ASKING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let window = window.clone();
+    MessageDialogBuilder::new(t("dialog.close.title"), t("dialog.close.body"))
+        .parent(&window)
+        .buttons(MessageDialogButtons::OkCancelWithLabels(
+            t("dialog.close.keep"),
+            t("dialog.close.quit"),
+        ))
+        .show(move |keep| {
+            ASKING.store(false, Ordering::SeqCst);
+            let action = if keep {
+                CloseAction::HideToTray
+            } else {
+                CloseAction::Quit
+            };
+            if let Err(e) = config::update(&app, |c| c.tray.on_close = action) {
+                log::warn!("Could not save the close action: {}", e);
+            }
+            close(&window, action);
+        });
+}
+
+/// Files the clipboard's text as a snippet.
+async fn quick_capture(app: &AppHandle) -> Result<(), String> {
+    let body = arboard::Clipboard::new()
+        .and_then(|mut c| c.get_text())
+        .map_err(|e| format!("No text on the clipboard: {e}"))?;
+    if body.trim().is_empty() {
+        return Err("No text on the clipboard".into());
+    }
+    let capture = Capture {
+        body,
+        source: "tray".into(),
+        ..Default::default()
+    };
+    capture::submit(app, capture).await.map(|_| ())
+}
+
+/// Pastes the most recent snippet into the frontmost app.
+async fn paste_last(app: &AppHandle) -> Result<(), String> {
+    let recent = recent::snippets();
+    let last = recent.first().ok_or("No recent snippet")?;
+    paste::paste_snippet(app.clone(), last.id.clone()).await
+}
+
+fn clicked(app: &AppHandle, action: ClickAction) {
+    let app = app.clone();
+    if app_lock::locked() && !matches!(action, ClickAction::Nothing | ClickAction::ShowMenu) {
+        app_lock::prompt(&app);
+        return;
+    }
+    match action {
+        // The menu opens by itself (see `build`).
+        ClickAction::Nothing | ClickAction::ShowMenu => {}
+        ClickAction::ShowWindow => show_window(&app),
+        ClickAction::QuickCapture => {
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = quick_capture(&app).await {
+                    log::warn!("Tray: quick capture failed: {}", e);
+                }
+            });
+        }
+        ClickAction::PasteLast => {
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = paste_last(&app).await {
+                    log::warn!("Tray: could not paste the last snippet: {}", e);
+                }
+            });
+        }
+    }
+}
+
+/// Tray and Dock menu items by id.
+pub fn menu_action(app: &AppHandle, id: &str) {
+    if app_lock::locked() && id != "quit" {
+        app_lock::prompt(app);
+        return;
+    }
+    match id {
+        "open" => {
+            if let Some(w) = app.get_window("main") {
+                w.show().ok();
+                w.set_focus().ok();
+            }
+        }
+        "new_snippet" => {
+            if let Some(w) = app.get_window("main") {
+                w.show().ok();
+                w.set_focus().ok();
+                w.emit("tray-new-snippet", ()).ok();
+            }
+        }
+        "search" => {
+            if let Some(w) = app.get_window("main") {
+                w.show().ok();
+                w.set_focus().ok();
+                w.emit("tray-search", ()).ok();
+            }
+        }
+        "meeting" => meeting::toggle_from_tray(app),
+        "pause_capture" => clipboard::toggle_paused(app),
+        "lock" => app_lock::lock(app),
+        "quit" => {
+            app.exit(0);
+        }
+        id => {
+            if let Some(snippet) = id.strip_prefix(RECENT_PREFIX) {
+                recent_clicked(app, snippet);
+            } else if let Some(profile) = id.strip_prefix(PROFILE_PREFIX) {
+                if let Err(e) = profiles::switch(app, profile) {
+                    log::warn!("Tray: {}", e);
+                }
+            }
+        }
+    }
+}
+
+pub fn handle_event(app: &AppHandle, event: SystemTrayEvent) {
+    match event {
+        SystemTrayEvent::LeftClick { .. } => clicked(app, config::current(app).tray.click),
+        SystemTrayEvent::DoubleClick { .. } => clicked(app, config::current(app).tray.double_click),
+        SystemTrayEvent::MenuItemClick { id, .. } => menu_action(app, &id),
+        _ => {}
+    }
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn get_tray_settings(app: AppHandle) -> TraySettings {
+    settings(config::current(&app).tray)
+}
+
+/// Sets how many recent snippets the tray lists and what a click does.
+#[tauri::command]
+pub fn set_tray_recent(
+    app: AppHandle,
+    recent_count: usize,
+    recent_action: RecentAction,
+) -> Result<TraySettings, String> {
+    let updated = config::update(&app, |c| {
+        c.tray.recent_count = recent_count.min(recent::MAX);
+        c.tray.recent_action = recent_action;
+    })?;
+    refresh(&app);
+    Ok(settings(updated.tray))
+}
+
+/// Sets what a click and a double click on the tray icon do.
+#[tauri::command]
+pub fn set_tray_clicks(
+    app: AppHandle,
+    click: ClickAction,
+    double_click: ClickAction,
+) -> Result<TraySettings, String> {
+    if !click_actions().contains(&click) || !ACTIONS.contains(&double_click) {
+        return Err("That click action isn't available on this platform".into());
+    }
+    let updated = config::update(&app, |c| {
+        c.tray.click = click;
+        c.tray.double_click = double_click;
+    })?;
+    Ok(settings(updated.tray))
+}
+
+/// Starts the app in the tray from the next launch on.
+#[tauri::command]
+pub fn set_tray_start_minimized(app: AppHandle, enabled: bool) -> Result<TraySettings, String> {
+    let updated = config::update(&app, |c| c.tray.start_minimized = enabled)?;
+    Ok(settings(updated.tray))
+}
+
+/// Sets what the main window's close button does; "ask" asks again.
+#[tauri::command]
+pub fn set_close_action(app: AppHandle, action: CloseAction) -> Result<TraySettings, String> {
+    let updated = config::update(&app, |c| c.tray.on_close = action)?;
+    Ok(settings(updated.tray))
+}
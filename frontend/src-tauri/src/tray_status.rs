@@ -0,0 +1,276 @@
+// Tray status — the tray icon shows what the backend is doing.
+//
+// health.rs reports each step of the backend's lifecycle here: spawned,
+// health check passed or failed, crashed. The icon is greyed out while the
+// backend starts, normal once it answers, and carries a red badge after it
+// crashes or stops answering (the tooltip says why). While an AI job runs in
+// the shell (transcription, OCR, a model pull; see jobs.rs) a healthy icon
+// gets a blue activity dot. The variants are drawn on a base that suits
+// the platform (see `glyph`): on macOS the normal icon is a pushpin template
+// image the menu bar tints itself, and the others a light or dark pushpin
+// for the menu bar's appearance, so the badge and dot keep their colours; on
+// Windows a pushpin for the taskbar's light or dark setting; on Linux, where
+// the panel's colour can't be known, the bundled full-colour icon. Theme
+// changes (see theme.rs) redraw it.
+//
+// The tooltip spells it out from the same events: backend state and port,
+// running background jobs (see jobs.rs) and when a sync last succeeded (Git,
+// WebDAV, S3 or LAN; see `synced`). Linux trays show no tooltips. Items awaiting review add a count badge (see badge.rs).
+
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Local};
+use image::{Rgba, RgbaImage};
+use once_cell::sync::{Lazy, OnceCell};
+use tauri::{AppHandle, Icon};
+
+use crate::i18n::{t, tf};
+use crate::theme::{self, SystemTheme};
+use crate::{badge, jobs};
+
+const ICON: &[u8] = include_bytes!("../icons/icon.png");
+/// Side of the drawn pushpin glyphs.
+const GLYPH_SIZE: u32 = 64;
+/// Template images use only the alpha channel.
+const TEMPLATE_COLOR: Rgba<u8> = Rgba([0, 0, 0, 255]);
+/// For dark menu bars and taskbars.
+const LIGHT_COLOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+/// For light ones.
+const DARK_COLOR: Rgba<u8> = Rgba([31, 41, 55, 255]);
+const ERROR_COLOR: Rgba<u8> = Rgba([220, 38, 38, 255]);
+const ACTIVITY_COLOR: Rgba<u8> = Rgba([37, 99, 235, 255]);
+/// Windows cuts tooltips off at 127 characters.
+const MAX_REASON: usize = 50;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Backend {
+    Starting,
+    Healthy,
+    Down(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Look {
+    Starting,
+    Healthy,
+    Error,
+    Busy,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Glyph {
+    Color,
+    Template,
+    Light,
+    Dark,
+}
+
+static APP: OnceCell<AppHandle> = OnceCell::new();
+static BACKEND: Mutex<Backend> = Mutex::new(Backend::Starting);
+static LAST_SYNC: Mutex<Option<DateTime<Local>>> = Mutex::new(None);
+/// Look, badge count and glyph.
+type Drawn = (Look, u32, Glyph);
+/// What the tray shows now (icon, tooltip); unchanged states are not
+/// redrawn.
+static SHOWN: Mutex<(Option<Drawn>, String)> = Mutex::new((None, String::new()));
+
+static BASE: Lazy<Option<RgbaImage>> = Lazy::new(|| {
+    image::load_from_memory_with_format(ICON, image::ImageFormat::Png)
+        .map(|icon| icon.to_rgba8())
+        .map_err(|e| log::warn!("Tray status: could not decode the icon: {}", e))
+        .ok()
+});
+
+/// A sync finished without errors.
+pub fn synced() {
+    *LAST_SYNC.lock().unwrap() = Some(Local::now());
+    refresh();
+}
+
+// ── Lifecycle ──────────────────────────────────────────────────────────────
+/// A backend was spawned (or an external one chosen).
+pub fn starting() {
+    set(Backend::Starting);
+}
+
+/// A health check passed.
+pub fn healthy() {
+    set(Backend::Healthy);
+}
+
+/// The backend crashed or failed a health check.
+pub fn down(reason: &str) {
+    set(Backend::Down(reason.trim().to_string()));
+}
+
+fn set(state: Backend) {
+    *BACKEND.lock().unwrap() = state;
+    refresh();
+}
+
+// ── Icon ───────────────────────────────────────────────────────────────────
+fn look() -> Look {
+    match &*BACKEND.lock().unwrap() {
+        Backend::Starting => Look::Starting,
+        Backend::Down(_) => Look::Error,
+        Backend::Healthy if jobs::running().1 > 0 => Look::Busy,
+        Backend::Healthy => Look::Healthy,
+    }
+}
+
+/// A pushpin (round head with a highlight hole, collar, needle) in `color`,
+/// antialiased by 4×4 supersampling.
+fn pushpin(color: Rgba<u8>) -> RgbaImage {
+    const SAMPLES: u32 = 4;
+    let unit = GLYPH_SIZE as f32 / 32.0;
+    let covered = |x: f32, y: f32| {
+        let (x, y) = (x / unit, y / unit);
+        let head = (x - 16.0).hypot(y - 10.5) <= 7.5;
+        let collar = (13.5..=18.0).contains(&y) && (x - 16.0).abs() <= 5.5 - (y - 13.5) * 0.45;
+        let needle = (18.0..=29.5).contains(&y) && (x - 16.0).abs() <= 1.25;
+        let hole = (x - 13.5).hypot(y - 8.5) <= 2.0;
+        (head || collar || needle) && !hole
+    };
+    RgbaImage::from_fn(GLYPH_SIZE, GLYPH_SIZE, |px, py| {
+        let mut hits = 0;
+        for sy in 0..SAMPLES {
+            for sx in 0..SAMPLES {
+                let x = px as f32 + (sx as f32 + 0.5) / SAMPLES as f32;
+                let y = py as f32 + (sy as f32 + 0.5) / SAMPLES as f32;
+                hits += covered(x, y) as u32;
+            }
+        }
+        let [r, g, b, a] = color.0;
+        Rgba([r, g, b, (a as u32 * hits / (SAMPLES * SAMPLES)) as u8])
+    })
+}
+
+/// The icon to draw `look` on, by platform convention.
+fn glyph(look: Look, count: u32) -> Glyph {
+    if cfg!(target_os = "linux") {
+        return Glyph::Color;
+    }
+    if cfg!(target_os = "macos") && look == Look::Healthy && count == 0 {
+        return Glyph::Template;
+    }
+    match theme::taskbar() {
+        SystemTheme::Dark => Glyph::Light,
+        SystemTheme::Light => Glyph::Dark,
+    }
+}
+
+fn tooltip() -> String {
+    let port = crate::BACKEND_PORT.load(Ordering::SeqCst);
+    let backend = match &*BACKEND.lock().unwrap() {
+        Backend::Starting => tf("tooltip.backend_starting", &[("port", &port)]),
+        Backend::Healthy => tf("tooltip.backend_running", &[("port", &port)]),
+        Backend::Down(reason) if reason.is_empty() => t("tooltip.backend_stopped"),
+        Backend::Down(reason) => {
+            let reason = match reason.char_indices().nth(MAX_REASON) {
+                Some((end, _)) => format!("{}…", &reason[..end]),
+                None => reason.clone(),
+            };
+            tf("tooltip.backend_stopped_because", &[("reason", &reason)])
+        }
+    };
+    let jobs = match jobs::running().0 {
+        0 => t("tooltip.jobs_none"),
+        1 => t("tooltip.jobs_one"),
+        n => tf("tooltip.jobs_many", &[("count", &n)]),
+    };
+    let sync = match *LAST_SYNC.lock().unwrap() {
+        Some(at) if at.date_naive() == Local::now().date_naive() => {
+            tf("tooltip.last_sync", &[("time", &at.format("%H:%M"))])
+        }
+        Some(at) => tf(
+            "tooltip.last_sync",
+            &[("time", &at.format("%b %-d, %H:%M"))],
+        ),
+        None => t("tooltip.not_synced"),
+    };
+    format!("Pin-Up AI\n{backend}\n{jobs}\n{sync}")
+}
+
+/// Faded grey, for a backend that isn't up yet.
+fn grey(image: &mut RgbaImage) {
+    for pixel in image.pixels_mut() {
+        let [r, g, b, a] = pixel.0;
+        let luma = ((r as u32 * 30 + g as u32 * 59 + b as u32 * 11) / 100) as u8;
+        *pixel = Rgba([luma, luma, luma, a / 2]);
+    }
+}
+
+/// A dot in the bottom-right corner, `size` of the icon across, cut out
+/// from the icon by a transparent ring.
+fn dot(image: &mut RgbaImage, size: f32, color: Rgba<u8>) {
+    let side = image.width().min(image.height()) as f32;
+    let radius = side * size / 2.0;
+    let ring = (side * 0.06).max(1.0);
+    let cx = image.width() as f32 - radius - ring;
+    let cy = image.height() as f32 - radius - ring;
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        let distance = (x as f32 + 0.5 - cx).hypot(y as f32 + 0.5 - cy);
+        if distance <= radius {
+            *pixel = color;
+        } else if distance <= radius + ring {
+            *pixel = Rgba([0, 0, 0, 0]);
+        }
+    }
+}
+
+fn icon(look: Look, count: u32, glyph: Glyph) -> Option<Icon> {
+    let mut image = match glyph {
+        Glyph::Color => BASE.clone()?,
+        Glyph::Template => pushpin(TEMPLATE_COLOR),
+        Glyph::Light => pushpin(LIGHT_COLOR),
+        Glyph::Dark => pushpin(DARK_COLOR),
+    };
+    match look {
+        Look::Starting => grey(&mut image),
+        Look::Healthy => {}
+        Look::Error => dot(&mut image, 0.5, ERROR_COLOR),
+        Look::Busy => dot(&mut image, 0.34, ACTIVITY_COLOR),
+    }
+    if count > 0 {
+        let size = image.width().min(image.height()) * 9 / 16;
+        badge::stamp(&mut image, count, size);
+    }
+    Some(Icon::Rgba {
+        width: image.width(),
+        height: image.height(),
+        rgba: image.into_raw(),
+    })
+}
+
+/// Redraws the icon and tooltip if anything shown has changed.
+pub fn refresh() {
+    let Some(app) = APP.get() else {
+        return;
+    };
+    // Held until the tray is updated, so concurrent changes land in order.
+    let mut shown = SHOWN.lock().unwrap();
+    let (look, count, tooltip) = (look(), badge::count(), tooltip());
+    let glyph = glyph(look, count);
+    let tray = app.tray_handle();
+    if shown.0 != Some((look, count, glyph)) {
+        if let Some(icon) = icon(look, count, glyph) {
+            if let Err(e) = tray.set_icon(icon) {
+                log::warn!("Tray status: {}", e);
+            }
+        }
+        #[cfg(target_os = "macos")]
+        tray.set_icon_as_template(glyph == Glyph::Template).ok();
+    }
+    if shown.1 != tooltip {
+        tray.set_tooltip(&tooltip).ok();
+    }
+    *shown = (Some((look, count, glyph)), tooltip);
+}
+
+/// Shows the state reached before the tray existed.
+pub fn start(app: &AppHandle) {
+    if APP.set(app.clone()).is_ok() {
+        refresh();
+    }
+}
@@ -0,0 +1,246 @@
+// Unfurl — title, description, favicon and preview image of a link.
+//
+// For pasted links to show as cards without the webview reaching out to
+// other sites. Only the start of the page is read, up to the end of its
+// <head> or PAGE_LIMIT, with OpenGraph and Twitter tags preferred over plain
+// <title> and <meta name="description">. The favicon and preview image are
+// downloaded under their own size limits and handed over as data URLs, so
+// the card renders offline. Results are cached per URL in
+// app_dir()/cache/unfurl for CACHE_TTL; a failed fetch falls back to a
+// stale entry when there is one.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use base64::Engine;
+use kuchikiki::traits::*;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+
+use crate::{clipper, proxy};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const CACHE_TTL: Duration = Duration::from_secs(7 * 24 * 3600);
+const PAGE_LIMIT: usize = 1024 * 1024;
+const IMAGE_LIMIT: usize = 2 * 1024 * 1024;
+const FAVICON_LIMIT: usize = 256 * 1024;
+const USER_AGENT: &str = "Mozilla/5.0 (compatible; Pin-Up AI link preview)";
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Unfurl {
+    url: String,
+    /// Where redirects ended.
+    final_url: String,
+    title: Option<String>,
+    description: Option<String>,
+    site_name: Option<String>,
+    /// Data URLs.
+    favicon: Option<String>,
+    image: Option<String>,
+    /// Unix seconds.
+    fetched_at: i64,
+}
+
+fn cache_path(url: &str) -> PathBuf {
+    let key = hex::encode(&Sha256::digest(url.as_bytes())[..16]);
+    crate::app_dir()
+        .join("cache")
+        .join("unfurl")
+        .join(format!("{key}.json"))
+}
+
+fn read_cache(url: &str) -> Option<Unfurl> {
+    let text = std::fs::read_to_string(cache_path(url)).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+fn write_cache(unfurl: &Unfurl) {
+    let path = cache_path(&unfurl.url);
+    std::fs::create_dir_all(path.parent().unwrap()).ok();
+    if let Ok(text) = serde_json::to_string(unfurl) {
+        std::fs::write(&path, text).ok();
+    }
+}
+
+/// Up to `limit` bytes of `url`, its final URL and content type. With
+/// `until`, reading stops once that text has arrived.
+async fn read(
+    client: &reqwest::Client,
+    url: &str,
+    limit: usize,
+    until: Option<&str>,
+) -> Result<(Vec<u8>, Url, String), String> {
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Could not fetch {url}: {e}"))?;
+    let final_url = response.url().clone();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_lowercase();
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Could not fetch {url}: {e}"))?
+    {
+        bytes.extend_from_slice(&chunk);
+        let done = until.is_some_and(|marker| {
+            let tail = bytes.len().saturating_sub(chunk.len() + marker.len());
+            String::from_utf8_lossy(&bytes[tail..])
+                .to_lowercase()
+                .contains(marker)
+        });
+        if done || bytes.len() >= limit {
+            break;
+        }
+    }
+    bytes.truncate(limit);
+    Ok((bytes, final_url, content_type))
+}
+
+/// An image as a data URL; None when it isn't one or is too big.
+async fn image(client: &reqwest::Client, url: &str, limit: usize) -> Option<String> {
+    let (bytes, _, content_type) = read(client, url, limit + 1, None).await.ok()?;
+    let mime = content_type.split(';').next().unwrap_or_default().trim();
+    if bytes.len() > limit || !mime.starts_with("image/") {
+        return None;
+    }
+    let data = base64::engine::general_purpose::STANDARD.encode(bytes);
+    Some(format!("data:{mime};base64,{data}"))
+}
+
+/// The page's <head> tags, with links resolved against `base`.
+struct Head {
+    title: Option<String>,
+    description: Option<String>,
+    site_name: Option<String>,
+    favicon: Option<Url>,
+    image: Option<Url>,
+}
+
+fn parse_head(html: &str, base: &Url) -> Head {
+    let document = kuchikiki::parse_html().one(html);
+    let meta = |selectors: &[&str]| {
+        selectors
+            .iter()
+            .find_map(|selector| clipper::meta(&document, selector))
+    };
+    let description = meta(&[
+        r#"meta[property="og:description"]"#,
+        r#"meta[name="twitter:description"]"#,
+        r#"meta[name="description"]"#,
+    ]);
+    let image = meta(&[
+        r#"meta[property="og:image:secure_url"]"#,
+        r#"meta[property="og:image"]"#,
+        r#"meta[name="twitter:image"]"#,
+    ]);
+    // Largest declared icon wins, Apple touch icons included.
+    let favicon = document
+        .select("link[rel][href]")
+        .into_iter()
+        .flatten()
+        .filter_map(|link| {
+            let node = link.as_node();
+            let rel = clipper::attr(node, "rel")?.to_lowercase();
+            if !rel
+                .split_whitespace()
+                .any(|r| r == "icon" || r == "apple-touch-icon")
+            {
+                return None;
+            }
+            let size = clipper::attr(node, "sizes")
+                .and_then(|s| s.split(['x', 'X']).next()?.parse::<u32>().ok())
+                .unwrap_or(16);
+            Some((size, clipper::attr(node, "href")?))
+        })
+        .max_by_key(|(size, _)| *size)
+        .map(|(_, href)| href);
+    Head {
+        title: clipper::title(&document),
+        description,
+        site_name: meta(&[r#"meta[property="og:site_name"]"#]),
+        favicon: favicon
+            .and_then(|href| base.join(&href).ok())
+            .or_else(|| base.join("/favicon.ico").ok()),
+        image: image.and_then(|href| base.join(&href).ok()),
+    }
+}
+
+async fn fetch(app: &AppHandle, url: &Url) -> Result<Unfurl, String> {
+    let client = proxy::client_builder(app)
+        .timeout(TIMEOUT)
+        .user_agent(USER_AGENT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let (bytes, final_url, content_type) =
+        read(&client, url.as_str(), PAGE_LIMIT, Some("</head>")).await?;
+    let mut unfurl = Unfurl {
+        url: url.to_string(),
+        final_url: final_url.to_string(),
+        fetched_at: chrono::Utc::now().timestamp(),
+        ..Default::default()
+    };
+    if !content_type.is_empty() && !content_type.contains("html") {
+        // A file: its name is the best title there is.
+        unfurl.title = final_url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .filter(|name| !name.is_empty())
+            .map(str::to_string);
+        if content_type.starts_with("image/") {
+            unfurl.image = image(&client, final_url.as_str(), IMAGE_LIMIT).await;
+        }
+        return Ok(unfurl);
+    }
+
+    let head = parse_head(&clipper::decode(&bytes, &content_type), &final_url);
+    let limit = |text: Option<String>, max: usize| {
+        text.map(|t| match t.char_indices().nth(max) {
+            Some((at, _)) => format!("{}…", t[..at].trim_end()),
+            None => t,
+        })
+    };
+    unfurl.title = limit(head.title, 200);
+    unfurl.description = limit(head.description, 500);
+    unfurl.site_name = limit(head.site_name, 100);
+    if let Some(favicon) = head.favicon {
+        unfurl.favicon = image(&client, favicon.as_str(), FAVICON_LIMIT).await;
+    }
+    if let Some(preview) = head.image {
+        unfurl.image = image(&client, preview.as_str(), IMAGE_LIMIT).await;
+    }
+    Ok(unfurl)
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+/// Card details for `url`, from the cache while fresh.
+#[tauri::command]
+pub async fn unfurl_url(app: AppHandle, url: String) -> Result<Unfurl, String> {
+    let parsed = Url::parse(url.trim()).map_err(|e| format!("Invalid URL: {e}"))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("Only web links can be previewed".into());
+    }
+    let cached = read_cache(parsed.as_str());
+    let now = chrono::Utc::now().timestamp();
+    if let Some(cached) = &cached {
+        if now - cached.fetched_at < CACHE_TTL.as_secs() as i64 {
+            return Ok(cached.clone());
+        }
+    }
+    match fetch(&app, &parsed).await {
+        Ok(unfurl) => {
+            write_cache(&unfurl);
+            Ok(unfurl)
+        }
+        Err(e) => cached.ok_or(e),
+    }
+}
@@ -0,0 +1,320 @@
+// Updater — release channels and a deferrable install on top of the Tauri
+// updater.
+//
+// Channels:
+//   stable  the endpoints in tauri.conf.json (latest GitHub release)
+//   beta    the manifest attached to the rolling "beta" release
+// Every `updater.check_interval_hours` (and on `check_for_updates`) the
+// channel's manifest is fetched; a newer version emits "update-available".
+// Packages must be signed: checks are refused while tauri.conf.json has no
+// updater pubkey, and the Tauri updater verifies each download against it.
+//
+// With `updater.auto_download`, macOS and Linux download and swap the bundle
+// in the background — the running app is untouched until it restarts — and
+// then emit "update-ready". The Windows installer needs the app to exit, so
+// there "update-ready" (with `requires_exit`) is sent straight away and the
+// download happens at install time. `install_update(now = false)` defers to
+// the next launch; Tauri's own "tauri://update-download-progress" events
+// report download progress.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::updater::UpdateResponse;
+use tauri::{AppHandle, Manager, Wry};
+
+use crate::{backups, config};
+
+const BETA_ENDPOINT: &str =
+    "https://github.com/holeyfield33-art/pin-up-ai/releases/download/beta/latest.json";
+/// Delay before the first check so it doesn't compete with startup.
+const FIRST_CHECK_DELAY: Duration = Duration::from_secs(60);
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct UpdaterConfig {
+    pub channel: UpdateChannel,
+    pub auto_check: bool,
+    pub auto_download: bool,
+    pub check_interval_hours: u64,
+    /// Version the user chose to install on next launch.
+    pub pending: Option<String>,
+}
+
+impl Default for UpdaterConfig {
+    fn default() -> Self {
+        Self {
+            channel: UpdateChannel::Stable,
+            auto_check: true,
+            auto_download: true,
+            check_interval_hours: 6,
+            pending: None,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum UpdateState {
+    #[default]
+    Idle,
+    Checking,
+    Available,
+    Downloading,
+    Ready,
+    Error,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct UpdateStatus {
+    state: UpdateState,
+    channel: UpdateChannel,
+    current_version: String,
+    version: Option<String>,
+    notes: Option<String>,
+    date: Option<String>,
+    /// True when installing closes the app (Windows installer).
+    requires_exit: bool,
+    error: Option<String>,
+}
+
+static STATUS: Mutex<Option<UpdateStatus>> = Mutex::new(None);
+/// The last check's result, kept for download and install.
+static UPDATE: Mutex<Option<UpdateResponse<Wry>>> = Mutex::new(None);
+
+fn status(app: &AppHandle) -> UpdateStatus {
+    STATUS
+        .lock()
+        .unwrap()
+        .clone()
+        .unwrap_or_else(|| UpdateStatus {
+            channel: config::current(app).updater.channel,
+            current_version: app.package_info().version.to_string(),
+            requires_exit: cfg!(windows),
+            ..Default::default()
+        })
+}
+
+fn set_status(app: &AppHandle, change: impl FnOnce(&mut UpdateStatus)) -> UpdateStatus {
+    let mut next = status(app);
+    change(&mut next);
+    *STATUS.lock().unwrap() = Some(next.clone());
+    next
+}
+
+fn endpoints(app: &AppHandle, channel: UpdateChannel) -> Vec<String> {
+    match channel {
+        UpdateChannel::Stable => app
+            .config()
+            .tauri
+            .updater
+            .endpoints
+            .iter()
+            .flatten()
+            .map(|url| url.to_string())
+            .collect(),
+        UpdateChannel::Beta => vec![BETA_ENDPOINT.to_string()],
+    }
+}
+
+/// Fetches the channel manifest; emits "update-available" for a new version.
+async fn check(app: &AppHandle) -> Result<UpdateStatus, String> {
+    if app.config().tauri.updater.pubkey.trim().is_empty() {
+        return Err("Updater public key is not configured; refusing unsigned updates".into());
+    }
+    let channel = config::current(app).updater.channel;
+    let before = status(app);
+    set_status(app, |s| {
+        s.state = UpdateState::Checking;
+        s.channel = channel;
+        s.error = None;
+    });
+
+    let result = tauri::updater::builder(app.clone())
+        .skip_events()
+        .endpoints(&endpoints(app, channel))
+        .check()
+        .await;
+    let update = match result {
+        Ok(update) if update.is_update_available() => update,
+        Ok(_) | Err(tauri::updater::Error::UpToDate) => {
+            *UPDATE.lock().unwrap() = None;
+            return Ok(set_status(app, |s| {
+                s.state = UpdateState::Idle;
+                s.version = None;
+                s.notes = None;
+                s.date = None;
+            }));
+        }
+        Err(e) => {
+            let message = format!("Update check failed: {e}");
+            set_status(app, |s| {
+                s.state = UpdateState::Error;
+                s.error = Some(message.clone());
+            });
+            return Err(message);
+        }
+    };
+
+    let version = update.latest_version().to_string();
+    // Already downloaded (or downloading); keep that state.
+    if before.version.as_deref() == Some(version.as_str())
+        && matches!(before.state, UpdateState::Ready | UpdateState::Downloading)
+    {
+        return Ok(set_status(app, |s| s.state = before.state));
+    }
+    let next = set_status(app, |s| {
+        s.state = UpdateState::Available;
+        s.version = Some(version.clone());
+        s.notes = update.body().cloned();
+        s.date = update.date().map(|d| d.to_string());
+    });
+    *UPDATE.lock().unwrap() = Some(update);
+    log::info!("Update {} available on the {:?} channel", version, channel);
+    app.emit_all("update-available", &next).ok();
+    Ok(next)
+}
+
+/// Installs the checked update in place (macOS/Linux) and emits "update-ready".
+/// On Windows this only announces it, since installing ends the process.
+async fn prepare(app: &AppHandle) -> Result<UpdateStatus, String> {
+    if cfg!(windows) {
+        let next = set_status(app, |s| s.state = UpdateState::Ready);
+        app.emit_all("update-ready", &next).ok();
+        return Ok(next);
+    }
+    let Some(update) = UPDATE.lock().unwrap().take() else {
+        return Err("No update to download".into());
+    };
+    set_status(app, |s| s.state = UpdateState::Downloading);
+    backups::before_update(app, &format!("pre-update-{}", update.latest_version())).await;
+    if let Err(e) = update.download_and_install().await {
+        let message = format!("Update download failed: {e}");
+        set_status(app, |s| {
+            s.state = UpdateState::Error;
+            s.error = Some(message.clone());
+        });
+        return Err(message);
+    }
+    let next = set_status(app, |s| s.state = UpdateState::Ready);
+    log::info!("Update {:?} ready after restart", next.version);
+    app.emit_all("update-ready", &next).ok();
+    Ok(next)
+}
+
+/// Runs the Windows installer for a deferred update (the process exits).
+async fn install_pending(app: &AppHandle) -> Result<(), String> {
+    let Some(update) = UPDATE.lock().unwrap().take() else {
+        return Err("No update to install".into());
+    };
+    backups::before_update(app, &format!("pre-update-{}", update.latest_version())).await;
+    update
+        .download_and_install()
+        .await
+        .map_err(|e| format!("Update install failed: {e}"))
+}
+
+/// Finishes a deferred install, then checks on the configured interval.
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let pending = config::current(&handle).updater.pending;
+        if let Some(version) = pending {
+            if version == handle.package_info().version.to_string() || !cfg!(windows) {
+                // Already applied in place, or the running version is the update.
+                config::update(&handle, |c| c.updater.pending = None).ok();
+            } else if let Err(e) = check(&handle).await {
+                log::warn!("{}", e);
+            } else {
+                config::update(&handle, |c| c.updater.pending = None).ok();
+                if let Err(e) = install_pending(&handle).await {
+                    log::error!("{}", e);
+                }
+            }
+        }
+
+        tokio::time::sleep(FIRST_CHECK_DELAY).await;
+        loop {
+            let cfg = config::current(&handle).updater;
+            if cfg.auto_check {
+                match check(&handle).await {
+                    Ok(s) if s.state == UpdateState::Available && cfg.auto_download => {
+                        if let Err(e) = prepare(&handle).await {
+                            log::warn!("{}", e);
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::warn!("{}", e),
+                }
+            }
+            let hours = cfg.check_interval_hours.max(1);
+            tokio::time::sleep(Duration::from_secs(hours * 3600)).await;
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn get_update_status(app: AppHandle) -> UpdateStatus {
+    status(&app)
+}
+
+#[tauri::command]
+pub async fn check_for_updates(app: AppHandle) -> Result<UpdateStatus, String> {
+    check(&app).await
+}
+
+/// Switches channel and checks it right away.
+#[tauri::command]
+pub async fn set_update_channel(
+    app: AppHandle,
+    channel: UpdateChannel,
+) -> Result<UpdateStatus, String> {
+    config::update(&app, |c| c.updater.channel = channel)?;
+    *UPDATE.lock().unwrap() = None;
+    set_status(&app, |s| {
+        s.channel = channel;
+        s.state = UpdateState::Idle;
+        s.version = None;
+    });
+    check(&app).await
+}
+
+/// Downloads the available update without waiting for the background check.
+#[tauri::command]
+pub async fn download_update(app: AppHandle) -> Result<UpdateStatus, String> {
+    prepare(&app).await
+}
+
+/// Applies a ready update now (restart, or the installer on Windows) or
+/// defers it to the next launch.
+#[tauri::command]
+pub async fn install_update(app: AppHandle, now: bool) -> Result<(), String> {
+    let current = status(&app);
+    let Some(version) = current.version.clone() else {
+        return Err("No update available".into());
+    };
+    if current.state != UpdateState::Ready {
+        prepare(&app).await?;
+    }
+    if !now {
+        log::info!("Update {} deferred to next launch", version);
+        config::update(&app, |c| c.updater.pending = Some(version))?;
+        return Ok(());
+    }
+    if cfg!(windows) {
+        install_pending(&app).await
+    } else {
+        app.restart();
+        Ok(())
+    }
+}
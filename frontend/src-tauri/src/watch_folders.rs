@@ -0,0 +1,354 @@
+// Watch folders — imports files that appear in folders the user picks.
+//
+// Each entry in the `watch_folders` config section names a folder (a
+// screenshots directory, a "drop here" folder, ...) with its own rules:
+// which extensions to pick up (empty: every kind import.rs understands), a
+// tag for the snippets made from them, whether subfolders count, and whether
+// each file is deleted once imported. One notify watcher covers them all;
+// the list is re-read every CONFIG_CHECK, so changes apply without a restart.
+//
+// A file is imported once it has gone SETTLE without events, so screenshots
+// and downloads still being written are left alone, as are hidden files and
+// partial downloads. Settled files go through the drag-and-drop path
+// (import.rs) as an "import_watched" task, duplicate policy included. A
+// folder with delete_after_import is also swept when watching starts:
+// whatever is still in it hasn't been imported yet.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant};
+
+use notify::event::ModifyKind;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::import::{self, FileKind, ImportRules};
+use crate::{config, suspend, tasks};
+
+const SETTLE: Duration = Duration::from_secs(2);
+const CONFIG_CHECK: Duration = Duration::from_secs(5);
+const POLL: Duration = Duration::from_millis(500);
+/// Files other apps are still writing.
+const PARTIAL_EXTENSIONS: [&str; 5] = ["tmp", "part", "crdownload", "download", "partial"];
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct WatchFolder {
+    pub path: String,
+    pub enabled: bool,
+    /// Lowercase, without the dot; empty picks up every supported file.
+    pub extensions: Vec<String>,
+    /// Added to snippets made from Markdown, image and PDF files.
+    pub tag: Option<String>,
+    pub recursive: bool,
+    pub delete_after_import: bool,
+}
+
+impl Default for WatchFolder {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            enabled: true,
+            extensions: Vec::new(),
+            tag: None,
+            recursive: false,
+            delete_after_import: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct WatchFoldersConfig {
+    pub folders: Vec<WatchFolder>,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+struct FolderState {
+    watching: bool,
+    imported: usize,
+    /// RFC 3339.
+    last_import: Option<String>,
+    last_error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct WatchFolderStatus {
+    #[serde(flatten)]
+    folder: WatchFolder,
+    #[serde(flatten)]
+    state: FolderState,
+}
+
+/// Keyed by the configured path.
+static STATE: Lazy<Mutex<HashMap<String, FolderState>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// A folder being watched, by its canonical path (what events report).
+struct Root {
+    dir: PathBuf,
+    folder: WatchFolder,
+}
+
+fn lower_ext(path: &Path) -> String {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Index of the root `path` belongs to; the deepest one when roots nest.
+fn owner(roots: &[Root], path: &Path) -> Option<usize> {
+    roots
+        .iter()
+        .enumerate()
+        .filter(|(_, root)| {
+            if root.folder.recursive {
+                path.starts_with(&root.dir) && path != root.dir
+            } else {
+                path.parent() == Some(root.dir.as_path())
+            }
+        })
+        .max_by_key(|(_, root)| root.dir.components().count())
+        .map(|(index, _)| index)
+}
+
+fn wanted(folder: &WatchFolder, path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let ext = lower_ext(path);
+    if name.starts_with('.') || name.starts_with('~') || PARTIAL_EXTENSIONS.contains(&ext.as_str())
+    {
+        return false;
+    }
+    if !folder.extensions.is_empty() && !folder.extensions.contains(&ext) {
+        return false;
+    }
+    path.is_file() && import::detect(path) != FileKind::Unsupported
+}
+
+fn is_arrival(kind: &EventKind) -> bool {
+    matches!(
+        kind,
+        EventKind::Create(_)
+            | EventKind::Modify(ModifyKind::Data(_) | ModifyKind::Name(_) | ModifyKind::Any)
+    )
+}
+
+/// Files already in `dir` (and below when recursive).
+fn existing_files(dir: &Path, recursive: bool, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if recursive {
+                existing_files(&path, recursive, out);
+            }
+        } else {
+            out.push(path);
+        }
+    }
+}
+
+/// Swaps the watched folders for `folders`; returns the new roots.
+fn rewatch(
+    watcher: &mut RecommendedWatcher,
+    old: &[Root],
+    folders: &[WatchFolder],
+    pending: &mut HashMap<PathBuf, Instant>,
+) -> Vec<Root> {
+    for root in old {
+        watcher.unwatch(&root.dir).ok();
+    }
+    let mut states = STATE.lock().unwrap();
+    states.retain(|path, _| folders.iter().any(|f| &f.path == path));
+    let mut roots = Vec::new();
+    for folder in folders {
+        let state = states.entry(folder.path.clone()).or_default();
+        let dir =
+            std::fs::canonicalize(&folder.path).unwrap_or_else(|_| folder.path.clone().into());
+        let mode = if folder.recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        if let Err(e) = watcher.watch(&dir, mode) {
+            log::warn!("Cannot watch {:?}: {}", dir, e);
+            state.watching = false;
+            state.last_error = Some(format!("Cannot watch this folder: {e}"));
+            continue;
+        }
+        state.watching = true;
+        let was_watched = old.iter().any(|r| r.dir == dir);
+        if folder.delete_after_import && !was_watched {
+            let mut files = Vec::new();
+            existing_files(&dir, folder.recursive, &mut files);
+            for file in files {
+                pending.insert(file, Instant::now());
+            }
+        }
+        roots.push(Root {
+            dir,
+            folder: folder.clone(),
+        });
+    }
+    log::info!("Watching {} folders for imports", roots.len());
+    roots
+}
+
+fn spawn_import(app: &AppHandle, folder: &WatchFolder, paths: Vec<PathBuf>) {
+    let rules = ImportRules {
+        tag: folder.tag.clone().filter(|t| !t.trim().is_empty()),
+        delete_after_import: folder.delete_after_import,
+    };
+    let key = folder.path.clone();
+    let handle = app.clone();
+    log::info!("Watch folder {}: importing {} files", key, paths.len());
+    tasks::spawn(app, "import_watched", move |task| async move {
+        let result = import::import_paths(handle, task, paths, rules).await;
+        let mut states = STATE.lock().unwrap();
+        let state = states.entry(key).or_default();
+        match &result {
+            Ok(summary) => {
+                state.imported += summary.imported();
+                state.last_import = Some(chrono::Local::now().to_rfc3339());
+                state.last_error = match summary.failed() {
+                    0 => None,
+                    n => Some(format!("{n} files could not be imported")),
+                };
+            }
+            Err(e) => state.last_error = Some(e.clone()),
+        }
+        result
+    });
+}
+
+fn run(app: AppHandle) {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = match notify::recommended_watcher(tx) {
+        Ok(w) => w,
+        Err(e) => {
+            log::error!("Cannot watch folders for imports: {}", e);
+            return;
+        }
+    };
+    let mut folders: Vec<WatchFolder> = Vec::new();
+    let mut roots: Vec<Root> = Vec::new();
+    let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+    let mut checked: Option<Instant> = None;
+    loop {
+        if checked.map_or(true, |at| at.elapsed() >= CONFIG_CHECK) {
+            checked = Some(Instant::now());
+            let current: Vec<WatchFolder> = config::current(&app)
+                .watch_folders
+                .folders
+                .into_iter()
+                .filter(|f| f.enabled)
+                .collect();
+            if current != folders {
+                roots = rewatch(&mut watcher, &roots, &current, &mut pending);
+                pending.retain(|path, _| owner(&roots, path).is_some());
+                folders = current;
+            }
+        }
+
+        match rx.recv_timeout(POLL) {
+            Ok(Ok(event)) if is_arrival(&event.kind) => {
+                for path in event.paths {
+                    if owner(&roots, &path).is_some() {
+                        pending.insert(path, Instant::now());
+                    }
+                }
+            }
+            Ok(Ok(_)) | Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Ok(Err(e)) => log::warn!("Watch folders: {}", e),
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+
+        if pending.is_empty()
+            || suspend::paused()
+            || crate::BACKEND_PORT.load(Ordering::SeqCst) == 0
+        {
+            continue;
+        }
+        let settled: Vec<PathBuf> = pending
+            .iter()
+            .filter(|(_, at)| at.elapsed() >= SETTLE)
+            .map(|(path, _)| path.clone())
+            .collect();
+        let mut batches: HashMap<usize, Vec<PathBuf>> = HashMap::new();
+        for path in settled {
+            pending.remove(&path);
+            if let Some(index) = owner(&roots, &path) {
+                if wanted(&roots[index].folder, &path) {
+                    batches.entry(index).or_default().push(path);
+                }
+            }
+        }
+        for (index, paths) in batches {
+            spawn_import(&app, &roots[index].folder, paths);
+        }
+    }
+}
+
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    std::thread::spawn(move || run(handle));
+}
+
+fn status(app: &AppHandle) -> Vec<WatchFolderStatus> {
+    let states = STATE.lock().unwrap();
+    config::current(app)
+        .watch_folders
+        .folders
+        .into_iter()
+        .map(|folder| WatchFolderStatus {
+            state: states.get(&folder.path).cloned().unwrap_or_default(),
+            folder,
+        })
+        .collect()
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub fn get_watch_folders(app: AppHandle) -> Vec<WatchFolderStatus> {
+    status(&app)
+}
+
+/// Adds `folder`, or replaces the entry with the same path.
+#[tauri::command]
+pub fn set_watch_folder(
+    app: AppHandle,
+    mut folder: WatchFolder,
+) -> Result<Vec<WatchFolderStatus>, String> {
+    if !Path::new(&folder.path).is_dir() {
+        return Err(format!("{} is not a folder", folder.path));
+    }
+    folder.extensions = folder
+        .extensions
+        .iter()
+        .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+        .filter(|e| !e.is_empty())
+        .collect();
+    folder.extensions.dedup();
+    config::update(&app, |c| {
+        let folders = &mut c.watch_folders.folders;
+        match folders.iter_mut().find(|f| f.path == folder.path) {
+            Some(existing) => *existing = folder,
+            None => folders.push(folder),
+        }
+    })?;
+    Ok(status(&app))
+}
+
+#[tauri::command]
+pub fn remove_watch_folder(app: AppHandle, path: String) -> Result<Vec<WatchFolderStatus>, String> {
+    config::update(&app, |c| c.watch_folders.folders.retain(|f| f.path != path))?;
+    Ok(status(&app))
+}
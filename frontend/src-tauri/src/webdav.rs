@@ -0,0 +1,384 @@
+// WebDAV sync — the sync log (see sync_log.rs) on any WebDAV server.
+//
+// Points at a folder on a WebDAV server (Nextcloud, Fastmail Files, ...)
+// shared by every device that syncs, and syncs every `interval_minutes`
+// (times the poll factor) while enabled. Files are sealed with a passphrase
+// derived from the sync key (see sync_key.rs), or one the user chose. The
+// server password and any such passphrase are kept in the OS keyring (see
+// keyring.rs) under "webdav:<url>", never in the config.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::sync_log::{self, blocking, Remote, SyncReport, Target};
+use crate::{config, keyring, performance, proxy, suspend, sync_key, tray_status};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// `<d:href>` in a PROPFIND response, whatever the namespace prefix.
+static HREF: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)<(?:[a-z0-9]+:)?href>([^<]*)</(?:[a-z0-9]+:)?href>").unwrap());
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct WebDavConfig {
+    pub enabled: bool,
+    /// Folder on the server; created if missing.
+    pub url: Option<String>,
+    pub username: Option<String>,
+    pub interval_minutes: u64,
+    pub snapshot_hours: u64,
+    /// Snapshots kept per device.
+    pub keep_snapshots: usize,
+    /// Change files older than this are removed from the server.
+    pub retention_days: u64,
+}
+
+impl Default for WebDavConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: None,
+            username: None,
+            interval_minutes: 30,
+            snapshot_hours: 24,
+            keep_snapshots: 3,
+            retention_days: 30,
+        }
+    }
+}
+
+/// Keyring entry, as JSON.
+#[derive(Serialize, Deserialize)]
+struct Credentials {
+    password: String,
+    /// Empty when the sync key is used.
+    #[serde(default)]
+    passphrase: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct WebDavStatus {
+    config: WebDavConfig,
+    /// Password and passphrase are in the keyring.
+    has_credentials: bool,
+    syncing: bool,
+    last_sync: Option<SyncReport>,
+    last_error: Option<String>,
+}
+
+static SYNCING: AtomicBool = AtomicBool::new(false);
+static LAST: Mutex<(Option<SyncReport>, Option<String>)> = Mutex::new((None, None));
+static LAST_RUN: Mutex<Option<Instant>> = Mutex::new(None);
+
+fn account(url: &str) -> String {
+    format!("webdav:{url}")
+}
+
+async fn credentials(url: &str) -> Result<Option<Credentials>, String> {
+    let account = account(url);
+    let Some(text) = blocking(move || keyring::get(&account)).await? else {
+        return Ok(None);
+    };
+    serde_json::from_str(&text)
+        .map(Some)
+        .map_err(|e| format!("Unreadable WebDAV credentials: {e}"))
+}
+
+// ── Server ─────────────────────────────────────────────────────────────────
+pub struct Dav {
+    client: reqwest::Client,
+    /// Ends with a slash.
+    base: String,
+    username: String,
+    password: String,
+}
+
+impl Dav {
+    fn new(app: &AppHandle, url: &str, username: &str, password: &str) -> Result<Self, String> {
+        let client = proxy::client_builder(app)
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            client,
+            base: format!("{}/", url.trim_end_matches('/')),
+            username: username.to_string(),
+            password: password.to_string(),
+        })
+    }
+
+    /// Any status but 401, which means the credentials are wrong.
+    async fn send(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<Vec<u8>>,
+        headers: &[(&str, &str)],
+    ) -> Result<reqwest::Response, String> {
+        let verb = Method::from_bytes(method.as_bytes()).map_err(|e| e.to_string())?;
+        let mut request = self
+            .client
+            .request(verb, format!("{}{path}", self.base))
+            .basic_auth(&self.username, Some(&self.password));
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        if let Some(body) = body {
+            request = request.body(body);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("WebDAV {method} {path} failed: {e}"))?;
+        if response.status() == StatusCode::UNAUTHORIZED {
+            return Err("The WebDAV server rejected the username or password".into());
+        }
+        Ok(response)
+    }
+
+    async fn request(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<Vec<u8>>,
+        headers: &[(&str, &str)],
+    ) -> Result<reqwest::Response, String> {
+        let response = self.send(method, path, body, headers).await?;
+        match response.status() {
+            status if status.is_success() => Ok(response),
+            status => Err(format!("WebDAV {method} {path} failed: {status}")),
+        }
+    }
+
+    /// Creates the collection `path`; one that exists already is fine.
+    pub async fn mkcol(&self, path: &str) -> Result<(), String> {
+        match self.send("MKCOL", path, None, &[]).await?.status() {
+            status if status.is_success() || status == StatusCode::METHOD_NOT_ALLOWED => Ok(()),
+            status => Err(format!("Could not create {}{path}: {status}", self.base)),
+        }
+    }
+
+    /// Names of the files in the collection `dir`.
+    pub async fn list(&self, dir: &str) -> Result<Vec<String>, String> {
+        let body = br#"<?xml version="1.0" encoding="utf-8"?><d:propfind xmlns:d="DAV:"><d:prop><d:resourcetype/></d:prop></d:propfind>"#;
+        let response = self
+            .request(
+                "PROPFIND",
+                dir,
+                Some(body.to_vec()),
+                &[("Depth", "1"), ("Content-Type", "application/xml")],
+            )
+            .await?;
+        let text = response.text().await.map_err(|e| e.to_string())?;
+        Ok(HREF
+            .captures_iter(&text)
+            .filter_map(|c| c[1].trim().rsplit('/').next().map(str::to_string))
+            .collect())
+    }
+
+    pub async fn get(&self, path: &str) -> Result<Vec<u8>, String> {
+        let response = self.request("GET", path, None, &[]).await?;
+        let bytes = response.bytes().await.map_err(|e| e.to_string())?;
+        Ok(bytes.to_vec())
+    }
+
+    pub async fn put(&self, path: &str, bytes: Vec<u8>) -> Result<(), String> {
+        self.request(
+            "PUT",
+            path,
+            Some(bytes),
+            &[("Content-Type", "application/octet-stream")],
+        )
+        .await
+        .map(drop)
+    }
+
+    pub async fn delete(&self, path: &str) -> Result<(), String> {
+        match self.send("DELETE", path, None, &[]).await?.status() {
+            status if status.is_success() || status == StatusCode::NOT_FOUND => Ok(()),
+            status => Err(format!("WebDAV DELETE {path} failed: {status}")),
+        }
+    }
+}
+
+async fn run(app: &AppHandle, cfg: &WebDavConfig) -> Result<SyncReport, String> {
+    let url = cfg.url.clone().ok_or("No WebDAV folder set")?;
+    let secrets = credentials(&url)
+        .await?
+        .ok_or("No WebDAV password saved; set up sync again")?;
+    let dav = Dav::new(
+        app,
+        &url,
+        cfg.username.as_deref().unwrap_or_default(),
+        &secrets.password,
+    )?;
+    let passphrase = match secrets.passphrase.as_str() {
+        "" => sync_key::passphrase("webdav").await?,
+        own => own.to_string(),
+    };
+    let target = Target {
+        key: url,
+        label: "WebDAV".into(),
+        state_file: "webdav-sync.json".into(),
+        passphrase: &passphrase,
+        snapshot_hours: cfg.snapshot_hours,
+        keep_snapshots: cfg.keep_snapshots,
+        retention_days: cfg.retention_days,
+        prune: true,
+    };
+    sync_log::run(&Remote::WebDav(dav), &target).await
+}
+
+async fn sync(app: &AppHandle) -> Result<SyncReport, String> {
+    let cfg = config::current(app).webdav;
+    if cfg.url.is_none() {
+        return Err("No WebDAV folder set".into());
+    }
+    if SYNCING.swap(true, Ordering::SeqCst) {
+        return Err("A sync is already running".into());
+    }
+    let result = run(app, &cfg).await;
+    SYNCING.store(false, Ordering::SeqCst);
+    *LAST_RUN.lock().unwrap() = Some(Instant::now());
+
+    let mut last = LAST.lock().unwrap();
+    match &result {
+        Ok(report) => {
+            log::info!(
+                "WebDAV sync: {} uploaded, {} change files applied ({} created, {} updated, {} deleted, {} skipped)",
+                report.uploaded,
+                report.applied,
+                report.created,
+                report.updated,
+                report.deleted,
+                report.skipped
+            );
+            *last = (Some(report.clone()), None);
+            tray_status::synced();
+            app.emit_all("webdav-synced", report).ok();
+        }
+        Err(e) => {
+            log::warn!("WebDAV sync failed: {}", e);
+            last.1 = Some(e.clone());
+        }
+    }
+    result
+}
+
+async fn status(app: &AppHandle) -> WebDavStatus {
+    let cfg = config::current(app).webdav;
+    let has_credentials = match &cfg.url {
+        Some(url) => credentials(url).await.is_ok_and(|c| c.is_some()),
+        None => false,
+    };
+    let (last_sync, last_error) = LAST.lock().unwrap().clone();
+    WebDavStatus {
+        config: cfg,
+        has_credentials,
+        syncing: SYNCING.load(Ordering::SeqCst),
+        last_sync,
+        last_error,
+    }
+}
+
+/// Syncs on the configured interval while enabled.
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let cfg = config::current(&handle).webdav;
+            if !cfg.enabled || cfg.url.is_none() || suspend::paused() {
+                continue;
+            }
+            let factor = performance::capabilities(&handle).poll_factor as u32;
+            let interval = Duration::from_secs(cfg.interval_minutes.max(1) * 60) * factor;
+            let due = LAST_RUN
+                .lock()
+                .unwrap()
+                .map_or(true, |at| at.elapsed() >= interval);
+            if due && crate::BACKEND_PORT.load(Ordering::SeqCst) != 0 {
+                sync(&handle).await.ok();
+            }
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub async fn get_webdav_status(app: AppHandle) -> WebDavStatus {
+    status(&app).await
+}
+
+/// Turns sync on for the folder at `url`. The password and passphrase are
+/// saved to the keyring; either may be left out to keep the saved one. With
+/// no passphrase at all, files are sealed with the sync key.
+#[tauri::command]
+pub async fn set_webdav(
+    app: AppHandle,
+    url: String,
+    username: String,
+    password: Option<String>,
+    passphrase: Option<String>,
+) -> Result<WebDavStatus, String> {
+    let url = url.trim().trim_end_matches('/').to_string();
+    let parsed = reqwest::Url::parse(&url).map_err(|e| format!("Invalid URL: {e}"))?;
+    if !matches!(parsed.scheme(), "https" | "http") {
+        return Err("The WebDAV URL must start with https://".into());
+    }
+    let saved = credentials(&url).await.ok().flatten();
+    let password = password
+        .filter(|p| !p.is_empty())
+        .or_else(|| saved.as_ref().map(|c| c.password.clone()))
+        .ok_or("Enter the WebDAV password")?;
+    let passphrase = passphrase
+        .filter(|p| !p.is_empty())
+        .or_else(|| saved.map(|c| c.passphrase))
+        .unwrap_or_default();
+    if passphrase.is_empty() {
+        sync_key::passphrase("webdav").await?;
+    } else if passphrase.chars().count() < 8 {
+        return Err("The passphrase must be at least 8 characters".into());
+    }
+    let secret = serde_json::to_string(&Credentials {
+        password,
+        passphrase,
+    })
+    .map_err(|e| e.to_string())?;
+    let account = account(&url);
+    blocking(move || keyring::set(&account, &secret)).await?;
+
+    config::update(&app, |c| {
+        c.webdav.enabled = true;
+        c.webdav.url = Some(url);
+        c.webdav.username = Some(username.trim().to_string()).filter(|u| !u.is_empty());
+    })?;
+    sync(&app).await?;
+    Ok(status(&app).await)
+}
+
+/// Turns sync off; `forget` also removes the saved password and passphrase.
+#[tauri::command]
+pub async fn disable_webdav(app: AppHandle, forget: bool) -> Result<WebDavStatus, String> {
+    let url = config::current(&app).webdav.url;
+    config::update(&app, |c| c.webdav.enabled = false)?;
+    if let (true, Some(url)) = (forget, url) {
+        let account = account(&url);
+        blocking(move || keyring::delete(&account)).await?;
+    }
+    Ok(status(&app).await)
+}
+
+#[tauri::command]
+pub async fn sync_now(app: AppHandle) -> Result<SyncReport, String> {
+    sync(&app).await
+}
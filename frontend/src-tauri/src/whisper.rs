@@ -0,0 +1,458 @@
+// Whisper — offline transcription through an optional whisper.cpp sidecar.
+//
+// Nothing is bundled. The binary is looked up in `whisper.binary_path`,
+// app_dir()/whisper/bin/ and PATH (whisper-cli, whisper-cpp), or installed
+// with `install_whisper_binary(url, sha256)`; the checksum is mandatory for
+// executables. Models (ggml-<name>.bin) are downloaded from the whisper.cpp
+// model repository into app_dir()/whisper/models/ and checked for the ggml
+// magic, plus `whisper.model_sha256` when set. Both go through the download
+// manager (see downloads.rs), so progress arrives as "model-download-progress".
+//
+// `transcribe(attachment)` converts the audio to 16 kHz mono WAV, spawns the
+// binary for that one job (CPU-only with `-ng` when no GPU backend is
+// available, see hardware.rs) and forwards its progress as
+// "transcription-progress" events. Transcripts are cached per attachment.
+// With `whisper.auto_transcribe`, new voice notes get their transcript
+// appended once it is ready (deferred while in power saver mode).
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::api::process::{Command, CommandEvent};
+use tauri::{AppHandle, Manager};
+
+use crate::hardware::{self, Accelerator};
+use crate::{
+    attachments, backend, badge, config, downloads, history, i18n, jobs, notifications, power,
+};
+
+const MODEL_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+/// First bytes of a ggml model file ("ggml" as a little-endian u32).
+const GGML_MAGIC: &[u8; 4] = b"lmgg";
+const SAMPLE_RATE: u32 = 16_000;
+
+static PROGRESS: Lazy<Regex> = Lazy::new(|| Regex::new(r"progress\s*=\s*(\d+)%").unwrap());
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct WhisperConfig {
+    /// Model name as in ggml-<model>.bin: "tiny", "base.en", "small", ...
+    pub model: String,
+    /// Expected SHA-256 of the model file, if you want it pinned.
+    pub model_sha256: Option<String>,
+    pub binary_path: Option<String>,
+    /// Spoken language code, or "auto".
+    pub language: String,
+    /// 0 lets whisper.cpp decide.
+    pub threads: u32,
+    pub auto_transcribe: bool,
+}
+
+impl Default for WhisperConfig {
+    fn default() -> Self {
+        Self {
+            model: "base".into(),
+            model_sha256: None,
+            binary_path: None,
+            language: "auto".into(),
+            threads: 0,
+            auto_transcribe: false,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Transcript {
+    attachment: String,
+    model: String,
+    text: String,
+    cached: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct WhisperStatus {
+    binary: Option<String>,
+    model: String,
+    model_installed: bool,
+    installed_models: Vec<String>,
+}
+
+fn whisper_dir() -> PathBuf {
+    crate::app_dir().join("whisper")
+}
+
+fn model_path(model: &str) -> PathBuf {
+    whisper_dir()
+        .join("models")
+        .join(format!("ggml-{}.bin", model))
+}
+
+fn transcript_path(hash: &str, model: &str) -> PathBuf {
+    whisper_dir()
+        .join("transcripts")
+        .join(format!("{}.{}.txt", hash, model))
+}
+
+fn exe_name(name: &str) -> String {
+    if cfg!(windows) {
+        format!("{}.exe", name)
+    } else {
+        name.to_string()
+    }
+}
+
+fn check_model_name(model: &str) -> Result<(), String> {
+    let ok = !model.is_empty()
+        && !model.contains("..")
+        && model
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if ok {
+        Ok(())
+    } else {
+        Err(format!("Invalid model name {:?}", model))
+    }
+}
+
+fn find_binary(cfg: &WhisperConfig) -> Option<PathBuf> {
+    if let Some(path) = &cfg.binary_path {
+        return Some(PathBuf::from(path)).filter(|p| p.is_file());
+    }
+    let mut dirs = vec![whisper_dir().join("bin")];
+    if let Some(path) = std::env::var_os("PATH") {
+        dirs.extend(std::env::split_paths(&path));
+    }
+    dirs.extend(["/opt/homebrew/bin", "/usr/local/bin"].map(PathBuf::from));
+    dirs.iter()
+        .flat_map(|dir| ["whisper-cli", "whisper-cpp"].map(|n| dir.join(exe_name(n))))
+        .find(|p| p.is_file())
+}
+
+fn verify_model(path: &Path) -> Result<(), String> {
+    let mut magic = [0u8; 4];
+    std::io::Read::read_exact(
+        &mut std::fs::File::open(path).map_err(|e| e.to_string())?,
+        &mut magic,
+    )
+    .map_err(|e| e.to_string())?;
+    if &magic != GGML_MAGIC {
+        return Err("Not a ggml model file".into());
+    }
+    Ok(())
+}
+
+/// Decodes `input` (any WAV) to 16 kHz mono 16-bit PCM at `output`.
+fn to_whisper_wav(input: &Path, output: &Path) -> Result<(), String> {
+    let mut reader =
+        hound::WavReader::open(input).map_err(|e| format!("Unsupported audio file: {e}"))?;
+    let spec = reader.spec();
+    let channels = spec.channels.max(1) as usize;
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().filter_map(Result::ok).collect(),
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .filter_map(Result::ok)
+                .map(|s| s as f32 / scale)
+                .collect()
+        }
+    };
+    let mono: Vec<f32> = samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+
+    // Linear resampling is plenty for speech recognition.
+    let ratio = spec.sample_rate as f64 / SAMPLE_RATE as f64;
+    let out_len = (mono.len() as f64 / ratio) as usize;
+    let mut writer = hound::WavWriter::create(
+        output,
+        hound::WavSpec {
+            channels: 1,
+            sample_rate: SAMPLE_RATE,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        },
+    )
+    .map_err(|e| e.to_string())?;
+    for i in 0..out_len {
+        let pos = i as f64 * ratio;
+        let index = pos as usize;
+        let frac = (pos - index as f64) as f32;
+        let a = mono.get(index).copied().unwrap_or(0.0);
+        let b = mono.get(index + 1).copied().unwrap_or(a);
+        let value = (a + (b - a) * frac).clamp(-1.0, 1.0);
+        writer
+            .write_sample((value * i16::MAX as f32) as i16)
+            .map_err(|e| e.to_string())?;
+    }
+    writer.finalize().map_err(|e| e.to_string())
+}
+
+/// Runs whisper.cpp on `input` and returns the transcript.
+async fn run_whisper(
+    app: &AppHandle,
+    job: &jobs::Job,
+    hash: &str,
+    cfg: &WhisperConfig,
+    binary: &Path,
+    model: &Path,
+    input: PathBuf,
+) -> Result<String, String> {
+    let work = crate::runtime_dir().join(format!("whisper-{}", hash));
+    std::fs::create_dir_all(&work).map_err(|e| e.to_string())?;
+    let wav = work.join("input.wav");
+    let prepared = {
+        let wav = wav.clone();
+        tauri::async_runtime::spawn_blocking(move || to_whisper_wav(&input, &wav))
+            .await
+            .map_err(|e| e.to_string())?
+    };
+    if let Err(e) = prepared {
+        std::fs::remove_dir_all(&work).ok();
+        return Err(e);
+    }
+
+    let out_base = work.join("transcript");
+    let mut args = vec![
+        "-m".to_string(),
+        model.to_string_lossy().into_owned(),
+        "-f".into(),
+        wav.to_string_lossy().into_owned(),
+        "-l".into(),
+        cfg.language.clone(),
+        "-otxt".into(),
+        "-of".into(),
+        out_base.to_string_lossy().into_owned(),
+        "-pp".into(),
+        "-np".into(),
+    ];
+    if cfg.threads > 0 {
+        args.extend(["-t".into(), cfg.threads.to_string()]);
+    }
+    let hardware = tauri::async_runtime::spawn_blocking(hardware::info)
+        .await
+        .map_err(|e| e.to_string())?;
+    if hardware.recommendation.accelerator == Accelerator::Cpu {
+        args.push("-ng".into());
+    }
+
+    log::info!("Transcribing {} with {}", hash, cfg.model);
+    let (mut rx, _child) = Command::new(binary.to_string_lossy())
+        .args(args)
+        .spawn()
+        .map_err(|e| format!("Could not start whisper.cpp: {e}"))?;
+    let mut code = None;
+    let mut tail = Vec::new();
+    while let Some(event) = rx.recv().await {
+        match event {
+            CommandEvent::Stdout(line) | CommandEvent::Stderr(line) => {
+                if let Some(caps) = PROGRESS.captures(&line) {
+                    let percent: u32 = caps[1].parse().unwrap_or(0);
+                    app.emit_all(
+                        "transcription-progress",
+                        json!({ "attachment": hash, "percent": percent }),
+                    )
+                    .ok();
+                    job.progress(percent as u64, 100);
+                } else {
+                    tail.push(line);
+                    if tail.len() > 20 {
+                        tail.remove(0);
+                    }
+                }
+            }
+            CommandEvent::Terminated(payload) => code = payload.code,
+            _ => {}
+        }
+    }
+
+    let result = match code {
+        Some(0) => std::fs::read_to_string(out_base.with_extension("txt"))
+            .map(|text| text.trim().to_string())
+            .map_err(|e| format!("whisper.cpp produced no transcript: {e}")),
+        _ => Err(format!(
+            "whisper.cpp failed ({:?}): {}",
+            code,
+            tail.join("\n").trim()
+        )),
+    };
+    std::fs::remove_dir_all(&work).ok();
+    result
+}
+
+pub async fn transcribe_attachment(app: &AppHandle, hash: &str) -> Result<Transcript, String> {
+    let cfg = config::current(app).whisper;
+    check_model_name(&cfg.model)?;
+    let cache = transcript_path(hash, &cfg.model);
+    if let Ok(text) = std::fs::read_to_string(&cache) {
+        return Ok(Transcript {
+            attachment: hash.into(),
+            model: cfg.model,
+            text,
+            cached: true,
+        });
+    }
+
+    let attachment = attachments::get(hash).ok_or("Unknown attachment")?;
+    let binary = find_binary(&cfg).ok_or("whisper.cpp is not installed")?;
+    let model = model_path(&cfg.model);
+    if !model.is_file() {
+        return Err(format!("Model {} is not installed", cfg.model));
+    }
+
+    let job = jobs::begin("transcribe", &i18n::t("job.transcribe"));
+    let result = run_whisper(
+        app,
+        &job,
+        hash,
+        &cfg,
+        &binary,
+        &model,
+        attachments::path_of(&attachment),
+    )
+    .await;
+    job.end(&result);
+    let text = result?;
+
+    if let Some(dir) = cache.parent() {
+        std::fs::create_dir_all(dir).ok();
+    }
+    std::fs::write(&cache, &text).ok();
+    app.emit_all(
+        "transcription-progress",
+        json!({ "attachment": hash, "percent": 100 }),
+    )
+    .ok();
+    Ok(Transcript {
+        attachment: hash.into(),
+        model: cfg.model,
+        text,
+        cached: false,
+    })
+}
+
+/// Transcribes a new voice note in the background and appends the text to
+/// its snippet, if `auto_transcribe` is on.
+pub fn auto_transcribe(app: &AppHandle, snippet_id: String, body: String, hash: String) {
+    if !config::current(app).whisper.auto_transcribe {
+        return;
+    }
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        while power::saving() {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        }
+        let transcript = match transcribe_attachment(&handle, &hash).await {
+            Ok(t) if !t.text.is_empty() => t,
+            Ok(_) => return,
+            Err(e) => {
+                log::warn!("Voice note transcription failed: {}", e);
+                return;
+            }
+        };
+        let body = format!("{}\n\n{}", body, transcript.text);
+        match backend::patch(
+            &format!("/snippets/{}", snippet_id),
+            &json!({ "body": body }),
+        )
+        .await
+        {
+            Ok(snippet) => {
+                history::record_snippet(&handle, &snippet);
+                badge::arrived(&handle);
+                let title = snippet["title"].as_str().unwrap_or("Voice note");
+                notifications::notify(
+                    &handle,
+                    &i18n::t("notification.transcript_ready"),
+                    title,
+                    Some(&snippet_id),
+                );
+            }
+            Err(e) => log::warn!("Could not save transcript: {}", e),
+        }
+    });
+}
+
+// ── IPC Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+pub async fn transcribe(app: AppHandle, attachment: String) -> Result<Transcript, String> {
+    transcribe_attachment(&app, &attachment).await
+}
+
+#[tauri::command]
+pub fn get_whisper_status(app: AppHandle) -> WhisperStatus {
+    let cfg = config::current(&app).whisper;
+    let installed_models = std::fs::read_dir(whisper_dir().join("models"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| {
+                    let name = e.file_name().to_string_lossy().into_owned();
+                    Some(
+                        name.strip_prefix("ggml-")?
+                            .strip_suffix(".bin")?
+                            .to_string(),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    WhisperStatus {
+        binary: find_binary(&cfg).map(|p| p.to_string_lossy().into_owned()),
+        model_installed: model_path(&cfg.model).is_file(),
+        model: cfg.model,
+        installed_models,
+    }
+}
+
+/// Downloads `model` (default: the configured one) and verifies it.
+#[tauri::command]
+pub async fn install_whisper_model(
+    app: AppHandle,
+    model: Option<String>,
+) -> Result<String, String> {
+    let cfg = config::current(&app).whisper;
+    let model = model.unwrap_or(cfg.model.clone());
+    check_model_name(&model)?;
+    let expected = cfg.model_sha256.filter(|_| model == cfg.model);
+    let dest = downloads::fetch(
+        &app,
+        format!("{}/ggml-{}.bin", MODEL_URL, model),
+        model_path(&model),
+        expected,
+    )
+    .await?;
+    if let Err(e) = verify_model(&dest) {
+        std::fs::remove_file(&dest).ok();
+        return Err(e);
+    }
+    log::info!("Installed whisper model {}", model);
+    Ok(model)
+}
+
+/// Downloads a whisper.cpp executable into app_dir()/whisper/bin/.
+#[tauri::command]
+pub async fn install_whisper_binary(
+    app: AppHandle,
+    url: String,
+    sha256: String,
+) -> Result<String, String> {
+    let dest = whisper_dir().join("bin").join(exe_name("whisper-cli"));
+    let staged = dest.with_extension("download");
+    downloads::fetch(&app, url.clone(), staged.clone(), Some(sha256)).await?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| e.to_string())?;
+    }
+    std::fs::rename(&staged, &dest).map_err(|e| e.to_string())?;
+    log::info!("Installed whisper.cpp binary from {}", url);
+    Ok(dest.to_string_lossy().into_owned())
+}
@@ -20,6 +20,8 @@ export default function App() {
   const setPage = useAppStore((s) => s.setPage);
   const setBackendStatus = useAppStore((s) => s.setBackendStatus);
   const setIsCreating = useAppStore((s) => s.setIsCreating);
+  const setSelectedSnippetId = useAppStore((s) => s.setSelectedSnippetId);
+  const setSearchQuery = useAppStore((s) => s.setSearchQuery);
   const { toast, showToast, hideToast } = useToast();
 
   // Onboarding state
@@ -81,6 +83,32 @@ export default function App() {
     return () => window.removeEventListener('keydown', handler);
   }, [setPage, setIsCreating]);
 
+  // pinup://open links (see deep_link.rs)
+  useEffect(() => {
+    if (!(window as any).__TAURI__) return;
+    let unlisten: (() => void) | undefined;
+    let cancelled = false;
+    import('@tauri-apps/api/event').then(({ listen }) =>
+      listen<{ id?: string; query?: string }>('deep-link-open', ({ payload }) => {
+        setPage('snippets');
+        setIsCreating(false);
+        if (payload.query) {
+          setSearchQuery(payload.query);
+        } else if (payload.id) {
+          setSearchQuery('');
+          setSelectedSnippetId(payload.id);
+        }
+      }).then((fn) => {
+        if (cancelled) fn();
+        else unlisten = fn;
+      }),
+    );
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, [setPage, setIsCreating, setSearchQuery, setSelectedSnippetId]);
+
   // Health polling
   const healthQuery = useHealth();
   useEffect(() => {
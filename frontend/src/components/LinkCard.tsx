@@ -0,0 +1,73 @@
+import React from 'react';
+import { ExternalLink } from 'lucide-react';
+import { useUnfurl } from '../hooks/useApi';
+import { Skeleton } from './Skeleton';
+
+interface LinkCardProps {
+  url: string;
+}
+
+/** Rich preview of a link; falls back to the bare URL outside the shell or on failure. */
+export const LinkCard: React.FC<LinkCardProps> = ({ url }) => {
+  const { data, isLoading } = useUnfurl(url);
+
+  if (isLoading) {
+    return (
+      <div className="flex gap-3 p-3 border border-gray-200 dark:border-gray-700 rounded-lg">
+        <div className="flex-1 space-y-2">
+          <Skeleton className="h-4 w-2/3" />
+          <Skeleton className="h-3 w-full" />
+          <Skeleton className="h-3 w-1/3" />
+        </div>
+        <Skeleton className="w-24 h-16" />
+      </div>
+    );
+  }
+
+  const host = (() => {
+    try {
+      return new URL(data?.final_url ?? url).host;
+    } catch {
+      return url;
+    }
+  })();
+
+  if (!data || (!data.title && !data.description && !data.image)) {
+    return (
+      <a
+        href={url}
+        target="_blank"
+        rel="noopener noreferrer"
+        className="flex items-center gap-1 text-xs text-brand-600 hover:underline"
+      >
+        <ExternalLink className="w-3 h-3" />
+        {url}
+      </a>
+    );
+  }
+
+  return (
+    <a
+      href={url}
+      target="_blank"
+      rel="noopener noreferrer"
+      className="flex gap-3 p-3 border border-gray-200 dark:border-gray-700 rounded-lg hover:bg-gray-50 dark:hover:bg-gray-800 transition-colors"
+    >
+      <div className="flex-1 min-w-0">
+        {data.title && (
+          <p className="text-sm font-medium text-gray-900 dark:text-gray-100 truncate">{data.title}</p>
+        )}
+        {data.description && (
+          <p className="mt-1 text-xs text-gray-500 dark:text-gray-400 line-clamp-2">{data.description}</p>
+        )}
+        <p className="mt-2 flex items-center gap-1.5 text-xs text-gray-400">
+          {data.favicon && <img src={data.favicon} alt="" className="w-4 h-4" />}
+          <span className="truncate">{data.site_name || host}</span>
+        </p>
+      </div>
+      {data.image && (
+        <img src={data.image} alt="" className="w-24 h-16 object-cover rounded-md flex-shrink-0" />
+      )}
+    </a>
+  );
+};
@@ -15,6 +15,8 @@ import {
   Folder,
   Plus,
   Check,
+  QrCode,
+  Bell,
 } from 'lucide-react';
 import { cn, formatDate, formatBytes } from '../utils/helpers';
 import {
@@ -24,7 +26,14 @@ import {
   useDeleteSnippet,
   usePinSnippet,
   useArchiveSnippet,
+  useSnippetQr,
+  useReminders,
+  useSetReminder,
+  useSnoozeReminder,
+  useDismissReminder,
 } from '../hooks/useApi';
+import type { SnippetQr } from '../hooks/useApi';
+import { LinkCard } from './LinkCard';
 import type { SnippetCreateInput, SnippetPatchInput, SnippetOut } from '../types';
 
 /* -------------------------------------------------------------------------- */
@@ -54,6 +63,21 @@ const BLANK: SnippetCreateInput = {
   pinned: false,
 };
 
+/** Quick picks for a reminder, from now. */
+const REMINDER_PRESETS: { label: string; at: () => Date }[] = [
+  { label: 'In 1 hour', at: () => new Date(Date.now() + 3_600_000) },
+  {
+    label: 'Tomorrow 9:00',
+    at: () => {
+      const d = new Date();
+      d.setDate(d.getDate() + 1);
+      d.setHours(9, 0, 0, 0);
+      return d;
+    },
+  },
+  { label: 'Next week', at: () => new Date(Date.now() + 7 * 86_400_000) },
+];
+
 /* -------------------------------------------------------------------------- */
 /*  Component                                                                 */
 /* -------------------------------------------------------------------------- */
@@ -75,6 +99,12 @@ export const SnippetDetail: React.FC<SnippetDetailProps> = ({
   const deleteMut = useDeleteSnippet();
   const pinMut = usePinSnippet();
   const archiveMut = useArchiveSnippet();
+  const qrMut = useSnippetQr();
+  const isShell = !!(window as any).__TAURI__;
+  const remindersQuery = useReminders(isShell);
+  const setReminderMut = useSetReminder();
+  const snoozeMut = useSnoozeReminder();
+  const dismissMut = useDismissReminder();
 
   /* ── Local edit/create state ─────────────────────────────────────────── */
   const [isEditing, setIsEditing] = useState(false);
@@ -92,6 +122,9 @@ export const SnippetDetail: React.FC<SnippetDetailProps> = ({
 
   const [confirmDelete, setConfirmDelete] = useState(false);
   const [copied, setCopied] = useState(false);
+  const [qr, setQr] = useState<SnippetQr | null>(null);
+  const [reminding, setReminding] = useState(false);
+  const [remindAt, setRemindAt] = useState('');
   const codeRef = useRef<HTMLElement>(null);
   const bodyRef = useRef<HTMLTextAreaElement>(null);
 
@@ -248,6 +281,42 @@ export const SnippetDetail: React.FC<SnippetDetailProps> = ({
     }
   };
 
+  // A code shown for one snippet shouldn't linger on the next.
+  useEffect(() => setQr(null), [snippetId]);
+  useEffect(() => setReminding(false), [snippetId]);
+
+  const reminders = (remindersQuery.data ?? []).filter((r) => r.snippet_id === snippetId);
+
+  const handleRemind = (at: Date) => {
+    if (!snippet) return;
+    if (Number.isNaN(at.getTime())) {
+      showToast('Pick a time for the reminder', 'warning');
+      return;
+    }
+    setReminderMut.mutate(
+      { snippetId: snippet.id, title: snippet.title, dueAt: at },
+      {
+        onSuccess: () => {
+          setRemindAt('');
+          showToast(`Reminder set for ${at.toLocaleString()}`, 'success');
+        },
+        onError: (e) => showToast(String(e), 'error'),
+      },
+    );
+  };
+
+  const handleQr = () => {
+    if (!snippet) return;
+    if (qr) {
+      setQr(null);
+      return;
+    }
+    qrMut.mutate(snippet.id, {
+      onSuccess: setQr,
+      onError: (e) => showToast(String(e), 'error'),
+    });
+  };
+
   const handleKeyDown = (e: React.KeyboardEvent) => {
     if ((e.metaKey || e.ctrlKey) && e.key === 's') {
       e.preventDefault();
@@ -465,6 +534,35 @@ export const SnippetDetail: React.FC<SnippetDetailProps> = ({
         >
           {copied ? <Check className="w-4 h-4 text-green-500" /> : <Copy className="w-4 h-4" />}
         </button>
+        {(window as any).__TAURI__ && (
+          <button
+            onClick={handleQr}
+            disabled={qrMut.isPending}
+            className={cn(
+              'p-1.5 rounded-md hover:bg-gray-200 dark:hover:bg-gray-700 disabled:opacity-50',
+              qr ? 'text-brand-600' : 'text-gray-500 hover:text-gray-700 dark:text-gray-400 dark:hover:text-gray-200',
+            )}
+            aria-label="Show QR code"
+            title="Show as QR code"
+          >
+            <QrCode className="w-4 h-4" />
+          </button>
+        )}
+        {isShell && (
+          <button
+            onClick={() => setReminding((r) => !r)}
+            className={cn(
+              'p-1.5 rounded-md hover:bg-gray-200 dark:hover:bg-gray-700',
+              reminding || reminders.length > 0
+                ? 'text-brand-600'
+                : 'text-gray-500 hover:text-gray-700 dark:text-gray-400 dark:hover:text-gray-200',
+            )}
+            aria-label="Remind me"
+            title="Remind me"
+          >
+            <Bell className="w-4 h-4" />
+          </button>
+        )}
         <button
           onClick={handlePin}
           disabled={pinMut.isPending}
@@ -549,6 +647,78 @@ export const SnippetDetail: React.FC<SnippetDetailProps> = ({
           <span>Updated {formatDate(snippet.updated_at)}</span>
         </div>
 
+        {/* Link preview */}
+        {snippet.source_url && (window as any).__TAURI__ && <LinkCard url={snippet.source_url} />}
+
+        {/* QR code */}
+        {qr && (
+          <div className="flex items-center gap-4">
+            <img
+              src={`data:image/png;base64,${qr.png}`}
+              alt="Snippet QR code"
+              className="w-48 h-48 border border-gray-200 rounded-lg"
+            />
+            <p className="text-xs text-gray-500 dark:text-gray-400">
+              {qr.content === 'link'
+                ? 'Too long for a QR code, so this holds the link. Scan it with a phone camera.'
+                : 'Scan with a phone camera to copy this snippet.'}
+            </p>
+          </div>
+        )}
+
+        {/* Reminders */}
+        {(reminding || reminders.length > 0) && (
+          <div className="space-y-2 text-xs">
+            {reminders.map((r) => (
+              <div key={r.id} className="flex items-center gap-2">
+                <Bell className={cn('w-3.5 h-3.5', r.state === 'due' ? 'text-amber-500' : 'text-gray-400')} />
+                <span className="text-gray-600 dark:text-gray-300 flex-1">
+                  {r.state === 'due' ? 'Was due' : 'Remind me'} {new Date(r.due_at).toLocaleString()}
+                </span>
+                <button
+                  onClick={() => snoozeMut.mutate({ id: r.id, minutes: 60 }, { onError: (e) => showToast(String(e), 'error') })}
+                  className="px-2 py-1 text-gray-600 hover:text-gray-900 dark:text-gray-300"
+                >
+                  Snooze 1h
+                </button>
+                <button
+                  onClick={() => dismissMut.mutate(r.id, { onError: (e) => showToast(String(e), 'error') })}
+                  className="px-2 py-1 text-gray-600 hover:text-red-600 dark:text-gray-300"
+                >
+                  Dismiss
+                </button>
+              </div>
+            ))}
+            {reminding && (
+              <div className="flex flex-wrap items-center gap-1.5">
+                {REMINDER_PRESETS.map((p) => (
+                  <button
+                    key={p.label}
+                    onClick={() => handleRemind(p.at())}
+                    disabled={setReminderMut.isPending}
+                    className="px-2 py-1 border border-gray-200 rounded-lg hover:bg-gray-100 dark:hover:bg-gray-700 disabled:opacity-50"
+                  >
+                    {p.label}
+                  </button>
+                ))}
+                <input
+                  type="datetime-local"
+                  value={remindAt}
+                  onChange={(e) => setRemindAt(e.target.value)}
+                  className="px-2 py-1 text-xs border border-gray-200 rounded-lg"
+                />
+                <button
+                  onClick={() => handleRemind(new Date(remindAt))}
+                  disabled={!remindAt || setReminderMut.isPending}
+                  className="px-2 py-1 bg-brand-600 text-white rounded-lg hover:bg-brand-700 disabled:opacity-50"
+                >
+                  Set
+                </button>
+              </div>
+            )}
+          </div>
+        )}
+
         {/* Tags */}
         {snippet.tags.length > 0 && (
           <div className="flex flex-wrap gap-1.5">
@@ -1,6 +1,7 @@
 // ─────────────────────────────────────────────────────────────────────────────
 // TanStack Query hooks for all API endpoints
 // ─────────────────────────────────────────────────────────────────────────────
+import { useEffect } from 'react';
 import {
   useQuery,
   useMutation,
@@ -49,12 +50,48 @@ export const qk = {
 };
 
 // ─── Health ─────────────────────────────────────────────────────────────────
+export interface PowerProfile {
+  saver: boolean;
+  mode: 'auto' | 'normal' | 'saver';
+  battery: { present: boolean; on_battery: boolean; percent: number | null };
+  health_interval_ms: number;
+}
+
+/**
+ * Shell-only: the battery-aware power profile, kept current from the
+ * shell's `power-profile-changed` event.
+ */
+export function usePowerProfile() {
+  const qc = useQueryClient();
+  const enabled = !!(window as any).__TAURI__;
+  useEffect(() => {
+    if (!enabled) return;
+    const unlisten = import('@tauri-apps/api/event').then(({ listen }) =>
+      listen<PowerProfile>('power-profile-changed', (e) => qc.setQueryData(['power'], e.payload)),
+    );
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [enabled, qc]);
+  return useQuery<PowerProfile>({
+    queryKey: ['power'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<PowerProfile>('get_power_profile');
+    },
+    enabled,
+    staleTime: Infinity,
+  });
+}
+
+/** Polls less often while the shell is saving power. */
 export function useHealth() {
+  const power = usePowerProfile();
   return useQuery<HealthResponse>({
     queryKey: qk.health,
     queryFn: () => healthAPI.check(),
     retry: 2,
-    refetchInterval: 30_000,
+    refetchInterval: power.data?.health_interval_ms ?? 30_000,
   });
 }
 
@@ -82,11 +119,20 @@ export function useSnippet(id: string | null) {
   });
 }
 
+/** In the app, tells the shell the library changed, for its recent-snippet menus. */
+function notifyShell() {
+  if (!(window as any).__TAURI__) return;
+  import('@tauri-apps/api/event')
+    .then(({ emit }) => emit('snippets-changed'))
+    .catch(() => {});
+}
+
 export function useCreateSnippet() {
   const qc = useQueryClient();
   return useMutation({
     mutationFn: (data: SnippetCreateInput) => snippetsAPI.create(data),
     onSuccess: () => {
+      notifyShell();
       qc.invalidateQueries({ queryKey: ['snippets'] });
       qc.invalidateQueries({ queryKey: ['tags'] });
       qc.invalidateQueries({ queryKey: ['collections'] });
@@ -101,6 +147,7 @@ export function useUpdateSnippet() {
     mutationFn: ({ id, data }: { id: string; data: SnippetPatchInput }) =>
       snippetsAPI.update(id, data),
     onSuccess: (_data, { id }) => {
+      notifyShell();
       qc.invalidateQueries({ queryKey: ['snippets'] });
       qc.invalidateQueries({ queryKey: qk.snippet(id) });
       qc.invalidateQueries({ queryKey: ['tags'] });
@@ -114,6 +161,7 @@ export function useDeleteSnippet() {
   return useMutation({
     mutationFn: (id: string) => snippetsAPI.delete(id),
     onSuccess: () => {
+      notifyShell();
       qc.invalidateQueries({ queryKey: ['snippets'] });
       qc.invalidateQueries({ queryKey: ['stats'] });
     },
@@ -126,6 +174,7 @@ export function usePinSnippet() {
     mutationFn: ({ id, pinned }: { id: string; pinned: boolean }) =>
       pinned ? snippetsAPI.pin(id) : snippetsAPI.unpin(id),
     onSuccess: () => {
+      notifyShell();
       qc.invalidateQueries({ queryKey: ['snippets'] });
       qc.invalidateQueries({ queryKey: ['stats'] });
     },
@@ -144,6 +193,49 @@ export function useArchiveSnippet() {
   });
 }
 
+export interface SnippetQr {
+  /** PNG, base64. */
+  png: string;
+  /** 'link' when the body was too long and the source URL was used. */
+  content: 'body' | 'link';
+}
+
+/** Shell-only: the snippet as a QR code, to scan with a phone. */
+export function useSnippetQr() {
+  return useMutation({
+    mutationFn: async (id: string) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<SnippetQr>('snippet_to_qr', { id });
+    },
+  });
+}
+
+export interface Unfurl {
+  url: string;
+  final_url: string;
+  title?: string | null;
+  description?: string | null;
+  site_name?: string | null;
+  /** Data URLs. */
+  favicon?: string | null;
+  image?: string | null;
+  fetched_at: number;
+}
+
+/** Shell-only: a link's title, description and images for a preview card. */
+export function useUnfurl(url?: string | null) {
+  return useQuery<Unfurl>({
+    queryKey: ['unfurl', url],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<Unfurl>('unfurl_url', { url });
+    },
+    enabled: !!url && !!(window as any).__TAURI__,
+    staleTime: Infinity,
+    retry: false,
+  });
+}
+
 // ─── Search ─────────────────────────────────────────────────────────────────
 export function useSearch(query: string, enabled = true) {
   return useQuery<SearchResponse>({
@@ -191,6 +283,16 @@ export function useDeleteTag() {
   });
 }
 
+/** Shell-only: the OS color dialog; `#rrggbb`, or null if cancelled. */
+export function usePickColor() {
+  return useMutation({
+    mutationFn: async (initial?: string) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<string | null>('pick_color', { initial: initial ?? null });
+    },
+  });
+}
+
 // ─── Collections ────────────────────────────────────────────────────────────
 export function useCollections() {
   return useQuery<{ items: CollectionOutWithCount[]; total: number }>({
@@ -301,7 +403,14 @@ export function useRunBackup() {
 export function useRestoreBackup() {
   const qc = useQueryClient();
   return useMutation({
-    mutationFn: (name: string) => backupAPI.restore(name),
+    mutationFn: async (name: string) => {
+      // In the app the shell restores with the backend stopped, then restarts it.
+      if ((window as any).__TAURI__) {
+        const { invoke } = await import('@tauri-apps/api/tauri');
+        return invoke('restore_backup', { name });
+      }
+      return backupAPI.restore(name);
+    },
     onSuccess: () => {
       qc.invalidateQueries();
     },
@@ -316,6 +425,1692 @@ export function useExportJson() {
   });
 }
 
+// Shell imports/exports run as tasks: the command returns a task id and the
+// shell reports progress as `task-progress:{id}` until the task ends.
+export interface TaskStatus<T = unknown> {
+  id: number;
+  kind: string;
+  state: 'running' | 'done' | 'failed' | 'cancelled';
+  done: number;
+  total: number | null;
+  current: string | null;
+  cancel_requested: boolean;
+  /** Partial when cancelled. */
+  result: T | null;
+  error: string | null;
+}
+
+export type TaskProgress = (status: TaskStatus) => void;
+
+/**
+ * Shell-only: runs a task command and resolves with its final status (null
+ * when the command started nothing, e.g. a cancelled dialog). Rejects when
+ * the task fails; a cancelled task resolves with its partial result.
+ */
+export async function runTask<T>(
+  command: string,
+  args: Record<string, unknown>,
+  onProgress?: TaskProgress,
+): Promise<TaskStatus<T> | null> {
+  const { invoke } = await import('@tauri-apps/api/tauri');
+  const { listen } = await import('@tauri-apps/api/event');
+  const id = await invoke<number | null>(command, args);
+  if (id == null) return null;
+  return new Promise((resolve, reject) => {
+    let settled = false;
+    let unlisten: (() => void) | undefined;
+    const handle = (status: TaskStatus<T>) => {
+      if (settled) return;
+      onProgress?.(status as TaskStatus);
+      if (status.state === 'running') return;
+      settled = true;
+      unlisten?.();
+      if (status.state === 'failed') reject(status.error);
+      else resolve(status);
+    };
+    listen<TaskStatus<T>>(`task-progress:${id}`, (e) => handle(e.payload)).then((fn) => {
+      unlisten = fn;
+      if (settled) fn();
+      // Catches up if the task moved on (or ended) before we subscribed.
+      invoke<TaskStatus<T> | null>('get_task', { id }).then((status) => status && handle(status));
+    });
+  });
+}
+
+/** Shell-only: asks a running task to stop. */
+export async function cancelTask(id: number) {
+  const { invoke } = await import('@tauri-apps/api/tauri');
+  return invoke('cancel_task', { id });
+}
+
+export interface JobStatus {
+  /** "task:<task id>" for tasks, "job:<n>" for the rest. */
+  id: string;
+  kind: string;
+  label: string;
+  state: TaskStatus['state'];
+  ai: boolean;
+  done: number;
+  total: number | null;
+  current: string | null;
+  error: string | null;
+  snippet_id: string | null;
+  started_at: string;
+  finished_at: string | null;
+}
+
+/**
+ * Shell-only: every background job, kept current from the shell's
+ * `job-started` / `job-progress` / `job-finished` events; nothing polls.
+ */
+export function useJobs(enabled: boolean) {
+  const qc = useQueryClient();
+  useEffect(() => {
+    if (!enabled) return;
+    const put = (job: JobStatus) =>
+      qc.setQueryData<JobStatus[]>(['jobs'], (jobs = []) => {
+        const i = jobs.findIndex((j) => j.id === job.id);
+        return i < 0 ? [...jobs, job] : jobs.map((j, k) => (k === i ? job : j));
+      });
+    const unlisten = import('@tauri-apps/api/event').then(({ listen }) =>
+      Promise.all(
+        ['job-started', 'job-progress', 'job-finished'].map((event) =>
+          listen<JobStatus>(event, (e) => put(e.payload)),
+        ),
+      ),
+    );
+    return () => {
+      unlisten.then((fns) => fns.forEach((fn) => fn()));
+    };
+  }, [enabled, qc]);
+  return useQuery<JobStatus[]>({
+    queryKey: ['jobs'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<JobStatus[]>('list_jobs');
+    },
+    enabled,
+    staleTime: Infinity,
+  });
+}
+
+export interface Reminder {
+  id: string;
+  snippet_id: string;
+  title: string;
+  note: string;
+  /** RFC 3339. */
+  due_at: string;
+  /** "due" once it has fired, until snoozed or dismissed. */
+  state: 'scheduled' | 'due';
+  snoozes: number;
+}
+
+/** Shell-only: reminders, soonest first, kept current from `reminders-changed`. */
+export function useReminders(enabled: boolean) {
+  const qc = useQueryClient();
+  useEffect(() => {
+    if (!enabled) return;
+    const unlisten = import('@tauri-apps/api/event').then(({ listen }) =>
+      listen<Reminder[]>('reminders-changed', (e) => qc.setQueryData(['reminders'], e.payload)),
+    );
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [enabled, qc]);
+  return useQuery<Reminder[]>({
+    queryKey: ['reminders'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<Reminder[]>('list_reminders');
+    },
+    enabled,
+    staleTime: Infinity,
+  });
+}
+
+export function useSetReminder() {
+  return useMutation({
+    mutationFn: async (args: { snippetId: string; title: string; dueAt: Date; note?: string }) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<Reminder>('set_reminder', {
+        snippetId: args.snippetId,
+        title: args.title,
+        dueAt: args.dueAt.toISOString(),
+        note: args.note ?? null,
+      });
+    },
+  });
+}
+
+export function useSnoozeReminder() {
+  return useMutation({
+    mutationFn: async ({ id, minutes }: { id: string; minutes?: number }) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<Reminder>('snooze_reminder', { id, minutes: minutes ?? null });
+    },
+  });
+}
+
+export function useDismissReminder() {
+  return useMutation({
+    mutationFn: async (id: string) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke('dismiss_reminder', { id });
+    },
+  });
+}
+
+export type DuplicatePolicy = 'skip' | 'merge' | 'flag';
+
+/** What the duplicate policy did during a shell import. */
+export interface DuplicateCounts {
+  policy: DuplicatePolicy;
+  found: number;
+  skipped: number;
+  merged: number;
+  flagged: number;
+}
+
+/** Shell-only: how imports treat items already in the library. */
+export function useDuplicatePolicy(enabled: boolean) {
+  return useQuery<DuplicatePolicy>({
+    queryKey: ['duplicate-policy'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      const config = await invoke<{ dedupe: { policy: DuplicatePolicy } }>('get_shell_config');
+      return config.dedupe.policy;
+    },
+    enabled,
+  });
+}
+
+export function useSetDuplicatePolicy() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (policy: DuplicatePolicy) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      await invoke('update_shell_config', { patch: { dedupe: { policy } } });
+      return policy;
+    },
+    onSuccess: (policy) => qc.setQueryData(['duplicate-policy'], policy),
+  });
+}
+
+/** Shell-only: saves a passphrase-encrypted JSON export (null if cancelled). */
+export function useExportEncrypted() {
+  return useMutation({
+    mutationFn: ({ passphrase, onProgress }: { passphrase: string; onProgress?: TaskProgress }) =>
+      runTask<{ path: string; size_bytes: number }>(
+        'export_encrypted',
+        { passphrase },
+        onProgress,
+      ),
+  });
+}
+
+/** Shell-only: picks an encrypted export and imports it (null if cancelled). */
+export function useImportEncrypted() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: ({ passphrase, onProgress }: { passphrase: string; onProgress?: TaskProgress }) =>
+      runTask<ImportResponse & { duplicates: DuplicateCounts }>(
+        'import_encrypted',
+        { passphrase },
+        onProgress,
+      ),
+    onSuccess: () => qc.invalidateQueries(),
+  });
+}
+
+/** Shell-only: ZIP of the JSON export plus linked attachments (null if cancelled). */
+export function useExportArchive() {
+  return useMutation({
+    mutationFn: async ({ onProgress }: { onProgress?: TaskProgress } = {}) => {
+      const { save } = await import('@tauri-apps/api/dialog');
+      const path = await save({
+        defaultPath: 'pinup-archive.zip',
+        filters: [{ name: 'ZIP archive', extensions: ['zip'] }],
+      });
+      if (!path) return null;
+      return runTask<{ path: string; size_bytes: number; attachments: number; missing: string[] }>(
+        'export_archive',
+        { path },
+        onProgress,
+      );
+    },
+  });
+}
+
+/** Shell-only: one Markdown file per snippet in a picked folder (null if cancelled). */
+export function useExportMarkdownFolder() {
+  return useMutation({
+    mutationFn: async ({ onProgress }: { onProgress?: TaskProgress } = {}) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      const path = await invoke<string | null>('show_save_dialog', { folder: true });
+      if (!path) return null;
+      return runTask<{ path: string; files: number; attachments: number; missing: string[] }>(
+        'export_markdown_folder',
+        { path },
+        onProgress,
+      );
+    },
+  });
+}
+
+export interface ObsidianStatus {
+  root: string | null;
+  syncing: boolean;
+  last_sync: { at: string; written: number; unchanged: number; removed: number; conflicts: string[] } | null;
+  last_error: string | null;
+}
+
+/** Shell-only: state of the Obsidian vault mirror. */
+export function useObsidianStatus(enabled: boolean) {
+  return useQuery<ObsidianStatus>({
+    queryKey: ['obsidian'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<ObsidianStatus>('get_obsidian_status');
+    },
+    enabled,
+    refetchInterval: 60_000,
+  });
+}
+
+/** Shell-only: picks a vault folder to mirror into; `stop` turns the mirror off. */
+export function useSetObsidianVault() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (stop: boolean) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      const vault = stop ? null : await invoke<string | null>('show_save_dialog', { folder: true });
+      if (!stop && !vault) return null;
+      return invoke<ObsidianStatus>('set_obsidian_vault', { vault });
+    },
+    onSuccess: () => qc.invalidateQueries({ queryKey: ['obsidian'] }),
+  });
+}
+
+export interface GitSyncReport {
+  at: string;
+  commit: string | null;
+  committed: number;
+  created: number;
+  updated: number;
+  deleted: number;
+  pending: number;
+  conflicts: number;
+  pushed: boolean;
+}
+
+export interface GitSyncStatus {
+  config: { enabled: boolean; repo: string | null; remote: string | null; branch: string; encrypt: boolean };
+  syncing: boolean;
+  last_sync: GitSyncReport | null;
+  last_error: string | null;
+}
+
+/** Shell-only: state of sync through a Git repository. */
+export function useGitSyncStatus(enabled: boolean) {
+  return useQuery<GitSyncStatus>({
+    queryKey: ['git-sync'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<GitSyncStatus>('get_git_sync_status');
+    },
+    enabled,
+    refetchInterval: 60_000,
+  });
+}
+
+/**
+ * Shell-only: points sync at a repository folder (picked when `repo` is
+ * left out) with an optional remote URL; `stop` turns sync off. `encrypt`
+ * left out keeps the current choice.
+ */
+export function useSetGitSync() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({
+      stop = false,
+      repo,
+      remote,
+      encrypt,
+    }: {
+      stop?: boolean;
+      repo?: string;
+      remote: string | null;
+      encrypt?: boolean;
+    }) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      const picked = stop ? null : repo ?? (await invoke<string | null>('show_save_dialog', { folder: true }));
+      if (!stop && !picked) return null;
+      return invoke<GitSyncStatus>('set_git_sync', { repo: picked, remote, branch: null, encrypt: encrypt ?? null });
+    },
+    onSettled: () => qc.invalidateQueries({ queryKey: ['git-sync'] }),
+  });
+}
+
+export function useSyncGitNow() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<GitSyncReport>('sync_git_now');
+    },
+    onSettled: () => qc.invalidateQueries({ queryKey: ['git-sync'] }),
+  });
+}
+
+export interface SyncKeyStatus {
+  has_key: boolean;
+  /** Short fingerprint, the same on every device holding the key. */
+  key_id: string | null;
+}
+
+/** Shell-only: whether this device holds the sync key. */
+export function useSyncKeyStatus(enabled: boolean) {
+  return useQuery<SyncKeyStatus>({
+    queryKey: ['sync-key'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<SyncKeyStatus>('get_sync_key_status');
+    },
+    enabled,
+  });
+}
+
+function useSyncKeyCommand<Args, Result>(command: string, toArgs: (args: Args) => Record<string, unknown>) {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (args: Args) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<Result>(command, toArgs(args));
+    },
+    onSettled: () => qc.invalidateQueries({ queryKey: ['sync-key'] }),
+  });
+}
+
+/** Shell-only: makes a sync key and returns its recovery phrase. */
+export function useCreateSyncKey() {
+  return useSyncKeyCommand<{ replace: boolean }, string>('create_sync_key', (args) => args);
+}
+
+export function useRecoveryPhrase() {
+  return useSyncKeyCommand<void, string>('get_recovery_phrase', () => ({}));
+}
+
+/** Shell-only: saves the key a recovery phrase from another device stands for. */
+export function useRestoreSyncKey() {
+  return useSyncKeyCommand<string, SyncKeyStatus>('restore_sync_key', (phrase) => ({ phrase }));
+}
+
+/** Shell-only: saves the key sealed under a passphrase; null when cancelled. */
+export function useBackupSyncKey() {
+  return useSyncKeyCommand<string, string | null>('backup_sync_key', (passphrase) => ({ passphrase }));
+}
+
+export function useRestoreSyncKeyBackup() {
+  return useSyncKeyCommand<string, SyncKeyStatus | null>('restore_sync_key_backup', (passphrase) => ({
+    passphrase,
+  }));
+}
+
+export function useForgetSyncKey() {
+  return useSyncKeyCommand<void, SyncKeyStatus>('forget_sync_key', () => ({}));
+}
+
+/** One run of the encrypted change-log sync (WebDAV or S3). */
+export interface SyncReport {
+  at: string;
+  uploaded: number;
+  applied: number;
+  created: number;
+  updated: number;
+  deleted: number;
+  skipped: number;
+  conflicts: number;
+  snapshot: boolean;
+  restored_from: string | null;
+}
+
+export interface WebDavStatus {
+  config: { enabled: boolean; url: string | null; username: string | null; interval_minutes: number };
+  /** The password and passphrase are saved in the OS keyring. */
+  has_credentials: boolean;
+  syncing: boolean;
+  last_sync: SyncReport | null;
+  last_error: string | null;
+}
+
+/** Shell-only: state of encrypted sync through a WebDAV server. */
+export function useWebDavStatus(enabled: boolean) {
+  return useQuery<WebDavStatus>({
+    queryKey: ['webdav'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<WebDavStatus>('get_webdav_status');
+    },
+    enabled,
+    refetchInterval: 60_000,
+  });
+}
+
+/** Shell-only: turns WebDAV sync on; empty secrets keep the saved ones. */
+export function useSetWebDav() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (args: { url: string; username: string; password: string; passphrase: string }) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<WebDavStatus>('set_webdav', {
+        url: args.url,
+        username: args.username,
+        password: args.password || null,
+        passphrase: args.passphrase || null,
+      });
+    },
+    onSettled: () => qc.invalidateQueries({ queryKey: ['webdav'] }),
+  });
+}
+
+/** Shell-only: turns WebDAV sync off; `forget` removes the saved secrets. */
+export function useDisableWebDav() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (forget: boolean) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<WebDavStatus>('disable_webdav', { forget });
+    },
+    onSuccess: (status) => qc.setQueryData(['webdav'], status),
+  });
+}
+
+export function useWebDavSyncNow() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<SyncReport>('sync_now');
+    },
+    onSettled: () => qc.invalidateQueries({ queryKey: ['webdav'] }),
+  });
+}
+
+export interface S3Status {
+  config: {
+    enabled: boolean;
+    endpoint: string | null;
+    region: string;
+    bucket: string | null;
+    prefix: string;
+    path_style: boolean;
+    interval_minutes: number;
+  };
+  /** The access keys and passphrase are saved in the OS keyring. */
+  has_credentials: boolean;
+  syncing: boolean;
+  last_sync: SyncReport | null;
+  last_error: string | null;
+}
+
+/** Shell-only: state of encrypted sync through S3-compatible storage. */
+export function useS3Status(enabled: boolean) {
+  return useQuery<S3Status>({
+    queryKey: ['s3-sync'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<S3Status>('get_s3_status');
+    },
+    enabled,
+    refetchInterval: 60_000,
+  });
+}
+
+/** Shell-only: turns S3 sync on; empty secrets keep the saved ones. */
+export function useSetS3Sync() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (args: {
+      endpoint: string;
+      region: string;
+      bucket: string;
+      prefix: string;
+      pathStyle: boolean;
+      accessKeyId: string;
+      secretAccessKey: string;
+      passphrase: string;
+    }) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<S3Status>('set_s3_sync', {
+        endpoint: args.endpoint,
+        region: args.region || null,
+        bucket: args.bucket,
+        prefix: args.prefix,
+        pathStyle: args.pathStyle,
+        accessKeyId: args.accessKeyId || null,
+        secretAccessKey: args.secretAccessKey || null,
+        passphrase: args.passphrase || null,
+      });
+    },
+    onSettled: () => qc.invalidateQueries({ queryKey: ['s3-sync'] }),
+  });
+}
+
+/** Shell-only: turns S3 sync off; `forget` removes the saved secrets. */
+export function useDisableS3Sync() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (forget: boolean) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<S3Status>('disable_s3_sync', { forget });
+    },
+    onSuccess: (status) => qc.setQueryData(['s3-sync'], status),
+  });
+}
+
+export function useS3SyncNow() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<SyncReport>('sync_s3_now');
+    },
+    onSettled: () => qc.invalidateQueries({ queryKey: ['s3-sync'] }),
+  });
+}
+
+export interface LanPeer {
+  device: string;
+  name: string;
+  /** The peer hosts the log this device syncs with; otherwise it syncs with ours. */
+  host: boolean;
+  paired_at: number;
+  online: boolean;
+  last_contact: string | null;
+  last_sync: SyncReport | null;
+  last_error: string | null;
+}
+
+export interface LanSyncStatus {
+  enabled: boolean;
+  device_id: string | null;
+  device_name: string;
+  listening: boolean;
+  pairing: { code: string; expires_at: number } | null;
+  peers: LanPeer[];
+  /** Unpaired devices advertising LAN sync. */
+  nearby: { device: string; name: string }[];
+  syncing: boolean;
+  last_sync: SyncReport | null;
+  last_error: string | null;
+  network_error: string | null;
+}
+
+/** Shell-only: paired devices, nearby devices and the pairing code. */
+export function useLanSyncStatus(enabled: boolean) {
+  return useQuery<LanSyncStatus>({
+    queryKey: ['lan-sync'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<LanSyncStatus>('get_lan_sync_status');
+    },
+    enabled,
+    // Fast enough to see a nearby device or a finished pairing.
+    refetchInterval: 5_000,
+  });
+}
+
+function useLanSyncCommand<Args>(command: string, toArgs: (args: Args) => Record<string, unknown>) {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (args: Args) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<LanSyncStatus>(command, toArgs(args));
+    },
+    onSuccess: (status) => qc.setQueryData(['lan-sync'], status),
+  });
+}
+
+/** Shell-only: turns LAN sync on or off; an empty name uses the host name. */
+export function useSetLanSync() {
+  return useLanSyncCommand('set_lan_sync', (args: { enabled: boolean; deviceName?: string }) => ({
+    enabled: args.enabled,
+    deviceName: args.deviceName ?? null,
+  }));
+}
+
+/** Shell-only: shows a pairing code for five minutes. */
+export function useStartLanPairing() {
+  return useLanSyncCommand('start_lan_pairing', () => ({}));
+}
+
+export function useCancelLanPairing() {
+  return useLanSyncCommand('cancel_lan_pairing', () => ({}));
+}
+
+/** Shell-only: pairs with a nearby device using the code it shows. */
+export function usePairLanDevice() {
+  return useLanSyncCommand('pair_lan_device', (args: { device: string; code: string }) => args);
+}
+
+export function useRemoveLanPeer() {
+  return useLanSyncCommand('remove_lan_peer', (args: { device: string; host: boolean }) => args);
+}
+
+export function useLanSyncNow() {
+  return useLanSyncCommand('sync_lan_now', () => ({}));
+}
+
+export interface CompanionDevice {
+  device: string;
+  name: string;
+  paired_at: number;
+  last_capture: string | null;
+}
+
+export interface CompanionStatus {
+  enabled: boolean;
+  port: number;
+  /** Where phones reach this device, while listening. */
+  address: string | null;
+  listening: boolean;
+  /** `qr_svg` holds `link`, for the phone to scan. */
+  pairing: { link: string; qr_svg: string; expires_at: number } | null;
+  devices: CompanionDevice[];
+  network_error: string | null;
+}
+
+/** Shell-only: paired phones and the pairing QR code. */
+export function useCompanionStatus(enabled: boolean) {
+  return useQuery<CompanionStatus>({
+    queryKey: ['companion'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<CompanionStatus>('get_companion_status');
+    },
+    enabled,
+    // Fast enough to see a finished pairing.
+    refetchInterval: 5_000,
+  });
+}
+
+function useCompanionCommand<Args>(command: string, toArgs: (args: Args) => Record<string, unknown>) {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (args: Args) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<CompanionStatus>(command, toArgs(args));
+    },
+    onSuccess: (status) => qc.setQueryData(['companion'], status),
+  });
+}
+
+/** Shell-only: turns the phone listener on or off, optionally on another port. */
+export function useSetCompanion() {
+  return useCompanionCommand('set_companion', (args: { enabled: boolean; port?: number }) => ({
+    enabled: args.enabled,
+    port: args.port ?? null,
+  }));
+}
+
+/** Shell-only: shows a pairing QR code for five minutes. */
+export function useStartCompanionPairing() {
+  return useCompanionCommand('start_companion_pairing', () => ({}));
+}
+
+export function useCancelCompanionPairing() {
+  return useCompanionCommand('cancel_companion_pairing', () => ({}));
+}
+
+export function useRemoveCompanionDevice() {
+  return useCompanionCommand('remove_companion_device', (device: string) => ({ device }));
+}
+
+/** A snippet two devices changed without seeing each other's change. */
+export interface SyncConflict {
+  id: string;
+  snippet_id: string;
+  title: string;
+  /** "Git", "WebDAV", "S3" or "LAN". */
+  source: string;
+  detected_at: number;
+  /** This device's version; null when it was deleted here. */
+  local: Record<string, unknown> | null;
+  /** The other device's version; null when it was deleted there. */
+  remote: Record<string, unknown> | null;
+  /** The version the sync left in the library. */
+  kept: 'local' | 'remote';
+}
+
+export type ConflictStrategy = 'keep_local' | 'keep_remote' | 'keep_both' | 'merge' | 'dismiss';
+
+/** Shell-only: sync conflicts waiting for review, newest first. */
+export function useSyncConflicts(enabled: boolean) {
+  return useQuery<SyncConflict[]>({
+    queryKey: ['sync-conflicts'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<SyncConflict[]>('list_conflicts');
+    },
+    enabled,
+    refetchInterval: 60_000,
+  });
+}
+
+/** Shell-only: settles a conflict; `merged` is the version saved by 'merge'. */
+export function useResolveConflict() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (args: { id: string; strategy: ConflictStrategy; merged?: Record<string, unknown> }) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<SyncConflict[]>('resolve_conflict', {
+        id: args.id,
+        strategy: args.strategy,
+        merged: args.merged ?? null,
+      });
+    },
+    onSuccess: (conflicts) => {
+      qc.setQueryData(['sync-conflicts'], conflicts);
+      qc.invalidateQueries({ queryKey: ['snippets'] });
+    },
+  });
+}
+
+export interface WatchFolder {
+  path: string;
+  enabled: boolean;
+  /** Lowercase, without the dot; empty picks up every supported file. */
+  extensions: string[];
+  tag: string | null;
+  recursive: boolean;
+  delete_after_import: boolean;
+}
+
+export interface WatchFolderStatus extends WatchFolder {
+  watching: boolean;
+  imported: number;
+  last_import: string | null;
+  last_error: string | null;
+}
+
+/** Shell-only: folders whose new files are imported automatically. */
+export function useWatchFolders(enabled: boolean) {
+  return useQuery<WatchFolderStatus[]>({
+    queryKey: ['watch-folders'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<WatchFolderStatus[]>('get_watch_folders');
+    },
+    enabled,
+    refetchInterval: 30_000,
+  });
+}
+
+/** Shell-only: adds a folder or updates its rules (matched by path). */
+export function useSetWatchFolder() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (folder: WatchFolder) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<WatchFolderStatus[]>('set_watch_folder', { folder });
+    },
+    onSuccess: (folders) => qc.setQueryData(['watch-folders'], folders),
+  });
+}
+
+export function useRemoveWatchFolder() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (path: string) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<WatchFolderStatus[]>('remove_watch_folder', { path });
+    },
+    onSuccess: (folders) => qc.setQueryData(['watch-folders'], folders),
+  });
+}
+
+export interface Feed {
+  url: string;
+  title: string | null;
+  enabled: boolean;
+  /** Overrides the default collection. */
+  collection: string | null;
+  tags: string[];
+  /** Keep each entry's full article rather than the feed's text. */
+  full_article: boolean;
+  interval_minutes: number | null;
+}
+
+export interface FeedStatus extends Feed {
+  /** Unix seconds. */
+  checked_at: number | null;
+  last_error: string | null;
+  imported: number;
+}
+
+export interface FeedsStatus {
+  enabled: boolean;
+  interval_minutes: number;
+  collection: string;
+  refreshing: boolean;
+  feeds: FeedStatus[];
+}
+
+/** Shell-only: RSS/Atom subscriptions filed into the library. */
+export function useFeeds(enabled: boolean) {
+  return useQuery<FeedsStatus>({
+    queryKey: ['feeds'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<FeedsStatus>('get_feeds');
+    },
+    enabled,
+    refetchInterval: 30_000,
+  });
+}
+
+export function useSetFeeds() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (settings: { enabled?: boolean; intervalMinutes?: number; collection?: string }) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<FeedsStatus>('set_feeds', settings);
+    },
+    onSuccess: (status) => qc.setQueryData(['feeds'], status),
+  });
+}
+
+/** Shell-only: subscribes to a feed (or a page linking to one) or updates its settings. */
+export function useSetFeed() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (feed: Feed) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<FeedsStatus>('set_feed', { feed });
+    },
+    onSuccess: (status) => qc.setQueryData(['feeds'], status),
+  });
+}
+
+export function useRemoveFeed() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (url: string) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<FeedsStatus>('remove_feed', { url });
+    },
+    onSuccess: (status) => qc.setQueryData(['feeds'], status),
+  });
+}
+
+export function useRefreshFeeds() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<{ checked: number; added: number; failed: number }>('refresh_feeds');
+    },
+    onSuccess: () => {
+      qc.invalidateQueries({ queryKey: ['feeds'] });
+      qc.invalidateQueries({ queryKey: ['snippets'] });
+      qc.invalidateQueries({ queryKey: ['stats'] });
+    },
+  });
+}
+
+export interface EmailInConfig {
+  enabled: boolean;
+  host: string | null;
+  port: number;
+  /** STARTTLS on `port` rather than TLS from the start. */
+  starttls: boolean;
+  username: string | null;
+  folder: string;
+  interval_minutes: number;
+  collection: string | null;
+  tags: string[];
+  delete_after_import: boolean;
+}
+
+export interface EmailCheck {
+  imported: number;
+  skipped: number;
+}
+
+export interface EmailInStatus {
+  config: EmailInConfig;
+  /** The password is saved in the OS keyring. */
+  has_password: boolean;
+  checking: boolean;
+  last_check: EmailCheck | null;
+  last_error: string | null;
+}
+
+/** Shell-only: state of importing mail from an IMAP folder. */
+export function useEmailInStatus(enabled: boolean) {
+  return useQuery<EmailInStatus>({
+    queryKey: ['email-in'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<EmailInStatus>('get_email_in_status');
+    },
+    enabled,
+    refetchInterval: 60_000,
+  });
+}
+
+/** Shell-only: signs in with `settings` and turns email in on; an empty password keeps the saved one. */
+export function useSetEmailIn() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (args: { settings: EmailInConfig; password: string }) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<EmailInStatus>('set_email_in', {
+        settings: args.settings,
+        password: args.password || null,
+      });
+    },
+    onSettled: () => qc.invalidateQueries({ queryKey: ['email-in'] }),
+  });
+}
+
+/** Shell-only: turns email in off; `forget` removes the saved password. */
+export function useDisableEmailIn() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (forget: boolean) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<EmailInStatus>('disable_email_in', { forget });
+    },
+    onSuccess: (status) => qc.setQueryData(['email-in'], status),
+  });
+}
+
+export function useCheckEmailNow() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<EmailCheck>('check_email_now');
+    },
+    onSettled: () => {
+      qc.invalidateQueries({ queryKey: ['email-in'] });
+      qc.invalidateQueries({ queryKey: ['snippets'] });
+    },
+  });
+}
+
+export interface NativeHostBrowser {
+  name: string;
+  /** The browser looks installed for this user. */
+  installed: boolean;
+  registered: boolean;
+}
+
+export interface NativeHostStatus {
+  enabled: boolean;
+  chrome_extensions: string[];
+  firefox_extensions: string[];
+  host_name: string;
+  listening: boolean;
+  browsers: NativeHostBrowser[];
+  /** RFC 3339. */
+  last_message: string | null;
+  error: string | null;
+}
+
+/** Shell-only: the native messaging host browser extensions send clips through. */
+export function useNativeHostStatus(enabled: boolean) {
+  return useQuery<NativeHostStatus>({
+    queryKey: ['native-host'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<NativeHostStatus>('get_native_host_status');
+    },
+    enabled,
+    refetchInterval: 30_000,
+  });
+}
+
+/** Shell-only: turns the host on or off; extension lists left out stay as they are. */
+export function useSetNativeHost() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (args: { enabled: boolean; chromeExtensions?: string[]; firefoxExtensions?: string[] }) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<NativeHostStatus>('set_native_host', {
+        enabled: args.enabled,
+        chromeExtensions: args.chromeExtensions ?? null,
+        firefoxExtensions: args.firefoxExtensions ?? null,
+      });
+    },
+    onSuccess: (status) => qc.setQueryData(['native-host'], status),
+  });
+}
+
+export interface DeepLinksStatus {
+  enabled: boolean;
+  /** Schemes x-success / x-error may call back, e.g. "raycast". */
+  callback_schemes: string[];
+  scheme: string;
+  /** The OS sends pinup:// links to this app. */
+  registered: boolean;
+  error: string | null;
+}
+
+/** Shell-only: pinup:// link handling for launchers. */
+export function useDeepLinks(enabled: boolean) {
+  return useQuery<DeepLinksStatus>({
+    queryKey: ['deep-links'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<DeepLinksStatus>('get_deep_links');
+    },
+    enabled,
+  });
+}
+
+/** Shell-only: turns links on or off; `callbackSchemes` left out stays as is. */
+export function useSetDeepLinks() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (args: { enabled: boolean; callbackSchemes?: string[] }) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<DeepLinksStatus>('set_deep_links', {
+        enabled: args.enabled,
+        callbackSchemes: args.callbackSchemes ?? null,
+      });
+    },
+    onSuccess: (status) => qc.setQueryData(['deep-links'], status),
+  });
+}
+
+export interface AutostartStatus {
+  enabled: boolean;
+  /** Starts to the tray without opening the window. */
+  start_hidden: boolean;
+}
+
+/** Shell-only: whether the app launches at login. */
+export function useAutostart(enabled: boolean) {
+  return useQuery<AutostartStatus>({
+    queryKey: ['autostart'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<AutostartStatus>('get_autostart');
+    },
+    enabled,
+  });
+}
+
+export function useSetAutostart() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({ enabled, start_hidden }: AutostartStatus) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<AutostartStatus>('set_autostart', { enabled, startHidden: start_hidden });
+    },
+    onSuccess: (status) => qc.setQueryData(['autostart'], status),
+  });
+}
+
+export interface AppLockStatus {
+  enabled: boolean;
+  /** Minutes of system idle time before the app locks. */
+  idle_minutes: number;
+  locked: boolean;
+  has_passcode: boolean;
+  /** False where idle time can't be read; locking by hand still works. */
+  idle_supported: boolean;
+  /** Offer Touch ID / Windows Hello on the lock prompt. */
+  biometrics: boolean;
+  /** "Touch ID" or "Windows Hello" when set up on this machine. */
+  biometrics_name: string | null;
+}
+
+/** Shell-only: the passcode lock that kicks in after idle time. */
+export function useAppLockStatus(enabled: boolean) {
+  return useQuery<AppLockStatus>({
+    queryKey: ['app-lock'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<AppLockStatus>('get_app_lock_status');
+    },
+    enabled,
+  });
+}
+
+export interface EncryptionStatus {
+  enabled: boolean;
+  unlocked: boolean;
+  database_encrypted: boolean;
+  /** Unencrypted copies of the database still on disk. */
+  plaintext_copies: string[];
+}
+
+/** Shell-only: SQLCipher database encryption. */
+export function useEncryptionStatus(enabled: boolean) {
+  return useQuery<EncryptionStatus>({
+    queryKey: ['encryption'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<EncryptionStatus>('get_encryption_status');
+    },
+    enabled,
+  });
+}
+
+/** Overwrites and deletes the unencrypted copies. */
+export function usePurgePlaintextCopies() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<EncryptionStatus>('purge_plaintext_copies');
+    },
+    onSuccess: (status) => qc.setQueryData(['encryption'], status),
+  });
+}
+
+/** A non-empty `passcode` replaces the current one. */
+export function useSetAppLock() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({
+      enabled,
+      idle_minutes,
+      passcode,
+    }: {
+      enabled: boolean;
+      idle_minutes: number;
+      passcode?: string;
+    }) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<AppLockStatus>('set_app_lock', { enabled, idleMinutes: idle_minutes, passcode: passcode || null });
+    },
+    onSuccess: (status) => qc.setQueryData(['app-lock'], status),
+  });
+}
+
+export function useSetAppLockBiometrics() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (enabled: boolean) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<AppLockStatus>('set_app_lock_biometrics', { enabled });
+    },
+    onSuccess: (status) => qc.setQueryData(['app-lock'], status),
+  });
+}
+
+export function useLockApp() {
+  return useMutation({
+    mutationFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<void>('lock_app');
+    },
+  });
+}
+
+export interface LocaleStatus {
+  /** The chosen language code; null follows the OS. */
+  locale: string | null;
+  /** The language in use. */
+  resolved: string;
+  available: { code: string; name: string }[];
+}
+
+/** Shell-only: the language of the tray, dialogs and notifications. */
+export function useLocale(enabled: boolean) {
+  return useQuery<LocaleStatus>({
+    queryKey: ['locale'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<LocaleStatus>('get_locale');
+    },
+    enabled,
+  });
+}
+
+export function useSetLocale() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (locale: string | null) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<LocaleStatus>('set_locale', { locale });
+    },
+    onSuccess: (status) => qc.setQueryData(['locale'], status),
+  });
+}
+
+export interface ProfileInfo {
+  id: string;
+  name: string;
+  /** Its data folder. */
+  path: string;
+  /** Moved away from its original folder; deleting it keeps that folder. */
+  moved: boolean;
+  active: boolean;
+}
+
+export interface ProfilesStatus {
+  active: string;
+  profiles: ProfileInfo[];
+}
+
+/** Shell-only: workspaces with separate databases and settings. */
+export function useProfiles(enabled: boolean) {
+  return useQuery<ProfilesStatus>({
+    queryKey: ['profiles'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<ProfilesStatus>('list_profiles');
+    },
+    enabled,
+  });
+}
+
+export function useCreateProfile() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (name: string) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<ProfilesStatus>('create_profile', { name });
+    },
+    onSuccess: (status) => qc.setQueryData(['profiles'], status),
+  });
+}
+
+/** Restarts the app on the chosen profile. */
+export function useSwitchProfile() {
+  return useMutation({
+    mutationFn: async (name: string) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<void>('switch_profile', { name });
+    },
+  });
+}
+
+export function useDeleteProfile() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (id: string) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<ProfilesStatus>('delete_profile', { id });
+    },
+    onSuccess: (status) => qc.setQueryData(['profiles'], status),
+  });
+}
+
+export interface DataLocation {
+  /** The active profile's data folder. */
+  path: string;
+  /** Where it lives unless moved. */
+  home: string;
+  moved: boolean;
+  /** PINUP_DATA_DIR, when set. */
+  env_override: string | null;
+}
+
+/** Shell-only: where the active profile's notes are kept. */
+export function useDataLocation(enabled: boolean) {
+  return useQuery({
+    queryKey: ['data-location'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<DataLocation>('get_data_location');
+    },
+    enabled,
+  });
+}
+
+/**
+ * Shell-only: copies the profile's data to a picked folder, verifies it and
+ * restarts the app there (null if the dialog was cancelled).
+ */
+export function useMoveDataDir() {
+  return useMutation({
+    mutationFn: ({ onProgress }: { onProgress?: TaskProgress } = {}) =>
+      runTask<{ path: string; files: number; size_bytes: number }>(
+        'move_data_dir',
+        { path: null },
+        onProgress,
+      ),
+  });
+}
+
+/**
+ * Shell-only: points the profile at a folder that already holds its data
+ * (picked in a dialog), or back at its original folder with `reset`, and
+ * restarts. Nothing is copied; null if the dialog was cancelled.
+ */
+export function useSetDataDir() {
+  return useMutation({
+    mutationFn: async ({ reset }: { reset?: boolean } = {}) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      let path: string | null = null;
+      if (!reset) {
+        const { open } = await import('@tauri-apps/api/dialog');
+        const picked = await open({ directory: true, title: 'Use Data Folder' });
+        if (typeof picked !== 'string') return null;
+        path = picked;
+      }
+      return invoke<DataLocation>('set_data_dir', { path });
+    },
+  });
+}
+
+export interface SendToStatus {
+  enabled: boolean;
+  /** Explorer has these entries on Windows only. */
+  supported: boolean;
+  registered: boolean;
+  error: string | null;
+}
+
+/** Shell-only: "Send to Pin-Up AI" in Windows Explorer. */
+export function useSendToStatus(enabled: boolean) {
+  return useQuery<SendToStatus>({
+    queryKey: ['send-to'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<SendToStatus>('get_send_to_status');
+    },
+    enabled,
+  });
+}
+
+/** Shell-only: adds or removes the Explorer entries. */
+export function useSetSendTo() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (enabled: boolean) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<SendToStatus>('set_send_to', { enabled });
+    },
+    onSuccess: (status) => qc.setQueryData(['send-to'], status),
+  });
+}
+
+export type TrayClickAction = 'none' | 'show_window' | 'quick_capture' | 'paste_last' | 'show_menu';
+
+export type CloseAction = 'ask' | 'hide_to_tray' | 'quit';
+
+export interface TraySettings {
+  /** Snippets in the tray's Recent submenu; 0 hides it. */
+  recent_count: number;
+  recent_action: 'copy' | 'open';
+  click: TrayClickAction;
+  double_click: TrayClickAction;
+  /** Every launch goes to the tray without showing the window. */
+  start_minimized: boolean;
+  /** What the window's close button does; "ask" asks on the next close. */
+  on_close: CloseAction;
+  /** False on Linux, whose trays report no clicks. */
+  clicks_supported: boolean;
+  /** Actions `click` can take on this platform; `double_click` can't open the menu. */
+  click_actions: TrayClickAction[];
+}
+
+/** Shell-only: the tray's Recent submenu. */
+export function useTraySettings(enabled: boolean) {
+  return useQuery<TraySettings>({
+    queryKey: ['tray-settings'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<TraySettings>('get_tray_settings');
+    },
+    enabled,
+  });
+}
+
+export function useSetTrayRecent() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (settings: TraySettings) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<TraySettings>('set_tray_recent', {
+        recentCount: settings.recent_count,
+        recentAction: settings.recent_action,
+      });
+    },
+    onSuccess: (settings) => qc.setQueryData(['tray-settings'], settings),
+  });
+}
+
+export function useSetTrayClicks() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (settings: TraySettings) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<TraySettings>('set_tray_clicks', {
+        click: settings.click,
+        doubleClick: settings.double_click,
+      });
+    },
+    onSuccess: (settings) => qc.setQueryData(['tray-settings'], settings),
+  });
+}
+
+export function useSetCloseAction() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (action: CloseAction) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<TraySettings>('set_close_action', { action });
+    },
+    onSuccess: (settings) => qc.setQueryData(['tray-settings'], settings),
+  });
+}
+
+export function useSetTrayStartMinimized() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (enabled: boolean) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<TraySettings>('set_tray_start_minimized', { enabled });
+    },
+    onSuccess: (settings) => qc.setQueryData(['tray-settings'], settings),
+  });
+}
+
+export type FocusAction = 'queue' | 'suppress' | 'badge';
+
+export interface NotificationSettings {
+  enabled: boolean;
+  /** What happens to notifications during Do Not Disturb / Focus. */
+  during_focus: FocusAction;
+}
+
+/** Shell-only: native notifications when background jobs finish. */
+export function useNotificationSettings(enabled: boolean) {
+  return useQuery<NotificationSettings>({
+    queryKey: ['notification-settings'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<NotificationSettings>('get_notification_settings');
+    },
+    enabled,
+  });
+}
+
+export function useSetNotifications() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (enabled: boolean) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<NotificationSettings>('set_notifications', { enabled });
+    },
+    onSuccess: (settings) => qc.setQueryData(['notification-settings'], settings),
+  });
+}
+
+export function useSetNotificationsDuringFocus() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (action: FocusAction) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<NotificationSettings>('set_notifications_during_focus', { action });
+    },
+    onSuccess: (settings) => qc.setQueryData(['notification-settings'], settings),
+  });
+}
+
+export interface FocusState {
+  active: boolean;
+  /** What turned it on, e.g. "Focus" or "Presentation mode". */
+  source: string | null;
+  /** False where the OS state can't be read. */
+  supported: boolean;
+}
+
+/** Shell-only: the OS Do Not Disturb / Focus state, kept current from `focus-changed`. */
+export function useFocusState(enabled: boolean) {
+  const qc = useQueryClient();
+  useEffect(() => {
+    if (!enabled) return;
+    const unlisten = import('@tauri-apps/api/event').then(({ listen }) =>
+      listen<FocusState>('focus-changed', (e) => qc.setQueryData(['focus-state'], e.payload)),
+    );
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [enabled, qc]);
+  return useQuery<FocusState>({
+    queryKey: ['focus-state'],
+    queryFn: async () => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<FocusState>('get_focus_state');
+    },
+    enabled,
+    staleTime: Infinity,
+  });
+}
+
+export interface NotionImport {
+  dry_run: boolean;
+  pages: number;
+  databases: string[];
+  imported: number;
+  failed: number;
+  attachments: number;
+  duplicates: DuplicateCounts;
+  entries: { title: string; tags: string[]; attachments: number; chars: number }[];
+}
+
+/** Shell-only: imports (or with dryRun, previews) a Notion export ZIP. */
+export function useImportNotion() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: ({
+      path,
+      dryRun,
+      onProgress,
+    }: {
+      path: string;
+      dryRun: boolean;
+      onProgress?: TaskProgress;
+    }) => runTask<NotionImport>('import_notion_export', { path, dryRun }, onProgress),
+    onSuccess: (status) => {
+      if (!status?.result?.dry_run) qc.invalidateQueries();
+    },
+  });
+}
+
+export interface EnexImport {
+  notes: number;
+  imported: number;
+  failed: number;
+  attachments: number;
+  skipped_resources: number;
+  duplicates: DuplicateCounts;
+}
+
+/** Shell-only: imports an Evernote .enex export (null if cancelled). */
+export function useImportEnex() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({ onProgress }: { onProgress?: TaskProgress } = {}) => {
+      const { open } = await import('@tauri-apps/api/dialog');
+      const path = await open({ filters: [{ name: 'Evernote export', extensions: ['enex'] }] });
+      if (typeof path !== 'string') return null;
+      return runTask<EnexImport>('import_enex', { path }, onProgress);
+    },
+    onSuccess: (result) => {
+      if (result) qc.invalidateQueries();
+    },
+  });
+}
+
+export interface BookmarksImport {
+  format: 'chromium' | 'html' | 'firefox';
+  found: number;
+  imported: number;
+  /** Rejected by the backend's own duplicate check. */
+  rejected: number;
+  failed: number;
+  skipped: number;
+  duplicates: DuplicateCounts;
+}
+
+/**
+ * Shell-only: imports a Chrome/Edge `Bookmarks` file, an HTML bookmarks
+ * export or Firefox's places.sqlite (null if cancelled). No file filter:
+ * Chrome's file has no extension.
+ */
+export function useImportBookmarks() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({ onProgress }: { onProgress?: TaskProgress } = {}) => {
+      const { open } = await import('@tauri-apps/api/dialog');
+      const path = await open({ title: 'Choose a bookmarks file' });
+      if (typeof path !== 'string') return null;
+      return runTask<BookmarksImport>('import_bookmarks', { path }, onProgress);
+    },
+    onSuccess: (result) => {
+      if (result) qc.invalidateQueries();
+    },
+  });
+}
+
+/** Shell-only: fetches a page and saves its article as a snippet. */
+export function useClipUrl() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async (url: string) => {
+      const { invoke } = await import('@tauri-apps/api/tauri');
+      return invoke<SnippetOut>('clip_url', { url });
+    },
+    onSuccess: () => {
+      qc.invalidateQueries({ queryKey: ['snippets'] });
+      qc.invalidateQueries({ queryKey: ['stats'] });
+    },
+  });
+}
+
+/** Shell-only: imports a ZIP from useExportArchive (null if cancelled). */
+export function useImportArchive() {
+  const qc = useQueryClient();
+  return useMutation({
+    mutationFn: async ({ onProgress }: { onProgress?: TaskProgress } = {}) => {
+      const { open } = await import('@tauri-apps/api/dialog');
+      const path = await open({ filters: [{ name: 'ZIP archive', extensions: ['zip'] }] });
+      if (typeof path !== 'string') return null;
+      return runTask<{
+        imported: ImportResponse | null;
+        attachments: number;
+        duplicates: DuplicateCounts;
+      }>(
+        'import_archive',
+        { path },
+        onProgress,
+      );
+    },
+    onSuccess: () => qc.invalidateQueries(),
+  });
+}
+
 export function useImportFile() {
   const qc = useQueryClient();
   return useMutation({
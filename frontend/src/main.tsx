@@ -3,6 +3,7 @@ import ReactDOM from 'react-dom/client';
 import { QueryClient, QueryClientProvider } from '@tanstack/react-query';
 import App from './App';
 import { setBootstrap } from './api/client';
+import { followSystemTheme } from './stores/appStore';
 import './index.css';
 
 // ── Apply stored theme immediately (avoid flash) ────────────────────────────
@@ -47,4 +48,13 @@ bootstrap().then(() => {
       </QueryClientProvider>
     </React.StrictMode>,
   );
+  // Startup metrics: tell the shell once the first frame is on screen.
+  if ((window as any).__TAURI__) {
+    followSystemTheme().catch(() => {});
+    requestAnimationFrame(() => {
+      import('@tauri-apps/api/tauri')
+        .then(({ invoke }) => invoke('mark_first_paint'))
+        .catch(() => {});
+    });
+  }
 });
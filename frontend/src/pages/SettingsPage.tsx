@@ -25,6 +25,113 @@ import {
   useRestoreBackup,
   useExportJson,
   useImportFile,
+  useExportEncrypted,
+  useImportEncrypted,
+  useExportArchive,
+  useImportArchive,
+  useExportMarkdownFolder,
+  useObsidianStatus,
+  useSetObsidianVault,
+  useImportNotion,
+  useImportEnex,
+  useImportBookmarks,
+  useClipUrl,
+  useDuplicatePolicy,
+  useSetDuplicatePolicy,
+  useWatchFolders,
+  useFeeds,
+  useSetFeeds,
+  useSetFeed,
+  useRemoveFeed,
+  useRefreshFeeds,
+  useEmailInStatus,
+  useSetEmailIn,
+  useDisableEmailIn,
+  useCheckEmailNow,
+  useNativeHostStatus,
+  useSetNativeHost,
+  useDeepLinks,
+  useSetDeepLinks,
+  useAutostart,
+  useAppLockStatus,
+  useSetAppLock,
+  useSetAppLockBiometrics,
+  useLockApp,
+  useLocale,
+  useSetLocale,
+  useProfiles,
+  useCreateProfile,
+  useSwitchProfile,
+  useDeleteProfile,
+  useDataLocation,
+  useEncryptionStatus,
+  usePurgePlaintextCopies,
+  useMoveDataDir,
+  useSetDataDir,
+  useSetAutostart,
+  useSendToStatus,
+  useSetSendTo,
+  useTraySettings,
+  useSetTrayRecent,
+  useSetTrayClicks,
+  useSetTrayStartMinimized,
+  useSetCloseAction,
+  useNotificationSettings,
+  useSetNotifications,
+  useSetNotificationsDuringFocus,
+  useFocusState,
+  useSetWatchFolder,
+  useRemoveWatchFolder,
+  useGitSyncStatus,
+  useSetGitSync,
+  useSyncGitNow,
+  useWebDavStatus,
+  useSetWebDav,
+  useDisableWebDav,
+  useWebDavSyncNow,
+  useS3Status,
+  useSetS3Sync,
+  useDisableS3Sync,
+  useS3SyncNow,
+  useLanSyncStatus,
+  useSetLanSync,
+  useStartLanPairing,
+  useCancelLanPairing,
+  usePairLanDevice,
+  useRemoveLanPeer,
+  useLanSyncNow,
+  useCompanionStatus,
+  useSetCompanion,
+  useStartCompanionPairing,
+  useCancelCompanionPairing,
+  useRemoveCompanionDevice,
+  useSyncConflicts,
+  useResolveConflict,
+  useSyncKeyStatus,
+  useCreateSyncKey,
+  useRecoveryPhrase,
+  useRestoreSyncKey,
+  useBackupSyncKey,
+  useRestoreSyncKeyBackup,
+  useForgetSyncKey,
+  cancelTask,
+} from '../hooks/useApi';
+import type {
+  ConflictStrategy,
+  DuplicateCounts,
+  DuplicatePolicy,
+  EmailInConfig,
+  Feed,
+  FeedStatus,
+  SyncConflict,
+  TaskStatus,
+  TraySettings,
+  TrayClickAction,
+  FocusAction,
+  CloseAction,
+  ProfileInfo,
+  WatchFolder,
+  WatchFolderStatus,
 } from '../hooks/useApi';
 import { settingsAPI } from '../api/client';
 import { setBootstrap, getBaseUrl, getToken } from '../api/client';
@@ -55,6 +162,301 @@ const Section: React.FC<{
   </div>
 );
 
+/** How a Notion preview describes what happens to duplicate pages. */
+const DUPLICATE_OUTCOME: Record<DuplicatePolicy, string> = {
+  skip: 'skipped',
+  merge: 'merged into the existing pages',
+  flag: 'imported tagged "duplicate"',
+};
+
+/** Tray click actions as the selects name them. */
+const TRAY_CLICK_LABELS: Record<TrayClickAction, string> = {
+  none: 'nothing',
+  show_window: 'show window',
+  quick_capture: 'capture clipboard',
+  paste_last: 'paste last snippet',
+  show_menu: 'open menu',
+};
+
+const FOCUS_ACTION_LABELS: Record<FocusAction, string> = {
+  queue: 'Show when it ends',
+  badge: 'Only update the badge',
+  suppress: 'Drop them',
+};
+
+/* -------------------------------------------------------------------------- */
+/*  Watch folder row                                                          */
+/* -------------------------------------------------------------------------- */
+const WatchFolderRow: React.FC<{
+  folder: WatchFolderStatus;
+  onChange: (folder: WatchFolder) => void;
+  onRemove: () => void;
+}> = ({ folder, onChange, onRemove }) => {
+  const [extensions, setExtensions] = useState(folder.extensions.join(', '));
+  const [tag, setTag] = useState(folder.tag ?? '');
+  const update = (patch: Partial<WatchFolder>) => {
+    const rules: WatchFolder = {
+      path: folder.path,
+      enabled: folder.enabled,
+      extensions: folder.extensions,
+      tag: folder.tag,
+      recursive: folder.recursive,
+      delete_after_import: folder.delete_after_import,
+    };
+    onChange({ ...rules, ...patch });
+  };
+  const commitExtensions = () => {
+    const list = extensions.split(/[\s,]+/).filter(Boolean);
+    if (list.join(',') !== folder.extensions.join(',')) update({ extensions: list });
+  };
+  const commitTag = () => {
+    if (tag.trim() !== (folder.tag ?? '')) update({ tag: tag.trim() || null });
+  };
+
+  return (
+    <div className="border border-gray-200 rounded-lg p-3 space-y-2 text-xs text-gray-600">
+      <div className="flex items-center gap-3">
+        <span className="flex-1 truncate font-medium text-gray-800" title={folder.path}>
+          {folder.path}
+        </span>
+        <label className="flex items-center gap-1">
+          <input
+            type="checkbox"
+            checked={folder.enabled}
+            onChange={(e) => update({ enabled: e.target.checked })}
+          />
+          Watching
+        </label>
+        <button onClick={onRemove} className="text-gray-400 hover:text-red-500" aria-label="Remove watch folder">
+          <Trash2 className="w-4 h-4" />
+        </button>
+      </div>
+      <div className="flex flex-wrap items-center gap-3">
+        <input
+          value={extensions}
+          onChange={(e) => setExtensions(e.target.value)}
+          onBlur={commitExtensions}
+          placeholder="File types, e.g. png, pdf (all if empty)"
+          className="flex-1 min-w-[12rem] px-2 py-1 border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+        />
+        <input
+          value={tag}
+          onChange={(e) => setTag(e.target.value)}
+          onBlur={commitTag}
+          placeholder="Tag to apply"
+          className="w-32 px-2 py-1 border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+        />
+        <label className="flex items-center gap-1">
+          <input
+            type="checkbox"
+            checked={folder.recursive}
+            onChange={(e) => update({ recursive: e.target.checked })}
+          />
+          Subfolders
+        </label>
+        <label className="flex items-center gap-1">
+          <input
+            type="checkbox"
+            checked={folder.delete_after_import}
+            onChange={(e) => update({ delete_after_import: e.target.checked })}
+          />
+          Delete after import
+        </label>
+      </div>
+      <p className="text-gray-500">
+        {folder.enabled && !folder.watching ? 'Not watching' : `${folder.imported} imported`}
+        {folder.last_import && ` · last ${formatDate(Date.parse(folder.last_import))}`}
+        {folder.last_error && ` · ${folder.last_error}`}
+      </p>
+    </div>
+  );
+};
+
+/* -------------------------------------------------------------------------- */
+/*  Feed row                                                                  */
+/* -------------------------------------------------------------------------- */
+const FeedRow: React.FC<{
+  feed: FeedStatus;
+  onChange: (feed: Feed) => void;
+  onRemove: () => void;
+}> = ({ feed, onChange, onRemove }) => {
+  const [collection, setCollection] = useState(feed.collection ?? '');
+  const [tags, setTags] = useState(feed.tags.join(', '));
+  const update = (patch: Partial<Feed>) => {
+    const settings: Feed = {
+      url: feed.url,
+      title: feed.title,
+      enabled: feed.enabled,
+      collection: feed.collection,
+      tags: feed.tags,
+      full_article: feed.full_article,
+      interval_minutes: feed.interval_minutes,
+    };
+    onChange({ ...settings, ...patch });
+  };
+  const commitCollection = () => {
+    if (collection.trim() !== (feed.collection ?? '')) update({ collection: collection.trim() || null });
+  };
+  const commitTags = () => {
+    const list = tags.split(',').map((t) => t.trim()).filter(Boolean);
+    if (list.join(',') !== feed.tags.join(',')) update({ tags: list });
+  };
+
+  return (
+    <div className="border border-gray-200 rounded-lg p-3 space-y-2 text-xs text-gray-600">
+      <div className="flex items-center gap-3">
+        <span className="flex-1 truncate font-medium text-gray-800" title={feed.url}>
+          {feed.title || feed.url}
+        </span>
+        <label className="flex items-center gap-1">
+          <input
+            type="checkbox"
+            checked={feed.enabled}
+            onChange={(e) => update({ enabled: e.target.checked })}
+          />
+          Following
+        </label>
+        <button onClick={onRemove} className="text-gray-400 hover:text-red-500" aria-label="Remove feed">
+          <Trash2 className="w-4 h-4" />
+        </button>
+      </div>
+      <div className="flex flex-wrap items-center gap-3">
+        <input
+          value={collection}
+          onChange={(e) => setCollection(e.target.value)}
+          onBlur={commitCollection}
+          placeholder="Collection (default if empty)"
+          className="w-44 px-2 py-1 border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+        />
+        <input
+          value={tags}
+          onChange={(e) => setTags(e.target.value)}
+          onBlur={commitTags}
+          placeholder="Tags, e.g. news, rust"
+          className="flex-1 min-w-[10rem] px-2 py-1 border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+        />
+        <select
+          value={feed.interval_minutes ?? ''}
+          onChange={(e) => update({ interval_minutes: e.target.value ? Number(e.target.value) : null })}
+          className="px-2 py-1 border border-gray-200 rounded-lg"
+        >
+          <option value="">Default interval</option>
+          <option value="15">Every 15 min</option>
+          <option value="60">Hourly</option>
+          <option value="360">Every 6 hours</option>
+          <option value="1440">Daily</option>
+        </select>
+        <label className="flex items-center gap-1">
+          <input
+            type="checkbox"
+            checked={feed.full_article}
+            onChange={(e) => update({ full_article: e.target.checked })}
+          />
+          Full articles
+        </label>
+      </div>
+      <p className="text-gray-500">
+        {feed.imported} filed
+        {feed.checked_at && ` · checked ${formatDate(feed.checked_at * 1000)}`}
+        {feed.last_error && ` · ${feed.last_error}`}
+      </p>
+    </div>
+  );
+};
+
+/* -------------------------------------------------------------------------- */
+/*  Sync conflict row                                                         */
+/* -------------------------------------------------------------------------- */
+const ConflictVersion: React.FC<{ label: string; version: Record<string, unknown> | null; kept: boolean }> = ({
+  label,
+  version,
+  kept,
+}) => (
+  <div className="flex-1 min-w-0 space-y-1">
+    <p className="font-medium text-gray-700">
+      {label}
+      {kept && <span className="ml-1 text-gray-400">(in the library now)</span>}
+    </p>
+    {version ? (
+      <>
+        <p className="truncate">{String(version.title ?? 'Untitled')}</p>
+        <pre className="max-h-32 overflow-auto whitespace-pre-wrap rounded bg-gray-50 p-2 font-mono text-[11px]">
+          {String(version.body ?? '')}
+        </pre>
+      </>
+    ) : (
+      <p className="italic text-gray-400">Deleted</p>
+    )}
+  </div>
+);
+
+const ConflictRow: React.FC<{
+  conflict: SyncConflict;
+  busy: boolean;
+  onResolve: (strategy: ConflictStrategy, merged?: Record<string, unknown>) => void;
+}> = ({ conflict, busy, onResolve }) => {
+  const base = conflict.kept === 'local' ? conflict.local : conflict.remote;
+  const [merged, setMerged] = useState<string | null>(null);
+  const button =
+    'px-3 py-1 text-xs font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50';
+
+  return (
+    <div className="border border-gray-200 rounded-lg p-3 space-y-2 text-xs text-gray-600">
+      <p>
+        <span className="font-medium text-gray-800">{conflict.title}</span> changed on both sides ·{' '}
+        {conflict.source} · {formatDate(conflict.detected_at)}
+      </p>
+      <div className="flex gap-3">
+        <ConflictVersion label="This device" version={conflict.local} kept={conflict.kept === 'local'} />
+        <ConflictVersion label="Other device" version={conflict.remote} kept={conflict.kept === 'remote'} />
+      </div>
+      {merged !== null && (
+        <textarea
+          value={merged}
+          onChange={(e) => setMerged(e.target.value)}
+          rows={6}
+          className="w-full px-2 py-1 font-mono border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+        />
+      )}
+      <div className="flex flex-wrap items-center gap-2">
+        <button onClick={() => onResolve('keep_local')} disabled={busy} className={button}>
+          Keep this device's
+        </button>
+        <button onClick={() => onResolve('keep_remote')} disabled={busy} className={button}>
+          Keep the other
+        </button>
+        {conflict.local && conflict.remote && (
+          <button onClick={() => onResolve('keep_both')} disabled={busy} className={button}>
+            Keep both
+          </button>
+        )}
+        {merged === null ? (
+          base && (
+            <button
+              onClick={() => setMerged(String(conflict.local?.body ?? conflict.remote?.body ?? ''))}
+              disabled={busy}
+              className={button}
+            >
+              Merge…
+            </button>
+          )
+        ) : (
+          <button
+            onClick={() => onResolve('merge', { ...base, body: merged })}
+            disabled={busy}
+            className={button}
+          >
+            Save merged
+          </button>
+        )}
+        <button onClick={() => onResolve('dismiss')} disabled={busy} className="ml-auto text-gray-400 hover:text-gray-600">
+          Dismiss
+        </button>
+      </div>
+    </div>
+  );
+};
+
 /* -------------------------------------------------------------------------- */
 /*  Component                                                                 */
 /* -------------------------------------------------------------------------- */
@@ -70,6 +472,11 @@ export const SettingsPage: React.FC<SettingsPageProps> = ({ showToast }) => {
   const restoreBackup = useRestoreBackup();
   const exportJson = useExportJson();
   const importFile = useImportFile();
+  const exportEncrypted = useExportEncrypted();
+  const importEncrypted = useImportEncrypted();
+  const exportArchive = useExportArchive();
+  const importArchive = useImportArchive();
+  const exportMarkdown = useExportMarkdownFolder();
 
   const settings = settingsQuery.data;
   const license = licenseQuery.data;
@@ -79,7 +486,112 @@ export const SettingsPage: React.FC<SettingsPageProps> = ({ showToast }) => {
   const [licenseKey, setLicenseKey] = useState('');
   const [confirmRestore, setConfirmRestore] = useState<string | null>(null);
   const [rotatingToken, setRotatingToken] = useState(false);
+  const [exportPassphrase, setExportPassphrase] = useState('');
+  const [task, setTask] = useState<TaskStatus | null>(null);
   const fileInputRef = useRef<HTMLInputElement>(null);
+  const inShell = Boolean((window as any).__TAURI__);
+  const obsidian = useObsidianStatus(inShell);
+  const setObsidianVault = useSetObsidianVault();
+  const importNotion = useImportNotion();
+  const importEnex = useImportEnex();
+  const importBookmarks = useImportBookmarks();
+  const clipUrl = useClipUrl();
+  const duplicatePolicy = useDuplicatePolicy(inShell);
+  const setDuplicatePolicy = useSetDuplicatePolicy();
+  const watchFolders = useWatchFolders(inShell);
+  const setWatchFolder = useSetWatchFolder();
+  const removeWatchFolder = useRemoveWatchFolder();
+  const feeds = useFeeds(inShell);
+  const setFeeds = useSetFeeds();
+  const setFeed = useSetFeed();
+  const removeFeed = useRemoveFeed();
+  const refreshFeeds = useRefreshFeeds();
+  const emailIn = useEmailInStatus(inShell);
+  const setEmailIn = useSetEmailIn();
+  const disableEmailIn = useDisableEmailIn();
+  const checkEmailNow = useCheckEmailNow();
+  const [emailDraft, setEmailDraft] = useState<Partial<EmailInConfig>>({});
+  const [emailPassword, setEmailPassword] = useState('');
+  const nativeHost = useNativeHostStatus(inShell);
+  const setNativeHost = useSetNativeHost();
+  const deepLinks = useDeepLinks(inShell);
+  const setDeepLinks = useSetDeepLinks();
+  const autostart = useAutostart(inShell);
+  const setAutostart = useSetAutostart();
+  const appLock = useAppLockStatus(inShell);
+  const setAppLock = useSetAppLock();
+  const setAppLockBiometrics = useSetAppLockBiometrics();
+  const lockApp = useLockApp();
+  const locale = useLocale(inShell);
+  const setLocale = useSetLocale();
+  const profiles = useProfiles(inShell);
+  const createProfile = useCreateProfile();
+  const switchProfile = useSwitchProfile();
+  const deleteProfile = useDeleteProfile();
+  const dataLocation = useDataLocation(inShell);
+  const moveDataDir = useMoveDataDir();
+  const setDataDir = useSetDataDir();
+  const encryption = useEncryptionStatus(inShell);
+  const purgePlaintext = usePurgePlaintextCopies();
+  const [lockPasscode, setLockPasscode] = useState('');
+  const sendTo = useSendToStatus(inShell);
+  const setSendTo = useSetSendTo();
+  const traySettings = useTraySettings(inShell);
+  const setTrayRecent = useSetTrayRecent();
+  const setTrayClicks = useSetTrayClicks();
+  const setTrayStartMinimized = useSetTrayStartMinimized();
+  const setCloseAction = useSetCloseAction();
+  const notificationSettings = useNotificationSettings(inShell);
+  const setNotifications = useSetNotifications();
+  const setDuringFocus = useSetNotificationsDuringFocus();
+  const focusState = useFocusState(inShell);
+  const gitSync = useGitSyncStatus(inShell);
+  const setGitSync = useSetGitSync();
+  const syncGitNow = useSyncGitNow();
+  const [gitRemote, setGitRemote] = useState<string | null>(null);
+  const webdav = useWebDavStatus(inShell);
+  const setWebDav = useSetWebDav();
+  const disableWebDav = useDisableWebDav();
+  const webdavSyncNow = useWebDavSyncNow();
+  const [webdavUrl, setWebdavUrl] = useState<string | null>(null);
+  const [webdavUser, setWebdavUser] = useState<string | null>(null);
+  const [webdavPassword, setWebdavPassword] = useState('');
+  const [webdavPassphrase, setWebdavPassphrase] = useState('');
+  const s3 = useS3Status(inShell);
+  const setS3Sync = useSetS3Sync();
+  const disableS3Sync = useDisableS3Sync();
+  const s3SyncNow = useS3SyncNow();
+  const [s3Endpoint, setS3Endpoint] = useState<string | null>(null);
+  const [s3Region, setS3Region] = useState<string | null>(null);
+  const [s3Bucket, setS3Bucket] = useState<string | null>(null);
+  const [s3Prefix, setS3Prefix] = useState<string | null>(null);
+  const [s3PathStyle, setS3PathStyle] = useState<boolean | null>(null);
+  const [s3AccessKey, setS3AccessKey] = useState('');
+  const [s3SecretKey, setS3SecretKey] = useState('');
+  const [s3Passphrase, setS3Passphrase] = useState('');
+  const lanSync = useLanSyncStatus(inShell);
+  const setLanSync = useSetLanSync();
+  const startLanPairing = useStartLanPairing();
+  const cancelLanPairing = useCancelLanPairing();
+  const pairLanDevice = usePairLanDevice();
+  const removeLanPeer = useRemoveLanPeer();
+  const lanSyncNow = useLanSyncNow();
+  const [lanName, setLanName] = useState<string | null>(null);
+  const companion = useCompanionStatus(inShell);
+  const setCompanion = useSetCompanion();
+  const startCompanionPairing = useStartCompanionPairing();
+  const cancelCompanionPairing = useCancelCompanionPairing();
+  const removeCompanionDevice = useRemoveCompanionDevice();
+  const syncKey = useSyncKeyStatus(inShell);
+  const createSyncKey = useCreateSyncKey();
+  const recoveryPhrase = useRecoveryPhrase();
+  const restoreSyncKey = useRestoreSyncKey();
+  const backupSyncKey = useBackupSyncKey();
+  const restoreSyncKeyBackup = useRestoreSyncKeyBackup();
+  const forgetSyncKey = useForgetSyncKey();
+  const [shownPhrase, setShownPhrase] = useState<string | null>(null);
+  const syncConflicts = useSyncConflicts(inShell);
+  const resolveConflict = useResolveConflict();
 
   /* ── Handlers ────────────────────────────────────────────────────────── */
   const handleToggleDedupe = () => {
@@ -129,31 +641,752 @@ export const SettingsPage: React.FC<SettingsPageProps> = ({ showToast }) => {
     });
   };
 
-  const handleRestore = (name: string) => {
-    restoreBackup.mutate(name, {
-      onSuccess: () => {
-        showToast('Backup restored — data reloaded', 'success');
-        setConfirmRestore(null);
+  const handleRestore = (name: string) => {
+    restoreBackup.mutate(name, {
+      onSuccess: () => {
+        showToast('Backup restored — data reloaded', 'success');
+        setConfirmRestore(null);
+      },
+      onError: (e) => showToast(e.message, 'error'),
+    });
+  };
+
+  const handleExportJson = () => {
+    exportJson.mutate(
+      {},
+      {
+        onSuccess: (data) => {
+          const blob = new Blob([JSON.stringify(data, null, 2)], { type: 'application/json' });
+          const url = URL.createObjectURL(blob);
+          const a = document.createElement('a');
+          a.href = url;
+          a.download = `pinup-export-${Date.now()}.json`;
+          a.click();
+          URL.revokeObjectURL(url);
+          showToast('Export downloaded', 'success');
+        },
+        onError: (e) => showToast(e.message, 'error'),
+      },
+    );
+  };
+
+  /** Shows how a shell task ended; a cancelled one reports what it got done. */
+  const reportTask = <T,>(
+    status: TaskStatus<T> | null,
+    describe: (result: T) => [string, 'success' | 'warning'],
+  ) => {
+    setTask(null);
+    if (!status) return;
+    if (status.result == null) {
+      showToast(status.error ?? 'Cancelled', 'info');
+      return;
+    }
+    const [message, level] = describe(status.result);
+    showToast(status.state === 'cancelled' ? `Cancelled. ${message}` : message, level);
+  };
+
+  /** ", 3 duplicates skipped" etc.; empty when none were found. */
+  const duplicatesNote = (d: DuplicateCounts) =>
+    [
+      d.skipped && `${d.skipped} duplicates skipped`,
+      d.merged && `${d.merged} merged into existing snippets`,
+      d.flagged && `${d.flagged} flagged as duplicates`,
+    ]
+      .filter(Boolean)
+      .map((part) => `, ${part}`)
+      .join('');
+
+  const handleDuplicatePolicy = (policy: DuplicatePolicy) => {
+    setDuplicatePolicy.mutate(policy, {
+      onSuccess: () => showToast('Settings saved', 'success'),
+      onError: (e) => showToast(String(e), 'error'),
+    });
+  };
+
+  const handleAddWatchFolder = async () => {
+    const { open } = await import('@tauri-apps/api/dialog');
+    const path = await open({ directory: true, title: 'Choose a folder to watch' });
+    if (typeof path !== 'string') return;
+    setWatchFolder.mutate(
+      {
+        path,
+        enabled: true,
+        extensions: [],
+        tag: null,
+        recursive: false,
+        delete_after_import: false,
+      },
+      {
+        onSuccess: () => showToast(`Watching ${path} for new files`, 'success'),
+        onError: (e) => showToast(String(e), 'error'),
+      },
+    );
+  };
+
+  const handleWatchFolderChange = (folder: WatchFolder) => {
+    setWatchFolder.mutate(folder, { onError: (e) => showToast(String(e), 'error') });
+  };
+
+  const handleCreateProfile = () => {
+    const name = window.prompt('Name of the new profile, e.g. Work:');
+    if (!name?.trim()) return;
+    createProfile.mutate(name.trim(), {
+      onSuccess: () => showToast(`Profile "${name.trim()}" created`, 'success'),
+      onError: (e) => showToast(String(e), 'error'),
+    });
+  };
+
+  const handleSwitchProfile = (profile: ProfileInfo) => {
+    if (!window.confirm(`Switch to "${profile.name}"? Pin-Up AI restarts with that profile's notes.`)) return;
+    switchProfile.mutate(profile.id, { onError: (e) => showToast(String(e), 'error') });
+  };
+
+  const handleDeleteProfile = (profile: ProfileInfo) => {
+    const message = profile.moved
+      ? `Remove "${profile.name}" from Pin-Up AI? Its notes stay in ${profile.path}.`
+      : `Delete "${profile.name}" and all of its notes? This can't be undone.`;
+    if (!window.confirm(message)) return;
+    deleteProfile.mutate(profile.id, { onError: (e) => showToast(String(e), 'error') });
+  };
+
+  const handleMoveDataDir = () => {
+    if (
+      !window.confirm(
+        'Move your notes to another folder? The backend stops while they are copied and checked, then Pin-Up AI restarts from the new folder.',
+      )
+    )
+      return;
+    moveDataDir.mutate(
+      { onProgress: setTask },
+      {
+        onSuccess: (status) =>
+          reportTask(status, (result) => [`Data moved to ${result.path}`, 'success']),
+        onError: onTaskError,
+      },
+    );
+  };
+
+  const handleSetDataDir = (reset: boolean) => {
+    const message = reset
+      ? 'Go back to the original data folder? Pin-Up AI restarts there; notes in the current folder are not copied.'
+      : 'Use notes already in another folder? Pin-Up AI restarts from the folder you pick; nothing is copied.';
+    if (!window.confirm(message)) return;
+    setDataDir.mutate({ reset }, { onError: (e) => showToast(String(e), 'error') });
+  };
+
+  const handlePurgePlaintext = (copies: string[]) => {
+    if (
+      !window.confirm(
+        `Overwrite and delete ${copies.length} unencrypted ${copies.length === 1 ? 'copy' : 'copies'} of your notes? Backups among them can't be restored afterwards.`,
+      )
+    )
+      return;
+    purgePlaintext.mutate(undefined, {
+      onSuccess: () => showToast('Unencrypted copies deleted', 'success'),
+      onError: (e) => showToast(String(e), 'error'),
+    });
+  };
+
+  const handleRemoveWatchFolder = (path: string) => {
+    removeWatchFolder.mutate(path, { onError: (e) => showToast(String(e), 'error') });
+  };
+
+  const handleAddFeed = () => {
+    const url = window.prompt('Feed or website address to follow:');
+    if (!url?.trim()) return;
+    setFeed.mutate(
+      {
+        url: url.trim(),
+        title: null,
+        enabled: true,
+        collection: null,
+        tags: [],
+        full_article: true,
+        interval_minutes: null,
+      },
+      {
+        onSuccess: () => showToast('Feed added; new entries will be filed as they appear', 'success'),
+        onError: (e) => showToast(String(e), 'error'),
+      },
+    );
+  };
+
+  const handleFeedChange = (feed: Feed) => {
+    setFeed.mutate(feed, { onError: (e) => showToast(String(e), 'error') });
+  };
+
+  const handleRemoveFeed = (url: string) => {
+    removeFeed.mutate(url, { onError: (e) => showToast(String(e), 'error') });
+  };
+
+  const handleFeedsCollection = (collection: string) => {
+    if (collection.trim() === feeds.data?.collection) return;
+    setFeeds.mutate({ collection }, { onError: (e) => showToast(String(e), 'error') });
+  };
+
+  const handleRefreshFeeds = () => {
+    refreshFeeds.mutate(undefined, {
+      onSuccess: (r) =>
+        showToast(
+          r.added === 0 ? 'No new feed entries' : `Filed ${r.added} new ${r.added === 1 ? 'entry' : 'entries'}`,
+          r.failed > 0 ? 'warning' : 'success',
+        ),
+      onError: (e) => showToast(String(e), 'error'),
+    });
+  };
+
+  const emailSettings: EmailInConfig | undefined = emailIn.data && { ...emailIn.data.config, ...emailDraft };
+
+  const handleSetEmailIn = () => {
+    if (!emailSettings) return;
+    setEmailIn.mutate(
+      { settings: emailSettings, password: emailPassword },
+      {
+        onSuccess: () => {
+          setEmailPassword('');
+          setEmailDraft({});
+          showToast(`Mail in "${emailSettings.folder}" will be imported`, 'success');
+        },
+        onError: (e) => showToast(String(e), 'error'),
+      },
+    );
+  };
+
+  const handleDisableEmailIn = () => {
+    const forget = window.confirm('Also remove the saved mail password from this device?');
+    disableEmailIn.mutate(forget, {
+      onSuccess: () => showToast('Email import stopped', 'success'),
+      onError: (e) => showToast(String(e), 'error'),
+    });
+  };
+
+  const handleNativeHost = (args: { enabled: boolean; chromeExtensions?: string[]; firefoxExtensions?: string[] }) => {
+    setNativeHost.mutate(args, {
+      onSuccess: (status) =>
+        showToast(
+          status.enabled ? 'Browser extension connection updated' : 'Browser extension connection turned off',
+          'success',
+        ),
+      onError: (e) => showToast(String(e), 'error'),
+    });
+  };
+
+  const handleNativeHostIds = (kind: 'chrome' | 'firefox', text: string) => {
+    if (!nativeHost.data) return;
+    const ids = text.split(/[\s,]+/).filter(Boolean);
+    const current = kind === 'chrome' ? nativeHost.data.chrome_extensions : nativeHost.data.firefox_extensions;
+    if (ids.join(',') === current.join(',')) return;
+    handleNativeHost(
+      kind === 'chrome'
+        ? { enabled: nativeHost.data.enabled, chromeExtensions: ids }
+        : { enabled: nativeHost.data.enabled, firefoxExtensions: ids },
+    );
+  };
+
+  const handleDeepLinks = (args: { enabled: boolean; callbackSchemes?: string[] }) => {
+    setDeepLinks.mutate(args, { onError: (e) => showToast(String(e), 'error') });
+  };
+
+  const handleCallbackSchemes = (text: string) => {
+    if (!deepLinks.data) return;
+    const schemes = text.split(/[\s,]+/).filter(Boolean);
+    if (schemes.join(',') === deepLinks.data.callback_schemes.join(',')) return;
+    handleDeepLinks({ enabled: deepLinks.data.enabled, callbackSchemes: schemes });
+  };
+
+  const handleTrayRecent = (patch: Partial<TraySettings>) => {
+    if (!traySettings.data) return;
+    setTrayRecent.mutate({ ...traySettings.data, ...patch }, { onError: (e) => showToast(String(e), 'error') });
+  };
+
+  const handleNotifications = (enabled: boolean) => {
+    setNotifications.mutate(enabled, { onError: (e) => showToast(String(e), 'error') });
+  };
+
+  const handleDuringFocus = (action: FocusAction) => {
+    setDuringFocus.mutate(action, { onError: (e) => showToast(String(e), 'error') });
+  };
+
+  const handleTrayClicks = (patch: Partial<TraySettings>) => {
+    if (!traySettings.data) return;
+    setTrayClicks.mutate({ ...traySettings.data, ...patch }, { onError: (e) => showToast(String(e), 'error') });
+  };
+
+  const handleAutostart = (patch: { enabled?: boolean; start_hidden?: boolean }) => {
+    if (!autostart.data) return;
+    setAutostart.mutate({ ...autostart.data, ...patch }, { onError: (e) => showToast(String(e), 'error') });
+  };
+
+  const handleAppLock = (patch: { enabled?: boolean; idle_minutes?: number; passcode?: string }) => {
+    if (!appLock.data) return;
+    setAppLock.mutate(
+      { enabled: appLock.data.enabled, idle_minutes: appLock.data.idle_minutes, ...patch },
+      {
+        onSuccess: () => {
+          if (patch.passcode) {
+            setLockPasscode('');
+            showToast('Passcode set', 'success');
+          }
+        },
+        onError: (e) => showToast(String(e), 'error'),
+      },
+    );
+  };
+
+  const handleSendTo = (enabled: boolean) => {
+    setSendTo.mutate(enabled, { onError: (e) => showToast(String(e), 'error') });
+  };
+
+  const handleCheckEmailNow = () => {
+    checkEmailNow.mutate(undefined, {
+      onSuccess: (check) =>
+        showToast(
+          check.imported === 0
+            ? 'No new mail'
+            : `Imported ${check.imported} ${check.imported === 1 ? 'email' : 'emails'}`,
+          check.skipped > 0 ? 'warning' : 'success',
+        ),
+      onError: (e) => showToast(String(e), 'error'),
+    });
+  };
+
+  const handleCreateSyncKey = () => {
+    const replace = !!syncKey.data?.has_key;
+    if (
+      replace &&
+      !window.confirm(
+        'Replace the sync key? Data synced with the current key can no longer be read, and every device needs the new recovery phrase.',
+      )
+    )
+      return;
+    createSyncKey.mutate(
+      { replace },
+      {
+        onSuccess: (phrase) => setShownPhrase(phrase),
+        onError: (e) => showToast(String(e), 'error'),
+      },
+    );
+  };
+
+  const handleRestoreSyncKey = () => {
+    const phrase = window.prompt('Enter the recovery phrase shown on your other device:');
+    if (!phrase) return;
+    restoreSyncKey.mutate(phrase, {
+      onSuccess: (status) => showToast(`Sync key ${status.key_id} saved`, 'success'),
+      onError: (e) => showToast(String(e), 'error'),
+    });
+  };
+
+  const handleBackupSyncKey = () => {
+    const passphrase = window.prompt('Choose a passphrase for the backup file (8+ characters):');
+    if (!passphrase) return;
+    backupSyncKey.mutate(passphrase, {
+      onSuccess: (path) => path && showToast(`Sync key backed up to ${path}`, 'success'),
+      onError: (e) => showToast(String(e), 'error'),
+    });
+  };
+
+  const handleRestoreSyncKeyBackup = () => {
+    const passphrase = window.prompt('Passphrase of the backup file:');
+    if (!passphrase) return;
+    restoreSyncKeyBackup.mutate(passphrase, {
+      onSuccess: (status) => status && showToast(`Sync key ${status.key_id} restored`, 'success'),
+      onError: (e) => showToast(String(e), 'error'),
+    });
+  };
+
+  const handleForgetSyncKey = () => {
+    if (!window.confirm('Remove the sync key from this device? Sync stops until it is restored.')) return;
+    forgetSyncKey.mutate(undefined, {
+      onSuccess: () => setShownPhrase(null),
+      onError: (e) => showToast(String(e), 'error'),
+    });
+  };
+
+  const gitRemoteValue = gitRemote ?? gitSync.data?.config.remote ?? '';
+
+  const handleGitSync = (stop: boolean) => {
+    setGitSync.mutate(
+      { stop, remote: gitRemoteValue.trim() || null },
+      {
+        onSuccess: (status) => {
+          if (!status) return;
+          showToast(stop ? 'Git sync stopped' : `Syncing with ${status.config.repo}`, 'success');
+        },
+        onError: (e) => showToast(String(e), 'error'),
+      },
+    );
+  };
+
+  const handleGitRemote = () => {
+    const repo = gitSync.data?.config.repo;
+    const remote = gitRemoteValue.trim() || null;
+    if (!repo || remote === gitSync.data?.config.remote) return;
+    setGitSync.mutate(
+      { repo, remote },
+      {
+        onSuccess: () => showToast(remote ? `Syncing with ${remote}` : 'Remote removed', 'success'),
+        onError: (e) => showToast(String(e), 'error'),
+      },
+    );
+  };
+
+  const handleSyncGitNow = () => {
+    syncGitNow.mutate(undefined, {
+      onSuccess: (report) =>
+        showToast(
+          `Synced: ${report.committed} local changes, ${report.created + report.updated + report.deleted} pulled` +
+            (report.pending ? `, ${report.pending} not applied yet` : ''),
+          report.pending ? 'warning' : 'success',
+        ),
+      onError: (e) => showToast(String(e), 'error'),
+    });
+  };
+
+  const webdavUrlValue = webdavUrl ?? webdav.data?.config.url ?? '';
+  const webdavUserValue = webdavUser ?? webdav.data?.config.username ?? '';
+
+  const handleSetWebDav = () => {
+    setWebDav.mutate(
+      {
+        url: webdavUrlValue,
+        username: webdavUserValue,
+        password: webdavPassword,
+        passphrase: webdavPassphrase,
+      },
+      {
+        onSuccess: () => {
+          setWebdavPassword('');
+          setWebdavPassphrase('');
+          showToast('WebDAV sync is on', 'success');
+        },
+        onError: (e) => showToast(String(e), 'error'),
+      },
+    );
+  };
+
+  const handleDisableWebDav = () => {
+    const forget = window.confirm(
+      'Also remove the saved WebDAV password and passphrase from this device?',
+    );
+    disableWebDav.mutate(forget, {
+      onSuccess: () => showToast('WebDAV sync stopped', 'success'),
+      onError: (e) => showToast(String(e), 'error'),
+    });
+  };
+
+  const handleWebDavSyncNow = () => {
+    webdavSyncNow.mutate(undefined, {
+      onSuccess: (report) =>
+        showToast(
+          `Synced: ${report.uploaded} local changes sent, ` +
+            `${report.created + report.updated + report.deleted} received` +
+            (report.restored_from ? ' (from a snapshot)' : ''),
+          'success',
+        ),
+      onError: (e) => showToast(String(e), 'error'),
+    });
+  };
+
+  const s3EndpointValue = s3Endpoint ?? s3.data?.config.endpoint ?? '';
+  const s3RegionValue = s3Region ?? s3.data?.config.region ?? '';
+  const s3BucketValue = s3Bucket ?? s3.data?.config.bucket ?? '';
+  const s3PrefixValue = s3Prefix ?? s3.data?.config.prefix ?? '';
+  const s3PathStyleValue = s3PathStyle ?? s3.data?.config.path_style ?? true;
+
+  const handleSetS3Sync = () => {
+    setS3Sync.mutate(
+      {
+        endpoint: s3EndpointValue,
+        region: s3RegionValue,
+        bucket: s3BucketValue,
+        prefix: s3PrefixValue,
+        pathStyle: s3PathStyleValue,
+        accessKeyId: s3AccessKey,
+        secretAccessKey: s3SecretKey,
+        passphrase: s3Passphrase,
+      },
+      {
+        onSuccess: () => {
+          setS3AccessKey('');
+          setS3SecretKey('');
+          setS3Passphrase('');
+          showToast('S3 sync is on', 'success');
+        },
+        onError: (e) => showToast(String(e), 'error'),
+      },
+    );
+  };
+
+  const handleDisableS3Sync = () => {
+    const forget = window.confirm(
+      'Also remove the saved S3 keys and passphrase from this device?',
+    );
+    disableS3Sync.mutate(forget, {
+      onSuccess: () => showToast('S3 sync stopped', 'success'),
+      onError: (e) => showToast(String(e), 'error'),
+    });
+  };
+
+  const handleS3SyncNow = () => {
+    s3SyncNow.mutate(undefined, {
+      onSuccess: (report) =>
+        showToast(
+          `Synced: ${report.uploaded} local changes sent, ` +
+            `${report.created + report.updated + report.deleted} received` +
+            (report.restored_from ? ' (from a snapshot)' : ''),
+          'success',
+        ),
+      onError: (e) => showToast(String(e), 'error'),
+    });
+  };
+
+  const handleSetLanSync = (enabled: boolean) => {
+    setLanSync.mutate(
+      { enabled, deviceName: lanName ?? undefined },
+      {
+        onSuccess: () => {
+          setLanName(null);
+          showToast(enabled ? 'LAN sync is on' : 'LAN sync stopped', 'success');
+        },
+        onError: (e) => showToast(String(e), 'error'),
+      },
+    );
+  };
+
+  const handlePairLanDevice = (device: string, name: string) => {
+    const code = window.prompt(`Enter the pairing code shown on ${name}:`);
+    if (!code) return;
+    pairLanDevice.mutate(
+      { device, code },
+      {
+        onSuccess: () => showToast(`Paired with ${name}`, 'success'),
+        onError: (e) => showToast(String(e), 'error'),
+      },
+    );
+  };
+
+  const handleRemoveLanPeer = (device: string, host: boolean, name: string) => {
+    if (!window.confirm(`Unpair ${name}? It will no longer sync with this device.`)) return;
+    removeLanPeer.mutate(
+      { device, host },
+      { onError: (e) => showToast(String(e), 'error') },
+    );
+  };
+
+  const handleSetCompanion = (enabled: boolean) => {
+    setCompanion.mutate(
+      { enabled },
+      {
+        onSuccess: () =>
+          showToast(enabled ? 'Phones can now pair and send captures' : 'Mobile companion stopped', 'success'),
+        onError: (e) => showToast(String(e), 'error'),
+      },
+    );
+  };
+
+  const handleCompanionPort = () => {
+    const port = window.prompt('Port phones connect to (1024–65535):', String(companion.data?.port ?? ''));
+    if (!port) return;
+    setCompanion.mutate(
+      { enabled: !!companion.data?.enabled, port: Number(port) },
+      { onError: (e) => showToast(String(e), 'error') },
+    );
+  };
+
+  const handleRemoveCompanionDevice = (device: string, name: string) => {
+    if (!window.confirm(`Unpair ${name}? It will no longer be able to send captures.`)) return;
+    removeCompanionDevice.mutate(device, { onError: (e) => showToast(String(e), 'error') });
+  };
+
+  const handleResolveConflict = (
+    id: string,
+    strategy: ConflictStrategy,
+    merged?: Record<string, unknown>,
+  ) => {
+    resolveConflict.mutate(
+      { id, strategy, merged },
+      { onError: (e) => showToast(String(e), 'error') },
+    );
+  };
+
+  const onTaskError = (e: unknown) => {
+    setTask(null);
+    showToast(String(e), 'error');
+  };
+
+  const handleExportEncrypted = () => {
+    exportEncrypted.mutate(
+      { passphrase: exportPassphrase, onProgress: setTask },
+      {
+        onSuccess: (status) => {
+          if (status?.state === 'done') setExportPassphrase('');
+          reportTask(status, (result) => [
+            `Encrypted export saved (${formatBytes(result.size_bytes)})`,
+            'success',
+          ]);
+        },
+        onError: onTaskError,
+      },
+    );
+  };
+
+  const handleImportEncrypted = () => {
+    importEncrypted.mutate(
+      { passphrase: exportPassphrase, onProgress: setTask },
+      {
+        onSuccess: (status) => {
+          if (status?.state === 'done') setExportPassphrase('');
+          reportTask(status, (result) => {
+            const total = Object.values(result.imported).reduce((a, b) => a + (b as number), 0);
+            return [`Imported ${total} items${duplicatesNote(result.duplicates)}`, 'success'];
+          });
+        },
+        onError: onTaskError,
+      },
+    );
+  };
+
+  const handleExportArchive = () => {
+    exportArchive.mutate(
+      { onProgress: setTask },
+      {
+        onSuccess: (status) =>
+          reportTask(status, (result) => {
+            const missing = result.missing.length ? `, ${result.missing.length} missing` : '';
+            return [
+              `Archive saved with ${result.attachments} attachments${missing} (${formatBytes(result.size_bytes)})`,
+              result.missing.length ? 'warning' : 'success',
+            ];
+          }),
+        onError: onTaskError,
+      },
+    );
+  };
+
+  const handleExportMarkdown = () => {
+    exportMarkdown.mutate(
+      { onProgress: setTask },
+      {
+        onSuccess: (status) =>
+          reportTask(status, (result) => [
+            `Wrote ${result.files} Markdown files and ${result.attachments} attachments`,
+            result.missing.length ? 'warning' : 'success',
+          ]),
+        onError: onTaskError,
+      },
+    );
+  };
+
+  const handleObsidianVault = (stop: boolean) => {
+    setObsidianVault.mutate(stop, {
+      onSuccess: (status) => {
+        if (!status) return;
+        showToast(stop ? 'Obsidian mirror stopped' : `Mirroring into ${status.root}`, 'success');
+      },
+      onError: (e) => showToast(String(e), 'error'),
+    });
+  };
+
+  const handleImportNotion = async () => {
+    const { open } = await import('@tauri-apps/api/dialog');
+    const path = await open({ filters: [{ name: 'Notion export', extensions: ['zip'] }] });
+    if (typeof path !== 'string') return;
+    try {
+      const previewed = await importNotion.mutateAsync({ path, dryRun: true, onProgress: setTask });
+      const preview = previewed?.result;
+      if (!preview || previewed.state === 'cancelled') {
+        setTask(null);
+        return;
+      }
+      const databases = preview.databases.length
+        ? ` from ${preview.databases.length} databases (${preview.databases.join(', ')})`
+        : '';
+      const ok = window.confirm(
+        `Import ${preview.imported} pages${databases} with ${preview.attachments} attachments?` +
+          (preview.duplicates.found
+            ? `\n${preview.duplicates.found} pages are already in your library and will be ` +
+              `${DUPLICATE_OUTCOME[preview.duplicates.policy]}.`
+            : '') +
+          (preview.failed ? `\n${preview.failed} pages can't be read and will be skipped.` : ''),
+      );
+      if (!ok) {
+        setTask(null);
+        return;
+      }
+      const status = await importNotion.mutateAsync({ path, dryRun: false, onProgress: setTask });
+      reportTask(status, (result) => [
+        `Imported ${result.imported} Notion pages` +
+          duplicatesNote(result.duplicates) +
+          (result.failed ? `, ${result.failed} failed` : ''),
+        result.failed ? 'warning' : 'success',
+      ]);
+    } catch (e) {
+      onTaskError(e);
+    }
+  };
+
+  const handleImportEnex = () => {
+    importEnex.mutate(
+      { onProgress: setTask },
+      {
+        onSuccess: (status) =>
+          reportTask(status, (result) => [
+            `Imported ${result.imported} Evernote notes with ${result.attachments} attachments` +
+              duplicatesNote(result.duplicates) +
+              (result.failed ? `, ${result.failed} failed` : '') +
+              (result.skipped_resources
+                ? `, ${result.skipped_resources} attachments skipped`
+                : ''),
+            result.failed + result.skipped_resources ? 'warning' : 'success',
+          ]),
+        onError: onTaskError,
+      },
+    );
+  };
+
+  const handleImportBookmarks = () => {
+    importBookmarks.mutate(
+      { onProgress: setTask },
+      {
+        onSuccess: (status) =>
+          reportTask(status, (result) => [
+            `Imported ${result.imported} of ${result.found} bookmarks` +
+              duplicatesNote(result.duplicates) +
+              (result.rejected ? `, ${result.rejected} already saved` : '') +
+              (result.failed ? `, ${result.failed} failed` : ''),
+            result.failed ? 'warning' : 'success',
+          ]),
+        onError: onTaskError,
       },
-      onError: (e) => showToast(e.message, 'error'),
+    );
+  };
+
+  const handleClipUrl = () => {
+    const url = window.prompt('Web page to clip:');
+    if (!url) return;
+    clipUrl.mutate(url, {
+      onSuccess: (snippet) => showToast(`Clipped "${snippet.title}"`, 'success'),
+      onError: (e) => showToast(String(e), 'error'),
     });
   };
 
-  const handleExportJson = () => {
-    exportJson.mutate(
-      {},
+  const handleImportArchive = () => {
+    importArchive.mutate(
+      { onProgress: setTask },
       {
-        onSuccess: (data) => {
-          const blob = new Blob([JSON.stringify(data, null, 2)], { type: 'application/json' });
-          const url = URL.createObjectURL(blob);
-          const a = document.createElement('a');
-          a.href = url;
-          a.download = `pinup-export-${Date.now()}.json`;
-          a.click();
-          URL.revokeObjectURL(url);
-          showToast('Export downloaded', 'success');
-        },
-        onError: (e) => showToast(e.message, 'error'),
+        onSuccess: (status) =>
+          reportTask(status, (result) => {
+            const total = result.imported
+              ? Object.values(result.imported.imported).reduce((a, b) => a + (b as number), 0)
+              : 0;
+            return [
+              `Imported ${total} items and ${result.attachments} attachments` +
+                duplicatesNote(result.duplicates),
+              'success',
+            ];
+          }),
+        onError: onTaskError,
       },
     );
   };
@@ -271,6 +1504,316 @@ export const SettingsPage: React.FC<SettingsPageProps> = ({ showToast }) => {
             />
           </button>
         </label>
+
+        {/* Language */}
+        {locale.data && (
+          <div className="flex items-center justify-between">
+            <div>
+              <p className="text-sm text-gray-700 dark:text-gray-300 font-medium">Language</p>
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                For the tray menu, dialogs and notifications
+              </p>
+            </div>
+            <select
+              value={locale.data.locale ?? ''}
+              onChange={(e) =>
+                setLocale.mutate(e.target.value || null, {
+                  onError: (err) => showToast(String(err), 'error'),
+                })
+              }
+              disabled={setLocale.isPending}
+              className="px-2 py-1 text-xs border border-gray-200 rounded-lg"
+            >
+              <option value="">System default</option>
+              {locale.data.available.map((language) => (
+                <option key={language.code} value={language.code}>
+                  {language.name}
+                </option>
+              ))}
+            </select>
+          </div>
+        )}
+
+        {/* Profiles */}
+        {profiles.data && (
+          <div className="space-y-2">
+            <div className="flex items-center justify-between">
+              <div>
+                <p className="text-sm text-gray-700 dark:text-gray-300 font-medium">Profiles</p>
+                <p className="text-xs text-gray-500 dark:text-gray-400">
+                  Separate notes and settings, e.g. for work and personal; switching restarts the app
+                </p>
+              </div>
+              <button
+                onClick={handleCreateProfile}
+                disabled={createProfile.isPending}
+                className="px-3 py-1 text-xs border border-gray-200 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+              >
+                New profile…
+              </button>
+            </div>
+            {profiles.data.profiles.map((profile) => (
+              <div key={profile.id} className="flex items-center justify-between pl-3">
+                <span className="text-xs text-gray-700 dark:text-gray-300" title={profile.path}>
+                  {profile.name}
+                  {profile.active && <span className="text-gray-400"> (current)</span>}
+                </span>
+                {!profile.active && (
+                  <div className="flex items-center gap-2">
+                    <button
+                      onClick={() => handleSwitchProfile(profile)}
+                      disabled={switchProfile.isPending}
+                      className="px-3 py-1 text-xs border border-gray-200 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                    >
+                      Switch
+                    </button>
+                    {profile.id !== 'default' && (
+                      <button
+                        onClick={() => handleDeleteProfile(profile)}
+                        disabled={deleteProfile.isPending}
+                        className="text-xs text-red-600 hover:text-red-800 underline disabled:opacity-50"
+                      >
+                        Delete
+                      </button>
+                    )}
+                  </div>
+                )}
+              </div>
+            ))}
+          </div>
+        )}
+
+        {/* Data folder */}
+        {dataLocation.data && (
+          <div className="space-y-1">
+            <div className="flex items-center justify-between">
+              <div className="min-w-0">
+                <p className="text-sm text-gray-700 dark:text-gray-300 font-medium">Data folder</p>
+                <p className="text-xs text-gray-500 dark:text-gray-400 truncate" title={dataLocation.data.path}>
+                  {dataLocation.data.path}
+                  {dataLocation.data.env_override && ' (set by PINUP_DATA_DIR)'}
+                </p>
+              </div>
+              <div className="flex items-center gap-2">
+                <button
+                  onClick={handleMoveDataDir}
+                  disabled={moveDataDir.isPending || setDataDir.isPending}
+                  className="px-3 py-1 text-xs border border-gray-200 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                >
+                  {moveDataDir.isPending ? 'Moving…' : 'Move…'}
+                </button>
+                <button
+                  onClick={() => handleSetDataDir(false)}
+                  disabled={moveDataDir.isPending || setDataDir.isPending}
+                  className="px-3 py-1 text-xs border border-gray-200 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                >
+                  Use folder…
+                </button>
+                {dataLocation.data.moved && (
+                  <button
+                    onClick={() => handleSetDataDir(true)}
+                    disabled={moveDataDir.isPending || setDataDir.isPending}
+                    className="text-xs text-gray-600 hover:text-gray-800 underline disabled:opacity-50"
+                  >
+                    Reset
+                  </button>
+                )}
+              </div>
+            </div>
+            {task?.kind === 'move_data_dir' && task.state === 'running' && (
+              <div className="flex items-center gap-3 text-xs text-gray-500">
+                <span>
+                  {task.total ? `${Math.round((task.done / task.total) * 100)}%` : 'Preparing…'}
+                </span>
+                <button
+                  onClick={() => cancelTask(task.id).catch(() => {})}
+                  disabled={task.cancel_requested}
+                  className="px-2 py-1 border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                >
+                  {task.cancel_requested ? 'Cancelling…' : 'Cancel'}
+                </button>
+              </div>
+            )}
+          </div>
+        )}
+
+        {/* Tray */}
+        {autostart.data && (
+          <div className="flex items-center justify-between">
+            <div>
+              <p className="text-sm text-gray-700 dark:text-gray-300 font-medium">Launch at login</p>
+              <p className="text-xs text-gray-500 dark:text-gray-400">Start Pin-Up AI when you log in, optionally in the tray only</p>
+            </div>
+            <div className="flex items-center gap-3">
+              <label className="flex items-center gap-1.5 text-xs text-gray-600 dark:text-gray-300">
+                <input
+                  type="checkbox"
+                  checked={autostart.data.start_hidden}
+                  disabled={setAutostart.isPending || !autostart.data.enabled}
+                  onChange={(e) => handleAutostart({ start_hidden: e.target.checked })}
+                />
+                Start in tray
+              </label>
+              <input
+                type="checkbox"
+                checked={autostart.data.enabled}
+                disabled={setAutostart.isPending}
+                onChange={(e) => handleAutostart({ enabled: e.target.checked })}
+              />
+            </div>
+          </div>
+        )}
+        {traySettings.data && (
+          <div className="flex items-center justify-between">
+            <div>
+              <p className="text-sm text-gray-700 dark:text-gray-300 font-medium">Closing the window</p>
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                Keep running in the tray (capture, sync and reminders continue) or quit
+              </p>
+            </div>
+            <select
+              value={traySettings.data.on_close}
+              onChange={(e) =>
+                setCloseAction.mutate(e.target.value as CloseAction, {
+                  onError: (err) => showToast(String(err), 'error'),
+                })
+              }
+              disabled={setCloseAction.isPending}
+              className="px-2 py-1 text-xs border border-gray-200 rounded-lg"
+            >
+              <option value="hide_to_tray">Keep running in tray</option>
+              <option value="quit">Quit</option>
+              <option value="ask">Ask next time</option>
+            </select>
+          </div>
+        )}
+        {traySettings.data && (
+          <div className="flex items-center justify-between">
+            <div>
+              <p className="text-sm text-gray-700 dark:text-gray-300 font-medium">Start minimized</p>
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                Open to the tray on every launch; the window waits until you open it from the tray
+              </p>
+            </div>
+            <input
+              type="checkbox"
+              checked={traySettings.data.start_minimized}
+              disabled={setTrayStartMinimized.isPending}
+              onChange={(e) =>
+                setTrayStartMinimized.mutate(e.target.checked, { onError: (err) => showToast(String(err), 'error') })
+              }
+            />
+          </div>
+        )}
+        {traySettings.data && (
+          <div className="flex items-center justify-between">
+            <div>
+              <p className="text-sm text-gray-700 dark:text-gray-300 font-medium">Recent snippets in the tray</p>
+              <p className="text-xs text-gray-500 dark:text-gray-400">The tray menu's Recent submenu</p>
+            </div>
+            <div className="flex items-center gap-2">
+              <select
+                value={traySettings.data.recent_count}
+                onChange={(e) => handleTrayRecent({ recent_count: Number(e.target.value) })}
+                disabled={setTrayRecent.isPending}
+                className="px-2 py-1 text-xs border border-gray-200 rounded-lg"
+              >
+                <option value="0">Off</option>
+                <option value="3">3</option>
+                <option value="5">5</option>
+                <option value="10">10</option>
+              </select>
+              <select
+                value={traySettings.data.recent_action}
+                onChange={(e) => handleTrayRecent({ recent_action: e.target.value as TraySettings['recent_action'] })}
+                disabled={setTrayRecent.isPending || traySettings.data.recent_count === 0}
+                className="px-2 py-1 text-xs border border-gray-200 rounded-lg"
+              >
+                <option value="copy">Click copies</option>
+                <option value="open">Click opens</option>
+              </select>
+            </div>
+          </div>
+        )}
+        {traySettings.data?.clicks_supported && (
+          <div className="flex items-center justify-between">
+            <div>
+              <p className="text-sm text-gray-700 dark:text-gray-300 font-medium">Tray icon clicks</p>
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                What a click and a double click on the tray icon do
+                {traySettings.data.click_actions.includes('show_menu') && '; opening the menu changes on the next launch'}
+              </p>
+            </div>
+            <div className="flex items-center gap-2">
+              <select
+                value={traySettings.data.click}
+                onChange={(e) => handleTrayClicks({ click: e.target.value as TrayClickAction })}
+                disabled={setTrayClicks.isPending}
+                className="px-2 py-1 text-xs border border-gray-200 rounded-lg"
+              >
+                {traySettings.data.click_actions.map((action) => (
+                  <option key={action} value={action}>
+                    Click: {TRAY_CLICK_LABELS[action]}
+                  </option>
+                ))}
+              </select>
+              <select
+                value={traySettings.data.double_click}
+                onChange={(e) => handleTrayClicks({ double_click: e.target.value as TrayClickAction })}
+                disabled={setTrayClicks.isPending}
+                className="px-2 py-1 text-xs border border-gray-200 rounded-lg"
+              >
+                {traySettings.data.click_actions
+                  .filter((action) => action !== 'show_menu')
+                  .map((action) => (
+                    <option key={action} value={action}>
+                      Double-click: {TRAY_CLICK_LABELS[action]}
+                    </option>
+                  ))}
+              </select>
+            </div>
+          </div>
+        )}
+        {notificationSettings.data && (
+          <div className="flex items-center justify-between">
+            <div>
+              <p className="text-sm text-gray-700 dark:text-gray-300 font-medium">Job notifications</p>
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                Notify when imports, exports and AI jobs finish while the window is in the background
+              </p>
+            </div>
+            <input
+              type="checkbox"
+              checked={notificationSettings.data.enabled}
+              disabled={setNotifications.isPending}
+              onChange={(e) => handleNotifications(e.target.checked)}
+            />
+          </div>
+        )}
+        {notificationSettings.data && focusState.data?.supported && (
+          <div className="flex items-center justify-between">
+            <div>
+              <p className="text-sm text-gray-700 dark:text-gray-300 font-medium">During Do Not Disturb</p>
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                {focusState.data.active
+                  ? `${focusState.data.source ?? 'Do Not Disturb'} is on now`
+                  : 'Notifications and reminders while the system is in Focus or Do Not Disturb'}
+              </p>
+            </div>
+            <select
+              value={notificationSettings.data.during_focus}
+              onChange={(e) => handleDuringFocus(e.target.value as FocusAction)}
+              disabled={setDuringFocus.isPending}
+              className="px-2 py-1 text-xs border border-gray-200 rounded-lg"
+            >
+              {(Object.keys(FOCUS_ACTION_LABELS) as FocusAction[]).map((action) => (
+                <option key={action} value={action}>
+                  {FOCUS_ACTION_LABELS[action]}
+                </option>
+              ))}
+            </select>
+          </div>
+        )}
       </Section>
 
       {/* ── License ────────────────────────────────────────────────────── */}
@@ -361,6 +1904,981 @@ export const SettingsPage: React.FC<SettingsPageProps> = ({ showToast }) => {
             aria-label="Choose file to import"
           />
         </div>
+
+        {inShell && (
+          <div className="mt-4 space-y-2">
+            {task?.state === 'running' && (
+              <div className="flex items-center gap-3 text-xs text-gray-500">
+                <div className="flex-1 h-1.5 bg-gray-100 rounded-full overflow-hidden">
+                  <div
+                    className="h-full bg-brand-500"
+                    style={{
+                      width: task.total ? `${Math.min(100, (task.done / task.total) * 100)}%` : '0%',
+                    }}
+                  />
+                </div>
+                <span className="truncate max-w-[14rem]">{task.current}</span>
+                <button
+                  onClick={() => cancelTask(task.id).catch(() => {})}
+                  disabled={task.cancel_requested}
+                  className="px-2 py-1 border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                >
+                  {task.cancel_requested ? 'Cancelling…' : 'Cancel'}
+                </button>
+              </div>
+            )}
+            <div className="flex flex-wrap items-center gap-3 text-sm">
+              <label htmlFor="duplicate-policy" className="text-gray-700">
+                Items already in your library
+              </label>
+              <select
+                id="duplicate-policy"
+                value={duplicatePolicy.data ?? 'skip'}
+                onChange={(e) => handleDuplicatePolicy(e.target.value as DuplicatePolicy)}
+                disabled={!duplicatePolicy.data || setDuplicatePolicy.isPending}
+                className="px-3 py-2 text-sm border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none disabled:opacity-50"
+              >
+                <option value="skip">Skip them</option>
+                <option value="merge">Merge tags and collections into the existing snippet</option>
+                <option value="flag">Import them tagged "duplicate"</option>
+              </select>
+            </div>
+            <p className="text-xs text-gray-500">
+              Encrypted exports are safe to keep in cloud drives. Without the passphrase they
+              cannot be opened.
+            </p>
+            <div className="flex flex-wrap gap-3">
+              <input
+                type="password"
+                value={exportPassphrase}
+                onChange={(e) => setExportPassphrase(e.target.value)}
+                placeholder="Passphrase (8+ characters)"
+                autoComplete="new-password"
+                className="flex-1 min-w-[12rem] px-3 py-2 text-sm border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+              />
+              <button
+                onClick={handleExportEncrypted}
+                disabled={exportPassphrase.length < 8 || exportEncrypted.isPending}
+                className="flex items-center gap-1.5 px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+              >
+                <Shield className="w-4 h-4" />
+                {exportEncrypted.isPending ? 'Encrypting…' : 'Export encrypted'}
+              </button>
+              <button
+                onClick={handleImportEncrypted}
+                disabled={!exportPassphrase || importEncrypted.isPending}
+                className="flex items-center gap-1.5 px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+              >
+                <Upload className="w-4 h-4" />
+                {importEncrypted.isPending ? 'Importing…' : 'Import encrypted…'}
+              </button>
+            </div>
+            <div className="flex flex-wrap gap-3">
+              <button
+                onClick={handleExportArchive}
+                disabled={exportArchive.isPending}
+                className="flex items-center gap-1.5 px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+              >
+                <Download className="w-4 h-4" />
+                {exportArchive.isPending ? 'Exporting…' : 'Export archive with attachments'}
+              </button>
+              <button
+                onClick={handleImportArchive}
+                disabled={importArchive.isPending}
+                className="flex items-center gap-1.5 px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+              >
+                <Upload className="w-4 h-4" />
+                {importArchive.isPending ? 'Importing…' : 'Import archive…'}
+              </button>
+              <button
+                onClick={handleExportMarkdown}
+                disabled={exportMarkdown.isPending}
+                className="flex items-center gap-1.5 px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+              >
+                <Download className="w-4 h-4" />
+                {exportMarkdown.isPending ? 'Exporting…' : 'Export Markdown folder…'}
+              </button>
+              <button
+                onClick={handleImportNotion}
+                disabled={importNotion.isPending}
+                className="flex items-center gap-1.5 px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+              >
+                <Upload className="w-4 h-4" />
+                {importNotion.isPending ? 'Reading Notion export…' : 'Import from Notion…'}
+              </button>
+              <button
+                onClick={handleImportEnex}
+                disabled={importEnex.isPending}
+                className="flex items-center gap-1.5 px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+              >
+                <Upload className="w-4 h-4" />
+                {importEnex.isPending ? 'Importing Evernote notes…' : 'Import from Evernote…'}
+              </button>
+              <button
+                onClick={handleImportBookmarks}
+                disabled={importBookmarks.isPending}
+                className="flex items-center gap-1.5 px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+              >
+                <Upload className="w-4 h-4" />
+                {importBookmarks.isPending ? 'Importing bookmarks…' : 'Import browser bookmarks…'}
+              </button>
+              <button
+                onClick={handleClipUrl}
+                disabled={clipUrl.isPending}
+                className="flex items-center gap-1.5 px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+              >
+                <Download className="w-4 h-4" />
+                {clipUrl.isPending ? 'Clipping page…' : 'Clip web page…'}
+              </button>
+            </div>
+            <div className="flex flex-wrap items-center gap-3">
+              <button
+                onClick={() => handleObsidianVault(false)}
+                disabled={setObsidianVault.isPending}
+                className="flex items-center gap-1.5 px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+              >
+                <RefreshCw className="w-4 h-4" />
+                {obsidian.data?.root ? 'Change Obsidian vault…' : 'Mirror to Obsidian vault…'}
+              </button>
+              {obsidian.data?.root && (
+                <>
+                  <button
+                    onClick={() => handleObsidianVault(true)}
+                    disabled={setObsidianVault.isPending}
+                    className="px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                  >
+                    Stop mirroring
+                  </button>
+                  <span className="text-xs text-gray-500">
+                    {obsidian.data.root}
+                    {obsidian.data.last_sync &&
+                      ` · synced ${formatDate(Date.parse(obsidian.data.last_sync.at))}`}
+                    {obsidian.data.last_sync?.conflicts.length
+                      ? ` · ${obsidian.data.last_sync.conflicts.length} edited in Obsidian, not updated`
+                      : ''}
+                    {obsidian.data.last_error && ` · ${obsidian.data.last_error}`}
+                  </span>
+                </>
+              )}
+            </div>
+            {syncKey.data && (
+              <div className="space-y-2">
+                <p className="text-xs text-gray-500">
+                  Everything sync sends is encrypted on this device with your sync key, so no
+                  provider sees your snippets. Create it once, then enter its recovery phrase on
+                  your other devices. Keep the phrase somewhere safe: without it synced data can't
+                  be read.
+                </p>
+                <div className="flex flex-wrap items-center gap-3">
+                  <span className="flex items-center gap-1.5 text-sm">
+                    <Key className="w-4 h-4 text-gray-500" />
+                    {syncKey.data.has_key ? `Sync key ${syncKey.data.key_id}` : 'No sync key on this device'}
+                  </span>
+                  <button
+                    onClick={handleCreateSyncKey}
+                    disabled={createSyncKey.isPending}
+                    className="px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                  >
+                    {syncKey.data.has_key ? 'Replace…' : 'Create sync key'}
+                  </button>
+                  <button
+                    onClick={handleRestoreSyncKey}
+                    disabled={restoreSyncKey.isPending}
+                    className="px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                  >
+                    Enter recovery phrase…
+                  </button>
+                  {syncKey.data.has_key ? (
+                    <>
+                      <button
+                        onClick={() =>
+                          recoveryPhrase.mutate(undefined, {
+                            onSuccess: setShownPhrase,
+                            onError: (e) => showToast(String(e), 'error'),
+                          })
+                        }
+                        className="px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50"
+                      >
+                        Show recovery phrase
+                      </button>
+                      <button
+                        onClick={handleBackupSyncKey}
+                        disabled={backupSyncKey.isPending}
+                        className="px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                      >
+                        Back up to file…
+                      </button>
+                      <button
+                        onClick={handleForgetSyncKey}
+                        className="p-1 text-gray-400 hover:text-red-600"
+                        title="Remove from this device"
+                      >
+                        <Trash2 className="w-4 h-4" />
+                      </button>
+                    </>
+                  ) : (
+                    <button
+                      onClick={handleRestoreSyncKeyBackup}
+                      disabled={restoreSyncKeyBackup.isPending}
+                      className="px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                    >
+                      Restore from file…
+                    </button>
+                  )}
+                </div>
+                {shownPhrase && (
+                  <div className="flex items-center gap-3">
+                    <code className="px-3 py-2 text-sm font-mono tracking-wide bg-gray-50 border border-gray-200 rounded-lg select-all">
+                      {shownPhrase}
+                    </code>
+                    <button onClick={() => setShownPhrase(null)} className="text-xs text-gray-500 hover:text-gray-700">
+                      Hide
+                    </button>
+                  </div>
+                )}
+              </div>
+            )}
+            <div className="flex flex-wrap items-center gap-3">
+              <button
+                onClick={() => handleGitSync(false)}
+                disabled={setGitSync.isPending}
+                className="flex items-center gap-1.5 px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+              >
+                <RefreshCw className="w-4 h-4" />
+                {gitSync.data?.config.repo ? 'Change Git repository…' : 'Sync with a Git repository…'}
+              </button>
+              <input
+                type="text"
+                value={gitRemoteValue}
+                onChange={(e) => setGitRemote(e.target.value)}
+                onBlur={handleGitRemote}
+                placeholder="Remote URL (optional)"
+                className="w-64 px-3 py-2 text-sm border border-gray-300 rounded-lg"
+              />
+              {gitSync.data?.config.repo && (
+                <label
+                  className="flex items-center gap-1.5 text-sm text-gray-600"
+                  title="Earlier commits keep their plaintext; start a new repository to be rid of it"
+                >
+                  <input
+                    type="checkbox"
+                    checked={gitSync.data.config.encrypt}
+                    onChange={(e) =>
+                      setGitSync.mutate(
+                        {
+                          repo: gitSync.data?.config.repo ?? undefined,
+                          remote: gitRemoteValue.trim() || null,
+                          encrypt: e.target.checked,
+                        },
+                        { onError: (err) => showToast(String(err), 'error') },
+                      )
+                    }
+                  />
+                  Encrypt
+                </label>
+              )}
+              {gitSync.data?.config.repo && (
+                <>
+                  <button
+                    onClick={handleSyncGitNow}
+                    disabled={syncGitNow.isPending || gitSync.data.syncing}
+                    className="px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                  >
+                    {syncGitNow.isPending ? 'Syncing…' : 'Sync now'}
+                  </button>
+                  <button
+                    onClick={() => handleGitSync(true)}
+                    disabled={setGitSync.isPending}
+                    className="px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                  >
+                    Stop syncing
+                  </button>
+                  <span className="text-xs text-gray-500">
+                    {gitSync.data.config.repo}
+                    {gitSync.data.last_sync &&
+                      ` · synced ${formatDate(Date.parse(gitSync.data.last_sync.at))}` +
+                        (gitSync.data.last_sync.commit ? ` at ${gitSync.data.last_sync.commit}` : '')}
+                    {gitSync.data.last_sync?.pending
+                      ? ` · ${gitSync.data.last_sync.pending} pulled snippets not applied yet`
+                      : ''}
+                    {gitSync.data.last_error && ` · ${gitSync.data.last_error}`}
+                  </span>
+                </>
+              )}
+            </div>
+            <div className="space-y-2">
+              <p className="text-xs text-gray-500">
+                WebDAV sync (Nextcloud, Fastmail…) encrypts everything with your sync key before
+                upload, or with a passphrase of your own. Use the same folder on every device.
+              </p>
+              <div className="flex flex-wrap gap-3">
+                <input
+                  type="url"
+                  value={webdavUrlValue}
+                  onChange={(e) => setWebdavUrl(e.target.value)}
+                  placeholder="https://cloud.example.com/remote.php/dav/files/me/PinUp"
+                  className="flex-1 min-w-[16rem] px-3 py-2 text-sm border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                />
+                <input
+                  type="text"
+                  value={webdavUserValue}
+                  onChange={(e) => setWebdavUser(e.target.value)}
+                  placeholder="Username"
+                  autoComplete="username"
+                  className="w-40 px-3 py-2 text-sm border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                />
+              </div>
+              <div className="flex flex-wrap gap-3">
+                <input
+                  type="password"
+                  value={webdavPassword}
+                  onChange={(e) => setWebdavPassword(e.target.value)}
+                  placeholder={webdav.data?.has_credentials ? 'Password (saved)' : 'Password or app password'}
+                  autoComplete="current-password"
+                  className="flex-1 min-w-[12rem] px-3 py-2 text-sm border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                />
+                <input
+                  type="password"
+                  value={webdavPassphrase}
+                  onChange={(e) => setWebdavPassphrase(e.target.value)}
+                  placeholder={webdav.data?.has_credentials ? 'Own passphrase (unchanged)' : 'Own passphrase (optional)'}
+                  autoComplete="new-password"
+                  className="flex-1 min-w-[12rem] px-3 py-2 text-sm border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                />
+                <button
+                  onClick={handleSetWebDav}
+                  disabled={!webdavUrlValue || setWebDav.isPending}
+                  className="flex items-center gap-1.5 px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                >
+                  <Shield className="w-4 h-4" />
+                  {setWebDav.isPending ? 'Connecting…' : webdav.data?.config.enabled ? 'Save' : 'Turn on WebDAV sync'}
+                </button>
+              </div>
+              {webdav.data?.config.enabled && (
+                <div className="flex flex-wrap items-center gap-3">
+                  <button
+                    onClick={handleWebDavSyncNow}
+                    disabled={webdavSyncNow.isPending || webdav.data.syncing}
+                    className="px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                  >
+                    {webdavSyncNow.isPending ? 'Syncing…' : 'Sync now'}
+                  </button>
+                  <button
+                    onClick={handleDisableWebDav}
+                    disabled={disableWebDav.isPending}
+                    className="px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                  >
+                    Stop syncing
+                  </button>
+                  <span className="text-xs text-gray-500">
+                    {webdav.data.last_sync
+                      ? `Synced ${formatDate(Date.parse(webdav.data.last_sync.at))}`
+                      : 'Not synced yet'}
+                    {webdav.data.last_error && ` · ${webdav.data.last_error}`}
+                  </span>
+                </div>
+              )}
+            </div>
+            <div className="space-y-2">
+              <p className="text-xs text-gray-500">
+                S3 sync (AWS, MinIO, Backblaze B2, R2…) uses the same encrypted change log. Change sets go
+                under <code>changes/</code> and snapshots under <code>snapshots/</code> in the prefix, for
+                bucket lifecycle rules.
+              </p>
+              <div className="flex flex-wrap gap-3">
+                <input
+                  type="url"
+                  value={s3EndpointValue}
+                  onChange={(e) => setS3Endpoint(e.target.value)}
+                  placeholder="https://s3.us-west-004.backblazeb2.com"
+                  className="flex-1 min-w-[16rem] px-3 py-2 text-sm border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                />
+                <input
+                  type="text"
+                  value={s3RegionValue}
+                  onChange={(e) => setS3Region(e.target.value)}
+                  placeholder="Region"
+                  className="w-32 px-3 py-2 text-sm border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                />
+              </div>
+              <div className="flex flex-wrap items-center gap-3">
+                <input
+                  type="text"
+                  value={s3BucketValue}
+                  onChange={(e) => setS3Bucket(e.target.value)}
+                  placeholder="Bucket"
+                  className="w-48 px-3 py-2 text-sm border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                />
+                <input
+                  type="text"
+                  value={s3PrefixValue}
+                  onChange={(e) => setS3Prefix(e.target.value)}
+                  placeholder="Prefix (optional)"
+                  className="w-40 px-3 py-2 text-sm border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                />
+                <label className="flex items-center gap-2 text-sm text-gray-700">
+                  <input
+                    type="checkbox"
+                    checked={s3PathStyleValue}
+                    onChange={(e) => setS3PathStyle(e.target.checked)}
+                  />
+                  Path-style URLs
+                </label>
+              </div>
+              <div className="flex flex-wrap gap-3">
+                <input
+                  type="text"
+                  value={s3AccessKey}
+                  onChange={(e) => setS3AccessKey(e.target.value)}
+                  placeholder={s3.data?.has_credentials ? 'Access key ID (saved)' : 'Access key ID'}
+                  autoComplete="off"
+                  className="flex-1 min-w-[10rem] px-3 py-2 text-sm border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                />
+                <input
+                  type="password"
+                  value={s3SecretKey}
+                  onChange={(e) => setS3SecretKey(e.target.value)}
+                  placeholder={s3.data?.has_credentials ? 'Secret key (saved)' : 'Secret access key'}
+                  autoComplete="off"
+                  className="flex-1 min-w-[10rem] px-3 py-2 text-sm border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                />
+                <input
+                  type="password"
+                  value={s3Passphrase}
+                  onChange={(e) => setS3Passphrase(e.target.value)}
+                  placeholder={s3.data?.has_credentials ? 'Own passphrase (unchanged)' : 'Own passphrase (optional)'}
+                  autoComplete="new-password"
+                  className="flex-1 min-w-[10rem] px-3 py-2 text-sm border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                />
+                <button
+                  onClick={handleSetS3Sync}
+                  disabled={!s3EndpointValue || !s3BucketValue || setS3Sync.isPending}
+                  className="flex items-center gap-1.5 px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                >
+                  <Shield className="w-4 h-4" />
+                  {setS3Sync.isPending ? 'Connecting…' : s3.data?.config.enabled ? 'Save' : 'Turn on S3 sync'}
+                </button>
+              </div>
+              {s3.data?.config.enabled && (
+                <div className="flex flex-wrap items-center gap-3">
+                  <button
+                    onClick={handleS3SyncNow}
+                    disabled={s3SyncNow.isPending || s3.data.syncing}
+                    className="px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                  >
+                    {s3SyncNow.isPending ? 'Syncing…' : 'Sync now'}
+                  </button>
+                  <button
+                    onClick={handleDisableS3Sync}
+                    disabled={disableS3Sync.isPending}
+                    className="px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                  >
+                    Stop syncing
+                  </button>
+                  <span className="text-xs text-gray-500">
+                    {s3.data.last_sync
+                      ? `Synced ${formatDate(Date.parse(s3.data.last_sync.at))}`
+                      : 'Not synced yet'}
+                    {s3.data.last_error && ` · ${s3.data.last_error}`}
+                  </span>
+                </div>
+              )}
+            </div>
+            {lanSync.data && (
+              <div className="space-y-2">
+                <p className="text-xs text-gray-500">
+                  LAN sync keeps devices on the same network in sync directly, with no cloud. Show a
+                  code on one device and pair from the other; changes are encrypted end to end.
+                </p>
+                <div className="flex flex-wrap items-center gap-3">
+                  <input
+                    type="text"
+                    value={lanName ?? lanSync.data.device_name}
+                    onChange={(e) => setLanName(e.target.value)}
+                    placeholder="This device's name"
+                    className="w-56 px-3 py-2 text-sm border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                  />
+                  <button
+                    onClick={() => handleSetLanSync(lanName !== null || !lanSync.data.enabled)}
+                    disabled={setLanSync.isPending}
+                    className="px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                  >
+                    {lanSync.data.enabled ? (lanName !== null ? 'Save' : 'Stop LAN sync') : 'Turn on LAN sync'}
+                  </button>
+                  {lanSync.data.enabled &&
+                    (lanSync.data.pairing ? (
+                      <>
+                        <span className="font-mono text-sm font-semibold tracking-widest">
+                          {lanSync.data.pairing.code}
+                        </span>
+                        <span className="text-xs text-gray-500">
+                          until {new Date(lanSync.data.pairing.expires_at).toLocaleTimeString()}
+                        </span>
+                        <button
+                          onClick={() => cancelLanPairing.mutate(undefined)}
+                          className="px-3 py-2 text-sm border border-gray-300 rounded-lg hover:bg-gray-50"
+                        >
+                          Cancel
+                        </button>
+                      </>
+                    ) : (
+                      <button
+                        onClick={() =>
+                          startLanPairing.mutate(undefined, {
+                            onError: (e) => showToast(String(e), 'error'),
+                          })
+                        }
+                        className="px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50"
+                      >
+                        Show pairing code
+                      </button>
+                    ))}
+                  {lanSync.data.enabled && lanSync.data.peers.length > 0 && (
+                    <button
+                      onClick={() =>
+                        lanSyncNow.mutate(undefined, {
+                          onSuccess: () => showToast('LAN sync finished', 'success'),
+                          onError: (e) => showToast(String(e), 'error'),
+                        })
+                      }
+                      disabled={lanSyncNow.isPending || lanSync.data.syncing}
+                      className="px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                    >
+                      {lanSyncNow.isPending ? 'Syncing…' : 'Sync now'}
+                    </button>
+                  )}
+                </div>
+                {lanSync.data.network_error && (
+                  <p className="text-xs text-red-600">{lanSync.data.network_error}</p>
+                )}
+                {lanSync.data.peers.map((peer) => (
+                  <div key={`${peer.device}-${peer.host}`} className="flex items-center gap-3 text-sm">
+                    <span className={peer.online ? 'text-green-600' : 'text-gray-400'}>●</span>
+                    <span className="font-medium">{peer.name}</span>
+                    <span className="text-xs text-gray-500">
+                      {peer.host ? 'syncs through its log' : 'syncs through this device'}
+                      {peer.host && peer.last_sync && ` · synced ${formatDate(Date.parse(peer.last_sync.at))}`}
+                      {!peer.host && peer.last_contact && ` · last seen ${formatDate(Date.parse(peer.last_contact))}`}
+                      {peer.last_error && ` · ${peer.last_error}`}
+                    </span>
+                    <button
+                      onClick={() => handleRemoveLanPeer(peer.device, peer.host, peer.name)}
+                      className="ml-auto p-1 text-gray-400 hover:text-red-600"
+                      title="Unpair"
+                    >
+                      <Trash2 className="w-4 h-4" />
+                    </button>
+                  </div>
+                ))}
+                {lanSync.data.enabled &&
+                  lanSync.data.nearby.map((nearby) => (
+                    <div key={nearby.device} className="flex items-center gap-3 text-sm">
+                      <span className="text-gray-400">○</span>
+                      <span>{nearby.name}</span>
+                      <button
+                        onClick={() => handlePairLanDevice(nearby.device, nearby.name)}
+                        disabled={pairLanDevice.isPending}
+                        className="px-3 py-1 text-xs font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                      >
+                        {pairLanDevice.isPending ? 'Pairing…' : 'Pair'}
+                      </button>
+                    </div>
+                  ))}
+              </div>
+            )}
+            {companion.data && (
+              <div className="space-y-2">
+                <p className="text-xs text-gray-500">
+                  The mobile companion lets a paired phone on the same network send captures here.
+                  Scan the code with the Pin-Up AI app; a paired phone can only add snippets.
+                </p>
+                <div className="flex flex-wrap items-center gap-3">
+                  <button
+                    onClick={() => handleSetCompanion(!companion.data.enabled)}
+                    disabled={setCompanion.isPending}
+                    className="px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                  >
+                    {companion.data.enabled ? 'Stop mobile companion' : 'Turn on mobile companion'}
+                  </button>
+                  {companion.data.enabled && !companion.data.pairing && (
+                    <button
+                      onClick={() =>
+                        startCompanionPairing.mutate(undefined, {
+                          onError: (e) => showToast(String(e), 'error'),
+                        })
+                      }
+                      className="px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50"
+                    >
+                      Pair a phone
+                    </button>
+                  )}
+                  <button onClick={handleCompanionPort} className="text-xs text-gray-500 hover:underline">
+                    {companion.data.address ?? `Port ${companion.data.port}`}
+                  </button>
+                </div>
+                {companion.data.network_error && (
+                  <p className="text-xs text-red-600">{companion.data.network_error}</p>
+                )}
+                {companion.data.pairing && (
+                  <div className="flex items-center gap-4">
+                    <img
+                      src={`data:image/svg+xml;utf8,${encodeURIComponent(companion.data.pairing.qr_svg)}`}
+                      alt="Pairing QR code"
+                      className="w-40 h-40 border border-gray-200 rounded-lg"
+                    />
+                    <div className="space-y-2">
+                      <p className="text-xs text-gray-500">
+                        Scan with the phone before{' '}
+                        {new Date(companion.data.pairing.expires_at).toLocaleTimeString()}.
+                      </p>
+                      <button
+                        onClick={() => cancelCompanionPairing.mutate(undefined)}
+                        className="px-3 py-2 text-sm border border-gray-300 rounded-lg hover:bg-gray-50"
+                      >
+                        Cancel
+                      </button>
+                    </div>
+                  </div>
+                )}
+                {companion.data.devices.map((device) => (
+                  <div key={device.device} className="flex items-center gap-3 text-sm">
+                    <span className="font-medium">{device.name}</span>
+                    <span className="text-xs text-gray-500">
+                      paired {formatDate(device.paired_at)}
+                      {device.last_capture && ` · last capture ${formatDate(Date.parse(device.last_capture))}`}
+                    </span>
+                    <button
+                      onClick={() => handleRemoveCompanionDevice(device.device, device.name)}
+                      className="ml-auto p-1 text-gray-400 hover:text-red-600"
+                      title="Unpair"
+                    >
+                      <Trash2 className="w-4 h-4" />
+                    </button>
+                  </div>
+                ))}
+              </div>
+            )}
+            {syncConflicts.data && syncConflicts.data.length > 0 && (
+              <div className="space-y-2">
+                <p className="flex items-center gap-1.5 text-xs text-amber-700">
+                  <AlertTriangle className="w-4 h-4" />
+                  {syncConflicts.data.length === 1
+                    ? 'A snippet was changed on two devices at once.'
+                    : `${syncConflicts.data.length} snippets were changed on two devices at once.`}{' '}
+                  Sync kept one version; choose which to keep.
+                </p>
+                {syncConflicts.data.map((conflict) => (
+                  <ConflictRow
+                    key={conflict.id}
+                    conflict={conflict}
+                    busy={resolveConflict.isPending}
+                    onResolve={(strategy, merged) => handleResolveConflict(conflict.id, strategy, merged)}
+                  />
+                ))}
+              </div>
+            )}
+            <div className="space-y-2">
+              <div className="flex items-center gap-3">
+                <button
+                  onClick={handleAddWatchFolder}
+                  disabled={setWatchFolder.isPending}
+                  className="flex items-center gap-1.5 px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                >
+                  <Clock className="w-4 h-4" />
+                  Add watch folder…
+                </button>
+                <span className="text-xs text-gray-500">
+                  New files in these folders are imported automatically.
+                </span>
+              </div>
+              {watchFolders.data?.map((folder) => (
+                <WatchFolderRow
+                  key={folder.path}
+                  folder={folder}
+                  onChange={handleWatchFolderChange}
+                  onRemove={() => handleRemoveWatchFolder(folder.path)}
+                />
+              ))}
+            </div>
+            <div className="space-y-2">
+              <div className="flex flex-wrap items-center gap-3">
+                <button
+                  onClick={handleAddFeed}
+                  disabled={setFeed.isPending}
+                  className="flex items-center gap-1.5 px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                >
+                  <Download className="w-4 h-4" />
+                  {setFeed.isPending ? 'Checking feed…' : 'Follow feed…'}
+                </button>
+                <button
+                  onClick={handleRefreshFeeds}
+                  disabled={refreshFeeds.isPending || feeds.data?.refreshing || !feeds.data?.feeds.length}
+                  className="flex items-center gap-1.5 px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                >
+                  <RefreshCw className={cn('w-4 h-4', (refreshFeeds.isPending || feeds.data?.refreshing) && 'animate-spin')} />
+                  Refresh feeds
+                </button>
+                {feeds.data && (
+                  <>
+                    <label className="flex items-center gap-1 text-xs text-gray-600">
+                      <input
+                        type="checkbox"
+                        checked={feeds.data.enabled}
+                        onChange={(e) =>
+                          setFeeds.mutate({ enabled: e.target.checked }, { onError: (err) => showToast(String(err), 'error') })
+                        }
+                      />
+                      Check automatically
+                    </label>
+                    <select
+                      value={feeds.data.interval_minutes}
+                      onChange={(e) =>
+                        setFeeds.mutate(
+                          { intervalMinutes: Number(e.target.value) },
+                          { onError: (err) => showToast(String(err), 'error') },
+                        )
+                      }
+                      className="px-2 py-1 text-xs border border-gray-200 rounded-lg"
+                    >
+                      <option value={15}>Every 15 min</option>
+                      <option value={60}>Hourly</option>
+                      <option value={360}>Every 6 hours</option>
+                      <option value={1440}>Daily</option>
+                    </select>
+                    <input
+                      key={feeds.data.collection}
+                      defaultValue={feeds.data.collection}
+                      onBlur={(e) => handleFeedsCollection(e.target.value)}
+                      placeholder="Default collection"
+                      title="Collection new entries are filed into"
+                      className="w-36 px-2 py-1 text-xs border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                    />
+                  </>
+                )}
+              </div>
+              {feeds.data?.feeds.map((feed) => (
+                <FeedRow
+                  key={feed.url}
+                  feed={feed}
+                  onChange={handleFeedChange}
+                  onRemove={() => handleRemoveFeed(feed.url)}
+                />
+              ))}
+            </div>
+            {emailSettings && (
+              <div className="space-y-2">
+                <p className="text-xs text-gray-500">
+                  Email in: mail that arrives in a folder (or Gmail label) of your mailbox becomes a snippet,
+                  attachments included. Set up a filter that moves mail you send yourself there, and use an app
+                  password where your provider offers one.
+                </p>
+                <div className="flex flex-wrap gap-3">
+                  <input
+                    type="text"
+                    value={emailSettings.host ?? ''}
+                    onChange={(e) => setEmailDraft({ ...emailDraft, host: e.target.value })}
+                    placeholder="imap.example.com"
+                    className="flex-1 min-w-[12rem] px-3 py-2 text-sm border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                  />
+                  <input
+                    type="number"
+                    value={emailSettings.port}
+                    onChange={(e) => setEmailDraft({ ...emailDraft, port: Number(e.target.value) })}
+                    className="w-20 px-3 py-2 text-sm border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                  />
+                  <label className="flex items-center gap-1 text-xs text-gray-600">
+                    <input
+                      type="checkbox"
+                      checked={emailSettings.starttls}
+                      onChange={(e) =>
+                        setEmailDraft({ ...emailDraft, starttls: e.target.checked, port: e.target.checked ? 143 : 993 })
+                      }
+                    />
+                    STARTTLS
+                  </label>
+                </div>
+                <div className="flex flex-wrap gap-3">
+                  <input
+                    type="text"
+                    value={emailSettings.username ?? ''}
+                    onChange={(e) => setEmailDraft({ ...emailDraft, username: e.target.value })}
+                    placeholder="Username"
+                    autoComplete="username"
+                    className="w-48 px-3 py-2 text-sm border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                  />
+                  <input
+                    type="password"
+                    value={emailPassword}
+                    onChange={(e) => setEmailPassword(e.target.value)}
+                    placeholder={emailIn.data?.has_password ? 'Password (saved)' : 'Password or app password'}
+                    autoComplete="current-password"
+                    className="flex-1 min-w-[12rem] px-3 py-2 text-sm border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                  />
+                </div>
+                <div className="flex flex-wrap items-center gap-3">
+                  <input
+                    type="text"
+                    value={emailSettings.folder}
+                    onChange={(e) => setEmailDraft({ ...emailDraft, folder: e.target.value })}
+                    placeholder="Folder"
+                    title="Folder or label to import from"
+                    className="w-36 px-3 py-2 text-sm border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                  />
+                  <input
+                    type="text"
+                    value={emailSettings.collection ?? ''}
+                    onChange={(e) => setEmailDraft({ ...emailDraft, collection: e.target.value || null })}
+                    placeholder="Collection (optional)"
+                    className="w-44 px-3 py-2 text-sm border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                  />
+                  <label className="flex items-center gap-1 text-xs text-gray-600">
+                    <input
+                      type="checkbox"
+                      checked={emailSettings.delete_after_import}
+                      onChange={(e) => setEmailDraft({ ...emailDraft, delete_after_import: e.target.checked })}
+                    />
+                    Delete after import
+                  </label>
+                  <button
+                    onClick={handleSetEmailIn}
+                    disabled={!emailSettings.host || !emailSettings.username || setEmailIn.isPending}
+                    className="flex items-center gap-1.5 px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                  >
+                    <Shield className="w-4 h-4" />
+                    {setEmailIn.isPending
+                      ? 'Signing in…'
+                      : emailIn.data?.config.enabled
+                        ? 'Save'
+                        : 'Turn on email in'}
+                  </button>
+                </div>
+                {emailIn.data?.config.enabled && (
+                  <div className="flex flex-wrap items-center gap-3">
+                    <button
+                      onClick={handleCheckEmailNow}
+                      disabled={checkEmailNow.isPending || emailIn.data.checking}
+                      className="px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                    >
+                      {checkEmailNow.isPending ? 'Checking…' : 'Check now'}
+                    </button>
+                    <button
+                      onClick={handleDisableEmailIn}
+                      disabled={disableEmailIn.isPending}
+                      className="px-4 py-2 text-sm font-medium border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                    >
+                      Turn off
+                    </button>
+                    <span className="text-xs text-gray-500">
+                      {emailIn.data.last_check
+                        ? `Last check: ${emailIn.data.last_check.imported} imported`
+                        : 'Not checked yet'}
+                      {emailIn.data.last_error && ` · ${emailIn.data.last_error}`}
+                    </span>
+                  </div>
+                )}
+              </div>
+            )}
+            {nativeHost.data && (
+              <div className="space-y-2">
+                <p className="text-xs text-gray-500">
+                  Browser extension: the Pin-Up AI extension sends selections, links and whole pages straight here,
+                  starting the app if needed. Chrome-family browsers need the extension's ID from their extensions page.
+                </p>
+                <div className="flex flex-wrap items-center gap-3">
+                  <label className="flex items-center gap-1 text-xs text-gray-600">
+                    <input
+                      type="checkbox"
+                      checked={nativeHost.data.enabled}
+                      disabled={setNativeHost.isPending}
+                      onChange={(e) => handleNativeHost({ enabled: e.target.checked })}
+                    />
+                    Accept clips from browser extensions
+                  </label>
+                  <span className="text-xs text-gray-500">
+                    {nativeHost.data.browsers
+                      .filter((b) => b.registered)
+                      .map((b) => b.name)
+                      .join(', ') || 'Not registered with any browser'}
+                    {nativeHost.data.last_message &&
+                      ` · Last clip: ${formatDate(Date.parse(nativeHost.data.last_message))}`}
+                    {nativeHost.data.error && ` · ${nativeHost.data.error}`}
+                  </span>
+                </div>
+                {nativeHost.data.enabled && (
+                  <div className="flex flex-wrap gap-3">
+                    <input
+                      key={`chrome-${nativeHost.data.chrome_extensions.join(',')}`}
+                      defaultValue={nativeHost.data.chrome_extensions.join(', ')}
+                      onBlur={(e) => handleNativeHostIds('chrome', e.target.value)}
+                      placeholder="Chrome/Edge extension IDs"
+                      title="Extensions in Chrome, Edge, Brave or Vivaldi allowed to send clips"
+                      className="flex-1 min-w-[12rem] px-3 py-2 text-xs font-mono border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                    />
+                    <input
+                      key={`firefox-${nativeHost.data.firefox_extensions.join(',')}`}
+                      defaultValue={nativeHost.data.firefox_extensions.join(', ')}
+                      onBlur={(e) => handleNativeHostIds('firefox', e.target.value)}
+                      placeholder="Firefox add-on IDs"
+                      title="Firefox add-ons allowed to send clips"
+                      className="flex-1 min-w-[12rem] px-3 py-2 text-xs font-mono border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                    />
+                  </div>
+                )}
+              </div>
+            )}
+            {deepLinks.data && (
+              <div className="space-y-2">
+                <p className="text-xs text-gray-500">
+                  Launcher links: Raycast, Alfred and scripts can search, open, copy and paste snippets through
+                  <code className="mx-1">{deepLinks.data.scheme}://search?q=…</code>
+                  links. Answers go back through x-success callbacks to the apps listed here.
+                </p>
+                <div className="flex flex-wrap items-center gap-3">
+                  <label className="flex items-center gap-1 text-xs text-gray-600">
+                    <input
+                      type="checkbox"
+                      checked={deepLinks.data.enabled}
+                      disabled={setDeepLinks.isPending}
+                      onChange={(e) => handleDeepLinks({ enabled: e.target.checked })}
+                    />
+                    Open {deepLinks.data.scheme}:// links
+                  </label>
+                  {deepLinks.data.enabled && (
+                    <input
+                      key={deepLinks.data.callback_schemes.join(',')}
+                      defaultValue={deepLinks.data.callback_schemes.join(', ')}
+                      onBlur={(e) => handleCallbackSchemes(e.target.value)}
+                      placeholder="Callback schemes"
+                      title="Apps x-success and x-error may call back, by URL scheme"
+                      className="flex-1 min-w-[12rem] px-3 py-2 text-xs font-mono border border-gray-200 rounded-lg focus:ring-2 focus:ring-brand-500 outline-none"
+                    />
+                  )}
+                  {deepLinks.data.error && <span className="text-xs text-red-500">{deepLinks.data.error}</span>}
+                </div>
+              </div>
+            )}
+            {sendTo.data?.supported && (
+              <div className="space-y-2">
+                <p className="text-xs text-gray-500">
+                  Explorer: Send to Pin-Up AI, on files' context menus and in the Send to menu, imports the selected
+                  files.
+                </p>
+                <div className="flex flex-wrap items-center gap-3">
+                  <label className="flex items-center gap-1 text-xs text-gray-600">
+                    <input
+                      type="checkbox"
+                      checked={sendTo.data.enabled}
+                      disabled={setSendTo.isPending}
+                      onChange={(e) => handleSendTo(e.target.checked)}
+                    />
+                    Add Send to Pin-Up AI to Explorer
+                  </label>
+                  {sendTo.data.error && <span className="text-xs text-red-500">{sendTo.data.error}</span>}
+                </div>
+              </div>
+            )}
+          </div>
+        )}
       </Section>
 
       {/* ── Backup & Restore ───────────────────────────────────────────── */}
@@ -392,7 +2910,17 @@ export const SettingsPage: React.FC<SettingsPageProps> = ({ showToast }) => {
               <tbody className="divide-y divide-gray-100">
                 {backups.map((b) => (
                   <tr key={b.name}>
-                    <td className="px-3 py-2 text-gray-700 font-mono text-xs">{b.name}</td>
+                    <td className="px-3 py-2 text-gray-700 font-mono text-xs">
+                      {b.name}
+                      {b.tag && (
+                        <span
+                          className="ml-2 px-1.5 py-0.5 rounded-full bg-amber-100 text-amber-700 font-sans text-[10px]"
+                          title="Taken automatically before an update or migration"
+                        >
+                          {b.tag}
+                        </span>
+                      )}
+                    </td>
                     <td className="px-3 py-2 text-gray-500 text-xs flex items-center gap-1">
                       <Clock className="w-3 h-3" />
                       {formatDate(b.created_at)}
@@ -448,6 +2976,102 @@ export const SettingsPage: React.FC<SettingsPageProps> = ({ showToast }) => {
           <RefreshCw className={cn('w-4 h-4', rotatingToken && 'animate-spin')} />
           {rotatingToken ? 'Rotating…' : 'Rotate API Token'}
         </button>
+        {encryption.data && encryption.data.plaintext_copies.length > 0 && (
+          <div className="mt-4 p-3 space-y-2 border border-amber-300 bg-amber-50 rounded-lg">
+            <p className="text-sm text-amber-800 font-medium">Unencrypted copies of your notes</p>
+            <p className="text-xs text-amber-700">
+              Your database is encrypted, but these copies from before are not:
+            </p>
+            <ul className="text-xs text-amber-700 list-disc pl-4">
+              {encryption.data.plaintext_copies.map((path) => (
+                <li key={path} className="truncate" title={path}>
+                  {path}
+                </li>
+              ))}
+            </ul>
+            <button
+              onClick={() => handlePurgePlaintext(encryption.data!.plaintext_copies)}
+              disabled={purgePlaintext.isPending}
+              className="text-xs text-red-600 hover:text-red-800 underline disabled:opacity-50"
+            >
+              {purgePlaintext.isPending ? 'Deleting…' : 'Delete unencrypted copies'}
+            </button>
+          </div>
+        )}
+        {appLock.data && (
+          <div className="mt-4 space-y-2">
+            <div className="flex items-center justify-between">
+              <div>
+                <p className="text-sm text-gray-700 dark:text-gray-300 font-medium">Lock when idle</p>
+                <p className="text-xs text-gray-500 dark:text-gray-400">
+                  {appLock.data.idle_supported
+                    ? 'Hide every window and pause clipboard capture until you enter your passcode'
+                    : "Idle time can't be read on this desktop; use Lock now instead"}
+                </p>
+              </div>
+              <div className="flex items-center gap-3">
+                <select
+                  value={appLock.data.idle_minutes}
+                  onChange={(e) => handleAppLock({ idle_minutes: Number(e.target.value) })}
+                  disabled={setAppLock.isPending}
+                  className="px-2 py-1 text-xs border border-gray-200 rounded-lg"
+                >
+                  {[1, 5, 10, 15, 30, 60].map((m) => (
+                    <option key={m} value={m}>
+                      After {m} min
+                    </option>
+                  ))}
+                </select>
+                <input
+                  type="checkbox"
+                  checked={appLock.data.enabled}
+                  disabled={setAppLock.isPending || (!appLock.data.has_passcode && !lockPasscode)}
+                  onChange={(e) => handleAppLock({ enabled: e.target.checked, passcode: lockPasscode })}
+                />
+              </div>
+            </div>
+            <div className="flex items-center gap-2">
+              <input
+                type="password"
+                value={lockPasscode}
+                onChange={(e) => setLockPasscode(e.target.value)}
+                placeholder={appLock.data.has_passcode ? 'New passcode' : 'Passcode (4+ characters)'}
+                autoComplete="new-password"
+                className="flex-1 px-2 py-1 text-xs border border-gray-200 rounded-lg"
+              />
+              <button
+                onClick={() => handleAppLock({ passcode: lockPasscode })}
+                disabled={setAppLock.isPending || !lockPasscode}
+                className="px-3 py-1 text-xs border border-gray-200 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+              >
+                {appLock.data.has_passcode ? 'Change passcode' : 'Set passcode'}
+              </button>
+              <button
+                onClick={() => lockApp.mutate(undefined, { onError: (e) => showToast(String(e), 'error') })}
+                disabled={lockApp.isPending || !appLock.data.has_passcode}
+                title="Also from the tray, or Ctrl+Alt+L (⌘⌥L on macOS)"
+                className="px-3 py-1 text-xs border border-gray-200 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+              >
+                Lock now
+              </button>
+            </div>
+            {appLock.data.biometrics_name && (
+              <label className="flex items-center gap-1.5 text-xs text-gray-600 dark:text-gray-300">
+                <input
+                  type="checkbox"
+                  checked={appLock.data.biometrics}
+                  disabled={setAppLockBiometrics.isPending}
+                  onChange={(e) =>
+                    setAppLockBiometrics.mutate(e.target.checked, {
+                      onError: (err) => showToast(String(err), 'error'),
+                    })
+                  }
+                />
+                Unlock with {appLock.data.biometrics_name}; the passcode still works
+              </label>
+            )}
+          </div>
+        )}
       </Section>
     </div>
   );
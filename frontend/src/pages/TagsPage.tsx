@@ -8,7 +8,7 @@ import {
   X,
   Palette,
 } from 'lucide-react';
-import { useTags, useCreateTag, useUpdateTag, useDeleteTag } from '../hooks/useApi';
+import { useTags, useCreateTag, useUpdateTag, useDeleteTag, usePickColor } from '../hooks/useApi';
 import { cn } from '../utils/helpers';
 import type { TagOutWithCount } from '../types';
 
@@ -28,6 +28,56 @@ const COLORS = [
   '#3b82f6', '#64748b',
 ];
 
+/* -------------------------------------------------------------------------- */
+/*  Color swatches                                                            */
+/* -------------------------------------------------------------------------- */
+const ColorSwatches: React.FC<{
+  value: string;
+  onChange: (color: string) => void;
+  onError: (message: string) => void;
+}> = ({ value, onChange, onError }) => {
+  const isShell = !!(window as any).__TAURI__;
+  const pickColor = usePickColor();
+  const custom = !COLORS.includes(value);
+
+  return (
+    <div className="flex gap-1 flex-wrap">
+      {COLORS.map((c) => (
+        <button
+          key={c}
+          onClick={() => onChange(c)}
+          className={cn(
+            'w-5 h-5 rounded-full border-2 transition-transform',
+            value === c ? 'border-gray-900 scale-110' : 'border-transparent',
+          )}
+          style={{ backgroundColor: c }}
+          aria-label={`Color ${c}`}
+        />
+      ))}
+      {isShell && (
+        <button
+          onClick={() =>
+            pickColor.mutate(value, {
+              onSuccess: (color) => color && onChange(color),
+              onError: (e: any) => onError(String(e?.message ?? e)),
+            })
+          }
+          disabled={pickColor.isPending}
+          className={cn(
+            'w-5 h-5 rounded-full border-2 flex items-center justify-center transition-transform',
+            custom ? 'border-gray-900 scale-110' : 'border-gray-300 dark:border-gray-600',
+          )}
+          style={custom ? { backgroundColor: value } : undefined}
+          aria-label="Custom color"
+          title="Custom color…"
+        >
+          {!custom && <Palette className="w-3 h-3 text-gray-500" />}
+        </button>
+      )}
+    </div>
+  );
+};
+
 /* -------------------------------------------------------------------------- */
 /*  Inline edit row                                                           */
 /* -------------------------------------------------------------------------- */
@@ -157,20 +207,11 @@ export const TagsPage: React.FC<TagsPageProps> = ({ showToast }) => {
             {editRow && editRow.tagId === null && (
               <tr className="bg-brand-50">
                 <td className="px-4 py-2">
-                  <div className="flex gap-1 flex-wrap">
-                    {COLORS.map((c) => (
-                      <button
-                        key={c}
-                        onClick={() => setEditRow({ ...editRow, color: c })}
-                        className={cn(
-                          'w-5 h-5 rounded-full border-2 transition-transform',
-                          editRow.color === c ? 'border-gray-900 scale-110' : 'border-transparent',
-                        )}
-                        style={{ backgroundColor: c }}
-                        aria-label={`Color ${c}`}
-                      />
-                    ))}
-                  </div>
+                  <ColorSwatches
+                    value={editRow.color}
+                    onChange={(color) => setEditRow({ ...editRow, color })}
+                    onError={(message) => showToast(message, 'error')}
+                  />
                 </td>
                 <td className="px-4 py-2">
                   <input
@@ -213,20 +254,11 @@ export const TagsPage: React.FC<TagsPageProps> = ({ showToast }) => {
                   {/* Color */}
                   <td className="px-4 py-2.5">
                     {isEditingThis ? (
-                      <div className="flex gap-1 flex-wrap">
-                        {COLORS.map((c) => (
-                          <button
-                            key={c}
-                            onClick={() => setEditRow({ ...editRow!, color: c })}
-                            className={cn(
-                              'w-5 h-5 rounded-full border-2 transition-transform',
-                              editRow!.color === c ? 'border-gray-900 scale-110' : 'border-transparent',
-                            )}
-                            style={{ backgroundColor: c }}
-                            aria-label={`Color ${c}`}
-                          />
-                        ))}
-                      </div>
+                      <ColorSwatches
+                        value={editRow!.color}
+                        onChange={(color) => setEditRow({ ...editRow!, color })}
+                        onError={(message) => showToast(message, 'error')}
+                      />
                     ) : (
                       <span
                         className="inline-block w-4 h-4 rounded-full"
@@ -69,8 +69,11 @@ function getInitialTheme(): Theme {
   return 'system';
 }
 
+// The OS appearance; the shell forwards changes the webview may not see.
+let systemDark = window.matchMedia('(prefers-color-scheme: dark)').matches;
+
 function applyTheme(theme: Theme) {
-  const isDark = theme === 'dark' || (theme === 'system' && window.matchMedia('(prefers-color-scheme: dark)').matches);
+  const isDark = theme === 'dark' || (theme === 'system' && systemDark);
   document.documentElement.classList.toggle('dark', isDark);
 }
 
@@ -118,3 +121,15 @@ export const useAppStore = create<AppStore>((set) => ({
   error: null,
   setError: (error) => set({ error }),
 }));
+
+/** Shell-only: keeps "system" mode in step with the OS light/dark setting. */
+export async function followSystemTheme() {
+  const { invoke } = await import('@tauri-apps/api/tauri');
+  const { listen } = await import('@tauri-apps/api/event');
+  const update = (theme: 'light' | 'dark') => {
+    systemDark = theme === 'dark';
+    applyTheme(useAppStore.getState().theme);
+  };
+  await listen<'light' | 'dark'>('theme-changed', (event) => update(event.payload));
+  update(await invoke<'light' | 'dark'>('get_system_theme'));
+}
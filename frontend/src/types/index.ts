@@ -132,6 +132,8 @@ export interface BackupInfo {
   created_at: number;
   db_size_bytes: number;
   app_version: string;
+  /** Why it was taken, e.g. "pre-update-0.3.0" or "pre-migration". */
+  tag?: string | null;
 }
 
 export interface ImportResponse {